@@ -0,0 +1,99 @@
+//! Native copier vs rsync on one large file
+//!
+//! `cargo bench --bench copy`; `TIERFLOW_BENCH_DIR` picks the filesystem
+//! (default: the system temp dir) and `TIERFLOW_BENCH_MB` the file size.
+
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+use tierflow::config::{NativeCopyConfig, PreserveConfig};
+use tierflow::mover::native;
+
+const ROUNDS: u32 = 3;
+
+fn main() -> io::Result<()> {
+    let size_mb: usize = env::var("TIERFLOW_BENCH_MB")
+        .ok()
+        .and_then(|mb| mb.parse().ok())
+        .unwrap_or(1024);
+    let base = env::var_os("TIERFLOW_BENCH_DIR").map_or_else(env::temp_dir, PathBuf::from);
+    let dir = tempfile::Builder::new()
+        .prefix("tierflow-bench")
+        .tempdir_in(base)?;
+    let source = dir.path().join("source.bin");
+    let destination = dir.path().join("destination.bin");
+
+    write_source(&source, size_mb)?;
+    println!("{size_mb} MB in {}", dir.path().display());
+
+    let preserve = PreserveConfig::default();
+    let options = NativeCopyConfig::default();
+    let (elapsed, method) = time(&destination, || {
+        native::copy_file(&source, &destination, &preserve, options, &mut |_| {})
+    })?;
+    report(&format!("native ({method})"), size_mb, elapsed);
+
+    if Command::new("rsync").arg("--version").output().is_ok() {
+        let (elapsed, ()) = time(&destination, || {
+            let status = Command::new("rsync")
+                .args([
+                    "--perms", "--owner", "--group", "--xattrs", "--acls", "--times",
+                ])
+                .arg(&source)
+                .arg(&destination)
+                .status()?;
+            if status.success() {
+                Ok(())
+            } else {
+                Err(io::Error::other(format!("rsync exited with {status}")))
+            }
+        })?;
+        report("rsync", size_mb, elapsed);
+    } else {
+        println!("rsync not found, skipping");
+    }
+    Ok(())
+}
+
+/// Best of `ROUNDS` runs, the destination removed before each
+fn time<T>(
+    destination: &Path,
+    mut copy: impl FnMut() -> io::Result<T>,
+) -> io::Result<(Duration, T)> {
+    let mut best: Option<(Duration, T)> = None;
+    for _ in 0..ROUNDS {
+        // rsync would skip an up-to-date destination
+        if destination.exists() {
+            fs::remove_file(destination)?;
+        }
+        let started = Instant::now();
+        let value = copy()?;
+        let elapsed = started.elapsed();
+        if best.as_ref().is_none_or(|(fastest, _)| elapsed < *fastest) {
+            best = Some((elapsed, value));
+        }
+    }
+    best.ok_or_else(|| io::Error::other("no rounds"))
+}
+
+fn write_source(path: &Path, size_mb: usize) -> io::Result<()> {
+    let mut file = fs::File::create(path)?;
+    let chunk: Vec<u8> = (0..1_000_000u32)
+        .map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8)
+        .collect();
+    for _ in 0..size_mb {
+        file.write_all(&chunk)?;
+    }
+    file.sync_all()
+}
+
+fn report(name: &str, size_mb: usize, elapsed: Duration) {
+    println!(
+        "{name:>28}: {:>8.2}s {:>8.1} MB/s",
+        elapsed.as_secs_f64(),
+        size_mb as f64 / elapsed.as_secs_f64()
+    );
+}
@@ -0,0 +1,91 @@
+//! Hashing and native copy throughput on one file
+//!
+//! `cargo bench --bench movers`; `TIERFLOW_BENCH_DIR` picks the filesystem
+//! (default: the system temp dir). The file is written once, so after the
+//! first sample it is read from the page cache: this measures the CPU side
+//! of the movers, the `copy` bench compares real disk throughput.
+
+// Benchmarks abort on a setup failure
+#![allow(clippy::unwrap_used)]
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use std::env;
+use std::fs;
+use std::hint::black_box;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tierflow::HashAlgorithm;
+use tierflow::config::{NativeCopyConfig, PreserveConfig};
+use tierflow::mover::{HashBudget, Hasher, native};
+
+const FILE_MB: u64 = 256;
+const MB: u64 = 1_000_000;
+
+fn write_source(path: &Path) {
+    let mut file = fs::File::create(path).unwrap();
+    let chunk: Vec<u8> = (0..MB as u32)
+        .map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8)
+        .collect();
+    for _ in 0..FILE_MB {
+        file.write_all(&chunk).unwrap();
+    }
+}
+
+fn movers(c: &mut Criterion) {
+    let base = env::var_os("TIERFLOW_BENCH_DIR").map_or_else(env::temp_dir, PathBuf::from);
+    let dir = tempfile::Builder::new()
+        .prefix("tierflow-bench")
+        .tempdir_in(base)
+        .unwrap();
+    let source = dir.path().join("source.bin");
+    let destination = dir.path().join("destination.bin");
+    write_source(&source);
+
+    let mut group = c.benchmark_group("hash");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(FILE_MB * MB));
+    let budget = HashBudget::unlimited();
+    for algorithm in [
+        HashAlgorithm::Xxh3,
+        HashAlgorithm::Blake3,
+        HashAlgorithm::Sha256,
+    ] {
+        let hasher = Hasher::new(algorithm);
+        group.bench_function(algorithm.to_string(), |b| {
+            b.iter(|| black_box(hasher.hash_file(&source, &budget).unwrap()));
+        });
+    }
+    let threaded = Hasher::new(HashAlgorithm::Xxh3).with_threads(4);
+    group.bench_function("xxh3, 4 threads", |b| {
+        b.iter(|| black_box(threaded.hash_file(&source, &budget).unwrap()));
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("copy");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(FILE_MB * MB));
+    let preserve = PreserveConfig::default();
+    for (name, options) in [
+        ("native", NativeCopyConfig::default()),
+        (
+            "native, no preallocation",
+            NativeCopyConfig {
+                preallocate: false,
+                ..NativeCopyConfig::default()
+            },
+        ),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                if destination.exists() {
+                    fs::remove_file(&destination).unwrap();
+                }
+                native::copy_file(&source, &destination, &preserve, options, &mut |_| {}).unwrap()
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, movers);
+criterion_main!(benches);
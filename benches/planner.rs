@@ -0,0 +1,100 @@
+//! Scanning, Pass 2 placement and eviction on synthetic libraries
+//!
+//! `cargo bench --bench planner`. Planning runs against in-memory file lists
+//! and fixed disk space, so only the planner is measured; the scan benchmark
+//! walks a tree of empty files in the system temp dir.
+
+// Benchmarks abort on a setup failure
+#![allow(clippy::unwrap_used)]
+
+mod support;
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use std::hint::black_box;
+use std::path::Path;
+use std::sync::Arc;
+use support::{GB, library, strategies, tier, write_library};
+use tierflow::{Balancer, FileScanner, FixedClock, MemoryScanner, Tier, TierScanner};
+
+const SCANNED_FILES: usize = 20_000;
+const PLANNED_FILES: usize = 100_000;
+
+fn scan(c: &mut Criterion) {
+    let dir = tempfile::Builder::new()
+        .prefix("tierflow-bench")
+        .tempdir()
+        .unwrap();
+    let root = dir.path().join("cache");
+    write_library(&library(&root, SCANNED_FILES, 1)).unwrap();
+    let cache = Tier::new("cache".into(), root, 1, None, None).unwrap();
+
+    let mut group = c.benchmark_group("scan");
+    group.throughput(Throughput::Elements(SCANNED_FILES as u64));
+    group.bench_function("20k files", |b| {
+        b.iter(|| black_box(TierScanner.scan(&cache)));
+    });
+    group.finish();
+}
+
+/// Balancer over `cache_files` on cache and the rest of `PLANNED_FILES` on storage
+fn balancer(cache_files: usize, cache: Tier, storage: Tier) -> Balancer {
+    let scanner = library(Path::new("/mnt/cache"), cache_files, 1)
+        .into_iter()
+        .map(|file| ("cache", file))
+        .chain(
+            library(Path::new("/mnt/storage"), PLANNED_FILES - cache_files, 2)
+                .into_iter()
+                .map(|file| ("storage", file)),
+        )
+        .fold(MemoryScanner::new(), |scanner, (tier, file)| {
+            scanner.with_file(tier, file)
+        });
+    Balancer::builder(vec![cache, storage], strategies())
+        .scanner(Arc::new(scanner))
+        .clock(Arc::new(FixedClock::new(support::now())))
+        .build()
+}
+
+fn placement(c: &mut Criterion) {
+    // Room on both tiers, so Pass 3 has nothing to do
+    let balancer = || {
+        balancer(
+            PLANNED_FILES / 3,
+            tier("cache", 1, None, 200_000 * GB, 100_000 * GB),
+            tier("storage", 2, None, 1_000_000 * GB, 600_000 * GB),
+        )
+    };
+    let parallel = balancer();
+    let serial = balancer().with_planning_threads(1);
+
+    let mut group = c.benchmark_group("placement");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(PLANNED_FILES as u64));
+    group.bench_function("100k files", |b| {
+        b.iter(|| black_box(parallel.plan_rebalance()));
+    });
+    group.bench_function("100k files, one thread", |b| {
+        b.iter(|| black_box(serial.plan_rebalance()));
+    });
+    group.finish();
+}
+
+fn eviction(c: &mut Criterion) {
+    // Every file on a 95% full cache capped at 80%, so Pass 3 evicts thousands
+    let balancer = balancer(
+        PLANNED_FILES,
+        tier("cache", 1, Some(80), 200_000 * GB, 10_000 * GB),
+        tier("storage", 2, None, 1_000_000 * GB, 1_000_000 * GB),
+    );
+
+    let mut group = c.benchmark_group("eviction");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(PLANNED_FILES as u64));
+    group.bench_function("100k files, cache over limit", |b| {
+        b.iter(|| black_box(balancer.plan_rebalance()));
+    });
+    group.finish();
+}
+
+criterion_group!(benches, scan, placement, eviction);
+criterion_main!(benches);
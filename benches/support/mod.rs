@@ -0,0 +1,121 @@
+//! Synthetic media libraries for the benchmarks
+//!
+//! Files are laid out like a Plex library, `Show NNNN/Season NN/Episode NN.mkv`
+//! plus a subtitle per episode, with sizes and ages drawn from a fixed seed so
+//! every run plans the same library.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tierflow::conditions::{AgeCondition, FileSizeCondition};
+use tierflow::{DiskOperations, FileInfo, PlacementStrategy, Tier};
+
+pub const GB: u64 = 1_000_000_000;
+
+/// Planning instant of every benchmark
+pub fn now() -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+}
+
+/// Linear congruential generator, enough for spreading sizes and ages
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, below: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (self.0 >> 33) % below
+    }
+}
+
+/// `count` files under `root`: episodes of 200 MB to 4 GB and 50 KB subtitles,
+/// modified up to 120 days before `now`; `seed` varies sizes and ages
+pub fn library(root: &Path, count: usize, seed: u64) -> Vec<FileInfo> {
+    let mut rng = Lcg(seed);
+    (0..count)
+        .map(|i| {
+            let episode = i / 2;
+            let dir = root
+                .join(format!("Show {:04}", episode / 100))
+                .join(format!("Season {:02}", episode / 10 % 10));
+            let (path, size) = if i % 2 == 0 {
+                let size = 200_000_000 + rng.next(3_800_000_000);
+                (dir.join(format!("Episode {:02}.mkv", episode % 10)), size)
+            } else {
+                (dir.join(format!("Episode {:02}.srt", episode % 10)), 50_000)
+            };
+            let modified = now() - Duration::from_secs(rng.next(120 * 24) * 3600);
+            FileInfo {
+                path,
+                size,
+                modified,
+                accessed: modified,
+                owner: None,
+            }
+        })
+        .collect()
+}
+
+/// Writes `files` as empty files with their modification times
+///
+/// The scanner reads metadata only, so content would just cost disk space.
+pub fn write_library(files: &[FileInfo]) -> io::Result<()> {
+    for file in files {
+        if let Some(parent) = file.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::File::create(&file.path)?.set_modified(file.modified)?;
+    }
+    Ok(())
+}
+
+/// Fixed space for every path
+pub struct FixedDisk {
+    pub total: u64,
+    pub free: u64,
+}
+
+impl DiskOperations for FixedDisk {
+    fn get_total_space(&self, _path: &Path) -> u64 {
+        self.total
+    }
+
+    fn get_free_space(&self, _path: &Path) -> u64 {
+        self.free
+    }
+}
+
+/// A tier reporting `total` bytes with `free` of them available
+pub fn tier(name: &str, priority: u32, max_usage: Option<u64>, total: u64, free: u64) -> Tier {
+    Tier::new_unchecked(
+        name.into(),
+        PathBuf::from(format!("/mnt/{name}")),
+        priority,
+        max_usage,
+        None,
+    )
+    .unwrap()
+    .with_disk_ops(Arc::new(FixedDisk { total, free }))
+}
+
+/// Recent episodes on cache, old or huge files on storage, the rest where they are
+pub fn strategies() -> Vec<PlacementStrategy> {
+    vec![
+        PlacementStrategy::new("huge".into(), 90)
+            .add_condition(Box::new(FileSizeCondition::new(Some(3_000), None)))
+            .add_preferred_tier("storage".into()),
+        PlacementStrategy::new("recent".into(), 80)
+            .add_condition(Box::new(AgeCondition::new(None, Some(14 * 24))))
+            .add_preferred_tier("cache".into()),
+        PlacementStrategy::new("old".into(), 50)
+            .add_condition(Box::new(AgeCondition::new(Some(60 * 24), None)))
+            .add_preferred_tier("storage".into()),
+        PlacementStrategy::new("default".into(), 1)
+            .add_preferred_tier("cache".into())
+            .add_preferred_tier("storage".into()),
+    ]
+}
@@ -0,0 +1,392 @@
+//! HTTP control API for the daemon: trigger a run, pause/resume, status and the latest plan
+//!
+//! `GET /status`, `GET /plan`, `POST /run`, `POST /pause`, `POST /resume`; all responses are JSON.
+
+use crate::timestamp::unix_now;
+use crate::{BalancingPlan, RunMetrics};
+use serde::Serialize;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::iter;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, TrySendError};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Threads handling API connections
+const WORKERS: usize = 4;
+
+/// Accepted connections waiting for a worker before new ones are dropped
+const QUEUED_CONNECTIONS: usize = 16;
+
+/// Time a client has to send its whole request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Request line, headers and body together; the rest is never read
+const MAX_REQUEST_BYTES: u64 = 16 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+struct CurrentRun {
+    number: u64,
+    started: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct LastRun {
+    number: u64,
+    started: u64,
+    finished: u64,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    files_moved: usize,
+    bytes_moved: u64,
+    errors: usize,
+}
+
+#[derive(Debug, Default)]
+struct ControlState {
+    paused: bool,
+    run_requested: bool,
+    current_run: Option<CurrentRun>,
+    last_run: Option<LastRun>,
+    next_run: Option<u64>,
+    /// JSON of the plan being executed, or of the last one
+    plan: Option<serde_json::Value>,
+}
+
+/// State shared between the daemon loop and the HTTP API
+#[derive(Debug, Default)]
+pub struct DaemonControl {
+    state: Mutex<ControlState>,
+}
+
+impl DaemonControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scheduled runs are skipped until `resume`; requested runs still happen
+    pub fn pause(&self) {
+        self.lock().paused = true;
+    }
+
+    pub fn resume(&self) {
+        self.lock().paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.lock().paused
+    }
+
+    /// Asks the daemon to start a run without waiting for the schedule
+    pub fn request_run(&self) {
+        self.lock().run_requested = true;
+    }
+
+    /// Whether a run was requested since the last call
+    pub fn take_run_request(&self) -> bool {
+        std::mem::take(&mut self.lock().run_requested)
+    }
+
+    pub fn set_next_run(&self, at: SystemTime) {
+        self.lock().next_run = Some(unix_secs(at));
+    }
+
+    pub fn run_started(&self, number: u64) {
+        self.lock().current_run = Some(CurrentRun {
+            number,
+            started: unix_now(),
+        });
+    }
+
+    pub fn set_plan(&self, plan: &BalancingPlan) {
+        match serde_json::to_value(plan) {
+            Ok(plan) => self.lock().plan = Some(plan),
+            Err(e) => tracing::warn!("Failed to serialize plan for the API: {e}"),
+        }
+    }
+
+    /// Records the outcome of the current run: its metrics, or why it failed
+    pub fn run_finished(&self, outcome: Result<&RunMetrics, String>) {
+        let mut state = self.lock();
+        let Some(run) = state.current_run.take() else {
+            return;
+        };
+        let (metrics, error) = match outcome {
+            Ok(metrics) => (Some(metrics), None),
+            Err(error) => (None, Some(error)),
+        };
+        state.last_run = Some(LastRun {
+            number: run.number,
+            started: run.started,
+            finished: unix_now(),
+            success: error.is_none(),
+            error,
+            files_moved: metrics.map_or(0, |m| m.files_moved),
+            bytes_moved: metrics.map_or(0, |m| m.bytes_moved),
+            errors: metrics.map_or(0, |m| m.errors),
+        });
+    }
+
+    /// Body of `GET /status`
+    pub fn status(&self) -> serde_json::Value {
+        let state = self.lock();
+        let phase = if state.current_run.is_some() {
+            "running"
+        } else if state.paused {
+            "paused"
+        } else {
+            "sleeping"
+        };
+        serde_json::json!({
+            "state": phase,
+            "paused": state.paused,
+            "run_requested": state.run_requested,
+            "current_run": state.current_run,
+            "last_run": state.last_run,
+            "next_run": state.next_run,
+        })
+    }
+
+    /// Body of `GET /plan`
+    pub fn plan(&self) -> Option<serde_json::Value> {
+        self.lock().plan.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ControlState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn unix_secs(at: SystemTime) -> u64 {
+    at.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// Serves the control API on `addr` from a background thread
+pub fn serve_api(addr: SocketAddr, control: Arc<DaemonControl>) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    tracing::info!("Serving control API on http://{addr}");
+    serve_listener(listener, control);
+    Ok(())
+}
+
+/// Accepts connections on a background thread and hands them to `WORKERS`
+/// threads, so a slow client cannot hold up the rest
+///
+/// Connections beyond what the workers and queue can take are dropped.
+fn serve_listener(listener: TcpListener, control: Arc<DaemonControl>) {
+    let (sender, receiver) = mpsc::sync_channel::<TcpStream>(QUEUED_CONNECTIONS);
+    let receiver = Arc::new(Mutex::new(receiver));
+    for control in iter::repeat_n(control, WORKERS) {
+        let receiver = Arc::clone(&receiver);
+        thread::spawn(move || {
+            loop {
+                let next = receiver
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .recv();
+                let Ok(stream) = next else {
+                    break;
+                };
+                if let Err(e) = handle_connection(stream, &control) {
+                    tracing::debug!("API request failed: {e}");
+                }
+            }
+        });
+    }
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if let Err(TrySendError::Full(_)) = sender.try_send(stream) {
+                        tracing::debug!("Control API busy, dropping a connection");
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to accept API connection: {e}"),
+            }
+        }
+    });
+}
+
+/// Reads from `stream` until `deadline`, however slowly the client sends
+struct DeadlineReader<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+}
+
+impl Read for DeadlineReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(remaining))?;
+        self.stream.read(buf)
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, control: &DaemonControl) -> io::Result<()> {
+    let deadline = DeadlineReader {
+        stream: &stream,
+        deadline: Instant::now() + REQUEST_TIMEOUT,
+    };
+    let mut reader = BufReader::new(deadline.take(MAX_REQUEST_BYTES));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    drain_request(&mut reader)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("GET");
+    let path = parts.next().unwrap_or("/");
+
+    let (status, body) = match (method, path) {
+        ("GET", "/status") => ("200 OK", control.status()),
+        ("GET", "/plan") => match control.plan() {
+            Some(plan) => ("200 OK", plan),
+            None => ("404 Not Found", error_body("no plan yet")),
+        },
+        ("POST", "/run") => {
+            tracing::info!("Run requested via API");
+            control.request_run();
+            ("202 Accepted", control.status())
+        }
+        ("POST", "/pause") => {
+            tracing::info!("Daemon paused via API");
+            control.pause();
+            ("200 OK", control.status())
+        }
+        ("POST", "/resume") => {
+            tracing::info!("Daemon resumed via API");
+            control.resume();
+            ("200 OK", control.status())
+        }
+        (_, "/status" | "/plan" | "/run" | "/pause" | "/resume") => {
+            ("405 Method Not Allowed", error_body("method not allowed"))
+        }
+        _ => ("404 Not Found", error_body("not found")),
+    };
+
+    let body = body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+/// Reads the headers and any body, so closing the connection does not reset it
+/// before the client has read the response; the caller bounds how much is read
+fn drain_request(reader: &mut impl BufRead) -> io::Result<()> {
+    let mut content_length = 0;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || line.trim_end().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().unwrap_or(0);
+        }
+    }
+    io::copy(&mut io::Read::take(reader, content_length), &mut io::sink())?;
+    Ok(())
+}
+
+fn error_body(message: &str) -> serde_json::Value {
+    serde_json::json!({ "error": message })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn request(addr: SocketAddr, method: &str, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: 2\r\n\r\n{{}}"
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_run_lifecycle() {
+        let control = DaemonControl::new();
+        control.run_started(1);
+        assert_eq!(control.status()["state"], "running");
+
+        control.run_finished(Err("config broken".to_string()));
+        let status = control.status();
+        assert_eq!(status["state"], "sleeping");
+        assert_eq!(status["last_run"]["number"], 1);
+        assert_eq!(status["last_run"]["success"], false);
+        assert_eq!(status["last_run"]["error"], "config broken");
+
+        control.pause();
+        assert_eq!(control.status()["state"], "paused");
+    }
+
+    #[test]
+    fn test_serve_api() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let control = Arc::new(DaemonControl::new());
+        serve_listener(listener, Arc::clone(&control));
+
+        // An idle client does not hold up the others
+        let _idle = TcpStream::connect(addr).unwrap();
+
+        assert!(request(addr, "GET", "/plan").starts_with("HTTP/1.1 404"));
+        assert!(request(addr, "POST", "/run").starts_with("HTTP/1.1 202 Accepted"));
+        assert!(control.take_run_request());
+        assert!(!control.take_run_request());
+
+        let response = request(addr, "POST", "/pause");
+        assert!(response.contains(r#""paused":true"#));
+        assert!(control.is_paused());
+        request(addr, "POST", "/resume");
+        assert!(!control.is_paused());
+
+        assert!(request(addr, "GET", "/run").starts_with("HTTP/1.1 405"));
+        assert!(request(addr, "GET", "/status").starts_with("HTTP/1.1 200 OK"));
+    }
+
+    #[test]
+    fn test_request_reads_are_bounded() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+
+        // A request line with no end stops at the size cap instead of growing
+        client.write_all(&vec![b'A'; 32 * 1024]).unwrap();
+        let deadline = DeadlineReader {
+            stream: &server,
+            deadline: Instant::now() + REQUEST_TIMEOUT,
+        };
+        let mut reader = BufReader::new(deadline.take(MAX_REQUEST_BYTES));
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line.len() as u64, MAX_REQUEST_BYTES);
+
+        // A client that stops sending is cut off once the deadline passes
+        let mut stalled = DeadlineReader {
+            stream: &server,
+            deadline: Instant::now() + Duration::from_millis(100),
+        };
+        let mut rest = Vec::new();
+        let started = Instant::now();
+        assert!(stalled.read_to_end(&mut rest).is_err());
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,5 @@
+mod sonarr;
+mod stats;
+
+pub use sonarr::{CalendarEpisode, Season, Series, SonarrClient};
+pub use stats::SonarrStats;
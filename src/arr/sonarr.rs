@@ -0,0 +1,177 @@
+use crate::error::{AppError, Result};
+use reqwest::blocking::Client;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+/// Sonarr v3 API client
+pub struct SonarrClient {
+    base_url: String,
+    api_key: String,
+    client: Client,
+}
+
+impl SonarrClient {
+    /// Create new Sonarr client
+    pub fn new(base_url: String, api_key: String) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| AppError::Io(std::io::Error::other(e)))?;
+
+        // Normalize base_url: ensure it ends with /
+        let base_url = if base_url.ends_with('/') {
+            base_url
+        } else {
+            format!("{base_url}/")
+        };
+
+        Ok(Self {
+            base_url,
+            api_key,
+            client,
+        })
+    }
+
+    fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = format!("{}api/v3/{path}", self.base_url);
+        let response = self
+            .client
+            .get(&url)
+            .header("X-Api-Key", &self.api_key)
+            .send()
+            .map_err(|e| {
+                AppError::External(format!(
+                    "Failed to connect to Sonarr at {}: {e}",
+                    self.base_url
+                ))
+            })?;
+
+        if !response.status().is_success() {
+            return Err(AppError::External(format!(
+                "Sonarr API returned error status: {}",
+                response.status()
+            )));
+        }
+
+        response
+            .json()
+            .map_err(|e| AppError::External(format!("Failed to parse Sonarr response: {e}")))
+    }
+
+    /// Health check - verify Sonarr is reachable and API key is valid
+    pub fn health_check(&self) -> Result<()> {
+        tracing::info!("Performing Sonarr health check: {}", self.base_url);
+
+        let status: SystemStatus = self.get("system/status")?;
+        tracing::info!("Sonarr health check passed (version {})", status.version);
+        Ok(())
+    }
+
+    /// All series in the library
+    pub fn get_series(&self) -> Result<Vec<Series>> {
+        let series: Vec<Series> = self.get("series")?;
+        tracing::debug!("Fetched {} series from Sonarr", series.len());
+        Ok(series)
+    }
+
+    /// Monitored episodes airing between `start` and `end` (ISO 8601)
+    pub fn get_calendar(&self, start: &str, end: &str) -> Result<Vec<CalendarEpisode>> {
+        let episodes: Vec<CalendarEpisode> = self.get(&format!(
+            "calendar?start={start}&end={end}&unmonitored=false"
+        ))?;
+        tracing::debug!("Fetched {} upcoming episodes from Sonarr", episodes.len());
+        Ok(episodes)
+    }
+}
+
+// API Response structures
+
+#[derive(Debug, Deserialize)]
+struct SystemStatus {
+    version: String,
+}
+
+/// Series from the Sonarr API
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Series {
+    pub id: u64,
+    pub title: String,
+    #[serde(default)]
+    pub monitored: bool,
+    #[serde(default)]
+    pub seasons: Vec<Season>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Season {
+    pub season_number: u32,
+    #[serde(default)]
+    pub monitored: bool,
+}
+
+/// Episode from the Sonarr calendar
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarEpisode {
+    pub series_id: u64,
+    pub season_number: u32,
+    pub episode_number: u32,
+    /// Air time in UTC, missing for episodes without a date yet
+    #[serde(default)]
+    pub air_date_utc: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_series() {
+        let json = r#"{
+            "id": 7,
+            "title": "The Expanse",
+            "monitored": true,
+            "path": "/tv/The Expanse",
+            "seasons": [
+                {"seasonNumber": 0, "monitored": false},
+                {"seasonNumber": 1, "monitored": true}
+            ]
+        }"#;
+
+        let series: Series = serde_json::from_str(json).expect("Should deserialize");
+        assert_eq!(series.id, 7);
+        assert_eq!(series.title, "The Expanse");
+        assert!(series.monitored);
+        assert_eq!(series.seasons.len(), 2);
+        assert!(series.seasons[1].monitored);
+    }
+
+    #[test]
+    fn test_deserialize_calendar_episode() {
+        let json = r#"{
+            "seriesId": 7,
+            "seasonNumber": 2,
+            "episodeNumber": 3,
+            "airDateUtc": "2024-01-15T01:00:00Z",
+            "monitored": true
+        }"#;
+
+        let episode: CalendarEpisode = serde_json::from_str(json).expect("Should deserialize");
+        assert_eq!(episode.series_id, 7);
+        assert_eq!(episode.episode_number, 3);
+        assert_eq!(
+            episode.air_date_utc.as_deref(),
+            Some("2024-01-15T01:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_url_normalization_without_trailing_slash() {
+        let client = SonarrClient::new("http://localhost:8989".to_string(), "test-key".to_string())
+            .expect("Should create client");
+        assert_eq!(client.base_url, "http://localhost:8989/");
+    }
+}
@@ -0,0 +1,162 @@
+use super::{CalendarEpisode, Series};
+use crate::tautulli::{normalize_show_name, parse_episode};
+use crate::timestamp::parse_utc;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Sonarr library state for file placement decisions
+///
+/// Files are matched to series by the show name parsed from the filename.
+#[derive(Debug, Clone, Default)]
+pub struct SonarrStats {
+    /// Monitored seasons of monitored series
+    /// Key: (`normalized_show_name`, season)
+    monitored_seasons: HashSet<(String, u32)>,
+
+    /// Earliest upcoming air time per series (Unix seconds)
+    /// Key: `normalized_show_name`
+    next_airing: HashMap<String, u64>,
+
+    /// Time the stats were built (Unix seconds)
+    now: u64,
+}
+
+impl SonarrStats {
+    /// Build `SonarrStats` from the series list and upcoming calendar
+    pub fn build(series: &[Series], calendar: &[CalendarEpisode], now: u64) -> Self {
+        let monitored_seasons = series
+            .iter()
+            .filter(|s| s.monitored)
+            .flat_map(|s| {
+                let name = normalize_show_name(&s.title);
+                s.seasons
+                    .iter()
+                    .filter(|season| season.monitored)
+                    .map(move |season| (name.clone(), season.season_number))
+            })
+            .collect();
+
+        let titles: HashMap<u64, String> = series
+            .iter()
+            .map(|s| (s.id, normalize_show_name(&s.title)))
+            .collect();
+        let mut next_airing: HashMap<String, u64> = HashMap::new();
+        for episode in calendar {
+            let (Some(name), Some(aired)) = (
+                titles.get(&episode.series_id),
+                episode.air_date_utc.as_deref().and_then(parse_utc),
+            ) else {
+                continue;
+            };
+            if aired < now {
+                continue;
+            }
+            next_airing
+                .entry(name.clone())
+                .and_modify(|next| *next = (*next).min(aired))
+                .or_insert(aired);
+        }
+
+        Self {
+            monitored_seasons,
+            next_airing,
+            now,
+        }
+    }
+
+    /// Check if file is an episode of a monitored season
+    pub fn is_monitored(&self, file_path: &Path) -> bool {
+        parse_episode(file_path).is_some_and(|episode| {
+            self.monitored_seasons
+                .contains(&(normalize_show_name(&episode.show_name), episode.season))
+        })
+    }
+
+    /// Check if the file's series has an episode airing within `days`
+    pub fn airs_within(&self, file_path: &Path, days: u32) -> bool {
+        let deadline = self.now + u64::from(days) * 24 * 3600;
+        parse_episode(file_path).is_some_and(|episode| {
+            self.next_airing
+                .get(&normalize_show_name(&episode.show_name))
+                .is_some_and(|&next| next <= deadline)
+        })
+    }
+
+    /// Number of series with an upcoming episode
+    pub fn upcoming_series(&self) -> usize {
+        self.next_airing.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arr::Season;
+
+    // 2024-01-15T00:00:00Z
+    const NOW: u64 = 1_705_276_800;
+
+    fn series(id: u64, title: &str, monitored: bool, seasons: &[(u32, bool)]) -> Series {
+        Series {
+            id,
+            title: title.to_string(),
+            monitored,
+            seasons: seasons
+                .iter()
+                .map(|&(season_number, monitored)| Season {
+                    season_number,
+                    monitored,
+                })
+                .collect(),
+        }
+    }
+
+    fn airing(series_id: u64, air_date_utc: &str) -> CalendarEpisode {
+        CalendarEpisode {
+            series_id,
+            season_number: 2,
+            episode_number: 1,
+            air_date_utc: Some(air_date_utc.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_monitored_seasons() {
+        let stats = SonarrStats::build(
+            &[
+                series(1, "The Expanse (2015)", true, &[(1, false), (2, true)]),
+                series(2, "Lost", false, &[(1, true)]),
+            ],
+            &[],
+            NOW,
+        );
+
+        assert!(stats.is_monitored(Path::new("/tv/The.Expanse.S02E03.mkv")));
+        assert!(!stats.is_monitored(Path::new("/tv/The.Expanse.S01E03.mkv")));
+        assert!(!stats.is_monitored(Path::new("/tv/Lost - s01e01.mkv")));
+        assert!(!stats.is_monitored(Path::new("/movies/Heat (1995).mkv")));
+    }
+
+    #[test]
+    fn test_airs_within() {
+        let stats = SonarrStats::build(
+            &[
+                series(1, "Severance", true, &[]),
+                series(2, "Andor", true, &[]),
+            ],
+            &[
+                airing(1, "2024-01-18T02:00:00Z"),
+                airing(1, "2024-01-25T02:00:00Z"),
+                airing(2, "2024-01-10T02:00:00Z"),
+            ],
+            NOW,
+        );
+
+        let episode = Path::new("/tv/Severance - S01E09.mkv");
+        assert!(stats.airs_within(episode, 7));
+        assert!(!stats.airs_within(episode, 2));
+        // Already aired episodes don't count
+        assert!(!stats.airs_within(Path::new("/tv/Andor.S01E01.mkv"), 30));
+        assert_eq!(stats.upcoming_series(), 1);
+    }
+}
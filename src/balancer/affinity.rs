@@ -0,0 +1,281 @@
+use super::PlacementDecision;
+use super::quota::QuotaTracker;
+use crate::Tier;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Keeps sidecar files (subtitles, `.nfo`) on the tier their media file moves to
+///
+/// A sidecar belongs to the file in the same directory whose name it extends:
+/// `Movie.en.srt` follows `Movie.mkv`.
+#[derive(Debug, Clone, Default)]
+pub struct SidecarAffinity {
+    /// Lowercase extensions without the dot
+    extensions: Vec<String>,
+}
+
+impl SidecarAffinity {
+    pub fn new(extensions: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        Self {
+            extensions: extensions
+                .into_iter()
+                .map(|ext| ext.as_ref().trim_start_matches('.').to_ascii_lowercase())
+                .collect(),
+        }
+    }
+
+    pub fn is_sidecar(&self, path: &Path) -> bool {
+        path.extension().is_some_and(|ext| {
+            let ext = ext.to_string_lossy().to_ascii_lowercase();
+            self.extensions.contains(&ext)
+        })
+    }
+
+    /// Gives sidecars of moving media files companion decisions to the same tier
+    ///
+    /// The sidecar's own decision is undone in `tier_free_space` before its
+    /// companion move is counted. Sidecars that don't fit, or would be a new move
+    /// past the run quota, stay as planned. Returns the number of sidecars retargeted.
+    pub(super) fn attach_sidecars(
+        &self,
+        tiers: &[Tier],
+        decisions: &mut [PlacementDecision],
+        tier_free_space: &mut HashMap<String, u64>,
+        quotas: &mut QuotaTracker,
+    ) -> usize {
+        // Directories relative to the tier root, so sidecars on another tier are found too
+        let relative_dir = |decision: &PlacementDecision| {
+            let path = decision.file_path();
+            tiers
+                .iter()
+                .find(|t| t.name == decision.current_tier())
+                .and_then(|t| t.relative_path(path))
+                .unwrap_or(path)
+                .parent()
+                .map(Path::to_path_buf)
+        };
+        let mut sidecars: HashMap<PathBuf, Vec<usize>> = HashMap::new();
+        for (index, decision) in decisions.iter().enumerate() {
+            if self.is_sidecar(decision.file_path())
+                && let Some(dir) = relative_dir(decision)
+            {
+                sidecars.entry(dir).or_default().push(index);
+            }
+        }
+        if sidecars.is_empty() {
+            return 0;
+        }
+
+        let priority_of = |name: &str| tiers.iter().find(|t| t.name == name).map(|t| t.priority);
+        let mut attached = HashSet::new();
+        for media in 0..decisions.len() {
+            let decision = &decisions[media];
+            // Copies leave the media file in place, so its sidecars stay too
+            if matches!(
+                decision,
+                PlacementDecision::Stay { .. } | PlacementDecision::Copy { .. }
+            ) || self.is_sidecar(decision.file_path())
+            {
+                continue;
+            }
+            let (Some(dir), Some(stem)) =
+                (relative_dir(decision), decision.file_path().file_stem())
+            else {
+                continue;
+            };
+            let Some(candidates) = sidecars.get(&dir) else {
+                continue;
+            };
+            let prefix = format!("{}.", stem.to_string_lossy());
+            let to_tier = decision.target_tier().to_string();
+            let strategy = decision.strategy_name().to_string();
+            let priority = decision.strategy_priority();
+
+            for &index in candidates {
+                let sidecar = &decisions[index];
+                let belongs = sidecar
+                    .file_path()
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with(&prefix));
+                if !belongs || attached.contains(&index) || sidecar.target_tier() == to_tier {
+                    continue;
+                }
+
+                let file = Arc::clone(sidecar.file());
+                let from_tier = sidecar.current_tier().to_string();
+                let planned_tier = sidecar.target_tier().to_string();
+                let free = tier_free_space.get(&to_tier).copied().unwrap_or(0);
+                if from_tier != to_tier && free < file.size {
+                    tracing::debug!(
+                        "No space on tier '{to_tier}' for sidecar {}",
+                        file.path.display()
+                    );
+                    continue;
+                }
+                if planned_tier == from_tier
+                    && from_tier != to_tier
+                    && !quotas.try_reserve_global(file.size)
+                {
+                    continue;
+                }
+
+                shift(tier_free_space, &planned_tier, &from_tier, file.size);
+                shift(tier_free_space, &from_tier, &to_tier, file.size);
+                decisions[index] = if from_tier == to_tier {
+                    PlacementDecision::Stay {
+                        file,
+                        current_tier: from_tier,
+                        strategy: strategy.clone(),
+                        priority,
+                    }
+                } else if priority_of(&to_tier) < priority_of(&from_tier) {
+                    PlacementDecision::Promote {
+                        file,
+                        from_tier,
+                        to_tier: to_tier.clone(),
+                        strategy: strategy.clone(),
+                        priority,
+                    }
+                } else {
+                    PlacementDecision::Demote {
+                        file,
+                        from_tier,
+                        to_tier: to_tier.clone(),
+                        strategy: strategy.clone(),
+                        priority,
+                    }
+                };
+                attached.insert(index);
+            }
+        }
+        attached.len()
+    }
+}
+
+/// Counts `size` bytes moving from tier `from` to tier `to`
+fn shift(tier_free_space: &mut HashMap<String, u64>, from: &str, to: &str, size: u64) {
+    if from == to {
+        return;
+    }
+    if let Some(free) = tier_free_space.get_mut(from) {
+        *free = free.saturating_add(size);
+    }
+    if let Some(free) = tier_free_space.get_mut(to) {
+        *free = free.saturating_sub(size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileInfo;
+    use crate::balancer::MoveQuota;
+    use std::time::SystemTime;
+
+    fn unlimited() -> QuotaTracker {
+        QuotaTracker::new(MoveQuota::default())
+    }
+
+    fn file(path: &str, size: u64) -> Arc<FileInfo> {
+        Arc::new(FileInfo {
+            path: PathBuf::from(path),
+            size,
+            modified: SystemTime::UNIX_EPOCH,
+            accessed: SystemTime::UNIX_EPOCH,
+            owner: None,
+        })
+    }
+
+    fn tiers() -> Vec<Tier> {
+        vec![
+            Tier::new_unchecked("cache".into(), "/mnt/cache".into(), 1, None, None).unwrap(),
+            Tier::new_unchecked("storage".into(), "/mnt/storage".into(), 2, None, None).unwrap(),
+        ]
+    }
+
+    fn stay(path: &str, size: u64, tier: &str) -> PlacementDecision {
+        PlacementDecision::Stay {
+            file: file(path, size),
+            current_tier: tier.to_string(),
+            strategy: "default".to_string(),
+            priority: 1,
+        }
+    }
+
+    #[test]
+    fn test_sidecars_follow_media_file() {
+        let tiers = tiers();
+        let mut decisions = vec![
+            PlacementDecision::Demote {
+                file: file("/mnt/cache/Movie/Movie.mkv", 100),
+                from_tier: "cache".to_string(),
+                to_tier: "storage".to_string(),
+                strategy: "old".to_string(),
+                priority: 50,
+            },
+            stay("/mnt/cache/Movie/Movie.en.srt", 10, "cache"),
+            stay("/mnt/cache/Movie/Movie.nfo", 5, "cache"),
+            stay("/mnt/cache/Movie/Other.srt", 10, "cache"),
+            stay("/mnt/cache/Movie/Movie.jpg", 10, "cache"),
+        ];
+        let mut free = HashMap::from([("cache".to_string(), 600), ("storage".to_string(), 400)]);
+
+        let affinity = SidecarAffinity::new(["srt", ".NFO"]);
+        let attached =
+            affinity.attach_sidecars(&tiers, &mut decisions, &mut free, &mut unlimited());
+
+        assert_eq!(attached, 2);
+        for decision in &decisions[1..3] {
+            assert!(matches!(decision, PlacementDecision::Demote { .. }));
+            assert_eq!(decision.target_tier(), "storage");
+            assert_eq!(decision.strategy_name(), "old");
+        }
+        assert_eq!(decisions[3].target_tier(), "cache");
+        assert_eq!(decisions[4].target_tier(), "cache");
+        assert_eq!(free["cache"], 615);
+        assert_eq!(free["storage"], 385);
+    }
+
+    #[test]
+    fn test_sidecar_moving_elsewhere_is_retargeted() {
+        let tiers = tiers();
+        let mut decisions = vec![
+            stay("/mnt/cache/Show/S01E01.srt", 10, "cache"),
+            PlacementDecision::Promote {
+                file: file("/mnt/storage/Show/S01E01.mkv", 100),
+                from_tier: "storage".to_string(),
+                to_tier: "cache".to_string(),
+                strategy: "hot".to_string(),
+                priority: 10,
+            },
+        ];
+        // A subtitle already staying on the destination is left alone
+        let mut free = HashMap::from([("cache".to_string(), 400), ("storage".to_string(), 600)]);
+
+        let affinity = SidecarAffinity::new(["srt"]);
+        assert_eq!(
+            affinity.attach_sidecars(&tiers, &mut decisions, &mut free, &mut unlimited()),
+            0
+        );
+        assert_eq!(decisions[0].target_tier(), "cache");
+
+        decisions[0] = PlacementDecision::Demote {
+            file: file("/mnt/cache/Show/S01E01.srt", 10),
+            from_tier: "cache".to_string(),
+            to_tier: "storage".to_string(),
+            strategy: "old".to_string(),
+            priority: 50,
+        };
+        // Demoting it would split the episode; it stays with the promoted video instead
+        let mut free = HashMap::from([("cache".to_string(), 410), ("storage".to_string(), 590)]);
+        assert_eq!(
+            affinity.attach_sidecars(&tiers, &mut decisions, &mut free, &mut unlimited()),
+            1
+        );
+        assert!(matches!(decisions[0], PlacementDecision::Stay { .. }));
+        assert_eq!(decisions[0].strategy_name(), "hot");
+        assert_eq!(free["cache"], 400);
+        assert_eq!(free["storage"], 600);
+    }
+}
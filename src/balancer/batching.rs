@@ -0,0 +1,326 @@
+use super::{PlacementDecision, PlanWarning};
+use crate::Tier;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Moves into a spin-down aware tier, planned as one contiguous batch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MoveBatch {
+    pub tier: String,
+    pub files: usize,
+    pub bytes: u64,
+    /// Batch was below the tier's minimum size and postponed to a later run
+    pub deferred: bool,
+}
+
+/// Turns moves into spin-down aware tiers back into Stay when the pending batch
+/// is smaller than the tier's `spindown_min_batch_bytes`.
+///
+/// A batch is only deferred if every source tier can afford to keep the files,
+/// otherwise the space it frees is needed and the batch runs anyway.
+pub(super) fn defer_small_batches(
+    tiers: &[Tier],
+    decisions: &mut [PlacementDecision],
+    tier_free_space: &mut HashMap<String, u64>,
+    warnings: &mut Vec<PlanWarning>,
+) -> Vec<MoveBatch> {
+    let mut deferred = Vec::new();
+
+    for tier in tiers.iter().filter(|t| t.spindown_aware) {
+        let Some(min_batch_bytes) = tier.spindown_min_batch_bytes else {
+            continue;
+        };
+
+        let indices = moves_into(decisions, &tier.name);
+        let bytes: u64 = indices.iter().map(|&i| decisions[i].file_size()).sum();
+        if indices.is_empty() || bytes >= min_batch_bytes {
+            continue;
+        }
+
+        let mut kept_by_source: HashMap<&str, u64> = HashMap::new();
+        for &i in indices.iter().filter(|&&i| !decisions[i].is_copy()) {
+            *kept_by_source
+                .entry(decisions[i].current_tier())
+                .or_default() += decisions[i].file_size();
+        }
+        let affordable = kept_by_source.iter().all(|(source, kept)| {
+            tier_free_space
+                .get(*source)
+                .is_some_and(|free| free >= kept)
+        });
+        if !affordable {
+            tracing::info!(
+                "Running small batch into spin-down tier '{}' ({} bytes < {} bytes): source tiers need the space",
+                tier.name,
+                bytes,
+                min_batch_bytes
+            );
+            continue;
+        }
+
+        for &i in &indices {
+            let decision = &decisions[i];
+            let (size, from, to) = (
+                decision.file_size(),
+                decision.current_tier().to_string(),
+                decision.target_tier().to_string(),
+            );
+            if !decision.is_copy()
+                && let Some(free) = tier_free_space.get_mut(&from)
+            {
+                *free = free.saturating_sub(size);
+            }
+            if let Some(free) = tier_free_space.get_mut(&to) {
+                *free = free.saturating_add(size);
+            }
+            decisions[i] = decision.to_stay();
+        }
+
+        tracing::info!(
+            "Deferring {} move(s) into spin-down tier '{}' ({} bytes < {} bytes batch minimum)",
+            indices.len(),
+            tier.name,
+            bytes,
+            min_batch_bytes
+        );
+        warnings.push(PlanWarning::BatchDeferred {
+            tier: tier.name.clone(),
+            files: indices.len(),
+            bytes,
+            min_batch_bytes,
+        });
+        deferred.push(MoveBatch {
+            tier: tier.name.clone(),
+            files: indices.len(),
+            bytes,
+            deferred: true,
+        });
+    }
+
+    deferred
+}
+
+/// Reorders sorted decisions so moves into each spin-down aware tier run back to back.
+///
+/// Grouping happens separately for demotes and promotes, so demotes that free
+/// space still run before the promotes that need it.
+pub(super) fn group_spindown_moves(
+    tiers: &[Tier],
+    decisions: &mut Vec<PlacementDecision>,
+) -> Vec<MoveBatch> {
+    let spindown: Vec<&str> = tiers
+        .iter()
+        .filter(|t| t.spindown_aware)
+        .map(|t| t.name.as_str())
+        .collect();
+    if spindown.is_empty() {
+        return Vec::new();
+    }
+
+    let group_key = |d: &PlacementDecision| -> Option<(String, bool)> {
+        let target = d.target_tier();
+        let is_demote = match d {
+            PlacementDecision::Stay { .. } => return None,
+            PlacementDecision::Demote { .. } | PlacementDecision::Copy { .. } => true,
+            PlacementDecision::Promote { .. } => false,
+        };
+        spindown
+            .contains(&target)
+            .then(|| (target.to_string(), is_demote))
+    };
+
+    let mut groups: HashMap<(String, bool), Vec<PlacementDecision>> = HashMap::new();
+    for decision in decisions.iter() {
+        if let Some(key) = group_key(decision) {
+            groups.entry(key).or_default().push(decision.clone());
+        }
+    }
+
+    let mut batches: Vec<MoveBatch> = Vec::new();
+    let mut reordered = Vec::with_capacity(decisions.len());
+    for decision in decisions.drain(..) {
+        match group_key(&decision) {
+            None => reordered.push(decision),
+            Some(key) => {
+                // The first member of a group pulls in the whole group
+                if let Some(group) = groups.remove(&key) {
+                    let bytes = group.iter().map(PlacementDecision::file_size).sum();
+                    match batches.iter_mut().find(|b| b.tier == key.0) {
+                        Some(batch) => {
+                            batch.files += group.len();
+                            batch.bytes += bytes;
+                        }
+                        None => batches.push(MoveBatch {
+                            tier: key.0.clone(),
+                            files: group.len(),
+                            bytes,
+                            deferred: false,
+                        }),
+                    }
+                    reordered.extend(group);
+                }
+            }
+        }
+    }
+
+    *decisions = reordered;
+    batches
+}
+
+fn moves_into(decisions: &[PlacementDecision], tier_name: &str) -> Vec<usize> {
+    decisions
+        .iter()
+        .enumerate()
+        .filter(|(_, d)| {
+            !matches!(d, PlacementDecision::Stay { .. }) && d.target_tier() == tier_name
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileInfo;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::SystemTime;
+
+    const TB: u64 = 1024 * 1024 * 1024 * 1024;
+
+    fn file(name: &str, size: u64) -> Arc<FileInfo> {
+        Arc::new(FileInfo {
+            path: PathBuf::from(format!("/mock/{name}")),
+            size,
+            modified: SystemTime::now(),
+            accessed: SystemTime::now(),
+            owner: None,
+        })
+    }
+
+    fn demote(name: &str, size: u64, to_tier: &str) -> PlacementDecision {
+        PlacementDecision::Demote {
+            file: file(name, size),
+            from_tier: "cache".to_string(),
+            to_tier: to_tier.to_string(),
+            strategy: "old".to_string(),
+            priority: 10,
+        }
+    }
+
+    fn tiers(min_batch_bytes: Option<u64>) -> Vec<Tier> {
+        vec![
+            Tier::new_mock_with_usage("cache", 1, None, TB, 50),
+            Tier::new_mock_with_usage("storage", 5, None, TB, 0),
+            Tier::new_mock_with_usage("archive", 10, None, TB, 0).with_spindown(min_batch_bytes),
+        ]
+    }
+
+    fn free_space(tiers: &[Tier]) -> HashMap<String, u64> {
+        tiers
+            .iter()
+            .map(|t| (t.name.clone(), t.get_free_space()))
+            .collect()
+    }
+
+    #[test]
+    fn test_group_spindown_moves_makes_batch_contiguous() {
+        let tiers = tiers(None);
+        let mut decisions = vec![
+            demote("a.mkv", 100, "archive"),
+            demote("b.mkv", 100, "storage"),
+            demote("c.mkv", 100, "archive"),
+            demote("d.mkv", 100, "storage"),
+            demote("e.mkv", 100, "archive"),
+        ];
+
+        let batches = group_spindown_moves(&tiers, &mut decisions);
+
+        let order: Vec<_> = decisions
+            .iter()
+            .map(|d| d.file_path().display().to_string())
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                "/mock/a.mkv",
+                "/mock/c.mkv",
+                "/mock/e.mkv",
+                "/mock/b.mkv",
+                "/mock/d.mkv"
+            ]
+        );
+        assert_eq!(
+            batches,
+            vec![MoveBatch {
+                tier: "archive".to_string(),
+                files: 3,
+                bytes: 300,
+                deferred: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_defer_small_batch() {
+        let tiers = tiers(Some(1000));
+        let mut free = free_space(&tiers);
+        let cache_free = free["cache"];
+        let mut warnings = Vec::new();
+        let mut decisions = vec![
+            demote("a.mkv", 100, "archive"),
+            demote("b.mkv", 100, "storage"),
+        ];
+
+        let deferred = defer_small_batches(&tiers, &mut decisions, &mut free, &mut warnings);
+
+        assert_eq!(deferred.len(), 1);
+        assert!(deferred[0].deferred);
+        assert!(matches!(decisions[0], PlacementDecision::Stay { .. }));
+        assert!(matches!(decisions[1], PlacementDecision::Demote { .. }));
+        assert_eq!(free["cache"], cache_free - 100);
+        assert!(matches!(
+            warnings[0],
+            PlanWarning::BatchDeferred {
+                files: 1,
+                bytes: 100,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_large_batch_is_not_deferred() {
+        let tiers = tiers(Some(150));
+        let mut free = free_space(&tiers);
+        let mut warnings = Vec::new();
+        let mut decisions = vec![
+            demote("a.mkv", 100, "archive"),
+            demote("b.mkv", 100, "archive"),
+        ];
+
+        let deferred = defer_small_batches(&tiers, &mut decisions, &mut free, &mut warnings);
+
+        assert!(deferred.is_empty());
+        assert!(warnings.is_empty());
+        assert!(
+            decisions
+                .iter()
+                .all(|d| matches!(d, PlacementDecision::Demote { .. }))
+        );
+    }
+
+    #[test]
+    fn test_small_batch_runs_when_source_needs_space() {
+        let tiers = tiers(Some(1000));
+        let mut free = free_space(&tiers);
+        free.insert("cache".to_string(), 50);
+        let mut warnings = Vec::new();
+        let mut decisions = vec![demote("a.mkv", 100, "archive")];
+
+        let deferred = defer_small_batches(&tiers, &mut decisions, &mut free, &mut warnings);
+
+        assert!(deferred.is_empty());
+        assert!(matches!(decisions[0], PlacementDecision::Demote { .. }));
+    }
+}
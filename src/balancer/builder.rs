@@ -0,0 +1,579 @@
+use super::{Balancer, PlanningProgressCallback};
+use crate::{
+    Clock, DiskOperations, FileScanner, PlacementStrategy, Tier, WatchHistoryConfig,
+    WatchHistoryProvider,
+};
+use std::sync::Arc;
+
+/// Builds a `Balancer` for embedding, with scanning, disk space, time and
+/// watch history replaceable by the caller
+///
+/// Anything not set keeps the `Balancer::new` default: tiers are walked on
+/// disk, space comes from the filesystem and time from the system clock.
+pub struct BalancerBuilder {
+    balancer: Balancer,
+    disk_ops: Option<Arc<dyn DiskOperations>>,
+}
+
+impl BalancerBuilder {
+    pub fn new(tiers: Vec<Tier>, strategies: Vec<PlacementStrategy>) -> Self {
+        Self {
+            balancer: Balancer::new(tiers, strategies, None),
+            disk_ops: None,
+        }
+    }
+
+    pub fn scanner(mut self, scanner: Arc<dyn FileScanner>) -> Self {
+        self.balancer = self.balancer.with_scanner(scanner);
+        self
+    }
+
+    /// Disk space reported for every tier
+    pub fn disk_ops(mut self, disk_ops: Arc<dyn DiskOperations>) -> Self {
+        self.disk_ops = Some(disk_ops);
+        self
+    }
+
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.balancer = self.balancer.with_clock(clock);
+        self
+    }
+
+    pub fn watch_history(mut self, config: WatchHistoryConfig) -> Self {
+        self.balancer.watch_history = Some(config);
+        self
+    }
+
+    /// Fetch history from `provider`; `watch_history` still supplies the settings
+    pub fn watch_history_provider(mut self, provider: Arc<dyn WatchHistoryProvider>) -> Self {
+        self.balancer = self.balancer.with_watch_history_provider(provider);
+        self
+    }
+
+    pub fn progress(mut self, callback: PlanningProgressCallback) -> Self {
+        self.balancer = self.balancer.with_progress(callback);
+        self
+    }
+
+    pub fn build(self) -> Balancer {
+        let mut balancer = self.balancer;
+        if let Some(disk_ops) = self.disk_ops {
+            balancer.tiers = balancer
+                .tiers
+                .into_iter()
+                .map(|tier| tier.with_disk_ops(Arc::clone(&disk_ops)))
+                .collect();
+        }
+        balancer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conditions::AgeCondition;
+    use crate::disk::MockDisk;
+    use crate::{
+        FileInfo, FixedClock, MemoryScanner, MoveQuota, PlacementDecision, PlanWarning,
+        PlanningPhase, PlanningProgress, ScanFilter, SidecarAffinity,
+    };
+    use std::path::{Path, PathBuf};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    const GB: u64 = 1024 * 1024 * 1024;
+
+    fn file(path: &str, age_hours: u64, now: std::time::SystemTime) -> FileInfo {
+        let modified = now - Duration::from_secs(age_hours * 3600);
+        FileInfo {
+            path: PathBuf::from(path),
+            size: GB,
+            modified,
+            accessed: modified,
+            owner: None,
+        }
+    }
+
+    fn in_memory_balancer(now: std::time::SystemTime) -> Balancer {
+        let tiers = vec![
+            Tier::new_unchecked("cache".into(), "/mnt/cache".into(), 1, None, None).unwrap(),
+            Tier::new_unchecked("storage".into(), "/mnt/storage".into(), 2, None, None).unwrap(),
+        ];
+        let strategies = vec![
+            PlacementStrategy::new("old".into(), 100)
+                .add_condition(Box::new(AgeCondition::new(Some(24), None)))
+                .add_preferred_tier("storage".into()),
+            PlacementStrategy::new("default".into(), 1).add_preferred_tier("cache".into()),
+        ];
+        let scanner = MemoryScanner::new()
+            .with_file("cache", file("/mnt/cache/old.mkv", 48, now))
+            .with_file("cache", file("/mnt/cache/new.mkv", 1, now));
+
+        Balancer::builder(tiers, strategies)
+            .scanner(Arc::new(scanner))
+            .disk_ops(Arc::new(MockDisk::new(100 * GB, 50 * GB)))
+            .clock(Arc::new(FixedClock::new(now)))
+            .build()
+    }
+
+    #[test]
+    fn test_plan_in_memory() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let plan = in_memory_balancer(now).plan_rebalance();
+
+        assert_eq!(plan.decisions.len(), 2);
+        for decision in &plan.decisions {
+            match decision {
+                PlacementDecision::Demote { file, to_tier, .. } => {
+                    assert_eq!(file.path, PathBuf::from("/mnt/cache/old.mkv"));
+                    assert_eq!(to_tier, "storage");
+                }
+                PlacementDecision::Stay { file, .. } => {
+                    assert_eq!(file.path, PathBuf::from("/mnt/cache/new.mkv"));
+                }
+                other => panic!("unexpected decision: {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_overlapping_tiers_plan_each_file_once() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let tiers = vec![
+            Tier::new_unchecked("cache".into(), "/mnt/cache".into(), 1, None, None).unwrap(),
+            Tier::new_unchecked("all".into(), "/mnt".into(), 2, None, None).unwrap(),
+        ];
+        let strategies =
+            vec![PlacementStrategy::new("default".into(), 1).add_preferred_tier("cache".into())];
+        let scanner = MemoryScanner::new()
+            .with_file("cache", file("/mnt/cache/a.mkv", 1, now))
+            .with_file("all", file("/mnt/cache/a.mkv", 1, now))
+            .with_file("all", file("/mnt/storage/b.mkv", 1, now));
+
+        let plan = Balancer::builder(tiers, strategies)
+            .scanner(Arc::new(scanner))
+            .disk_ops(Arc::new(MockDisk::new(100 * GB, 50 * GB)))
+            .clock(Arc::new(FixedClock::new(now)))
+            .build()
+            .plan_rebalance();
+
+        let mut paths: Vec<_> = plan
+            .decisions
+            .iter()
+            .map(PlacementDecision::file_path)
+            .collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            [
+                Path::new("/mnt/cache/a.mkv"),
+                Path::new("/mnt/storage/b.mkv")
+            ]
+        );
+        assert!(matches!(
+            plan.decisions
+                .iter()
+                .find(|d| d.file_path() == Path::new("/mnt/cache/a.mkv")),
+            Some(PlacementDecision::Stay { .. })
+        ));
+    }
+
+    #[test]
+    fn test_progress_reports() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let reports = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reports);
+        let mut balancer = in_memory_balancer(now);
+        balancer = balancer.with_progress(Arc::new(move |progress: &PlanningProgress| {
+            sink.lock().unwrap().push(*progress);
+        }));
+
+        balancer.plan_rebalance();
+
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports[0].phase, PlanningPhase::Scanning);
+        let done = reports.last().unwrap();
+        assert_eq!(done.phase, PlanningPhase::Done);
+        assert_eq!((done.files_scanned, done.files_planned), (2, 2));
+        assert_eq!(done.percent(), 100);
+    }
+
+    #[test]
+    fn test_identical_state_gives_identical_json() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let first = in_memory_balancer(now).plan_rebalance();
+        let second = in_memory_balancer(now).plan_rebalance();
+
+        assert_eq!(
+            serde_json::to_string(&first).unwrap(),
+            serde_json::to_string(&second).unwrap()
+        );
+        assert_eq!(first.fingerprint(), second.fingerprint());
+    }
+
+    #[test]
+    fn test_parallel_matching_gives_identical_plan() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let plan = |threads| {
+            let tiers = vec![
+                Tier::new_unchecked("cache".into(), "/mnt/cache".into(), 1, None, None).unwrap(),
+                Tier::new_unchecked("storage".into(), "/mnt/storage".into(), 2, None, None)
+                    .unwrap(),
+            ];
+            let strategies = vec![
+                PlacementStrategy::new("old".into(), 100)
+                    .add_condition(Box::new(AgeCondition::new(Some(24), None)))
+                    .add_preferred_tier("storage".into()),
+                PlacementStrategy::new("default".into(), 1).add_preferred_tier("cache".into()),
+            ];
+            // More files than fit, so placement order decides which ones move
+            let scanner = (0..3000).fold(MemoryScanner::new(), |scanner, i| {
+                let tier = if i % 2 == 0 { "cache" } else { "storage" };
+                scanner.with_file(tier, file(&format!("/mnt/{tier}/{i}.mkv"), i % 96, now))
+            });
+            Balancer::builder(tiers, strategies)
+                .scanner(Arc::new(scanner))
+                .disk_ops(Arc::new(MockDisk::new(1000 * GB, 500 * GB)))
+                .clock(Arc::new(FixedClock::new(now)))
+                .build()
+                .with_planning_threads(threads)
+                .plan_rebalance()
+        };
+
+        assert_eq!(
+            serde_json::to_string(&plan(1)).unwrap(),
+            serde_json::to_string(&plan(4)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_scan_filter_leaves_files_out_of_plan() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let cache = Tier::new_unchecked("cache".into(), "/mnt/cache".into(), 1, None, None)
+            .unwrap()
+            .with_scan_filter(ScanFilter {
+                ignore_hidden: true,
+                min_file_size: 0,
+            });
+        let strategies =
+            vec![PlacementStrategy::new("default".into(), 1).add_preferred_tier("cache".into())];
+        let scanner = MemoryScanner::new()
+            .with_file("cache", file("/mnt/cache/movie.mkv", 1, now))
+            .with_file("cache", file("/mnt/cache/.movie.mkv.tmp", 1, now));
+
+        let plan = Balancer::builder(vec![cache], strategies)
+            .scanner(Arc::new(scanner))
+            .disk_ops(Arc::new(MockDisk::new(100 * GB, 50 * GB)))
+            .clock(Arc::new(FixedClock::new(now)))
+            .build()
+            .plan_rebalance();
+
+        assert_eq!(plan.total_files(), 1);
+        assert_eq!(plan.filtered_files.len(), 1);
+        assert_eq!(plan.filtered_files[0].tier, "cache");
+        assert_eq!(plan.filtered_files[0].hidden, 1);
+        assert_eq!(plan.filtered_files[0].bytes, GB);
+    }
+
+    #[test]
+    fn test_small_sidecars_pass_scan_filter() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let cache = Tier::new_unchecked("cache".into(), "/mnt/cache".into(), 1, None, None)
+            .unwrap()
+            .with_scan_filter(ScanFilter {
+                ignore_hidden: false,
+                min_file_size: 1_000_000,
+            });
+        let storage =
+            Tier::new_unchecked("storage".into(), "/mnt/storage".into(), 2, None, None).unwrap();
+        let strategies = vec![
+            PlacementStrategy::new("old".into(), 100)
+                .add_condition(Box::new(AgeCondition::new(Some(24), None)))
+                .add_preferred_tier("storage".into()),
+            PlacementStrategy::new("default".into(), 1).add_preferred_tier("cache".into()),
+        ];
+        let small = |path: &str| FileInfo {
+            size: 1000,
+            ..file(path, 1, now)
+        };
+        let scanner = MemoryScanner::new()
+            .with_file("cache", file("/mnt/cache/Movie.mkv", 48, now))
+            .with_file("cache", small("/mnt/cache/Movie.en.srt"))
+            .with_file("cache", small("/mnt/cache/notes.txt"));
+
+        let plan = Balancer::builder(vec![cache, storage], strategies)
+            .scanner(Arc::new(scanner))
+            .disk_ops(Arc::new(MockDisk::new(100 * GB, 50 * GB)))
+            .clock(Arc::new(FixedClock::new(now)))
+            .build()
+            .with_sidecar_affinity(SidecarAffinity::new(["srt"]))
+            .plan_rebalance();
+
+        assert_eq!(plan.filtered_files[0].too_small, 1);
+        let mut demoted: Vec<_> = plan
+            .decisions
+            .iter()
+            .filter(|d| matches!(d, PlacementDecision::Demote { .. }))
+            .map(PlacementDecision::file_path)
+            .collect();
+        demoted.sort();
+        assert_eq!(
+            demoted,
+            [
+                Path::new("/mnt/cache/Movie.en.srt"),
+                Path::new("/mnt/cache/Movie.mkv")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_only_tier_is_never_a_destination() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let tiers = vec![
+            Tier::new_unchecked("cache".into(), "/mnt/cache".into(), 1, None, None)
+                .unwrap()
+                .with_read_only(true),
+            Tier::new_unchecked("storage".into(), "/mnt/storage".into(), 2, None, None).unwrap(),
+        ];
+        let strategies = vec![
+            PlacementStrategy::new("old".into(), 100)
+                .add_condition(Box::new(AgeCondition::new(Some(24), None)))
+                .add_preferred_tier("storage".into()),
+            PlacementStrategy::new("default".into(), 1)
+                .add_preferred_tier("cache".into())
+                .add_preferred_tier("storage".into()),
+        ];
+        let scanner = MemoryScanner::new()
+            .with_file("cache", file("/mnt/cache/old.mkv", 48, now))
+            .with_file("cache", file("/mnt/cache/new.mkv", 1, now))
+            .with_file("storage", file("/mnt/storage/hot.mkv", 1, now));
+
+        let plan = Balancer::builder(tiers, strategies)
+            .scanner(Arc::new(scanner))
+            .disk_ops(Arc::new(MockDisk::new(100 * GB, 50 * GB)))
+            .clock(Arc::new(FixedClock::new(now)))
+            .build()
+            .plan_rebalance();
+
+        let decision = |path: &str| {
+            plan.decisions
+                .iter()
+                .find(|d| d.file_path() == Path::new(path))
+                .unwrap()
+        };
+        // Files still leave the read-only tier and may stay on it
+        assert_eq!(decision("/mnt/cache/old.mkv").target_tier(), "storage");
+        assert_eq!(decision("/mnt/cache/new.mkv").target_tier(), "cache");
+        assert_eq!(decision("/mnt/storage/hot.mkv").target_tier(), "storage");
+    }
+
+    #[test]
+    fn test_drain_tier_moves_every_file_off() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let tiers = vec![
+            Tier::new_unchecked("cache".into(), "/mnt/cache".into(), 1, None, None).unwrap(),
+            Tier::new_unchecked("disk1".into(), "/mnt/disk1".into(), 2, None, None)
+                .unwrap()
+                .with_drain(true),
+            Tier::new_unchecked("disk2".into(), "/mnt/disk2".into(), 3, None, None).unwrap(),
+        ];
+        let mut pinned = PlacementStrategy::new("pinned".into(), 200)
+            .add_condition(Box::new(AgeCondition::new(None, Some(2))))
+            .add_preferred_tier("disk1".into());
+        pinned.action = crate::StrategyAction::Stay;
+        let strategies = vec![
+            pinned,
+            PlacementStrategy::new("archive".into(), 100)
+                .add_condition(Box::new(AgeCondition::new(Some(24), None)))
+                .add_preferred_tier("disk1".into())
+                .add_preferred_tier("disk2".into()),
+        ];
+        let scanner = MemoryScanner::new()
+            .with_file("cache", file("/mnt/cache/old.mkv", 48, now))
+            .with_file("disk1", file("/mnt/disk1/old.mkv", 48, now))
+            .with_file("disk1", file("/mnt/disk1/pinned.mkv", 1, now))
+            .with_file("disk1", file("/mnt/disk1/unmatched.mkv", 10, now));
+
+        let plan = Balancer::builder(tiers, strategies)
+            .scanner(Arc::new(scanner))
+            .disk_ops(Arc::new(MockDisk::new(100 * GB, 50 * GB)))
+            .clock(Arc::new(FixedClock::new(now)))
+            .build()
+            .plan_rebalance();
+
+        let decision = |path: &str| {
+            plan.decisions
+                .iter()
+                .find(|d| d.file_path() == Path::new(path))
+                .unwrap()
+        };
+        // The draining tier is skipped as a destination, even when preferred
+        assert_eq!(decision("/mnt/cache/old.mkv").target_tier(), "disk2");
+        assert_eq!(decision("/mnt/disk1/old.mkv").target_tier(), "disk2");
+        // Pinned and unmatched files leave too, for the nearest slower tier
+        assert_eq!(decision("/mnt/disk1/pinned.mkv").target_tier(), "disk2");
+        let unmatched = decision("/mnt/disk1/unmatched.mkv");
+        assert_eq!(unmatched.target_tier(), "disk2");
+        assert_eq!(unmatched.strategy_name(), "drain");
+
+        assert_eq!(plan.draining.len(), 1);
+        assert_eq!(plan.draining[0].tier, "disk1");
+        assert_eq!(plan.draining[0].files, 3);
+        assert_eq!(plan.draining[0].files_leaving, 3);
+        assert!(plan.draining[0].is_complete());
+    }
+
+    #[test]
+    fn test_max_file_size_falls_through_to_next_tier() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let tiers = vec![
+            Tier::new_unchecked("nvme".into(), "/mnt/nvme".into(), 1, None, None)
+                .unwrap()
+                .with_max_file_size(Some(40 * GB)),
+            Tier::new_unchecked("ssd".into(), "/mnt/ssd".into(), 2, None, None).unwrap(),
+            Tier::new_unchecked("storage".into(), "/mnt/storage".into(), 3, None, None).unwrap(),
+        ];
+        let strategies = vec![
+            PlacementStrategy::new("hot".into(), 100)
+                .add_preferred_tier("nvme".into())
+                .add_preferred_tier("ssd".into()),
+        ];
+        let remux = FileInfo {
+            size: 50 * GB,
+            ..file("/mnt/storage/remux.mkv", 1, now)
+        };
+        let scanner = MemoryScanner::new()
+            .with_file("storage", remux)
+            .with_file("storage", file("/mnt/storage/episode.mkv", 1, now));
+
+        let plan = Balancer::builder(tiers, strategies)
+            .scanner(Arc::new(scanner))
+            .disk_ops(Arc::new(MockDisk::new(1000 * GB, 900 * GB)))
+            .clock(Arc::new(FixedClock::new(now)))
+            .build()
+            .plan_rebalance();
+
+        let target = |path: &str| {
+            plan.decisions
+                .iter()
+                .find(|d| d.file_path() == Path::new(path))
+                .unwrap()
+                .target_tier()
+        };
+        assert_eq!(target("/mnt/storage/remux.mkv"), "ssd");
+        assert_eq!(target("/mnt/storage/episode.mkv"), "nvme");
+    }
+
+    #[test]
+    fn test_run_quota_bounds_evictions() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let tiers = vec![
+            Tier::new_unchecked("cache".into(), "/mnt/cache".into(), 1, Some(50), None)
+                .unwrap()
+                .with_disk_ops(Arc::new(MockDisk::new(10 * GB, 2 * GB))),
+            Tier::new_unchecked("storage".into(), "/mnt/storage".into(), 2, None, None)
+                .unwrap()
+                .with_disk_ops(Arc::new(MockDisk::new(100 * GB, 100 * GB))),
+        ];
+        let strategies =
+            vec![PlacementStrategy::new("default".into(), 1).add_preferred_tier("cache".into())];
+        // 80% used; reaching 50% takes three evictions
+        let scanner = (0..8).fold(MemoryScanner::new(), |scanner, i| {
+            scanner.with_file("cache", file(&format!("/mnt/cache/{i}.mkv"), i, now))
+        });
+
+        let plan = Balancer::builder(tiers, strategies)
+            .scanner(Arc::new(scanner))
+            .clock(Arc::new(FixedClock::new(now)))
+            .build()
+            .with_run_quota(MoveQuota::new(Some(5), Some(2 * GB)))
+            .plan_rebalance();
+
+        assert_eq!(plan.move_count(), 2);
+        assert_eq!(plan.move_bytes(), 2 * GB);
+        assert!(plan.warnings.iter().any(|w| matches!(
+            w,
+            PlanWarning::QuotaReached {
+                strategy: None,
+                deferred_files: 1,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn test_below_min_usage_holds_demotions() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let tiers = vec![
+            Tier::new_unchecked("cache".into(), "/mnt/cache".into(), 1, None, Some(60)).unwrap(),
+            Tier::new_unchecked("storage".into(), "/mnt/storage".into(), 2, None, None).unwrap(),
+        ];
+        let strategies = vec![
+            PlacementStrategy::new("archive".into(), 100)
+                .add_condition(Box::new(AgeCondition::new(Some(24), None)))
+                .add_preferred_tier("storage".into()),
+        ];
+        let scanner = MemoryScanner::new().with_file("cache", file("/mnt/cache/old.mkv", 48, now));
+
+        let plan = Balancer::builder(tiers, strategies)
+            .scanner(Arc::new(scanner))
+            .disk_ops(Arc::new(MockDisk::new(100 * GB, 50 * GB)))
+            .clock(Arc::new(FixedClock::new(now)))
+            .build()
+            .plan_rebalance();
+
+        assert_eq!(plan.move_count(), 0);
+        assert_eq!(
+            plan.warnings,
+            vec![PlanWarning::BelowMinUsage {
+                tier: "cache".into(),
+                usage_percent: 50,
+                min_usage_percent: 60,
+                files: 1,
+                bytes: plan.decisions[0].file_size(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_copy_strategy_keeps_original() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let tiers = vec![
+            Tier::new_unchecked("cache".into(), "/mnt/cache".into(), 1, None, None).unwrap(),
+            Tier::new_unchecked("storage".into(), "/mnt/storage".into(), 2, None, None).unwrap(),
+        ];
+        let mut seeding = PlacementStrategy::new("seeding".into(), 100)
+            .add_condition(Box::new(AgeCondition::new(None, Some(24 * 14))))
+            .add_preferred_tier("storage".into());
+        seeding.action = crate::StrategyAction::Copy;
+        let strategies = vec![
+            seeding,
+            PlacementStrategy::new("default".into(), 1).add_preferred_tier("storage".into()),
+        ];
+        // `copied.mkv` already has its copy on storage
+        let scanner = MemoryScanner::new()
+            .with_file("cache", file("/mnt/cache/new.mkv", 1, now))
+            .with_file("cache", file("/mnt/cache/copied.mkv", 1, now))
+            .with_file("storage", file("/mnt/storage/copied.mkv", 1, now));
+
+        let plan = Balancer::builder(tiers, strategies)
+            .scanner(Arc::new(scanner))
+            .disk_ops(Arc::new(MockDisk::new(100 * GB, 50 * GB)))
+            .clock(Arc::new(FixedClock::new(now)))
+            .build()
+            .plan_rebalance();
+
+        let decision = |path: &str| {
+            plan.decisions
+                .iter()
+                .find(|d| d.file_path() == Path::new(path))
+                .unwrap()
+        };
+        assert!(matches!(
+            decision("/mnt/cache/new.mkv"),
+            PlacementDecision::Copy { to_tier, .. } if to_tier == "storage"
+        ));
+        for path in ["/mnt/cache/copied.mkv", "/mnt/storage/copied.mkv"] {
+            assert!(matches!(decision(path), PlacementDecision::Stay { .. }));
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use super::{BalancingPlan, PlacementDecision};
+use crate::{AppError, Result, Tier};
+use std::collections::HashMap;
+
+/// Strategies named when a plan exceeds its caps
+const TOP_STRATEGIES: usize = 3;
+
+/// Limits on how much a single plan may move, against a bad strategy edit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlanCaps {
+    pub max_bytes: Option<u64>,
+    /// Share of a tier's capacity that may move onto it or off it
+    pub max_percent_of_tier: Option<u64>,
+}
+
+impl PlanCaps {
+    pub const fn new(max_bytes: Option<u64>, max_percent_of_tier: Option<u64>) -> Self {
+        Self {
+            max_bytes,
+            max_percent_of_tier,
+        }
+    }
+
+    /// Fails if the plan moves more than allowed, naming the strategies moving the most bytes
+    pub fn check(&self, plan: &BalancingPlan, tiers: &[Tier]) -> Result<()> {
+        match self.exceeded(plan, tiers) {
+            Some(reason) => Err(AppError::PlanTooLarge {
+                reason,
+                strategies: top_strategies(plan),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn exceeded(&self, plan: &BalancingPlan, tiers: &[Tier]) -> Option<String> {
+        let total = plan.move_bytes();
+        if let Some(max) = self.max_bytes
+            && total > max
+        {
+            return Some(format!(
+                "{} in total, over safety.max_plan_bytes ({})",
+                gb(total),
+                gb(max)
+            ));
+        }
+
+        let max_percent = self.max_percent_of_tier?;
+        let mut arriving: HashMap<&str, u64> = HashMap::new();
+        let mut leaving: HashMap<&str, u64> = HashMap::new();
+        for decision in &plan.decisions {
+            if matches!(decision, PlacementDecision::Stay { .. }) {
+                continue;
+            }
+            *arriving.entry(decision.target_tier()).or_default() += decision.file_size();
+            // A copy leaves its original in place
+            if !matches!(decision, PlacementDecision::Copy { .. }) {
+                *leaving.entry(decision.current_tier()).or_default() += decision.file_size();
+            }
+        }
+
+        tiers.iter().find_map(|tier| {
+            let total = tier.get_total_space();
+            let moved = arriving
+                .get(tier.name.as_str())
+                .copied()
+                .unwrap_or(0)
+                .max(leaving.get(tier.name.as_str()).copied().unwrap_or(0));
+            (total > 0 && u128::from(moved) * 100 > u128::from(total) * u128::from(max_percent))
+                .then(|| {
+                    format!(
+                        "{} onto or off tier '{}' ({}% of it), over safety.max_plan_percent_of_tier ({max_percent}%)",
+                        gb(moved),
+                        tier.name,
+                        u128::from(moved) * 100 / u128::from(total)
+                    )
+                })
+        })
+    }
+}
+
+/// Strategies moving the most bytes, biggest first: `old (40.00 GB), default (5.00 GB)`
+fn top_strategies(plan: &BalancingPlan) -> String {
+    let mut bytes: HashMap<&str, u64> = HashMap::new();
+    for decision in &plan.decisions {
+        if !matches!(decision, PlacementDecision::Stay { .. }) {
+            *bytes.entry(decision.strategy_name()).or_default() += decision.file_size();
+        }
+    }
+    let mut bytes: Vec<_> = bytes.into_iter().collect();
+    bytes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    bytes
+        .into_iter()
+        .take(TOP_STRATEGIES)
+        .map(|(strategy, bytes)| format!("{strategy} ({})", gb(bytes)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn gb(bytes: u64) -> String {
+    format!("{:.2} GB", bytes as f64 / 1_000_000_000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileInfo;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::SystemTime;
+
+    const GB: u64 = 1_000_000_000;
+
+    fn demote(path: &str, size: u64, strategy: &str) -> PlacementDecision {
+        PlacementDecision::Demote {
+            file: Arc::new(FileInfo {
+                path: PathBuf::from(path),
+                size,
+                modified: SystemTime::UNIX_EPOCH,
+                accessed: SystemTime::UNIX_EPOCH,
+                owner: None,
+            }),
+            from_tier: "cache".to_string(),
+            to_tier: "storage".to_string(),
+            strategy: strategy.to_string(),
+            priority: 1,
+        }
+    }
+
+    #[test]
+    fn test_plan_caps() {
+        let tiers = vec![
+            Tier::new_mock_with_usage("cache", 1, None, 100 * GB, 50),
+            Tier::new_mock_with_usage("storage", 2, None, 1000 * GB, 10),
+        ];
+        let plan = BalancingPlan {
+            decisions: vec![
+                demote("/mnt/cache/a.mkv", 30 * GB, "old"),
+                demote("/mnt/cache/b.mkv", 5 * GB, "default"),
+                demote("/mnt/cache/c.mkv", 10 * GB, "old"),
+            ],
+            ..Default::default()
+        };
+
+        assert!(PlanCaps::default().check(&plan, &tiers).is_ok());
+        assert!(
+            PlanCaps::new(Some(45 * GB), Some(45))
+                .check(&plan, &tiers)
+                .is_ok()
+        );
+
+        let err = PlanCaps::new(Some(40 * GB), None)
+            .check(&plan, &tiers)
+            .unwrap_err();
+        let AppError::PlanTooLarge { reason, strategies } = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert!(reason.contains("45.00 GB in total"), "{reason}");
+        assert_eq!(strategies, "old (40.00 GB), default (5.00 GB)");
+
+        // 45 GB leave the 100 GB cache
+        let err = PlanCaps::new(None, Some(40))
+            .check(&plan, &tiers)
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("tier 'cache' (45% of it)"),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{StateJournal, Tier};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Files moved recently enough that they must not move again yet
+#[derive(Debug, Clone, Default)]
+pub struct MoveCooldown {
+    window: Duration,
+    /// Time since the last move, keyed by path relative to the tier root
+    recently_moved: HashMap<PathBuf, Duration>,
+}
+
+impl MoveCooldown {
+    pub fn from_journal(journal: &StateJournal, window: Duration, now: SystemTime) -> Self {
+        let recently_moved = journal
+            .moved_files()
+            .filter_map(|path| {
+                journal
+                    .time_since_move(path, now)
+                    .filter(|since| *since < window)
+                    .map(|since| (path.to_path_buf(), since))
+            })
+            .collect();
+
+        Self {
+            window,
+            recently_moved,
+        }
+    }
+
+    pub const fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Time since the last move if the file is still cooling down
+    pub fn time_since_move(&self, relative_path: &Path) -> Option<Duration> {
+        self.recently_moved.get(relative_path).copied()
+    }
+
+    /// Whether `path` on `tier` moved within the window
+    pub fn is_cooling_down(&self, tier: &Tier, path: &Path) -> bool {
+        tier.relative_path(path)
+            .is_some_and(|relative| self.recently_moved.contains_key(relative))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    #[test]
+    fn test_only_moves_within_window_cool_down() {
+        let now = SystemTime::now();
+        let mut journal = StateJournal::in_memory();
+        journal.record_move(PathBuf::from("recent.mkv"), now - HOUR);
+        journal.record_move(PathBuf::from("old.mkv"), now - 30 * HOUR);
+
+        let cooldown = MoveCooldown::from_journal(&journal, 24 * HOUR, now);
+
+        assert!(cooldown.time_since_move(Path::new("recent.mkv")).is_some());
+        assert!(cooldown.time_since_move(Path::new("old.mkv")).is_none());
+        assert!(cooldown.time_since_move(Path::new("unknown.mkv")).is_none());
+    }
+}
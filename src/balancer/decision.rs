@@ -1,8 +1,10 @@
 use crate::FileInfo;
+use serde::Serialize;
 use std::path::PathBuf;
 use std::sync::Arc;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
 pub enum PlacementDecision {
     Stay {
         file: Arc<FileInfo>,
@@ -24,6 +26,14 @@ pub enum PlacementDecision {
         strategy: String,
         priority: u32,
     },
+    /// Duplicate onto `to_tier` while the original stays, e.g. for seeding
+    Copy {
+        file: Arc<FileInfo>,
+        from_tier: String,
+        to_tier: String,
+        strategy: String,
+        priority: u32,
+    },
 }
 
 impl PlacementDecision {
@@ -32,7 +42,7 @@ impl PlacementDecision {
         match self {
             Self::Stay { .. } => 0,
             Self::Demote { priority, .. } => 1000 + priority,
-            Self::Promote { priority, .. } => *priority,
+            Self::Promote { priority, .. } | Self::Copy { priority, .. } => *priority,
         }
     }
 
@@ -42,6 +52,7 @@ impl PlacementDecision {
             Self::Stay { file, .. } => &file.path,
             Self::Promote { file, .. } => &file.path,
             Self::Demote { file, .. } => &file.path,
+            Self::Copy { file, .. } => &file.path,
         }
     }
 
@@ -50,6 +61,7 @@ impl PlacementDecision {
             Self::Stay { file, .. } => file.size,
             Self::Promote { file, .. } => file.size,
             Self::Demote { file, .. } => file.size,
+            Self::Copy { file, .. } => file.size,
         }
     }
 
@@ -58,6 +70,7 @@ impl PlacementDecision {
             Self::Stay { priority, .. } => *priority,
             Self::Promote { priority, .. } => *priority,
             Self::Demote { priority, .. } => *priority,
+            Self::Copy { priority, .. } => *priority,
         }
     }
 
@@ -66,6 +79,7 @@ impl PlacementDecision {
             Self::Stay { strategy, .. } => strategy,
             Self::Promote { strategy, .. } => strategy,
             Self::Demote { strategy, .. } => strategy,
+            Self::Copy { strategy, .. } => strategy,
         }
     }
 
@@ -74,6 +88,41 @@ impl PlacementDecision {
             Self::Stay { current_tier, .. } => current_tier,
             Self::Promote { from_tier, .. } => from_tier,
             Self::Demote { from_tier, .. } => from_tier,
+            Self::Copy { from_tier, .. } => from_tier,
+        }
+    }
+
+    /// `stay`, `promote`, `demote` or `copy`, as in the serialized `action` tag
+    pub const fn action(&self) -> &'static str {
+        match self {
+            Self::Stay { .. } => "stay",
+            Self::Promote { .. } => "promote",
+            Self::Demote { .. } => "demote",
+            Self::Copy { .. } => "copy",
+        }
+    }
+
+    /// The same file kept on its current tier
+    pub fn to_stay(&self) -> Self {
+        Self::Stay {
+            file: Arc::clone(self.file()),
+            current_tier: self.current_tier().to_string(),
+            strategy: self.strategy_name().to_string(),
+            priority: self.strategy_priority(),
+        }
+    }
+
+    pub const fn is_copy(&self) -> bool {
+        matches!(self, Self::Copy { .. })
+    }
+
+    /// Tier the file ends up on once the decision is applied
+    pub fn target_tier(&self) -> &str {
+        match self {
+            Self::Stay { current_tier, .. } => current_tier,
+            Self::Promote { to_tier, .. } => to_tier,
+            Self::Demote { to_tier, .. } => to_tier,
+            Self::Copy { to_tier, .. } => to_tier,
         }
     }
 
@@ -82,6 +131,7 @@ impl PlacementDecision {
             Self::Stay { file, .. } => file,
             Self::Promote { file, .. } => file,
             Self::Demote { file, .. } => file,
+            Self::Copy { file, .. } => file,
         }
     }
 }
@@ -97,6 +147,7 @@ mod tests {
             size,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         }
     }
 
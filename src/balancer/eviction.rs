@@ -1,15 +1,40 @@
-use super::{PlacementDecision, state::BlockedPlacement};
+use super::quota::QuotaTracker;
+use super::{MoveCooldown, PlacementDecision, state::BlockedPlacement};
 use crate::Tier;
-use std::collections::HashMap;
+use crate::config::EvictionPolicy;
+use crate::move_policy::MovePolicies;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 use std::sync::Arc;
 
+/// How many tiers down an eviction may push files to make room
+const MAX_CASCADE_DEPTH: usize = 3;
+
 pub struct EvictionPlanner<'a> {
     tiers: &'a [Tier],
+    move_policies: Option<&'a MovePolicies>,
+    cooldown: Option<&'a MoveCooldown>,
 }
 
 impl<'a> EvictionPlanner<'a> {
     pub fn new(tiers: &'a [Tier]) -> Self {
-        Self { tiers }
+        Self {
+            tiers,
+            move_policies: None,
+            cooldown: None,
+        }
+    }
+
+    /// Never pick a fallback tier that move policies forbid
+    pub fn with_move_policies(mut self, move_policies: &'a MovePolicies) -> Self {
+        self.move_policies = Some(move_policies);
+        self
+    }
+
+    /// Neither evict nor re-plan files that moved recently
+    pub fn with_cooldown(mut self, cooldown: Option<&'a MoveCooldown>) -> Self {
+        self.cooldown = cooldown;
+        self
     }
 
     pub fn evict_to_make_space(
@@ -17,16 +42,20 @@ impl<'a> EvictionPlanner<'a> {
         decisions: &mut [PlacementDecision],
         blocked_placements: Vec<BlockedPlacement>,
         tier_free_space: &mut HashMap<String, u64>,
-    ) {
+        quotas: &mut QuotaTracker,
+    ) -> usize {
         if blocked_placements.is_empty() {
-            return;
+            return 0;
         }
 
         let by_tier = self.group_by_tier(blocked_placements);
 
-        for (tier_name, blocked_list) in by_tier {
-            self.evict_from_tier(&tier_name, blocked_list, decisions, tier_free_space);
-        }
+        by_tier
+            .into_iter()
+            .map(|(tier_name, blocked_list)| {
+                self.evict_from_tier(&tier_name, blocked_list, decisions, tier_free_space, quotas)
+            })
+            .sum()
     }
 
     /// Aggressively evict files from tiers exceeding `max_usage_percent`
@@ -35,7 +64,10 @@ impl<'a> EvictionPlanner<'a> {
         &self,
         decisions: &mut [PlacementDecision],
         tier_free_space: &mut HashMap<String, u64>,
-    ) {
+        quotas: &mut QuotaTracker,
+    ) -> usize {
+        let mut evicted_count = 0;
+
         for tier in self.tiers {
             if let Some(max_percent) = tier.max_usage_percent {
                 let total = tier.get_total_space();
@@ -57,10 +89,18 @@ impl<'a> EvictionPlanner<'a> {
                         overage
                     );
 
-                    self.evict_to_target_usage(&tier.name, max_percent, decisions, tier_free_space);
+                    evicted_count += self.evict_to_target_usage(
+                        &tier.name,
+                        max_percent,
+                        decisions,
+                        tier_free_space,
+                        quotas,
+                    );
                 }
             }
         }
+
+        evicted_count
     }
 
     /// Evict files from a tier until usage is at or below `target_percent`
@@ -70,10 +110,11 @@ impl<'a> EvictionPlanner<'a> {
         target_percent: u64,
         decisions: &mut [PlacementDecision],
         tier_free_space: &mut HashMap<String, u64>,
-    ) {
+        quotas: &mut QuotaTracker,
+    ) -> usize {
         let tier = match self.find_tier(tier_name) {
             Some(t) => t,
-            None => return,
+            None => return 0,
         };
 
         let total = tier.get_total_space();
@@ -81,8 +122,7 @@ impl<'a> EvictionPlanner<'a> {
 
         let mut candidates = self.find_eviction_candidates(tier_name, decisions);
 
-        // Sort candidates using eviction policy (can be changed later without breaking SRP)
-        self.sort_eviction_candidates(&mut candidates, decisions);
+        self.sort_eviction_candidates(tier.eviction_policy, &mut candidates, decisions);
 
         let mut evicted_count = 0;
 
@@ -101,8 +141,14 @@ impl<'a> EvictionPlanner<'a> {
                 strategy,
                 priority,
             }) = decisions.get(idx).cloned()
-                && let Some(fallback_tier) =
-                    self.find_fallback_tier(&current_tier, tier_free_space, file.size)
+                && let Some(fallback_tier) = self.make_room_in_fallback(
+                    &current_tier,
+                    file.size,
+                    priority,
+                    decisions,
+                    tier_free_space,
+                    quotas,
+                )
             {
                 tracing::debug!(
                     "Aggressively evicting {} from {} to {} (reducing usage from {}% to target {}%)",
@@ -129,6 +175,8 @@ impl<'a> EvictionPlanner<'a> {
                 );
 
                 evicted_count += 1;
+            } else if quotas.is_global_exhausted() {
+                break;
             }
         }
 
@@ -150,13 +198,15 @@ impl<'a> EvictionPlanner<'a> {
                 "Consider tuning strategies to avoid evictions: increase max_usage_percent, add higher-priority strategies, or add more tiers"
             );
         }
+
+        evicted_count
     }
 
     fn group_by_tier(
         &self,
         blocked_placements: Vec<BlockedPlacement>,
-    ) -> HashMap<String, Vec<BlockedPlacement>> {
-        let mut by_tier: HashMap<String, Vec<BlockedPlacement>> = HashMap::new();
+    ) -> BTreeMap<String, Vec<BlockedPlacement>> {
+        let mut by_tier: BTreeMap<String, Vec<BlockedPlacement>> = BTreeMap::new();
         for blocked in blocked_placements {
             by_tier
                 .entry(blocked.desired_tier.clone())
@@ -172,15 +222,22 @@ impl<'a> EvictionPlanner<'a> {
         mut blocked_list: Vec<BlockedPlacement>,
         decisions: &mut [PlacementDecision],
         tier_free_space: &mut HashMap<String, u64>,
-    ) {
+        quotas: &mut QuotaTracker,
+    ) -> usize {
         blocked_list.sort_by_key(|b| std::cmp::Reverse(b.strategy_priority));
 
-        let candidates = self.find_eviction_candidates(tier_name, decisions);
+        let mut candidates = self.find_eviction_candidates(tier_name, decisions);
+        let policy = self
+            .find_tier(tier_name)
+            .map_or_else(EvictionPolicy::default, |tier| tier.eviction_policy);
+        self.sort_eviction_candidates(policy, &mut candidates, decisions);
+
         let needed_space = self.calculate_needed_space(&blocked_list);
         let to_evict = self.select_files_to_evict(candidates, needed_space, &blocked_list);
 
-        self.apply_evictions(&to_evict, decisions, tier_free_space);
-        self.replan_blocked_files(&blocked_list, decisions, tier_free_space);
+        let evicted_count = self.apply_evictions(&to_evict, decisions, tier_free_space, quotas);
+        self.replan_blocked_files(&blocked_list, decisions, tier_free_space, quotas);
+        evicted_count
     }
 
     fn find_eviction_candidates(
@@ -192,36 +249,51 @@ impl<'a> EvictionPlanner<'a> {
             .iter()
             .enumerate()
             .filter(|(_, d)| {
-                matches!(d, PlacementDecision::Stay { .. }) && d.current_tier() == tier_name
+                matches!(d, PlacementDecision::Stay { .. })
+                    && d.current_tier() == tier_name
+                    && !self.is_cooling_down(tier_name, d.file_path())
             })
             .map(|(idx, d)| (idx, d.strategy_priority(), d.file_size()))
             .collect()
     }
 
-    /// Sort eviction candidates by eviction policy.
+    /// Sort eviction candidates by the tier's eviction policy.
     ///
-    /// Current policy: priority (low first) → age (old first) → size (large first)
+    /// Default policy (`priority_then_lru`): priority (low first) → age (old first) → size (large first)
     /// This ensures:
     /// 1. Low priority strategies are evicted first
     /// 2. Among same priority, older files are evicted first (LRU-like)
     /// 3. Among same priority+age, larger files free more space
     ///
-    /// Extracted as separate method for easy policy changes without breaking SRP.
+    /// Pass 3a still only evicts files with lower priority than the blocked ones,
+    /// whatever the order.
     fn sort_eviction_candidates(
         &self,
+        policy: EvictionPolicy,
         candidates: &mut [(usize, u32, u64)],
         decisions: &[PlacementDecision],
     ) {
         candidates.sort_by(|(idx1, priority1, size1), (idx2, priority2, size2)| {
-            priority1
-                .cmp(priority2) // Lower priority evicted first
-                .then_with(|| {
-                    // Among same priority: older files evicted first
-                    let file1 = decisions[*idx1].file();
-                    let file2 = decisions[*idx2].file();
-                    file1.modified.cmp(&file2.modified)
-                })
-                .then_with(|| size2.cmp(size1)) // Among same priority+age: larger files first
+            let file1 = decisions[*idx1].file();
+            let file2 = decisions[*idx2].file();
+
+            match policy {
+                EvictionPolicy::PriorityThenLru => priority1
+                    .cmp(priority2) // Lower priority evicted first
+                    .then_with(|| file1.modified.cmp(&file2.modified)) // Then older files
+                    .then_with(|| size2.cmp(size1)), // Then larger files
+                EvictionPolicy::Lru => file1
+                    .accessed
+                    .cmp(&file2.accessed)
+                    .then_with(|| size2.cmp(size1)),
+                EvictionPolicy::LargestFirst => {
+                    size2.cmp(size1).then_with(|| priority1.cmp(priority2))
+                }
+                EvictionPolicy::OldestFirst => file1
+                    .modified
+                    .cmp(&file2.modified)
+                    .then_with(|| size2.cmp(size1)),
+            }
         });
     }
 
@@ -263,7 +335,10 @@ impl<'a> EvictionPlanner<'a> {
         to_evict: &[usize],
         decisions: &mut [PlacementDecision],
         tier_free_space: &mut HashMap<String, u64>,
-    ) {
+        quotas: &mut QuotaTracker,
+    ) -> usize {
+        let mut evicted_count = 0;
+
         for &evict_idx in to_evict.iter().rev() {
             if let Some(PlacementDecision::Stay {
                 file,
@@ -271,8 +346,14 @@ impl<'a> EvictionPlanner<'a> {
                 strategy,
                 priority,
             }) = decisions.get(evict_idx).cloned()
-                && let Some(fallback_tier) =
-                    self.find_fallback_tier(&current_tier, tier_free_space, file.size)
+                && let Some(fallback_tier) = self.make_room_in_fallback(
+                    &current_tier,
+                    file.size,
+                    priority,
+                    decisions,
+                    tier_free_space,
+                    quotas,
+                )
             {
                 tracing::debug!(
                     "Evicting {} from {} to {} (priority {} < required priority)",
@@ -296,8 +377,14 @@ impl<'a> EvictionPlanner<'a> {
                     &current_tier,
                     &fallback_tier.name,
                 );
+
+                evicted_count += 1;
+            } else if quotas.is_global_exhausted() {
+                break;
             }
         }
+
+        evicted_count
     }
 
     fn replan_blocked_files(
@@ -305,16 +392,26 @@ impl<'a> EvictionPlanner<'a> {
         blocked_list: &[BlockedPlacement],
         decisions: &mut [PlacementDecision],
         tier_free_space: &mut HashMap<String, u64>,
+        quotas: &mut QuotaTracker,
     ) {
         for blocked in blocked_list {
+            if self.is_cooling_down(&blocked.current_tier, &blocked.file.path) {
+                continue;
+            }
             if let Some(target_tier) = self.find_tier(&blocked.desired_tier)
                 && let Some(current_tier) = self.find_tier(&blocked.current_tier)
                 && tier_free_space
                     .get(&target_tier.name)
                     .is_some_and(|&free| self.can_accept_file(target_tier, blocked.file.size, free))
-                && let Some(decision_idx) = decisions
-                    .iter()
-                    .position(|d| d.file().path == blocked.file.path)
+                // A file evicted from its tier meanwhile has already moved
+                && let Some(decision_idx) = decisions.iter().position(|d| {
+                    d.file().path == blocked.file.path && matches!(d, PlacementDecision::Stay { .. })
+                })
+                && quotas.try_reserve_for(
+                    &blocked.strategy_name,
+                    blocked.strategy_quota,
+                    blocked.file.size,
+                )
             {
                 tracing::debug!(
                     "Re-planning {} to {} after eviction (priority {})",
@@ -352,31 +449,192 @@ impl<'a> EvictionPlanner<'a> {
         }
     }
 
-    fn find_tier(&self, name: &str) -> Option<&Tier> {
+    fn is_cooling_down(&self, tier_name: &str, path: &Path) -> bool {
+        self.cooldown.is_some_and(|cooldown| {
+            self.find_tier(tier_name)
+                .is_some_and(|tier| cooldown.is_cooling_down(tier, path))
+        })
+    }
+
+    fn find_tier(&self, name: &str) -> Option<&'a Tier> {
         self.tiers.iter().find(|t| t.name == name)
     }
 
+    /// Next lower tier that files from `current_tier` may be demoted to
+    fn next_tier(&self, current_tier: &str) -> Option<&'a Tier> {
+        let current_tier_obj = self.find_tier(current_tier)?;
+
+        self.tiers
+            .iter()
+            .filter(|t| t.priority > current_tier_obj.priority && t.accepts_files())
+            .filter(|t| {
+                self.move_policies
+                    .is_none_or(|policies| policies.is_allowed(current_tier, &t.name))
+            })
+            .min_by_key(|t| t.priority)
+    }
+
     fn find_fallback_tier(
         &self,
         current_tier: &str,
         tier_free_space: &HashMap<String, u64>,
         file_size: u64,
-    ) -> Option<&Tier> {
-        let current_tier_obj = self.find_tier(current_tier)?;
+    ) -> Option<&'a Tier> {
+        self.next_tier(current_tier).filter(|tier| {
+            tier_free_space
+                .get(&tier.name)
+                .is_some_and(|&free| self.can_accept_file(tier, file_size, free))
+        })
+    }
 
-        self.tiers
-            .iter()
-            .filter(|t| t.priority > current_tier_obj.priority)
-            .min_by_key(|t| t.priority)
-            .filter(|tier| {
-                tier_free_space
-                    .get(&tier.name)
-                    .is_some_and(|&free| self.can_accept_file(tier, file_size, free))
-            })
+    /// Fallback tier for a file evicted from `current_tier`, cascading when it is full
+    ///
+    /// If the next tier cannot take the file, files with priority up to `priority` are
+    /// evicted from it to the tier below (recursively, at most `MAX_CASCADE_DEPTH` hops).
+    /// Files are only picked when together they free enough space, and a cascade
+    /// that cannot finish leaves decisions and free space as they were. Run quota
+    /// is reserved for the evicted file and every cascaded move.
+    fn make_room_in_fallback(
+        &self,
+        current_tier: &str,
+        file_size: u64,
+        priority: u32,
+        decisions: &mut [PlacementDecision],
+        tier_free_space: &mut HashMap<String, u64>,
+        quotas: &mut QuotaTracker,
+    ) -> Option<&'a Tier> {
+        if !quotas.try_reserve_global(file_size) {
+            return None;
+        }
+        let fallback = self.cascade(
+            current_tier,
+            file_size,
+            priority,
+            decisions,
+            tier_free_space,
+            quotas,
+            0,
+            &mut Vec::new(),
+        );
+        if fallback.is_none() {
+            quotas.release_global(file_size);
+        }
+        fallback
+    }
+
+    /// One level of `make_room_in_fallback`; `undo` holds the replaced decisions
+    #[allow(clippy::too_many_arguments)]
+    fn cascade(
+        &self,
+        current_tier: &str,
+        file_size: u64,
+        priority: u32,
+        decisions: &mut [PlacementDecision],
+        tier_free_space: &mut HashMap<String, u64>,
+        quotas: &mut QuotaTracker,
+        depth: usize,
+        undo: &mut Vec<(usize, PlacementDecision)>,
+    ) -> Option<&'a Tier> {
+        if let Some(fallback_tier) =
+            self.find_fallback_tier(current_tier, tier_free_space, file_size)
+        {
+            return Some(fallback_tier);
+        }
+        if depth >= MAX_CASCADE_DEPTH {
+            return None;
+        }
+
+        let fallback_tier = self.next_tier(current_tier)?;
+        let free = tier_free_space.get(&fallback_tier.name).copied()?;
+
+        let mut candidates = self.find_eviction_candidates(&fallback_tier.name, decisions);
+        candidates.retain(|&(_, candidate_priority, _)| candidate_priority <= priority);
+        self.sort_eviction_candidates(fallback_tier.eviction_policy, &mut candidates, decisions);
+
+        // Pick just enough files, or give up without touching anything
+        let mut freed = 0u64;
+        let mut selected = Vec::new();
+        for (idx, candidate_priority, size) in candidates {
+            if self.can_accept_file(fallback_tier, file_size, free.saturating_add(freed)) {
+                break;
+            }
+            freed += size;
+            selected.push((idx, candidate_priority));
+        }
+        if !self.can_accept_file(fallback_tier, file_size, free.saturating_add(freed)) {
+            return None;
+        }
+
+        let undo_from = undo.len();
+        let free_before = tier_free_space.clone();
+        let roll_back = |decisions: &mut [PlacementDecision],
+                         tier_free_space: &mut HashMap<String, u64>,
+                         quotas: &mut QuotaTracker,
+                         undo: &mut Vec<(usize, PlacementDecision)>| {
+            for (idx, decision) in undo.drain(undo_from..).rev() {
+                quotas.release_global(decision.file_size());
+                decisions[idx] = decision;
+            }
+            tier_free_space.clone_from(&free_before);
+        };
+
+        for (idx, candidate_priority) in selected {
+            let Some(PlacementDecision::Stay { file, strategy, .. }) = decisions.get(idx).cloned()
+            else {
+                continue;
+            };
+            if !quotas.try_reserve_global(file.size) {
+                roll_back(decisions, tier_free_space, quotas, undo);
+                return None;
+            }
+            let Some(target_tier) = self.cascade(
+                &fallback_tier.name,
+                file.size,
+                candidate_priority,
+                decisions,
+                tier_free_space,
+                quotas,
+                depth + 1,
+                undo,
+            ) else {
+                quotas.release_global(file.size);
+                roll_back(decisions, tier_free_space, quotas, undo);
+                return None;
+            };
+
+            tracing::debug!(
+                "Cascading eviction: {} from {} to {} to make room for a file from {}",
+                file.path.display(),
+                fallback_tier.name,
+                target_tier.name,
+                current_tier
+            );
+
+            let demote = PlacementDecision::Demote {
+                file: Arc::clone(&file),
+                from_tier: fallback_tier.name.clone(),
+                to_tier: target_tier.name.clone(),
+                strategy,
+                priority: candidate_priority,
+            };
+            undo.push((idx, std::mem::replace(&mut decisions[idx], demote)));
+            self.apply_move(
+                tier_free_space,
+                file.size,
+                &fallback_tier.name,
+                &target_tier.name,
+            );
+        }
+
+        let fallback = self.find_fallback_tier(current_tier, tier_free_space, file_size);
+        if fallback.is_none() {
+            roll_back(decisions, tier_free_space, quotas, undo);
+        }
+        fallback
     }
 
     fn can_accept_file(&self, tier: &Tier, file_size: u64, simulated_free: u64) -> bool {
-        if simulated_free < file_size {
+        if simulated_free < file_size || !tier.fits_file_size(file_size) {
             return false;
         }
 
@@ -415,6 +673,11 @@ impl<'a> EvictionPlanner<'a> {
 mod tests {
     use super::*;
     use crate::FileInfo;
+    use crate::balancer::MoveQuota;
+
+    fn unlimited() -> QuotaTracker {
+        QuotaTracker::new(MoveQuota::default())
+    }
 
     // Test constants for readability
     const GB: u64 = 1024 * 1024 * 1024;
@@ -438,6 +701,7 @@ mod tests {
             size: 1000,
             modified: std::time::SystemTime::now(),
             accessed: std::time::SystemTime::now(),
+            owner: None,
         };
 
         let high_priority_file = FileInfo {
@@ -445,6 +709,7 @@ mod tests {
             size: 500,
             modified: std::time::SystemTime::now(),
             accessed: std::time::SystemTime::now(),
+            owner: None,
         };
 
         let mut decisions = vec![PlacementDecision::Stay {
@@ -460,13 +725,19 @@ mod tests {
             desired_tier: "cache".to_string(),
             strategy_name: "high_priority".to_string(),
             strategy_priority: 90,
+            strategy_quota: MoveQuota::default(),
         }];
 
         let mut tier_free_space = HashMap::new();
         tier_free_space.insert("cache".to_string(), 100);
         tier_free_space.insert("storage".to_string(), 10000);
 
-        eviction_planner.evict_to_make_space(&mut decisions, blocked, &mut tier_free_space);
+        eviction_planner.evict_to_make_space(
+            &mut decisions,
+            blocked,
+            &mut tier_free_space,
+            &mut unlimited(),
+        );
 
         let low_priority_decision = decisions
             .iter()
@@ -494,6 +765,7 @@ mod tests {
             size: 1000,
             modified: std::time::SystemTime::now(),
             accessed: std::time::SystemTime::now(),
+            owner: None,
         };
 
         let mut decisions = vec![PlacementDecision::Stay {
@@ -509,18 +781,25 @@ mod tests {
                 size: 500,
                 modified: std::time::SystemTime::now(),
                 accessed: std::time::SystemTime::now(),
+                owner: None,
             }),
             current_tier: "storage".to_string(),
             desired_tier: "cache".to_string(),
             strategy_name: "medium".to_string(),
             strategy_priority: 50,
+            strategy_quota: MoveQuota::default(),
         }];
 
         let mut tier_free_space = HashMap::new();
         tier_free_space.insert("cache".to_string(), 1000);
         tier_free_space.insert("storage".to_string(), 10000);
 
-        eviction_planner.evict_to_make_space(&mut decisions, blocked, &mut tier_free_space);
+        eviction_planner.evict_to_make_space(
+            &mut decisions,
+            blocked,
+            &mut tier_free_space,
+            &mut unlimited(),
+        );
 
         let high_priority_decision = decisions
             .iter()
@@ -551,6 +830,7 @@ mod tests {
                     size: file_size,
                     modified: std::time::SystemTime::now(),
                     accessed: std::time::SystemTime::now(),
+                    owner: None,
                 }),
                 current_tier: "cache".to_string(),
                 strategy: "default".to_string(),
@@ -562,6 +842,7 @@ mod tests {
                     size: file_size,
                     modified: std::time::SystemTime::now(),
                     accessed: std::time::SystemTime::now(),
+                    owner: None,
                 }),
                 current_tier: "cache".to_string(),
                 strategy: "default".to_string(),
@@ -573,6 +854,7 @@ mod tests {
                     size: file_size,
                     modified: std::time::SystemTime::now(),
                     accessed: std::time::SystemTime::now(),
+                    owner: None,
                 }),
                 current_tier: "cache".to_string(),
                 strategy: "default".to_string(),
@@ -603,6 +885,7 @@ mod tests {
                     size: file_size,
                     modified: std::time::SystemTime::now(),
                     accessed: std::time::SystemTime::now(),
+                    owner: None,
                 }),
                 current_tier: "cache".to_string(),
                 strategy: "default".to_string(),
@@ -616,7 +899,7 @@ mod tests {
         tier_free_space.insert("cache".to_string(), simulated_free);
 
         // Run aggressive eviction
-        eviction_planner.evict_excess_usage(&mut decisions, &mut tier_free_space);
+        eviction_planner.evict_excess_usage(&mut decisions, &mut tier_free_space, &mut unlimited());
 
         // Check that some files were demoted
         let demoted_after = decisions
@@ -656,6 +939,7 @@ mod tests {
                 size: file_size,
                 modified: std::time::SystemTime::now(),
                 accessed: std::time::SystemTime::now(),
+                owner: None,
             }),
             current_tier: "cache".to_string(),
             strategy: "default".to_string(),
@@ -668,7 +952,7 @@ mod tests {
         tier_free_space.insert("cache".to_string(), simulated_free);
         tier_free_space.insert("storage".to_string(), storage.get_free_space());
 
-        eviction_planner.evict_excess_usage(&mut decisions, &mut tier_free_space);
+        eviction_planner.evict_excess_usage(&mut decisions, &mut tier_free_space, &mut unlimited());
 
         // No files should be demoted
         let demoted = decisions
@@ -697,6 +981,7 @@ mod tests {
             size: file_size,
             modified: std::time::SystemTime::now(),
             accessed: std::time::SystemTime::now(),
+            owner: None,
         };
 
         let high_priority_file = FileInfo {
@@ -704,6 +989,7 @@ mod tests {
             size: file_size,
             modified: std::time::SystemTime::now(),
             accessed: std::time::SystemTime::now(),
+            owner: None,
         };
 
         let mut decisions = vec![
@@ -729,6 +1015,7 @@ mod tests {
                     size: file_size,
                     modified: std::time::SystemTime::now(),
                     accessed: std::time::SystemTime::now(),
+                    owner: None,
                 }),
                 current_tier: "cache".to_string(),
                 strategy: "low".to_string(),
@@ -743,7 +1030,7 @@ mod tests {
         tier_free_space.insert("cache".to_string(), simulated_free);
         tier_free_space.insert("storage".to_string(), storage.get_free_space());
 
-        eviction_planner.evict_excess_usage(&mut decisions, &mut tier_free_space);
+        eviction_planner.evict_excess_usage(&mut decisions, &mut tier_free_space, &mut unlimited());
 
         // High priority file should still be on cache
         let high_priority_decision = decisions
@@ -764,4 +1051,324 @@ mod tests {
             "Low priority files should be demoted first"
         );
     }
+
+    #[test]
+    fn test_fallback_tier_skips_denied_pair() {
+        use crate::config::MovePolicyAction;
+        use crate::move_policy::MovePolicy;
+
+        let cache = create_test_tier("cache", 1, None);
+        let warm = create_test_tier("warm", 5, None);
+        let cold = create_test_tier("cold", 10, None);
+        let tiers = vec![cache, warm, cold];
+        let policies = MovePolicies::new(vec![MovePolicy {
+            from: "cache".to_string(),
+            to: "warm".to_string(),
+            action: MovePolicyAction::Deny,
+        }]);
+
+        let mut tier_free_space = HashMap::new();
+        tier_free_space.insert("cache".to_string(), TB);
+        tier_free_space.insert("warm".to_string(), TB);
+        tier_free_space.insert("cold".to_string(), TB);
+
+        let unrestricted = EvictionPlanner::new(&tiers);
+        let fallback = unrestricted
+            .find_fallback_tier("cache", &tier_free_space, GB)
+            .unwrap();
+        assert_eq!(fallback.name, "warm");
+
+        let restricted = EvictionPlanner::new(&tiers).with_move_policies(&policies);
+        let fallback = restricted
+            .find_fallback_tier("cache", &tier_free_space, GB)
+            .unwrap();
+        assert_eq!(fallback.name, "cold");
+    }
+
+    /// Stay decision for `/<tier>/<file>`
+    fn stay(path: &str, size: u64, age_secs: u64, priority: u32) -> PlacementDecision {
+        let modified = std::time::SystemTime::now() - std::time::Duration::from_secs(age_secs);
+        let tier = path.split('/').nth(1).unwrap_or_default();
+        PlacementDecision::Stay {
+            file: Arc::new(FileInfo {
+                path: std::path::PathBuf::from(path),
+                size,
+                modified,
+                accessed: modified,
+                owner: None,
+            }),
+            current_tier: tier.to_string(),
+            strategy: "default".to_string(),
+            priority,
+        }
+    }
+
+    fn demoted(decisions: &[PlacementDecision]) -> Vec<&str> {
+        decisions
+            .iter()
+            .filter(|d| matches!(d, PlacementDecision::Demote { .. }))
+            .filter_map(|d| d.file_path().to_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_excess_usage_follows_eviction_policy() {
+        let run = |policy: EvictionPolicy| {
+            let cache = create_test_tier("cache", 1, Some(50)).with_eviction_policy(policy);
+            let storage = create_test_tier("storage", 10, None);
+            let tiers = vec![cache, storage];
+
+            // 60% used: one old small file and one new large file
+            let mut decisions = vec![
+                stay("/cache/old_small.mkv", TB / 10, 3600, 10),
+                stay("/cache/new_large.mkv", TB / 2, 60, 10),
+            ];
+            let mut tier_free_space = HashMap::new();
+            tier_free_space.insert("cache".to_string(), TB - TB * 6 / 10);
+            tier_free_space.insert("storage".to_string(), TB);
+
+            EvictionPlanner::new(&tiers).evict_excess_usage(
+                &mut decisions,
+                &mut tier_free_space,
+                &mut unlimited(),
+            );
+            demoted(&decisions)
+                .into_iter()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            run(EvictionPolicy::PriorityThenLru),
+            vec!["/cache/old_small.mkv"]
+        );
+        assert_eq!(
+            run(EvictionPolicy::OldestFirst),
+            vec!["/cache/old_small.mkv"]
+        );
+        assert_eq!(
+            run(EvictionPolicy::LargestFirst),
+            vec!["/cache/new_large.mkv"]
+        );
+    }
+
+    #[test]
+    fn test_make_space_follows_eviction_policy() {
+        let cache =
+            create_test_tier("cache", 1, None).with_eviction_policy(EvictionPolicy::LargestFirst);
+        let storage = create_test_tier("storage", 10, None);
+        let tiers = vec![cache, storage];
+
+        let mut decisions = vec![
+            stay("/cache/a_small.mkv", 300, 3600, 10),
+            stay("/cache/b_small.mkv", 300, 3600, 10),
+            stay("/cache/large.mkv", 1000, 60, 10),
+            stay("/cache/important.mkv", 5000, 7200, 95),
+        ];
+        let blocked = vec![BlockedPlacement {
+            file: Arc::new(FileInfo {
+                path: std::path::PathBuf::from("/storage/wanted.mkv"),
+                size: 500,
+                modified: std::time::SystemTime::now(),
+                accessed: std::time::SystemTime::now(),
+                owner: None,
+            }),
+            current_tier: "storage".to_string(),
+            desired_tier: "cache".to_string(),
+            strategy_name: "wanted".to_string(),
+            strategy_priority: 90,
+            strategy_quota: MoveQuota::default(),
+        }];
+        let mut tier_free_space = HashMap::new();
+        tier_free_space.insert("cache".to_string(), 100);
+        tier_free_space.insert("storage".to_string(), 10000);
+
+        EvictionPlanner::new(&tiers).evict_to_make_space(
+            &mut decisions,
+            blocked,
+            &mut tier_free_space,
+            &mut unlimited(),
+        );
+
+        // One large file frees enough space; higher-priority files are never evicted
+        assert_eq!(demoted(&decisions), vec!["/cache/large.mkv"]);
+    }
+
+    #[test]
+    fn test_eviction_cascades_through_full_tier() {
+        let cache = create_test_tier("cache", 1, Some(50));
+        let warm = create_test_tier("warm", 5, None);
+        let cold = create_test_tier("cold", 10, None);
+        let tiers = vec![cache, warm, cold];
+
+        let mut decisions = vec![
+            stay("/cache/movie.mkv", TB / 5, 3600, 10),
+            stay("/warm/old.mkv", TB / 4, 7200, 10),
+            stay("/warm/important.mkv", TB / 4, 7200, 90),
+        ];
+        // cache at 70%, warm has no room for the evicted file, cold is empty
+        let mut tier_free_space = HashMap::new();
+        tier_free_space.insert("cache".to_string(), TB * 3 / 10);
+        tier_free_space.insert("warm".to_string(), TB / 10);
+        tier_free_space.insert("cold".to_string(), TB);
+
+        let evicted = EvictionPlanner::new(&tiers).evict_excess_usage(
+            &mut decisions,
+            &mut tier_free_space,
+            &mut unlimited(),
+        );
+
+        assert_eq!(evicted, 1);
+        assert!(matches!(
+            &decisions[0],
+            PlacementDecision::Demote { to_tier, .. } if to_tier == "warm"
+        ));
+        assert!(matches!(
+            &decisions[1],
+            PlacementDecision::Demote { from_tier, to_tier, .. } if from_tier == "warm" && to_tier == "cold"
+        ));
+        // Files more important than the evicted one are not pushed down
+        assert!(matches!(&decisions[2], PlacementDecision::Stay { .. }));
+        assert_eq!(tier_free_space["cold"], TB - TB / 4);
+    }
+
+    #[test]
+    fn test_eviction_cascade_gives_up_without_room() {
+        let cache = create_test_tier("cache", 1, Some(50));
+        let warm = create_test_tier("warm", 5, None);
+        let tiers = vec![cache, warm];
+
+        let mut decisions = vec![
+            stay("/cache/movie.mkv", TB / 5, 3600, 10),
+            stay("/warm/old.mkv", TB / 4, 7200, 10),
+        ];
+        let mut tier_free_space = HashMap::new();
+        tier_free_space.insert("cache".to_string(), TB * 3 / 10);
+        tier_free_space.insert("warm".to_string(), TB / 10);
+
+        let evicted = EvictionPlanner::new(&tiers).evict_excess_usage(
+            &mut decisions,
+            &mut tier_free_space,
+            &mut unlimited(),
+        );
+
+        // No tier below warm: nothing moves
+        assert_eq!(evicted, 0);
+        assert!(demoted(&decisions).is_empty());
+    }
+
+    #[test]
+    fn test_replan_skips_file_cascaded_meanwhile() {
+        let cache = create_test_tier("cache", 1, None);
+        let warm = create_test_tier("warm", 5, None);
+        let cold = create_test_tier("cold", 10, None);
+        let tiers = vec![cache, warm, cold];
+
+        let mut decisions = vec![
+            stay("/cache/evictable.mkv", TB / 2, 3600, 60),
+            stay("/warm/filler.mkv", TB / 4, 3600, 10),
+            stay("/warm/wanted.mkv", TB / 4, 3600, 50),
+            stay("/cold/urgent.mkv", TB / 4, 3600, 90),
+        ];
+        let blocked = |idx: usize, priority: u32| BlockedPlacement {
+            file: Arc::clone(decisions[idx].file()),
+            current_tier: decisions[idx].current_tier().to_string(),
+            desired_tier: "cache".to_string(),
+            strategy_name: "default".to_string(),
+            strategy_priority: priority,
+            strategy_quota: MoveQuota::default(),
+        };
+        let blocked = vec![blocked(3, 90), blocked(2, 50)];
+        let mut tier_free_space = HashMap::new();
+        tier_free_space.insert("cache".to_string(), TB / 10);
+        tier_free_space.insert("warm".to_string(), 0);
+        tier_free_space.insert("cold".to_string(), TB);
+
+        EvictionPlanner::new(&tiers).evict_to_make_space(
+            &mut decisions,
+            blocked,
+            &mut tier_free_space,
+            &mut unlimited(),
+        );
+
+        // Making room on warm pushed the wanted file to cold; it is not promoted from warm too
+        assert!(matches!(
+            &decisions[2],
+            PlacementDecision::Demote { from_tier, to_tier, .. } if from_tier == "warm" && to_tier == "cold"
+        ));
+        assert!(matches!(&decisions[3], PlacementDecision::Promote { .. }));
+        assert_eq!(tier_free_space["cache"], TB / 10 + TB / 2 - TB / 4);
+        assert_eq!(tier_free_space["warm"], 0);
+    }
+
+    #[test]
+    fn test_excess_usage_skips_files_in_cooldown() {
+        use crate::StateJournal;
+        use std::time::{Duration, SystemTime};
+
+        let now = SystemTime::now();
+        let mut journal = StateJournal::in_memory();
+        journal.record_move(
+            std::path::PathBuf::from("recent.mkv"),
+            now - Duration::from_secs(3600),
+        );
+        let cooldown = MoveCooldown::from_journal(&journal, Duration::from_secs(24 * 3600), now);
+
+        let cache = create_test_tier("cache", 1, Some(50));
+        let storage = create_test_tier("storage", 10, None);
+        let tiers = vec![cache, storage];
+
+        let on_cache = |name: &str, size: u64, priority: u32| {
+            let mut decision = stay(&format!("/mock/{name}"), size, 3600, priority);
+            if let PlacementDecision::Stay { current_tier, .. } = &mut decision {
+                *current_tier = "cache".to_string();
+            }
+            decision
+        };
+        // 60% used; the recently moved file would be evicted first
+        let mut decisions = vec![
+            on_cache("recent.mkv", TB * 3 / 10, 10),
+            on_cache("other.mkv", TB / 10, 50),
+        ];
+        let mut tier_free_space = HashMap::new();
+        tier_free_space.insert("cache".to_string(), TB * 4 / 10);
+        tier_free_space.insert("storage".to_string(), TB);
+
+        let evicted = EvictionPlanner::new(&tiers)
+            .with_cooldown(Some(&cooldown))
+            .evict_excess_usage(&mut decisions, &mut tier_free_space, &mut unlimited());
+
+        assert_eq!(evicted, 1);
+        assert_eq!(demoted(&decisions), vec!["/mock/other.mkv"]);
+    }
+
+    #[test]
+    fn test_failed_cascade_leaves_no_moves() {
+        let cache = create_test_tier("cache", 1, Some(50));
+        let warm = create_test_tier("warm", 5, None);
+        let cold = create_test_tier("cold", 10, None);
+        let tiers = vec![cache, warm, cold];
+
+        let mut decisions = vec![
+            stay("/cache/movie.mkv", TB / 5, 3600, 10),
+            stay("/warm/first.mkv", TB / 10, 7200, 10),
+            stay("/warm/second.mkv", TB / 10, 3600, 10),
+        ];
+        // warm needs both files gone; cold has room for the first only
+        let mut tier_free_space = HashMap::new();
+        tier_free_space.insert("cache".to_string(), TB * 3 / 10);
+        tier_free_space.insert("warm".to_string(), 0);
+        tier_free_space.insert("cold".to_string(), TB / 10);
+        let free_before = tier_free_space.clone();
+
+        let evicted = EvictionPlanner::new(&tiers).evict_excess_usage(
+            &mut decisions,
+            &mut tier_free_space,
+            &mut unlimited(),
+        );
+
+        assert_eq!(evicted, 0);
+        assert!(demoted(&decisions).is_empty());
+        assert_eq!(tier_free_space, free_before);
+    }
 }
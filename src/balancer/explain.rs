@@ -0,0 +1,121 @@
+use crate::file::serialize_path_lossy;
+use crate::inventory::InventoryAction;
+use serde::Serialize;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Why the planner decided what it did for a single file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileExplanation {
+    #[serde(serialize_with = "serialize_path_lossy")]
+    pub path: PathBuf,
+    pub size: u64,
+    pub current_tier: String,
+    /// Every strategy, highest priority first
+    pub strategies: Vec<StrategyEvaluation>,
+    /// Highest-priority matching strategy, `None` if nothing matched
+    pub winning_strategy: Option<String>,
+    /// Preferred tiers of the winning strategy, in order
+    pub tier_checks: Vec<TierCheck>,
+    pub decision: InventoryAction,
+    pub target_tier: Option<String>,
+    /// Strategy behind the final decision; differs from the winner after eviction or promotion
+    pub decided_by: String,
+    /// Plan warnings about this file
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StrategyEvaluation {
+    pub strategy: String,
+    pub priority: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    /// Inside its schedule when the plan was made
+    pub active: bool,
+    pub conditions: Vec<ConditionCheck>,
+    pub matched: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConditionCheck {
+    pub condition: String,
+    pub passed: bool,
+}
+
+/// Whether a preferred tier could take the file when planning started
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TierCheck {
+    pub tier: String,
+    pub free_bytes: u64,
+    /// Enough free space, within `max_usage_percent`
+    pub fits: bool,
+    /// Move policies allow moving from the current tier
+    pub allowed: bool,
+}
+
+impl fmt::Display for FileExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "File: {} ({} bytes)", self.path.display(), self.size)?;
+        writeln!(f, "Current tier: {}", self.current_tier)?;
+
+        writeln!(f, "\nStrategies (highest priority first):")?;
+        for strategy in &self.strategies {
+            write!(
+                f,
+                "  [{}] {} (priority {}",
+                if strategy.matched {
+                    "match"
+                } else {
+                    "no match"
+                },
+                strategy.strategy,
+                strategy.priority
+            )?;
+            match &strategy.schedule {
+                Some(schedule) if strategy.active => writeln!(f, ", schedule {schedule})")?,
+                Some(schedule) => writeln!(f, ", outside schedule {schedule})")?,
+                None => writeln!(f, ")")?,
+            }
+            for condition in &strategy.conditions {
+                writeln!(
+                    f,
+                    "      {} {}",
+                    if condition.passed { "pass" } else { "FAIL" },
+                    condition.condition
+                )?;
+            }
+        }
+
+        match &self.winning_strategy {
+            Some(strategy) => writeln!(f, "\nWinning strategy: {strategy}")?,
+            None => writeln!(f, "\nWinning strategy: none (file stays where it is)")?,
+        }
+        for check in &self.tier_checks {
+            writeln!(
+                f,
+                "  {}: {} bytes free, {}, {}",
+                check.tier,
+                check.free_bytes,
+                if check.fits { "fits" } else { "does not fit" },
+                if check.allowed {
+                    "move allowed"
+                } else {
+                    "move denied by policy"
+                }
+            )?;
+        }
+
+        let decision = match (&self.decision, &self.target_tier) {
+            (InventoryAction::Stay, _) | (_, None) => format!("stay on {}", self.current_tier),
+            (InventoryAction::Promote, Some(to)) => format!("promote to {to}"),
+            (InventoryAction::Demote, Some(to)) => format!("demote to {to}"),
+            (InventoryAction::Copy, Some(to)) => format!("copy to {to}"),
+        };
+        writeln!(f, "\nDecision: {decision} (strategy: {})", self.decided_by)?;
+        for warning in &self.warnings {
+            writeln!(f, "  warning: {warning}")?;
+        }
+        Ok(())
+    }
+}
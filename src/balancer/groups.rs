@@ -0,0 +1,255 @@
+use super::{BalancingPlan, PlacementDecision};
+use crate::Tier;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+/// Moves of one strategy out of one directory between the same two tiers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MoveGroup {
+    pub strategy: String,
+    /// Parent directory relative to the source tier root, `.` at the root
+    pub directory: String,
+    pub from_tier: String,
+    pub to_tier: String,
+    pub copy: bool,
+    pub files: usize,
+    pub bytes: u64,
+    /// Positions of the group's decisions in `BalancingPlan::decisions`
+    #[serde(skip)]
+    pub decisions: Vec<usize>,
+}
+
+/// Moves of one kind out of one directory between the same two tiers, across strategies
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DirectoryRollup {
+    /// `promote`, `demote` or `copy`
+    pub action: &'static str,
+    pub from_tier: String,
+    /// Parent directory relative to the source tier root, `.` at the root
+    pub directory: String,
+    pub to_tier: String,
+    pub files: usize,
+    pub bytes: u64,
+    /// Strategies behind the moves, sorted by name
+    pub strategies: Vec<String>,
+}
+
+/// `Demote 42 files (120.00 GB) from cache:/Show.X → storage`
+impl fmt::Display for DirectoryRollup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut chars = self.action.chars();
+        let action: String = chars
+            .next()
+            .map(|first| first.to_ascii_uppercase())
+            .into_iter()
+            .chain(chars)
+            .collect();
+        let directory = if self.directory == "." {
+            ""
+        } else {
+            &self.directory
+        };
+        write!(
+            f,
+            "{action} {} file{} ({:.2} GB) from {}:/{directory} → {}",
+            self.files,
+            if self.files == 1 { "" } else { "s" },
+            self.bytes as f64 / 1_000_000_000.0,
+            self.from_tier,
+            self.to_tier
+        )
+    }
+}
+
+/// Parent directory of the decision's file relative to its tier root, `.` at the root
+fn parent_directory(decision: &PlacementDecision, tiers: &[Tier]) -> String {
+    tiers
+        .iter()
+        .find(|t| t.name == decision.current_tier())
+        .and_then(|tier| tier.relative_path(decision.file_path()))
+        .and_then(Path::parent)
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .map_or_else(|| ".".to_string(), |parent| parent.display().to_string())
+}
+
+impl BalancingPlan {
+    /// The plan's moves and copies grouped by strategy and directory, sorted by both
+    pub fn move_groups(&self, tiers: &[Tier]) -> Vec<MoveGroup> {
+        let mut groups: BTreeMap<(&str, String, &str, &str, bool), MoveGroup> = BTreeMap::new();
+        for (index, decision) in self.decisions.iter().enumerate() {
+            if matches!(decision, PlacementDecision::Stay { .. }) {
+                continue;
+            }
+            let directory = parent_directory(decision, tiers);
+            let key = (
+                decision.strategy_name(),
+                directory.clone(),
+                decision.current_tier(),
+                decision.target_tier(),
+                decision.is_copy(),
+            );
+            let group = groups.entry(key).or_insert_with(|| MoveGroup {
+                strategy: decision.strategy_name().to_string(),
+                directory,
+                from_tier: decision.current_tier().to_string(),
+                to_tier: decision.target_tier().to_string(),
+                copy: decision.is_copy(),
+                files: 0,
+                bytes: 0,
+                decisions: Vec::new(),
+            });
+            group.files += 1;
+            group.bytes += decision.file_size();
+            group.decisions.push(index);
+        }
+        groups.into_values().collect()
+    }
+
+    /// The plan's moves and copies summed per directory, action and tier pair, most bytes first
+    pub fn rollup_by_directory(&self, tiers: &[Tier]) -> Vec<DirectoryRollup> {
+        let mut rollups: BTreeMap<(&str, String, &str, &str), DirectoryRollup> = BTreeMap::new();
+        for decision in &self.decisions {
+            if matches!(decision, PlacementDecision::Stay { .. }) {
+                continue;
+            }
+            let directory = parent_directory(decision, tiers);
+            let key = (
+                decision.current_tier(),
+                directory.clone(),
+                decision.target_tier(),
+                decision.action(),
+            );
+            let rollup = rollups.entry(key).or_insert_with(|| DirectoryRollup {
+                action: decision.action(),
+                from_tier: decision.current_tier().to_string(),
+                directory,
+                to_tier: decision.target_tier().to_string(),
+                files: 0,
+                bytes: 0,
+                strategies: Vec::new(),
+            });
+            rollup.files += 1;
+            rollup.bytes += decision.file_size();
+            if let Err(at) = rollup
+                .strategies
+                .binary_search_by(|s| s.as_str().cmp(decision.strategy_name()))
+            {
+                rollup
+                    .strategies
+                    .insert(at, decision.strategy_name().to_string());
+            }
+        }
+        let mut rollups: Vec<DirectoryRollup> = rollups.into_values().collect();
+        // Stable, so equal sizes keep the tier and directory order
+        rollups.sort_by_key(|rollup| Reverse(rollup.bytes));
+        rollups
+    }
+
+    /// Keeps the files of the given decisions in place; returns how many moves were dropped
+    ///
+    /// Projections and batches are left as planned.
+    pub fn skip_decisions(&mut self, indices: impl IntoIterator<Item = usize>) -> usize {
+        let mut skipped = 0;
+        for index in indices {
+            if let Some(decision) = self.decisions.get_mut(index)
+                && !matches!(decision, PlacementDecision::Stay { .. })
+            {
+                *decision = decision.to_stay();
+                skipped += 1;
+            }
+        }
+        skipped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileInfo;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::SystemTime;
+
+    fn demote(path: &str, size: u64, strategy: &str) -> PlacementDecision {
+        PlacementDecision::Demote {
+            file: Arc::new(FileInfo {
+                path: PathBuf::from(path),
+                size,
+                modified: SystemTime::UNIX_EPOCH,
+                accessed: SystemTime::UNIX_EPOCH,
+                owner: None,
+            }),
+            from_tier: "cache".to_string(),
+            to_tier: "storage".to_string(),
+            strategy: strategy.to_string(),
+            priority: 1,
+        }
+    }
+
+    #[test]
+    fn test_move_groups_and_skip() {
+        let tiers =
+            vec![Tier::new_unchecked("cache".into(), "/mnt/cache".into(), 1, None, None).unwrap()];
+        let mut plan = BalancingPlan {
+            decisions: vec![
+                demote("/mnt/cache/Show/S01/e1.mkv", 10, "old"),
+                demote("/mnt/cache/Movie.mkv", 5, "old"),
+                demote("/mnt/cache/Show/S01/e2.mkv", 20, "old"),
+                demote("/mnt/cache/Show/S01/e3.mkv", 1, "big"),
+            ],
+            ..Default::default()
+        };
+
+        let groups = plan.move_groups(&tiers);
+        let summary: Vec<_> = groups
+            .iter()
+            .map(|g| (g.strategy.as_str(), g.directory.as_str(), g.files, g.bytes))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("big", "Show/S01", 1, 1),
+                ("old", ".", 1, 5),
+                ("old", "Show/S01", 2, 30),
+            ]
+        );
+        assert_eq!(groups[2].decisions, vec![0, 2]);
+
+        assert_eq!(plan.skip_decisions(groups[2].decisions.clone()), 2);
+        assert_eq!(plan.move_count(), 2);
+        assert!(matches!(plan.decisions[0], PlacementDecision::Stay { .. }));
+        assert_eq!(plan.decisions[0].strategy_name(), "old");
+        assert_eq!(plan.skip_decisions([0]), 0);
+    }
+
+    #[test]
+    fn test_rollup_by_directory() {
+        let tiers =
+            vec![Tier::new_unchecked("cache".into(), "/mnt/cache".into(), 1, None, None).unwrap()];
+        let plan = BalancingPlan {
+            decisions: vec![
+                demote("/mnt/cache/Show.X/e1.mkv", 10, "old"),
+                demote("/mnt/cache/Movie.mkv", 5, "old"),
+                demote("/mnt/cache/Show.X/e2.mkv", 20, "big"),
+                demote("/mnt/cache/Show.X/e3.mkv", 40, "old").to_stay(),
+            ],
+            ..Default::default()
+        };
+
+        let rollups = plan.rollup_by_directory(&tiers);
+        assert_eq!(rollups.len(), 2);
+        assert_eq!((rollups[0].files, rollups[0].bytes), (2, 30));
+        assert_eq!(rollups[0].strategies, vec!["big", "old"]);
+        assert_eq!(
+            rollups[0].to_string(),
+            "Demote 2 files (0.00 GB) from cache:/Show.X → storage"
+        );
+        assert_eq!(
+            rollups[1].to_string(),
+            "Demote 1 file (0.00 GB) from cache:/ → storage"
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use super::PlacementDecision;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// What Pass 2 made of the files one strategy won
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct StrategyHits {
+    /// Files the strategy won
+    pub files: usize,
+    pub bytes: u64,
+    /// Promotions, demotions and copies
+    pub moves: usize,
+    pub stays: usize,
+    /// Files whose preferred tier had no room
+    pub blocked: usize,
+}
+
+/// Per-strategy outcome of Pass 2, by strategy name
+///
+/// Every configured strategy is listed, so one that never matches shows up
+/// with zero files. Evictions and promotions in Pass 3 are not counted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct StrategyStats {
+    strategies: BTreeMap<String, StrategyHits>,
+}
+
+impl StrategyStats {
+    /// Starts every named strategy at zero
+    pub fn new<'a>(strategies: impl IntoIterator<Item = &'a str>) -> Self {
+        Self {
+            strategies: strategies
+                .into_iter()
+                .map(|name| (name.to_string(), StrategyHits::default()))
+                .collect(),
+        }
+    }
+
+    /// Counts the decision Pass 2 made for one file
+    pub fn record(&mut self, decision: &PlacementDecision, blocked: bool) {
+        let hits = self
+            .strategies
+            .entry(decision.strategy_name().to_string())
+            .or_default();
+        hits.files += 1;
+        hits.bytes += decision.file_size();
+        if matches!(decision, PlacementDecision::Stay { .. }) {
+            hits.stays += 1;
+        } else {
+            hits.moves += 1;
+        }
+        if blocked {
+            hits.blocked += 1;
+        }
+    }
+
+    pub fn get(&self, strategy: &str) -> Option<&StrategyHits> {
+        self.strategies.get(strategy)
+    }
+
+    /// Strategies and their hits, by name
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &StrategyHits)> {
+        self.strategies
+            .iter()
+            .map(|(name, hits)| (name.as_str(), hits))
+    }
+
+    /// Strategies that won no file
+    pub fn unused(&self) -> impl Iterator<Item = &str> {
+        self.iter()
+            .filter(|(_, hits)| hits.files == 0)
+            .map(|(name, _)| name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strategies.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileInfo;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::SystemTime;
+
+    fn demote(size: u64, strategy: &str) -> PlacementDecision {
+        PlacementDecision::Demote {
+            file: Arc::new(FileInfo {
+                path: PathBuf::from("/mnt/cache/a.mkv"),
+                size,
+                modified: SystemTime::UNIX_EPOCH,
+                accessed: SystemTime::UNIX_EPOCH,
+                owner: None,
+            }),
+            from_tier: "cache".to_string(),
+            to_tier: "storage".to_string(),
+            strategy: strategy.to_string(),
+            priority: 1,
+        }
+    }
+
+    #[test]
+    fn test_strategy_stats() {
+        let mut stats = StrategyStats::new(["old", "dead"]);
+        stats.record(&demote(10, "old"), false);
+        stats.record(&demote(5, "old").to_stay(), true);
+        stats.record(&demote(1, "no-match").to_stay(), false);
+
+        assert_eq!(
+            stats.get("old"),
+            Some(&StrategyHits {
+                files: 2,
+                bytes: 15,
+                moves: 1,
+                stays: 1,
+                blocked: 1,
+            })
+        );
+        assert_eq!(stats.get("no-match").map(|h| h.stays), Some(1));
+        assert_eq!(stats.unused().collect::<Vec<_>>(), vec!["dead"]);
+        assert_eq!(
+            serde_json::to_value(&stats).unwrap()["dead"]["files"],
+            serde_json::json!(0)
+        );
+    }
+}
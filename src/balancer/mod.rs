@@ -1,126 +1,677 @@
+mod affinity;
+mod batching;
+mod builder;
+mod caps;
+mod cooldown;
 mod decision;
 mod eviction;
+mod explain;
+mod groups;
+mod hits;
 mod plan;
+mod progress;
+mod promotion;
+#[cfg(test)]
+mod properties;
+mod quota;
 mod state;
+mod ties;
 
+pub use affinity::SidecarAffinity;
+pub use batching::MoveBatch;
+pub use builder::BalancerBuilder;
+pub use caps::PlanCaps;
+pub use cooldown::MoveCooldown;
 pub use decision::PlacementDecision;
-pub use plan::{BalancingPlan, PlanWarning, TierUsageProjection};
-
-use crate::{Context, FileInfo, FileStats, GlobalStats, PlacementStrategy, TautulliConfig, Tier};
+pub use explain::{ConditionCheck, FileExplanation, StrategyEvaluation, TierCheck};
+pub use groups::{DirectoryRollup, MoveGroup};
+pub use hits::{StrategyHits, StrategyStats};
+pub use plan::{
+    BalancingPlan, DirectoryComposition, DrainProgress, PlanWarning, RemainingDirectory,
+    RemainingFile, StrategyBytes, TierOffenders, TierUsageProjection, WarningSeverity,
+};
+pub use progress::{PlanningPhase, PlanningProgress, PlanningProgressCallback};
+pub use quota::MoveQuota;
+
+use crate::conditions::ConditionMemo;
+use crate::move_policy::MovePolicies;
+use crate::{
+    Clock, Context, FileInfo, FileScanner, FileStats, FilteredFiles, FixedClock, GlobalStats,
+    PlacementStrategy, SeenAges, SonarrConfig, SystemClock, Tier, TierScanner, TorrentClientConfig,
+    WatchHistoryConfig, WatchHistoryProvider,
+};
+use quota::QuotaTracker;
 use state::{BlockedPlacement, PlanningState};
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::path::{Component, Path};
 use std::sync::Arc;
+use std::thread;
+use std::time::SystemTime;
+
+/// Files and directories listed per tier in `largest_remaining`
+const LARGEST_REMAINING: usize = 5;
+
+/// Strategies named in a `PinnedOverCapacity` warning
+const TOP_PINNED_STRATEGIES: usize = 3;
+
+/// Strategy named on decisions for draining files no strategy matched
+const DRAIN_STRATEGY: &str = "drain";
+
+/// Files matched ahead of placement at a time, bounding the buffered match results
+const MATCH_BATCH: usize = 64 * 1024;
+
+/// Smallest batch whose matching is spread over threads
+const PARALLEL_MATCH_MIN: usize = 1024;
+
+/// A scanned file and the tier it is on
+type ScannedFile<'a> = (Arc<FileInfo>, &'a Tier);
 
 pub struct Balancer {
     tiers: Vec<Tier>,
     strategies: Vec<PlacementStrategy>,
-    tautulli_config: Option<TautulliConfig>,
+    watch_history: Option<WatchHistoryConfig>,
+    sonarr: Option<SonarrConfig>,
+    sonarr_lookahead_days: u32,
+    torrent_client: Option<TorrentClientConfig>,
+    move_policies: MovePolicies,
+    run_quota: MoveQuota,
+    cooldown: Option<MoveCooldown>,
+    seen_ages: Option<Arc<SeenAges>>,
+    clock: Arc<dyn Clock>,
+    scanner: Arc<dyn FileScanner>,
+    watch_history_provider: Option<Arc<dyn WatchHistoryProvider>>,
+    seed: Option<u64>,
+    progress: Option<PlanningProgressCallback>,
+    sidecar_affinity: Option<SidecarAffinity>,
+    planning_threads: Option<usize>,
 }
 
 impl Balancer {
-    pub const fn new(
+    pub fn new(
         tiers: Vec<Tier>,
         strategies: Vec<PlacementStrategy>,
-        tautulli_config: Option<TautulliConfig>,
+        watch_history: Option<WatchHistoryConfig>,
     ) -> Self {
         Self {
             tiers,
             strategies,
-            tautulli_config,
+            watch_history,
+            sonarr: None,
+            sonarr_lookahead_days: 0,
+            torrent_client: None,
+            move_policies: MovePolicies::new(Vec::new()),
+            run_quota: MoveQuota::new(None, None),
+            cooldown: None,
+            seen_ages: None,
+            clock: Arc::new(SystemClock),
+            scanner: Arc::new(TierScanner),
+            watch_history_provider: None,
+            seed: None,
+            progress: None,
+            sidecar_affinity: None,
+            planning_threads: None,
         }
     }
 
+    /// Configure a balancer step by step, with injectable I/O and time
+    pub fn builder(tiers: Vec<Tier>, strategies: Vec<PlacementStrategy>) -> BalancerBuilder {
+        BalancerBuilder::new(tiers, strategies)
+    }
+
+    /// Restrict which tier-to-tier moves the planner may schedule
+    pub fn with_move_policies(mut self, move_policies: MovePolicies) -> Self {
+        self.move_policies = move_policies;
+        self
+    }
+
+    /// Limit the moves planned per run across all strategies
+    pub const fn with_run_quota(mut self, run_quota: MoveQuota) -> Self {
+        self.run_quota = run_quota;
+        self
+    }
+
+    /// Load Sonarr library state, fetching the calendar `lookahead_days` ahead
+    pub fn with_sonarr(mut self, config: SonarrConfig, lookahead_days: u32) -> Self {
+        self.sonarr = Some(config);
+        self.sonarr_lookahead_days = lookahead_days;
+        self
+    }
+
+    /// Load the torrent list for `torrent_*` conditions
+    pub fn with_torrent_client(mut self, config: TorrentClientConfig) -> Self {
+        self.torrent_client = Some(config);
+        self
+    }
+
+    /// Keep files that moved recently where they are
+    pub fn with_move_cooldown(mut self, cooldown: MoveCooldown) -> Self {
+        self.cooldown = Some(cooldown);
+        self
+    }
+
+    /// First-seen ages for `seen_age` conditions
+    pub fn with_seen_ages(mut self, seen_ages: SeenAges) -> Self {
+        self.seen_ages = Some(Arc::new(seen_ages));
+        self
+    }
+
+    /// Time strategy schedules are evaluated at, the current time by default
+    pub fn with_planned_at(self, planned_at: SystemTime) -> Self {
+        self.with_clock(Arc::new(FixedClock::new(planned_at)))
+    }
+
+    /// Source of the current time for schedules and age conditions
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Lists the files of each tier, walking the tier directory by default
+    pub fn with_scanner(mut self, scanner: Arc<dyn FileScanner>) -> Self {
+        self.scanner = scanner;
+        self
+    }
+
+    /// Fetch watch history from `provider` instead of the one named in the config
+    pub fn with_watch_history_provider(mut self, provider: Arc<dyn WatchHistoryProvider>) -> Self {
+        self.watch_history_provider = Some(provider);
+        self
+    }
+
+    /// Seed recorded in the plan for reproducibility reports
+    pub const fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Report scanning, placement and eviction progress to `callback`
+    pub fn with_progress(mut self, callback: PlanningProgressCallback) -> Self {
+        self.progress = Some(callback);
+        self
+    }
+
+    /// Move sidecar files along with their media file
+    pub fn with_sidecar_affinity(mut self, affinity: SidecarAffinity) -> Self {
+        self.sidecar_affinity = Some(affinity);
+        self
+    }
+
+    /// Threads matching files against strategies in Pass 2, all cores by default
+    ///
+    /// Plans are identical for any count; 1 keeps matching on the planning thread.
+    /// Matching stays on it anyway while any condition's `reads_planning` is true,
+    /// the default for conditions outside this crate.
+    pub const fn with_planning_threads(mut self, threads: usize) -> Self {
+        self.planning_threads = Some(threads);
+        self
+    }
+
+    fn report(&self, progress: &PlanningProgress) {
+        if let Some(callback) = &self.progress {
+            callback(progress);
+        }
+    }
+
+    fn planned_at(&self) -> SystemTime {
+        self.clock.now()
+    }
+
     pub fn plan_rebalance(&self) -> BalancingPlan {
-        let file_map = self.scan_all_tiers();
+        let mut progress = PlanningProgress::new();
+        let (files, filtered_files) = self.scan_all_tiers(&mut progress);
+        progress.phase = PlanningPhase::Statistics;
+        self.report(&progress);
+        let global_stats = self.collect_all_stats(&files);
+        let mut plan = self.plan_with_stats(files, &global_stats, &mut progress);
+        plan.filtered_files = filtered_files;
+        plan
+    }
 
-        // PASS 1: Collect statistics from all files
+    /// Runs the planner and explains the decision for the file at `path`
+    ///
+    /// Returns `None` if no tier contains the file.
+    pub fn explain_file(&self, path: &Path) -> Option<FileExplanation> {
+        let mut progress = PlanningProgress::new();
+        let (files, _) = self.scan_all_tiers(&mut progress);
+        let (file, current_tier) = files
+            .iter()
+            .find(|(file, _)| file.path == path)
+            .map(|(file, tier)| (Arc::clone(file), *tier))?;
+        let global_stats = self.collect_all_stats(&files);
+
+        let mut context = Context::new()
+            .with_global_stats(&global_stats)
+            .with_now(self.planned_at());
+        context.current_tier_path = current_tier.root_of(&file.path).map(Path::to_path_buf);
+        context.planning = PlanningState::new(&self.tiers).view;
+
+        let mut strategies: Vec<_> = self.strategies.iter().collect();
+        strategies.sort_by(|s1, s2| {
+            s2.priority
+                .cmp(&s1.priority)
+                .then_with(|| s1.name.cmp(&s2.name))
+        });
+        let now = self.planned_at();
+        let strategies = strategies
+            .into_iter()
+            .map(|strategy| {
+                let active = strategy.is_active_at(now);
+                let conditions: Vec<_> = strategy
+                    .evaluate_conditions(&file, &context)
+                    .map(|(condition, passed)| ConditionCheck {
+                        condition: condition.to_string(),
+                        passed,
+                    })
+                    .collect();
+                StrategyEvaluation {
+                    strategy: strategy.name.clone(),
+                    priority: strategy.priority,
+                    schedule: strategy.schedule().map(ToString::to_string),
+                    active,
+                    matched: active && conditions.iter().all(|c| c.passed),
+                    conditions,
+                }
+            })
+            .collect();
+
+        let winner = self.find_matching_strategy(&file, &context);
+        let tier_checks = winner
+            .map(|strategy| {
+                self.preferred_tiers(strategy)
+                    .map(|tier| {
+                        let free_bytes = tier.get_free_space();
+                        TierCheck {
+                            tier: tier.name.clone(),
+                            free_bytes,
+                            fits: self.can_accept_file(tier, file.size, free_bytes),
+                            allowed: self.may_move_to(current_tier, tier),
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let winning_strategy = winner.map(|s| s.name.clone());
+        let current_tier = current_tier.name.clone();
+
+        let plan = self.plan_with_stats(files, &global_stats, &mut progress);
+        let entry = crate::inventory::InventoryEntry::from_decision(
+            plan.decisions.iter().find(|d| d.file_path() == path)?,
+        );
+
+        Some(FileExplanation {
+            path: file.path.clone(),
+            size: file.size,
+            current_tier,
+            strategies,
+            winning_strategy,
+            tier_checks,
+            decision: entry.decision,
+            target_tier: entry.target_tier,
+            decided_by: entry.strategy.unwrap_or_else(|| "no-match".to_string()),
+            warnings: plan
+                .warnings
+                .iter()
+                .filter(|w| w.file() == Some(path))
+                .map(ToString::to_string)
+                .collect(),
+        })
+    }
+
+    /// PASS 1: Collect statistics from all files and external services
+    fn collect_all_stats(&self, files: &[ScannedFile<'_>]) -> Arc<GlobalStats> {
         tracing::info!(
             "Pass 1: Collecting statistics from {} files...",
-            file_map.len()
+            files.len()
         );
-        let mut global_stats = self.collect_global_stats(file_map.keys().map(|arc| &**arc));
+        let mut global_stats = self.collect_global_stats(files.iter().map(|(file, _)| &**file));
 
-        // Load Tautulli data if configured
-        if let Some(tautulli_config) = &self.tautulli_config {
-            tracing::info!("Loading Tautulli viewing history...");
-            match self.load_tautulli_stats(file_map.keys().map(|arc| &**arc), tautulli_config) {
+        // Load viewing history if configured
+        if let Some(watch_history) = &self.watch_history {
+            tracing::info!("Loading {} viewing history...", watch_history.provider);
+            match self.load_tautulli_stats(files.iter().map(|(file, _)| &**file), watch_history) {
                 Ok(tautulli_stats) => {
                     tracing::info!(
-                        "Tautulli loaded: {} active episodes across {} users",
+                        "{} loaded: {} active episodes across {} users",
+                        watch_history.provider,
                         tautulli_stats.active_window_episodes.len(),
                         tautulli_stats.user_progress.len()
                     );
                     global_stats = global_stats.with_tautulli(tautulli_stats);
                 }
                 Err(e) => {
-                    tracing::warn!("Failed to load Tautulli data: {e}. Continuing without it.");
+                    tracing::warn!(
+                        "Failed to load {} data: {e}. Continuing without it.",
+                        watch_history.provider
+                    );
+                }
+            }
+        }
+
+        if let Some(sonarr) = &self.sonarr {
+            tracing::info!("Loading Sonarr library...");
+            match self.load_sonarr_stats(sonarr) {
+                Ok(sonarr_stats) => {
+                    tracing::info!(
+                        "Sonarr loaded: {} series airing in the next {} days",
+                        sonarr_stats.upcoming_series(),
+                        self.sonarr_lookahead_days
+                    );
+                    global_stats = global_stats.with_sonarr(sonarr_stats);
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to load Sonarr data: {e}. Continuing without it.");
                 }
             }
         }
 
+        if let Some(torrent_client) = &self.torrent_client {
+            tracing::info!("Loading {} torrents...", torrent_client.client);
+            match self.load_torrent_stats(torrent_client) {
+                Ok(torrent_stats) => {
+                    tracing::info!(
+                        "{} loaded: {} torrents, {} files seeding",
+                        torrent_client.client,
+                        torrent_stats.torrent_count(),
+                        torrent_stats.seeding_files()
+                    );
+                    global_stats = global_stats.with_torrents(torrent_stats);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to load {} data: {e}. Continuing without it.",
+                        torrent_client.client
+                    );
+                }
+            }
+        }
+
+        if let Some(seen_ages) = &self.seen_ages {
+            global_stats = global_stats.with_seen_ages(seen_ages);
+        }
+
         let global_stats = Arc::new(global_stats);
         tracing::info!(
             "Statistics collected: {} directories",
-            global_stats.file_stats.directory_files.len()
+            global_stats.file_stats.directory_count()
         );
+        global_stats
+    }
 
+    /// PASS 2 and 3: Place every file, then evict and promote to fit the tiers
+    ///
+    /// Holds every scanned file until the plan is done: files are placed in a
+    /// global deterministic order and evictions revisit earlier decisions, so
+    /// placement cannot stream batches straight from the scan.
+    fn plan_with_stats(
+        &self,
+        files: Vec<ScannedFile<'_>>,
+        global_stats: &Arc<GlobalStats>,
+        progress: &mut PlanningProgress,
+    ) -> BalancingPlan {
         // PASS 2: Apply strategies with statistics
         tracing::info!("Pass 2: Planning file placement...");
+        progress.phase = PlanningPhase::Placement;
+        self.report(progress);
         let mut state = PlanningState::new(&self.tiers);
+        state.decisions.reserve_exact(files.len());
+        if self
+            .strategies
+            .iter()
+            .any(|s| s.action == crate::StrategyAction::Copy)
+        {
+            state.present = files
+                .iter()
+                .filter_map(|(file, tier)| {
+                    let relative = tier.relative_path(&file.path)?;
+                    Some((tier.name.clone(), relative.to_path_buf()))
+                })
+                .collect();
+        }
 
-        let files: Vec<_> = file_map.into_iter().collect();
         let files = self.sort_files_deterministically(files);
 
-        let mut context = Context::new().with_global_stats(&global_stats);
+        let mut context = Context::new()
+            .with_global_stats(global_stats)
+            .with_now(self.planned_at());
+        let mut quotas = QuotaTracker::new(self.run_quota);
+        let mut strategy_stats =
+            StrategyStats::new(self.strategies.iter().map(|s| s.name.as_str()));
+        let threads = self.matching_threads();
+
+        for batch in files.chunks(MATCH_BATCH) {
+            // Matching runs ahead on all threads, placement stays serial and in order
+            let mut matched = if threads > 1 && batch.len() >= PARALLEL_MATCH_MIN {
+                self.match_in_parallel(batch, &context, threads, &mut state.condition_memo)
+            } else {
+                Vec::new()
+            }
+            .into_iter();
+            for (file, current_tier) in batch {
+                let (file, current_tier) = (Arc::clone(file), *current_tier);
+                context.current_tier_path = current_tier.root_of(&file.path).map(Path::to_path_buf);
+                let candidates = matched.next().unwrap_or_else(|| {
+                    context.planning.clone_from(&state.view);
+                    state.condition_memo.start_file();
+                    self.top_matching_strategies(&file, &context, &mut state.condition_memo)
+                });
+                let blocked = state.blocked_placements.len();
+                self.plan_file_placement(&file, current_tier, &candidates, &mut state, &mut quotas);
+                if let Some(decision) = state.decisions.last() {
+                    strategy_stats.record(decision, state.blocked_placements.len() > blocked);
+                }
+                progress.files_planned += 1;
+                if progress
+                    .files_planned
+                    .is_multiple_of(progress::REPORT_EVERY)
+                {
+                    self.report(progress);
+                }
+            }
+        }
+        self.report(progress);
+        log_condition_counters(&state.condition_memo);
 
-        for (file, current_tier) in files {
-            context.current_tier_path = Some(current_tier.path.clone());
-            self.plan_file_placement(&file, current_tier, &context, &mut state);
+        for warning in std::mem::take(&mut state.ties).into_warnings() {
+            tracing::warn!("{warning}");
+            state.warnings.push(warning);
+        }
+        for (tier_name, (files, bytes)) in std::mem::take(&mut state.held_demotions) {
+            let Some(tier) = self.tiers.iter().find(|t| t.name == tier_name) else {
+                continue;
+            };
+            let warning = PlanWarning::BelowMinUsage {
+                tier: tier_name,
+                usage_percent: tier.usage_percent(),
+                min_usage_percent: tier.min_usage_percent.unwrap_or(0),
+                files,
+                bytes,
+            };
+            tracing::info!("{warning}");
+            state.warnings.push(warning);
         }
 
         let blocked_count = state.blocked_placements.len();
+        let mut evicted_count = 0;
+        progress.phase = PlanningPhase::Eviction;
+        self.report(progress);
         if blocked_count > 0 {
             tracing::info!(
                 "Pass 3a: Evicting low-priority files to make space ({} blocked placements)",
                 blocked_count
             );
-            let eviction_planner = eviction::EvictionPlanner::new(&self.tiers);
+            let eviction_planner = eviction::EvictionPlanner::new(&self.tiers)
+                .with_move_policies(&self.move_policies)
+                .with_cooldown(self.cooldown.as_ref());
             let blocked = std::mem::take(&mut state.blocked_placements);
-            eviction_planner.evict_to_make_space(
+            evicted_count += eviction_planner.evict_to_make_space(
                 &mut state.decisions,
                 blocked,
                 &mut state.tier_free_space,
+                &mut quotas,
             );
         }
 
         // PASS 3b: Aggressive eviction for tiers exceeding max_usage_percent
         tracing::info!("Pass 3b: Checking for tiers exceeding max_usage_percent...");
-        let eviction_planner = eviction::EvictionPlanner::new(&self.tiers);
-        eviction_planner.evict_excess_usage(&mut state.decisions, &mut state.tier_free_space);
+        for warning in self.pinned_over_capacity(&state.decisions) {
+            tracing::warn!("{warning}");
+            state.warnings.push(warning);
+        }
+        let eviction_planner = eviction::EvictionPlanner::new(&self.tiers)
+            .with_move_policies(&self.move_policies)
+            .with_cooldown(self.cooldown.as_ref());
+        evicted_count += eviction_planner.evict_excess_usage(
+            &mut state.decisions,
+            &mut state.tier_free_space,
+            &mut quotas,
+        );
+
+        progress.evictions = evicted_count;
+        progress.phase = PlanningPhase::Promotion;
+        self.report(progress);
+
+        // PASS 3c: Fill tiers below min_usage_percent
+        tracing::info!("Pass 3c: Checking for tiers below min_usage_percent...");
+        let promotion_planner = promotion::PromotionPlanner::new(&self.tiers)
+            .with_move_policies(&self.move_policies)
+            .with_cooldown(self.cooldown.as_ref())
+            .with_pinned_strategies(
+                self.strategies
+                    .iter()
+                    .filter(|s| s.action == crate::StrategyAction::Stay)
+                    .map(|s| s.name.as_str()),
+            );
+        let promoted_count = promotion_planner.fill_underused_tiers(
+            &mut state.decisions,
+            &mut state.tier_free_space,
+            &mut quotas,
+        );
+        if promoted_count > 0 {
+            tracing::info!("Pass 3c: Promoted {promoted_count} files to fill underused tiers");
+        }
+
+        if let Some(affinity) = &self.sidecar_affinity {
+            let attached = affinity.attach_sidecars(
+                &self.tiers,
+                &mut state.decisions,
+                &mut state.tier_free_space,
+                &mut quotas,
+            );
+            if attached > 0 {
+                tracing::info!("Moving {attached} sidecar file(s) with their media files");
+            }
+        }
+        for warning in quotas.into_warnings() {
+            tracing::info!("{warning}");
+            state.warnings.push(warning);
+        }
+
+        let mut move_batches = batching::defer_small_batches(
+            &self.tiers,
+            &mut state.decisions,
+            &mut state.tier_free_space,
+            &mut state.warnings,
+        );
 
         state.decisions.sort_by(|d1, d2| {
             d2.sort_priority()
                 .cmp(&d1.sort_priority())
                 .then_with(|| d1.file_path().cmp(d2.file_path()))
         });
+        move_batches.extend(batching::group_spindown_moves(
+            &self.tiers,
+            &mut state.decisions,
+        ));
+
+        let draining = DrainProgress::collect(&self.tiers, &state.decisions);
+        for drain in &draining {
+            tracing::info!(
+                "Draining tier '{}': {} of {} file(s) move off in this run",
+                drain.tier,
+                drain.files_leaving,
+                drain.files
+            );
+        }
 
         let projected_usage = self.calculate_projected_usage(&state);
+        let directory_usage = self.calculate_directory_usage(&state.decisions);
+        let largest_remaining = TierOffenders::collect(
+            state
+                .decisions
+                .iter()
+                .map(|d| (d, self.parent_directory(d))),
+            LARGEST_REMAINING,
+        );
+        progress.phase = PlanningPhase::Done;
+        self.report(progress);
 
         BalancingPlan {
             decisions: state.decisions,
             projected_tier_usage: projected_usage,
+            projected_directory_usage: directory_usage,
             warnings: state.warnings,
+            blocked_placements: blocked_count,
+            evictions: evicted_count,
+            move_batches,
+            largest_remaining,
+            seed: self.seed,
+            filtered_files: Vec::new(),
+            draining,
+            strategy_stats,
         }
     }
 
-    fn scan_all_tiers(&self) -> HashMap<Arc<FileInfo>, &Tier> {
-        let mut file_map = HashMap::new();
+    /// Files of every tier that pass its scan filter, and what each filter skipped
+    ///
+    /// A file reached through overlapping tier roots is kept once, on the first
+    /// tier that listed it.
+    fn scan_all_tiers(
+        &self,
+        progress: &mut PlanningProgress,
+    ) -> (Vec<ScannedFile<'_>>, Vec<FilteredFiles>) {
+        let mut files = Vec::new();
+        let mut seen = HashSet::new();
+        let mut filtered_files = Vec::new();
+        self.report(progress);
         for tier in &self.tiers {
-            for file in tier.get_all_files() {
-                file_map.insert(Arc::new(file), tier);
+            let mut scanned = self.scanner.scan(tier);
+            // Sidecars must be in the plan to follow their media file
+            let filtered = tier
+                .scan_filter
+                .apply_keeping_small(tier, &mut scanned, |path| {
+                    self.sidecar_affinity
+                        .as_ref()
+                        .is_some_and(|affinity| affinity.is_sidecar(path))
+                });
+            if filtered.total() > 0 {
+                tracing::debug!(
+                    "Scan filter skipped {} file(s) on tier '{}'",
+                    filtered.total(),
+                    tier.name
+                );
+                filtered_files.push(filtered);
             }
+            let mut duplicates = 0;
+            for file in scanned {
+                let file = Arc::new(file);
+                if seen.insert(Arc::clone(&file)) {
+                    files.push((file, tier));
+                } else {
+                    duplicates += 1;
+                }
+            }
+            if duplicates > 0 {
+                tracing::warn!(
+                    "Tier '{}' lists {duplicates} file(s) already scanned on another tier; check for overlapping paths",
+                    tier.name
+                );
+            }
+            progress.files_scanned = files.len();
+            self.report(progress);
         }
-        file_map
+        (files, filtered_files)
     }
 
     /// Sorts files deterministically:
@@ -130,8 +681,8 @@ impl Balancer {
     /// 4. By tier name (for complete stability)
     fn sort_files_deterministically<'a>(
         &self,
-        mut files: Vec<(Arc<FileInfo>, &'a Tier)>,
-    ) -> Vec<(Arc<FileInfo>, &'a Tier)> {
+        mut files: Vec<ScannedFile<'a>>,
+    ) -> Vec<ScannedFile<'a>> {
         files.sort_by(|(f1, t1), (f2, t2)| {
             f2.size
                 .cmp(&f1.size)
@@ -148,19 +699,99 @@ impl Balancer {
         file: &FileInfo,
         context: &Context,
     ) -> Option<&PlacementStrategy> {
-        self.strategies
+        self.top_matching_strategies(file, context, &mut ConditionMemo::new())
+            .into_iter()
+            .next()
+    }
+
+    /// Threads for Pass 2 matching; 1 when a condition reads the simulated
+    /// tier state, since its result depends on the files placed before
+    fn matching_threads(&self) -> usize {
+        if self
+            .strategies
             .iter()
-            .filter(|s| s.matches(file, context))
-            .max_by(|s1, s2| {
-                s1.priority
-                    .cmp(&s2.priority)
-                    .then_with(|| s1.name.cmp(&s2.name))
-            })
+            .any(PlacementStrategy::reads_planning)
+        {
+            return 1;
+        }
+        self.planning_threads
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, NonZeroUsize::get))
+    }
+
+    /// `top_matching_strategies` for every file, split over `threads`, in file order
+    ///
+    /// Only valid when no condition reads the simulated tier state.
+    fn match_in_parallel(
+        &self,
+        files: &[ScannedFile<'_>],
+        context: &Context,
+        threads: usize,
+        memo: &mut ConditionMemo,
+    ) -> Vec<Vec<&PlacementStrategy>> {
+        let chunk = files.len().div_ceil(threads);
+        thread::scope(|scope| {
+            let workers: Vec<_> = files
+                .chunks(chunk)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        let mut context = context.clone();
+                        let mut memo = ConditionMemo::new();
+                        let matched: Vec<_> = chunk
+                            .iter()
+                            .map(|(file, tier)| {
+                                context.current_tier_path =
+                                    tier.root_of(&file.path).map(Path::to_path_buf);
+                                memo.start_file();
+                                self.top_matching_strategies(file, &context, &mut memo)
+                            })
+                            .collect();
+                        (matched, memo)
+                    })
+                })
+                .collect();
+            let mut all = Vec::with_capacity(files.len());
+            for worker in workers {
+                match worker.join() {
+                    Ok((matched, worker_memo)) => {
+                        all.extend(matched);
+                        memo.merge(worker_memo);
+                    }
+                    Err(panic) => std::panic::resume_unwind(panic),
+                }
+            }
+            all
+        })
+    }
+
+    /// Matching strategies that share the highest priority, the winner first
+    ///
+    /// Ties are broken by name, the greatest name wins.
+    fn top_matching_strategies(
+        &self,
+        file: &FileInfo,
+        context: &Context,
+        memo: &mut ConditionMemo,
+    ) -> Vec<&PlacementStrategy> {
+        let now = self.planned_at();
+        let mut top: Vec<&PlacementStrategy> = Vec::new();
+        for strategy in self
+            .strategies
+            .iter()
+            .filter(|s| s.is_active_at(now) && s.matches_with(file, context, memo))
+        {
+            match top.first().map(|t| strategy.priority.cmp(&t.priority)) {
+                Some(Ordering::Less) => {}
+                Some(Ordering::Equal) => top.push(strategy),
+                Some(Ordering::Greater) | None => top = vec![strategy],
+            }
+        }
+        top.sort_by(|s1, s2| s2.name.cmp(&s1.name));
+        top
     }
 
     /// Checks if tier can accept file considering simulated free space and `max_usage_percent`
     fn can_accept_file(&self, tier: &Tier, file_size: u64, simulated_free: u64) -> bool {
-        if simulated_free < file_size {
+        if simulated_free < file_size || !tier.fits_file_size(file_size) {
             return false;
         }
 
@@ -178,22 +809,68 @@ impl Balancer {
         true
     }
 
-    /// Finds ideal tier considering simulated free space and `max_usage_percent`
-    fn find_ideal_tier_simulated(
-        &self,
-        strategy: &PlacementStrategy,
+    /// Finds ideal tier considering simulated free space, `max_usage_percent`,
+    /// `max_file_size` and move policies
+    fn find_ideal_tier_simulated<'a>(
+        &'a self,
+        strategy: &'a PlacementStrategy,
+        file: &FileInfo,
+        current_tier: &Tier,
+        simulated_free_space: &HashMap<String, u64>,
+    ) -> Option<&'a Tier> {
+        self.preferred_tiers(strategy)
+            .filter(|tier| self.may_move_to(current_tier, tier))
+            .find(|tier| self.has_simulated_space(tier, file, simulated_free_space))
+    }
+
+    /// Whether move policies and write protection let files from `current_tier` onto `tier`
+    ///
+    /// Staying on a read-only tier is always allowed.
+    fn may_move_to(&self, current_tier: &Tier, tier: &Tier) -> bool {
+        tier.name == current_tier.name
+            || (tier.accepts_files()
+                && self
+                    .move_policies
+                    .is_allowed(&current_tier.name, &tier.name))
+    }
+
+    /// Returns the preferred tier that would have been chosen if move policies
+    /// didn't forbid moving there from `current_tier`
+    fn find_denied_preference<'a>(
+        &'a self,
+        strategy: &'a PlacementStrategy,
         file: &FileInfo,
+        current_tier: &Tier,
         simulated_free_space: &HashMap<String, u64>,
-    ) -> Option<&Tier> {
+    ) -> Option<&'a Tier> {
+        self.preferred_tiers(strategy)
+            .find(|tier| self.has_simulated_space(tier, file, simulated_free_space))
+            .filter(|tier| {
+                !self
+                    .move_policies
+                    .is_allowed(&current_tier.name, &tier.name)
+            })
+    }
+
+    fn preferred_tiers<'a>(
+        &'a self,
+        strategy: &'a PlacementStrategy,
+    ) -> impl Iterator<Item = &'a Tier> + 'a {
         strategy
             .preferred_tiers()
             .iter()
             .filter_map(|tier_name| self.tiers.iter().find(|t| &t.name == tier_name))
-            .find(|tier| {
-                simulated_free_space
-                    .get(&tier.name)
-                    .is_some_and(|&free| self.can_accept_file(tier, file.size, free))
-            })
+    }
+
+    fn has_simulated_space(
+        &self,
+        tier: &Tier,
+        file: &FileInfo,
+        simulated_free_space: &HashMap<String, u64>,
+    ) -> bool {
+        simulated_free_space
+            .get(&tier.name)
+            .is_some_and(|&free| self.can_accept_file(tier, file.size, free))
     }
 
     fn make_decision(
@@ -240,10 +917,23 @@ impl Balancer {
         &self,
         file: &Arc<FileInfo>,
         current_tier: &Tier,
-        context: &Context,
+        candidates: &[&PlacementStrategy],
         state: &mut PlanningState,
+        quotas: &mut QuotaTracker,
     ) {
-        if let Some(strategy) = self.find_matching_strategy(file, context) {
+        if current_tier.drain {
+            self.plan_drain(
+                file,
+                current_tier,
+                candidates.first().copied(),
+                state,
+                quotas,
+            );
+            return;
+        }
+        if let Some((&strategy, rivals)) = candidates.split_first() {
+            state.ties.record(strategy, rivals, &file.path);
+
             if strategy.action == crate::StrategyAction::Stay {
                 state.decisions.push(PlacementDecision::Stay {
                     file: Arc::clone(file),
@@ -254,20 +944,70 @@ impl Balancer {
                 return;
             }
 
+            if strategy.action == crate::StrategyAction::Copy {
+                self.plan_copy(file, current_tier, strategy, state, quotas);
+                return;
+            }
+
+            if let Some(denied_tier) =
+                self.find_denied_preference(strategy, file, current_tier, &state.tier_free_space)
+            {
+                state.warnings.push(PlanWarning::MoveDenied {
+                    file: file.path.clone(),
+                    strategy: strategy.name.clone(),
+                    from_tier: current_tier.name.clone(),
+                    to_tier: denied_tier.name.clone(),
+                });
+            }
+
             if let Some(ideal_tier) =
-                self.find_ideal_tier_simulated(strategy, file, &state.tier_free_space)
+                self.find_ideal_tier_simulated(strategy, file, current_tier, &state.tier_free_space)
             {
-                let decision =
+                let mut decision =
                     self.make_decision(Arc::clone(file), current_tier, ideal_tier, strategy);
+                if ideal_tier.name != current_tier.name
+                    && matches!(decision, PlacementDecision::Stay { .. })
+                {
+                    let held = state
+                        .held_demotions
+                        .entry(current_tier.name.clone())
+                        .or_default();
+                    held.0 += 1;
+                    held.1 += file.size;
+                }
 
                 if !matches!(decision, PlacementDecision::Stay { .. }) {
-                    state.apply_move(file.size, &current_tier.name, &ideal_tier.name);
+                    let cooling_down = self.cooldown_warning(file, current_tier, strategy);
+                    let is_held = cooling_down.is_some();
+                    if let Some(warning) = cooling_down {
+                        state.warnings.push(warning);
+                    }
+
+                    if !is_held && quotas.try_reserve(strategy, file.size) {
+                        state.apply_move(file.size, &current_tier.name, &ideal_tier.name);
+                    } else {
+                        decision = PlacementDecision::Stay {
+                            file: Arc::clone(file),
+                            current_tier: current_tier.name.clone(),
+                            strategy: strategy.name.clone(),
+                            priority: strategy.priority,
+                        };
+                    }
                 }
 
                 state.decisions.push(decision);
             } else {
-                if let Some(first_preferred) = strategy.preferred_tiers().first()
+                if let Some(first_preferred) = self
+                    .preferred_tiers(strategy)
+                    .find(|tier| {
+                        self.may_move_to(current_tier, tier) && tier.fits_file_size(file.size)
+                    })
+                    .map(|tier| &tier.name)
                     && first_preferred != &current_tier.name
+                    && !self
+                        .cooldown
+                        .as_ref()
+                        .is_some_and(|cooldown| cooldown.is_cooling_down(current_tier, &file.path))
                 {
                     state.blocked_placements.push(BlockedPlacement {
                         file: Arc::clone(file),
@@ -275,6 +1015,7 @@ impl Balancer {
                         desired_tier: first_preferred.clone(),
                         strategy_name: strategy.name.clone(),
                         strategy_priority: strategy.priority,
+                        strategy_quota: strategy.quota,
                     });
                 }
 
@@ -303,10 +1044,146 @@ impl Balancer {
         }
     }
 
+    /// Copies `file` to the first allowed preferred tier with room; the original stays
+    ///
+    /// Files already on a preferred tier, or with a copy on one, are left alone.
+    fn plan_copy(
+        &self,
+        file: &Arc<FileInfo>,
+        current_tier: &Tier,
+        strategy: &PlacementStrategy,
+        state: &mut PlanningState,
+        quotas: &mut QuotaTracker,
+    ) {
+        let copied = current_tier
+            .relative_path(&file.path)
+            .is_none_or(|relative| {
+                self.preferred_tiers(strategy).any(|tier| {
+                    tier.name == current_tier.name
+                        || state
+                            .present
+                            .contains(&(tier.name.clone(), relative.to_path_buf()))
+                })
+            });
+        let target = self
+            .preferred_tiers(strategy)
+            .filter(|tier| self.may_move_to(current_tier, tier))
+            .find(|tier| self.has_simulated_space(tier, file, &state.tier_free_space));
+
+        match target {
+            Some(target) if !copied && quotas.try_reserve(strategy, file.size) => {
+                state.apply_copy(file.size, &target.name);
+                state.decisions.push(PlacementDecision::Copy {
+                    file: Arc::clone(file),
+                    from_tier: current_tier.name.clone(),
+                    to_tier: target.name.clone(),
+                    strategy: strategy.name.clone(),
+                    priority: strategy.priority,
+                });
+            }
+            _ => state.decisions.push(PlacementDecision::Stay {
+                file: Arc::clone(file),
+                current_tier: current_tier.name.clone(),
+                strategy: strategy.name.clone(),
+                priority: strategy.priority,
+            }),
+        }
+    }
+
+    /// Moves `file` off a draining tier, whatever its strategy's action
+    ///
+    /// The strategy's preferred tiers come first, then the other tiers nearest
+    /// in priority, slower ones before faster ones. Files without a matching
+    /// strategy are planned as `drain` and only count against the run quota.
+    fn plan_drain(
+        &self,
+        file: &Arc<FileInfo>,
+        current_tier: &Tier,
+        strategy: Option<&PlacementStrategy>,
+        state: &mut PlanningState,
+        quotas: &mut QuotaTracker,
+    ) {
+        let mut nearest: Vec<&Tier> = self.tiers.iter().collect();
+        nearest.sort_by_key(|tier| {
+            (
+                tier.priority < current_tier.priority,
+                tier.priority.abs_diff(current_tier.priority),
+            )
+        });
+        let target = strategy
+            .into_iter()
+            .flat_map(|strategy| self.preferred_tiers(strategy))
+            .chain(nearest)
+            .filter(|tier| tier.name != current_tier.name && self.may_move_to(current_tier, tier))
+            .find(|tier| self.has_simulated_space(tier, file, &state.tier_free_space));
+        let (name, priority) =
+            strategy.map_or((DRAIN_STRATEGY, 0), |s| (s.name.as_str(), s.priority));
+
+        let reserved = target.is_some()
+            && match strategy {
+                Some(strategy) => quotas.try_reserve(strategy, file.size),
+                None => quotas.try_reserve_global(file.size),
+            };
+        let decision = match target {
+            Some(target) if reserved => {
+                state.apply_move(file.size, &current_tier.name, &target.name);
+                if target.priority < current_tier.priority {
+                    PlacementDecision::Promote {
+                        file: Arc::clone(file),
+                        from_tier: current_tier.name.clone(),
+                        to_tier: target.name.clone(),
+                        strategy: name.to_string(),
+                        priority,
+                    }
+                } else {
+                    PlacementDecision::Demote {
+                        file: Arc::clone(file),
+                        from_tier: current_tier.name.clone(),
+                        to_tier: target.name.clone(),
+                        strategy: name.to_string(),
+                        priority,
+                    }
+                }
+            }
+            _ => PlacementDecision::Stay {
+                file: Arc::clone(file),
+                current_tier: current_tier.name.clone(),
+                strategy: name.to_string(),
+                priority,
+            },
+        };
+        state.decisions.push(decision);
+    }
+
+    /// Warning for a file that moved within the cooldown window, if any
+    fn cooldown_warning(
+        &self,
+        file: &FileInfo,
+        current_tier: &Tier,
+        strategy: &PlacementStrategy,
+    ) -> Option<PlanWarning> {
+        let cooldown = self.cooldown.as_ref()?;
+        let relative = current_tier.relative_path(&file.path)?;
+        let since = cooldown.time_since_move(relative)?;
+
+        tracing::debug!(
+            "Keeping {} in place: moved {}h ago (cooldown {}h)",
+            file.path.display(),
+            since.as_secs() / 3600,
+            cooldown.window().as_secs() / 3600
+        );
+        Some(PlanWarning::CooldownActive {
+            file: file.path.clone(),
+            strategy: strategy.name.clone(),
+            moved_hours_ago: since.as_secs() / 3600,
+            min_hours_between_moves: cooldown.window().as_secs() / 3600,
+        })
+    }
+
     fn calculate_projected_usage(
         &self,
         state: &PlanningState,
-    ) -> HashMap<String, TierUsageProjection> {
+    ) -> BTreeMap<String, TierUsageProjection> {
         self.tiers
             .iter()
             .map(|tier| {
@@ -343,6 +1220,119 @@ impl Balancer {
             .collect()
     }
 
+    /// Aggregates decisions per top-level directory (relative to the tier root)
+    fn calculate_directory_usage(
+        &self,
+        decisions: &[PlacementDecision],
+    ) -> BTreeMap<String, DirectoryComposition> {
+        let mut directories: BTreeMap<String, DirectoryComposition> = BTreeMap::new();
+
+        for decision in decisions {
+            let current_tier = decision.current_tier();
+            let directory = self
+                .tiers
+                .iter()
+                .find(|t| t.name == current_tier)
+                .map_or_else(
+                    || ".".to_string(),
+                    |tier| {
+                        top_level_directory(
+                            tier.root_of(decision.file_path()).unwrap_or(&tier.path),
+                            decision.file_path(),
+                        )
+                    },
+                );
+
+            let composition = directories
+                .entry(directory.clone())
+                .or_insert_with(|| DirectoryComposition::new(directory));
+            if decision.is_copy() {
+                composition.add_copy(decision.file_size(), current_tier, decision.target_tier());
+            } else {
+                composition.add_file(decision.file_size(), current_tier, decision.target_tier());
+            }
+        }
+
+        directories
+    }
+
+    /// Tiers whose `action: stay` files alone exceed `max_usage_percent`, which
+    /// eviction can never bring back under the limit
+    fn pinned_over_capacity(&self, decisions: &[PlacementDecision]) -> Vec<PlanWarning> {
+        let pinned: HashSet<&str> = self
+            .strategies
+            .iter()
+            .filter(|s| s.action == crate::StrategyAction::Stay)
+            .map(|s| s.name.as_str())
+            .collect();
+        if pinned.is_empty() {
+            return Vec::new();
+        }
+
+        let mut by_tier: HashMap<&str, HashMap<&str, u64>> = HashMap::new();
+        for decision in decisions {
+            if let PlacementDecision::Stay {
+                current_tier,
+                strategy,
+                file,
+                ..
+            } = decision
+                && pinned.contains(strategy.as_str())
+            {
+                *by_tier
+                    .entry(current_tier)
+                    .or_default()
+                    .entry(strategy)
+                    .or_default() += file.size;
+            }
+        }
+
+        self.tiers
+            .iter()
+            .filter_map(|tier| {
+                let max_percent = tier.max_usage_percent?;
+                let strategies = by_tier.remove(tier.name.as_str())?;
+                let pinned_bytes: u64 = strategies.values().sum();
+                let allowed_bytes =
+                    (tier.get_total_space() as f64 * max_percent as f64 / 100.0) as u64;
+                if pinned_bytes <= allowed_bytes {
+                    return None;
+                }
+                let mut strategies: Vec<StrategyBytes> = strategies
+                    .into_iter()
+                    .map(|(strategy, bytes)| StrategyBytes {
+                        strategy: strategy.to_string(),
+                        bytes,
+                    })
+                    .collect();
+                strategies.sort_by(|a, b| {
+                    b.bytes
+                        .cmp(&a.bytes)
+                        .then_with(|| a.strategy.cmp(&b.strategy))
+                });
+                strategies.truncate(TOP_PINNED_STRATEGIES);
+                Some(PlanWarning::PinnedOverCapacity {
+                    tier: tier.name.clone(),
+                    pinned_bytes,
+                    allowed_bytes,
+                    strategies,
+                })
+            })
+            .collect()
+    }
+
+    /// Directory of the decision's file relative to its current tier root, `.` at the root
+    fn parent_directory(&self, decision: &PlacementDecision) -> String {
+        let file = decision.file_path();
+        self.tiers
+            .iter()
+            .find(|t| t.name == decision.current_tier())
+            .and_then(|tier| tier.relative_path(file))
+            .and_then(Path::parent)
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .map_or_else(|| ".".to_string(), |parent| parent.display().to_string())
+    }
+
     /// Collect global statistics from all files (Pass 1)
     fn collect_global_stats<'a, I>(&self, files: I) -> GlobalStats
     where
@@ -352,23 +1342,60 @@ impl Balancer {
         GlobalStats::new(file_stats)
     }
 
-    /// Load Tautulli viewing statistics (Pass 1)
+    /// Load Sonarr series and upcoming episodes (Pass 1)
+    fn load_sonarr_stats(&self, config: &SonarrConfig) -> crate::Result<crate::SonarrStats> {
+        use crate::timestamp::format_utc;
+        use std::time::UNIX_EPOCH;
+
+        let client = crate::SonarrClient::new(config.url.clone(), config.api_key.clone())?;
+        let series = client.get_series()?;
+
+        let now = self
+            .planned_at()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let calendar = if self.sonarr_lookahead_days > 0 {
+            let end = now + u64::from(self.sonarr_lookahead_days) * 24 * 3600;
+            client.get_calendar(&format_utc(now), &format_utc(end))?
+        } else {
+            Vec::new()
+        };
+
+        Ok(crate::SonarrStats::build(&series, &calendar, now))
+    }
+
+    /// Load the torrents and their files from the torrent client (Pass 1)
+    fn load_torrent_stats(
+        &self,
+        config: &TorrentClientConfig,
+    ) -> crate::Result<crate::TorrentStats> {
+        let torrents = crate::factory::build_torrent_client(config)?.torrents()?;
+        Ok(crate::TorrentStats::build(&torrents, &config.path_mappings))
+    }
+
+    /// Load viewing statistics from the watch history provider (Pass 1)
     fn load_tautulli_stats<'a, I>(
         &self,
         files: I,
-        config: &TautulliConfig,
+        config: &WatchHistoryConfig,
     ) -> crate::Result<crate::TautulliStats>
     where
         I: IntoIterator<Item = &'a FileInfo>,
     {
-        use crate::{TautulliClient, TautulliStats, build_progress};
+        use crate::{TautulliStats, build_in_progress, build_progress};
 
-        // Create Tautulli client
-        let client = TautulliClient::new(config.url.clone(), config.api_key.clone())?;
+        let provider = match &self.watch_history_provider {
+            Some(provider) => Arc::clone(provider),
+            None => Arc::from(crate::factory::build_watch_history_provider(config)?),
+        };
 
         // Fetch viewing history
-        let history = client.get_history(config.history_length)?;
-        tracing::debug!("Fetched {} history items from Tautulli", history.len());
+        let history = provider.get_history(config.history_length)?;
+        tracing::debug!(
+            "Fetched {} history items from {}",
+            history.len(),
+            provider.name()
+        );
 
         // Build user watch progress
         let user_progress = build_progress(&history, config.days_back, config.watched_threshold);
@@ -382,18 +1409,75 @@ impl Balancer {
                 .len()
         );
 
-        // Build TautulliStats with viewing windows
+        let in_progress = build_in_progress(&history, config.days_back, config.watched_threshold);
+        tracing::debug!("Tracked {} in-progress episodes", in_progress.len());
+
+        // Progress over all fetched history, however old
+        let next_unwatched_progress = if config.keep_next_unwatched {
+            build_progress(&history, u32::MAX, config.watched_threshold)
+        } else {
+            Vec::new()
+        };
+
+        // Resolve last watched episodes to real file paths
+        let resolved = if config.episode_matching == crate::EpisodeMatching::Metadata {
+            let resolved = crate::watch_history::resolve_episode_paths(
+                provider.as_ref(),
+                &history,
+                &user_progress,
+                &config.path_mappings,
+            );
+            tracing::debug!("Resolved {} episode paths from metadata", resolved.len());
+            resolved
+        } else {
+            HashMap::new()
+        };
+
+        // Build TautulliStats with viewing windows and movie watch state
         let tautulli_stats = TautulliStats::build(
             files,
             user_progress,
             config.backward_episodes,
             config.forward_episodes,
+        )
+        .with_in_progress(in_progress)
+        .with_next_unwatched(next_unwatched_progress, config.next_unwatched_episodes)
+        .with_resolved_paths(resolved)
+        .with_movie_history(&history, config.watched_threshold);
+        tracing::debug!(
+            "Tracked {} watched or in-progress movies",
+            tautulli_stats.movie_watches.len()
         );
 
         Ok(tautulli_stats)
     }
 }
 
+/// Logs how often each condition type ran in Pass 2 and how long it took
+fn log_condition_counters(memo: &ConditionMemo) {
+    for (name, counter) in memo.counters() {
+        tracing::debug!(
+            "Condition {name}: {} evaluated, {} reused, {:.1} ms",
+            counter.evaluations,
+            counter.reused,
+            counter.time.as_secs_f64() * 1000.0
+        );
+    }
+}
+
+/// First path component of `file` below `tier_root`, or "." for files at the root
+fn top_level_directory(tier_root: &Path, file: &Path) -> String {
+    let relative = file.strip_prefix(tier_root).unwrap_or(file);
+    let mut components = relative
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)));
+
+    match (components.next(), components.next()) {
+        (Some(first), Some(_)) => first.as_os_str().to_string_lossy().into_owned(),
+        _ => ".".to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -406,6 +1490,19 @@ mod tests {
         Tier::new_mock_with_usage(name, priority, max_usage, TB, 0)
     }
 
+    /// Matches and places one file the way Pass 2 does
+    fn place_file(
+        balancer: &Balancer,
+        file: &Arc<FileInfo>,
+        tier: &Tier,
+        context: &Context,
+        state: &mut PlanningState,
+        quotas: &mut QuotaTracker,
+    ) {
+        let candidates = balancer.top_matching_strategies(file, context, &mut ConditionMemo::new());
+        balancer.plan_file_placement(file, tier, &candidates, state, quotas);
+    }
+
     #[test]
     fn test_can_accept_file_sufficient_space() {
         let tier = create_test_tier("cache", 1, None);
@@ -531,4 +1628,493 @@ mod tests {
             "Should accept file when no max_usage_percent is set"
         );
     }
+
+    #[test]
+    fn test_find_ideal_tier_respects_move_policies() {
+        use crate::config::MovePolicyAction;
+        use crate::move_policy::MovePolicy;
+        use std::path::PathBuf;
+        use std::time::SystemTime;
+
+        let landing = create_test_tier("landing", 1, None);
+        let storage = create_test_tier("storage", 5, None);
+        let archive = create_test_tier("archive", 10, None);
+        let strategy = PlacementStrategy::new("archive_old".to_string(), 10)
+            .add_preferred_tier("archive".to_string())
+            .add_preferred_tier("storage".to_string());
+        let policies = MovePolicies::new(vec![MovePolicy {
+            from: "landing".to_string(),
+            to: "archive".to_string(),
+            action: MovePolicyAction::Deny,
+        }]);
+        let balancer = Balancer::new(
+            vec![landing.clone(), storage.clone(), archive.clone()],
+            vec![],
+            None,
+        )
+        .with_move_policies(policies);
+        let state = PlanningState::new(&balancer.tiers);
+        let file = FileInfo {
+            path: PathBuf::from("/mock/movie.mkv"),
+            size: 1024,
+            modified: SystemTime::now(),
+            accessed: SystemTime::now(),
+            owner: None,
+        };
+
+        let ideal = balancer
+            .find_ideal_tier_simulated(&strategy, &file, &landing, &state.tier_free_space)
+            .unwrap();
+        assert_eq!(ideal.name, "storage");
+        let denied = balancer
+            .find_denied_preference(&strategy, &file, &landing, &state.tier_free_space)
+            .unwrap();
+        assert_eq!(denied.name, "archive");
+
+        let ideal = balancer
+            .find_ideal_tier_simulated(&strategy, &file, &storage, &state.tier_free_space)
+            .unwrap();
+        assert_eq!(ideal.name, "archive");
+        assert!(
+            balancer
+                .find_denied_preference(&strategy, &file, &storage, &state.tier_free_space)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_plan_file_placement_respects_strategy_quota() {
+        use crate::conditions::AlwaysTrueCondition;
+        use std::path::PathBuf;
+        use std::time::SystemTime;
+
+        let cache = create_test_tier("cache", 1, None);
+        let storage = create_test_tier("storage", 10, None);
+        let strategy = PlacementStrategy::new("archive".to_string(), 10)
+            .add_condition(Box::new(AlwaysTrueCondition))
+            .add_preferred_tier("storage".to_string())
+            .with_quota(MoveQuota::new(Some(1), None));
+        let balancer = Balancer::new(vec![cache.clone(), storage], vec![strategy], None);
+        let mut state = PlanningState::new(&balancer.tiers);
+        let mut quotas = QuotaTracker::new(balancer.run_quota);
+        let context = Context::new();
+
+        for name in ["a.mkv", "b.mkv"] {
+            let file = Arc::new(FileInfo {
+                path: PathBuf::from(format!("/mock/{name}")),
+                size: 1024,
+                modified: SystemTime::now(),
+                accessed: SystemTime::now(),
+                owner: None,
+            });
+            place_file(&balancer, &file, &cache, &context, &mut state, &mut quotas);
+        }
+
+        assert!(matches!(
+            state.decisions[0],
+            PlacementDecision::Demote { .. }
+        ));
+        assert!(matches!(state.decisions[1], PlacementDecision::Stay { .. }));
+        assert!(state.blocked_placements.is_empty());
+        assert_eq!(
+            state.get_simulated_free_space("cache"),
+            Some(cache.get_free_space() + 1024)
+        );
+        assert_eq!(quotas.into_warnings().len(), 1);
+    }
+
+    #[test]
+    fn test_tier_usage_condition_sees_simulated_usage() {
+        use crate::conditions::TierUsageCondition;
+        use std::path::PathBuf;
+        use std::time::SystemTime;
+
+        // 800 of 1000 bytes used; demote only while cache is at 75% or more
+        let cache = Tier::new_mock_with_usage("cache", 1, None, 1000, 80);
+        let storage = create_test_tier("storage", 10, None);
+        let pressure = PlacementStrategy::new("pressure".to_string(), 10)
+            .add_condition(Box::new(TierUsageCondition::new(
+                "cache".to_string(),
+                Some(75),
+                None,
+            )))
+            .add_preferred_tier("storage".to_string());
+        let balancer = Balancer::new(vec![cache.clone(), storage], vec![pressure], None);
+        let files: Vec<_> = ["a.mkv", "b.mkv", "c.mkv"]
+            .into_iter()
+            .map(|name| {
+                let file = Arc::new(FileInfo {
+                    path: PathBuf::from(format!("/mock/{name}")),
+                    size: 30,
+                    modified: SystemTime::UNIX_EPOCH,
+                    accessed: SystemTime::now(),
+                    owner: None,
+                });
+                (file, &cache)
+            })
+            .collect();
+        let global_stats = balancer.collect_all_stats(&files);
+
+        let plan = balancer.plan_with_stats(files, &global_stats, &mut PlanningProgress::new());
+
+        // 80% -> 77% -> 74%: the third file no longer sees pressure
+        let demoted: Vec<_> = plan
+            .decisions
+            .iter()
+            .filter(|d| matches!(d, PlacementDecision::Demote { .. }))
+            .map(PlacementDecision::file_path)
+            .collect();
+        assert_eq!(
+            demoted,
+            vec![Path::new("/mock/a.mkv"), Path::new("/mock/b.mkv")]
+        );
+    }
+
+    #[test]
+    fn test_pinned_over_capacity_warning() {
+        use std::path::PathBuf;
+        use std::time::SystemTime;
+
+        let cache = create_test_tier("cache", 1, Some(50));
+        let storage = create_test_tier("storage", 10, Some(50));
+        let mut keep = PlacementStrategy::new("keep".to_string(), 90);
+        keep.action = crate::StrategyAction::Stay;
+        let balancer = Balancer::new(
+            vec![cache, storage],
+            vec![keep, PlacementStrategy::new("recent".to_string(), 50)],
+            None,
+        );
+        let stay = |name: &str, tier: &str, strategy: &str, size| PlacementDecision::Stay {
+            file: Arc::new(FileInfo {
+                path: PathBuf::from(format!("/mock/{name}")),
+                size,
+                modified: SystemTime::now(),
+                accessed: SystemTime::now(),
+                owner: None,
+            }),
+            current_tier: tier.to_string(),
+            strategy: strategy.to_string(),
+            priority: 90,
+        };
+        let decisions = vec![
+            stay("a.mkv", "cache", "keep", TB / 2),
+            stay("b.mkv", "cache", "keep", TB / 10),
+            // Unpinned files can still be evicted
+            stay("c.mkv", "cache", "recent", TB / 10),
+            stay("d.mkv", "storage", "keep", TB / 10),
+        ];
+
+        let warnings = balancer.pinned_over_capacity(&decisions);
+
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            PlanWarning::PinnedOverCapacity {
+                tier,
+                pinned_bytes,
+                allowed_bytes,
+                strategies,
+            } => {
+                assert_eq!(tier, "cache");
+                assert_eq!(*pinned_bytes, TB / 2 + TB / 10);
+                assert_eq!(*allowed_bytes, TB / 2);
+                assert_eq!(strategies.len(), 1);
+                assert_eq!(strategies[0].strategy, "keep");
+            }
+            other => panic!("Expected PinnedOverCapacity, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_plan_file_placement_reports_strategy_ties() {
+        use crate::conditions::AlwaysTrueCondition;
+        use std::path::PathBuf;
+        use std::time::SystemTime;
+
+        let cache = create_test_tier("cache", 1, None);
+        let storage = create_test_tier("storage", 10, None);
+        let strategy = |name: &str, priority: u32, tier: &str| {
+            PlacementStrategy::new(name.to_string(), priority)
+                .add_condition(Box::new(AlwaysTrueCondition))
+                .add_preferred_tier(tier.to_string())
+        };
+        let balancer = Balancer::new(
+            vec![cache.clone(), storage],
+            vec![
+                strategy("archive", 10, "storage"),
+                strategy("keep", 10, "cache"),
+                strategy("fallback", 1, "storage"),
+            ],
+            None,
+        );
+        let mut state = PlanningState::new(&balancer.tiers);
+        let mut quotas = QuotaTracker::new(balancer.run_quota);
+        let file = Arc::new(FileInfo {
+            path: PathBuf::from("/mock/a.mkv"),
+            size: 1024,
+            modified: SystemTime::now(),
+            accessed: SystemTime::now(),
+            owner: None,
+        });
+
+        place_file(
+            &balancer,
+            &file,
+            &cache,
+            &Context::new(),
+            &mut state,
+            &mut quotas,
+        );
+
+        assert_eq!(state.decisions[0].strategy_name(), "keep");
+        assert_eq!(
+            state.ties.into_warnings(),
+            vec![PlanWarning::AmbiguousStrategy {
+                strategies: vec!["archive".to_string(), "keep".to_string()],
+                priority: 10,
+                winner: "keep".to_string(),
+                files: 1,
+                example: file.path.clone(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_plan_file_placement_skips_files_in_cooldown() {
+        use crate::StateJournal;
+        use crate::conditions::AlwaysTrueCondition;
+        use std::path::PathBuf;
+        use std::time::{Duration, SystemTime};
+
+        let now = SystemTime::now();
+        let mut journal = StateJournal::in_memory();
+        journal.record_move(PathBuf::from("recent.mkv"), now - Duration::from_secs(3600));
+
+        let cache = create_test_tier("cache", 1, None);
+        let storage = create_test_tier("storage", 10, None);
+        let strategy = PlacementStrategy::new("archive".to_string(), 10)
+            .add_condition(Box::new(AlwaysTrueCondition))
+            .add_preferred_tier("storage".to_string());
+        let balancer =
+            Balancer::new(vec![cache.clone(), storage], vec![strategy], None).with_move_cooldown(
+                MoveCooldown::from_journal(&journal, Duration::from_secs(24 * 3600), now),
+            );
+        let mut state = PlanningState::new(&balancer.tiers);
+        let mut quotas = QuotaTracker::new(balancer.run_quota);
+        let context = Context::new();
+
+        for name in ["recent.mkv", "other.mkv"] {
+            let file = Arc::new(FileInfo {
+                path: PathBuf::from(format!("/mock/{name}")),
+                size: 1024,
+                modified: now,
+                accessed: now,
+                owner: None,
+            });
+            place_file(&balancer, &file, &cache, &context, &mut state, &mut quotas);
+        }
+
+        assert!(matches!(state.decisions[0], PlacementDecision::Stay { .. }));
+        assert!(matches!(
+            state.decisions[1],
+            PlacementDecision::Demote { .. }
+        ));
+        assert!(matches!(
+            state.warnings.as_slice(),
+            [PlanWarning::CooldownActive {
+                moved_hours_ago: 1,
+                min_hours_between_moves: 24,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn test_plan_file_placement_skips_strategies_outside_schedule() {
+        use crate::conditions::AlwaysTrueCondition;
+        use std::path::PathBuf;
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let cache = create_test_tier("cache", 1, None);
+        let storage = create_test_tier("storage", 10, None);
+        let night: crate::config::ScheduleConfig =
+            serde_yaml::from_str("start: \"01:00\"\nend: \"06:00\"").unwrap();
+        let strategies = || {
+            vec![
+                PlacementStrategy::new("night_demotion".to_string(), 10)
+                    .add_condition(Box::new(AlwaysTrueCondition))
+                    .add_preferred_tier("storage".to_string())
+                    .with_schedule(night.clone()),
+                PlacementStrategy::new("default".to_string(), 1)
+                    .add_condition(Box::new(AlwaysTrueCondition))
+                    .add_preferred_tier("cache".to_string()),
+            ]
+        };
+        // 2024-01-15 12:00 UTC
+        let noon = UNIX_EPOCH + Duration::from_secs(1_705_320_000);
+        let file = Arc::new(FileInfo {
+            path: PathBuf::from("/mock/a.mkv"),
+            size: 1024,
+            modified: noon,
+            accessed: noon,
+            owner: None,
+        });
+
+        for (planned_at, expected) in [
+            (noon, "default"),
+            (noon - Duration::from_secs(8 * 3600), "night_demotion"),
+        ] {
+            let balancer = Balancer::new(vec![cache.clone(), storage.clone()], strategies(), None)
+                .with_planned_at(planned_at);
+            let mut state = PlanningState::new(&balancer.tiers);
+            let mut quotas = QuotaTracker::new(balancer.run_quota);
+
+            place_file(
+                &balancer,
+                &file,
+                &cache,
+                &Context::new(),
+                &mut state,
+                &mut quotas,
+            );
+
+            assert_eq!(state.decisions[0].strategy_name(), expected);
+        }
+    }
+
+    #[test]
+    fn test_explain_file() {
+        use crate::conditions::{AgeCondition, AlwaysTrueCondition};
+        use crate::inventory::InventoryAction;
+
+        let cache_dir = tempfile::tempdir().unwrap();
+        let storage_dir = tempfile::tempdir().unwrap();
+        let file = cache_dir.path().join("movie.mkv");
+        std::fs::write(&file, b"movie").unwrap();
+
+        let tier = |name: &str, dir: &tempfile::TempDir, priority| {
+            Tier::new(
+                name.to_string(),
+                dir.path().to_path_buf(),
+                priority,
+                None,
+                None,
+            )
+            .unwrap()
+        };
+        let strategies = vec![
+            PlacementStrategy::new("archive_old".to_string(), 10)
+                .add_condition(Box::new(AlwaysTrueCondition))
+                .add_condition(Box::new(AgeCondition::new(Some(24), None)))
+                .add_preferred_tier("cache".to_string()),
+            PlacementStrategy::new("default".to_string(), 1)
+                .add_condition(Box::new(AlwaysTrueCondition))
+                .add_preferred_tier("storage".to_string()),
+        ];
+        let balancer = Balancer::new(
+            vec![
+                tier("cache", &cache_dir, 1),
+                tier("storage", &storage_dir, 10),
+            ],
+            strategies,
+            None,
+        );
+
+        let explanation = balancer.explain_file(&file).unwrap();
+
+        assert_eq!(explanation.current_tier, "cache");
+        let archive = &explanation.strategies[0];
+        assert_eq!(archive.strategy, "archive_old");
+        assert!(!archive.matched);
+        assert_eq!(
+            archive
+                .conditions
+                .iter()
+                .map(|c| (c.condition.as_str(), c.passed))
+                .collect::<Vec<_>>(),
+            vec![("always_true", true), ("age", false)]
+        );
+        assert_eq!(explanation.winning_strategy.as_deref(), Some("default"));
+        assert_eq!(explanation.tier_checks.len(), 1);
+        assert!(explanation.tier_checks[0].fits && explanation.tier_checks[0].allowed);
+        assert_eq!(explanation.decision, InventoryAction::Demote);
+        assert_eq!(explanation.target_tier.as_deref(), Some("storage"));
+        assert!(format!("{explanation}").contains("Decision: demote to storage"));
+
+        assert!(
+            balancer
+                .explain_file(&storage_dir.path().join("missing.mkv"))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_top_level_directory() {
+        let root = Path::new("/mnt/cache");
+        assert_eq!(
+            top_level_directory(root, Path::new("/mnt/cache/Breaking Bad/S01/e1.mkv")),
+            "Breaking Bad"
+        );
+        assert_eq!(
+            top_level_directory(root, Path::new("/mnt/cache/movie.mkv")),
+            "."
+        );
+    }
+
+    #[test]
+    fn test_calculate_directory_usage() {
+        use std::path::PathBuf;
+        use std::time::SystemTime;
+
+        let mut cache = create_test_tier("cache", 1, None);
+        cache.path = PathBuf::from("/mnt/cache");
+        let mut storage = create_test_tier("storage", 10, None);
+        storage.path = PathBuf::from("/mnt/storage");
+        let balancer = Balancer::new(vec![cache, storage], vec![], None);
+
+        let file = |path: &str, size: u64| {
+            Arc::new(FileInfo {
+                path: PathBuf::from(path),
+                size,
+                modified: SystemTime::now(),
+                accessed: SystemTime::now(),
+                owner: None,
+            })
+        };
+        let decisions = vec![
+            PlacementDecision::Stay {
+                file: file("/mnt/cache/Show/e1.mkv", 100),
+                current_tier: "cache".to_string(),
+                strategy: "hot".to_string(),
+                priority: 1,
+            },
+            PlacementDecision::Demote {
+                file: file("/mnt/cache/Show/e2.mkv", 200),
+                from_tier: "cache".to_string(),
+                to_tier: "storage".to_string(),
+                strategy: "cold".to_string(),
+                priority: 1,
+            },
+            PlacementDecision::Promote {
+                file: file("/mnt/storage/Movie/movie.mkv", 50),
+                from_tier: "storage".to_string(),
+                to_tier: "cache".to_string(),
+                strategy: "hot".to_string(),
+                priority: 1,
+            },
+        ];
+
+        let usage = balancer.calculate_directory_usage(&decisions);
+        assert_eq!(usage.len(), 2);
+
+        let show = &usage["Show"];
+        assert_eq!(show.total_bytes(), 300);
+        assert_eq!(show.current_bytes["cache"], 300);
+        assert_eq!(show.projected_bytes["cache"], 100);
+        assert_eq!(show.projected_bytes["storage"], 200);
+
+        let movie = &usage["Movie"];
+        assert_eq!(movie.current_bytes["storage"], 50);
+        assert_eq!(movie.projected_bytes["cache"], 50);
+        assert!(!movie.projected_bytes.contains_key("storage"));
+    }
 }
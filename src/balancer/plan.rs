@@ -1,15 +1,40 @@
-use super::PlacementDecision;
-use std::collections::HashMap;
-use std::path::PathBuf;
+use super::{MoveBatch, PlacementDecision, StrategyStats};
+use crate::Tier;
+use crate::file::serialize_path_lossy;
+use crate::scanner::FilteredFiles;
+use serde::{Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Write};
+use std::io;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct BalancingPlan {
     pub decisions: Vec<PlacementDecision>,
-    pub projected_tier_usage: HashMap<String, TierUsageProjection>,
+    pub projected_tier_usage: BTreeMap<String, TierUsageProjection>,
+    pub projected_directory_usage: BTreeMap<String, DirectoryComposition>,
+    #[serde(serialize_with = "serialize_warnings")]
     pub warnings: Vec<PlanWarning>,
+    /// Placements that found no space on their preferred tier during Pass 2
+    pub blocked_placements: usize,
+    /// Files demoted by eviction (Pass 3a and 3b)
+    pub evictions: usize,
+    /// Moves into spin-down aware tiers, executed back to back or deferred
+    pub move_batches: Vec<MoveBatch>,
+    /// Largest files and directories on each tier after the plan, by tier name
+    pub largest_remaining: Vec<TierOffenders>,
+    /// Seed from `--seed` or the config, echoed for reproducibility reports
+    pub seed: Option<u64>,
+    /// Files each tier's scan filter left out, for tiers that skipped any
+    pub filtered_files: Vec<FilteredFiles>,
+    /// Files left on each tier marked `drain`, so emptying it can be followed across runs
+    pub draining: Vec<DrainProgress>,
+    /// Files each strategy won in Pass 2 and what became of them
+    pub strategy_stats: StrategyStats,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct TierUsageProjection {
     pub tier_name: String,
     pub current_used: u64,
@@ -20,10 +45,229 @@ pub struct TierUsageProjection {
     pub projected_percent: u64,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Bytes of one top-level directory per tier, before and after the plan
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DirectoryComposition {
+    pub directory: String,
+    pub current_bytes: BTreeMap<String, u64>,
+    pub projected_bytes: BTreeMap<String, u64>,
+}
+
+impl DirectoryComposition {
+    pub fn new(directory: String) -> Self {
+        Self {
+            directory,
+            ..Self::default()
+        }
+    }
+
+    /// Records a file of `size` bytes moving from `current_tier` to `projected_tier`
+    pub fn add_file(&mut self, size: u64, current_tier: &str, projected_tier: &str) {
+        *self
+            .current_bytes
+            .entry(current_tier.to_string())
+            .or_default() += size;
+        *self
+            .projected_bytes
+            .entry(projected_tier.to_string())
+            .or_default() += size;
+    }
+
+    /// A copied file counts on both tiers after the plan
+    pub fn add_copy(&mut self, size: u64, current_tier: &str, copy_tier: &str) {
+        self.add_file(size, current_tier, copy_tier);
+        *self
+            .projected_bytes
+            .entry(current_tier.to_string())
+            .or_default() += size;
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.current_bytes.values().sum()
+    }
+}
+
+/// Largest files and directories left on one tier once the plan is applied
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TierOffenders {
+    pub tier: String,
+    /// Biggest first
+    pub files: Vec<RemainingFile>,
+    /// Parent directories relative to the tier root, biggest first
+    pub directories: Vec<RemainingDirectory>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RemainingFile {
+    #[serde(serialize_with = "serialize_path_lossy")]
+    pub path: PathBuf,
+    pub size: u64,
+    /// Strategy that kept the file on the tier or moved it there
+    pub strategy: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RemainingDirectory {
+    pub directory: String,
+    pub bytes: u64,
+    pub files: usize,
+    /// Bytes per strategy holding files here, biggest first
+    pub strategies: Vec<StrategyBytes>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StrategyBytes {
+    pub strategy: String,
+    pub bytes: u64,
+}
+
+impl TierOffenders {
+    /// Top `limit` files and directories per target tier, from decisions paired
+    /// with the directory of their file
+    pub fn collect<'a>(
+        decisions: impl IntoIterator<Item = (&'a PlacementDecision, String)>,
+        limit: usize,
+    ) -> Vec<Self> {
+        #[derive(Default)]
+        struct Directory<'a> {
+            bytes: u64,
+            files: usize,
+            strategies: HashMap<&'a str, u64>,
+        }
+
+        let mut files: HashMap<&str, Vec<&PlacementDecision>> = HashMap::new();
+        let mut directories: HashMap<&str, HashMap<String, Directory>> = HashMap::new();
+        for (decision, directory) in decisions {
+            let tier = decision.target_tier();
+            let size = decision.file_size();
+            files.entry(tier).or_default().push(decision);
+            let entry = directories
+                .entry(tier)
+                .or_default()
+                .entry(directory)
+                .or_default();
+            entry.bytes += size;
+            entry.files += 1;
+            *entry
+                .strategies
+                .entry(decision.strategy_name())
+                .or_default() += size;
+        }
+
+        let mut offenders: Vec<Self> = files
+            .into_iter()
+            .map(|(tier, mut decisions)| {
+                decisions.sort_by(|a, b| {
+                    b.file_size()
+                        .cmp(&a.file_size())
+                        .then_with(|| a.file_path().cmp(b.file_path()))
+                });
+                let mut dirs: Vec<RemainingDirectory> = directories
+                    .remove(tier)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(directory, totals)| {
+                        let mut strategies: Vec<StrategyBytes> = totals
+                            .strategies
+                            .into_iter()
+                            .map(|(strategy, bytes)| StrategyBytes {
+                                strategy: strategy.to_string(),
+                                bytes,
+                            })
+                            .collect();
+                        strategies.sort_by(|a, b| {
+                            b.bytes
+                                .cmp(&a.bytes)
+                                .then_with(|| a.strategy.cmp(&b.strategy))
+                        });
+                        RemainingDirectory {
+                            directory,
+                            bytes: totals.bytes,
+                            files: totals.files,
+                            strategies,
+                        }
+                    })
+                    .collect();
+                dirs.sort_by(|a, b| {
+                    b.bytes
+                        .cmp(&a.bytes)
+                        .then_with(|| a.directory.cmp(&b.directory))
+                });
+                dirs.truncate(limit);
+
+                Self {
+                    tier: tier.to_string(),
+                    files: decisions
+                        .into_iter()
+                        .take(limit)
+                        .map(|d| RemainingFile {
+                            path: d.file_path().clone(),
+                            size: d.file_size(),
+                            strategy: d.strategy_name().to_string(),
+                        })
+                        .collect(),
+                    directories: dirs,
+                }
+            })
+            .collect();
+        offenders.sort_by(|a, b| a.tier.cmp(&b.tier));
+        offenders
+    }
+}
+
+/// Files on a draining tier and how many of them the plan moves off
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DrainProgress {
+    pub tier: String,
+    pub files: usize,
+    pub bytes: u64,
+    pub files_leaving: usize,
+    pub bytes_leaving: u64,
+}
+
+impl DrainProgress {
+    /// One entry per tier marked `drain`, in tier order
+    pub fn collect(tiers: &[Tier], decisions: &[PlacementDecision]) -> Vec<Self> {
+        let mut draining: Vec<Self> = tiers
+            .iter()
+            .filter(|tier| tier.drain)
+            .map(|tier| Self {
+                tier: tier.name.clone(),
+                ..Self::default()
+            })
+            .collect();
+        if draining.is_empty() {
+            return draining;
+        }
+        for decision in decisions {
+            let Some(drain) = draining
+                .iter_mut()
+                .find(|d| d.tier == decision.current_tier())
+            else {
+                continue;
+            };
+            drain.files += 1;
+            drain.bytes += decision.file_size();
+            if decision.target_tier() != drain.tier {
+                drain.files_leaving += 1;
+                drain.bytes_leaving += decision.file_size();
+            }
+        }
+        draining
+    }
+
+    /// Whether the tier is empty once the plan is applied
+    pub const fn is_complete(&self) -> bool {
+        self.files_leaving == self.files
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum PlanWarning {
     /// Стратегия требует переместить файл, но нет места
     InsufficientSpace {
+        #[serde(serialize_with = "serialize_path_lossy")]
         file: PathBuf,
         strategy: String,
         needed: u64,
@@ -33,12 +277,263 @@ pub enum PlanWarning {
     /// Required strategy не может быть выполнена
     RequiredStrategyFailed {
         strategy: String,
+        #[serde(serialize_with = "serialize_path_lossy")]
         file: PathBuf,
         reason: String,
     },
+
+    /// Preferred tier was skipped because a move policy forbids the tier pair
+    MoveDenied {
+        #[serde(serialize_with = "serialize_path_lossy")]
+        file: PathBuf,
+        strategy: String,
+        from_tier: String,
+        to_tier: String,
+    },
+
+    /// Moves into a spin-down aware tier were postponed until the batch grows
+    BatchDeferred {
+        tier: String,
+        files: usize,
+        bytes: u64,
+        min_batch_bytes: u64,
+    },
+
+    /// File moved too recently and stays put until its cooldown ends
+    CooldownActive {
+        #[serde(serialize_with = "serialize_path_lossy")]
+        file: PathBuf,
+        strategy: String,
+        moved_hours_ago: u64,
+        min_hours_between_moves: u64,
+    },
+
+    /// Move quota was used up; remaining moves wait for later runs
+    QuotaReached {
+        /// Strategy whose quota was reached, `None` for the global quota
+        strategy: Option<String>,
+        deferred_files: usize,
+        deferred_bytes: u64,
+    },
+
+    /// Same-priority strategies with different destinations matched the same files
+    AmbiguousStrategy {
+        /// Competing strategies, sorted by name
+        strategies: Vec<String>,
+        priority: u32,
+        /// Strategy chosen by the name tiebreak
+        winner: String,
+        files: usize,
+        /// One of the affected files
+        #[serde(serialize_with = "serialize_path_lossy")]
+        example: PathBuf,
+    },
+
+    /// Files pinned by `action: stay` strategies alone exceed the tier's `max_usage_percent`
+    PinnedOverCapacity {
+        tier: String,
+        pinned_bytes: u64,
+        allowed_bytes: u64,
+        /// Largest pinning strategies, biggest first
+        strategies: Vec<StrategyBytes>,
+    },
+
+    /// Demotions held back because the tier is below its `min_usage_percent`
+    BelowMinUsage {
+        tier: String,
+        usage_percent: u64,
+        min_usage_percent: u64,
+        files: usize,
+        bytes: u64,
+    },
+}
+
+impl fmt::Display for PlanWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InsufficientSpace {
+                file,
+                strategy,
+                needed,
+                available,
+            } => write!(
+                f,
+                "Insufficient space for {} (strategy: {strategy}, needed: {needed} bytes, available: {available} bytes)",
+                file.display()
+            ),
+            Self::RequiredStrategyFailed {
+                strategy,
+                file,
+                reason,
+            } => write!(
+                f,
+                "Required strategy '{strategy}' failed for {}: {reason}",
+                file.display()
+            ),
+            Self::MoveDenied {
+                file,
+                strategy,
+                from_tier,
+                to_tier,
+            } => write!(
+                f,
+                "Move policy forbids {from_tier} -> {to_tier} for {} (strategy: {strategy})",
+                file.display()
+            ),
+            Self::BatchDeferred {
+                tier,
+                files,
+                bytes,
+                min_batch_bytes,
+            } => write!(
+                f,
+                "Deferred {files} move(s) into spin-down tier '{tier}' ({bytes} bytes < {min_batch_bytes} bytes batch minimum)"
+            ),
+            Self::CooldownActive {
+                file,
+                strategy,
+                moved_hours_ago,
+                min_hours_between_moves,
+            } => write!(
+                f,
+                "Kept {} in place (strategy: {strategy}): moved {moved_hours_ago}h ago, cooldown is {min_hours_between_moves}h",
+                file.display()
+            ),
+            Self::QuotaReached {
+                strategy,
+                deferred_files,
+                deferred_bytes,
+            } => match strategy {
+                Some(strategy) => write!(
+                    f,
+                    "Move quota of strategy '{strategy}' reached: {deferred_files} move(s) ({deferred_bytes} bytes) deferred to later runs"
+                ),
+                None => write!(
+                    f,
+                    "Global move quota reached: {deferred_files} move(s) ({deferred_bytes} bytes) deferred to later runs"
+                ),
+            },
+            Self::AmbiguousStrategy {
+                strategies,
+                priority,
+                winner,
+                files,
+                example,
+            } => write!(
+                f,
+                "Strategies '{}' (priority {priority}) tie on {files} file(s) with different destinations, '{winner}' wins by name (e.g. {})",
+                strategies.join("', '"),
+                example.display()
+            ),
+            Self::PinnedOverCapacity {
+                tier,
+                pinned_bytes,
+                allowed_bytes,
+                strategies,
+            } => write!(
+                f,
+                "Pinned files on tier '{tier}' ({pinned_bytes} bytes) exceed its max usage ({allowed_bytes} bytes), pinned by '{}'",
+                strategies
+                    .iter()
+                    .map(|s| s.strategy.as_str())
+                    .collect::<Vec<_>>()
+                    .join("', '")
+            ),
+            Self::BelowMinUsage {
+                tier,
+                usage_percent,
+                min_usage_percent,
+                files,
+                bytes,
+            } => write!(
+                f,
+                "Kept {files} file(s) ({bytes} bytes) on tier '{tier}': {usage_percent}% used is below its min_usage_percent ({min_usage_percent}%), so nothing is demoted from it"
+            ),
+        }
+    }
+}
+
+/// How much a warning matters, for `fail_on` and alerting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningSeverity {
+    /// Expected behavior worth knowing about, e.g. a cooldown or a deferred batch
+    Info,
+    /// A placement the config asked for didn't happen
+    Warning,
+    /// The config can't be honored: a required strategy failed or pinned files overflow a tier
+    Critical,
+}
+
+impl fmt::Display for WarningSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        })
+    }
+}
+
+/// Serializes warnings with their `severity` next to the `type` tag
+fn serialize_warnings<S: Serializer>(
+    warnings: &[PlanWarning],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct WithSeverity<'a> {
+        #[serde(flatten)]
+        warning: &'a PlanWarning,
+        severity: WarningSeverity,
+    }
+
+    serializer.collect_seq(warnings.iter().map(|warning| WithSeverity {
+        warning,
+        severity: warning.severity(),
+    }))
+}
+
+impl PlanWarning {
+    pub const fn severity(&self) -> WarningSeverity {
+        match self {
+            Self::RequiredStrategyFailed { .. } | Self::PinnedOverCapacity { .. } => {
+                WarningSeverity::Critical
+            }
+            Self::InsufficientSpace { .. }
+            | Self::MoveDenied { .. }
+            | Self::AmbiguousStrategy { .. } => WarningSeverity::Warning,
+            Self::BatchDeferred { .. }
+            | Self::CooldownActive { .. }
+            | Self::QuotaReached { .. }
+            | Self::BelowMinUsage { .. } => WarningSeverity::Info,
+        }
+    }
+
+    /// File the warning is about, `None` for warnings about whole batches
+    pub fn file(&self) -> Option<&Path> {
+        match self {
+            Self::InsufficientSpace { file, .. }
+            | Self::RequiredStrategyFailed { file, .. }
+            | Self::MoveDenied { file, .. }
+            | Self::CooldownActive { file, .. } => Some(file),
+            Self::BatchDeferred { .. }
+            | Self::QuotaReached { .. }
+            | Self::AmbiguousStrategy { .. }
+            | Self::PinnedOverCapacity { .. }
+            | Self::BelowMinUsage { .. } => None,
+        }
+    }
 }
 
 impl BalancingPlan {
+    /// Number of warnings with the given severity
+    pub fn warning_count(&self, severity: WarningSeverity) -> usize {
+        self.warnings
+            .iter()
+            .filter(|warning| warning.severity() == severity)
+            .count()
+    }
+
     /// Проверяет, пуст ли план (все решения - Stay)
     pub fn is_empty(&self) -> bool {
         self.decisions
@@ -54,6 +549,15 @@ impl BalancingPlan {
             .count()
     }
 
+    /// Total size of the files to move
+    pub fn move_bytes(&self) -> u64 {
+        self.decisions
+            .iter()
+            .filter(|d| !matches!(d, PlacementDecision::Stay { .. }))
+            .map(PlacementDecision::file_size)
+            .sum()
+    }
+
     /// Количество файлов, которые остаются на месте
     pub fn stay_count(&self) -> usize {
         self.decisions
@@ -66,6 +570,82 @@ impl BalancingPlan {
     pub const fn total_files(&self) -> usize {
         self.decisions.len()
     }
+
+    /// SHA-256 over the sorted decisions, equal for plans that move the same files
+    /// the same way regardless of decision order
+    pub fn fingerprint(&self) -> String {
+        let mut lines: Vec<String> = self
+            .decisions
+            .iter()
+            .map(|d| {
+                format!(
+                    "{}\t{}\t{}\t{}\t{}\t{}\n",
+                    d.action(),
+                    d.file_path().display(),
+                    d.file_size(),
+                    d.current_tier(),
+                    d.target_tier(),
+                    d.strategy_name()
+                )
+            })
+            .collect();
+        lines.sort_unstable();
+
+        let mut hasher = Sha256::new();
+        for line in &lines {
+            hasher.update(line.as_bytes());
+        }
+        hasher
+            .finalize()
+            .iter()
+            .fold(String::with_capacity(64), |mut hex, byte| {
+                let _ = write!(hex, "{byte:02x}");
+                hex
+            })
+    }
+
+    /// One row per decision after a header, fields split by `separator` (`,` or tab)
+    ///
+    /// Fields holding the separator, a quote or a line break are quoted as in RFC 4180.
+    pub fn write_delimited(&self, out: &mut impl io::Write, separator: char) -> io::Result<()> {
+        let field = |value: &str| {
+            if value.contains([separator, '"', '\n', '\r']) {
+                format!("\"{}\"", value.replace('"', "\"\""))
+            } else {
+                value.to_string()
+            }
+        };
+        let row = |fields: [&str; 7]| {
+            let mut line = fields.map(field).join(&separator.to_string());
+            line.push('\n');
+            line
+        };
+
+        out.write_all(
+            row([
+                "path", "size", "from", "to", "action", "strategy", "priority",
+            ])
+            .as_bytes(),
+        )?;
+        for decision in &self.decisions {
+            let path = decision.file_path().to_string_lossy();
+            let size = decision.file_size().to_string();
+            let priority = decision.strategy_priority().to_string();
+            out.write_all(
+                row([
+                    &path,
+                    &size,
+                    decision.current_tier(),
+                    decision.target_tier(),
+                    decision.action(),
+                    decision.strategy_name(),
+                    &priority,
+                ])
+                .as_bytes(),
+            )?;
+        }
+        out.flush()
+    }
 }
 
 #[cfg(test)]
@@ -81,6 +661,7 @@ mod tests {
             size: 1000,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         }
     }
 
@@ -101,8 +682,9 @@ mod tests {
                     priority: 1,
                 },
             ],
-            projected_tier_usage: HashMap::new(),
+            projected_tier_usage: BTreeMap::new(),
             warnings: vec![],
+            ..Default::default()
         };
 
         assert!(plan.is_empty());
@@ -128,8 +710,9 @@ mod tests {
                     priority: 10,
                 },
             ],
-            projected_tier_usage: HashMap::new(),
+            projected_tier_usage: BTreeMap::new(),
             warnings: vec![],
+            ..Default::default()
         };
 
         assert!(!plan.is_empty());
@@ -162,8 +745,9 @@ mod tests {
                     priority: 1,
                 },
             ],
-            projected_tier_usage: HashMap::new(),
+            projected_tier_usage: BTreeMap::new(),
             warnings: vec![],
+            ..Default::default()
         };
 
         assert_eq!(plan.move_count(), 2);
@@ -171,12 +755,77 @@ mod tests {
         assert_eq!(plan.total_files(), 3);
     }
 
+    #[test]
+    fn test_tier_offenders_largest_remaining() {
+        let file = |name: &str, size| {
+            Arc::new(FileInfo {
+                size,
+                ..create_test_file(name)
+            })
+        };
+        let decisions = [
+            PlacementDecision::Stay {
+                file: file("Show/e1.mkv", 300),
+                current_tier: "cache".to_string(),
+                strategy: "recent".to_string(),
+                priority: 50,
+            },
+            PlacementDecision::Promote {
+                file: file("Show/e2.mkv", 500),
+                from_tier: "storage".to_string(),
+                to_tier: "cache".to_string(),
+                strategy: "watching".to_string(),
+                priority: 60,
+            },
+            PlacementDecision::Stay {
+                file: file("Movie/m.mkv", 700),
+                current_tier: "cache".to_string(),
+                strategy: "pinned".to_string(),
+                priority: 90,
+            },
+            PlacementDecision::Demote {
+                file: file("Old/o.mkv", 900),
+                from_tier: "cache".to_string(),
+                to_tier: "storage".to_string(),
+                strategy: "old".to_string(),
+                priority: 10,
+            },
+        ];
+        let directory = |d: &PlacementDecision| {
+            d.file_path()
+                .parent()
+                .unwrap()
+                .strip_prefix("/test")
+                .unwrap()
+                .display()
+                .to_string()
+        };
+
+        let offenders = TierOffenders::collect(decisions.iter().map(|d| (d, directory(d))), 1);
+
+        assert_eq!(offenders.len(), 2);
+        let cache = &offenders[0];
+        assert_eq!(cache.tier, "cache");
+        assert_eq!(cache.files.len(), 1);
+        assert_eq!(cache.files[0].path, PathBuf::from("/test/Movie/m.mkv"));
+        assert_eq!(cache.files[0].strategy, "pinned");
+        assert_eq!(cache.directories.len(), 1);
+        let show = &cache.directories[0];
+        assert_eq!(
+            (show.directory.as_str(), show.bytes, show.files),
+            ("Show", 800, 2)
+        );
+        assert_eq!(show.strategies[0].strategy, "watching");
+        assert_eq!(offenders[1].files[0].path, PathBuf::from("/test/Old/o.mkv"));
+    }
+
     #[test]
     fn test_total_files() {
         let plan = BalancingPlan {
             decisions: vec![],
-            projected_tier_usage: HashMap::new(),
+            projected_tier_usage: BTreeMap::new(),
             warnings: vec![],
+            ..Default::default()
         };
 
         assert_eq!(plan.total_files(), 0);
@@ -210,6 +859,7 @@ mod tests {
             needed: 100_000_000_000,
             available: 50_000_000_000,
         };
+        assert_eq!(warning.severity(), WarningSeverity::Warning);
 
         match warning {
             PlanWarning::InsufficientSpace {
@@ -228,6 +878,7 @@ mod tests {
             file: PathBuf::from("/test/important.dat"),
             reason: "No tier with sufficient space".to_string(),
         };
+        assert_eq!(warning.severity(), WarningSeverity::Critical);
 
         match warning {
             PlanWarning::RequiredStrategyFailed { strategy, .. } => {
@@ -246,7 +897,7 @@ mod tests {
                 strategy: "test".to_string(),
                 priority: 1,
             }],
-            projected_tier_usage: HashMap::new(),
+            projected_tier_usage: BTreeMap::new(),
             warnings: vec![
                 PlanWarning::InsufficientSpace {
                     file: PathBuf::from("/test/large.mkv"),
@@ -260,6 +911,7 @@ mod tests {
                     reason: "No space".to_string(),
                 },
             ],
+            ..Default::default()
         };
 
         assert_eq!(plan.warnings.len(), 2);
@@ -267,7 +919,7 @@ mod tests {
 
     #[test]
     fn test_plan_with_projected_usage() {
-        let mut projected_usage = HashMap::new();
+        let mut projected_usage = BTreeMap::new();
         projected_usage.insert(
             "cache".to_string(),
             TierUsageProjection {
@@ -283,11 +935,118 @@ mod tests {
 
         let plan = BalancingPlan {
             decisions: vec![],
-            projected_tier_usage: projected_usage.clone(),
+            projected_tier_usage: projected_usage,
             warnings: vec![],
+            ..Default::default()
         };
 
         assert_eq!(plan.projected_tier_usage.len(), 1);
         assert!(plan.projected_tier_usage.contains_key("cache"));
     }
+
+    #[test]
+    fn test_plan_warning_display() {
+        let warning = PlanWarning::MoveDenied {
+            file: PathBuf::from("/test/movie.mkv"),
+            strategy: "archive".to_string(),
+            from_tier: "cache".to_string(),
+            to_tier: "archive".to_string(),
+        };
+
+        assert_eq!(
+            warning.to_string(),
+            "Move policy forbids cache -> archive for /test/movie.mkv (strategy: archive)"
+        );
+    }
+
+    #[test]
+    fn test_plan_serializes_to_json() {
+        let plan = BalancingPlan {
+            decisions: vec![PlacementDecision::Demote {
+                file: Arc::new(create_test_file("file1.mkv")),
+                from_tier: "cache".to_string(),
+                to_tier: "storage".to_string(),
+                strategy: "old_files".to_string(),
+                priority: 10,
+            }],
+            warnings: vec![PlanWarning::InsufficientSpace {
+                file: PathBuf::from("/test/large.mkv"),
+                strategy: "test".to_string(),
+                needed: 1000,
+                available: 500,
+            }],
+            ..Default::default()
+        };
+
+        let value = serde_json::to_value(&plan).unwrap();
+        let decision = &value["decisions"][0];
+        assert_eq!(decision["action"], "demote");
+        assert_eq!(decision["file"]["path"], "/test/file1.mkv");
+        assert_eq!(decision["to_tier"], "storage");
+        assert_eq!(value["warnings"][0]["type"], "insufficient_space");
+        assert_eq!(value["warnings"][0]["file"], "/test/large.mkv");
+        assert_eq!(value["warnings"][0]["severity"], "warning");
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_decision_order() {
+        let demote = PlacementDecision::Demote {
+            file: Arc::new(create_test_file("a.mkv")),
+            from_tier: "cache".to_string(),
+            to_tier: "storage".to_string(),
+            strategy: "old_files".to_string(),
+            priority: 10,
+        };
+        let stay = PlacementDecision::Stay {
+            file: Arc::new(create_test_file("b.mkv")),
+            current_tier: "cache".to_string(),
+            strategy: "default".to_string(),
+            priority: 1,
+        };
+        let plan = |decisions| BalancingPlan {
+            decisions,
+            ..Default::default()
+        };
+
+        let fingerprint = plan(vec![demote.clone(), stay.clone()]).fingerprint();
+        assert_eq!(fingerprint.len(), 64);
+        assert_eq!(fingerprint, plan(vec![stay.clone(), demote]).fingerprint());
+        assert_ne!(fingerprint, plan(vec![stay]).fingerprint());
+    }
+
+    #[test]
+    fn test_write_delimited_quotes_fields() {
+        let plan = BalancingPlan {
+            decisions: vec![
+                PlacementDecision::Demote {
+                    file: Arc::new(create_test_file("Movie, The (2020).mkv")),
+                    from_tier: "cache".to_string(),
+                    to_tier: "storage".to_string(),
+                    strategy: "old \"archive\"".to_string(),
+                    priority: 5,
+                },
+                PlacementDecision::Stay {
+                    file: Arc::new(create_test_file("b.mkv")),
+                    current_tier: "cache".to_string(),
+                    strategy: "test".to_string(),
+                    priority: 1,
+                },
+            ],
+            ..Default::default()
+        };
+
+        let mut csv = Vec::new();
+        plan.write_delimited(&mut csv, ',').unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "path,size,from,to,action,strategy,priority\n\
+             \"/test/Movie, The (2020).mkv\",1000,cache,storage,demote,\"old \"\"archive\"\"\",5\n\
+             /test/b.mkv,1000,cache,cache,stay,test,1\n"
+        );
+
+        let mut tsv = Vec::new();
+        plan.write_delimited(&mut tsv, '\t').unwrap();
+        let tsv = String::from_utf8(tsv).unwrap();
+        assert!(tsv.contains("\n/test/Movie, The (2020).mkv\t1000\tcache\tstorage\tdemote\t"));
+    }
 }
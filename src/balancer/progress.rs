@@ -0,0 +1,77 @@
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Files placed between two progress reports in Pass 2
+pub(super) const REPORT_EVERY: usize = 1024;
+
+/// Stage of `plan_rebalance`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanningPhase {
+    Scanning,
+    Statistics,
+    Placement,
+    Eviction,
+    Promotion,
+    Done,
+}
+
+impl PlanningPhase {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Scanning => "scanning",
+            Self::Statistics => "statistics",
+            Self::Placement => "placement",
+            Self::Eviction => "eviction",
+            Self::Promotion => "promotion",
+            Self::Done => "done",
+        }
+    }
+}
+
+/// Progress of a planning run, reported as it advances
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PlanningProgress {
+    pub phase: PlanningPhase,
+    pub files_scanned: usize,
+    /// Files placed by Pass 2 so far, out of `files_scanned`
+    pub files_planned: usize,
+    /// Evictions computed by Pass 3
+    pub evictions: usize,
+}
+
+impl PlanningProgress {
+    pub(super) const fn new() -> Self {
+        Self {
+            phase: PlanningPhase::Scanning,
+            files_scanned: 0,
+            files_planned: 0,
+            evictions: 0,
+        }
+    }
+
+    /// Share of the scanned files placed so far
+    pub fn percent(&self) -> u8 {
+        if self.files_scanned == 0 {
+            return 0;
+        }
+        (self.files_planned * 100 / self.files_scanned).min(100) as u8
+    }
+}
+
+/// Receives planning progress; called from the planning thread
+pub type PlanningProgressCallback = Arc<dyn Fn(&PlanningProgress) + Send + Sync>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent() {
+        let mut progress = PlanningProgress::new();
+        assert_eq!(progress.percent(), 0);
+        progress.files_scanned = 3;
+        progress.files_planned = 2;
+        assert_eq!(progress.percent(), 66);
+    }
+}
@@ -0,0 +1,350 @@
+use super::quota::QuotaTracker;
+use super::{MoveCooldown, PlacementDecision};
+use crate::Tier;
+use crate::move_policy::MovePolicies;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Fills tiers below `min_usage_percent` with files from slower tiers
+pub struct PromotionPlanner<'a> {
+    tiers: &'a [Tier],
+    move_policies: Option<&'a MovePolicies>,
+    cooldown: Option<&'a MoveCooldown>,
+    pinned_strategies: HashSet<&'a str>,
+}
+
+impl<'a> PromotionPlanner<'a> {
+    pub fn new(tiers: &'a [Tier]) -> Self {
+        Self {
+            tiers,
+            move_policies: None,
+            cooldown: None,
+            pinned_strategies: HashSet::new(),
+        }
+    }
+
+    /// Never promote across a move that policies forbid
+    pub fn with_move_policies(mut self, move_policies: &'a MovePolicies) -> Self {
+        self.move_policies = Some(move_policies);
+        self
+    }
+
+    /// Leave files that moved recently where they are
+    pub fn with_cooldown(mut self, cooldown: Option<&'a MoveCooldown>) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Strategies with `action: stay`, whose files never move
+    pub fn with_pinned_strategies(mut self, strategies: impl IntoIterator<Item = &'a str>) -> Self {
+        self.pinned_strategies = strategies.into_iter().collect();
+        self
+    }
+
+    /// Promote files into tiers below `min_usage_percent` until they reach it
+    ///
+    /// Fastest tiers are filled first. Candidates are files staying on slower tiers,
+    /// best first: higher strategy priority, then most recently accessed.
+    /// Unmatched (`no-match`) and `action: stay` files are never promoted, and
+    /// promotions stop once the run quota is used up.
+    pub fn fill_underused_tiers(
+        &self,
+        decisions: &mut [PlacementDecision],
+        tier_free_space: &mut HashMap<String, u64>,
+        quotas: &mut QuotaTracker,
+    ) -> usize {
+        let mut tiers: Vec<&Tier> = self.tiers.iter().collect();
+        tiers.sort_by_key(|t| t.priority);
+
+        let mut promoted_count = 0;
+        for tier in tiers {
+            let Some(min_percent) = tier.min_usage_percent.filter(|_| tier.accepts_files()) else {
+                continue;
+            };
+
+            let total = tier.get_total_space();
+            let target_used = total / 100 * min_percent;
+            let simulated_free = tier_free_space.get(&tier.name).copied().unwrap_or(0);
+            if total.saturating_sub(simulated_free) >= target_used {
+                continue;
+            }
+
+            tracing::info!(
+                "Tier '{}' is below min_usage_percent ({}%), looking for files to promote",
+                tier.name,
+                min_percent
+            );
+            promoted_count += self.fill_tier(tier, target_used, decisions, tier_free_space, quotas);
+        }
+
+        promoted_count
+    }
+
+    fn fill_tier(
+        &self,
+        tier: &Tier,
+        target_used: u64,
+        decisions: &mut [PlacementDecision],
+        tier_free_space: &mut HashMap<String, u64>,
+        quotas: &mut QuotaTracker,
+    ) -> usize {
+        let mut candidates = self.find_promotion_candidates(tier, decisions);
+        candidates.sort_by(|&idx1, &idx2| {
+            let (d1, d2) = (&decisions[idx1], &decisions[idx2]);
+            d2.strategy_priority()
+                .cmp(&d1.strategy_priority())
+                .then_with(|| d2.file().accessed.cmp(&d1.file().accessed))
+                .then_with(|| d1.file_path().cmp(d2.file_path()))
+        });
+
+        let total = tier.get_total_space();
+        let mut promoted_count = 0;
+
+        for idx in candidates {
+            let free = tier_free_space.get(&tier.name).copied().unwrap_or(0);
+            if total.saturating_sub(free) >= target_used {
+                break;
+            }
+
+            let Some(PlacementDecision::Stay {
+                file,
+                current_tier,
+                strategy,
+                priority,
+            }) = decisions.get(idx).cloned()
+            else {
+                continue;
+            };
+            if !self.can_accept_file(tier, file.size, free) {
+                continue;
+            }
+            if !quotas.try_reserve_global(file.size) {
+                break;
+            }
+
+            tracing::debug!(
+                "Promoting {} from {} to {} to reach min_usage_percent",
+                file.path.display(),
+                current_tier,
+                tier.name
+            );
+
+            self.apply_move(tier_free_space, file.size, &current_tier, &tier.name);
+            decisions[idx] = PlacementDecision::Promote {
+                file: Arc::clone(&file),
+                from_tier: current_tier,
+                to_tier: tier.name.clone(),
+                strategy,
+                priority,
+            };
+            promoted_count += 1;
+        }
+
+        promoted_count
+    }
+
+    fn find_promotion_candidates(
+        &self,
+        tier: &Tier,
+        decisions: &[PlacementDecision],
+    ) -> Vec<usize> {
+        decisions
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| {
+                matches!(d, PlacementDecision::Stay { .. })
+                    && d.strategy_priority() > 0
+                    && !self.pinned_strategies.contains(d.strategy_name())
+            })
+            .filter(|(_, d)| {
+                self.find_tier(d.current_tier()).is_some_and(|current| {
+                    current.priority > tier.priority
+                        && self
+                            .move_policies
+                            .is_none_or(|policies| policies.is_allowed(&current.name, &tier.name))
+                        && !self.is_cooling_down(current, d)
+                })
+            })
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    fn is_cooling_down(&self, current_tier: &Tier, decision: &PlacementDecision) -> bool {
+        self.cooldown
+            .is_some_and(|cooldown| cooldown.is_cooling_down(current_tier, decision.file_path()))
+    }
+
+    fn find_tier(&self, name: &str) -> Option<&'a Tier> {
+        self.tiers.iter().find(|t| t.name == name)
+    }
+
+    fn can_accept_file(&self, tier: &Tier, file_size: u64, simulated_free: u64) -> bool {
+        if simulated_free < file_size || !tier.fits_file_size(file_size) {
+            return false;
+        }
+
+        if let Some(max_percent) = tier.max_usage_percent {
+            let total = tier.get_total_space();
+            let after_used = total - simulated_free.saturating_sub(file_size);
+            let after_percent = (after_used as f64 / total as f64 * 100.0) as u64;
+
+            if after_percent > max_percent {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn apply_move(
+        &self,
+        tier_free_space: &mut HashMap<String, u64>,
+        file_size: u64,
+        from_tier: &str,
+        to_tier: &str,
+    ) {
+        if let Some(free) = tier_free_space.get_mut(from_tier) {
+            *free = free.saturating_add(file_size);
+        }
+
+        if let Some(free) = tier_free_space.get_mut(to_tier) {
+            *free = free.saturating_sub(file_size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileInfo;
+    use crate::balancer::MoveQuota;
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    fn unlimited() -> QuotaTracker {
+        QuotaTracker::new(MoveQuota::default())
+    }
+
+    const GB: u64 = 1024 * 1024 * 1024;
+    const TB: u64 = 1024 * GB;
+
+    fn stay(
+        path: &str,
+        size: u64,
+        accessed_secs_ago: u64,
+        strategy: &str,
+        priority: u32,
+    ) -> PlacementDecision {
+        PlacementDecision::Stay {
+            file: Arc::new(FileInfo {
+                path: PathBuf::from(path),
+                size,
+                modified: SystemTime::now(),
+                accessed: SystemTime::now() - Duration::from_secs(accessed_secs_ago),
+                owner: None,
+            }),
+            current_tier: path.split('/').nth(1).unwrap_or_default().to_string(),
+            strategy: strategy.to_string(),
+            priority,
+        }
+    }
+
+    fn promoted(decisions: &[PlacementDecision]) -> Vec<&str> {
+        decisions
+            .iter()
+            .filter(|d| matches!(d, PlacementDecision::Promote { .. }))
+            .map(|d| d.file_path().to_str().unwrap())
+            .collect()
+    }
+
+    fn free_space(tiers: &[Tier]) -> HashMap<String, u64> {
+        tiers
+            .iter()
+            .map(|t| (t.name.clone(), t.get_free_space()))
+            .collect()
+    }
+
+    #[test]
+    fn test_fills_underused_tier_by_priority_then_recency() {
+        let mut cache = Tier::new_mock_with_usage("cache", 1, None, TB, 0);
+        cache.min_usage_percent = Some(20);
+        let tiers = vec![
+            cache,
+            Tier::new_mock_with_usage("storage", 10, None, 10 * TB, 50),
+        ];
+
+        let mut decisions = vec![
+            stay("/storage/old_important.mkv", 100 * GB, 3600, "series", 50),
+            stay("/storage/recent.mkv", 100 * GB, 60, "movies", 10),
+            stay("/storage/stale.mkv", 100 * GB, 7200, "movies", 10),
+            stay("/storage/unmatched.mkv", 100 * GB, 0, "no-match", 0),
+        ];
+        let mut tier_free_space = free_space(&tiers);
+
+        let count = PromotionPlanner::new(&tiers).fill_underused_tiers(
+            &mut decisions,
+            &mut tier_free_space,
+            &mut unlimited(),
+        );
+
+        assert_eq!(count, 3);
+        assert_eq!(
+            promoted(&decisions),
+            vec![
+                "/storage/old_important.mkv",
+                "/storage/recent.mkv",
+                "/storage/stale.mkv"
+            ]
+        );
+        assert_eq!(tier_free_space["cache"], TB - 300 * GB);
+    }
+
+    #[test]
+    fn test_stops_at_min_usage_and_skips_pinned() {
+        let mut cache = Tier::new_mock_with_usage("cache", 1, None, TB, 0);
+        cache.min_usage_percent = Some(10);
+        let tiers = vec![
+            cache,
+            Tier::new_mock_with_usage("storage", 10, None, 10 * TB, 50),
+        ];
+
+        let mut decisions = vec![
+            stay("/storage/pinned.mkv", 200 * GB, 0, "keep", 90),
+            stay("/storage/first.mkv", 60 * GB, 0, "series", 50),
+            stay("/storage/second.mkv", 60 * GB, 10, "series", 50),
+            stay("/storage/third.mkv", 60 * GB, 20, "series", 50),
+        ];
+        let mut tier_free_space = free_space(&tiers);
+
+        PromotionPlanner::new(&tiers)
+            .with_pinned_strategies(["keep"])
+            .fill_underused_tiers(&mut decisions, &mut tier_free_space, &mut unlimited());
+
+        assert_eq!(
+            promoted(&decisions),
+            vec!["/storage/first.mkv", "/storage/second.mkv"]
+        );
+    }
+
+    #[test]
+    fn test_no_promotion_when_tier_reaches_min_usage() {
+        let mut cache = Tier::new_mock_with_usage("cache", 1, None, TB, 40);
+        cache.min_usage_percent = Some(30);
+        let tiers = vec![
+            cache,
+            Tier::new_mock_with_usage("storage", 10, None, 10 * TB, 50),
+        ];
+
+        let mut decisions = vec![stay("/storage/file.mkv", GB, 0, "series", 50)];
+        let mut tier_free_space = free_space(&tiers);
+
+        let count = PromotionPlanner::new(&tiers).fill_underused_tiers(
+            &mut decisions,
+            &mut tier_free_space,
+            &mut unlimited(),
+        );
+
+        assert_eq!(count, 0);
+        assert!(promoted(&decisions).is_empty());
+    }
+}
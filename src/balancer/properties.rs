@@ -0,0 +1,240 @@
+//! Invariants every plan keeps, checked on random tiers, files and strategies
+
+use super::{Balancer, BalancingPlan, PlacementDecision};
+use crate::conditions::{AgeCondition, FileSizeCondition};
+use crate::disk::MockDisk;
+use crate::{FileInfo, FixedClock, MemoryScanner, PlacementStrategy, StrategyAction, Tier};
+use proptest::prelude::*;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const GB: u64 = 1_000_000_000;
+const TIERS: usize = 3;
+
+fn now() -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+}
+
+#[derive(Debug, Clone)]
+struct TierSpec {
+    /// Bytes used by data outside the scanned files
+    other: u64,
+    free: u64,
+    max_usage: Option<u64>,
+    min_usage: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+struct StrategySpec {
+    priority: u32,
+    /// 0: none, 1: older than, 2: newer than, 3: larger than
+    condition: (u8, u64),
+    action: StrategyAction,
+    /// Indices into the tiers, in preference order
+    preferred: Vec<usize>,
+}
+
+fn tier_spec() -> impl Strategy<Value = TierSpec> {
+    (
+        0..500u64,
+        0..500u64,
+        proptest::option::of(50..=100u64),
+        proptest::option::of(0..=30u64),
+    )
+        .prop_map(|(other, free, max_usage, min_usage)| TierSpec {
+            other: other * GB,
+            free: free * GB,
+            max_usage,
+            min_usage,
+        })
+}
+
+/// Tier index, size in GB and age in hours of one file
+fn file_spec() -> impl Strategy<Value = (usize, u64, u64)> {
+    (0..TIERS, 1..=50u64, 0..=2000u64)
+}
+
+fn strategy_spec() -> impl Strategy<Value = StrategySpec> {
+    (
+        1..=100u32,
+        (0..4u8, 1..=1000u64),
+        prop_oneof![
+            6 => Just(StrategyAction::Evaluate),
+            1 => Just(StrategyAction::Stay),
+            1 => Just(StrategyAction::Copy),
+        ],
+        Just((0..TIERS).collect::<Vec<_>>())
+            .prop_shuffle()
+            .prop_flat_map(|order| (1..=TIERS).prop_map(move |n| order[..n].to_vec())),
+    )
+        .prop_map(|(priority, condition, action, preferred)| StrategySpec {
+            priority,
+            condition,
+            action,
+            preferred,
+        })
+}
+
+fn tier_name(index: usize) -> String {
+    format!("t{index}")
+}
+
+fn files(specs: &[(usize, u64, u64)]) -> Vec<(usize, FileInfo)> {
+    specs
+        .iter()
+        .enumerate()
+        .map(|(i, &(tier, size, age_hours))| {
+            let modified = now() - Duration::from_secs(age_hours * 3600);
+            let file = FileInfo {
+                path: PathBuf::from(format!("/mnt/t{tier}/show {}/f{i}.mkv", i % 5)),
+                size: size * GB,
+                modified,
+                accessed: modified,
+                owner: None,
+            };
+            (tier, file)
+        })
+        .collect()
+}
+
+/// Plans `files` in the given order; tier usage is their files plus `other`
+fn plan(
+    tiers: &[TierSpec],
+    strategies: &[StrategySpec],
+    files: &[(usize, FileInfo)],
+) -> BalancingPlan {
+    let tiers: Vec<Tier> = tiers
+        .iter()
+        .enumerate()
+        .map(|(i, spec)| {
+            let used = spec.other
+                + files
+                    .iter()
+                    .filter(|(tier, _)| *tier == i)
+                    .map(|(_, file)| file.size)
+                    .sum::<u64>();
+            Tier::new_unchecked(
+                tier_name(i),
+                PathBuf::from(format!("/mnt/t{i}")),
+                i as u32 + 1,
+                spec.max_usage,
+                spec.min_usage,
+            )
+            .unwrap()
+            .with_disk_ops(Arc::new(MockDisk::new(used + spec.free, spec.free)))
+        })
+        .collect();
+    let strategies = strategies
+        .iter()
+        .enumerate()
+        .map(|(i, spec)| {
+            let mut strategy = PlacementStrategy::new(format!("s{i}"), spec.priority);
+            strategy = match spec.condition {
+                (1, hours) => {
+                    strategy.add_condition(Box::new(AgeCondition::new(Some(hours), None)))
+                }
+                (2, hours) => {
+                    strategy.add_condition(Box::new(AgeCondition::new(None, Some(hours))))
+                }
+                (3, gb) => strategy
+                    .add_condition(Box::new(FileSizeCondition::new(Some(gb % 50 * 1000), None))),
+                _ => strategy,
+            };
+            strategy.action = spec.action;
+            spec.preferred
+                .iter()
+                .fold(strategy, |s, &t| s.add_preferred_tier(tier_name(t)))
+        })
+        .collect();
+    let scanner = files
+        .iter()
+        .fold(MemoryScanner::new(), |scanner, (tier, file)| {
+            scanner.with_file(tier_name(*tier), file.clone())
+        });
+
+    Balancer::builder(tiers, strategies)
+        .scanner(Arc::new(scanner))
+        .clock(Arc::new(FixedClock::new(now())))
+        .build()
+        .plan_rebalance()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(128))]
+
+    #[test]
+    fn test_plan_invariants(
+        tiers in proptest::collection::vec(tier_spec(), TIERS),
+        strategies in proptest::collection::vec(strategy_spec(), 1..=4),
+        (specs, order) in proptest::collection::vec(file_spec(), 0..60).prop_flat_map(|specs| {
+            let order = Just((0..specs.len()).collect::<Vec<_>>()).prop_shuffle();
+            (Just(specs), order)
+        }),
+    ) {
+        let files = files(&specs);
+        let plan = plan(&tiers, &strategies, &files);
+
+        // Every move leaves its tier, and bytes moved out arrive somewhere
+        let mut delta: BTreeMap<&str, i128> = BTreeMap::new();
+        let mut copied = 0;
+        for decision in &plan.decisions {
+            match decision {
+                PlacementDecision::Promote { file, from_tier, to_tier, .. }
+                | PlacementDecision::Demote { file, from_tier, to_tier, .. } => {
+                    prop_assert_ne!(from_tier, to_tier);
+                    *delta.entry(from_tier).or_default() -= i128::from(file.size);
+                    *delta.entry(to_tier).or_default() += i128::from(file.size);
+                }
+                PlacementDecision::Copy { file, from_tier, to_tier, .. } => {
+                    prop_assert_ne!(from_tier, to_tier);
+                    *delta.entry(to_tier).or_default() += i128::from(file.size);
+                    copied += i128::from(file.size);
+                }
+                PlacementDecision::Stay { .. } => {}
+            }
+        }
+        let mut current = 0;
+        let mut projected = 0;
+        for (name, usage) in &plan.projected_tier_usage {
+            prop_assert_eq!(
+                i128::from(usage.projected_used) - i128::from(usage.current_used),
+                delta.get(name.as_str()).copied().unwrap_or_default(),
+                "tier {}", name
+            );
+            current += i128::from(usage.current_used);
+            projected += i128::from(usage.projected_used);
+        }
+        prop_assert_eq!(projected, current + copied);
+
+        // No plan pushes a tier past max_usage. A tier that starts over it may
+        // stay over when nothing can take its files, but never grows.
+        for (i, spec) in tiers.iter().enumerate() {
+            let usage = &plan.projected_tier_usage[&tier_name(i)];
+            let Some(max) = spec.max_usage else {
+                continue;
+            };
+            if usage.current_percent <= max {
+                prop_assert!(
+                    usage.projected_percent <= max,
+                    "tier t{} went from {}% to {}%, limit {}%",
+                    i, usage.current_percent, usage.projected_percent, max
+                );
+            } else {
+                prop_assert!(
+                    usage.projected_used <= usage.current_used,
+                    "tier t{} over its {}% limit grew from {}% to {}%",
+                    i, max, usage.current_percent, usage.projected_percent
+                );
+            }
+        }
+
+        // The order files are scanned in does not matter
+        let shuffled: Vec<_> = order.iter().map(|&i| files[i].clone()).collect();
+        prop_assert_eq!(
+            serde_json::to_string(&plan).unwrap(),
+            serde_json::to_string(&self::plan(&tiers, &strategies, &shuffled)).unwrap()
+        );
+    }
+}
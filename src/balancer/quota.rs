@@ -0,0 +1,188 @@
+use super::PlanWarning;
+use crate::PlacementStrategy;
+use std::collections::BTreeMap;
+
+/// Upper bound on the moves planned in a single run
+///
+/// A move is admitted while the quota is not yet used up, so the last admitted
+/// file may overshoot `max_bytes`; this keeps files larger than the quota movable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MoveQuota {
+    pub max_moves: Option<usize>,
+    pub max_bytes: Option<u64>,
+}
+
+impl MoveQuota {
+    pub const fn new(max_moves: Option<usize>, max_bytes: Option<u64>) -> Self {
+        Self {
+            max_moves,
+            max_bytes,
+        }
+    }
+
+    fn is_exhausted(&self, used: QuotaUsage) -> bool {
+        self.max_moves.is_some_and(|max| used.moves >= max)
+            || self.max_bytes.is_some_and(|max| used.bytes >= max)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct QuotaUsage {
+    moves: usize,
+    bytes: u64,
+}
+
+impl QuotaUsage {
+    fn add(&mut self, size: u64) {
+        self.moves += 1;
+        self.bytes = self.bytes.saturating_add(size);
+    }
+
+    fn remove(&mut self, size: u64) {
+        self.moves = self.moves.saturating_sub(1);
+        self.bytes = self.bytes.saturating_sub(size);
+    }
+}
+
+/// Tracks per-strategy and global quota usage for every move a plan adds
+pub(super) struct QuotaTracker {
+    global: MoveQuota,
+    global_used: QuotaUsage,
+    strategy_used: BTreeMap<String, QuotaUsage>,
+    /// Moves held back, keyed by strategy name (`None` for the global quota)
+    deferred: BTreeMap<Option<String>, QuotaUsage>,
+}
+
+impl QuotaTracker {
+    pub fn new(global: MoveQuota) -> Self {
+        Self {
+            global,
+            global_used: QuotaUsage::default(),
+            strategy_used: BTreeMap::new(),
+            deferred: BTreeMap::new(),
+        }
+    }
+
+    /// Reserves quota for a move of `size` bytes, returns false if a quota is used up
+    pub fn try_reserve(&mut self, strategy: &PlacementStrategy, size: u64) -> bool {
+        self.try_reserve_for(&strategy.name, strategy.quota, size)
+    }
+
+    /// `try_reserve` for a strategy known by name and quota
+    pub fn try_reserve_for(&mut self, strategy: &str, quota: MoveQuota, size: u64) -> bool {
+        let strategy_used = self
+            .strategy_used
+            .get(strategy)
+            .copied()
+            .unwrap_or_default();
+
+        let exhausted = if quota.is_exhausted(strategy_used) {
+            Some(Some(strategy.to_string()))
+        } else if self.global.is_exhausted(self.global_used) {
+            Some(None)
+        } else {
+            None
+        };
+
+        if let Some(scope) = exhausted {
+            self.deferred.entry(scope).or_default().add(size);
+            return false;
+        }
+
+        self.strategy_used
+            .entry(strategy.to_string())
+            .or_default()
+            .add(size);
+        self.global_used.add(size);
+        true
+    }
+
+    /// Reserves only global quota, for a move no strategy asked for
+    pub fn try_reserve_global(&mut self, size: u64) -> bool {
+        if self.global.is_exhausted(self.global_used) {
+            self.deferred.entry(None).or_default().add(size);
+            return false;
+        }
+        self.global_used.add(size);
+        true
+    }
+
+    /// Whether the run quota admits no more moves
+    pub fn is_global_exhausted(&self) -> bool {
+        self.global.is_exhausted(self.global_used)
+    }
+
+    /// Gives back a `try_reserve_global` reservation for a move that was dropped
+    pub fn release_global(&mut self, size: u64) {
+        self.global_used.remove(size);
+    }
+
+    /// One warning per quota that held back moves
+    pub fn into_warnings(self) -> Vec<PlanWarning> {
+        self.deferred
+            .into_iter()
+            .map(|(strategy, deferred)| PlanWarning::QuotaReached {
+                strategy,
+                deferred_files: deferred.moves,
+                deferred_bytes: deferred.bytes,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strategy(name: &str, quota: MoveQuota) -> PlacementStrategy {
+        PlacementStrategy::new(name.to_string(), 10).with_quota(quota)
+    }
+
+    #[test]
+    fn test_strategy_quota_limits_moves() {
+        let limited = strategy("limited", MoveQuota::new(Some(2), None));
+        let mut tracker = QuotaTracker::new(MoveQuota::default());
+
+        assert!(tracker.try_reserve(&limited, 100));
+        assert!(tracker.try_reserve(&limited, 100));
+        assert!(!tracker.try_reserve(&limited, 100));
+        assert!(!tracker.try_reserve(&limited, 50));
+
+        assert_eq!(
+            tracker.into_warnings(),
+            vec![PlanWarning::QuotaReached {
+                strategy: Some("limited".to_string()),
+                deferred_files: 2,
+                deferred_bytes: 150,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_bytes_quota_admits_one_oversized_file() {
+        let limited = strategy("limited", MoveQuota::new(None, Some(100)));
+        let mut tracker = QuotaTracker::new(MoveQuota::default());
+
+        assert!(tracker.try_reserve(&limited, 500));
+        assert!(!tracker.try_reserve(&limited, 1));
+    }
+
+    #[test]
+    fn test_global_quota_spans_strategies() {
+        let a = strategy("a", MoveQuota::default());
+        let b = strategy("b", MoveQuota::default());
+        let mut tracker = QuotaTracker::new(MoveQuota::new(Some(1), None));
+
+        assert!(tracker.try_reserve(&a, 100));
+        assert!(!tracker.try_reserve(&b, 100));
+
+        assert_eq!(
+            tracker.into_warnings(),
+            vec![PlanWarning::QuotaReached {
+                strategy: None,
+                deferred_files: 1,
+                deferred_bytes: 100,
+            }]
+        );
+    }
+}
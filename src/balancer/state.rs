@@ -1,6 +1,9 @@
-use super::{PlacementDecision, PlanWarning};
+use super::ties::TieTracker;
+use super::{MoveQuota, PlacementDecision, PlanWarning};
+use crate::conditions::{ConditionMemo, PlanningView};
 use crate::{FileInfo, Tier};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 
 #[derive(Debug, Clone)]
@@ -10,6 +13,7 @@ pub(super) struct BlockedPlacement {
     pub desired_tier: String,
     pub strategy_name: String,
     pub strategy_priority: u32,
+    pub strategy_quota: MoveQuota,
 }
 
 pub(super) struct PlanningState {
@@ -17,6 +21,16 @@ pub(super) struct PlanningState {
     pub decisions: Vec<PlacementDecision>,
     pub warnings: Vec<PlanWarning>,
     pub blocked_placements: Vec<BlockedPlacement>,
+    pub ties: TieTracker,
+    /// What conditions see of this state
+    pub view: PlanningView,
+    /// Scanned files as (tier, path relative to the tier root), kept only when a
+    /// strategy copies files, to skip copies that already exist
+    pub present: HashSet<(String, PathBuf)>,
+    /// Files and bytes kept back by `Tier::can_demote`, by tier below its `min_usage_percent`
+    pub held_demotions: BTreeMap<String, (usize, u64)>,
+    /// Condition results for the file being placed, and counters for the run
+    pub condition_memo: ConditionMemo,
 }
 
 impl PlanningState {
@@ -29,6 +43,13 @@ impl PlanningState {
             decisions: Vec::new(),
             warnings: Vec::new(),
             blocked_placements: Vec::new(),
+            ties: TieTracker::default(),
+            view: tiers.iter().fold(PlanningView::new(), |view, t| {
+                view.with_tier(t.name.clone(), t.get_free_space(), t.get_total_space())
+            }),
+            present: HashSet::new(),
+            held_demotions: BTreeMap::new(),
+            condition_memo: ConditionMemo::new(),
         }
     }
 
@@ -41,6 +62,16 @@ impl PlanningState {
         if let Some(free) = self.tier_free_space.get_mut(to_tier) {
             *free = free.saturating_sub(file_size);
         }
+
+        self.view.record_move(file_size, from_tier, to_tier);
+    }
+
+    /// Updates simulated state after planning a copy; the source keeps its space
+    pub fn apply_copy(&mut self, file_size: u64, to_tier: &str) {
+        if let Some(free) = self.tier_free_space.get_mut(to_tier) {
+            *free = free.saturating_sub(file_size);
+        }
+        self.view.record_copy(file_size, to_tier);
     }
 
     #[cfg(test)]
@@ -93,6 +124,8 @@ mod tests {
 
         assert_eq!(cache_free_after, cache_free_before + 500);
         assert_eq!(storage_free_after, storage_free_before - 500);
+        assert_eq!(state.view.planned_moves(), 1);
+        assert_eq!(state.view.tier("cache").unwrap().free, cache_free_after);
     }
 
     #[test]
@@ -187,6 +220,7 @@ mod tests {
                 size: 1000,
                 modified: std::time::SystemTime::now(),
                 accessed: std::time::SystemTime::now(),
+                owner: None,
             }),
             current_tier: "cache".to_string(),
             strategy: "test".to_string(),
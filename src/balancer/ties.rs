@@ -0,0 +1,98 @@
+use super::PlanWarning;
+use crate::PlacementStrategy;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+struct Tie {
+    priority: u32,
+    winner: String,
+    files: usize,
+    example: PathBuf,
+}
+
+/// Collects same-priority matches that would send a file to different places
+///
+/// Such ties are settled by strategy name, which rarely reflects what the config
+/// author meant, so they are reported once per set of competing strategies.
+#[derive(Debug, Default)]
+pub(super) struct TieTracker {
+    ties: BTreeMap<Vec<String>, Tie>,
+}
+
+impl TieTracker {
+    /// Records `winner` beating `rivals` for `file`, if any rival disagrees on the destination
+    pub fn record(
+        &mut self,
+        winner: &PlacementStrategy,
+        rivals: &[&PlacementStrategy],
+        file: &Path,
+    ) {
+        let mut strategies: Vec<String> = rivals
+            .iter()
+            .filter(|rival| !rival.same_destination(winner))
+            .map(|rival| rival.name.clone())
+            .collect();
+        if strategies.is_empty() {
+            return;
+        }
+        strategies.push(winner.name.clone());
+        strategies.sort();
+
+        self.ties
+            .entry(strategies)
+            .or_insert_with(|| Tie {
+                priority: winner.priority,
+                winner: winner.name.clone(),
+                files: 0,
+                example: file.to_path_buf(),
+            })
+            .files += 1;
+    }
+
+    /// One warning per set of competing strategies
+    pub fn into_warnings(self) -> Vec<PlanWarning> {
+        self.ties
+            .into_iter()
+            .map(|(strategies, tie)| PlanWarning::AmbiguousStrategy {
+                strategies,
+                priority: tie.priority,
+                winner: tie.winner,
+                files: tie.files,
+                example: tie.example,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strategy(name: &str, tier: &str) -> PlacementStrategy {
+        PlacementStrategy::new(name.to_string(), 10).add_preferred_tier(tier.to_string())
+    }
+
+    #[test]
+    fn test_ties_reported_only_for_different_destinations() {
+        let cache = strategy("recent", "cache");
+        let also_cache = strategy("downloads", "cache");
+        let storage = strategy("archive", "storage");
+        let mut tracker = TieTracker::default();
+
+        tracker.record(&cache, &[&also_cache], Path::new("/a.mkv"));
+        tracker.record(&cache, &[&also_cache, &storage], Path::new("/b.mkv"));
+        tracker.record(&cache, &[&storage], Path::new("/c.mkv"));
+
+        assert_eq!(
+            tracker.into_warnings(),
+            vec![PlanWarning::AmbiguousStrategy {
+                strategies: vec!["archive".to_string(), "recent".to_string()],
+                priority: 10,
+                winner: "recent".to_string(),
+                files: 2,
+                example: PathBuf::from("/b.mkv"),
+            }]
+        );
+    }
+}
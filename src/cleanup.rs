@@ -0,0 +1,167 @@
+//! Removal of `.partial` copies and `.backup-<timestamp>` files left behind by failed runs
+//!
+//! Both live next to tier files under the scan roots. Partial copies of moves an
+//! interrupted run still lists as pending are kept, since `resume` may retry them.
+
+use crate::Tier;
+use crate::file::backup_timestamp;
+use std::collections::HashSet;
+use std::fs;
+use std::hash::BuildHasher;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtifactKind {
+    /// Unfinished copy of a move
+    Partial,
+    /// Destination file set aside by `on_conflict: backup`
+    Backup,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Artifact {
+    pub path: PathBuf,
+    pub kind: ArtifactKind,
+    pub size: u64,
+    /// When the artifact was left behind: the backup timestamp, or the partial's mtime
+    pub created: SystemTime,
+}
+
+impl Artifact {
+    fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        let metadata = fs::symlink_metadata(path).ok()?;
+        let (kind, created) = if name.ends_with(".partial") {
+            (ArtifactKind::Partial, metadata.modified().ok()?)
+        } else {
+            let timestamp = backup_timestamp(name)?;
+            (
+                ArtifactKind::Backup,
+                UNIX_EPOCH + Duration::from_secs(timestamp),
+            )
+        };
+
+        Some(Self {
+            path: path.to_path_buf(),
+            kind,
+            size: metadata.len(),
+            created,
+        })
+    }
+}
+
+/// Outcome of a cleanup pass
+#[derive(Debug, Default)]
+pub struct CleanupReport {
+    /// Removed artifacts, or the ones a dry run would remove
+    pub removed: Vec<Artifact>,
+    /// Artifacts younger than the retention
+    pub kept_recent: usize,
+    /// Partial copies of moves an interrupted run may still resume
+    pub kept_in_use: usize,
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+impl CleanupReport {
+    pub fn bytes_removed(&self) -> u64 {
+        self.removed.iter().map(|artifact| artifact.size).sum()
+    }
+}
+
+/// Artifacts under the scan roots of `tier`, outside its trash
+pub fn find_artifacts(tier: &Tier) -> Vec<Artifact> {
+    let trash_roots = tier.trash_roots();
+    tier.walk_scan_roots()
+        .filter(|e| !trash_roots.iter().any(|trash| e.path().starts_with(trash)))
+        .filter_map(|e| Artifact::from_path(e.path()))
+        .collect()
+}
+
+/// Removes artifacts older than `retention`, except the paths in `in_use`
+pub fn clean_artifacts<S: BuildHasher>(
+    tiers: &[Tier],
+    in_use: &HashSet<PathBuf, S>,
+    retention: Duration,
+    now: SystemTime,
+    dry_run: bool,
+) -> CleanupReport {
+    let mut report = CleanupReport::default();
+    for artifact in tiers.iter().flat_map(find_artifacts) {
+        if in_use.contains(&artifact.path) {
+            report.kept_in_use += 1;
+            continue;
+        }
+        let expired = now
+            .duration_since(artifact.created)
+            .is_ok_and(|age| age >= retention);
+        if !expired {
+            report.kept_recent += 1;
+            continue;
+        }
+
+        if dry_run {
+            tracing::info!("Would remove {}", artifact.path.display());
+        } else if let Err(e) = fs::remove_file(&artifact.path) {
+            tracing::warn!("Failed to remove {}: {e}", artifact.path.display());
+            report.failed.push((artifact.path, e.to_string()));
+            continue;
+        } else {
+            tracing::info!("Removed {}", artifact.path.display());
+        }
+        report.removed.push(artifact);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const DAY: Duration = Duration::from_secs(24 * 3600);
+
+    #[test]
+    fn test_clean_artifacts() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("storage");
+        fs::create_dir_all(root.join("Show/.trash")).unwrap();
+        let tier = Tier::new("storage".to_string(), root.clone(), 1, None, None)
+            .unwrap()
+            .with_trash_dir("Show/.trash");
+
+        let now = SystemTime::now() + 30 * DAY;
+        let now_secs = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let old_backup = root.join(format!("Show/e1.mkv.backup-{}", now_secs - 10 * 86400));
+        let new_backup = root.join(format!("Show/e2.mkv.backup-{}", now_secs - 3600));
+        let orphan = root.join("Show/e3.mkv.partial");
+        let resumable = root.join("Show/e4.mkv.partial");
+        let trashed = root.join("Show/.trash/e5.mkv.partial");
+        let regular = root.join("Show/e6.mkv");
+        for path in [
+            &old_backup,
+            &new_backup,
+            &orphan,
+            &resumable,
+            &trashed,
+            &regular,
+        ] {
+            fs::write(path, b"data").unwrap();
+        }
+        let in_use = HashSet::from([resumable.clone()]);
+
+        let dry = clean_artifacts(&[tier.clone()], &in_use, 7 * DAY, now, true);
+        assert_eq!(dry.removed.len(), 2);
+        assert!(orphan.exists());
+
+        let report = clean_artifacts(&[tier], &in_use, 7 * DAY, now, false);
+        let mut removed: Vec<_> = report.removed.iter().map(|a| a.path.clone()).collect();
+        removed.sort();
+        assert_eq!(removed, vec![old_backup.clone(), orphan.clone()]);
+        assert_eq!((report.kept_recent, report.kept_in_use), (1, 1));
+        assert_eq!(report.bytes_removed(), 8);
+        assert!(!old_backup.exists() && !orphan.exists());
+        assert!(new_backup.exists() && resumable.exists() && trashed.exists());
+        assert!(regular.exists());
+    }
+}
@@ -1,3 +1,5 @@
+use crate::config::{ByteSize, ConfigFormat};
+use crate::report::MoveSort;
 use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
@@ -34,6 +36,10 @@ pub enum OutputFormat {
     Json,
     /// YAML output for machine parsing
     Yaml,
+    /// One comma-separated row per decision, for spreadsheets
+    Csv,
+    /// One tab-separated row per decision
+    Tsv,
 }
 
 #[derive(Parser)]
@@ -43,6 +49,10 @@ pub enum OutputFormat {
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Config file format, detected from the file extension by default
+    #[arg(long, global = true, value_enum, value_name = "FORMAT")]
+    pub config_format: Option<ConfigFormat>,
 }
 
 #[derive(Subcommand)]
@@ -57,6 +67,54 @@ pub enum Commands {
         #[arg(short = 'n', long)]
         dry_run: bool,
 
+        /// Plan and execute up to N times, rescanning in between, until no moves are left
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+        iterations: Option<u32>,
+
+        /// Seed recorded in the plan output, overriding `seed` from the config
+        #[arg(long, value_name = "SEED")]
+        seed: Option<u64>,
+
+        /// Read watch history from a saved Tautulli `get_history` response instead of the API
+        #[arg(long, value_name = "FILE")]
+        tautulli_fixture: Option<PathBuf>,
+
+        /// Stop starting moves once this much has been moved (e.g. 500GB); the rest is deferred
+        #[arg(long, value_name = "SIZE")]
+        max_bytes: Option<ByteSize>,
+
+        /// Stop starting moves after this many minutes; the rest is deferred
+        #[arg(long, value_name = "MINUTES")]
+        max_duration: Option<u64>,
+
+        /// Plan even if a tier exceeds its `max_depth` / `max_files` guardrails
+        #[arg(long)]
+        force: bool,
+
+        /// Execute a plan even if it exceeds `safety.max_plan_bytes` / `max_plan_percent_of_tier`
+        #[arg(long)]
+        yes_i_mean_it: bool,
+
+        /// Approve or skip the planned moves group by group before they run
+        #[arg(short, long)]
+        interactive: bool,
+
+        /// List every move in the text plan instead of one line per directory
+        #[arg(long)]
+        expand: bool,
+
+        /// Order of the moves listed by --expand (default: execution order)
+        #[arg(long, value_enum, value_name = "ORDER")]
+        sort: Option<MoveSort>,
+
+        /// Directories or moves listed in the text plan, 0 for all
+        #[arg(long, value_name = "N", default_value = "10")]
+        limit: usize,
+
+        /// Plain text output without colors (also set by the `NO_COLOR` environment variable)
+        #[arg(long)]
+        no_color: bool,
+
         /// Increase logging verbosity (-v, -vv, -vvv for info, debug, trace)
         #[arg(short, long, action = clap::ArgAction::Count)]
         verbose: u8,
@@ -96,6 +154,249 @@ pub enum Commands {
         #[arg(long, value_enum, default_value = "text")]
         format: OutputFormat,
     },
+
+    /// Continue a plan whose execution was interrupted
+    Resume {
+        /// Path to configuration file
+        #[arg(short, long, value_name = "FILE", default_value_os_t = default_config_path())]
+        config: PathBuf,
+
+        /// Dry-run mode: show remaining moves without executing them
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+
+        /// Drop the interrupted plan instead of resuming it
+        #[arg(long)]
+        discard: bool,
+
+        /// Increase logging verbosity (-v, -vv, -vvv for info, debug, trace)
+        #[arg(short, long, action = clap::ArgAction::Count)]
+        verbose: u8,
+
+        /// Suppress all non-error output to stderr
+        #[arg(short, long)]
+        quiet: bool,
+
+        /// Output format for results
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Restore the files a run moved, from the trash (requires `safety.trash_dir`)
+    Undo {
+        /// Path to configuration file
+        #[arg(short, long, value_name = "FILE", default_value_os_t = default_config_path())]
+        config: PathBuf,
+
+        /// Run to undo, as printed after the run (`undo_run_id` in JSON output)
+        run_id: String,
+
+        /// Increase logging verbosity (-v, -vv, -vvv for info, debug, trace)
+        #[arg(short, long, action = clap::ArgAction::Count)]
+        verbose: u8,
+
+        /// Suppress all non-error output to stderr
+        #[arg(short, long)]
+        quiet: bool,
+    },
+
+    /// Delete trashed files older than `safety.retention_hours`
+    PurgeTrash {
+        /// Path to configuration file
+        #[arg(short, long, value_name = "FILE", default_value_os_t = default_config_path())]
+        config: PathBuf,
+
+        /// Increase logging verbosity (-v, -vv, -vvv for info, debug, trace)
+        #[arg(short, long, action = clap::ArgAction::Count)]
+        verbose: u8,
+
+        /// Suppress all non-error output to stderr
+        #[arg(short, long)]
+        quiet: bool,
+    },
+
+    /// Remove `.partial` and `.backup-*` files left behind by failed runs
+    Cleanup {
+        /// Path to configuration file
+        #[arg(short, long, value_name = "FILE", default_value_os_t = default_config_path())]
+        config: PathBuf,
+
+        /// List what would be removed without removing it
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+
+        /// Increase logging verbosity (-v, -vv, -vvv for info, debug, trace)
+        #[arg(short, long, action = clap::ArgAction::Count)]
+        verbose: u8,
+
+        /// Suppress all non-error output to stderr
+        #[arg(short, long)]
+        quiet: bool,
+    },
+
+    /// Fetch the real files of stubs left by a `leave_stubs` tier back in their place
+    Recall {
+        /// Stub files to replace with the real files
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+
+        /// Path to configuration file, whose tiers are locked during the recall
+        #[arg(short, long, value_name = "FILE", default_value_os_t = default_config_path())]
+        config: PathBuf,
+
+        /// Increase logging verbosity (-v, -vv, -vvv for info, debug, trace)
+        #[arg(short, long, action = clap::ArgAction::Count)]
+        verbose: u8,
+
+        /// Suppress all non-error output to stderr
+        #[arg(short, long)]
+        quiet: bool,
+    },
+
+    /// Reread moved files and compare them with the checksums recorded when they were moved
+    Scrub {
+        /// Path to configuration file
+        #[arg(short, long, value_name = "FILE", default_value_os_t = default_config_path())]
+        config: PathBuf,
+
+        /// Only scrub files currently on this tier
+        #[arg(long, value_name = "NAME")]
+        tier: Option<String>,
+
+        /// Reread a random sample of this percentage of the files
+        #[arg(long, value_name = "N", default_value_t = 100, value_parser = clap::value_parser!(u8).range(1..=100))]
+        sample_percent: u8,
+
+        /// Output format for results
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+
+        /// Increase logging verbosity (-v, -vv, -vvv for info, debug, trace)
+        #[arg(short, long, action = clap::ArgAction::Count)]
+        verbose: u8,
+
+        /// Suppress all non-error output to stderr
+        #[arg(short, long)]
+        quiet: bool,
+    },
+
+    /// Export every scanned file with its planned placement as JSON, without moving anything
+    ExportInventory {
+        /// Path to configuration file
+        #[arg(short, long, value_name = "FILE", default_value_os_t = default_config_path())]
+        config: PathBuf,
+
+        /// File to write the inventory to (stdout if omitted)
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Increase logging verbosity (-v, -vv, -vvv for info, debug, trace)
+        #[arg(short, long, action = clap::ArgAction::Count)]
+        verbose: u8,
+
+        /// Suppress all non-error output to stderr
+        #[arg(short, long)]
+        quiet: bool,
+    },
+
+    /// Show why the planner makes its decision for one file, without moving anything
+    Explain {
+        /// File to explain, as found on a tier
+        path: PathBuf,
+
+        /// Path to configuration file
+        #[arg(short, long, value_name = "FILE", default_value_os_t = default_config_path())]
+        config: PathBuf,
+
+        /// Output format for results
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+
+        /// Increase logging verbosity (-v, -vv, -vvv for info, debug, trace)
+        #[arg(short, long, action = clap::ArgAction::Count)]
+        verbose: u8,
+
+        /// Suppress all non-error output to stderr
+        #[arg(short, long)]
+        quiet: bool,
+    },
+
+    /// Validate a configuration and report likely mistakes
+    Validate {
+        /// Path to configuration file
+        #[arg(short, long, value_name = "FILE", default_value_os_t = default_config_path())]
+        config: PathBuf,
+
+        /// Output format for results
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Inspect or recover the lock for a configuration's tier set
+    Lock {
+        #[command(subcommand)]
+        action: LockAction,
+    },
+
+    /// Create a starter config or print the config JSON Schema
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Write an annotated starter config
+    Init {
+        /// Where to write the config
+        #[arg(short, long, value_name = "FILE", default_value_os_t = default_config_path())]
+        config: PathBuf,
+
+        /// Overwrite an existing file
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Print the JSON Schema of the config file, for editor validation
+    Schema {
+        /// Write the schema to a file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum LockAction {
+    /// Show who holds the lock for the configured tiers
+    Status {
+        /// Path to configuration file
+        #[arg(short, long, value_name = "FILE", default_value_os_t = default_config_path())]
+        config: PathBuf,
+
+        /// Output format for results
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Remove a stuck lock after checking its owner is gone
+    Break {
+        /// Path to configuration file
+        #[arg(short, long, value_name = "FILE", default_value_os_t = default_config_path())]
+        config: PathBuf,
+
+        /// PID recorded in the lock (guards against breaking the wrong lock)
+        #[arg(long)]
+        pid: u32,
+
+        /// Allow breaking locks whose owner cannot be checked (other hosts, unreadable lock)
+        #[arg(long)]
+        force: bool,
+
+        /// Minimum lock age before a forced break (in hours)
+        #[arg(long, value_name = "HOURS", default_value = "6")]
+        min_age_hours: u64,
+    },
 }
 
 #[cfg(test)]
@@ -159,6 +460,65 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_rebalance_iterations() {
+        let cli = Cli::parse_from(vec!["tierflow", "rebalance", "--iterations", "5"]);
+        match cli.command {
+            Commands::Rebalance { iterations, .. } => assert_eq!(iterations, Some(5)),
+            _ => panic!("Expected Rebalance command"),
+        }
+        assert!(Cli::try_parse_from(vec!["tierflow", "rebalance", "--iterations", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_rebalance_budget() {
+        let cli = Cli::parse_from(vec![
+            "tierflow",
+            "rebalance",
+            "--max-bytes",
+            "1.5GB",
+            "--max-duration",
+            "90",
+        ]);
+        match cli.command {
+            Commands::Rebalance {
+                max_bytes,
+                max_duration,
+                ..
+            } => {
+                assert_eq!(max_bytes, Some(ByteSize(1_500_000_000)));
+                assert_eq!(max_duration, Some(90));
+            }
+            _ => panic!("Expected Rebalance command"),
+        }
+        assert!(Cli::try_parse_from(vec!["tierflow", "rebalance", "--max-bytes", "lots"]).is_err());
+    }
+
+    #[test]
+    fn test_rebalance_seed() {
+        let cli = Cli::parse_from(vec!["tierflow", "rebalance", "--seed", "42"]);
+        match cli.command {
+            Commands::Rebalance { seed, .. } => assert_eq!(seed, Some(42)),
+            _ => panic!("Expected Rebalance command"),
+        }
+    }
+
+    #[test]
+    fn test_rebalance_tautulli_fixture() {
+        let cli = Cli::parse_from(vec![
+            "tierflow",
+            "rebalance",
+            "--tautulli-fixture",
+            "history.json",
+        ]);
+        match cli.command {
+            Commands::Rebalance {
+                tautulli_fixture, ..
+            } => assert_eq!(tautulli_fixture, Some(PathBuf::from("history.json"))),
+            _ => panic!("Expected Rebalance command"),
+        }
+    }
+
     #[test]
     fn test_rebalance_short_flags() {
         let cli = Cli::parse_from(vec!["tierflow", "rebalance", "-c", "custom.yaml", "-n"]);
@@ -209,6 +569,214 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_export_inventory_output() {
+        let cli = Cli::parse_from(vec![
+            "tierflow",
+            "export-inventory",
+            "--output",
+            "inventory.json",
+        ]);
+        match cli.command {
+            Commands::ExportInventory { output, .. } => {
+                assert_eq!(output, Some(PathBuf::from("inventory.json")));
+            }
+            _ => panic!("Expected ExportInventory command"),
+        }
+    }
+
+    #[test]
+    fn test_undo_command() {
+        let cli = Cli::parse_from(vec!["tierflow", "undo", "1700000000", "-c", "cfg.yaml"]);
+        match cli.command {
+            Commands::Undo { config, run_id, .. } => {
+                assert_eq!(config, PathBuf::from("cfg.yaml"));
+                assert_eq!(run_id, "1700000000");
+            }
+            _ => panic!("Expected Undo command"),
+        }
+    }
+
+    #[test]
+    fn test_config_format_flag() {
+        let cli = Cli::parse_from(vec!["tierflow", "rebalance", "-c", "cfg.toml"]);
+        assert_eq!(cli.config_format, None);
+
+        let cli = Cli::parse_from(vec![
+            "tierflow",
+            "rebalance",
+            "-c",
+            "cfg.conf",
+            "--config-format",
+            "json",
+        ]);
+        assert_eq!(cli.config_format, Some(ConfigFormat::Json));
+    }
+
+    #[test]
+    fn test_config_init_command() {
+        let cli = Cli::parse_from(vec![
+            "tierflow", "config", "init", "-c", "new.yaml", "--force",
+        ]);
+        match cli.command {
+            Commands::Config {
+                action: ConfigAction::Init { config, force },
+            } => {
+                assert_eq!(config, PathBuf::from("new.yaml"));
+                assert!(force);
+            }
+            _ => panic!("Expected Config Init command"),
+        }
+    }
+
+    #[test]
+    fn test_explain_command() {
+        let cli = Cli::parse_from(vec![
+            "tierflow",
+            "explain",
+            "/mnt/cache/Show/e1.mkv",
+            "-c",
+            "cfg.yaml",
+            "--format",
+            "json",
+        ]);
+        match cli.command {
+            Commands::Explain {
+                path,
+                config,
+                format,
+                ..
+            } => {
+                assert_eq!(path, PathBuf::from("/mnt/cache/Show/e1.mkv"));
+                assert_eq!(config, PathBuf::from("cfg.yaml"));
+                assert!(matches!(format, OutputFormat::Json));
+            }
+            _ => panic!("Expected Explain command"),
+        }
+    }
+
+    #[test]
+    fn test_validate_command() {
+        let cli = Cli::parse_from(vec![
+            "tierflow", "validate", "--config", "cfg.yaml", "--format", "json",
+        ]);
+        match cli.command {
+            Commands::Validate { config, format } => {
+                assert_eq!(config, PathBuf::from("cfg.yaml"));
+                assert!(matches!(format, OutputFormat::Json));
+            }
+            _ => panic!("Expected Validate command"),
+        }
+    }
+
+    #[test]
+    fn test_resume_flags() {
+        let cli = Cli::parse_from(vec!["tierflow", "resume", "--discard", "-n"]);
+        match cli.command {
+            Commands::Resume {
+                dry_run, discard, ..
+            } => {
+                assert!(dry_run);
+                assert!(discard);
+            }
+            _ => panic!("Expected Resume command"),
+        }
+    }
+
+    #[test]
+    fn test_cleanup_command() {
+        let cli = Cli::parse_from(vec!["tierflow", "cleanup", "-n", "-c", "/tmp/config.yaml"]);
+        match cli.command {
+            Commands::Cleanup {
+                config, dry_run, ..
+            } => {
+                assert!(dry_run);
+                assert_eq!(config, PathBuf::from("/tmp/config.yaml"));
+            }
+            _ => panic!("Expected Cleanup command"),
+        }
+    }
+
+    #[test]
+    fn test_recall_command() {
+        let cli = Cli::parse_from(vec![
+            "tierflow",
+            "recall",
+            "/mnt/ssd/a.mkv",
+            "-c",
+            "/tmp/config.yaml",
+        ]);
+        match cli.command {
+            Commands::Recall { paths, config, .. } => {
+                assert_eq!(paths, vec![PathBuf::from("/mnt/ssd/a.mkv")]);
+                assert_eq!(config, PathBuf::from("/tmp/config.yaml"));
+            }
+            _ => panic!("Expected Recall command"),
+        }
+    }
+
+    #[test]
+    fn test_scrub_command() {
+        let cli = Cli::parse_from(vec![
+            "tierflow",
+            "scrub",
+            "--tier",
+            "storage",
+            "--sample-percent",
+            "10",
+        ]);
+        match cli.command {
+            Commands::Scrub {
+                tier,
+                sample_percent,
+                ..
+            } => {
+                assert_eq!(tier.as_deref(), Some("storage"));
+                assert_eq!(sample_percent, 10);
+            }
+            _ => panic!("Expected Scrub command"),
+        }
+        assert!(Cli::try_parse_from(vec!["tierflow", "scrub", "--sample-percent", "0"]).is_err());
+        assert!(Cli::try_parse_from(vec!["tierflow", "scrub", "--sample-percent", "101"]).is_err());
+    }
+
+    #[test]
+    fn test_lock_break_flags() {
+        let cli = Cli::parse_from(vec![
+            "tierflow",
+            "lock",
+            "break",
+            "-c",
+            "custom.yaml",
+            "--pid",
+            "42",
+            "--force",
+        ]);
+        match cli.command {
+            Commands::Lock {
+                action:
+                    LockAction::Break {
+                        config,
+                        pid,
+                        force,
+                        min_age_hours,
+                    },
+            } => {
+                assert_eq!(config, PathBuf::from("custom.yaml"));
+                assert_eq!(pid, 42);
+                assert!(force);
+                assert_eq!(min_age_hours, 6);
+            }
+            _ => panic!("Expected Lock Break command"),
+        }
+    }
+
+    #[test]
+    fn test_lock_break_requires_pid() {
+        let result = Cli::try_parse_from(vec!["tierflow", "lock", "break"]);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_daemon_all_flags() {
         let cli = Cli::parse_from(vec![
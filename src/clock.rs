@@ -0,0 +1,34 @@
+//! Source of the current time for planning
+
+use std::time::SystemTime;
+
+/// Time used for schedules and age-based conditions
+pub trait Clock: Send + Sync {
+    fn now(&self) -> SystemTime;
+}
+
+/// The system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// A clock stopped at one instant, for reproducible plans
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(SystemTime);
+
+impl FixedClock {
+    pub const fn new(now: SystemTime) -> Self {
+        Self(now)
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        self.0
+    }
+}
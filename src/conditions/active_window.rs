@@ -38,6 +38,10 @@ impl Condition for ActiveWindowCondition {
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn reads_planning(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
@@ -55,17 +59,16 @@ mod tests {
             size: 1024,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         }
     }
 
     fn create_progress(show: &str, season: u32, episode: u32) -> ShowProgress {
-        let global = (season - 1) * 100 + episode;
         ShowProgress {
             user: "alice".to_string(),
             show_name: show.to_string(),
             last_watched_season: season,
             last_watched_episode: episode,
-            last_watched_global: global,
             last_watched_time: 1234567890,
         }
     }
@@ -106,7 +109,9 @@ mod tests {
     fn test_active_window_does_not_match_outside_window() {
         let files = [
             create_file_info("/mnt/cache/Breaking.Bad.S01E01.mkv"),
+            create_file_info("/mnt/cache/Breaking.Bad.S01E05.mkv"),
             create_file_info("/mnt/cache/Breaking.Bad.S01E06.mkv"),
+            create_file_info("/mnt/cache/Breaking.Bad.S01E07.mkv"),
             create_file_info("/mnt/cache/Breaking.Bad.S01E10.mkv"),
         ];
 
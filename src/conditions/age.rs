@@ -1,6 +1,6 @@
 use super::{Condition, Context};
 use crate::FileInfo;
-use std::time::{Duration, SystemTime};
+use std::time::Duration;
 
 /// Condition that checks file age
 ///
@@ -21,8 +21,8 @@ impl AgeCondition {
 }
 
 impl Condition for AgeCondition {
-    fn matches(&self, file: &FileInfo, _context: &Context) -> bool {
-        let now = SystemTime::now();
+    fn matches(&self, file: &FileInfo, context: &Context) -> bool {
+        let now = context.now();
         if let Ok(file_age) = now.duration_since(file.modified) {
             let mut matches = true;
 
@@ -48,12 +48,17 @@ impl Condition for AgeCondition {
     fn name(&self) -> &'static str {
         "age"
     }
+
+    fn reads_planning(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::path::PathBuf;
+    use std::time::SystemTime;
 
     fn create_test_file(hours_ago: u64) -> FileInfo {
         let modified = SystemTime::now() - Duration::from_secs(hours_ago * 3600);
@@ -62,6 +67,7 @@ mod tests {
             size: 1000,
             modified,
             accessed: SystemTime::now(),
+            owner: None,
         }
     }
 
@@ -138,6 +144,7 @@ mod tests {
             size: 1000,
             modified: SystemTime::now() + Duration::from_secs(3600),
             accessed: SystemTime::now(),
+            owner: None,
         };
 
         assert!(!condition.matches(&file, &context));
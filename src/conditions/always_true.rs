@@ -12,6 +12,10 @@ impl Condition for AlwaysTrueCondition {
     fn name(&self) -> &'static str {
         "always_true"
     }
+
+    fn reads_planning(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
@@ -26,6 +30,7 @@ mod tests {
             size: 1000,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         }
     }
 
@@ -55,6 +60,7 @@ mod tests {
                 size,
                 modified: SystemTime::now(),
                 accessed: SystemTime::now(),
+                owner: None,
             };
             assert!(condition.matches(&file, &context));
         }
@@ -0,0 +1,193 @@
+use super::{Condition, Context};
+use crate::{FileInfo, FileStats};
+use std::path::PathBuf;
+use std::time::Duration;
+
+const GB: u64 = 1024 * 1024 * 1024;
+
+/// Aggregates of the directory containing `file`, collected in Pass 1
+///
+/// Directories are per tier: a show split across two tiers is two directories.
+fn directory_stats<'a>(file: &FileInfo, context: &'a Context) -> Option<(&'a FileStats, PathBuf)> {
+    let stats = &context.global_stats.as_ref()?.file_stats;
+    let dir = file.path.parent()?.to_path_buf();
+    Some((stats, dir))
+}
+
+fn in_range<T: PartialOrd + Copy>(value: T, min: Option<T>, max: Option<T>) -> bool {
+    min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max)
+}
+
+/// Condition that matches files whose directory holds a total size in range
+pub struct DirectorySizeCondition {
+    min_gb: Option<u64>,
+    max_gb: Option<u64>,
+}
+
+impl DirectorySizeCondition {
+    pub const fn new(min_gb: Option<u64>, max_gb: Option<u64>) -> Self {
+        Self { min_gb, max_gb }
+    }
+}
+
+impl Condition for DirectorySizeCondition {
+    fn matches(&self, file: &FileInfo, context: &Context) -> bool {
+        directory_stats(file, context).is_some_and(|(stats, dir)| {
+            in_range(
+                stats.get_directory_size(&dir),
+                self.min_gb.map(|gb| gb * GB),
+                self.max_gb.map(|gb| gb * GB),
+            )
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "directory_size"
+    }
+
+    fn reads_planning(&self) -> bool {
+        false
+    }
+}
+
+/// Condition that matches files whose directory holds a number of files in range
+pub struct DirectoryFileCountCondition {
+    min: Option<usize>,
+    max: Option<usize>,
+}
+
+impl DirectoryFileCountCondition {
+    pub const fn new(min: Option<usize>, max: Option<usize>) -> Self {
+        Self { min, max }
+    }
+}
+
+impl Condition for DirectoryFileCountCondition {
+    fn matches(&self, file: &FileInfo, context: &Context) -> bool {
+        directory_stats(file, context).is_some_and(|(stats, dir)| {
+            in_range(stats.get_directory_file_count(&dir), self.min, self.max)
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "directory_file_count"
+    }
+
+    fn reads_planning(&self) -> bool {
+        false
+    }
+}
+
+/// Condition on the age of the newest file in the file's directory
+///
+/// With `max_hours` a whole folder matches while any of its files is fresh;
+/// with `min_hours` it matches once every file in it has gone stale.
+pub struct NewestInDirectoryAgeCondition {
+    min_hours: Option<u64>,
+    max_hours: Option<u64>,
+}
+
+impl NewestInDirectoryAgeCondition {
+    pub const fn new(min_hours: Option<u64>, max_hours: Option<u64>) -> Self {
+        Self {
+            min_hours,
+            max_hours,
+        }
+    }
+}
+
+impl Condition for NewestInDirectoryAgeCondition {
+    fn matches(&self, file: &FileInfo, context: &Context) -> bool {
+        let Some(newest) = directory_stats(file, context)
+            .and_then(|(stats, dir)| stats.get_newest_in_directory(&dir))
+        else {
+            return false;
+        };
+        // A file from the future is as fresh as it gets
+        let age = context.now().duration_since(newest).unwrap_or_default();
+        in_range(
+            age,
+            self.min_hours.map(|h| Duration::from_secs(h * 3600)),
+            self.max_hours.map(|h| Duration::from_secs(h * 3600)),
+        )
+    }
+
+    fn name(&self) -> &'static str {
+        "newest_in_directory_age"
+    }
+
+    fn reads_planning(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GlobalStats;
+    use std::sync::Arc;
+    use std::time::SystemTime;
+
+    fn file(path: &str, size: u64, hours_ago: u64) -> FileInfo {
+        FileInfo {
+            path: PathBuf::from(path),
+            size,
+            modified: SystemTime::now() - Duration::from_secs(hours_ago * 3600),
+            accessed: SystemTime::now(),
+            owner: None,
+        }
+    }
+
+    fn context(files: &[FileInfo]) -> Context {
+        let stats = GlobalStats::new(FileStats::collect(files));
+        Context::new().with_global_stats(&Arc::new(stats))
+    }
+
+    #[test]
+    fn test_directory_size_and_file_count() {
+        let files = [
+            file("/mnt/cache/Big/e1.mkv", 3 * GB, 1),
+            file("/mnt/cache/Big/e2.mkv", 3 * GB, 1),
+            file("/mnt/cache/Small/e1.mkv", GB / 2, 1),
+        ];
+        let context = context(&files);
+
+        let big = DirectorySizeCondition::new(Some(5), None);
+        assert!(big.matches(&files[0], &context));
+        assert!(!big.matches(&files[2], &context));
+        assert!(DirectorySizeCondition::new(None, Some(1)).matches(&files[2], &context));
+
+        let single = DirectoryFileCountCondition::new(None, Some(1));
+        assert!(!single.matches(&files[1], &context));
+        assert!(single.matches(&files[2], &context));
+        assert!(DirectoryFileCountCondition::new(Some(2), Some(2)).matches(&files[0], &context));
+    }
+
+    #[test]
+    fn test_newest_in_directory_age_keeps_folder_with_fresh_file() {
+        let files = [
+            file("/mnt/cache/Active/e1.mkv", 100, 24 * 60),
+            file("/mnt/cache/Active/e9.mkv", 100, 2),
+            file("/mnt/cache/Stale/e1.mkv", 100, 24 * 60),
+        ];
+        let context = context(&files);
+
+        let fresh = NewestInDirectoryAgeCondition::new(None, Some(24 * 7));
+        assert!(fresh.matches(&files[0], &context));
+        assert!(!fresh.matches(&files[2], &context));
+
+        let stale = NewestInDirectoryAgeCondition::new(Some(24 * 30), None);
+        assert!(!stale.matches(&files[0], &context));
+        assert!(stale.matches(&files[2], &context));
+    }
+
+    #[test]
+    fn test_directory_conditions_without_stats() {
+        let file = file("/mnt/cache/Show/e1.mkv", 100, 1);
+        let context = Context::new();
+
+        assert!(!DirectorySizeCondition::new(None, None).matches(&file, &context));
+        assert!(!DirectoryFileCountCondition::new(None, None).matches(&file, &context));
+        assert!(!NewestInDirectoryAgeCondition::new(None, None).matches(&file, &context));
+    }
+}
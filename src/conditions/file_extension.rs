@@ -52,6 +52,10 @@ impl Condition for FileExtensionCondition {
     fn name(&self) -> &'static str {
         "file_extension"
     }
+
+    fn reads_planning(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
@@ -66,6 +70,7 @@ mod tests {
             size: 1000,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         }
     }
 
@@ -3,23 +3,36 @@ use crate::FileInfo;
 
 /// Condition that checks file size
 ///
-/// Matches files within specified size range.
-/// Sizes are specified in megabytes for convenience.
+/// Matches files within specified size range (inclusive).
+/// `new` takes megabytes for convenience, `from_bytes` exact byte counts.
 pub struct FileSizeCondition {
-    min_size_mb: Option<u64>,
-    max_size_mb: Option<u64>,
+    min_bytes: Option<u64>,
+    max_bytes: Option<u64>,
 }
 
 impl FileSizeCondition {
     pub const fn new(min_size_mb: Option<u64>, max_size_mb: Option<u64>) -> Self {
         Self {
-            min_size_mb,
-            max_size_mb,
+            min_bytes: match min_size_mb {
+                Some(mb) => Some(Self::mb_to_bytes(mb)),
+                None => None,
+            },
+            max_bytes: match max_size_mb {
+                Some(mb) => Some(Self::mb_to_bytes(mb)),
+                None => None,
+            },
         }
     }
 
-    const fn mb_to_bytes(mb: u64) -> u64 {
-        mb * 1024 * 1024
+    pub const fn from_bytes(min_bytes: Option<u64>, max_bytes: Option<u64>) -> Self {
+        Self {
+            min_bytes,
+            max_bytes,
+        }
+    }
+
+    pub const fn mb_to_bytes(mb: u64) -> u64 {
+        mb.saturating_mul(1024 * 1024)
     }
 }
 
@@ -27,12 +40,12 @@ impl Condition for FileSizeCondition {
     fn matches(&self, file: &FileInfo, _context: &Context) -> bool {
         let mut matches = true;
 
-        if let Some(min_mb) = self.min_size_mb {
-            matches = matches && file.size >= Self::mb_to_bytes(min_mb);
+        if let Some(min_bytes) = self.min_bytes {
+            matches = matches && file.size >= min_bytes;
         }
 
-        if let Some(max_mb) = self.max_size_mb {
-            matches = matches && file.size <= Self::mb_to_bytes(max_mb);
+        if let Some(max_bytes) = self.max_bytes {
+            matches = matches && file.size <= max_bytes;
         }
 
         matches
@@ -41,6 +54,10 @@ impl Condition for FileSizeCondition {
     fn name(&self) -> &'static str {
         "file_size"
     }
+
+    fn reads_planning(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
@@ -55,6 +72,7 @@ mod tests {
             size: size_mb * 1024 * 1024,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         }
     }
 
@@ -151,6 +169,7 @@ mod tests {
             size: 1024, // 1 KB
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         };
 
         assert!(condition.matches(&small_file, &context));
@@ -166,6 +185,7 @@ mod tests {
             size: 1024 * 1024, // Ровно 1 MB
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         };
 
         assert!(condition.matches(&file, &context));
@@ -175,6 +195,7 @@ mod tests {
             size: 1024 * 1024 - 1, // На 1 байт меньше
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         };
 
         assert!(!condition.matches(&file_smaller, &context));
@@ -197,6 +218,7 @@ mod tests {
             size: 15 * 1024 * 1024 * 1024, // 15 GB
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         };
 
         assert!(condition.matches(&large_file, &context));
@@ -79,6 +79,10 @@ impl Condition for FilenameContainsCondition {
     fn name(&self) -> &'static str {
         "filename_contains"
     }
+
+    fn reads_planning(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
@@ -93,6 +97,7 @@ mod tests {
             size: 1000,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         }
     }
 
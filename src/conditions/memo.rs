@@ -0,0 +1,130 @@
+use super::{Condition, Context};
+use crate::FileInfo;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Work done by one condition type while planning
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConditionCounter {
+    /// Times the condition actually ran
+    pub evaluations: u64,
+    /// Times a result from another strategy was reused instead
+    pub reused: u64,
+    /// Time spent running it
+    pub time: Duration,
+}
+
+/// Results of the conditions already evaluated for the file being placed
+///
+/// Strategies often repeat a condition, e.g. through a shared template.
+/// Conditions built from the same config share a key and run once per file;
+/// the planning state conditions see doesn't change while one file is placed,
+/// so the result holds for every strategy. Call `start_file` before each file.
+#[derive(Debug, Default)]
+pub struct ConditionMemo {
+    results: HashMap<Arc<str>, bool>,
+    counters: Vec<(String, ConditionCounter)>,
+}
+
+impl ConditionMemo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forgets the results of the previous file
+    pub fn start_file(&mut self) {
+        self.results.clear();
+    }
+
+    /// Result of `condition` for `file`, reused when a condition with `key` already ran
+    pub fn evaluate(
+        &mut self,
+        key: Option<&Arc<str>>,
+        condition: &dyn Condition,
+        file: &FileInfo,
+        context: &Context,
+    ) -> bool {
+        if let Some(&result) = key.and_then(|key| self.results.get(key)) {
+            self.counter(condition.name()).reused += 1;
+            return result;
+        }
+        let started = Instant::now();
+        let result = condition.matches(file, context);
+        let counter = self.counter(condition.name());
+        counter.evaluations += 1;
+        counter.time += started.elapsed();
+        if let Some(key) = key {
+            self.results.insert(Arc::clone(key), result);
+        }
+        result
+    }
+
+    /// Adds the counters of `other`, e.g. a memo used on another thread
+    pub fn merge(&mut self, other: Self) {
+        for (name, counter) in other.counters {
+            let total = self.counter(&name);
+            total.evaluations += counter.evaluations;
+            total.reused += counter.reused;
+            total.time += counter.time;
+        }
+    }
+
+    fn counter(&mut self, name: &str) -> &mut ConditionCounter {
+        // A handful of condition types, so a scan beats allocating a key per evaluation
+        let index = if let Some(index) = self.counters.iter().position(|(n, _)| n == name) {
+            index
+        } else {
+            self.counters
+                .push((name.to_string(), ConditionCounter::default()));
+            self.counters.len() - 1
+        };
+        &mut self.counters[index].1
+    }
+
+    /// Counters by condition type, alphabetically
+    pub fn counters(&self) -> Vec<(&str, ConditionCounter)> {
+        let mut counters: Vec<_> = self
+            .counters
+            .iter()
+            .map(|(name, counter)| (name.as_str(), *counter))
+            .collect();
+        counters.sort_by_key(|(name, _)| *name);
+        counters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conditions::AgeCondition;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    #[test]
+    fn test_memo_reuses_results_within_a_file() {
+        let condition = AgeCondition::new(Some(1), None);
+        let key: Arc<str> = Arc::from("Age { min_hours: Some(1), max_hours: None }");
+        let file = FileInfo {
+            path: PathBuf::from("/mnt/cache/a.mkv"),
+            size: 1,
+            modified: SystemTime::UNIX_EPOCH,
+            accessed: SystemTime::UNIX_EPOCH,
+            owner: None,
+        };
+        let context = Context::new();
+        let mut memo = ConditionMemo::new();
+
+        for _ in 0..3 {
+            assert!(memo.evaluate(Some(&key), &condition, &file, &context));
+        }
+        memo.evaluate(None, &condition, &file, &context);
+        memo.start_file();
+        memo.evaluate(Some(&key), &condition, &file, &context);
+
+        let counters = memo.counters();
+        assert_eq!(counters.len(), 1);
+        assert_eq!(counters[0].0, "age");
+        assert_eq!((counters[0].1.evaluations, counters[0].1.reused), (3, 2));
+    }
+}
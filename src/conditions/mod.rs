@@ -1,22 +1,45 @@
 mod active_window;
 mod age;
 mod always_true;
+mod directory;
 mod file_extension;
 mod file_size;
 mod filename_contains;
+mod memo;
+mod owner;
+mod path_glob;
 mod path_prefix;
+mod planning;
+mod recently_watched_movie;
+mod seen_age;
+mod sonarr;
+mod tier_usage;
+mod torrent;
 
 pub use active_window::ActiveWindowCondition;
 pub use age::AgeCondition;
 pub use always_true::AlwaysTrueCondition;
+pub use directory::{
+    DirectoryFileCountCondition, DirectorySizeCondition, NewestInDirectoryAgeCondition,
+};
 pub use file_extension::{ExtensionMode, FileExtensionCondition};
 pub use file_size::FileSizeCondition;
 pub use filename_contains::{ContainsMode, FilenameContainsCondition};
+pub use memo::{ConditionCounter, ConditionMemo};
+pub use owner::{OwnerCondition, OwnerKind, OwnerMode};
+pub use path_glob::{GlobMode, PathGlobCondition};
 pub use path_prefix::{PathPrefixCondition, PrefixMode};
+pub use planning::{PlanningView, SimulatedTier};
+pub use recently_watched_movie::RecentlyWatchedMovieCondition;
+pub use seen_age::SeenAgeCondition;
+pub use sonarr::{SonarrAiringWithinCondition, SonarrMonitoredCondition};
+pub use tier_usage::TierUsageCondition;
+pub use torrent::{TorrentRatioCondition, TorrentSeedingCondition};
 
 use crate::{FileInfo, GlobalStats};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::SystemTime;
 
 /// Execution context for conditions, allows passing additional information
 #[derive(Debug, Clone)]
@@ -27,6 +50,12 @@ pub struct Context {
     /// Global statistics collected from all files (optional)
     /// Shared across all condition evaluations via Arc
     pub global_stats: Option<Arc<GlobalStats>>,
+
+    /// Simulated tier state and planned moves, kept current by the planner
+    pub planning: PlanningView,
+
+    /// Instant age-based conditions measure against; the system time when unset
+    pub now: Option<SystemTime>,
 }
 
 impl Context {
@@ -34,9 +63,21 @@ impl Context {
         Self {
             current_tier_path: None,
             global_stats: None,
+            planning: PlanningView::new(),
+            now: None,
         }
     }
 
+    pub const fn with_now(mut self, now: SystemTime) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    /// The planning instant, or the current system time
+    pub fn now(&self) -> SystemTime {
+        self.now.unwrap_or_else(SystemTime::now)
+    }
+
     pub fn with_tier_path(mut self, tier_path: PathBuf) -> Self {
         self.current_tier_path = Some(tier_path);
         self
@@ -58,6 +99,15 @@ impl Default for Context {
 pub trait Condition: Send + Sync {
     fn matches(&self, file: &FileInfo, context: &Context) -> bool;
     fn name(&self) -> &str;
+
+    /// Whether the result depends on `Context::planning`, which changes as
+    /// moves are planned; such conditions keep placement matching serial
+    ///
+    /// Defaults to `true` so a custom condition is never matched on stale
+    /// state; return `false` to let Pass 2 match it on all cores.
+    fn reads_planning(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
@@ -81,4 +131,20 @@ mod tests {
         fn assert_send_sync<T: Send + Sync>() {}
         assert_send_sync::<Box<dyn Condition>>();
     }
+
+    #[test]
+    fn test_custom_conditions_keep_matching_serial() {
+        struct Custom;
+        impl Condition for Custom {
+            fn matches(&self, _file: &FileInfo, _context: &Context) -> bool {
+                true
+            }
+            fn name(&self) -> &'static str {
+                "custom"
+            }
+        }
+
+        assert!(Custom.reads_planning());
+        assert!(!AlwaysTrueCondition.reads_planning());
+    }
 }
@@ -0,0 +1,149 @@
+use super::{Condition, Context};
+use crate::FileInfo;
+use std::fs;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OwnerMode {
+    /// Whitelist: file MUST be owned by one of the accounts
+    Whitelist,
+    /// Blacklist: file must NOT be owned by any of the accounts
+    Blacklist,
+}
+
+/// Which half of the file's ownership is checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OwnerKind {
+    User,
+    Group,
+}
+
+impl OwnerKind {
+    const fn database(self) -> &'static str {
+        match self {
+            Self::User => "/etc/passwd",
+            Self::Group => "/etc/group",
+        }
+    }
+}
+
+/// Condition that checks the owning user (uid) or group (gid) of a file
+///
+/// Accounts are numeric ids; names are resolved once when the condition is built.
+/// Files without ownership information never match, in either mode.
+pub struct OwnerCondition {
+    kind: OwnerKind,
+    ids: Vec<u32>,
+    mode: OwnerMode,
+}
+
+impl OwnerCondition {
+    pub const fn new(kind: OwnerKind, ids: Vec<u32>, mode: OwnerMode) -> Self {
+        Self { kind, ids, mode }
+    }
+
+    /// Numeric id of a user or group name from `/etc/passwd` or `/etc/group`
+    pub fn resolve(kind: OwnerKind, name: &str) -> Option<u32> {
+        let database = fs::read_to_string(kind.database()).ok()?;
+        lookup_id(&database, name)
+    }
+}
+
+/// Third field of the `name:password:id:...` line for `name`
+fn lookup_id(database: &str, name: &str) -> Option<u32> {
+    database.lines().find_map(|line| {
+        let mut fields = line.split(':');
+        (fields.next() == Some(name))
+            .then(|| fields.nth(1)?.parse().ok())
+            .flatten()
+    })
+}
+
+impl Condition for OwnerCondition {
+    fn matches(&self, file: &FileInfo, _context: &Context) -> bool {
+        let Some(owner) = file.owner else {
+            return false;
+        };
+        let id = match self.kind {
+            OwnerKind::User => owner.uid,
+            OwnerKind::Group => owner.gid,
+        };
+        let listed = self.ids.contains(&id);
+
+        match self.mode {
+            OwnerMode::Whitelist => listed,
+            OwnerMode::Blacklist => !listed,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self.kind {
+            OwnerKind::User => "owner",
+            OwnerKind::Group => "group",
+        }
+    }
+
+    fn reads_planning(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::FileOwner;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    fn file(owner: Option<FileOwner>) -> FileInfo {
+        FileInfo {
+            path: PathBuf::from("/mnt/cache/downloads/movie.mkv"),
+            size: 1000,
+            modified: SystemTime::now(),
+            accessed: SystemTime::now(),
+            owner,
+        }
+    }
+
+    #[test]
+    fn test_matches_owner_and_group() {
+        let context = Context::new();
+        let alice = file(Some(FileOwner {
+            uid: 1000,
+            gid: 100,
+        }));
+        let bob = file(Some(FileOwner {
+            uid: 1001,
+            gid: 100,
+        }));
+
+        let users = OwnerCondition::new(OwnerKind::User, vec![1000], OwnerMode::Whitelist);
+        assert!(users.matches(&alice, &context));
+        assert!(!users.matches(&bob, &context));
+
+        let groups = OwnerCondition::new(OwnerKind::Group, vec![100], OwnerMode::Blacklist);
+        assert!(!groups.matches(&alice, &context));
+        assert!(!groups.matches(&bob, &context));
+        assert_eq!(groups.name(), "group");
+    }
+
+    #[test]
+    fn test_unknown_owner_never_matches() {
+        let context = Context::new();
+        for mode in [OwnerMode::Whitelist, OwnerMode::Blacklist] {
+            let condition = OwnerCondition::new(OwnerKind::User, vec![0], mode);
+            assert!(!condition.matches(&file(None), &context));
+        }
+    }
+
+    #[test]
+    fn test_lookup_id() {
+        let passwd = "root:x:0:0:root:/root:/bin/bash\n\
+                      alice:x:1000:1000::/home/alice:/bin/sh\n\
+                      broken:x:notanumber:1::/:/bin/false\n";
+        assert_eq!(lookup_id(passwd, "alice"), Some(1000));
+        assert_eq!(lookup_id(passwd, "root"), Some(0));
+        assert_eq!(lookup_id(passwd, "ali"), None);
+        assert_eq!(lookup_id(passwd, "broken"), None);
+        assert_eq!(lookup_id("media:x:1500:alice,bob", "media"), Some(1500));
+    }
+}
@@ -0,0 +1,178 @@
+use super::{Condition, Context};
+use crate::FileInfo;
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlobMode {
+    /// Whitelist: relative path MUST match one of the patterns
+    Whitelist,
+    /// Blacklist: relative path must NOT match any of the patterns
+    Blacklist,
+}
+
+/// Condition that matches the path relative to the tier root against glob patterns
+///
+/// `*` and `?` stay within one path component, `**` spans any number of them.
+/// A pattern ending in `/` matches everything under a matching directory.
+///
+/// Example:
+/// - Relative path: `Show/Season 01/s01e01.mkv`
+/// - `*/Season */` → matches ✓
+/// - `**/extras/**` → doesn't match ✗
+pub struct PathGlobCondition {
+    patterns: GlobSet,
+    mode: GlobMode,
+}
+
+impl PathGlobCondition {
+    pub fn new(patterns: &[String]) -> Result<Self, globset::Error> {
+        Self::new_with_mode(patterns, GlobMode::Whitelist)
+    }
+
+    pub fn new_with_mode(patterns: &[String], mode: GlobMode) -> Result<Self, globset::Error> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(Self::compile(pattern)?);
+        }
+        Ok(Self {
+            patterns: builder.build()?,
+            mode,
+        })
+    }
+
+    fn compile(pattern: &str) -> Result<Glob, globset::Error> {
+        let pattern = pattern.trim_start_matches('/');
+        let pattern = if pattern.ends_with('/') {
+            format!("{pattern}**")
+        } else {
+            pattern.to_string()
+        };
+        GlobBuilder::new(&pattern)
+            .literal_separator(true)
+            .backslash_escape(true)
+            .build()
+    }
+}
+
+impl Condition for PathGlobCondition {
+    fn matches(&self, file: &FileInfo, context: &Context) -> bool {
+        let Some(tier_path) = &context.current_tier_path else {
+            tracing::warn!(
+                "PathGlobCondition requires current_tier_path in context, but it's None"
+            );
+            return false;
+        };
+
+        let Ok(relative_path) = file.path.strip_prefix(tier_path) else {
+            tracing::warn!(
+                "File {} is not under tier {}",
+                file.path.display(),
+                tier_path.display()
+            );
+            return false;
+        };
+
+        let matched = self.patterns.is_match(relative_path);
+        match self.mode {
+            GlobMode::Whitelist => matched,
+            GlobMode::Blacklist => !matched,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "path_glob"
+    }
+
+    fn reads_planning(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    fn matches(patterns: &[&str], mode: GlobMode, relative: &str) -> bool {
+        let patterns: Vec<String> = patterns.iter().map(ToString::to_string).collect();
+        let condition = PathGlobCondition::new_with_mode(&patterns, mode).unwrap();
+        let file = FileInfo {
+            path: PathBuf::from("/mnt/cache").join(relative),
+            size: 1000,
+            modified: SystemTime::now(),
+            accessed: SystemTime::now(),
+            owner: None,
+        };
+        condition.matches(&file, &Context::new().with_tier_path("/mnt/cache".into()))
+    }
+
+    #[test]
+    fn test_single_star_stays_in_component() {
+        let season = ["*/Season */"];
+        assert!(matches(
+            &season,
+            GlobMode::Whitelist,
+            "Show/Season 01/e01.mkv"
+        ));
+        assert!(!matches(
+            &season,
+            GlobMode::Whitelist,
+            "tv/Show/Season 01/e01.mkv"
+        ));
+        assert!(!matches(&["*.mkv"], GlobMode::Whitelist, "Show/e01.mkv"));
+    }
+
+    #[test]
+    fn test_double_star_spans_components() {
+        let extras = ["**/extras/**"];
+        assert!(matches(
+            &extras,
+            GlobMode::Whitelist,
+            "Movie (2020)/extras/trailer.mkv"
+        ));
+        assert!(matches(&extras, GlobMode::Whitelist, "a/b/extras/c/d.mkv"));
+        assert!(!matches(
+            &extras,
+            GlobMode::Whitelist,
+            "Movie (2020)/movie.mkv"
+        ));
+        assert!(matches(
+            &["**/*.nfo", "**/*.srt"],
+            GlobMode::Whitelist,
+            "a/b.srt"
+        ));
+    }
+
+    #[test]
+    fn test_blacklist_mode() {
+        assert!(!matches(
+            &["downloads/**"],
+            GlobMode::Blacklist,
+            "downloads/x.mkv"
+        ));
+        assert!(matches(
+            &["downloads/**"],
+            GlobMode::Blacklist,
+            "movies/x.mkv"
+        ));
+    }
+
+    #[test]
+    fn test_invalid_pattern_rejected() {
+        assert!(PathGlobCondition::new(&["Season [1-".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_requires_tier_path() {
+        let condition = PathGlobCondition::new(&["**".to_string()]).unwrap();
+        let file = FileInfo {
+            path: PathBuf::from("/mnt/cache/a.mkv"),
+            size: 1,
+            modified: SystemTime::now(),
+            accessed: SystemTime::now(),
+            owner: None,
+        };
+        assert!(!condition.matches(&file, &Context::new()));
+    }
+}
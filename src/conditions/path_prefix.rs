@@ -85,6 +85,10 @@ impl Condition for PathPrefixCondition {
     fn name(&self) -> &'static str {
         "path_prefix"
     }
+
+    fn reads_planning(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
@@ -99,6 +103,7 @@ mod tests {
             size: 1000,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         }
     }
 
@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+
+/// Simulated state of one tier while planning
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SimulatedTier {
+    pub free: u64,
+    pub total: u64,
+    /// Moves planned onto this tier so far
+    pub moves_in: usize,
+    /// Moves planned off this tier so far
+    pub moves_out: usize,
+}
+
+impl SimulatedTier {
+    /// Used share of the tier in percent; an empty tier counts as 0%
+    pub fn usage_percent(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.total.saturating_sub(self.free) as f64 / self.total as f64 * 100.0
+    }
+}
+
+/// Read-only view of the planner's simulated state, as of the file being evaluated
+///
+/// Pass 2 updates it after every planned move, so conditions see the effect of
+/// the decisions made before them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlanningView {
+    tiers: BTreeMap<String, SimulatedTier>,
+    planned_moves: usize,
+    planned_bytes: u64,
+}
+
+impl PlanningView {
+    pub const fn new() -> Self {
+        Self {
+            tiers: BTreeMap::new(),
+            planned_moves: 0,
+            planned_bytes: 0,
+        }
+    }
+
+    pub fn with_tier(mut self, name: impl Into<String>, free: u64, total: u64) -> Self {
+        self.tiers.insert(
+            name.into(),
+            SimulatedTier {
+                free,
+                total,
+                ..SimulatedTier::default()
+            },
+        );
+        self
+    }
+
+    pub fn tier(&self, name: &str) -> Option<&SimulatedTier> {
+        self.tiers.get(name)
+    }
+
+    pub fn tiers(&self) -> impl Iterator<Item = (&str, &SimulatedTier)> {
+        self.tiers.iter().map(|(name, tier)| (name.as_str(), tier))
+    }
+
+    /// Moves planned so far, across all tiers
+    pub const fn planned_moves(&self) -> usize {
+        self.planned_moves
+    }
+
+    /// Bytes of the moves planned so far
+    pub const fn planned_bytes(&self) -> u64 {
+        self.planned_bytes
+    }
+
+    /// Account for a planned move of `size` bytes
+    pub fn record_move(&mut self, size: u64, from_tier: &str, to_tier: &str) {
+        if let Some(from) = self.tiers.get_mut(from_tier) {
+            from.free = from.free.saturating_add(size);
+            from.moves_out += 1;
+        }
+        if let Some(to) = self.tiers.get_mut(to_tier) {
+            to.free = to.free.saturating_sub(size);
+            to.moves_in += 1;
+        }
+        self.planned_moves += 1;
+        self.planned_bytes = self.planned_bytes.saturating_add(size);
+    }
+
+    /// Account for a planned copy of `size` bytes; the source tier keeps its file
+    pub fn record_copy(&mut self, size: u64, to_tier: &str) {
+        if let Some(to) = self.tiers.get_mut(to_tier) {
+            to.free = to.free.saturating_sub(size);
+            to.moves_in += 1;
+        }
+        self.planned_moves += 1;
+        self.planned_bytes = self.planned_bytes.saturating_add(size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_move() {
+        let mut view = PlanningView::new()
+            .with_tier("cache", 200, 1000)
+            .with_tier("storage", 5000, 10000);
+
+        view.record_move(300, "cache", "storage");
+
+        let cache = view.tier("cache").unwrap();
+        assert_eq!((cache.free, cache.moves_out, cache.moves_in), (500, 1, 0));
+        assert!((cache.usage_percent() - 50.0).abs() < f64::EPSILON);
+        assert_eq!(view.tier("storage").unwrap().moves_in, 1);
+        assert_eq!((view.planned_moves(), view.planned_bytes()), (1, 300));
+        assert!(view.tier("ssd").is_none());
+    }
+}
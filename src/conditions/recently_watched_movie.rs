@@ -0,0 +1,115 @@
+use super::{Condition, Context};
+use crate::FileInfo;
+use std::time::UNIX_EPOCH;
+
+/// Condition that matches movies watched or in progress within the last `days`
+///
+/// Files are matched to Tautulli history by title and year parsed from the filename.
+///
+/// Requires Tautulli to be configured and `TautulliStats` to be present in `GlobalStats`.
+#[derive(Debug, Clone, Copy)]
+pub struct RecentlyWatchedMovieCondition {
+    days: u32,
+}
+
+impl RecentlyWatchedMovieCondition {
+    pub const fn new(days: u32) -> Self {
+        Self { days }
+    }
+}
+
+impl Condition for RecentlyWatchedMovieCondition {
+    fn matches(&self, file: &FileInfo, context: &Context) -> bool {
+        // If Tautulli failed to load, Balancer already logged a warning in Pass 1
+        let Some(tautulli_stats) = context
+            .global_stats
+            .as_ref()
+            .and_then(|global_stats| global_stats.tautulli_stats.as_ref())
+        else {
+            return false;
+        };
+
+        let now = context
+            .now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let cutoff = now.saturating_sub(u64::from(self.days) * 24 * 3600);
+        tautulli_stats.is_movie_watched_since(&file.path, cutoff)
+    }
+
+    fn name(&self) -> &'static str {
+        "recently_watched_movie"
+    }
+
+    fn reads_planning(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tautulli::{HistoryItem, TautulliStats};
+    use crate::timestamp::unix_now;
+    use crate::{FileStats, GlobalStats};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::SystemTime;
+
+    fn create_file_info(path: &str) -> FileInfo {
+        FileInfo {
+            path: PathBuf::from(path),
+            size: 1024,
+            modified: SystemTime::now(),
+            accessed: SystemTime::now(),
+            owner: None,
+        }
+    }
+
+    fn create_movie_item(title: &str, year: u32, days_ago: u64) -> HistoryItem {
+        HistoryItem {
+            user: "alice".to_string(),
+            rating_key: "500".to_string(),
+            media_type: "movie".to_string(),
+            title: title.to_string(),
+            year,
+            grandparent_title: String::new(),
+            parent_media_index: 0,
+            media_index: 0,
+            percent_complete: 100,
+            stopped: unix_now() - days_ago * 24 * 3600,
+        }
+    }
+
+    #[test]
+    fn test_recently_watched_movie() {
+        let files = [
+            create_file_info("/mnt/cache/Dune (2021).mkv"),
+            create_file_info("/mnt/cache/Heat (1995).mkv"),
+            create_file_info("/mnt/cache/Alien (1979).mkv"),
+        ];
+        let history = [
+            create_movie_item("Dune", 2021, 2),
+            create_movie_item("Heat", 1995, 30),
+        ];
+        let tautulli_stats =
+            TautulliStats::build(files.iter(), vec![], 1, 1).with_movie_history(&history, 90);
+
+        let mut global_stats = GlobalStats::new(FileStats::new());
+        global_stats.tautulli_stats = Some(tautulli_stats);
+        let context = Context::new().with_global_stats(&Arc::new(global_stats));
+
+        let condition = RecentlyWatchedMovieCondition::new(7);
+        assert!(condition.matches(&files[0], &context));
+        assert!(!condition.matches(&files[1], &context));
+        assert!(!condition.matches(&files[2], &context));
+    }
+
+    #[test]
+    fn test_recently_watched_movie_no_tautulli_stats() {
+        let context = Context::new();
+        let file = create_file_info("/mnt/cache/Dune (2021).mkv");
+
+        assert!(!RecentlyWatchedMovieCondition::new(7).matches(&file, &context));
+    }
+}
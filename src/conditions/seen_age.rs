@@ -0,0 +1,107 @@
+use super::{Condition, Context};
+use crate::FileInfo;
+use std::time::Duration;
+
+/// Condition that checks how long tierflow has known the file
+///
+/// Unlike [`AgeCondition`](super::AgeCondition) this ignores the modification
+/// time, which media files often keep from their release. The age comes from
+/// first-seen timestamps in the state journal; files without one count as new.
+pub struct SeenAgeCondition {
+    min_hours: Option<u64>,
+    max_hours: Option<u64>,
+}
+
+impl SeenAgeCondition {
+    pub const fn new(min_hours: Option<u64>, max_hours: Option<u64>) -> Self {
+        Self {
+            min_hours,
+            max_hours,
+        }
+    }
+}
+
+impl Condition for SeenAgeCondition {
+    fn matches(&self, file: &FileInfo, context: &Context) -> bool {
+        let age = context
+            .global_stats
+            .as_ref()
+            .and_then(|stats| stats.seen_ages.as_ref())
+            .zip(context.current_tier_path.as_ref())
+            .and_then(|(ages, tier_path)| {
+                let relative = file.path.strip_prefix(tier_path).ok()?;
+                Some(ages.age(relative))
+            })
+            .unwrap_or_default();
+
+        self.min_hours
+            .is_none_or(|h| age >= Duration::from_secs(h * 3600))
+            && self
+                .max_hours
+                .is_none_or(|h| age <= Duration::from_secs(h * 3600))
+    }
+
+    fn name(&self) -> &'static str {
+        "seen_age"
+    }
+
+    fn reads_planning(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileStats, GlobalStats, SeenAges, StateJournal};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::SystemTime;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    fn file(path: &str) -> FileInfo {
+        FileInfo {
+            path: PathBuf::from(path),
+            size: 1000,
+            // Released years ago, which must not matter
+            modified: SystemTime::UNIX_EPOCH,
+            accessed: SystemTime::now(),
+            owner: None,
+        }
+    }
+
+    fn context(now: SystemTime) -> Context {
+        let mut journal = StateJournal::in_memory();
+        journal.record_first_seen(PathBuf::from("Show/old.mkv"), now - 72 * HOUR);
+        journal.record_first_seen(PathBuf::from("Show/new.mkv"), now - 2 * HOUR);
+        let seen_ages = Arc::new(SeenAges::from_journal(&journal, now));
+
+        let stats = GlobalStats::new(FileStats::new()).with_seen_ages(&seen_ages);
+        Context::new()
+            .with_global_stats(&Arc::new(stats))
+            .with_tier_path(PathBuf::from("/mnt/cache"))
+    }
+
+    #[test]
+    fn test_seen_age_ignores_mtime() {
+        let context = context(SystemTime::now());
+        let condition = SeenAgeCondition::new(Some(48), None);
+
+        assert!(condition.matches(&file("/mnt/cache/Show/old.mkv"), &context));
+        assert!(!condition.matches(&file("/mnt/cache/Show/new.mkv"), &context));
+        // Never seen before: new, despite the ancient mtime
+        assert!(!condition.matches(&file("/mnt/cache/Show/unknown.mkv"), &context));
+    }
+
+    #[test]
+    fn test_seen_age_max_hours() {
+        let context = context(SystemTime::now());
+        let condition = SeenAgeCondition::new(None, Some(24));
+
+        assert!(!condition.matches(&file("/mnt/cache/Show/old.mkv"), &context));
+        assert!(condition.matches(&file("/mnt/cache/Show/new.mkv"), &context));
+        assert!(condition.matches(&file("/mnt/cache/Show/unknown.mkv"), &context));
+        assert!(condition.matches(&file("/mnt/cache/Show/old.mkv"), &Context::new()));
+    }
+}
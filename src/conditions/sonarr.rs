@@ -0,0 +1,131 @@
+use super::{Condition, Context};
+use crate::FileInfo;
+use crate::arr::SonarrStats;
+
+fn sonarr_stats(context: &Context) -> Option<&SonarrStats> {
+    // If Sonarr failed to load, Balancer already logged a warning in Pass 1
+    context.global_stats.as_ref()?.sonarr_stats.as_ref()
+}
+
+/// Condition that matches episodes of seasons monitored in Sonarr
+///
+/// Requires Sonarr to be configured and `SonarrStats` to be present in `GlobalStats`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SonarrMonitoredCondition;
+
+impl Condition for SonarrMonitoredCondition {
+    fn matches(&self, file: &FileInfo, context: &Context) -> bool {
+        sonarr_stats(context).is_some_and(|stats| stats.is_monitored(&file.path))
+    }
+
+    fn name(&self) -> &'static str {
+        "sonarr_monitored"
+    }
+
+    fn reads_planning(&self) -> bool {
+        false
+    }
+}
+
+/// Condition that matches episodes of series with a new episode airing soon
+///
+/// Requires Sonarr to be configured and `SonarrStats` to be present in `GlobalStats`.
+#[derive(Debug, Clone, Copy)]
+pub struct SonarrAiringWithinCondition {
+    days: u32,
+}
+
+impl SonarrAiringWithinCondition {
+    pub const fn new(days: u32) -> Self {
+        Self { days }
+    }
+}
+
+impl Condition for SonarrAiringWithinCondition {
+    fn matches(&self, file: &FileInfo, context: &Context) -> bool {
+        sonarr_stats(context).is_some_and(|stats| stats.airs_within(&file.path, self.days))
+    }
+
+    fn name(&self) -> &'static str {
+        "sonarr_airing_within"
+    }
+
+    fn reads_planning(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arr::{CalendarEpisode, Season, Series};
+    use crate::{FileStats, GlobalStats};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::SystemTime;
+
+    // 2024-01-15T00:00:00Z
+    const NOW: u64 = 1_705_276_800;
+
+    fn create_file_info(path: &str) -> FileInfo {
+        FileInfo {
+            path: PathBuf::from(path),
+            size: 1024,
+            modified: SystemTime::now(),
+            accessed: SystemTime::now(),
+            owner: None,
+        }
+    }
+
+    fn context_with_sonarr() -> Context {
+        let series = Series {
+            id: 1,
+            title: "Severance".to_string(),
+            monitored: true,
+            seasons: vec![Season {
+                season_number: 2,
+                monitored: true,
+            }],
+        };
+        let calendar = CalendarEpisode {
+            series_id: 1,
+            season_number: 2,
+            episode_number: 4,
+            air_date_utc: Some("2024-01-18T02:00:00Z".to_string()),
+        };
+        let stats = SonarrStats::build(&[series], &[calendar], NOW);
+
+        let mut global_stats = GlobalStats::new(FileStats::new());
+        global_stats.sonarr_stats = Some(stats);
+        Context::new().with_global_stats(&Arc::new(global_stats))
+    }
+
+    #[test]
+    fn test_sonarr_monitored() {
+        let context = context_with_sonarr();
+
+        let file = create_file_info("/mnt/cache/Severance.S02E03.mkv");
+        assert!(SonarrMonitoredCondition.matches(&file, &context));
+
+        let file = create_file_info("/mnt/cache/Severance.S01E03.mkv");
+        assert!(!SonarrMonitoredCondition.matches(&file, &context));
+    }
+
+    #[test]
+    fn test_sonarr_airing_within() {
+        let context = context_with_sonarr();
+        let file = create_file_info("/mnt/cache/Severance.S01E03.mkv");
+
+        assert!(SonarrAiringWithinCondition::new(7).matches(&file, &context));
+        assert!(!SonarrAiringWithinCondition::new(1).matches(&file, &context));
+    }
+
+    #[test]
+    fn test_sonarr_conditions_without_stats() {
+        let file = create_file_info("/mnt/cache/Severance.S02E03.mkv");
+        let context = Context::new();
+
+        assert!(!SonarrMonitoredCondition.matches(&file, &context));
+        assert!(!SonarrAiringWithinCondition::new(7).matches(&file, &context));
+    }
+}
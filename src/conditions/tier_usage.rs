@@ -0,0 +1,89 @@
+use super::{Condition, Context};
+use crate::FileInfo;
+
+/// Condition on how full a tier is, independent of the file
+///
+/// Evaluated against the simulated usage in the context, so a rule like
+/// "demote while cache is above 75%" stops matching once enough moves are planned.
+/// Without usage for the tier in the context nothing matches.
+pub struct TierUsageCondition {
+    tier: String,
+    min_percent: Option<u64>,
+    max_percent: Option<u64>,
+}
+
+impl TierUsageCondition {
+    pub const fn new(tier: String, min_percent: Option<u64>, max_percent: Option<u64>) -> Self {
+        Self {
+            tier,
+            min_percent,
+            max_percent,
+        }
+    }
+}
+
+impl Condition for TierUsageCondition {
+    fn matches(&self, _file: &FileInfo, context: &Context) -> bool {
+        let Some(usage) = context
+            .planning
+            .tier(&self.tier)
+            .map(super::SimulatedTier::usage_percent)
+        else {
+            tracing::warn!(
+                "TierUsageCondition has no usage for tier '{}' in context",
+                self.tier
+            );
+            return false;
+        };
+
+        self.min_percent.is_none_or(|min| usage >= min as f64)
+            && self.max_percent.is_none_or(|max| usage <= max as f64)
+    }
+
+    fn name(&self) -> &'static str {
+        "tier_usage"
+    }
+
+    fn reads_planning(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conditions::PlanningView;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    fn file() -> FileInfo {
+        FileInfo {
+            path: PathBuf::from("/mnt/cache/movie.mkv"),
+            size: 1000,
+            modified: SystemTime::now(),
+            accessed: SystemTime::now(),
+            owner: None,
+        }
+    }
+
+    #[test]
+    fn test_matches_usage_range() {
+        let mut context = Context::new();
+        context.planning = PlanningView::new().with_tier("cache", 20, 100);
+
+        let pressure = TierUsageCondition::new("cache".to_string(), Some(75), None);
+        assert!(pressure.matches(&file(), &context));
+
+        context.planning.record_move(10, "cache", "storage");
+        assert!(!pressure.matches(&file(), &context));
+
+        let calm = TierUsageCondition::new("cache".to_string(), None, Some(70));
+        assert!(calm.matches(&file(), &context));
+    }
+
+    #[test]
+    fn test_unknown_tier_never_matches() {
+        let condition = TierUsageCondition::new("ssd".to_string(), None, None);
+        assert!(!condition.matches(&file(), &Context::new()));
+    }
+}
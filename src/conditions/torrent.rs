@@ -0,0 +1,142 @@
+use super::{Condition, Context};
+use crate::FileInfo;
+use crate::torrent::TorrentFile;
+
+fn torrent_file<'a>(file: &FileInfo, context: &'a Context) -> Option<&'a TorrentFile> {
+    // If the torrent client failed to load, Balancer already logged a warning in Pass 1
+    context
+        .global_stats
+        .as_ref()?
+        .torrent_stats
+        .as_ref()?
+        .get(&file.path)
+}
+
+/// Condition that matches files of torrents the client is seeding
+///
+/// Requires a torrent client to be configured and `TorrentStats` to be present in `GlobalStats`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TorrentSeedingCondition;
+
+impl Condition for TorrentSeedingCondition {
+    fn matches(&self, file: &FileInfo, context: &Context) -> bool {
+        torrent_file(file, context).is_some_and(|torrent| torrent.seeding)
+    }
+
+    fn name(&self) -> &'static str {
+        "torrent_seeding"
+    }
+
+    fn reads_planning(&self) -> bool {
+        false
+    }
+}
+
+/// Condition that matches torrent files by share ratio
+///
+/// Files in several torrents use the lowest ratio; files in no torrent never match.
+#[derive(Debug, Clone, Copy)]
+pub struct TorrentRatioCondition {
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl TorrentRatioCondition {
+    pub const fn new(min: Option<f64>, max: Option<f64>) -> Self {
+        Self { min, max }
+    }
+}
+
+impl Condition for TorrentRatioCondition {
+    fn matches(&self, file: &FileInfo, context: &Context) -> bool {
+        torrent_file(file, context).is_some_and(|torrent| {
+            self.min.is_none_or(|min| torrent.ratio >= min)
+                && self.max.is_none_or(|max| torrent.ratio <= max)
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "torrent_ratio"
+    }
+
+    fn reads_planning(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::torrent::{Torrent, TorrentStats};
+    use crate::{FileStats, GlobalStats};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::SystemTime;
+
+    fn create_file_info(path: &str) -> FileInfo {
+        FileInfo {
+            path: PathBuf::from(path),
+            size: 1024,
+            modified: SystemTime::now(),
+            accessed: SystemTime::now(),
+            owner: None,
+        }
+    }
+
+    fn context_with_torrents() -> Context {
+        let torrents = [
+            Torrent {
+                name: "seeding".to_string(),
+                seeding: true,
+                ratio: 0.4,
+                files: vec!["/mnt/cache/torrents/seeding.mkv".to_string()],
+            },
+            Torrent {
+                name: "done".to_string(),
+                seeding: false,
+                ratio: 2.0,
+                files: vec!["/mnt/cache/torrents/done.mkv".to_string()],
+            },
+        ];
+        let mut global_stats = GlobalStats::new(FileStats::new());
+        global_stats.torrent_stats = Some(TorrentStats::build(&torrents, &[]));
+        Context::new().with_global_stats(&Arc::new(global_stats))
+    }
+
+    #[test]
+    fn test_torrent_seeding() {
+        let context = context_with_torrents();
+
+        let file = create_file_info("/mnt/cache/torrents/seeding.mkv");
+        assert!(TorrentSeedingCondition.matches(&file, &context));
+
+        let file = create_file_info("/mnt/cache/torrents/done.mkv");
+        assert!(!TorrentSeedingCondition.matches(&file, &context));
+    }
+
+    #[test]
+    fn test_torrent_ratio() {
+        let context = context_with_torrents();
+        let seeding = create_file_info("/mnt/cache/torrents/seeding.mkv");
+        let done = create_file_info("/mnt/cache/torrents/done.mkv");
+        let untracked = create_file_info("/mnt/cache/movies/other.mkv");
+
+        let reached = TorrentRatioCondition::new(Some(1.0), None);
+        assert!(!reached.matches(&seeding, &context));
+        assert!(reached.matches(&done, &context));
+        assert!(!reached.matches(&untracked, &context));
+
+        let below = TorrentRatioCondition::new(None, Some(1.0));
+        assert!(below.matches(&seeding, &context));
+        assert!(!below.matches(&untracked, &context));
+    }
+
+    #[test]
+    fn test_torrent_conditions_without_stats() {
+        let file = create_file_info("/mnt/cache/torrents/seeding.mkv");
+        let context = Context::new();
+
+        assert!(!TorrentSeedingCondition.matches(&file, &context));
+        assert!(!TorrentRatioCondition::new(None, None).matches(&file, &context));
+    }
+}
@@ -0,0 +1,69 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// How files are checked for active writers before a move
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityCheckMethod {
+    /// lsof, then fuser; size-stability sampling if neither is installed
+    #[default]
+    Auto,
+    Lsof,
+    Fuser,
+    /// Only sample size and mtime (works for writers on other NFS clients)
+    SizeStability,
+}
+
+/// Skip files that are open or still growing when a move is about to start
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ActivityCheckConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    #[serde(default)]
+    pub method: ActivityCheckMethod,
+
+    /// Seconds between the two size samples of a recently modified file
+    #[serde(default = "default_stability_secs")]
+    pub stability_secs: u64,
+}
+
+impl Default for ActivityCheckConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            method: ActivityCheckMethod::default(),
+            stability_secs: default_stability_secs(),
+        }
+    }
+}
+
+const fn default_true() -> bool {
+    true
+}
+
+const fn default_stability_secs() -> u64 {
+    5
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_activity_check_defaults() {
+        let config: ActivityCheckConfig = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(config, ActivityCheckConfig::default());
+        assert!(config.enabled);
+        assert_eq!(config.method, ActivityCheckMethod::Auto);
+    }
+
+    #[test]
+    fn test_deserialize_activity_check_full() {
+        let yaml = "enabled: true\nmethod: size_stability\nstability_secs: 10";
+        let config: ActivityCheckConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.method, ActivityCheckMethod::SizeStability);
+        assert_eq!(config.stability_secs, 10);
+    }
+}
@@ -0,0 +1,44 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+fn default_sidecar_extensions() -> Vec<String> {
+    ["srt", "ass", "ssa", "sub", "idx", "vtt", "nfo"]
+        .map(String::from)
+        .to_vec()
+}
+
+/// Sidecar files that move together with their media file
+///
+/// A sidecar belongs to the file in the same directory whose name it extends:
+/// `Movie.en.srt` and `Movie.nfo` follow `Movie.mkv`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AffinityConfig {
+    /// Extensions of sidecar files, without the dot
+    #[serde(default = "default_sidecar_extensions")]
+    pub sidecar_extensions: Vec<String>,
+}
+
+impl Default for AffinityConfig {
+    fn default() -> Self {
+        Self {
+            sidecar_extensions: default_sidecar_extensions(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_affinity_config() {
+        let config: AffinityConfig = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(config, AffinityConfig::default());
+        assert!(config.sidecar_extensions.contains(&"srt".to_string()));
+
+        let config: AffinityConfig =
+            serde_yaml::from_str("sidecar_extensions: [srt, jpg]").unwrap();
+        assert_eq!(config.sidecar_extensions, vec!["srt", "jpg"]);
+    }
+}
@@ -0,0 +1,22 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::net::SocketAddr;
+
+/// HTTP control API, served in daemon mode
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ApiConfig {
+    /// Address to listen on (e.g., "127.0.0.1:9465")
+    pub listen_addr: SocketAddr,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_api_config() {
+        let config: ApiConfig = serde_yaml::from_str("listen_addr: 127.0.0.1:9465").unwrap();
+        assert_eq!(config.listen_addr.port(), 9465);
+    }
+}
@@ -0,0 +1,40 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// Sonarr/Radarr ("*arr") integrations
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ArrConfig {
+    /// Sonarr instance for `sonarr_*` conditions
+    #[serde(default)]
+    pub sonarr: Option<SonarrConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SonarrConfig {
+    /// Base URL of the Sonarr instance (e.g., "<http://localhost:8989>")
+    pub url: String,
+
+    /// API key (Settings -> General -> Security)
+    pub api_key: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_arr_config() {
+        let yaml = r#"
+sonarr:
+  url: "http://localhost:8989"
+  api_key: "sonarr-key"
+"#;
+
+        let config: ArrConfig = serde_yaml::from_str(yaml).expect("Should parse");
+        let sonarr = config.sonarr.expect("Sonarr should be configured");
+        assert_eq!(sonarr.url, "http://localhost:8989");
+        assert_eq!(sonarr.api_key, "sonarr-key");
+    }
+}
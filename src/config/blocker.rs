@@ -1,7 +1,8 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct BlockersConfig {
     #[serde(default)]
@@ -11,7 +12,7 @@ pub struct BlockersConfig {
     pub providers: Vec<BlockerProviderConfig>,
 }
 
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum BlockerErrorPolicyConfig {
     #[default]
@@ -19,14 +20,14 @@ pub enum BlockerErrorPolicyConfig {
     FailOpen,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 #[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
 pub enum BlockerProviderConfig {
     Tdarr(TdarrBlockerConfig),
 }
 
 #[allow(clippy::struct_excessive_bools)]
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct TdarrBlockerConfig {
     pub url: String,
@@ -50,7 +51,7 @@ pub struct TdarrBlockerConfig {
     pub path_mappings: Vec<PathMappingConfig>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct PathMappingConfig {
     pub host_prefix: PathBuf,
@@ -0,0 +1,116 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::fmt;
+
+/// Byte count given as an integer or a string with a unit: `700MiB`, `1.5GB`, `200KB`
+///
+/// SI units (`KB`, `MB`, `GB`, `TB`) are powers of 1000, binary units (`KiB`, `MiB`,
+/// `GiB`, `TiB`) powers of 1024. Units are case-insensitive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, JsonSchema)]
+#[serde(try_from = "ByteSizeInput")]
+pub struct ByteSize(pub u64);
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum ByteSizeInput {
+    Bytes(u64),
+    Text(String),
+}
+
+impl TryFrom<ByteSizeInput> for ByteSize {
+    type Error = String;
+
+    fn try_from(input: ByteSizeInput) -> Result<Self, Self::Error> {
+        match input {
+            ByteSizeInput::Bytes(bytes) => Ok(Self(bytes)),
+            ByteSizeInput::Text(text) => text.parse(),
+        }
+    }
+}
+
+impl std::str::FromStr for ByteSize {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid size '{value}', expected e.g. 1048576, 700MiB or 1.5GB");
+        let trimmed = value.trim();
+        let split = trimmed
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(trimmed.len());
+        let (number, unit) = trimmed.split_at(split);
+        let multiplier: u128 = match unit.trim().to_ascii_lowercase().as_str() {
+            "" | "b" => 1,
+            "k" | "kb" => 1000,
+            "m" | "mb" => 1000_u128.pow(2),
+            "g" | "gb" => 1000_u128.pow(3),
+            "t" | "tb" => 1000_u128.pow(4),
+            "kib" => 1 << 10,
+            "mib" => 1 << 20,
+            "gib" => 1 << 30,
+            "tib" => 1 << 40,
+            _ => return Err(invalid()),
+        };
+
+        // Whole and fractional parts apart, so `1.5GB` is exact
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+        if whole.is_empty() && fraction.is_empty() {
+            return Err(invalid());
+        }
+        let whole: u128 = if whole.is_empty() {
+            0
+        } else {
+            whole.parse().map_err(|_| invalid())?
+        };
+        let fraction_bytes = if fraction.is_empty() {
+            0
+        } else {
+            let digits = u32::try_from(fraction.len()).map_err(|_| invalid())?;
+            let scale = 10_u128.checked_pow(digits).ok_or_else(invalid)?;
+            let fraction: u128 = fraction.parse().map_err(|_| invalid())?;
+            fraction * multiplier / scale
+        };
+
+        whole
+            .checked_mul(multiplier)
+            .and_then(|bytes| bytes.checked_add(fraction_bytes))
+            .and_then(|bytes| u64::try_from(bytes).ok())
+            .map(Self)
+            .ok_or_else(invalid)
+    }
+}
+
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bytes", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_units() {
+        let parse = |s: &str| s.parse::<ByteSize>().unwrap().0;
+        assert_eq!(parse("1048576"), 1_048_576);
+        assert_eq!(parse("700MiB"), 700 * 1024 * 1024);
+        assert_eq!(parse("1.5GB"), 1_500_000_000);
+        assert_eq!(parse("200 KB"), 200_000);
+        assert_eq!(parse("2gib"), 2 * 1024 * 1024 * 1024);
+        assert_eq!(parse("0.5KiB"), 512);
+        assert_eq!(parse("12B"), 12);
+
+        for bad in ["", "MB", "1.5.2GB", "10 parsecs", "-5MB", "99999999999TB"] {
+            assert!(bad.parse::<ByteSize>().is_err(), "{bad} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_deserialize_number_or_string() {
+        let sizes: Vec<ByteSize> = serde_yaml::from_str("[1024, \"1KiB\", 1.5MB]").unwrap();
+        assert_eq!(
+            sizes,
+            vec![ByteSize(1024), ByteSize(1024), ByteSize(1_500_000)]
+        );
+    }
+}
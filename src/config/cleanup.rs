@@ -0,0 +1,45 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::time::Duration;
+
+const fn default_retention_hours() -> u64 {
+    7 * 24
+}
+
+/// Removal of `.partial` and `.backup-*` files left behind by failed runs
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CleanupConfig {
+    /// How old a leftover file must be before it is removed
+    #[serde(default = "default_retention_hours")]
+    pub retention_hours: u64,
+}
+
+impl Default for CleanupConfig {
+    fn default() -> Self {
+        Self {
+            retention_hours: default_retention_hours(),
+        }
+    }
+}
+
+impl CleanupConfig {
+    pub const fn retention(&self) -> Duration {
+        Duration::from_secs(self.retention_hours * 3600)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_cleanup_config() {
+        let config: CleanupConfig = serde_yaml::from_str("retention_hours: 48").unwrap();
+        assert_eq!(config.retention(), Duration::from_secs(48 * 3600));
+
+        let config: CleanupConfig = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(config, CleanupConfig::default());
+        assert_eq!(config.retention_hours, 168);
+    }
+}
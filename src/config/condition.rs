@@ -1,7 +1,13 @@
-use crate::{ContainsMode, ExtensionMode, PrefixMode};
+use super::ByteSize;
+use crate::{ContainsMode, ExtensionMode, GlobMode, OwnerMode, PrefixMode};
+use schemars::JsonSchema;
 use serde::Deserialize;
+use std::collections::BTreeMap;
 
-#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+/// Named extension sets referenced from `file_extension` conditions via `group`
+pub type ExtensionGroups = BTreeMap<String, Vec<String>>;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ExtensionModeConfig {
     Whitelist,
@@ -17,7 +23,7 @@ impl From<ExtensionModeConfig> for ExtensionMode {
     }
 }
 
-#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum PrefixModeConfig {
     Whitelist,
@@ -33,7 +39,47 @@ impl From<PrefixModeConfig> for PrefixMode {
     }
 }
 
-#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum GlobModeConfig {
+    Whitelist,
+    Blacklist,
+}
+
+impl From<GlobModeConfig> for GlobMode {
+    fn from(config: GlobModeConfig) -> Self {
+        match config {
+            GlobModeConfig::Whitelist => Self::Whitelist,
+            GlobModeConfig::Blacklist => Self::Blacklist,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OwnerModeConfig {
+    Whitelist,
+    Blacklist,
+}
+
+impl From<OwnerModeConfig> for OwnerMode {
+    fn from(config: OwnerModeConfig) -> Self {
+        match config {
+            OwnerModeConfig::Whitelist => Self::Whitelist,
+            OwnerModeConfig::Blacklist => Self::Blacklist,
+        }
+    }
+}
+
+/// User or group, by numeric id or by name
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(untagged)]
+pub enum AccountConfig {
+    Id(u32),
+    Name(String),
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ContainsModeConfig {
     Whitelist,
@@ -49,7 +95,7 @@ impl From<ContainsModeConfig> for ContainsMode {
     }
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Clone, PartialEq, JsonSchema)]
 #[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
 pub enum ConditionConfig {
     Age {
@@ -58,16 +104,77 @@ pub enum ConditionConfig {
         #[serde(skip_serializing_if = "Option::is_none")]
         max_hours: Option<u64>,
     },
+    /// Time since tierflow first saw the file, regardless of its mtime
+    SeenAge {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        min_hours: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max_hours: Option<u64>,
+    },
+    /// Total size of the file's directory
+    DirectorySize {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        min_gb: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max_gb: Option<u64>,
+    },
+    /// Number of files in the file's directory
+    DirectoryFileCount {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        min: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max: Option<usize>,
+    },
+    /// Age of the newest file in the file's directory
+    NewestInDirectoryAge {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        min_hours: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max_hours: Option<u64>,
+    },
     AlwaysTrue,
+    /// `extensions` and the extensions of the named `group` are combined
     FileExtension {
+        #[serde(default)]
         extensions: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        group: Option<String>,
         mode: ExtensionModeConfig,
     },
     PathPrefix {
         prefix: String,
         mode: PrefixModeConfig,
     },
+    /// Files owned by one of `users` (Unix uid)
+    Owner {
+        users: Vec<AccountConfig>,
+        mode: OwnerModeConfig,
+    },
+    /// Files whose group is one of `groups` (Unix gid)
+    Group {
+        groups: Vec<AccountConfig>,
+        mode: OwnerModeConfig,
+    },
+    /// Usage of `tier` (0-100), as simulated while planning
+    TierUsage {
+        tier: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        min_percent: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max_percent: Option<u64>,
+    },
+    /// Glob patterns against the tier-relative path: `*/Season */`, `**/extras/**`
+    PathGlob {
+        patterns: Vec<String>,
+        mode: GlobModeConfig,
+    },
+    /// File size bounds; `min_size`/`max_size` take bytes or strings like `700MiB`,
+    /// `min_size_mb`/`max_size_mb` whole MiB
     FileSize {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        min_size: Option<ByteSize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max_size: Option<ByteSize>,
         #[serde(skip_serializing_if = "Option::is_none")]
         min_size_mb: Option<u64>,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -82,6 +189,25 @@ pub enum ConditionConfig {
     ActiveWindow {
         name: String,
     },
+    /// Movies watched or in progress within the last `days`
+    RecentlyWatchedMovie {
+        days: u32,
+    },
+    /// Episodes of seasons monitored in Sonarr
+    SonarrMonitored,
+    /// Episodes of series with a new episode airing within `days`
+    SonarrAiringWithin {
+        days: u32,
+    },
+    /// Files of torrents the torrent client is seeding
+    TorrentSeeding,
+    /// Share ratio of the file's torrents; files in no torrent never match
+    TorrentRatio {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        min: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max: Option<f64>,
+    },
 }
 
 const fn default_true() -> bool {
@@ -102,6 +228,7 @@ mod tests {
             size: 1000,
             modified,
             accessed: SystemTime::now(),
+            owner: None,
         }
     }
 
@@ -121,6 +248,99 @@ min_hours: 168
         );
     }
 
+    #[test]
+    fn test_deserialize_seen_age() {
+        let yaml = r"
+type: seen_age
+min_hours: 336
+";
+        let config: ConditionConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            config,
+            ConditionConfig::SeenAge {
+                min_hours: Some(336),
+                max_hours: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_deserialize_directory_conditions() {
+        let yaml = r"
+- type: directory_size
+  min_gb: 50
+- type: directory_file_count
+  max: 3
+- type: newest_in_directory_age
+  max_hours: 168
+";
+        let config: Vec<ConditionConfig> = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            config,
+            vec![
+                ConditionConfig::DirectorySize {
+                    min_gb: Some(50),
+                    max_gb: None
+                },
+                ConditionConfig::DirectoryFileCount {
+                    min: None,
+                    max: Some(3)
+                },
+                ConditionConfig::NewestInDirectoryAge {
+                    min_hours: None,
+                    max_hours: Some(168)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deserialize_recently_watched_movie() {
+        let yaml = r"
+type: recently_watched_movie
+days: 14
+";
+        let config: ConditionConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config, ConditionConfig::RecentlyWatchedMovie { days: 14 });
+    }
+
+    #[test]
+    fn test_deserialize_torrent_conditions() {
+        let yaml = r"
+- type: torrent_seeding
+- type: torrent_ratio
+  min: 1.5
+";
+        let config: Vec<ConditionConfig> = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            config,
+            vec![
+                ConditionConfig::TorrentSeeding,
+                ConditionConfig::TorrentRatio {
+                    min: Some(1.5),
+                    max: None
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deserialize_sonarr_conditions() {
+        let yaml = r"
+- type: sonarr_monitored
+- type: sonarr_airing_within
+  days: 3
+";
+        let config: Vec<ConditionConfig> = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            config,
+            vec![
+                ConditionConfig::SonarrMonitored,
+                ConditionConfig::SonarrAiringWithin { days: 3 }
+            ]
+        );
+    }
+
     #[test]
     fn test_deserialize_always_true() {
         let yaml = r"
@@ -173,7 +393,7 @@ type: unknown_condition
             min_hours: Some(24),
             max_hours: None,
         };
-        let condition = factory::build_condition(config);
+        let condition = factory::build_condition(config, &ExtensionGroups::new()).unwrap();
         let context = Context::new();
 
         // Старый файл должен матчиться
@@ -185,10 +405,96 @@ type: unknown_condition
         assert!(!condition.matches(&new_file, &context));
     }
 
+    #[test]
+    fn test_deserialize_path_glob() {
+        let yaml = r#"
+type: path_glob
+patterns: ["**/extras/**", "*/Season */"]
+mode: blacklist
+"#;
+        let config: ConditionConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            config,
+            ConditionConfig::PathGlob {
+                patterns: vec!["**/extras/**".to_string(), "*/Season */".to_string()],
+                mode: GlobModeConfig::Blacklist,
+            }
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_into_condition_owner() {
+        let yaml = r"
+- type: owner
+  users: [root, 1000]
+  mode: whitelist
+- type: group
+  groups: [1000]
+  mode: blacklist
+- type: owner
+  users: [no-such-user-tierflow]
+  mode: whitelist
+";
+        let mut configs: Vec<ConditionConfig> = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            configs[0],
+            ConditionConfig::Owner {
+                users: vec![
+                    AccountConfig::Name("root".to_string()),
+                    AccountConfig::Id(1000)
+                ],
+                mode: OwnerModeConfig::Whitelist,
+            }
+        );
+
+        let unknown = factory::build_condition(configs.pop().unwrap(), &ExtensionGroups::new());
+        assert_eq!(
+            unknown.err().as_deref(),
+            Some("unknown user: no-such-user-tierflow")
+        );
+
+        let file = crate::FileInfo {
+            owner: Some(crate::FileOwner { uid: 0, gid: 0 }),
+            ..create_test_file(0)
+        };
+        let context = Context::new();
+        for config in configs {
+            let condition = factory::build_condition(config, &ExtensionGroups::new()).unwrap();
+            assert!(condition.matches(&file, &context));
+        }
+    }
+
+    #[test]
+    fn test_into_condition_file_size_units() {
+        let yaml = r"
+- type: file_size
+  min_size: 1KB
+- type: file_size
+  min_size: 1KiB
+- type: file_size
+  min_size: 1000
+  max_size_mb: 1
+";
+        let configs: Vec<ConditionConfig> = serde_yaml::from_str(yaml).unwrap();
+        let context = Context::new();
+        // Тестовый файл ровно 1000 байт
+        let file = create_test_file(0);
+        let matches: Vec<bool> = configs
+            .into_iter()
+            .map(|config| {
+                factory::build_condition(config, &ExtensionGroups::new())
+                    .unwrap()
+                    .matches(&file, &context)
+            })
+            .collect();
+        assert_eq!(matches, vec![true, false, true]);
+    }
+
     #[test]
     fn test_into_condition_always_true() {
         let config = ConditionConfig::AlwaysTrue;
-        let condition = factory::build_condition(config);
+        let condition = factory::build_condition(config, &ExtensionGroups::new()).unwrap();
         let context = Context::new();
 
         let file = create_test_file(0);
@@ -222,8 +528,10 @@ type: unknown_condition
             ConditionConfig::AlwaysTrue,
         ];
 
-        let conditions: Vec<Box<dyn Condition>> =
-            configs.into_iter().map(factory::build_condition).collect();
+        let conditions: Vec<Box<dyn Condition>> = configs
+            .into_iter()
+            .map(|config| factory::build_condition(config, &ExtensionGroups::new()).unwrap())
+            .collect();
 
         assert_eq!(conditions.len(), 2);
 
@@ -247,6 +555,7 @@ mode: whitelist
             config,
             ConditionConfig::FileExtension {
                 extensions: vec!["mkv".to_string(), "mp4".to_string(), "avi".to_string()],
+                group: None,
                 mode: ExtensionModeConfig::Whitelist,
             }
         );
@@ -264,6 +573,7 @@ mode: whitelist
             config,
             ConditionConfig::FileExtension {
                 extensions: vec!["!qB".to_string()],
+                group: None,
                 mode: ExtensionModeConfig::Whitelist,
             }
         );
@@ -273,9 +583,10 @@ mode: whitelist
     fn test_into_condition_file_extension() {
         let config = ConditionConfig::FileExtension {
             extensions: vec!["mkv".to_string(), "!qB".to_string()],
+            group: None,
             mode: ExtensionModeConfig::Whitelist,
         };
-        let condition = factory::build_condition(config);
+        let condition = factory::build_condition(config, &ExtensionGroups::new()).unwrap();
         let context = Context::new();
 
         // MKV файл должен матчиться
@@ -284,6 +595,7 @@ mode: whitelist
             size: 1000,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         };
         assert!(condition.matches(&mkv_file, &context));
 
@@ -293,6 +605,7 @@ mode: whitelist
             size: 1000,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         };
         assert!(condition.matches(&incomplete_file, &context));
 
@@ -302,14 +615,54 @@ mode: whitelist
             size: 1000,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         };
         assert!(!condition.matches(&mp4_file, &context));
     }
 
+    #[test]
+    fn test_into_condition_file_extension_group() {
+        let yaml = r"
+type: file_extension
+group: video
+extensions: [srt]
+mode: whitelist
+";
+        let config: ConditionConfig = serde_yaml::from_str(yaml).unwrap();
+        let groups = ExtensionGroups::from([(
+            "video".to_string(),
+            vec!["mkv".to_string(), "mp4".to_string()],
+        )]);
+        let condition = factory::build_condition(config.clone(), &groups).unwrap();
+        let context = Context::new();
+
+        for (path, expected) in [
+            ("/test/movie.mp4", true),
+            ("/test/movie.en.srt", true),
+            ("/test/movie.nfo", false),
+        ] {
+            let file = crate::FileInfo {
+                path: PathBuf::from(path),
+                size: 1000,
+                modified: SystemTime::now(),
+                accessed: SystemTime::now(),
+                owner: None,
+            };
+            assert_eq!(condition.matches(&file, &context), expected, "{path}");
+        }
+
+        let unknown = factory::build_condition(config, &ExtensionGroups::new());
+        assert_eq!(
+            unknown.err().as_deref(),
+            Some("unknown extension group: video")
+        );
+    }
+
     #[test]
     fn test_file_extension_config_clone() {
         let config = ConditionConfig::FileExtension {
             extensions: vec!["mkv".to_string(), "mp4".to_string()],
+            group: None,
             mode: ExtensionModeConfig::Whitelist,
         };
         let cloned = config.clone();
@@ -328,6 +681,7 @@ mode: blacklist
             config,
             ConditionConfig::FileExtension {
                 extensions: vec!["!qB".to_string(), "part".to_string(), "tmp".to_string()],
+                group: None,
                 mode: ExtensionModeConfig::Blacklist,
             }
         );
@@ -345,6 +699,7 @@ mode: whitelist
             config,
             ConditionConfig::FileExtension {
                 extensions: vec!["mkv".to_string(), "mp4".to_string()],
+                group: None,
                 mode: ExtensionModeConfig::Whitelist,
             }
         );
@@ -354,9 +709,10 @@ mode: whitelist
     fn test_into_condition_file_extension_blacklist() {
         let config = ConditionConfig::FileExtension {
             extensions: vec!["!qB".to_string(), "part".to_string()],
+            group: None,
             mode: ExtensionModeConfig::Blacklist,
         };
-        let condition = factory::build_condition(config);
+        let condition = factory::build_condition(config, &ExtensionGroups::new()).unwrap();
         let context = Context::new();
 
         // MKV файл НЕ в blacklist → должен матчиться
@@ -365,6 +721,7 @@ mode: whitelist
             size: 1000,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         };
         assert!(condition.matches(&mkv_file, &context));
 
@@ -374,6 +731,7 @@ mode: whitelist
             size: 1000,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         };
         assert!(!condition.matches(&incomplete_file, &context));
     }
@@ -418,7 +776,7 @@ mode: whitelist
             prefix: "downloads".to_string(),
             mode: PrefixModeConfig::Whitelist,
         };
-        let condition = factory::build_condition(config);
+        let condition = factory::build_condition(config, &ExtensionGroups::new()).unwrap();
         let context = Context::new().with_tier_path(PathBuf::from("/mnt/cache"));
 
         // Файл в downloads должен матчиться
@@ -427,6 +785,7 @@ mode: whitelist
             size: 1000,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         };
         assert!(condition.matches(&downloads_file, &context));
 
@@ -436,6 +795,7 @@ mode: whitelist
             size: 1000,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         };
         assert!(!condition.matches(&other_file, &context));
     }
@@ -490,7 +850,7 @@ mode: whitelist
             prefix: "downloads".to_string(),
             mode: PrefixModeConfig::Blacklist,
         };
-        let condition = factory::build_condition(config);
+        let condition = factory::build_condition(config, &ExtensionGroups::new()).unwrap();
         let context = Context::new().with_tier_path(PathBuf::from("/mnt/cache"));
 
         // Файл НЕ в downloads → должен матчиться
@@ -499,6 +859,7 @@ mode: whitelist
             size: 1000,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         };
         assert!(condition.matches(&series_file, &context));
 
@@ -508,6 +869,7 @@ mode: whitelist
             size: 1000,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         };
         assert!(!condition.matches(&downloads_file, &context));
     }
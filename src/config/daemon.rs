@@ -0,0 +1,307 @@
+use super::{TimeOfDay, UtcOffset};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use xxhash_rust::xxh3::xxh3_64_with_seed;
+
+const SECONDS_PER_DAY: u64 = 24 * 3600;
+
+/// Daemon run scheduling
+///
+/// Without `run_at` runs start every `--interval` seconds, counted from the start of
+/// the previous run. Scheduled runs that pass while a run is still executing are skipped.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DaemonConfig {
+    /// Times of day to run at, replacing the interval
+    #[serde(default)]
+    pub run_at: Vec<TimeOfDay>,
+
+    /// Offset of the clock `run_at` times are given in; daylight saving is not followed
+    #[serde(default)]
+    pub utc_offset: UtcOffset,
+
+    /// Each run starts up to this many seconds after its scheduled time
+    #[serde(default)]
+    pub interval_jitter_seconds: u64,
+
+    /// Reread moved files after runs and compare them with their recorded checksums
+    #[serde(default)]
+    pub scrub: Option<ScrubConfig>,
+
+    /// Plan changed directories on filesystem events between scheduled runs
+    #[serde(default)]
+    pub watch: Option<WatchConfig>,
+}
+
+const fn default_watch_debounce_seconds() -> u64 {
+    60
+}
+
+/// Reactive mode: inotify events on the tiers trigger incremental runs
+///
+/// Scheduled runs still plan everything and catch changes the events missed.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WatchConfig {
+    /// Quiet time after the last event before the changed directories are planned
+    #[serde(default = "default_watch_debounce_seconds")]
+    pub debounce_seconds: u64,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            debounce_seconds: default_watch_debounce_seconds(),
+        }
+    }
+}
+
+impl WatchConfig {
+    pub const fn debounce(&self) -> Duration {
+        Duration::from_secs(self.debounce_seconds)
+    }
+}
+
+const fn default_scrub_interval_hours() -> u64 {
+    7 * 24
+}
+
+const fn default_scrub_sample_percent() -> u8 {
+    100
+}
+
+/// Daemon scrub cycle
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ScrubConfig {
+    /// Minimum time between scrubs; the first one follows the daemon's first run
+    #[serde(default = "default_scrub_interval_hours")]
+    pub interval_hours: u64,
+
+    /// Share of the files with a recorded checksum reread per scrub, 1-100
+    #[serde(default = "default_scrub_sample_percent")]
+    pub sample_percent: u8,
+}
+
+impl Default for ScrubConfig {
+    fn default() -> Self {
+        Self {
+            interval_hours: default_scrub_interval_hours(),
+            sample_percent: default_scrub_sample_percent(),
+        }
+    }
+}
+
+impl ScrubConfig {
+    pub const fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_hours * 3600)
+    }
+}
+
+/// When the next daemon run is due
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NextRun {
+    /// Scheduled time, before jitter
+    pub at: SystemTime,
+    /// Scheduled runs that passed while the previous run was executing
+    pub skipped: u64,
+}
+
+impl DaemonConfig {
+    /// Next run after one scheduled at `previous`, or the first run when `None`
+    pub fn next_run(
+        &self,
+        interval: Duration,
+        previous: Option<SystemTime>,
+        now: SystemTime,
+    ) -> NextRun {
+        let step = |at: SystemTime| {
+            if self.run_at.is_empty() {
+                at + interval.max(Duration::from_secs(1))
+            } else {
+                self.next_run_at(at)
+            }
+        };
+        let Some(previous) = previous else {
+            return NextRun {
+                at: if self.run_at.is_empty() {
+                    now
+                } else {
+                    self.next_run_at(now)
+                },
+                skipped: 0,
+            };
+        };
+
+        let mut next = NextRun {
+            at: step(previous),
+            skipped: 0,
+        };
+        while next.at <= now {
+            next.at = step(next.at);
+            next.skipped += 1;
+        }
+        next
+    }
+
+    /// Start delay for the run scheduled at `at`, up to `interval_jitter_seconds`
+    ///
+    /// Random unless `seed` is set; a seed gives the same delay for the same slot.
+    pub fn jitter(&self, seed: Option<u64>, at: SystemTime) -> Duration {
+        if self.interval_jitter_seconds == 0 {
+            return Duration::ZERO;
+        }
+        let random = seed.map_or_else(
+            || RandomState::new().build_hasher().finish(),
+            |seed| {
+                let slot = at.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+                xxh3_64_with_seed(&slot.to_le_bytes(), seed)
+            },
+        );
+        Duration::from_secs(random % (self.interval_jitter_seconds + 1))
+    }
+
+    /// First `run_at` time strictly after `after`
+    fn next_run_at(&self, after: SystemTime) -> SystemTime {
+        let offset = i64::from(self.utc_offset.0) * 60;
+        let local = after
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64)
+            + offset;
+        let day_start = local - local.rem_euclid(SECONDS_PER_DAY as i64);
+
+        let next_local = [day_start, day_start + SECONDS_PER_DAY as i64]
+            .into_iter()
+            .flat_map(|day| self.run_at.iter().map(move |t| day + i64::from(t.0) * 60))
+            .filter(|&at| at > local)
+            .min()
+            .unwrap_or(local + SECONDS_PER_DAY as i64);
+
+        UNIX_EPOCH + Duration::from_secs((next_local - offset).max(0) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Monday 2024-01-15 at `hh:mm` UTC
+    fn monday_at(hours: u64, minutes: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_705_276_800 + hours * 3600 + minutes * 60)
+    }
+
+    #[test]
+    fn test_interval_runs_skip_overrun_slots() {
+        let config = DaemonConfig::default();
+        let hour = Duration::from_secs(3600);
+
+        let first = config.next_run(hour, None, monday_at(1, 0));
+        assert_eq!(first.at, monday_at(1, 0));
+
+        // Run finished quickly: next one an interval after it started
+        let next = config.next_run(hour, Some(first.at), monday_at(1, 10));
+        assert_eq!(
+            next,
+            NextRun {
+                at: monday_at(2, 0),
+                skipped: 0
+            }
+        );
+
+        // Run took 2.5 hours: the 02:00 and 03:00 runs are skipped
+        let next = config.next_run(hour, Some(first.at), monday_at(3, 30));
+        assert_eq!(
+            next,
+            NextRun {
+                at: monday_at(4, 0),
+                skipped: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_run_at_times() {
+        let config: DaemonConfig =
+            serde_yaml::from_str("run_at: [\"03:00\", \"15:30\"]\nutc_offset: \"+02:00\"").unwrap();
+        let interval = Duration::from_secs(60);
+
+        // 03:00 at UTC+02:00 is 01:00 UTC
+        let first = config.next_run(interval, None, monday_at(0, 0));
+        assert_eq!(first.at, monday_at(1, 0));
+
+        let next = config.next_run(interval, Some(first.at), monday_at(1, 20));
+        assert_eq!(
+            next,
+            NextRun {
+                at: monday_at(13, 30),
+                skipped: 0
+            }
+        );
+
+        // Previous run overran 13:30 UTC, next is tomorrow's 01:00 UTC
+        let next = config.next_run(interval, Some(first.at), monday_at(14, 0));
+        assert_eq!(
+            next,
+            NextRun {
+                at: monday_at(25, 0),
+                skipped: 1
+            }
+        );
+
+        assert!(serde_yaml::from_str::<DaemonConfig>("run_at: [\"3am\"]").is_err());
+    }
+
+    #[test]
+    fn test_deserialize_scrub_config() {
+        let config: DaemonConfig =
+            serde_yaml::from_str("scrub:\n  interval_hours: 24\n  sample_percent: 5").unwrap();
+        let scrub = config.scrub.unwrap();
+        assert_eq!(scrub.interval(), Duration::from_secs(24 * 3600));
+        assert_eq!(scrub.sample_percent, 5);
+
+        let config: DaemonConfig = serde_yaml::from_str("scrub: {}").unwrap();
+        assert_eq!(config.scrub, Some(ScrubConfig::default()));
+    }
+
+    #[test]
+    fn test_deserialize_watch_config() {
+        let config: DaemonConfig = serde_yaml::from_str("watch: {}").unwrap();
+        assert_eq!(config.watch.unwrap().debounce(), Duration::from_secs(60));
+        assert!(DaemonConfig::default().watch.is_none());
+    }
+
+    #[test]
+    fn test_jitter_stays_in_range() {
+        let now = SystemTime::now();
+        assert_eq!(DaemonConfig::default().jitter(None, now), Duration::ZERO);
+        let config = DaemonConfig {
+            interval_jitter_seconds: 5,
+            ..DaemonConfig::default()
+        };
+        assert!((0..20).all(|_| config.jitter(None, now) <= Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_seeded_jitter_is_reproducible() {
+        let config = DaemonConfig {
+            interval_jitter_seconds: 3600,
+            ..DaemonConfig::default()
+        };
+        let slots: Vec<SystemTime> = (0..20)
+            .map(|hour| UNIX_EPOCH + Duration::from_secs(1_700_000_000 + hour * 3600))
+            .collect();
+        let delays = |seed| -> Vec<Duration> {
+            slots
+                .iter()
+                .map(|&at| config.jitter(Some(seed), at))
+                .collect()
+        };
+
+        assert_eq!(delays(42), delays(42));
+        assert_ne!(delays(42), delays(43));
+        assert!(delays(42).iter().all(|d| *d <= Duration::from_secs(3600)));
+    }
+}
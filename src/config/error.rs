@@ -10,18 +10,79 @@ pub enum ConfigError {
     #[error("Failed to parse YAML: {0}")]
     Parse(#[from] serde_yaml::Error),
 
+    #[error("Failed to parse TOML: {0}")]
+    ParseToml(#[from] toml::de::Error),
+
+    #[error("Failed to parse JSON: {0}")]
+    ParseJson(#[from] serde_json::Error),
+
+    #[error("Unresolved config variables: ${{{}}}", .names.join("}, ${"))]
+    UnresolvedVariables { names: Vec<String> },
+
+    #[error("Failed to load secrets file '{path}': {reason}")]
+    SecretsFile { path: PathBuf, reason: String },
+
     #[error("Invalid tier path '{path}': {reason}")]
     InvalidTierPath { path: PathBuf, reason: String },
 
+    #[error("Invalid capacity_override_bytes for tier '{tier}': must be greater than 0")]
+    InvalidTierCapacity { tier: String },
+
+    #[error("Invalid usage limits for tier '{tier}': {reason}")]
+    InvalidTierUsage { tier: String, reason: String },
+
+    #[error("Invalid safety.trash_dir '{path}': must be a relative path inside the tier")]
+    InvalidTrashDir { path: PathBuf },
+
     #[error("Duplicate tier name: {name}")]
     DuplicateTierName { name: String },
 
+    #[error("Strategy '{strategy}' extends unknown template: {template}")]
+    UnknownStrategyTemplate { strategy: String, template: String },
+
+    #[error("Strategy templates extend each other in a cycle: {chain}")]
+    StrategyTemplateCycle { chain: String },
+
+    #[error("Failed to parse config after resolving variables and templates: {0}")]
+    ParseTemplated(serde_json::Error),
+
     #[error("Duplicate strategy name: {name}")]
     DuplicateStrategyName { name: String },
 
     #[error("Strategy '{strategy}' references unknown tier: {tier}")]
     UnknownTier { strategy: String, tier: String },
 
+    #[error("Strategy '{strategy}' has an invalid condition: {reason}")]
+    InvalidCondition { strategy: String, reason: String },
+
+    #[error("Strategy '{strategy}' sets both {field} and {field}_mb in a file_size condition")]
+    ConflictingSizeBound {
+        strategy: String,
+        field: &'static str,
+    },
+
+    #[error("Move policy '{from}' -> '{to}' references unknown tier: {tier}")]
+    UnknownPolicyTier {
+        from: String,
+        to: String,
+        tier: String,
+    },
+
+    #[error("Invalid verify setting '{field}': must be greater than 0")]
+    InvalidVerifyLimit { field: &'static str },
+
+    #[error("Invalid hooks.timeout_secs: must be greater than 0")]
+    InvalidHookTimeout,
+
+    #[error("Invalid logging.max_size_mb: must be greater than 0")]
+    InvalidLogSize,
+
+    #[error("Invalid daemon.scrub.sample_percent: must be between 1 and 100")]
+    InvalidScrubSample,
+
+    #[error("Invalid {scope} quota '{field}': must be greater than 0")]
+    InvalidMoveQuota { scope: String, field: &'static str },
+
     #[error("No tiers defined in configuration")]
     NoTiers,
 
@@ -31,11 +92,23 @@ pub enum ConfigError {
     #[error("Mover '{mover}' is unavailable: {reason}")]
     MoverUnavailable { mover: String, reason: String },
 
-    #[error("Tautulli is required: {reason}")]
-    TautulliRequired { reason: String },
+    #[error("Watch history is required: {reason}")]
+    WatchHistoryRequired { reason: String },
+
+    #[error("{provider} is unavailable: {reason}")]
+    WatchHistoryUnavailable { provider: String, reason: String },
+
+    #[error("Sonarr is required: {reason}")]
+    SonarrRequired { reason: String },
+
+    #[error("Sonarr is unavailable: {reason}")]
+    SonarrUnavailable { reason: String },
+
+    #[error("Torrent client is required: {reason}")]
+    TorrentClientRequired { reason: String },
 
-    #[error("Tautulli is unavailable: {reason}")]
-    TautulliUnavailable { reason: String },
+    #[error("{client} is unavailable: {reason}")]
+    TorrentClientUnavailable { client: String, reason: String },
 
     #[error("Application error: {0}")]
     App(#[from] crate::AppError),
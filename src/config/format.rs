@@ -0,0 +1,123 @@
+use super::Result;
+use clap::ValueEnum;
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+/// Config file syntax; every format deserializes into the same structures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ConfigFormat {
+    #[default]
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl ConfigFormat {
+    /// Format implied by the file extension, YAML for anything else
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("toml") => Self::Toml,
+            Some("json") => Self::Json,
+            _ => Self::Yaml,
+        }
+    }
+
+    pub fn parse<T: DeserializeOwned>(self, contents: &str) -> Result<T> {
+        Ok(match self {
+            Self::Yaml => serde_yaml::from_str(contents)?,
+            Self::Toml => toml::from_str(contents)?,
+            Self::Json => serde_json::from_str(contents)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BalancingConfig;
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("/etc/tierflow/config.toml")),
+            ConfigFormat::Toml
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("config.JSON")),
+            ConfigFormat::Json
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("config.yml")),
+            ConfigFormat::Yaml
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("config")),
+            ConfigFormat::Yaml
+        );
+    }
+
+    #[test]
+    fn test_all_formats_parse_the_same_config() {
+        let yaml = r"
+tiers:
+  - name: cache
+    path: /mnt/cache
+    priority: 1
+strategies:
+  - name: recent
+    priority: 10
+    conditions:
+      - type: age
+        max_hours: 24
+    preferred_tiers: [cache]
+";
+        let toml = r#"
+[[tiers]]
+name = "cache"
+path = "/mnt/cache"
+priority = 1
+
+[[strategies]]
+name = "recent"
+priority = 10
+preferred_tiers = ["cache"]
+
+[[strategies.conditions]]
+type = "age"
+max_hours = 24
+"#;
+        let json = r#"{
+  "tiers": [{ "name": "cache", "path": "/mnt/cache", "priority": 1 }],
+  "strategies": [{
+    "name": "recent",
+    "priority": 10,
+    "conditions": [{ "type": "age", "max_hours": 24 }],
+    "preferred_tiers": ["cache"]
+  }]
+}"#;
+
+        let parsed: Vec<BalancingConfig> = [
+            (ConfigFormat::Yaml, yaml),
+            (ConfigFormat::Toml, toml),
+            (ConfigFormat::Json, json),
+        ]
+        .into_iter()
+        .map(|(format, contents)| format.parse(contents).unwrap())
+        .collect();
+
+        for config in &parsed {
+            assert_eq!(config.tiers, parsed[0].tiers);
+            assert_eq!(config.strategies, parsed[0].strategies);
+        }
+        assert!(
+            ConfigFormat::Toml
+                .parse::<BalancingConfig>("tiers = 1")
+                .is_err()
+        );
+    }
+}
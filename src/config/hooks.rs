@@ -0,0 +1,46 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+const fn default_timeout_secs() -> u64 {
+    60
+}
+
+/// Shell commands run after moves and after each run
+///
+/// `{placeholder}`s are replaced with shell-quoted values, which are also exported
+/// as `TIERFLOW_<PLACEHOLDER>` environment variables.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct HooksConfig {
+    /// Run after every successful move: `{source}`, `{destination}`, `{source_dir}`,
+    /// `{destination_dir}`, `{from_tier}`, `{to_tier}`, `{size}`
+    #[serde(default)]
+    pub post_move: Vec<String>,
+
+    /// Run once after a run that moved files: `{files_moved}`, `{bytes_moved}`, `{errors}`
+    #[serde(default)]
+    pub post_run: Vec<String>,
+
+    /// Hooks still running after this long are killed
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_hooks_config() {
+        let yaml = r#"
+post_move:
+  - "curl -s http://plex:32400/refresh?path={destination_dir}"
+post_run:
+  - /usr/local/bin/tdarr-scan.sh
+"#;
+        let config: HooksConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.post_move.len(), 1);
+        assert_eq!(config.post_run, vec!["/usr/local/bin/tdarr-scan.sh"]);
+        assert_eq!(config.timeout_secs, 60);
+    }
+}
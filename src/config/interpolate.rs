@@ -0,0 +1,199 @@
+use super::{ConfigError, ConfigFormat, Result};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Replaces every `${NAME}` in the string values of a parsed config
+///
+/// Values come from the environment, then from `secrets_file`. Working on the
+/// parsed document keeps values with quotes, `: ` or newlines intact and leaves
+/// comments out. `$${` is a literal `${`. Returns whether any value changed.
+pub fn interpolate_config(path: &Path, document: &mut Value) -> Result<bool> {
+    let secrets = match secrets_file(path, document)? {
+        Some(secrets_path) => load_secrets(&secrets_path)?,
+        None => BTreeMap::new(),
+    };
+
+    let mut missing = Vec::new();
+    let changed = interpolate_strings(
+        document,
+        &|name| env_var(name).or_else(|| secrets.get(name).cloned()),
+        &mut missing,
+    );
+    if missing.is_empty() {
+        Ok(changed)
+    } else {
+        Err(ConfigError::UnresolvedVariables { names: missing })
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+/// Path of the secrets file, relative to the config's directory
+///
+/// The path itself may only use environment variables.
+fn secrets_file(path: &Path, document: &Value) -> Result<Option<PathBuf>> {
+    let Some(file) = document.get("secrets_file").and_then(Value::as_str) else {
+        return Ok(None);
+    };
+    let file = PathBuf::from(
+        interpolate(file, env_var).map_err(|names| ConfigError::UnresolvedVariables { names })?,
+    );
+    Ok(Some(match path.parent() {
+        Some(dir) if file.is_relative() => dir.join(file),
+        _ => file,
+    }))
+}
+
+/// Flat `NAME: value` map, in the format implied by the extension
+fn load_secrets(path: &Path) -> Result<BTreeMap<String, String>> {
+    let error = |reason: String| ConfigError::SecretsFile {
+        path: path.to_path_buf(),
+        reason,
+    };
+    let contents = fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+    ConfigFormat::from_path(path)
+        .parse(&contents)
+        .map_err(|e| error(e.to_string()))
+}
+
+/// Interpolates every string in `value`, collecting unresolved names into `missing`
+fn interpolate_strings(
+    value: &mut Value,
+    lookup: &impl Fn(&str) -> Option<String>,
+    missing: &mut Vec<String>,
+) -> bool {
+    match value {
+        Value::String(text) => match interpolate(text, lookup) {
+            Ok(interpolated) if interpolated != *text => {
+                *text = interpolated;
+                true
+            }
+            Ok(_) => false,
+            Err(names) => {
+                for name in names {
+                    if !missing.contains(&name) {
+                        missing.push(name);
+                    }
+                }
+                false
+            }
+        },
+        Value::Array(items) => items.iter_mut().fold(false, |changed, item| {
+            interpolate_strings(item, lookup, missing) | changed
+        }),
+        Value::Object(map) => map.values_mut().fold(false, |changed, item| {
+            interpolate_strings(item, lookup, missing) | changed
+        }),
+        _ => false,
+    }
+}
+
+/// Substitutes `${NAME}` using `lookup`; on failure returns the unresolved names
+pub fn interpolate(
+    text: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> std::result::Result<String, Vec<String>> {
+    let mut output = String::with_capacity(text.len());
+    let mut missing = Vec::new();
+
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            output.push_str(&rest[..start - 1]);
+            output.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        output.push_str(&rest[..start]);
+        let Some(len) = rest[start + 2..].find('}') else {
+            output.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+        let name = &rest[start + 2..start + 2 + len];
+        match lookup(name) {
+            Some(value) => output.push_str(&value),
+            None => {
+                if !missing.iter().any(|m| m == name) {
+                    missing.push(name.to_string());
+                }
+            }
+        }
+        rest = &rest[start + 3 + len..];
+    }
+    output.push_str(rest);
+
+    if missing.is_empty() {
+        Ok(output)
+    } else {
+        Err(missing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_interpolate() {
+        let lookup = |name: &str| (name == "KEY").then(|| "secret".to_string());
+
+        assert_eq!(
+            interpolate("key=${KEY}, literal=$${KEY}", lookup),
+            Ok("key=secret, literal=${KEY}".to_string())
+        );
+        assert_eq!(
+            interpolate("${MISSING} ${KEY}-${OTHER}-${MISSING}", lookup),
+            Err(vec!["MISSING".to_string(), "OTHER".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_secrets_file_fills_variables() {
+        let temp = TempDir::new().unwrap();
+        let mut secrets = fs::File::create(temp.path().join("secrets.yaml")).unwrap();
+        writeln!(secrets, "TIERFLOW_TEST_TAUTULLI_KEY: abc123").unwrap();
+        let path = temp.path().join("config.yaml");
+
+        let mut document = json!({
+            "secrets_file": "secrets.yaml",
+            "api_key": "${TIERFLOW_TEST_TAUTULLI_KEY}",
+        });
+        assert!(interpolate_config(&path, &mut document).unwrap());
+        assert_eq!(
+            document,
+            json!({"secrets_file": "secrets.yaml", "api_key": "abc123"})
+        );
+
+        let err = interpolate_config(&path, &mut json!({"api_key": "${TIERFLOW_TEST_UNSET_KEY}"}))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unresolved config variables: ${TIERFLOW_TEST_UNSET_KEY}"
+        );
+    }
+
+    #[test]
+    fn test_values_are_substituted_verbatim_after_parsing() {
+        let temp = TempDir::new().unwrap();
+        let value = "p\"a: s #w\\rd\n- [x]";
+        fs::write(
+            temp.path().join("secrets.json"),
+            json!({ "TIERFLOW_TEST_ODD_SECRET": value }).to_string(),
+        )
+        .unwrap();
+        let config = "secrets_file: secrets.json\n\
+                      password: \"${TIERFLOW_TEST_ODD_SECRET}\"  # was ${TIERFLOW_TEST_UNSET_KEY}\n";
+        let mut document: Value = ConfigFormat::Yaml.parse(config).unwrap();
+
+        interpolate_config(&temp.path().join("config.yaml"), &mut document).unwrap();
+        assert_eq!(document["password"], value);
+    }
+}
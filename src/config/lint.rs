@@ -0,0 +1,320 @@
+use super::{BalancingConfig, ConditionConfig, PlacementStrategyConfig, StrategyAction};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+
+/// How serious a lint finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Info => write!(f, "info"),
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A single problem found while linting a config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintFinding {
+    pub severity: LintSeverity,
+    /// Stable identifier of the lint, e.g. `unreachable_strategy`
+    pub code: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<String>,
+    pub message: String,
+}
+
+impl LintFinding {
+    fn new(
+        severity: LintSeverity,
+        code: &'static str,
+        strategy: Option<&str>,
+        message: String,
+    ) -> Self {
+        Self {
+            severity,
+            code,
+            strategy: strategy.map(str::to_string),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{}]", self.severity, self.code)?;
+        if let Some(strategy) = &self.strategy {
+            write!(f, " strategy '{strategy}':")?;
+        }
+        write!(f, " {}", self.message)
+    }
+}
+
+impl BalancingConfig {
+    /// Full validation plus lints for likely mistakes, most severe first
+    pub fn lint(&self) -> Vec<LintFinding> {
+        let mut findings = Vec::new();
+
+        if let Err(e) = self.validate() {
+            findings.push(LintFinding::new(
+                LintSeverity::Error,
+                "invalid_config",
+                None,
+                e.to_string(),
+            ));
+        }
+        findings.extend(self.lint_strategies());
+        findings.extend(self.lint_watch_history());
+
+        findings.sort_by_key(|f| std::cmp::Reverse(f.severity));
+        findings
+    }
+
+    /// Lints that only look at the config itself
+    fn lint_strategies(&self) -> Vec<LintFinding> {
+        let mut findings = Vec::new();
+
+        // Same order the balancer uses to pick the matching strategy
+        let mut ordered: Vec<_> = self.strategies.iter().collect();
+        ordered.sort_by(|s1, s2| {
+            s2.priority
+                .cmp(&s1.priority)
+                .then_with(|| s2.name.cmp(&s1.name))
+        });
+        if let Some(pos) = ordered.iter().position(|s| is_catch_all(s)) {
+            let catch_all = ordered[pos];
+            for shadowed in &ordered[pos + 1..] {
+                findings.push(LintFinding::new(
+                    LintSeverity::Warning,
+                    "unreachable_strategy",
+                    Some(&shadowed.name),
+                    format!(
+                        "never matches: every file is taken by higher-priority strategy '{}' (priority {}) which always matches",
+                        catch_all.name, catch_all.priority
+                    ),
+                ));
+            }
+        }
+
+        let tier_priorities: HashMap<_, _> = self
+            .tiers
+            .iter()
+            .map(|t| (t.name.as_str(), t.priority))
+            .collect();
+
+        for strategy in &self.strategies {
+            if strategy.preferred_tiers.is_empty() && strategy.action != StrategyAction::Stay {
+                findings.push(LintFinding::new(
+                    LintSeverity::Warning,
+                    "no_target",
+                    Some(&strategy.name),
+                    "has no preferred tiers, so matching files are never placed".to_string(),
+                ));
+            }
+
+            let priorities: Vec<_> = strategy
+                .preferred_tiers
+                .iter()
+                .filter_map(|name| tier_priorities.get(name.as_str()).copied())
+                .collect();
+            let ascending = priorities.windows(2).all(|w| w[0] <= w[1]);
+            let descending = priorities.windows(2).all(|w| w[0] >= w[1]);
+            if !ascending && !descending {
+                let order = strategy
+                    .preferred_tiers
+                    .iter()
+                    .map(|name| match tier_priorities.get(name.as_str()) {
+                        Some(priority) => format!("{name} ({priority})"),
+                        None => name.clone(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                findings.push(LintFinding::new(
+                    LintSeverity::Warning,
+                    "tier_order",
+                    Some(&strategy.name),
+                    format!(
+                        "preferred_tiers [{order}] jump between faster and slower tiers; fallbacks should follow tier priority in one direction"
+                    ),
+                ));
+            }
+        }
+
+        findings
+    }
+
+    /// Checks the watch history provider when configured but not already checked by validation
+    fn lint_watch_history(&self) -> Option<LintFinding> {
+        let watch_history = self.watch_history.as_ref()?;
+        if self.has_watch_history_conditions() {
+            return None;
+        }
+
+        let result = crate::factory::build_watch_history_provider(watch_history)
+            .and_then(|provider| provider.health_check());
+
+        match result {
+            Ok(()) => None,
+            Err(e) => Some(LintFinding::new(
+                LintSeverity::Warning,
+                "watch_history_unreachable",
+                None,
+                format!("{} health check failed: {e}", watch_history.provider),
+            )),
+        }
+    }
+}
+
+/// Strategy that matches every file
+fn is_catch_all(strategy: &PlacementStrategyConfig) -> bool {
+    strategy
+        .conditions
+        .iter()
+        .all(|c| matches!(c, ConditionConfig::AlwaysTrue))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TierConfig;
+    use std::path::PathBuf;
+
+    fn tier(name: &str, priority: u32) -> TierConfig {
+        TierConfig {
+            name: name.to_string(),
+            path: PathBuf::from("/tmp"),
+            priority,
+            ..Default::default()
+        }
+    }
+
+    fn strategy(
+        name: &str,
+        priority: u32,
+        conditions: Vec<ConditionConfig>,
+    ) -> PlacementStrategyConfig {
+        PlacementStrategyConfig {
+            name: name.to_string(),
+            priority,
+            conditions,
+            preferred_tiers: vec!["cache".to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn config(strategies: Vec<PlacementStrategyConfig>) -> BalancingConfig {
+        BalancingConfig {
+            tiers: vec![tier("ssd", 1), tier("cache", 5), tier("storage", 10)],
+            strategies,
+            ..Default::default()
+        }
+    }
+
+    fn codes(findings: &[LintFinding]) -> Vec<(&'static str, Option<&str>)> {
+        findings
+            .iter()
+            .map(|f| (f.code, f.strategy.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_config_has_no_findings() {
+        let config = config(vec![
+            strategy(
+                "recent",
+                20,
+                vec![ConditionConfig::Age {
+                    min_hours: None,
+                    max_hours: Some(24),
+                }],
+            ),
+            strategy("default", 1, vec![]),
+        ]);
+        assert!(config.lint_strategies().is_empty());
+    }
+
+    #[test]
+    fn test_strategy_shadowed_by_catch_all() {
+        let config = config(vec![
+            strategy("everything", 20, vec![ConditionConfig::AlwaysTrue]),
+            strategy(
+                "old",
+                10,
+                vec![ConditionConfig::Age {
+                    min_hours: Some(168),
+                    max_hours: None,
+                }],
+            ),
+        ]);
+        assert_eq!(
+            codes(&config.lint_strategies()),
+            vec![("unreachable_strategy", Some("old"))]
+        );
+    }
+
+    #[test]
+    fn test_strategy_without_target() {
+        let mut no_target = strategy("nothing", 10, vec![]);
+        no_target.preferred_tiers.clear();
+        let mut stay = strategy(
+            "pinned",
+            20,
+            vec![ConditionConfig::Age {
+                min_hours: None,
+                max_hours: Some(1),
+            }],
+        );
+        stay.preferred_tiers.clear();
+        stay.action = StrategyAction::Stay;
+
+        let config = config(vec![no_target, stay]);
+        assert_eq!(
+            codes(&config.lint_strategies()),
+            vec![("no_target", Some("nothing"))]
+        );
+    }
+
+    #[test]
+    fn test_tier_order_must_be_monotonic() {
+        let mut jumping = strategy("jumping", 10, vec![]);
+        jumping.preferred_tiers = vec!["ssd".into(), "storage".into(), "cache".into()];
+        let mut slow_first = strategy(
+            "slow_first",
+            20,
+            vec![ConditionConfig::Age {
+                min_hours: Some(168),
+                max_hours: None,
+            }],
+        );
+        slow_first.preferred_tiers = vec!["storage".into(), "cache".into(), "ssd".into()];
+
+        let findings = config(vec![jumping, slow_first]).lint_strategies();
+        assert_eq!(codes(&findings), vec![("tier_order", Some("jumping"))]);
+        assert!(
+            findings[0]
+                .message
+                .contains("ssd (1), storage (10), cache (5)")
+        );
+    }
+
+    #[test]
+    fn test_lint_reports_validation_errors_first() {
+        let mut config = config(vec![strategy("default", 1, vec![])]);
+        config.mover.mover_type = crate::MoverType::DryRun;
+        config.strategies[0].preferred_tiers = vec!["missing".to_string()];
+
+        let findings = config.lint();
+        assert_eq!(findings[0].severity, LintSeverity::Error);
+        assert_eq!(findings[0].code, "invalid_config");
+        assert!(findings[0].to_string().contains("missing"));
+    }
+}
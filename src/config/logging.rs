@@ -0,0 +1,64 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// Most detailed level written to the log file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    #[default]
+    Debug,
+    Trace,
+}
+
+impl From<LogLevel> for tracing::Level {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => Self::ERROR,
+            LogLevel::Warn => Self::WARN,
+            LogLevel::Info => Self::INFO,
+            LogLevel::Debug => Self::DEBUG,
+            LogLevel::Trace => Self::TRACE,
+        }
+    }
+}
+
+const fn default_max_files() -> usize {
+    10
+}
+
+/// JSON log file written next to the stderr output
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LoggingConfig {
+    /// Log of the current run; each run starts a new file and older ones get `.1`, `.2`, ...
+    pub file: PathBuf,
+
+    #[serde(default)]
+    pub level: LogLevel,
+
+    /// Rotated files kept besides the current one
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+
+    /// Also rotate within a run once the file reaches this size
+    #[serde(default)]
+    pub max_size_mb: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_logging_config() {
+        let config: LoggingConfig =
+            serde_yaml::from_str("file: /var/log/tierflow/run.jsonl\nmax_size_mb: 50").unwrap();
+        assert_eq!(config.level, LogLevel::Debug);
+        assert_eq!(config.max_files, 10);
+        assert_eq!(config.max_size_mb, Some(50));
+    }
+}
@@ -0,0 +1,29 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::net::SocketAddr;
+
+/// Prometheus metrics endpoint, served in daemon mode
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MetricsConfig {
+    /// Address to listen on (e.g., "0.0.0.0:9464")
+    pub listen_addr: SocketAddr,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_metrics_config() {
+        let config: MetricsConfig = serde_yaml::from_str("listen_addr: 127.0.0.1:9464").unwrap();
+        assert_eq!(config.listen_addr.port(), 9464);
+    }
+
+    #[test]
+    fn test_deserialize_metrics_config_invalid_addr() {
+        let result: std::result::Result<MetricsConfig, _> =
+            serde_yaml::from_str("listen_addr: not-an-address");
+        assert!(result.is_err());
+    }
+}
@@ -1,40 +1,174 @@
+mod activity;
+mod affinity;
+mod api;
+mod arr;
 mod blocker;
+mod byte_size;
+mod cleanup;
 mod condition;
+mod daemon;
 mod error;
+mod format;
+mod hooks;
+mod interpolate;
+mod lint;
+mod logging;
+mod metrics;
+mod move_policy;
+mod native;
+mod notification;
+mod path_rewrite;
+mod preserve;
+mod reload;
+mod retry;
+mod safety;
+mod schedule;
 mod strategy;
-mod tautulli;
+mod template;
 mod tier;
+mod torrent;
+mod verify;
+mod watch_history;
 
+pub use activity::{ActivityCheckConfig, ActivityCheckMethod};
+pub use affinity::AffinityConfig;
+pub use api::ApiConfig;
+pub use arr::{ArrConfig, SonarrConfig};
 pub use blocker::{
     BlockerErrorPolicyConfig, BlockerProviderConfig, BlockersConfig, PathMappingConfig,
     TdarrBlockerConfig,
 };
-pub use condition::ConditionConfig;
+pub use byte_size::ByteSize;
+pub use cleanup::CleanupConfig;
+pub use condition::{AccountConfig, ConditionConfig, ExtensionGroups};
+pub use daemon::{DaemonConfig, NextRun, ScrubConfig, WatchConfig};
 pub use error::{ConfigError, Result};
+pub use format::ConfigFormat;
+pub use hooks::HooksConfig;
+pub use lint::{LintFinding, LintSeverity};
+pub use logging::{LogLevel, LoggingConfig};
+pub use metrics::MetricsConfig;
+pub use move_policy::{MovePolicyAction, MovePolicyConfig};
+pub use native::NativeCopyConfig;
+pub use notification::{NotificationsConfig, NotifyOn, WebhookConfig, WebhookFormat};
+pub use path_rewrite::{PathRewriteConfig, PlexScanConfig};
+pub use preserve::PreserveConfig;
+pub use reload::ConfigReloader;
+pub use retry::RetryConfig;
+pub use safety::SafetyConfig;
+pub use schedule::{ScheduleConfig, TimeOfDay, UtcOffset, Weekday};
 pub use strategy::{PlacementStrategyConfig, StrategyAction};
-pub use tautulli::TautulliConfig;
-pub use tier::TierConfig;
+pub use template::StrategyTemplateConfig;
+pub use tier::{EvictionPolicy, FsBackend, MemberSelection, ScanFilterConfig, TierConfig};
+pub use torrent::{TorrentClientConfig, TorrentClientType};
+pub use verify::{HashAlgorithm, VerificationMode, VerifyConfig};
+pub use watch_history::{EpisodeMatching, WatchHistoryConfig, WatchHistoryProviderType};
 
+use crate::remote::SshRemote;
+use schemars::JsonSchema;
 use serde::Deserialize;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 use std::process::Command;
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum MoverType {
     Rsync,
+    /// In-process reflink / `copy_file_range` copy instead of one rsync per file
+    NativeFast,
     DryRun,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// What the mover does when a different file already exists at the destination
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Rename the existing file to `<name>.backup-<timestamp>` and move
+    #[default]
+    Backup,
+    /// Leave both files in place and skip the move
+    Skip,
+    /// Replace the existing file
+    Overwrite,
+    /// Report the move as failed
+    Fail,
+}
+
+/// When `tierflow rebalance` exits with a non-zero status after completing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FailOn {
+    /// Exit 0 whenever the run completes
+    #[default]
+    Never,
+    /// Exit 2 when a move failed
+    ExecutionErrors,
+    /// Exit 3 on a critical plan warning, and 2 when a move failed
+    CriticalWarnings,
+}
+
+/// Exit status for a run whose moves failed
+pub const EXIT_EXECUTION_ERRORS: i32 = 2;
+/// Exit status for a run with critical plan warnings
+pub const EXIT_CRITICAL_WARNINGS: i32 = 3;
+
+impl FailOn {
+    /// Exit status for a completed run, `None` for success
+    pub const fn exit_code(self, critical_warnings: usize, execution_errors: usize) -> Option<i32> {
+        match self {
+            Self::Never => None,
+            Self::ExecutionErrors | Self::CriticalWarnings if execution_errors > 0 => {
+                Some(EXIT_EXECUTION_ERRORS)
+            }
+            Self::CriticalWarnings if critical_warnings > 0 => Some(EXIT_CRITICAL_WARNINGS),
+            Self::ExecutionErrors | Self::CriticalWarnings => None,
+        }
+    }
+}
+
+/// Order in which the executor starts the moves of a plan
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionOrder {
+    /// As planned: demotes first to free space, spin-down batches kept together
+    #[default]
+    Plan,
+    /// Promotes before demotes, so newly hot files arrive first
+    PromotesFirst,
+    /// Highest strategy priority first
+    PriorityDesc,
+    /// Smallest files first, for the most completed moves early on
+    SmallestFirst,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct MoverConfig {
     #[serde(rename = "type")]
     pub mover_type: MoverType,
     #[serde(default)]
     pub extra_args: Vec<String>,
+    /// Checks before the source is removed
+    /// (default: `checksum_once` with a `verify` block, otherwise `size_only`)
+    #[serde(default)]
+    pub verification: Option<VerificationMode>,
+    /// Metadata kept on moved files (everything by default)
+    #[serde(default)]
+    pub preserve: PreserveConfig,
+    /// Handling of a different file already at the destination
+    #[serde(default)]
+    pub on_conflict: ConflictPolicy,
+    /// Sparse file and preallocation handling of `native_fast`
+    #[serde(default)]
+    pub native: NativeCopyConfig,
+    /// Order the moves of a plan are executed in
+    #[serde(default)]
+    pub execution_order: ExecutionOrder,
+    /// Retries of moves that failed with a transient error
+    #[serde(default)]
+    pub retry: RetryConfig,
 }
 
 impl Default for MoverConfig {
@@ -42,30 +176,212 @@ impl Default for MoverConfig {
         Self {
             mover_type: MoverType::Rsync,
             extra_args: Vec::new(),
+            verification: None,
+            preserve: PreserveConfig::default(),
+            on_conflict: ConflictPolicy::default(),
+            native: NativeCopyConfig::default(),
+            execution_order: ExecutionOrder::default(),
+            retry: RetryConfig::default(),
         }
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, Default, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct BalancingConfig {
     pub tiers: Vec<TierConfig>,
     pub strategies: Vec<PlacementStrategyConfig>,
+    /// Shared strategy settings, merged into strategies that `extends` them
+    #[serde(default)]
+    pub strategy_templates: BTreeMap<String, StrategyTemplateConfig>,
+    /// Named extension sets for `file_extension` conditions: `video: [mkv, mp4]`
+    #[serde(default)]
+    pub extension_groups: ExtensionGroups,
     #[serde(default)]
     pub mover: MoverConfig,
     #[serde(default)]
     pub blockers: Option<BlockersConfig>,
-    pub tautulli: Option<TautulliConfig>,
+    /// Viewing history for `active_window` conditions (`tautulli:` is accepted too)
+    #[serde(default, alias = "tautulli")]
+    pub watch_history: Option<WatchHistoryConfig>,
+    /// Sonarr/Radarr integrations for `sonarr_*` conditions
+    #[serde(default)]
+    pub arr: Option<ArrConfig>,
+    /// qBittorrent or Transmission instance for `torrent_*` conditions
+    #[serde(default)]
+    pub torrent_client: Option<TorrentClientConfig>,
+    /// Allow/deny rules for moves between specific tier pairs
+    #[serde(default)]
+    pub move_policies: Vec<MovePolicyConfig>,
+    /// Prometheus metrics endpoint for daemon mode
+    #[serde(default)]
+    pub metrics: Option<MetricsConfig>,
+    /// HTTP control API for daemon mode (trigger runs, status, pause/resume)
+    #[serde(default)]
+    pub api: Option<ApiConfig>,
+    /// When daemon mode runs: fixed times of day and a random start delay
+    #[serde(default)]
+    pub daemon: DaemonConfig,
+    /// Hash-verify every copy before the source is removed
+    #[serde(default)]
+    pub verify: Option<VerifyConfig>,
+    /// Webhooks fired after each run
+    #[serde(default)]
+    pub notifications: Option<NotificationsConfig>,
+    /// Maximum moves planned per run across all strategies
+    #[serde(default)]
+    pub max_moves_per_run: Option<usize>,
+    /// Maximum bytes planned per run across all strategies
+    #[serde(default)]
+    pub max_bytes_per_run: Option<u64>,
+    /// Rescan and plan again after executing, until a plan has no moves
+    #[serde(default)]
+    pub until_converged: bool,
+    /// Keep a file in place if it moved less than this many hours ago
+    #[serde(default)]
+    pub min_hours_between_moves: Option<u64>,
+    /// Directory for persistent state such as the state journal
+    #[serde(default)]
+    pub state_dir: Option<PathBuf>,
+    /// Skip files that are open or still being written
+    #[serde(default)]
+    pub activity_check: Option<ActivityCheckConfig>,
+    /// Keep moved sources in a per-tier trash instead of deleting them
+    #[serde(default)]
+    pub safety: Option<SafetyConfig>,
+    /// Remove leftover `.partial` and `.backup-*` files at the start of each run
+    #[serde(default)]
+    pub cleanup: Option<CleanupConfig>,
+    /// Commands run after each move and after each run
+    #[serde(default)]
+    pub hooks: Option<HooksConfig>,
+    /// Where moved files went, for media servers that track files by path
+    #[serde(default)]
+    pub path_mapping: Option<PathRewriteConfig>,
+    /// Refuse to run when same-priority strategies with different destinations tie
+    #[serde(default)]
+    pub strict_strategy_ties: bool,
+    /// Exit with a non-zero status on critical warnings or failed moves
+    #[serde(default)]
+    pub fail_on: FailOn,
+    /// JSON log file per run, rotated by count and size
+    #[serde(default)]
+    pub logging: Option<LoggingConfig>,
+    /// `NAME: value` file for `${NAME}` references, relative to the config file
+    #[serde(default)]
+    pub secrets_file: Option<PathBuf>,
+    /// Makes the daemon start delay reproducible; echoed in plan output
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Hidden and small files left out of planning on every tier
+    #[serde(default)]
+    pub scan_filter: ScanFilterConfig,
+    /// Move subtitles and `.nfo` files together with their media file
+    #[serde(default)]
+    pub affinity: Option<AffinityConfig>,
 }
 
+/// Annotated starter config written by `tierflow config init`
+pub const STARTER_CONFIG: &str = include_str!("../../config.example.yaml");
+
+/// Plan-execute cycles of an `until_converged` run
+pub const CONVERGE_ITERATION_CAP: u32 = 10;
+
 impl BalancingConfig {
+    /// Plan-execute cycles per run when `--iterations` is not given
+    pub const fn iterations(&self) -> u32 {
+        if self.until_converged {
+            CONVERGE_ITERATION_CAP
+        } else {
+            1
+        }
+    }
+
+    /// JSON Schema of the config file, for editors that validate configs
+    pub fn json_schema() -> serde_json::Value {
+        schemars::schema_for!(Self).to_value()
+    }
+
+    /// Loads and validates the config, in the format implied by its extension
     pub fn from_file(path: &Path) -> Result<Self> {
-        let contents = fs::read_to_string(path)?;
-        let config: Self = serde_yaml::from_str(&contents)?;
+        Self::from_file_as(path, ConfigFormat::from_path(path))
+    }
+
+    pub fn from_file_as(path: &Path, format: ConfigFormat) -> Result<Self> {
+        let config = Self::parse_file_as(path, format)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Like [`Self::from_file_as`], with watch history read from `fixture`
+    ///
+    /// Keeps the rest of an existing `watch_history` section; without one, the
+    /// defaults are used.
+    pub fn from_file_with_history_fixture(
+        path: &Path,
+        format: ConfigFormat,
+        fixture: PathBuf,
+    ) -> Result<Self> {
+        let mut config = Self::parse_file_as(path, format)?;
+        match &mut config.watch_history {
+            Some(watch_history) => watch_history.fixture = Some(fixture),
+            None => config.watch_history = Some(WatchHistoryConfig::from_fixture(fixture)),
+        }
         config.validate()?;
         Ok(config)
     }
 
+    /// Configured state directory, or the default one
+    pub fn state_dir(&self) -> PathBuf {
+        self.state_dir
+            .clone()
+            .unwrap_or_else(crate::journal::default_state_dir)
+    }
+
+    /// `safety.trash_dir`, when moved sources go to the trash
+    pub fn trash_dir(&self) -> Option<&Path> {
+        self.safety.as_ref()?.trash_dir.as_deref()
+    }
+
+    /// Runtime tiers, with the trash directory excluded from scans when enabled
+    /// and stubs skipped while any tier leaves them
+    pub fn build_tiers(&self) -> std::io::Result<Vec<crate::Tier>> {
+        let skip_stubs = self.tiers.iter().any(|tier| tier.leave_stubs);
+        self.tiers
+            .iter()
+            .cloned()
+            .map(|tier| {
+                let tier = tier
+                    .with_default_scan_filter(self.scan_filter)
+                    .into_tier()?
+                    .with_skip_stubs(skip_stubs);
+                Ok(match self.trash_dir() {
+                    Some(trash_dir) => tier.with_trash_dir(trash_dir),
+                    None => tier,
+                })
+            })
+            .collect()
+    }
+
+    /// Parse the config without validation or external health checks
+    ///
+    /// Used by maintenance commands that must work while dependencies are down.
+    pub fn parse_file(path: &Path) -> Result<Self> {
+        Self::parse_file_as(path, ConfigFormat::from_path(path))
+    }
+
+    pub fn parse_file_as(path: &Path, format: ConfigFormat) -> Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let mut document: serde_json::Value = format.parse(&contents)?;
+        let interpolated = interpolate::interpolate_config(path, &mut document)?;
+        let extended = template::resolve_templates(&mut document)?;
+        if !interpolated && !extended {
+            // Parsed from the text again so errors keep their line numbers
+            return format.parse(&contents);
+        }
+        serde_json::from_value(document).map_err(ConfigError::ParseTemplated)
+    }
+
     fn validate(&self) -> Result<()> {
         if self.tiers.is_empty() {
             return Err(ConfigError::NoTiers);
@@ -82,6 +398,133 @@ impl BalancingConfig {
                     name: tier.name.clone(),
                 });
             }
+            if tier.path.as_os_str().is_empty() == tier.paths.is_empty() {
+                return Err(ConfigError::InvalidTierPath {
+                    path: tier.path.clone(),
+                    reason: format!(
+                        "tier '{}' needs exactly one of `path` or `paths`",
+                        tier.name
+                    ),
+                });
+            }
+            if tier
+                .paths
+                .iter()
+                .any(|path| SshRemote::parse(path).is_some())
+            {
+                return Err(ConfigError::InvalidTierPath {
+                    path: tier.path.clone(),
+                    reason: format!("tier group '{}' can't have remote members", tier.name),
+                });
+            }
+            if let Some(Err(reason)) = SshRemote::parse(&tier.path) {
+                return Err(ConfigError::InvalidTierPath {
+                    path: tier.path.clone(),
+                    reason,
+                });
+            }
+            if matches!(tier.fs_backend, FsBackend::Zfs | FsBackend::Btrfs)
+                && SshRemote::parse(&tier.path).is_some()
+            {
+                return Err(ConfigError::InvalidTierPath {
+                    path: tier.path.clone(),
+                    reason: format!(
+                        "remote tier '{}' is measured with df, fs_backend must be auto or posix",
+                        tier.name
+                    ),
+                });
+            }
+            if let Some(scan_path) = tier
+                .scan_paths
+                .iter()
+                .find(|p| p.is_absolute() || p.components().any(|c| c == Component::ParentDir))
+            {
+                return Err(ConfigError::InvalidTierPath {
+                    path: scan_path.clone(),
+                    reason: format!(
+                        "scan_paths of tier '{}' must be relative to the tier path",
+                        tier.name
+                    ),
+                });
+            }
+            if tier.capacity_override_bytes == Some(0) {
+                return Err(ConfigError::InvalidTierCapacity {
+                    tier: tier.name.clone(),
+                });
+            }
+            if let Some(reason) = tier.usage_limits_error() {
+                return Err(ConfigError::InvalidTierUsage {
+                    tier: tier.name.clone(),
+                    reason,
+                });
+            }
+        }
+
+        if let Some(trash_dir) = self.trash_dir()
+            && (trash_dir.as_os_str().is_empty()
+                || trash_dir.is_absolute()
+                || trash_dir.components().any(|c| c == Component::ParentDir))
+        {
+            return Err(ConfigError::InvalidTrashDir {
+                path: trash_dir.to_path_buf(),
+            });
+        }
+
+        if let Some(tier) = self
+            .tiers
+            .iter()
+            .find(|tier| SshRemote::parse(&tier.path).is_some())
+        {
+            let verification = self.mover.verification.unwrap_or(if self.verify.is_some() {
+                VerificationMode::ChecksumOnce
+            } else {
+                VerificationMode::SizeOnly
+            });
+            let hashes = verification.hashes()
+                && self
+                    .verify
+                    .as_ref()
+                    .is_none_or(|verify| verify.hash != HashAlgorithm::None);
+            let reason = if self.trash_dir().is_some() {
+                Some("safety.trash_dir can't keep sources on another host")
+            } else if hashes {
+                Some(
+                    "copies to and from other hosts are checked by size, set mover.verification to size_only or none",
+                )
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                return Err(ConfigError::InvalidTierPath {
+                    path: tier.path.clone(),
+                    reason: format!("remote tier '{}': {reason}", tier.name),
+                });
+            }
+        }
+
+        if self
+            .hooks
+            .as_ref()
+            .is_some_and(|hooks| hooks.timeout_secs == 0)
+        {
+            return Err(ConfigError::InvalidHookTimeout);
+        }
+
+        if self
+            .logging
+            .as_ref()
+            .is_some_and(|logging| logging.max_size_mb == Some(0))
+        {
+            return Err(ConfigError::InvalidLogSize);
+        }
+
+        if self
+            .daemon
+            .scrub
+            .as_ref()
+            .is_some_and(|scrub| !(1..=100).contains(&scrub.sample_percent))
+        {
+            return Err(ConfigError::InvalidScrubSample);
         }
 
         let mut strategy_names = HashSet::new();
@@ -93,7 +536,74 @@ impl BalancingConfig {
             }
         }
 
+        if self.max_moves_per_run == Some(0) {
+            return Err(ConfigError::InvalidMoveQuota {
+                scope: "global".to_string(),
+                field: "max_moves_per_run",
+            });
+        }
+        if self.max_bytes_per_run == Some(0) {
+            return Err(ConfigError::InvalidMoveQuota {
+                scope: "global".to_string(),
+                field: "max_bytes_per_run",
+            });
+        }
+
         for strategy in &self.strategies {
+            if strategy.max_moves_per_run == Some(0) {
+                return Err(ConfigError::InvalidMoveQuota {
+                    scope: format!("strategy '{}'", strategy.name),
+                    field: "max_moves_per_run",
+                });
+            }
+            if strategy.max_bytes_per_run == Some(0) {
+                return Err(ConfigError::InvalidMoveQuota {
+                    scope: format!("strategy '{}'", strategy.name),
+                    field: "max_bytes_per_run",
+                });
+            }
+
+            for condition in &strategy.conditions {
+                if let ConditionConfig::TierUsage { tier, .. } = condition
+                    && !tier_names.contains(tier)
+                {
+                    return Err(ConfigError::UnknownTier {
+                        strategy: strategy.name.clone(),
+                        tier: tier.clone(),
+                    });
+                }
+                // Same construction as at run time: extension groups, glob patterns
+                if let Err(reason) =
+                    crate::factory::build_condition(condition.clone(), &self.extension_groups)
+                {
+                    return Err(ConfigError::InvalidCondition {
+                        strategy: strategy.name.clone(),
+                        reason,
+                    });
+                }
+                if let ConditionConfig::FileSize {
+                    min_size,
+                    max_size,
+                    min_size_mb,
+                    max_size_mb,
+                } = condition
+                {
+                    let conflict = if min_size.is_some() && min_size_mb.is_some() {
+                        Some("min_size")
+                    } else if max_size.is_some() && max_size_mb.is_some() {
+                        Some("max_size")
+                    } else {
+                        None
+                    };
+                    if let Some(field) = conflict {
+                        return Err(ConfigError::ConflictingSizeBound {
+                            strategy: strategy.name.clone(),
+                            field,
+                        });
+                    }
+                }
+            }
+
             for tier_name in &strategy.preferred_tiers {
                 if !tier_names.contains(tier_name) {
                     return Err(ConfigError::UnknownTier {
@@ -104,6 +614,34 @@ impl BalancingConfig {
             }
         }
 
+        for policy in &self.move_policies {
+            for tier_name in [&policy.from, &policy.to] {
+                if tier_name != "*" && !tier_names.contains(tier_name) {
+                    return Err(ConfigError::UnknownPolicyTier {
+                        from: policy.from.clone(),
+                        to: policy.to.clone(),
+                        tier: tier_name.clone(),
+                    });
+                }
+            }
+        }
+
+        if let Some(verify) = &self.verify {
+            if verify.max_mbps == Some(0) {
+                return Err(ConfigError::InvalidVerifyLimit { field: "max_mbps" });
+            }
+            if verify.max_threads == Some(0) {
+                return Err(ConfigError::InvalidVerifyLimit {
+                    field: "max_threads",
+                });
+            }
+            if verify.threads_per_file == Some(0) {
+                return Err(ConfigError::InvalidVerifyLimit {
+                    field: "threads_per_file",
+                });
+            }
+        }
+
         // Validate mover availability
         match self.mover.mover_type {
             MoverType::Rsync => {
@@ -122,33 +660,95 @@ impl BalancingConfig {
                     }
                 }
             }
-            MoverType::DryRun => {
-                // DryRun mover is always available
+            MoverType::NativeFast | MoverType::DryRun => {
+                // In-process movers are always available
             }
         }
 
-        // Validate Tautulli configuration if active_window conditions are used
-        if self.has_active_window_conditions() {
-            if let Some(tautulli_config) = &self.tautulli {
+        // Validate watch history configuration if watch history conditions are used
+        if self.has_watch_history_conditions() {
+            if let Some(watch_history) = &self.watch_history {
                 tracing::info!(
-                    "Validating Tautulli configuration (active_window conditions detected)"
+                    "Validating {} configuration (watch history conditions detected)",
+                    watch_history.provider
                 );
 
+                if watch_history.episode_matching == EpisodeMatching::Metadata
+                    && watch_history.provider != WatchHistoryProviderType::Tautulli
+                {
+                    return Err(ConfigError::WatchHistoryUnavailable {
+                        provider: watch_history.provider.to_string(),
+                        reason: "episode_matching: metadata is only supported with Tautulli"
+                            .to_string(),
+                    });
+                }
+                if watch_history.fixture.is_some()
+                    && watch_history.episode_matching == EpisodeMatching::Metadata
+                {
+                    return Err(ConfigError::WatchHistoryUnavailable {
+                        provider: watch_history.provider.to_string(),
+                        reason: "episode_matching: metadata needs the Tautulli API, a fixture has no metadata"
+                            .to_string(),
+                    });
+                }
+                if watch_history.fixture.is_none() && watch_history.url.is_empty() {
+                    return Err(ConfigError::WatchHistoryUnavailable {
+                        provider: watch_history.provider.to_string(),
+                        reason: "url is required unless history is read from a fixture".to_string(),
+                    });
+                }
+
                 // Perform health check
-                use crate::TautulliClient;
-                let client = TautulliClient::new(
-                    tautulli_config.url.clone(),
-                    tautulli_config.api_key.clone(),
-                )?;
+                let provider = crate::factory::build_watch_history_provider(watch_history)?;
+                provider
+                    .health_check()
+                    .map_err(|e| ConfigError::WatchHistoryUnavailable {
+                        provider: watch_history.provider.to_string(),
+                        reason: format!("health check failed: {e}"),
+                    })?;
+            } else {
+                return Err(ConfigError::WatchHistoryRequired {
+                    reason: "active_window or recently_watched_movie condition is used but no watch history provider (tautulli or jellyfin) is configured"
+                        .to_string(),
+                });
+            }
+        }
+
+        // Validate Sonarr configuration if sonarr conditions are used
+        if self.has_sonarr_conditions() {
+            if let Some(sonarr) = self.sonarr() {
+                tracing::info!("Validating Sonarr configuration (sonarr conditions detected)");
 
-                client
+                crate::SonarrClient::new(sonarr.url.clone(), sonarr.api_key.clone())?
                     .health_check()
-                    .map_err(|e| ConfigError::TautulliUnavailable {
-                        reason: format!("Tautulli health check failed: {e}"),
+                    .map_err(|e| ConfigError::SonarrUnavailable {
+                        reason: format!("Sonarr health check failed: {e}"),
                     })?;
             } else {
-                return Err(ConfigError::TautulliRequired {
-                    reason: "active_window condition is used but tautulli is not configured"
+                return Err(ConfigError::SonarrRequired {
+                    reason: "sonarr conditions are used but arr.sonarr is not configured"
+                        .to_string(),
+                });
+            }
+        }
+
+        // Validate the torrent client if torrent conditions are used
+        if self.has_torrent_conditions() {
+            if let Some(torrent_client) = &self.torrent_client {
+                tracing::info!(
+                    "Validating {} configuration (torrent conditions detected)",
+                    torrent_client.client
+                );
+
+                crate::factory::build_torrent_client(torrent_client)?
+                    .health_check()
+                    .map_err(|e| ConfigError::TorrentClientUnavailable {
+                        client: torrent_client.client.to_string(),
+                        reason: format!("health check failed: {e}"),
+                    })?;
+            } else {
+                return Err(ConfigError::TorrentClientRequired {
+                    reason: "torrent conditions are used but torrent_client is not configured"
                         .to_string(),
                 });
             }
@@ -157,13 +757,58 @@ impl BalancingConfig {
         Ok(())
     }
 
-    /// Check if any strategy uses `active_window` condition
-    fn has_active_window_conditions(&self) -> bool {
+    /// Configured Sonarr instance, if any
+    pub fn sonarr(&self) -> Option<&SonarrConfig> {
+        self.arr.as_ref()?.sonarr.as_ref()
+    }
+
+    /// Largest `sonarr_airing_within` window, i.e. how far ahead to fetch the calendar
+    pub fn sonarr_lookahead_days(&self) -> u32 {
+        self.strategies
+            .iter()
+            .flat_map(|strategy| &strategy.conditions)
+            .filter_map(|condition| match condition {
+                ConditionConfig::SonarrAiringWithin { days } => Some(*days),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Check if any strategy uses a `sonarr_*` condition
+    fn has_sonarr_conditions(&self) -> bool {
         self.strategies.iter().any(|strategy| {
-            strategy
-                .conditions
-                .iter()
-                .any(|condition| matches!(condition, ConditionConfig::ActiveWindow { .. }))
+            strategy.conditions.iter().any(|condition| {
+                matches!(
+                    condition,
+                    ConditionConfig::SonarrMonitored | ConditionConfig::SonarrAiringWithin { .. }
+                )
+            })
+        })
+    }
+
+    /// Check if any strategy uses a `torrent_*` condition
+    fn has_torrent_conditions(&self) -> bool {
+        self.strategies.iter().any(|strategy| {
+            strategy.conditions.iter().any(|condition| {
+                matches!(
+                    condition,
+                    ConditionConfig::TorrentSeeding | ConditionConfig::TorrentRatio { .. }
+                )
+            })
+        })
+    }
+
+    /// Check if any strategy uses a condition backed by watch history
+    fn has_watch_history_conditions(&self) -> bool {
+        self.strategies.iter().any(|strategy| {
+            strategy.conditions.iter().any(|condition| {
+                matches!(
+                    condition,
+                    ConditionConfig::ActiveWindow { .. }
+                        | ConditionConfig::RecentlyWatchedMovie { .. }
+                )
+            })
         })
     }
 }
@@ -203,6 +848,59 @@ strategies:
         let config: BalancingConfig = serde_yaml::from_str(yaml).unwrap();
         assert_eq!(config.tiers.len(), 2);
         assert_eq!(config.strategies.len(), 2);
+        assert_eq!(config.mover.on_conflict, ConflictPolicy::Backup);
+        assert!(!config.strict_strategy_ties);
+        assert_eq!(config.fail_on, FailOn::Never);
+    }
+
+    #[test]
+    fn test_fail_on_exit_code() {
+        assert_eq!(FailOn::Never.exit_code(3, 3), None);
+        assert_eq!(FailOn::ExecutionErrors.exit_code(3, 0), None);
+        assert_eq!(
+            FailOn::ExecutionErrors.exit_code(0, 1),
+            Some(EXIT_EXECUTION_ERRORS)
+        );
+        assert_eq!(
+            FailOn::CriticalWarnings.exit_code(1, 0),
+            Some(EXIT_CRITICAL_WARNINGS)
+        );
+        assert_eq!(
+            FailOn::CriticalWarnings.exit_code(1, 1),
+            Some(EXIT_EXECUTION_ERRORS)
+        );
+        assert_eq!(FailOn::CriticalWarnings.exit_code(0, 0), None);
+    }
+
+    #[test]
+    fn test_starter_config_parses() {
+        let config: BalancingConfig = serde_yaml::from_str(STARTER_CONFIG).unwrap();
+        assert!(!config.tiers.is_empty());
+        assert!(!config.strategies.is_empty());
+    }
+
+    #[test]
+    fn test_json_schema_covers_config_types() {
+        let schema = BalancingConfig::json_schema();
+        assert!(schema["properties"]["tiers"].is_object());
+        assert!(
+            schema["required"]
+                .as_array()
+                .unwrap()
+                .contains(&serde_json::json!("strategies"))
+        );
+        assert_eq!(schema["additionalProperties"], false);
+
+        let conditions = schema["$defs"]["ConditionConfig"].to_string();
+        assert!(conditions.contains("\"age\""));
+        assert!(conditions.contains("\"sonarr_airing_within\""));
+    }
+
+    #[test]
+    fn test_deserialize_mover_on_conflict() {
+        let mover: MoverConfig = serde_yaml::from_str("type: rsync\non_conflict: skip").unwrap();
+        assert_eq!(mover.on_conflict, ConflictPolicy::Skip);
+        assert!(serde_yaml::from_str::<MoverConfig>("type: rsync\non_conflict: merge").is_err());
     }
 
     #[test]
@@ -255,10 +953,10 @@ strategies:
                 preferred_tiers: vec!["cache".to_string()],
                 required: false,
                 action: StrategyAction::Evaluate,
+                ..Default::default()
             }],
             mover: MoverConfig::default(),
-            blockers: None,
-            tautulli: None,
+            ..Default::default()
         };
 
         let result = config.validate();
@@ -275,11 +973,11 @@ strategies:
                 priority: 1,
                 max_usage_percent: None,
                 min_usage_percent: None,
+                ..Default::default()
             }],
             strategies: vec![],
             mover: MoverConfig::default(),
-            blockers: None,
-            tautulli: None,
+            ..Default::default()
         };
 
         let result = config.validate();
@@ -297,6 +995,7 @@ strategies:
                     priority: 1,
                     max_usage_percent: None,
                     min_usage_percent: None,
+                    ..Default::default()
                 },
                 TierConfig {
                     name: "cache".to_string(),
@@ -304,6 +1003,7 @@ strategies:
                     priority: 2,
                     max_usage_percent: None,
                     min_usage_percent: None,
+                    ..Default::default()
                 },
             ],
             strategies: vec![PlacementStrategyConfig {
@@ -313,10 +1013,10 @@ strategies:
                 preferred_tiers: vec!["cache".to_string()],
                 required: false,
                 action: StrategyAction::Evaluate,
+                ..Default::default()
             }],
             mover: MoverConfig::default(),
-            blockers: None,
-            tautulli: None,
+            ..Default::default()
         };
 
         let result = config.validate();
@@ -336,6 +1036,7 @@ strategies:
                 priority: 1,
                 max_usage_percent: None,
                 min_usage_percent: None,
+                ..Default::default()
             }],
             strategies: vec![
                 PlacementStrategyConfig {
@@ -345,6 +1046,7 @@ strategies:
                     preferred_tiers: vec!["cache".to_string()],
                     required: false,
                     action: StrategyAction::Evaluate,
+                    ..Default::default()
                 },
                 PlacementStrategyConfig {
                     name: "test".to_string(),
@@ -353,11 +1055,11 @@ strategies:
                     preferred_tiers: vec!["cache".to_string()],
                     required: false,
                     action: StrategyAction::Evaluate,
+                    ..Default::default()
                 },
             ],
             mover: MoverConfig::default(),
-            blockers: None,
-            tautulli: None,
+            ..Default::default()
         };
 
         let result = config.validate();
@@ -377,6 +1079,7 @@ strategies:
                 priority: 1,
                 max_usage_percent: None,
                 min_usage_percent: None,
+                ..Default::default()
             }],
             strategies: vec![PlacementStrategyConfig {
                 name: "test".to_string(),
@@ -385,10 +1088,10 @@ strategies:
                 preferred_tiers: vec!["nonexistent".to_string()],
                 required: false,
                 action: StrategyAction::Evaluate,
+                ..Default::default()
             }],
             mover: MoverConfig::default(),
-            blockers: None,
-            tautulli: None,
+            ..Default::default()
         };
 
         let result = config.validate();
@@ -412,6 +1115,7 @@ strategies:
                     priority: 1,
                     max_usage_percent: None,
                     min_usage_percent: None,
+                    ..Default::default()
                 },
                 TierConfig {
                     name: "storage".to_string(),
@@ -419,6 +1123,7 @@ strategies:
                     priority: 10,
                     max_usage_percent: None,
                     min_usage_percent: None,
+                    ..Default::default()
                 },
             ],
             strategies: vec![
@@ -429,6 +1134,7 @@ strategies:
                     preferred_tiers: vec!["storage".to_string()],
                     required: false,
                     action: StrategyAction::Evaluate,
+                    ..Default::default()
                 },
                 PlacementStrategyConfig {
                     name: "default".to_string(),
@@ -437,11 +1143,11 @@ strategies:
                     preferred_tiers: vec!["cache".to_string(), "storage".to_string()],
                     required: false,
                     action: StrategyAction::Evaluate,
+                    ..Default::default()
                 },
             ],
             mover: MoverConfig::default(),
-            blockers: None,
-            tautulli: None,
+            ..Default::default()
         };
 
         let result = config.validate();
@@ -535,4 +1241,380 @@ strategies:
         let err_msg = result.unwrap_err().to_string();
         assert!(err_msg.contains("unknown field"));
     }
+
+    #[test]
+    fn test_validate_move_policy_unknown_tier() {
+        let config = BalancingConfig {
+            tiers: vec![TierConfig {
+                name: "cache".to_string(),
+                path: "/tmp".into(),
+                priority: 1,
+                max_usage_percent: None,
+                min_usage_percent: None,
+                ..Default::default()
+            }],
+            strategies: vec![PlacementStrategyConfig {
+                name: "test".to_string(),
+                priority: 1,
+                conditions: vec![],
+                preferred_tiers: vec!["cache".to_string()],
+                required: false,
+                action: StrategyAction::Evaluate,
+                ..Default::default()
+            }],
+            move_policies: vec![MovePolicyConfig {
+                from: "*".to_string(),
+                to: "archive".to_string(),
+                action: MovePolicyAction::Deny,
+            }],
+            ..Default::default()
+        };
+
+        match config.validate().unwrap_err() {
+            ConfigError::UnknownPolicyTier { tier, .. } => assert_eq!(tier, "archive"),
+            other => panic!("Expected UnknownPolicyTier error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_validate_verify_zero_limit() {
+        let config = BalancingConfig {
+            tiers: vec![TierConfig {
+                name: "cache".to_string(),
+                path: "/tmp".into(),
+                priority: 1,
+                max_usage_percent: None,
+                min_usage_percent: None,
+                ..Default::default()
+            }],
+            strategies: vec![PlacementStrategyConfig {
+                name: "test".to_string(),
+                priority: 1,
+                conditions: vec![],
+                preferred_tiers: vec!["cache".to_string()],
+                required: false,
+                action: StrategyAction::Evaluate,
+                ..Default::default()
+            }],
+            verify: Some(VerifyConfig {
+                max_mbps: Some(0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        match config.validate().unwrap_err() {
+            ConfigError::InvalidVerifyLimit { field } => assert_eq!(field, "max_mbps"),
+            other => panic!("Expected InvalidVerifyLimit error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_validate_invalid_conditions() {
+        let config = BalancingConfig {
+            tiers: vec![TierConfig {
+                name: "cache".to_string(),
+                path: "/tmp".into(),
+                priority: 1,
+                ..Default::default()
+            }],
+            strategies: vec![PlacementStrategyConfig {
+                name: "videos".to_string(),
+                priority: 1,
+                preferred_tiers: vec!["cache".to_string()],
+                conditions: vec![ConditionConfig::FileExtension {
+                    extensions: vec![],
+                    group: Some("vidoe".to_string()),
+                    mode: condition::ExtensionModeConfig::Whitelist,
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        match config.validate().unwrap_err() {
+            ConfigError::InvalidCondition { strategy, reason } => {
+                assert_eq!(strategy, "videos");
+                assert_eq!(reason, "unknown extension group: vidoe");
+            }
+            other => panic!("Expected InvalidCondition error, got {other:?}"),
+        }
+
+        let mut config = config;
+        config.strategies[0].conditions = vec![ConditionConfig::PathGlob {
+            patterns: vec!["Season [1-".to_string()],
+            mode: condition::GlobModeConfig::Whitelist,
+        }];
+        match config.validate().unwrap_err() {
+            ConfigError::InvalidCondition { reason, .. } => {
+                assert!(reason.starts_with("invalid path_glob pattern"), "{reason}");
+            }
+            other => panic!("Expected InvalidCondition error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_validate_conflicting_size_bound() {
+        let config = BalancingConfig {
+            tiers: vec![TierConfig {
+                name: "cache".to_string(),
+                path: "/tmp".into(),
+                priority: 1,
+                ..Default::default()
+            }],
+            strategies: vec![PlacementStrategyConfig {
+                name: "big".to_string(),
+                priority: 1,
+                preferred_tiers: vec!["cache".to_string()],
+                conditions: vec![ConditionConfig::FileSize {
+                    min_size: None,
+                    max_size: Some(ByteSize(1_500_000_000)),
+                    min_size_mb: None,
+                    max_size_mb: Some(1000),
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        match config.validate().unwrap_err() {
+            ConfigError::ConflictingSizeBound { strategy, field } => {
+                assert_eq!(strategy, "big");
+                assert_eq!(field, "max_size");
+            }
+            other => panic!("Expected ConflictingSizeBound error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_validate_zero_strategy_quota() {
+        let config = BalancingConfig {
+            tiers: vec![TierConfig {
+                name: "cache".to_string(),
+                path: "/tmp".into(),
+                priority: 1,
+                ..Default::default()
+            }],
+            strategies: vec![PlacementStrategyConfig {
+                name: "test".to_string(),
+                priority: 1,
+                preferred_tiers: vec!["cache".to_string()],
+                max_moves_per_run: Some(0),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        match config.validate().unwrap_err() {
+            ConfigError::InvalidMoveQuota { scope, field } => {
+                assert_eq!(scope, "strategy 'test'");
+                assert_eq!(field, "max_moves_per_run");
+            }
+            other => panic!("Expected InvalidMoveQuota error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_validate_sonarr_conditions_require_sonarr() {
+        let config = BalancingConfig {
+            tiers: vec![TierConfig {
+                name: "cache".to_string(),
+                path: "/tmp".into(),
+                priority: 1,
+                ..Default::default()
+            }],
+            strategies: vec![PlacementStrategyConfig {
+                name: "upcoming".to_string(),
+                priority: 1,
+                conditions: vec![ConditionConfig::SonarrAiringWithin { days: 3 }],
+                preferred_tiers: vec!["cache".to_string()],
+                ..Default::default()
+            }],
+            mover: MoverConfig {
+                mover_type: MoverType::DryRun,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(config.sonarr_lookahead_days(), 3);
+        match config.validate().unwrap_err() {
+            ConfigError::SonarrRequired { .. } => {}
+            other => panic!("Expected SonarrRequired error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_history_fixture_replaces_url() {
+        let yaml = r"
+tiers:
+  - name: cache
+    path: /tmp
+    priority: 1
+strategies:
+  - name: watching
+    priority: 1
+    conditions:
+      - type: active_window
+        name: window
+    preferred_tiers:
+      - cache
+mover:
+  type: dry_run
+watch_history:
+  forward_episodes: 3
+";
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        fs::write(&path, yaml).unwrap();
+        let fixture = dir.path().join("history.json");
+        fs::write(
+            &fixture,
+            r#"{"response":{"result":"success","message":null,"data":{"data":[]}}}"#,
+        )
+        .unwrap();
+
+        match BalancingConfig::from_file(&path).unwrap_err() {
+            ConfigError::WatchHistoryUnavailable { .. } => {}
+            other => panic!("Expected WatchHistoryUnavailable error, got {other:?}"),
+        }
+        let config = BalancingConfig::from_file_with_history_fixture(
+            &path,
+            ConfigFormat::Yaml,
+            fixture.clone(),
+        )
+        .unwrap();
+        let watch_history = config.watch_history.unwrap();
+        assert_eq!(watch_history.fixture, Some(fixture));
+        assert_eq!(watch_history.forward_episodes, 3);
+    }
+
+    #[test]
+    fn test_validate_torrent_conditions_require_client() {
+        let config = BalancingConfig {
+            tiers: vec![TierConfig {
+                name: "cache".to_string(),
+                path: "/tmp".into(),
+                priority: 1,
+                ..Default::default()
+            }],
+            strategies: vec![PlacementStrategyConfig {
+                name: "seeding".to_string(),
+                priority: 1,
+                conditions: vec![ConditionConfig::TorrentSeeding],
+                preferred_tiers: vec!["cache".to_string()],
+                ..Default::default()
+            }],
+            mover: MoverConfig {
+                mover_type: MoverType::DryRun,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        match config.validate().unwrap_err() {
+            ConfigError::TorrentClientRequired { .. } => {}
+            other => panic!("Expected TorrentClientRequired error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_validate_tier_paths() {
+        let mut config = BalancingConfig {
+            tiers: vec![TierConfig {
+                name: "storage".to_string(),
+                path: "/tmp".into(),
+                paths: vec!["/tmp".into()],
+                priority: 1,
+                ..Default::default()
+            }],
+            strategies: vec![PlacementStrategyConfig {
+                name: "all".to_string(),
+                priority: 1,
+                preferred_tiers: vec!["storage".to_string()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            config.validate().unwrap_err(),
+            ConfigError::InvalidTierPath { .. }
+        ));
+
+        config.tiers[0].paths.clear();
+        config.tiers[0].path = PathBuf::new();
+        assert!(matches!(
+            config.validate().unwrap_err(),
+            ConfigError::InvalidTierPath { .. }
+        ));
+
+        config.tiers[0].path = "ssh://nas".into();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("needs a directory"), "{err}");
+
+        config.tiers[0].path = "ssh://nas/volume1".into();
+        config.tiers[0].fs_backend = FsBackend::Zfs;
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("fs_backend"), "{err}");
+        config.tiers[0].fs_backend = FsBackend::Posix;
+
+        // Remote copies are checked by size and their sources can't be trashed
+        config.verify = Some(VerifyConfig::default());
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("mover.verification"), "{err}");
+        config.mover.verification = Some(VerificationMode::SizeOnly);
+        config.safety = Some(SafetyConfig {
+            trash_dir: Some(".trash".into()),
+            retention_hours: 24,
+            max_plan_bytes: None,
+            max_plan_percent_of_tier: None,
+            max_drift_percent: None,
+        });
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("trash_dir"), "{err}");
+        (config.verify, config.safety) = (None, None);
+
+        config.tiers[0].path = "/tmp".into();
+        config.tiers[0].scan_paths = vec!["../appdata".into()];
+        assert!(matches!(
+            config.validate().unwrap_err(),
+            ConfigError::InvalidTierPath { .. }
+        ));
+
+        config.tiers[0].scan_paths.clear();
+        config.tiers[0].capacity_override_bytes = Some(0);
+        assert!(matches!(
+            config.validate().unwrap_err(),
+            ConfigError::InvalidTierCapacity { .. }
+        ));
+
+        config.tiers[0].capacity_override_bytes = None;
+        config.tiers[0].min_usage_percent = Some(80);
+        config.tiers[0].max_usage_percent = Some(80);
+        let err = config.validate().unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("min_usage_percent (80) must be less than max_usage_percent (80)"),
+            "{err}"
+        );
+
+        config.tiers[0].min_usage_percent = None;
+        config.tiers[0].reserve_percent = 100;
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("reserve_percent"), "{err}");
+
+        config.tiers[0].reserve_percent = 0;
+        config.safety = Some(SafetyConfig {
+            trash_dir: Some("/mnt/trash".into()),
+            retention_hours: 24,
+            max_plan_bytes: None,
+            max_plan_percent_of_tier: None,
+            max_drift_percent: None,
+        });
+        assert!(matches!(
+            config.validate().unwrap_err(),
+            ConfigError::InvalidTrashDir { .. }
+        ));
+    }
 }
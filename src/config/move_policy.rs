@@ -0,0 +1,57 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// What happens to a move matching a policy rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MovePolicyAction {
+    Allow,
+    Deny,
+}
+
+/// Allow/deny rule for moves between a pair of tiers
+///
+/// `from` and `to` are tier names, or `*` to match any tier.
+/// Rules are evaluated in order and the first match wins; moves that match
+/// no rule are allowed.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MovePolicyConfig {
+    pub from: String,
+    pub to: String,
+    pub action: MovePolicyAction,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_move_policies() {
+        let yaml = r"
+- from: landing
+  to: archive
+  action: deny
+- from: '*'
+  to: archive
+  action: allow
+";
+        let policies: Vec<MovePolicyConfig> = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(policies.len(), 2);
+        assert_eq!(policies[0].from, "landing");
+        assert_eq!(policies[0].action, MovePolicyAction::Deny);
+        assert_eq!(policies[1].from, "*");
+        assert_eq!(policies[1].action, MovePolicyAction::Allow);
+    }
+
+    #[test]
+    fn test_deserialize_move_policy_rejects_unknown_action() {
+        let yaml = r"
+from: landing
+to: archive
+action: maybe
+";
+        let result: std::result::Result<MovePolicyConfig, _> = serde_yaml::from_str(yaml);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,34 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// Options of the `native_fast` copier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct NativeCopyConfig {
+    /// Copy only the data regions of sparse files, leaving holes unallocated
+    pub sparse: bool,
+    /// Reserve the destination's space with `fallocate` before copying, to avoid fragmentation
+    pub preallocate: bool,
+}
+
+impl Default for NativeCopyConfig {
+    fn default() -> Self {
+        Self {
+            sparse: true,
+            preallocate: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_native_copy_config() {
+        let config: NativeCopyConfig = serde_yaml::from_str("preallocate: false").unwrap();
+        assert!(config.sparse);
+        assert!(!config.preallocate);
+        assert!(serde_yaml::from_str::<NativeCopyConfig>("fallocate: false").is_err());
+    }
+}
@@ -0,0 +1,76 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// Notifications sent after each run
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct NotificationsConfig {
+    #[serde(default)]
+    pub notify_on: NotifyOn,
+
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+}
+
+/// Which runs trigger a notification
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyOn {
+    /// Every run
+    #[default]
+    Always,
+    /// Runs with move errors, plan warnings, or a failure
+    Errors,
+    /// Runs that moved at least one file (or failed)
+    Moves,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    pub url: String,
+
+    #[serde(default)]
+    pub format: WebhookFormat,
+}
+
+/// Payload shape posted to the webhook
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookFormat {
+    /// Structured JSON with counts, warnings and errors
+    #[default]
+    Generic,
+    /// `{"content": "..."}` message for Discord
+    Discord,
+    /// `{"text": "..."}` message for Slack
+    Slack,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_notifications() {
+        let yaml = r#"
+notify_on: errors
+webhooks:
+  - url: "https://discord.com/api/webhooks/1/abc"
+    format: discord
+  - url: "http://localhost:9000/hook"
+"#;
+        let config: NotificationsConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.notify_on, NotifyOn::Errors);
+        assert_eq!(config.webhooks.len(), 2);
+        assert_eq!(config.webhooks[0].format, WebhookFormat::Discord);
+        assert_eq!(config.webhooks[1].format, WebhookFormat::Generic);
+    }
+
+    #[test]
+    fn test_deserialize_notifications_defaults() {
+        let config: NotificationsConfig = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(config.notify_on, NotifyOn::Always);
+        assert!(config.webhooks.is_empty());
+    }
+}
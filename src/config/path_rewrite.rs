@@ -0,0 +1,57 @@
+use super::PathMappingConfig;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// Tells external systems where moved files went, so they keep their metadata
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PathRewriteConfig {
+    /// JSON Lines file every executed move is appended to
+    #[serde(default)]
+    pub file: Option<PathBuf>,
+
+    /// Plex server asked to scan both directories of every move
+    #[serde(default)]
+    pub plex: Option<PlexScanConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PlexScanConfig {
+    /// Base URL of the Plex server (e.g., "<http://localhost:32400>")
+    pub url: String,
+
+    /// `X-Plex-Token` of an account that can manage the libraries
+    pub token: String,
+
+    /// Host paths as Plex sees them, when Plex runs in a container
+    #[serde(default)]
+    pub path_mappings: Vec<PathMappingConfig>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_path_rewrite_config() {
+        let yaml = r#"
+file: /var/lib/tierflow/moves.jsonl
+plex:
+  url: "http://localhost:32400"
+  token: "plex-token"
+  path_mappings:
+    - host_prefix: /mnt/storage/tv
+      app_prefix: /tv
+"#;
+        let config: PathRewriteConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            config.file,
+            Some(PathBuf::from("/var/lib/tierflow/moves.jsonl"))
+        );
+        let plex = config.plex.unwrap();
+        assert_eq!(plex.token, "plex-token");
+        assert_eq!(plex.path_mappings[0].app_prefix, "/tv");
+    }
+}
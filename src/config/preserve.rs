@@ -0,0 +1,51 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// File metadata carried over to the destination on every move
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct PreserveConfig {
+    /// Permission bits
+    pub mode: bool,
+    /// Owner and group (only takes effect when running as root)
+    pub owner: bool,
+    /// Extended attributes
+    pub xattrs: bool,
+    /// POSIX ACLs
+    pub acls: bool,
+    /// Modification times
+    pub times: bool,
+}
+
+impl Default for PreserveConfig {
+    fn default() -> Self {
+        Self {
+            mode: true,
+            owner: true,
+            xattrs: true,
+            acls: true,
+            times: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_preserve_config() {
+        let config: PreserveConfig = serde_yaml::from_str("xattrs: false\nacls: false").unwrap();
+        assert!(config.mode);
+        assert!(config.owner);
+        assert!(!config.xattrs);
+        assert!(!config.acls);
+        assert!(config.times);
+    }
+
+    #[test]
+    fn test_deserialize_preserve_config_rejects_unknown_fields() {
+        assert!(serde_yaml::from_str::<PreserveConfig>("perms: true").is_err());
+    }
+}
@@ -0,0 +1,148 @@
+use super::{BalancingConfig, ConfigFormat, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+
+/// Keeps the daemon's config current between runs
+///
+/// A reload happens when the file's mtime changes or a reload was requested
+/// (SIGHUP). The new config replaces the current one only if it validates;
+/// otherwise the previous config stays active.
+pub struct ConfigReloader {
+    path: PathBuf,
+    format: ConfigFormat,
+    current: BalancingConfig,
+    last_modified: Option<SystemTime>,
+    reload_requested: Arc<AtomicBool>,
+}
+
+impl ConfigReloader {
+    /// Loads the initial config, which must be valid
+    pub fn load(path: &Path, format: ConfigFormat) -> Result<Self> {
+        let last_modified = modified_time(path);
+        let current = BalancingConfig::from_file_as(path, format)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            format,
+            current,
+            last_modified,
+            reload_requested: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    pub fn current(&self) -> &BalancingConfig {
+        &self.current
+    }
+
+    /// Flag that forces a reload on the next check, e.g. set from a signal handler
+    pub fn reload_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.reload_requested)
+    }
+
+    /// Reloads the config if requested or changed on disk; returns true if it was swapped
+    pub fn reload_if_changed(&mut self) -> bool {
+        let requested = self.reload_requested.swap(false, Ordering::SeqCst);
+        let modified = modified_time(&self.path);
+        if !requested && modified == self.last_modified {
+            return false;
+        }
+
+        // Remember this version even if invalid so the warning isn't repeated every run
+        self.last_modified = modified;
+
+        match BalancingConfig::from_file_as(&self.path, self.format) {
+            Ok(config) => {
+                tracing::info!("Reloaded configuration from {}", self.path.display());
+                self.current = config;
+                true
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Ignoring invalid configuration in {}: {e}. Keeping the previous configuration.",
+                    self.path.display()
+                );
+                false
+            }
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn config_yaml(strategy: &str, tier_path: &Path) -> String {
+        format!(
+            r"
+tiers:
+  - name: cache
+    path: {}
+    priority: 1
+strategies:
+  - name: {strategy}
+    priority: 1
+    preferred_tiers: [cache]
+mover:
+  type: dry_run
+",
+            tier_path.display()
+        )
+    }
+
+    fn write_config(path: &Path, contents: &str, age: Duration) {
+        fs::write(path, contents).unwrap();
+        let file = fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() - age).unwrap();
+    }
+
+    #[test]
+    fn test_reload_on_change_and_request() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("config.yaml");
+        write_config(
+            &path,
+            &config_yaml("first", temp.path()),
+            Duration::from_secs(60),
+        );
+
+        let mut reloader = ConfigReloader::load(&path, ConfigFormat::Yaml).unwrap();
+        assert!(!reloader.reload_if_changed());
+
+        write_config(
+            &path,
+            &config_yaml("second", temp.path()),
+            Duration::from_secs(30),
+        );
+        assert!(reloader.reload_if_changed());
+        assert_eq!(reloader.current().strategies[0].name, "second");
+
+        reloader.reload_flag().store(true, Ordering::SeqCst);
+        assert!(reloader.reload_if_changed());
+        assert!(!reloader.reload_if_changed());
+    }
+
+    #[test]
+    fn test_invalid_config_keeps_previous() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("config.yaml");
+        write_config(
+            &path,
+            &config_yaml("first", temp.path()),
+            Duration::from_secs(60),
+        );
+        let mut reloader = ConfigReloader::load(&path, ConfigFormat::Yaml).unwrap();
+
+        write_config(&path, "tiers: [", Duration::from_secs(30));
+        assert!(!reloader.reload_if_changed());
+        assert_eq!(reloader.current().strategies[0].name, "first");
+    }
+}
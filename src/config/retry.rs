@@ -0,0 +1,49 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Retries of moves that failed with a transient error (timeouts, stale NFS handles, ...)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryConfig {
+    /// Extra attempts per move; 0 disables retries
+    pub retries: u32,
+    /// Wait before the first retry, doubled for every further one
+    pub backoff_seconds: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            retries: 0,
+            backoff_seconds: 30,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Wait before attempt `attempt + 1`, after `attempt` failed ones
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u64
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u64::MAX);
+        Duration::from_secs(self.backoff_seconds.saturating_mul(factor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_backoff_doubles() {
+        let config: RetryConfig = serde_yaml::from_str("retries: 3\nbackoff_seconds: 10").unwrap();
+        assert_eq!(config.retries, 3);
+        assert_eq!(config.backoff(1), Duration::from_secs(10));
+        assert_eq!(config.backoff(3), Duration::from_secs(40));
+        assert_eq!(config.backoff(200), Duration::from_secs(u64::MAX));
+
+        let config: RetryConfig = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(config, RetryConfig::default());
+    }
+}
@@ -0,0 +1,79 @@
+use super::ByteSize;
+use crate::PlanCaps;
+use crate::drift::DEFAULT_MAX_DRIFT_PERCENT;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const fn default_retention_hours() -> u64 {
+    24
+}
+
+/// Recoverable copies of moved files and limits on what one plan may move
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SafetyConfig {
+    /// Trash directory, relative to each tier root; sources are moved here instead of deleted
+    #[serde(default)]
+    pub trash_dir: Option<PathBuf>,
+
+    /// How long trashed sources are kept before they are purged
+    #[serde(default = "default_retention_hours")]
+    pub retention_hours: u64,
+
+    /// Refuse plans moving more than this in total: `2TB`
+    #[serde(default)]
+    pub max_plan_bytes: Option<ByteSize>,
+
+    /// Refuse plans moving more than this percent of any tier's capacity onto or off it
+    #[serde(default)]
+    pub max_plan_percent_of_tier: Option<u64>,
+
+    /// Warn when a tier's usage after a run is this many percent of its
+    /// capacity away from what the plan expected (default 2)
+    #[serde(default)]
+    pub max_drift_percent: Option<u64>,
+}
+
+impl SafetyConfig {
+    pub fn plan_caps(&self) -> PlanCaps {
+        PlanCaps::new(
+            self.max_plan_bytes.map(|size| size.0),
+            self.max_plan_percent_of_tier,
+        )
+    }
+
+    pub fn max_drift_percent(&self) -> u64 {
+        self.max_drift_percent.unwrap_or(DEFAULT_MAX_DRIFT_PERCENT)
+    }
+
+    pub const fn retention(&self) -> Duration {
+        Duration::from_secs(self.retention_hours * 3600)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_safety_config() {
+        let config: SafetyConfig =
+            serde_yaml::from_str("trash_dir: .tierflow-trash\nretention_hours: 48").unwrap();
+        assert_eq!(config.trash_dir, Some(PathBuf::from(".tierflow-trash")));
+        assert_eq!(config.retention(), Duration::from_secs(48 * 3600));
+
+        let config: SafetyConfig = serde_yaml::from_str("trash_dir: .trash").unwrap();
+        assert_eq!(config.retention_hours, 24);
+        assert_eq!(config.plan_caps(), PlanCaps::default());
+
+        let config: SafetyConfig =
+            serde_yaml::from_str("max_plan_bytes: 2TB\nmax_plan_percent_of_tier: 25").unwrap();
+        assert_eq!(config.trash_dir, None);
+        assert_eq!(
+            config.plan_caps(),
+            PlanCaps::new(Some(2_000_000_000_000), Some(25))
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Weekday {
+    #[serde(alias = "monday")]
+    Mon,
+    #[serde(alias = "tuesday")]
+    Tue,
+    #[serde(alias = "wednesday")]
+    Wed,
+    #[serde(alias = "thursday")]
+    Thu,
+    #[serde(alias = "friday")]
+    Fri,
+    #[serde(alias = "saturday")]
+    Sat,
+    #[serde(alias = "sunday")]
+    Sun,
+}
+
+impl Weekday {
+    const ALL: [Self; 7] = [
+        Self::Mon,
+        Self::Tue,
+        Self::Wed,
+        Self::Thu,
+        Self::Fri,
+        Self::Sat,
+        Self::Sun,
+    ];
+
+    /// Weekday of a day counted from the Unix epoch, a Thursday
+    fn from_epoch_day(day: i64) -> Self {
+        Self::ALL[(day + 3).rem_euclid(7) as usize]
+    }
+}
+
+impl fmt::Display for Weekday {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Mon => "mon",
+            Self::Tue => "tue",
+            Self::Wed => "wed",
+            Self::Thu => "thu",
+            Self::Fri => "fri",
+            Self::Sat => "sat",
+            Self::Sun => "sun",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Time of day as `HH:MM`, stored as minutes since midnight
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, JsonSchema)]
+#[serde(try_from = "String")]
+pub struct TimeOfDay(pub(super) u16);
+
+impl TryFrom<String> for TimeOfDay {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let invalid = || format!("invalid time of day '{value}', expected HH:MM");
+        let (hours, minutes) = value.trim().split_once(':').ok_or_else(invalid)?;
+        let hours: u16 = hours.parse().map_err(|_| invalid())?;
+        let minutes: u16 = minutes.parse().map_err(|_| invalid())?;
+        if hours > 23 || minutes > 59 {
+            return Err(invalid());
+        }
+        Ok(Self(hours * 60 + minutes))
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.0 / 60, self.0 % 60)
+    }
+}
+
+/// Fixed offset from UTC as `+HH:MM` or `-HH:MM`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(try_from = "String")]
+pub struct UtcOffset(pub(super) i16);
+
+impl TryFrom<String> for UtcOffset {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let invalid = || format!("invalid UTC offset '{value}', expected +HH:MM or -HH:MM");
+        let trimmed = value.trim();
+        let (sign, rest) = match trimmed.split_at_checked(1) {
+            Some(("+", rest)) => (1, rest),
+            Some(("-", rest)) => (-1, rest),
+            _ => return Err(invalid()),
+        };
+        let time = TimeOfDay::try_from(rest.to_string()).map_err(|_| invalid())?;
+        if time.0 > 14 * 60 {
+            return Err(invalid());
+        }
+        Ok(Self(sign * time.0 as i16))
+    }
+}
+
+impl fmt::Display for UtcOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { '-' } else { '+' };
+        let minutes = self.0.unsigned_abs();
+        write!(f, "UTC{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+    }
+}
+
+/// Window in which a strategy is active; outside it the strategy matches nothing
+///
+/// A window whose `end` is not after its `start` runs past midnight into the next
+/// day, and equal times cover the whole day. `days` are the days a window starts on.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleConfig {
+    /// Days the window starts on, every day if empty
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+
+    #[serde(default)]
+    pub start: TimeOfDay,
+
+    #[serde(default)]
+    pub end: TimeOfDay,
+
+    /// Offset of the clock `start` and `end` are given in; daylight saving is not followed
+    #[serde(default)]
+    pub utc_offset: UtcOffset,
+}
+
+impl ScheduleConfig {
+    pub fn is_active_at(&self, now: SystemTime) -> bool {
+        let minutes = now
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| (d.as_secs() / 60) as i64)
+            + i64::from(self.utc_offset.0);
+        let day = minutes.div_euclid(MINUTES_PER_DAY);
+        let minute = minutes.rem_euclid(MINUTES_PER_DAY) as u16;
+        let (start, end) = (self.start.0, self.end.0);
+
+        match start.cmp(&end) {
+            Ordering::Equal => self.starts_on(day),
+            Ordering::Less => (start..end).contains(&minute) && self.starts_on(day),
+            Ordering::Greater => {
+                (minute >= start && self.starts_on(day))
+                    || (minute < end && self.starts_on(day - 1))
+            }
+        }
+    }
+
+    fn starts_on(&self, day: i64) -> bool {
+        self.days.is_empty() || self.days.contains(&Weekday::from_epoch_day(day))
+    }
+}
+
+impl fmt::Display for ScheduleConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.days.is_empty() {
+            let days: Vec<_> = self.days.iter().map(ToString::to_string).collect();
+            write!(f, "{} ", days.join(","))?;
+        }
+        write!(f, "{}-{} {}", self.start, self.end, self.utc_offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Monday 2024-01-15 at `hh:mm` UTC
+    fn monday_at(hours: u64, minutes: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_705_276_800 + hours * 3600 + minutes * 60)
+    }
+
+    #[test]
+    fn test_deserialize_schedule() {
+        let yaml = "days: [sat, sunday]\nstart: \"22:00\"\nend: \"06:30\"\nutc_offset: \"-05:00\"";
+        let schedule: ScheduleConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(schedule.days, vec![Weekday::Sat, Weekday::Sun]);
+        assert_eq!(schedule.to_string(), "sat,sun 22:00-06:30 UTC-05:00");
+
+        assert!(serde_yaml::from_str::<ScheduleConfig>("start: \"24:00\"").is_err());
+        assert!(serde_yaml::from_str::<ScheduleConfig>("utc_offset: \"2\"").is_err());
+    }
+
+    #[test]
+    fn test_overnight_window_belongs_to_start_day() {
+        let schedule: ScheduleConfig =
+            serde_yaml::from_str("days: [mon]\nstart: \"22:00\"\nend: \"06:00\"").unwrap();
+
+        assert!(!schedule.is_active_at(monday_at(21, 59)));
+        assert!(schedule.is_active_at(monday_at(22, 0)));
+        // Tuesday 05:59, still Monday's window
+        assert!(schedule.is_active_at(monday_at(29, 59)));
+        assert!(!schedule.is_active_at(monday_at(30, 0)));
+        // Monday early morning belongs to Sunday's window
+        assert!(!schedule.is_active_at(monday_at(1, 0)));
+    }
+
+    #[test]
+    fn test_utc_offset_and_whole_days() {
+        let schedule: ScheduleConfig =
+            serde_yaml::from_str("start: \"09:00\"\nend: \"17:00\"\nutc_offset: \"+02:00\"")
+                .unwrap();
+        assert!(schedule.is_active_at(monday_at(7, 0)));
+        assert!(!schedule.is_active_at(monday_at(15, 0)));
+
+        let weekend: ScheduleConfig = serde_yaml::from_str("days: [sat, sun]").unwrap();
+        assert!(!weekend.is_active_at(monday_at(12, 0)));
+        // Sunday 23:00
+        assert!(weekend.is_active_at(monday_at(0, 0) - Duration::from_secs(3600)));
+    }
+}
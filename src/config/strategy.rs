@@ -1,9 +1,10 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 
-use super::ConditionConfig;
+use super::{ConditionConfig, ScheduleConfig};
 
 /// Действие стратегии при совпадении условий
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum StrategyAction {
     /// Обычная обработка: найти ideal tier и переместить файл если нужно
@@ -11,26 +12,42 @@ pub enum StrategyAction {
     Evaluate,
     /// Всегда оставлять файл на текущем месте (игнорировать)
     Stay,
+    /// Copy the file to the preferred tier and keep the original where it is
+    Copy,
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Clone, PartialEq, Default, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct PlacementStrategyConfig {
     pub name: String,
     pub priority: u32,
     #[serde(default)]
     pub conditions: Vec<ConditionConfig>,
+    #[serde(default)]
     pub preferred_tiers: Vec<String>,
     #[serde(default)]
     pub required: bool,
     /// Действие стратегии: evaluate (обычная обработка) или stay (игнорировать)
     #[serde(default)]
     pub action: StrategyAction,
+    /// Maximum moves this strategy may schedule per run
+    #[serde(default)]
+    pub max_moves_per_run: Option<usize>,
+    /// Maximum bytes this strategy may schedule per run
+    #[serde(default)]
+    pub max_bytes_per_run: Option<u64>,
+    /// Only active inside this window, evaluated when the plan is made
+    #[serde(default)]
+    pub schedule: Option<ScheduleConfig>,
+    /// Template from `strategy_templates` this strategy was merged with
+    #[serde(default)]
+    pub extends: Option<String>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::ExtensionGroups;
     use crate::{Context, FileInfo, factory};
     use std::path::PathBuf;
     use std::time::{Duration, SystemTime};
@@ -42,6 +59,7 @@ mod tests {
             size: 1000,
             modified,
             accessed: SystemTime::now(),
+            owner: None,
         }
     }
 
@@ -65,6 +83,40 @@ required: true
         assert!(config.required);
     }
 
+    #[test]
+    fn test_deserialize_strategy_quota() {
+        let yaml = r"
+name: backfill
+priority: 10
+preferred_tiers:
+  - storage
+max_moves_per_run: 100
+max_bytes_per_run: 500000000000
+";
+        let config: PlacementStrategyConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.max_moves_per_run, Some(100));
+        assert_eq!(config.max_bytes_per_run, Some(500_000_000_000));
+    }
+
+    #[test]
+    fn test_deserialize_strategy_schedule() {
+        let yaml = r#"
+name: night_demotion
+priority: 10
+preferred_tiers:
+  - storage
+schedule:
+  start: "01:00"
+  end: "06:00"
+"#;
+        let config: PlacementStrategyConfig = serde_yaml::from_str(yaml).unwrap();
+        let strategy = factory::build_strategy(config, &ExtensionGroups::new()).unwrap();
+        assert_eq!(
+            strategy.schedule().map(ToString::to_string).as_deref(),
+            Some("01:00-06:00 UTC+00:00")
+        );
+    }
+
     #[test]
     fn test_deserialize_strategy_minimal() {
         let yaml = r"
@@ -126,9 +178,10 @@ preferred_tiers:
             preferred_tiers: vec!["cache".to_string()],
             required: false,
             action: StrategyAction::Evaluate,
+            ..Default::default()
         };
 
-        let strategy = factory::build_strategy(config);
+        let strategy = factory::build_strategy(config, &ExtensionGroups::new()).unwrap();
         assert_eq!(strategy.name, "test");
         assert_eq!(strategy.priority, 1);
         assert!(!strategy.is_required);
@@ -151,9 +204,10 @@ preferred_tiers:
             preferred_tiers: vec!["storage".to_string()],
             required: false,
             action: StrategyAction::Evaluate,
+            ..Default::default()
         };
 
-        let strategy = factory::build_strategy(config);
+        let strategy = factory::build_strategy(config, &ExtensionGroups::new()).unwrap();
         let context = Context::new();
 
         // Старый файл матчится
@@ -174,9 +228,10 @@ preferred_tiers:
             preferred_tiers: vec!["cache".to_string()],
             required: true,
             action: StrategyAction::Evaluate,
+            ..Default::default()
         };
 
-        let strategy = factory::build_strategy(config);
+        let strategy = factory::build_strategy(config, &ExtensionGroups::new()).unwrap();
         assert!(strategy.is_required);
     }
 
@@ -195,9 +250,10 @@ preferred_tiers:
             preferred_tiers: vec!["cache".to_string()],
             required: false,
             action: StrategyAction::Evaluate,
+            ..Default::default()
         };
 
-        let strategy = factory::build_strategy(config);
+        let strategy = factory::build_strategy(config, &ExtensionGroups::new()).unwrap();
         let context = Context::new();
 
         // Оба условия должны выполниться (AND)
@@ -221,9 +277,10 @@ preferred_tiers:
             ],
             required: false,
             action: StrategyAction::Evaluate,
+            ..Default::default()
         };
 
-        let strategy = factory::build_strategy(config);
+        let strategy = factory::build_strategy(config, &ExtensionGroups::new()).unwrap();
         // Проверяем что все tier'ы добавлены (косвенно через публичные поля нельзя)
         // Но мы знаем что это работает благодаря тестам PlacementStrategy
         assert_eq!(strategy.name, "multi_tier");
@@ -238,6 +295,7 @@ preferred_tiers:
             preferred_tiers: vec!["cache".to_string()],
             required: false,
             action: StrategyAction::Evaluate,
+            ..Default::default()
         };
 
         let cloned = config.clone();
@@ -0,0 +1,243 @@
+//! `strategy_templates` and `extends`, resolved before the config is deserialized
+//!
+//! Merging happens on the parsed document so a key left out of a strategy can be
+//! told apart from one set to its default.
+
+use super::condition::ConditionConfig;
+use super::schedule::ScheduleConfig;
+use super::strategy::StrategyAction;
+use super::{ConfigError, Result};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+/// Strategy settings shared by the strategies that `extends` the template
+///
+/// Keys set on a strategy override the template's. Conditions are merged by
+/// `type`: a strategy condition replaces the template conditions of its type,
+/// the rest are kept.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StrategyTemplateConfig {
+    /// Template this one builds on
+    #[serde(default)]
+    pub extends: Option<String>,
+    #[serde(default)]
+    pub conditions: Vec<ConditionConfig>,
+    #[serde(default)]
+    pub preferred_tiers: Option<Vec<String>>,
+    #[serde(default)]
+    pub required: Option<bool>,
+    #[serde(default)]
+    pub action: Option<StrategyAction>,
+    #[serde(default)]
+    pub max_moves_per_run: Option<usize>,
+    #[serde(default)]
+    pub max_bytes_per_run: Option<u64>,
+    #[serde(default)]
+    pub schedule: Option<ScheduleConfig>,
+}
+
+/// Merges templates into the strategies of a parsed config document
+///
+/// Every template is checked for unknown parents and cycles, used or not.
+/// Returns whether any strategy extends a template.
+pub(super) fn resolve_templates(config: &mut Value) -> Result<bool> {
+    let templates = config
+        .get("strategy_templates")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    for name in templates.keys() {
+        resolve(name, &templates, &mut Vec::new())?;
+    }
+
+    let Some(strategies) = config.get_mut("strategies").and_then(Value::as_array_mut) else {
+        return Ok(false);
+    };
+    let mut extended = false;
+    for strategy in strategies.iter_mut().filter_map(Value::as_object_mut) {
+        let Some(template) = strategy.get("extends").and_then(Value::as_str) else {
+            continue;
+        };
+        if !templates.contains_key(template) {
+            return Err(ConfigError::UnknownStrategyTemplate {
+                strategy: strategy
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                template: template.to_string(),
+            });
+        }
+        let base = resolve(template, &templates, &mut Vec::new())?;
+        *strategy = merge(base, strategy);
+        extended = true;
+    }
+    Ok(extended)
+}
+
+/// Template `name` with its ancestors merged in, without `extends`
+fn resolve(
+    name: &str,
+    templates: &Map<String, Value>,
+    chain: &mut Vec<String>,
+) -> Result<Map<String, Value>> {
+    if chain.iter().any(|link| link == name) {
+        chain.push(name.to_string());
+        return Err(ConfigError::StrategyTemplateCycle {
+            chain: chain.join(" -> "),
+        });
+    }
+    let Some(template) = templates.get(name).and_then(Value::as_object) else {
+        return Err(ConfigError::UnknownStrategyTemplate {
+            strategy: chain.last().cloned().unwrap_or_default(),
+            template: name.to_string(),
+        });
+    };
+
+    chain.push(name.to_string());
+    let base = match template.get("extends").and_then(Value::as_str) {
+        Some(parent) => resolve(parent, templates, chain)?,
+        None => Map::new(),
+    };
+    chain.pop();
+
+    let mut merged = merge(base, template);
+    merged.remove("extends");
+    Ok(merged)
+}
+
+/// `over` laid on top of `base`, conditions merged by type
+fn merge(mut base: Map<String, Value>, over: &Map<String, Value>) -> Map<String, Value> {
+    for (key, value) in over {
+        let value = match (key.as_str(), base.remove(key), value) {
+            ("conditions", Some(Value::Array(inherited)), Value::Array(own)) => {
+                let replaced: HashSet<_> = own.iter().filter_map(condition_type).collect();
+                let mut conditions: Vec<Value> = inherited
+                    .into_iter()
+                    .filter(|condition| {
+                        condition_type(condition).is_none_or(|kind| !replaced.contains(kind))
+                    })
+                    .collect();
+                conditions.extend(own.iter().cloned());
+                Value::Array(conditions)
+            }
+            _ => value.clone(),
+        };
+        base.insert(key.clone(), value);
+    }
+    base
+}
+
+fn condition_type(condition: &Value) -> Option<&str> {
+    condition.get("type").and_then(Value::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BalancingConfig;
+    use crate::config::ConfigFormat;
+    use crate::config::condition::PrefixModeConfig;
+
+    const CONFIG: &str = r"
+tiers:
+  - name: cache
+    path: /mnt/cache
+    priority: 1
+  - name: storage
+    path: /mnt/storage
+    priority: 2
+strategy_templates:
+  media:
+    conditions:
+      - type: file_extension
+        extensions: [mkv, mp4]
+        mode: whitelist
+      - type: age
+        min_hours: 720
+    preferred_tiers: [storage]
+  quick_media:
+    extends: media
+    required: true
+strategies:
+  - name: old_movies
+    priority: 10
+    extends: quick_media
+    conditions:
+      - type: age
+        min_hours: 168
+      - type: path_prefix
+        prefix: movies
+        mode: whitelist
+  - name: old_shows
+    priority: 20
+    extends: media
+    preferred_tiers: [cache, storage]
+";
+
+    fn parse(yaml: &str) -> Result<BalancingConfig> {
+        let mut value: Value = ConfigFormat::Yaml.parse(yaml)?;
+        resolve_templates(&mut value)?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    #[test]
+    fn test_strategies_inherit_from_templates() {
+        let config = parse(CONFIG).unwrap();
+
+        let movies = &config.strategies[0];
+        assert_eq!(movies.extends.as_deref(), Some("quick_media"));
+        assert!(movies.required);
+        assert_eq!(movies.preferred_tiers, vec!["storage"]);
+        assert_eq!(
+            movies.conditions[1..],
+            [
+                ConditionConfig::Age {
+                    min_hours: Some(168),
+                    max_hours: None
+                },
+                ConditionConfig::PathPrefix {
+                    prefix: "movies".to_string(),
+                    mode: PrefixModeConfig::Whitelist
+                },
+            ]
+        );
+        assert!(matches!(
+            movies.conditions[0],
+            ConditionConfig::FileExtension { .. }
+        ));
+
+        let shows = &config.strategies[1];
+        assert!(!shows.required);
+        assert_eq!(shows.preferred_tiers, vec!["cache", "storage"]);
+        assert_eq!(shows.conditions.len(), 2);
+    }
+
+    #[test]
+    fn test_template_errors() {
+        let unknown = CONFIG.replace(
+            "extends: media\n    preferred",
+            "extends: films\n    preferred",
+        );
+        match parse(&unknown).unwrap_err() {
+            ConfigError::UnknownStrategyTemplate { strategy, template } => {
+                assert_eq!(
+                    (strategy.as_str(), template.as_str()),
+                    ("old_shows", "films")
+                );
+            }
+            other => panic!("Expected UnknownStrategyTemplate error, got {other:?}"),
+        }
+
+        let cycle = CONFIG.replace("  media:\n", "  media:\n    extends: quick_media\n");
+        match parse(&cycle).unwrap_err() {
+            ConfigError::StrategyTemplateCycle { chain } => {
+                assert_eq!(chain, "media -> quick_media -> media");
+            }
+            other => panic!("Expected StrategyTemplateCycle error, got {other:?}"),
+        }
+    }
+}
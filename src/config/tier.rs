@@ -1,13 +1,88 @@
+use super::ByteSize;
 use crate::Tier;
+use crate::disk::FsDisk;
+use crate::remote::SshRemote;
+use crate::scanner::ScanFilter;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use std::io;
 use std::path::PathBuf;
+use std::sync::Arc;
 
-#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+/// Order in which files are pushed off a tier when it needs space
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EvictionPolicy {
+    /// Lowest strategy priority first, then oldest modified, then largest
+    #[default]
+    PriorityThenLru,
+    /// Least recently accessed first (needs atime updates on the filesystem)
+    Lru,
+    /// Largest files first, freeing space with the fewest moves
+    LargestFirst,
+    /// Oldest modified first
+    OldestFirst,
+}
+
+/// How a tier group picks the member disk for an incoming file
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MemberSelection {
+    /// Member with the most free space
+    #[default]
+    MostFreeSpace,
+    /// Members in turn, skipping those without room
+    RoundRobin,
+}
+
+/// Where a tier's usage and free space come from
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FsBackend {
+    /// ZFS or Btrfs when the tier is on one, `statvfs` otherwise
+    Auto,
+    /// `statvfs`, which misses space held by snapshots and quotas
+    #[default]
+    Posix,
+    /// `zfs list`, counting snapshots and honoring dataset quotas
+    Zfs,
+    /// `btrfs qgroup show`, honoring the subvolume's qgroup limit
+    Btrfs,
+}
+
+/// Files left out of planning on every tier, unless the tier sets its own
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ScanFilterConfig {
+    /// Skip dotfiles, files in dot directories and system metadata (`Thumbs.db`, `@eaDir`)
+    #[serde(default)]
+    pub ignore_hidden: bool,
+    /// Skip files smaller than this: `1MiB`
+    #[serde(default)]
+    pub min_file_size: Option<ByteSize>,
+}
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Deserialize, Clone, Default, PartialEq, Eq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct TierConfig {
     pub name: String,
+    /// Tier root, or `ssh://user@host/path` for a tier on another machine; leave
+    /// empty when `paths` lists the member disks of a group
+    #[serde(default)]
     pub path: PathBuf,
+    /// Member disks treated as one logical tier
+    #[serde(default)]
+    pub paths: Vec<PathBuf>,
+    /// How a group picks the disk for incoming files (default: `most_free_space`)
+    #[serde(default)]
+    pub member_selection: MemberSelection,
+    /// Subdirectories to scan, relative to the tier path; other files on the mount are ignored
+    #[serde(default)]
+    pub scan_paths: Vec<PathBuf>,
+    /// Capacity allotted to the tier; usage counts only scanned files against it
+    #[serde(default)]
+    pub capacity_override_bytes: Option<u64>,
     pub priority: u32,
     /// Maximum tier usage percent (0-100). If not specified, tier can fill to 100%
     #[serde(default)]
@@ -15,17 +90,137 @@ pub struct TierConfig {
     /// Minimum tier usage percent (0-100). Won't demote files until this threshold is reached
     #[serde(default)]
     pub min_usage_percent: Option<u64>,
+    /// Execute moves into this tier back to back so sleeping disks wake up once
+    #[serde(default)]
+    pub spindown_aware: bool,
+    /// With `spindown_aware`, defer moves into this tier until they add up to this many GB
+    #[serde(default)]
+    pub spindown_min_batch_gb: Option<u64>,
+    /// Which files are evicted first when this tier needs space (default: `priority_then_lru`)
+    #[serde(default)]
+    pub eviction_policy: EvictionPolicy,
+    /// Free space that must remain on the destination disk after each move, in bytes
+    #[serde(default)]
+    pub safety_margin_bytes: u64,
+    /// Space kept for writers outside tierflow, taken off the tier's capacity in every plan
+    #[serde(default)]
+    pub reserve_bytes: u64,
+    /// Like `reserve_bytes`, as a percentage of the capacity; the larger of the two applies
+    #[serde(default)]
+    pub reserve_percent: u64,
+    /// Abort planning if an entry sits deeper than this below a scan root (a file directly in it is 1)
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+    /// Abort planning if the scan roots hold more files than this
+    #[serde(default)]
+    pub max_files: Option<usize>,
+    /// Leave dotfiles and system metadata out of planning; overrides `scan_filter.ignore_hidden`
+    #[serde(default)]
+    pub ignore_hidden: Option<bool>,
+    /// Leave smaller files out of planning: `1MiB`; overrides `scan_filter.min_file_size`
+    #[serde(default)]
+    pub min_file_size: Option<ByteSize>,
+    /// Never a destination for moves, copies or evictions; files on it can still move off
+    #[serde(default)]
+    pub read_only: bool,
+    /// Move every file off this tier and never onto it, to empty a disk before removing it
+    #[serde(default)]
+    pub drain: bool,
+    /// Never place larger files on this tier, e.g. `40GB` to keep remuxes off a small cache
+    #[serde(default)]
+    pub max_file_size: Option<ByteSize>,
+    /// Leave a small stub at the old path of every file moved onto this tier,
+    /// which `tierflow recall` swaps back for the real file
+    #[serde(default)]
+    pub leave_stubs: bool,
+    /// Where usage and free space come from: `posix` (default), `auto`, `zfs` or `btrfs`
+    #[serde(default)]
+    pub fs_backend: FsBackend,
 }
 
 impl TierConfig {
+    /// Takes the filter settings this tier leaves unset from `global`
+    #[must_use]
+    pub fn with_default_scan_filter(mut self, global: ScanFilterConfig) -> Self {
+        self.ignore_hidden.get_or_insert(global.ignore_hidden);
+        self.min_file_size = self.min_file_size.or(global.min_file_size);
+        self
+    }
+
+    /// Why the usage percentages or `reserve_percent` are invalid, if they are
+    pub fn usage_limits_error(&self) -> Option<String> {
+        match (self.min_usage_percent, self.max_usage_percent) {
+            (_, Some(max)) if !(1..=100).contains(&max) => Some(format!(
+                "max_usage_percent must be between 1 and 100, got {max}"
+            )),
+            (Some(min), _) if min > 100 => {
+                Some(format!("min_usage_percent must be <= 100, got {min}"))
+            }
+            (Some(min), Some(max)) if min >= max => Some(format!(
+                "min_usage_percent ({min}) must be less than max_usage_percent ({max})"
+            )),
+            _ if self.reserve_percent >= 100 => Some(format!(
+                "reserve_percent must be less than 100, got {}",
+                self.reserve_percent
+            )),
+            _ => None,
+        }
+    }
+
     pub fn into_tier(self) -> io::Result<Tier> {
-        Tier::new(
-            self.name,
-            self.path,
-            self.priority,
-            self.max_usage_percent,
-            self.min_usage_percent,
-        )
+        let mut paths = self.paths.into_iter();
+        let path = paths.next().unwrap_or(self.path);
+        let tier = match SshRemote::parse(&path) {
+            Some(remote) => {
+                let remote =
+                    remote.map_err(|reason| io::Error::new(io::ErrorKind::InvalidInput, reason))?;
+                Tier::new_unchecked(
+                    self.name,
+                    remote.path.clone(),
+                    self.priority,
+                    self.max_usage_percent,
+                    self.min_usage_percent,
+                )?
+                .with_remote(remote)
+            }
+            None => Tier::new(
+                self.name,
+                path,
+                self.priority,
+                self.max_usage_percent,
+                self.min_usage_percent,
+            )?,
+        }
+        .with_members(paths, self.member_selection)?
+        .with_scan_paths(self.scan_paths)
+        .with_eviction_policy(self.eviction_policy)
+        .with_safety_margin(self.safety_margin_bytes)
+        .with_reserve(self.reserve_bytes, self.reserve_percent)
+        .with_scan_limits(self.max_depth, self.max_files)
+        .with_scan_filter(ScanFilter {
+            ignore_hidden: self.ignore_hidden.unwrap_or(false),
+            min_file_size: self.min_file_size.map_or(0, |size| size.0),
+        })
+        .with_read_only(self.read_only)
+        .with_drain(self.drain)
+        .with_max_file_size(self.max_file_size.map(|size| size.0))
+        .with_leave_stubs(self.leave_stubs);
+        let tier = if tier.remote.is_none() && self.fs_backend != FsBackend::Posix {
+            tier.with_disk_ops(Arc::new(FsDisk::new(self.fs_backend)))
+        } else {
+            tier
+        };
+        let tier = match self.capacity_override_bytes {
+            Some(bytes) => tier.with_capacity_override(bytes),
+            None => tier,
+        };
+
+        if self.spindown_aware {
+            let min_batch_bytes = self.spindown_min_batch_gb.map(|gb| gb * 1024 * 1024 * 1024);
+            Ok(tier.with_spindown(min_batch_bytes))
+        } else {
+            Ok(tier)
+        }
     }
 }
 
@@ -44,6 +239,19 @@ priority: 1
         assert_eq!(config.name, "cache");
         assert_eq!(config.path, PathBuf::from("/mnt/cache"));
         assert_eq!(config.priority, 1);
+        assert_eq!(config.eviction_policy, EvictionPolicy::PriorityThenLru);
+    }
+
+    #[test]
+    fn test_deserialize_eviction_policy() {
+        let yaml = r"
+name: cache
+path: /mnt/cache
+priority: 1
+eviction_policy: largest_first
+";
+        let config: TierConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.eviction_policy, EvictionPolicy::LargestFirst);
     }
 
     #[test]
@@ -71,6 +279,7 @@ priority: 1
             priority: 1,
             max_usage_percent: None,
             min_usage_percent: None,
+            ..Default::default()
         };
 
         let tier = config.into_tier().unwrap();
@@ -87,12 +296,27 @@ priority: 1
             priority: 1,
             max_usage_percent: None,
             min_usage_percent: None,
+            ..Default::default()
         };
 
         let result = config.into_tier();
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_deserialize_tier_group() {
+        let yaml = r"
+name: storage
+paths: [/mnt/disk1, /mnt/disk2]
+priority: 10
+member_selection: round_robin
+";
+        let config: TierConfig = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.path.as_os_str().is_empty());
+        assert_eq!(config.paths.len(), 2);
+        assert_eq!(config.member_selection, MemberSelection::RoundRobin);
+    }
+
     #[test]
     fn test_tier_config_clone() {
         let config = TierConfig {
@@ -101,6 +325,7 @@ priority: 1
             priority: 1,
             max_usage_percent: Some(85),
             min_usage_percent: Some(30),
+            ..Default::default()
         };
 
         let cloned = config.clone();
@@ -131,4 +356,36 @@ priority: 10
         assert_eq!(config.name, "storage");
         assert_eq!(config.max_usage_percent, None);
     }
+
+    #[test]
+    fn test_tier_scan_filter_overrides_global() {
+        let yaml = r"
+name: cache
+path: /mnt/cache
+priority: 1
+ignore_hidden: false
+";
+        let config: TierConfig = serde_yaml::from_str(yaml).unwrap();
+        let global = ScanFilterConfig {
+            ignore_hidden: true,
+            min_file_size: Some(ByteSize(1024)),
+        };
+
+        let config = config.with_default_scan_filter(global);
+        assert_eq!(config.ignore_hidden, Some(false));
+        assert_eq!(config.min_file_size, Some(ByteSize(1024)));
+    }
+
+    #[test]
+    fn test_into_tier_spindown() {
+        let yaml = format!(
+            "name: archive\npath: {}\npriority: 10\nspindown_aware: true\nspindown_min_batch_gb: 2\n",
+            std::env::temp_dir().display()
+        );
+        let config: TierConfig = serde_yaml::from_str(&yaml).unwrap();
+
+        let tier = config.into_tier().unwrap();
+        assert!(tier.spindown_aware);
+        assert_eq!(tier.spindown_min_batch_bytes, Some(2 * 1024 * 1024 * 1024));
+    }
 }
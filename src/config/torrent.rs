@@ -0,0 +1,73 @@
+use super::PathMappingConfig;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// Torrent client queried for `torrent_*` conditions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TorrentClientType {
+    /// qBittorrent Web API v2
+    #[default]
+    Qbittorrent,
+    /// Transmission RPC
+    Transmission,
+}
+
+impl std::fmt::Display for TorrentClientType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Qbittorrent => write!(f, "qBittorrent"),
+            Self::Transmission => write!(f, "Transmission"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TorrentClientConfig {
+    /// Client to query (default: qbittorrent)
+    #[serde(default)]
+    pub client: TorrentClientType,
+
+    /// Base URL of the Web UI (e.g., "<http://localhost:8080>")
+    pub url: String,
+
+    /// Omit when the client doesn't ask for a login (e.g. whitelisted subnets)
+    #[serde(default)]
+    pub username: Option<String>,
+
+    #[serde(default)]
+    pub password: Option<String>,
+
+    /// Map the client's download paths to host paths, when it runs in a container
+    #[serde(default)]
+    pub path_mappings: Vec<PathMappingConfig>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_torrent_client_config() {
+        let yaml = r#"
+client: transmission
+url: "http://localhost:9091"
+username: admin
+password: secret
+path_mappings:
+  - host_prefix: /mnt/cache/torrents
+    app_prefix: /downloads
+"#;
+
+        let config: TorrentClientConfig = serde_yaml::from_str(yaml).expect("Should parse");
+        assert_eq!(config.client, TorrentClientType::Transmission);
+        assert_eq!(config.username.as_deref(), Some("admin"));
+        assert_eq!(config.path_mappings[0].app_prefix, "/downloads");
+
+        let config: TorrentClientConfig =
+            serde_yaml::from_str("url: http://localhost:8080").expect("Should parse");
+        assert_eq!(config.client, TorrentClientType::Qbittorrent);
+        assert!(config.password.is_none());
+    }
+}
@@ -0,0 +1,109 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::fmt;
+
+/// How much the mover checks a copy before removing the source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationMode {
+    /// Trust the copy tool's exit status
+    None,
+    /// Compare the sizes of copy and source
+    SizeOnly,
+    /// Hash source and copy once each before the atomic rename
+    ChecksumOnce,
+    /// Like `checksum_once`, then hash the renamed destination again before removing the source
+    Paranoid,
+}
+
+impl VerificationMode {
+    /// Whether this mode reads file contents
+    pub const fn hashes(self) -> bool {
+        matches!(self, Self::ChecksumOnce | Self::Paranoid)
+    }
+}
+
+/// Hash used to compare a copy with its source
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HashAlgorithm {
+    /// Fast non-cryptographic 64-bit hash
+    #[default]
+    Xxh3,
+    Blake3,
+    Sha256,
+    /// No hashing; checksum verification modes fall back to a size check
+    None,
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Xxh3 => "xxh3",
+            Self::Blake3 => "blake3",
+            Self::Sha256 => "sha256",
+            Self::None => "none",
+        })
+    }
+}
+
+/// Post-copy hash verification and its resource budget
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct VerifyConfig {
+    /// Cap on total hashing throughput across all moves, in MB/s
+    #[serde(default)]
+    pub max_mbps: Option<u64>,
+
+    /// Maximum number of files hashed at the same time
+    #[serde(default)]
+    pub max_threads: Option<usize>,
+
+    /// Hash algorithm
+    #[serde(default)]
+    pub hash: HashAlgorithm,
+
+    /// Threads hashing parts of one large file in parallel (default: 1)
+    #[serde(default)]
+    pub threads_per_file: Option<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_verify_config() {
+        let config: VerifyConfig = serde_yaml::from_str("max_mbps: 200\nmax_threads: 1").unwrap();
+        assert_eq!(config.max_mbps, Some(200));
+        assert_eq!(config.max_threads, Some(1));
+        assert_eq!(config.hash, HashAlgorithm::Xxh3);
+
+        let config: VerifyConfig = serde_yaml::from_str(
+            "hash: blake3
+threads_per_file: 4",
+        )
+        .unwrap();
+        assert_eq!(config.hash, HashAlgorithm::Blake3);
+        assert_eq!(config.threads_per_file, Some(4));
+        assert!(serde_yaml::from_str::<VerifyConfig>("hash: md5").is_err());
+    }
+
+    #[test]
+    fn test_deserialize_verify_config_defaults() {
+        let config: VerifyConfig = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(config, VerifyConfig::default());
+    }
+
+    #[test]
+    fn test_deserialize_verification_mode() {
+        let mode: VerificationMode = serde_yaml::from_str("checksum_once").unwrap();
+        assert_eq!(mode, VerificationMode::ChecksumOnce);
+        assert!(mode.hashes());
+        assert!(
+            !serde_yaml::from_str::<VerificationMode>("size_only")
+                .unwrap()
+                .hashes()
+        );
+    }
+}
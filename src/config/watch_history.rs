@@ -0,0 +1,231 @@
+use super::PathMappingConfig;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// Service that provides viewing history
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchHistoryProviderType {
+    /// Tautulli (Plex)
+    #[default]
+    Tautulli,
+    /// Jellyfin, or Emby which serves the same API
+    #[serde(alias = "emby")]
+    Jellyfin,
+}
+
+impl std::fmt::Display for WatchHistoryProviderType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tautulli => write!(f, "Tautulli"),
+            Self::Jellyfin => write!(f, "Jellyfin"),
+        }
+    }
+}
+
+/// How library files are matched to watched episodes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EpisodeMatching {
+    /// Parse show, season and episode from filenames (S01E05)
+    #[default]
+    Filename,
+    /// Also resolve watched episodes to their real file paths via Plex metadata
+    Metadata,
+}
+
+/// Watch history configuration for tracking viewing progress
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WatchHistoryConfig {
+    /// Provider to fetch history from (default: tautulli)
+    #[serde(default)]
+    pub provider: WatchHistoryProviderType,
+
+    /// Base URL of the provider (e.g., "<http://localhost:8181>"); not needed with `fixture`
+    #[serde(default)]
+    pub url: String,
+
+    /// API key for authentication
+    #[serde(default)]
+    pub api_key: String,
+
+    /// Read history from this saved Tautulli `get_history` response instead of the provider
+    #[serde(default)]
+    pub fixture: Option<PathBuf>,
+
+    /// Number of history items to fetch (default: 1000)
+    #[serde(default = "default_history_length")]
+    pub history_length: u32,
+
+    /// Percent complete threshold to consider episode "watched" (default: 90)
+    #[serde(default = "default_watched_threshold")]
+    pub watched_threshold: u8,
+
+    /// Only consider episodes watched in last N days (default: 30)
+    #[serde(default = "default_days_back")]
+    pub days_back: u32,
+
+    /// Number of episodes to keep before currently watched (default: 2)
+    #[serde(default = "default_backward_episodes")]
+    pub backward_episodes: u32,
+
+    /// Number of episodes to keep after currently watched (default: 5)
+    #[serde(default = "default_forward_episodes")]
+    pub forward_episodes: u32,
+
+    /// Keep the next unwatched episodes of every show in the fetched history,
+    /// even if it was last watched before `days_back` (default: false)
+    #[serde(default)]
+    pub keep_next_unwatched: bool,
+
+    /// Episodes kept per show by `keep_next_unwatched` (default: 1)
+    #[serde(default = "default_next_unwatched_episodes")]
+    pub next_unwatched_episodes: u32,
+
+    /// How files are matched to watched episodes (default: filename)
+    #[serde(default)]
+    pub episode_matching: EpisodeMatching,
+
+    /// Map Plex library paths to host paths for `episode_matching: metadata`
+    #[serde(default)]
+    pub path_mappings: Vec<PathMappingConfig>,
+}
+
+impl WatchHistoryConfig {
+    /// Default settings with history read from `fixture`, for a config without `watch_history`
+    pub fn from_fixture(fixture: PathBuf) -> Self {
+        Self {
+            provider: WatchHistoryProviderType::Tautulli,
+            url: String::new(),
+            api_key: String::new(),
+            fixture: Some(fixture),
+            history_length: default_history_length(),
+            watched_threshold: default_watched_threshold(),
+            days_back: default_days_back(),
+            backward_episodes: default_backward_episodes(),
+            forward_episodes: default_forward_episodes(),
+            keep_next_unwatched: false,
+            next_unwatched_episodes: default_next_unwatched_episodes(),
+            episode_matching: EpisodeMatching::default(),
+            path_mappings: Vec::new(),
+        }
+    }
+}
+
+const fn default_history_length() -> u32 {
+    1000
+}
+
+const fn default_watched_threshold() -> u8 {
+    90
+}
+
+const fn default_days_back() -> u32 {
+    30
+}
+
+const fn default_backward_episodes() -> u32 {
+    2
+}
+
+const fn default_forward_episodes() -> u32 {
+    5
+}
+
+const fn default_next_unwatched_episodes() -> u32 {
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tautulli_config_defaults() {
+        let yaml = r#"
+url: "http://localhost:8181"
+api_key: "test-key"
+"#;
+
+        let config: WatchHistoryConfig = serde_yaml::from_str(yaml).expect("Should parse");
+
+        assert_eq!(config.provider, WatchHistoryProviderType::Tautulli);
+        assert_eq!(config.url, "http://localhost:8181");
+        assert_eq!(config.api_key, "test-key");
+        assert_eq!(config.history_length, 1000);
+        assert_eq!(config.watched_threshold, 90);
+        assert_eq!(config.days_back, 30);
+        assert_eq!(config.backward_episodes, 2);
+        assert_eq!(config.forward_episodes, 5);
+        assert!(!config.keep_next_unwatched);
+        assert_eq!(config.next_unwatched_episodes, 1);
+        assert_eq!(config.episode_matching, EpisodeMatching::Filename);
+        assert!(config.path_mappings.is_empty());
+    }
+
+    #[test]
+    fn test_watch_history_metadata_matching() {
+        let yaml = r#"
+url: "http://localhost:8181"
+api_key: "test-key"
+episode_matching: metadata
+path_mappings:
+  - host_prefix: /mnt/cache/tv
+    app_prefix: /data/tv
+  - host_prefix: /mnt/storage/tv
+    app_prefix: /data/tv
+"#;
+
+        let config: WatchHistoryConfig = serde_yaml::from_str(yaml).expect("Should parse");
+
+        assert_eq!(config.episode_matching, EpisodeMatching::Metadata);
+        assert_eq!(config.path_mappings.len(), 2);
+        assert_eq!(config.path_mappings[1].app_prefix, "/data/tv");
+    }
+
+    #[test]
+    fn test_tautulli_config_custom_values() {
+        let yaml = r#"
+url: "http://192.168.1.100:8181"
+api_key: "my-api-key"
+history_length: 500
+watched_threshold: 85
+days_back: 14
+backward_episodes: 3
+forward_episodes: 10
+keep_next_unwatched: true
+next_unwatched_episodes: 2
+"#;
+
+        let config: WatchHistoryConfig = serde_yaml::from_str(yaml).expect("Should parse");
+
+        assert_eq!(config.url, "http://192.168.1.100:8181");
+        assert_eq!(config.api_key, "my-api-key");
+        assert_eq!(config.history_length, 500);
+        assert_eq!(config.watched_threshold, 85);
+        assert_eq!(config.days_back, 14);
+        assert_eq!(config.backward_episodes, 3);
+        assert_eq!(config.forward_episodes, 10);
+        assert!(config.keep_next_unwatched);
+        assert_eq!(config.next_unwatched_episodes, 2);
+    }
+
+    #[test]
+    fn test_watch_history_jellyfin_provider() {
+        let yaml = r#"
+provider: jellyfin
+url: "http://localhost:8096"
+api_key: "jellyfin-key"
+"#;
+
+        let config: WatchHistoryConfig = serde_yaml::from_str(yaml).expect("Should parse");
+        assert_eq!(config.provider, WatchHistoryProviderType::Jellyfin);
+
+        let emby: WatchHistoryConfig =
+            serde_yaml::from_str("provider: emby\nurl: http://emby:8096\napi_key: k")
+                .expect("Should parse");
+        assert_eq!(emby.provider, WatchHistoryProviderType::Jellyfin);
+    }
+}
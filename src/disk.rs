@@ -1,6 +1,15 @@
 //! Disk operations abstraction for testing and flexibility
 
-use std::path::Path;
+use crate::config::FsBackend;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Total and available bytes
+pub(crate) type Space = (u64, u64);
 
 /// Trait for disk space operations
 ///
@@ -54,6 +63,215 @@ impl DiskOperations for RealDisk {
     }
 }
 
+/// Space answers reused for a while, for backends that run a command per query
+#[derive(Debug)]
+pub(crate) struct SpaceCache {
+    ttl: Duration,
+    /// Space per path with when it was asked, `None` when the query failed
+    entries: Mutex<HashMap<PathBuf, (Instant, Option<Space>)>>,
+}
+
+impl SpaceCache {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The cached space of `path`, asking `query` when there is none or it is stale
+    ///
+    /// Failures are kept too, so a broken backend isn't asked on every call.
+    pub(crate) fn get(&self, path: &Path, query: impl FnOnce() -> Option<Space>) -> Option<Space> {
+        let mut entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some((at, space)) = entries.get(path)
+            && at.elapsed() < self.ttl
+        {
+            return *space;
+        }
+        let space = query();
+        entries.insert(path.to_path_buf(), (Instant::now(), space));
+        space
+    }
+}
+
+/// How long a `zfs` or `btrfs` answer is reused; short, since moves re-check free space
+const FS_SPACE_TTL: Duration = Duration::from_secs(2);
+
+/// Space from the filesystem's own tools on ZFS and Btrfs, `statvfs` elsewhere
+///
+/// `statvfs` leaves out what snapshots hold and ignores dataset quotas and
+/// qgroup limits, so projections on those filesystems come out wrong. When
+/// a tool fails, the `statvfs` answer is used with a warning.
+#[derive(Debug)]
+pub struct FsDisk {
+    backend: FsBackend,
+    cache: SpaceCache,
+    /// Whether a tool failure was already logged as a warning
+    warned: AtomicBool,
+}
+
+impl FsDisk {
+    pub fn new(backend: FsBackend) -> Self {
+        Self {
+            backend,
+            cache: SpaceCache::new(FS_SPACE_TTL),
+            warned: AtomicBool::new(false),
+        }
+    }
+
+    fn space(&self, path: &Path) -> Option<Space> {
+        let backend = match self.backend {
+            FsBackend::Auto => detect_backend(path),
+            backend => backend,
+        };
+        let query: fn(&Path) -> Result<Option<Space>, String> = match backend {
+            FsBackend::Auto | FsBackend::Posix => return posix_space(path),
+            FsBackend::Zfs => zfs_space,
+            FsBackend::Btrfs => btrfs_space,
+        };
+        self.cache.get(path, || match query(path) {
+            Ok(Some(space)) => Some(space),
+            Ok(None) => posix_space(path),
+            Err(e) => {
+                // Once per tier, since a tool that fails usually keeps failing
+                if self.warned.swap(true, Ordering::Relaxed) {
+                    tracing::debug!(
+                        "Failed to get {backend:?} space for {}, using statvfs: {e}",
+                        path.display()
+                    );
+                } else {
+                    tracing::warn!(
+                        "Failed to get {backend:?} space for {}, using statvfs: {e}",
+                        path.display()
+                    );
+                }
+                posix_space(path)
+            }
+        })
+    }
+}
+
+impl DiskOperations for FsDisk {
+    fn get_total_space(&self, path: &Path) -> u64 {
+        // 1 avoids division by zero, like the real disk
+        self.space(path).map_or(1, |(total, _)| total)
+    }
+
+    fn get_free_space(&self, path: &Path) -> u64 {
+        self.space(path).map_or(0, |(_, free)| free)
+    }
+}
+
+/// ZFS or Btrfs from the filesystem type of `path`, `Posix` for anything else
+#[cfg(target_os = "linux")]
+fn detect_backend(path: &Path) -> FsBackend {
+    use std::os::unix::ffi::OsStrExt;
+
+    const ZFS_SUPER_MAGIC: i64 = 0x2fc1_2fc2;
+    const BTRFS_SUPER_MAGIC: i64 = 0x9123_683e;
+
+    let Ok(path) = std::ffi::CString::new(path.as_os_str().as_bytes()) else {
+        return FsBackend::Posix;
+    };
+    let mut stat = std::mem::MaybeUninit::<libc::statfs>::uninit();
+    // SAFETY: `path` is NUL-terminated and `stat` is only read after statfs filled it
+    if unsafe { libc::statfs(path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return FsBackend::Posix;
+    }
+    #[allow(clippy::unnecessary_cast, clippy::cast_lossless)]
+    match unsafe { stat.assume_init() }.f_type as i64 {
+        ZFS_SUPER_MAGIC => FsBackend::Zfs,
+        BTRFS_SUPER_MAGIC => FsBackend::Btrfs,
+        _ => FsBackend::Posix,
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn detect_backend(_path: &Path) -> FsBackend {
+    FsBackend::Posix
+}
+
+fn posix_space(path: &Path) -> Option<Space> {
+    fs2::statvfs(path)
+        .inspect_err(|e| tracing::warn!("Failed to get space for {}: {e}", path.display()))
+        .ok()
+        .map(|stat| (stat.total_space(), stat.available_space()))
+}
+
+/// Runs a filesystem tool and returns its stdout
+fn run_tool(program: &str, args: &[&str], path: &Path) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .arg(path)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("{program}: {e}"))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(format!(
+            "{program} failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Dataset space, with snapshots counted as used and the quota as the limit
+fn zfs_space(path: &Path) -> Result<Option<Space>, String> {
+    let output = run_tool("zfs", &["list", "-Hp", "-o", "used,available"], path)?;
+    parse_zfs_list(&output)
+        .map(Some)
+        .ok_or_else(|| format!("unexpected zfs list output: {}", output.trim()))
+}
+
+/// Subvolume space under its qgroup limit; `None` when it has no limit
+fn btrfs_space(path: &Path) -> Result<Option<Space>, String> {
+    let rootid = run_tool("btrfs", &["inspect-internal", "rootid"], path)?;
+    let subvolume: u64 = rootid
+        .trim()
+        .parse()
+        .map_err(|_| format!("unexpected btrfs rootid output: {}", rootid.trim()))?;
+    let output = run_tool("btrfs", &["qgroup", "show", "-ref", "-f", "--raw"], path)?;
+    let Some((used, limit)) = parse_btrfs_qgroup(&output, subvolume) else {
+        return Ok(None);
+    };
+    // The limit may be larger than what the filesystem has left
+    let free = posix_space(path).map_or(0, |(_, free)| free);
+    Ok(Some((limit, limit.saturating_sub(used).min(free))))
+}
+
+/// Total (used + available) and available bytes from `zfs list -Hp -o used,available`
+fn parse_zfs_list(output: &str) -> Option<Space> {
+    let mut fields = output.lines().next()?.split('\t');
+    let used: u64 = fields.next()?.trim().parse().ok()?;
+    let available: u64 = fields.next()?.trim().parse().ok()?;
+    Some((used.saturating_add(available), available))
+}
+
+/// Used bytes and limit of the `0/<subvolume>` qgroup from
+/// `btrfs qgroup show -ref -f --raw`, if it is limited
+///
+/// The referenced limit wins over the exclusive one.
+fn parse_btrfs_qgroup(output: &str, subvolume: u64) -> Option<(u64, u64)> {
+    let qgroup = format!("0/{subvolume}");
+    output.lines().find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [id, rfer, excl, max_rfer, max_excl, ..] = fields[..] else {
+            return None;
+        };
+        if id != qgroup {
+            return None;
+        }
+        let limited = |used: &str, max: &str| Some((used.parse().ok()?, max.parse().ok()?));
+        limited(rfer, max_rfer).or_else(|| limited(excl, max_excl))
+    })
+}
+
 /// Mock disk operations for testing
 #[cfg(test)]
 pub struct MockDisk {
@@ -96,6 +314,41 @@ mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_parse_zfs_and_btrfs_space() {
+        assert_eq!(
+            parse_zfs_list("800000000000\t200000000000\n"),
+            Some((1_000_000_000_000, 200_000_000_000))
+        );
+        assert_eq!(parse_zfs_list("no datasets available\n"), None);
+
+        let qgroups = "\
+Qgroupid    Referenced    Exclusive   Max referenced   Max exclusive   Path
+--------    ----------    ---------   --------------   -------------   ----
+0/5         16384         16384       none             none            <toplevel>
+0/256       900000000     800000000   none             2000000000      downloads
+0/257       600000000     500000000   1000000000       none            media
+0/258       100000000     100000000   none             none            backups
+";
+        assert_eq!(
+            parse_btrfs_qgroup(qgroups, 257),
+            Some((600_000_000, 1_000_000_000))
+        );
+        // Another subvolume's limit is not this one's
+        assert_eq!(
+            parse_btrfs_qgroup(qgroups, 256),
+            Some((800_000_000, 2_000_000_000))
+        );
+        assert_eq!(parse_btrfs_qgroup(qgroups, 258), None);
+        assert_eq!(parse_btrfs_qgroup(qgroups, 25), None);
+        let unlimited = qgroups.replace("1000000000 ", "none       ");
+        assert_eq!(parse_btrfs_qgroup(&unlimited, 257), None);
+
+        let cache = SpaceCache::new(Duration::from_secs(60));
+        assert_eq!(cache.get(Path::new("/a"), || Some((2, 1))), Some((2, 1)));
+        assert_eq!(cache.get(Path::new("/a"), || None), Some((2, 1)));
+    }
+
     #[test]
     fn test_mock_disk_new() {
         let disk = MockDisk::new(1000, 600);
@@ -0,0 +1,156 @@
+//! Comparing the tier usage a plan projected with the usage after execution
+//!
+//! The planner simulates every move against the free space it measured, so
+//! its projections are only as good as that simulation. After a run, each
+//! tier's measured usage is checked against the planning-time usage adjusted
+//! by the moves that actually ran; a large gap means other writers, sparse or
+//! compressed files, or a filesystem reporting space late.
+
+use crate::{BalancingPlan, ExecutionResult, Tier};
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Drift, in percent of a tier's capacity, above which a run warns by default
+pub const DEFAULT_MAX_DRIFT_PERCENT: u64 = 2;
+
+/// Projected and measured usage of one tier after a run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TierDrift {
+    pub tier: String,
+    /// Usage the plan projected with all of its moves done
+    pub projected_used: u64,
+    /// Planning-time usage plus the moves and copies that actually ran
+    pub expected_used: u64,
+    /// Usage measured after execution
+    pub actual_used: u64,
+    /// Capacity of the tier when measured
+    pub total: u64,
+}
+
+impl TierDrift {
+    /// Measured minus expected usage; positive when the tier is fuller than expected
+    pub fn drift_bytes(&self) -> i128 {
+        i128::from(self.actual_used) - i128::from(self.expected_used)
+    }
+
+    /// Size of the drift as a percentage of the tier's capacity
+    pub fn drift_percent(&self) -> f64 {
+        self.drift_bytes().unsigned_abs() as f64 / self.total.max(1) as f64 * 100.0
+    }
+}
+
+/// Drift of every tier the plan projected, after one run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriftReport {
+    /// Unix time of the measurement
+    pub at: u64,
+    pub tiers: Vec<TierDrift>,
+}
+
+impl DriftReport {
+    /// Measures `tiers` and compares them with the plan, counting only the moves in `result`
+    pub fn reconcile(
+        plan: &BalancingPlan,
+        result: &ExecutionResult,
+        tiers: &[Tier],
+        at: SystemTime,
+    ) -> Self {
+        let tiers = tiers
+            .iter()
+            .filter_map(|tier| {
+                let projection = plan.projected_tier_usage.get(&tier.name)?;
+                let mut expected = i128::from(projection.current_used);
+                for moved in &result.moved {
+                    if moved.from_tier == tier.name {
+                        expected -= i128::from(moved.size);
+                    }
+                    if moved.to_tier == tier.name {
+                        expected += i128::from(moved.size);
+                    }
+                }
+                for copied in result.copied.iter().filter(|c| c.to_tier == tier.name) {
+                    expected += i128::from(copied.size);
+                }
+                let total = tier.get_total_space();
+                Some(TierDrift {
+                    tier: tier.name.clone(),
+                    projected_used: projection.projected_used,
+                    expected_used: u64::try_from(expected.max(0)).unwrap_or(u64::MAX),
+                    actual_used: total.saturating_sub(tier.get_free_space()),
+                    total,
+                })
+            })
+            .collect();
+        Self {
+            at: at.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+            tiers,
+        }
+    }
+
+    /// Tiers whose drift is above `max_percent` of their capacity
+    pub fn exceeding(&self, max_percent: u64) -> impl Iterator<Item = &TierDrift> {
+        self.tiers
+            .iter()
+            .filter(move |drift| drift.drift_percent() > max_percent as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Verification;
+    use crate::balancer::TierUsageProjection;
+    use crate::executor::ExecutionMove;
+    use std::path::PathBuf;
+
+    const GB: u64 = 1_000_000_000;
+
+    fn projection(tier: &str, current_used: u64, projected_used: u64) -> TierUsageProjection {
+        TierUsageProjection {
+            tier_name: tier.to_string(),
+            current_used,
+            current_free: 0,
+            projected_used,
+            projected_free: 0,
+            current_percent: 0,
+            projected_percent: 0,
+        }
+    }
+
+    #[test]
+    fn test_reconcile_counts_only_executed_moves() {
+        let mut plan = BalancingPlan::default();
+        // Two 50 GB demotions planned, one ran
+        plan.projected_tier_usage
+            .insert("cache".into(), projection("cache", 700 * GB, 600 * GB));
+        plan.projected_tier_usage
+            .insert("storage".into(), projection("storage", 100 * GB, 200 * GB));
+        let result = ExecutionResult {
+            moved: vec![ExecutionMove {
+                file: PathBuf::from("/mock/a.mkv"),
+                destination: PathBuf::from("/mock/a.mkv"),
+                from_tier: "cache".into(),
+                to_tier: "storage".into(),
+                size: 50 * GB,
+                verification: Verification::Size,
+            }],
+            ..Default::default()
+        };
+        // Cache is as expected, storage has 30 GB more than the move explains
+        let tiers = vec![
+            Tier::new_mock("cache", 1, None, 1000 * GB, 350 * GB),
+            Tier::new_mock("storage", 2, None, 1000 * GB, 820 * GB),
+        ];
+
+        let report = DriftReport::reconcile(&plan, &result, &tiers, UNIX_EPOCH);
+        assert_eq!(report.tiers[0].expected_used, 650 * GB);
+        assert_eq!(report.tiers[0].drift_bytes(), 0);
+        assert_eq!(report.tiers[1].projected_used, 200 * GB);
+        assert_eq!(report.tiers[1].drift_bytes(), i128::from(30 * GB));
+        assert!((report.tiers[1].drift_percent() - 3.0).abs() < 1e-9);
+
+        let exceeding: Vec<_> = report.exceeding(2).map(|d| d.tier.as_str()).collect();
+        assert_eq!(exceeding, ["storage"]);
+        assert_eq!(report.exceeding(5).count(), 0);
+    }
+}
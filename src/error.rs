@@ -17,9 +17,13 @@ pub enum AppError {
     #[error("JSON serialization error: {0}")]
     Json(#[from] serde_json::Error),
 
-    #[error("Tier '{tier}' is locked by process {owner_pid} on {owner_host} for {locked_for:?}")]
+    #[error(
+        "Tier '{tier}' ({}) is locked by process {owner_pid} on {owner_host} for {locked_for:?}",
+        path.display()
+    )]
     TierLocked {
         tier: String,
+        path: PathBuf,
         owner_pid: u32,
         owner_host: String,
         locked_for: Duration,
@@ -28,6 +32,16 @@ pub enum AppError {
     #[error("Failed to acquire lock: {message}")]
     LockError { message: String },
 
+    #[error(
+        "Tier '{tier}' exceeds its scan guardrails: {reason}. Check the tier path, raise the limit, or pass --force"
+    )]
+    ScanLimitExceeded { tier: String, reason: String },
+
+    #[error(
+        "Plan moves too much: {reason}. Largest strategies: {strategies}. Check recent strategy changes, or pass --yes-i-mean-it"
+    )]
+    PlanTooLarge { reason: String, strategies: String },
+
     #[error("Another instance is already running")]
     AlreadyRunning,
 
@@ -1,20 +1,67 @@
+use crate::hooks::{HookRun, Hooks};
 use crate::move_blocker::{BlockDecision, MoveBlocker, snapshot_or_fail_closed};
-use crate::{BalancingPlan, FileChecker, Mover, PlacementDecision, Tier};
+use crate::progress::{MoveStatus, ProgressLog};
+use crate::{
+    BalancingPlan, ConflictSkipped, ExecutionOrder, FileChecker, FileInfo, Mover,
+    PlacementDecision, RetryConfig, RsyncFailed, Tier, Verification, VerificationFailed,
+};
+use std::cmp::Reverse;
 
 #[cfg(test)]
 use crate::NoOpFileChecker;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct ExecutionResult {
     pub files_moved: usize,
     pub bytes_moved: u64,
     pub files_stayed: usize,
     pub files_blocked: usize,
+    pub files_skipped: usize,
+    /// Moves skipped because the destination held a different file (`on_conflict: skip`)
+    pub files_skipped_conflict: usize,
+    /// Moves skipped because the destination disk no longer had room for the file
+    /// plus the tier's safety margin
+    pub files_skipped_space: usize,
+    /// Moves never started because the run was cancelled
+    pub files_cancelled: usize,
+    /// Moves never started because the execution budget ran out; the next run picks them up
+    pub files_deferred: usize,
+    /// Files duplicated by `copy` strategies, originals kept in place
+    pub files_copied: usize,
+    pub bytes_copied: u64,
+    /// Successful moves, in execution order
+    pub moved: Vec<ExecutionMove>,
+    /// Successful copies, in execution order
+    pub copied: Vec<ExecutionMove>,
     pub blocked: Vec<ExecutionBlocked>,
+    /// Files left in place because they were open or still being written
+    pub skipped: Vec<ExecutionSkipped>,
     pub errors: Vec<ExecutionError>,
+    /// Number of `errors` of each kind
+    pub errors_by_kind: BTreeMap<ExecutionErrorKind, usize>,
+    /// Time hash verification spent waiting on the hashing budget
+    pub verify_throttle_time: Duration,
+    /// Post-move and post-run hook commands, in the order they ran
+    pub hooks: Vec<HookRun>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionMove {
+    /// Source path of the moved file
+    pub file: PathBuf,
+    /// Where the file was moved to
+    pub destination: PathBuf,
+    pub from_tier: String,
+    pub to_tier: String,
+    pub size: u64,
+    /// How the copy was checked before the source was removed
+    pub verification: Verification,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -26,17 +73,306 @@ pub struct ExecutionBlocked {
     pub reason: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionSkipped {
+    pub file: PathBuf,
+    pub from_tier: String,
+    pub to_tier: String,
+    pub reason: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ExecutionError {
     pub file: PathBuf,
     pub from_tier: String,
     pub to_tier: String,
     pub error: String,
+    pub kind: ExecutionErrorKind,
+    /// Whether the last failure was worth retrying
+    pub class: ErrorClass,
+    /// Times the move was tried, including retries
+    pub attempts: u32,
+}
+
+/// `EIO`, the same on Linux and the BSDs; `libc` is only a Linux dependency
+const EIO: i32 = 5;
+
+/// What went wrong with a failed move
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ExecutionErrorKind {
+    /// The source file was gone
+    SourceMissing,
+    /// The destination filesystem ran out of space or quota
+    DestinationFull,
+    /// The copy didn't match the source by size or checksum
+    VerificationFailed,
+    PermissionDenied,
+    /// A different file was at the destination (`on_conflict: fail`)
+    Conflict,
+    /// The file was in use or changed while it was copied
+    Busy,
+    /// Timeouts, dropped connections and stale handles of network filesystems
+    Network,
+    /// Read or write error reported by the disk or filesystem
+    Io,
+    /// The copy tool exited with an error
+    MoverFailed,
+    Other,
+}
+
+impl ExecutionErrorKind {
+    pub fn of(err: &io::Error) -> Self {
+        if let Some(inner) = err.get_ref() {
+            if inner.is::<VerificationFailed>() {
+                return Self::VerificationFailed;
+            }
+            if let Some(rsync) = inner.downcast_ref::<RsyncFailed>() {
+                return if rsync.is_transient() {
+                    Self::Network
+                } else {
+                    Self::MoverFailed
+                };
+            }
+        }
+
+        match err.kind() {
+            io::ErrorKind::NotFound => Self::SourceMissing,
+            io::ErrorKind::StorageFull
+            | io::ErrorKind::QuotaExceeded
+            | io::ErrorKind::FileTooLarge => Self::DestinationFull,
+            io::ErrorKind::PermissionDenied | io::ErrorKind::ReadOnlyFilesystem => {
+                Self::PermissionDenied
+            }
+            io::ErrorKind::AlreadyExists => Self::Conflict,
+            io::ErrorKind::ResourceBusy | io::ErrorKind::ExecutableFileBusy => Self::Busy,
+            io::ErrorKind::TimedOut
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::NotConnected
+            | io::ErrorKind::BrokenPipe
+            | io::ErrorKind::StaleNetworkFileHandle
+            | io::ErrorKind::NetworkDown
+            | io::ErrorKind::NetworkUnreachable
+            | io::ErrorKind::HostUnreachable => Self::Network,
+            _ if err.raw_os_error() == Some(EIO) => Self::Io,
+            _ => Self::Other,
+        }
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::SourceMissing => "source_missing",
+            Self::DestinationFull => "destination_full",
+            Self::VerificationFailed => "verification_failed",
+            Self::PermissionDenied => "permission_denied",
+            Self::Conflict => "conflict",
+            Self::Busy => "busy",
+            Self::Network => "network",
+            Self::Io => "io",
+            Self::MoverFailed => "mover_failed",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// Whether a failed move may succeed when tried again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// Timeouts, dropped connections, stale NFS handles and similar hiccups
+    Transient,
+    Permanent,
+}
+
+impl ErrorClass {
+    pub fn of(err: &io::Error) -> Self {
+        if let Some(rsync) = err
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<RsyncFailed>())
+        {
+            return if rsync.is_transient() {
+                Self::Transient
+            } else {
+                Self::Permanent
+            };
+        }
+
+        match err.kind() {
+            io::ErrorKind::TimedOut
+            | io::ErrorKind::Interrupted
+            | io::ErrorKind::WouldBlock
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::NotConnected
+            | io::ErrorKind::BrokenPipe
+            | io::ErrorKind::StaleNetworkFileHandle
+            | io::ErrorKind::ResourceBusy
+            | io::ErrorKind::NetworkDown
+            | io::ErrorKind::NetworkUnreachable
+            | io::ErrorKind::HostUnreachable => Self::Transient,
+            // NFS reports a server that stopped responding as EIO
+            _ if err.raw_os_error() == Some(EIO) => Self::Transient,
+            _ => Self::Permanent,
+        }
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Transient => "transient",
+            Self::Permanent => "permanent",
+        }
+    }
+}
+
+/// Limits on how much one run executes
+///
+/// A move that would take the run past `max_bytes`, or that would start after
+/// `deadline`, is not started; it and every move after it are deferred.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutionBudget {
+    pub max_bytes: Option<u64>,
+    pub deadline: Option<Instant>,
+}
+
+impl ExecutionBudget {
+    /// Budget of `max_bytes` and `max_duration` from now
+    pub fn new(max_bytes: Option<u64>, max_duration: Option<Duration>) -> Self {
+        Self {
+            max_bytes,
+            deadline: max_duration.map(|duration| Instant::now() + duration),
+        }
+    }
+
+    /// Budget left once `bytes` have been moved
+    pub fn spend(self, bytes: u64) -> Self {
+        Self {
+            max_bytes: self.max_bytes.map(|max| max.saturating_sub(bytes)),
+            ..self
+        }
+    }
+
+    /// Why a move of `size` bytes may not start after `bytes_moved`, if it may not
+    fn exhausted_by(&self, bytes_moved: u64, size: u64) -> Option<String> {
+        if let Some(deadline) = self.deadline
+            && Instant::now() >= deadline
+        {
+            return Some("time budget used up".to_string());
+        }
+        self.max_bytes
+            .filter(|&max| bytes_moved.saturating_add(size) > max)
+            .map(|max| format!("byte budget of {max} bytes used up"))
+    }
+}
+
+/// Destination disk too full for a move, found by the re-check right before it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsufficientSpace {
+    pub tier: String,
+    pub root: PathBuf,
+    /// File size plus the tier's safety margin
+    pub needed: u64,
+    pub available: u64,
+}
+
+impl std::fmt::Display for InsufficientSpace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Not enough free space on tier '{}' ({}): {} bytes needed including the safety margin, {} available",
+            self.tier,
+            self.root.display(),
+            self.needed,
+            self.available
+        )
+    }
+}
+
+impl std::error::Error for InsufficientSpace {}
+
+impl InsufficientSpace {
+    /// Whether `err` is a move skipped for lack of space
+    pub fn is_insufficient_space(err: &std::io::Error) -> bool {
+        matches!(err.get_ref(), Some(inner) if inner.is::<Self>())
+    }
+}
+
+/// Sleeps for `delay` in one-second steps; false if `cancel` was set meanwhile
+fn wait_unless_cancelled(delay: Duration, cancel: &AtomicBool) -> bool {
+    let until = Instant::now() + delay;
+    loop {
+        if cancel.load(Ordering::SeqCst) {
+            return false;
+        }
+        let left = until.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return true;
+        }
+        std::thread::sleep(left.min(Duration::from_secs(1)));
+    }
 }
 
 pub struct Executor;
 
 impl Executor {
+    /// Reorders `plan` for execution; `Stay` decisions go last, ties keep plan order
+    ///
+    /// Moves grouped for a spin-down tier (`move_batches`) stay together: they
+    /// are sorted among themselves and the group runs where its first move sorts.
+    pub fn order_for_execution(plan: &mut BalancingPlan, order: ExecutionOrder) {
+        let is_stay = |d: &PlacementDecision| matches!(d, PlacementDecision::Stay { .. });
+        match order {
+            ExecutionOrder::Plan => {}
+            ExecutionOrder::PromotesFirst => {
+                Self::sort_keeping_batches(plan, |d| match d {
+                    PlacementDecision::Promote { .. } => 0,
+                    PlacementDecision::Demote { .. } | PlacementDecision::Copy { .. } => 1,
+                    PlacementDecision::Stay { .. } => 2,
+                });
+            }
+            ExecutionOrder::PriorityDesc => {
+                Self::sort_keeping_batches(plan, |d| (is_stay(d), Reverse(d.strategy_priority())));
+            }
+            ExecutionOrder::SmallestFirst => {
+                Self::sort_keeping_batches(plan, |d| (is_stay(d), d.file_size()));
+            }
+        }
+    }
+
+    /// Stable sort by `key`, with each run of moves in one spin-down batch as a unit
+    fn sort_keeping_batches<K: Ord>(
+        plan: &mut BalancingPlan,
+        key: impl Fn(&PlacementDecision) -> K,
+    ) {
+        let batched: Vec<&str> = plan
+            .move_batches
+            .iter()
+            .filter(|batch| !batch.deferred)
+            .map(|batch| batch.tier.as_str())
+            .collect();
+        // Grouping matches `group_spindown_moves`: target tier and direction
+        let batch_of = |d: &PlacementDecision| match d {
+            PlacementDecision::Stay { .. } => None,
+            _ if !batched.contains(&d.target_tier()) => None,
+            PlacementDecision::Promote { .. } => Some((d.target_tier().to_string(), false)),
+            _ => Some((d.target_tier().to_string(), true)),
+        };
+
+        let mut units: Vec<Vec<PlacementDecision>> = Vec::new();
+        let mut last_batch = None;
+        for decision in plan.decisions.drain(..) {
+            let batch = batch_of(&decision);
+            match units.last_mut() {
+                Some(unit) if batch.is_some() && batch == last_batch => unit.push(decision),
+                _ => units.push(vec![decision]),
+            }
+            last_batch = batch;
+        }
+        for unit in &mut units {
+            unit.sort_by_key(&key);
+        }
+        units.sort_by(|a, b| key(&a[0]).cmp(&key(&b[0])));
+        plan.decisions = units.into_iter().flatten().collect();
+    }
+
     /// Выполняет план балансировки используя указанный Mover
     ///
     /// # Аргументы
@@ -51,6 +387,63 @@ impl Executor {
         tiers: &[Tier],
         file_checker: &dyn FileChecker,
         move_blocker: &dyn MoveBlocker,
+    ) -> ExecutionResult {
+        Self::execute_plan_with_hooks(
+            plan,
+            mover,
+            tiers,
+            file_checker,
+            move_blocker,
+            None,
+            &Hooks::default(),
+            ExecutionBudget::default(),
+            RetryConfig::default(),
+            &AtomicBool::new(false),
+        )
+    }
+
+    /// Same as [`Executor::execute_plan`], recording the outcome of every move in `progress`
+    pub fn execute_plan_with_progress(
+        plan: &BalancingPlan,
+        mover: &dyn Mover,
+        tiers: &[Tier],
+        file_checker: &dyn FileChecker,
+        move_blocker: &dyn MoveBlocker,
+        progress: &mut ProgressLog,
+    ) -> ExecutionResult {
+        Self::execute_plan_with_hooks(
+            plan,
+            mover,
+            tiers,
+            file_checker,
+            move_blocker,
+            Some(progress),
+            &Hooks::default(),
+            ExecutionBudget::default(),
+            RetryConfig::default(),
+            &AtomicBool::new(false),
+        )
+    }
+
+    /// Same as [`Executor::execute_plan`], running `hooks` after every move and at the end,
+    /// and recording progress when a log is given
+    ///
+    /// Once `cancel` is set no new moves are started; the move in progress is finished
+    /// and the rest are counted in `files_cancelled` and left unrecorded for `resume`.
+    /// Moves beyond `budget` are counted in `files_deferred` and recorded as deferred.
+    /// Moves failing with a transient error are tried again as `retry` allows.
+    #[allow(clippy::too_many_arguments)]
+    pub fn execute_plan_with_hooks(
+        plan: &BalancingPlan,
+        mover: &dyn Mover,
+        tiers: &[Tier],
+        file_checker: &dyn FileChecker,
+        move_blocker: &dyn MoveBlocker,
+        mut progress: Option<&mut ProgressLog>,
+        hooks: &Hooks,
+        budget: ExecutionBudget,
+        retry: RetryConfig,
+        cancel: &AtomicBool,
     ) -> ExecutionResult {
         let tier_map: HashMap<String, &Tier> = tiers.iter().map(|t| (t.name.clone(), t)).collect();
         let move_candidates: Vec<PathBuf> = plan
@@ -58,7 +451,8 @@ impl Executor {
             .iter()
             .filter_map(|decision| match decision {
                 PlacementDecision::Promote { file, .. }
-                | PlacementDecision::Demote { file, .. } => Some(file.path.clone()),
+                | PlacementDecision::Demote { file, .. }
+                | PlacementDecision::Copy { file, .. } => Some(file.path.clone()),
                 PlacementDecision::Stay { .. } => None,
             })
             .collect();
@@ -69,9 +463,31 @@ impl Executor {
             bytes_moved: 0,
             files_stayed: 0,
             files_blocked: 0,
+            files_skipped: 0,
+            files_skipped_conflict: 0,
+            files_skipped_space: 0,
+            files_cancelled: 0,
+            files_deferred: 0,
+            files_copied: 0,
+            bytes_copied: 0,
+            moved: Vec::new(),
+            copied: Vec::new(),
             blocked: Vec::new(),
+            skipped: Vec::new(),
             errors: Vec::new(),
+            errors_by_kind: BTreeMap::new(),
+            verify_throttle_time: Duration::ZERO,
+            hooks: Vec::new(),
         };
+        let throttle_before = mover.verify_throttle_time();
+        let mut move_index = 0;
+        let mut record = |status: MoveStatus| {
+            if let Some(progress) = progress.as_deref_mut() {
+                progress.record(move_index, status);
+            }
+            move_index += 1;
+        };
+        let mut budget_exhausted: Option<String> = None;
 
         for decision in &plan.decisions {
             match decision {
@@ -91,11 +507,33 @@ impl Executor {
                     to_tier,
                     strategy,
                     ..
+                }
+                | PlacementDecision::Copy {
+                    file,
+                    from_tier,
+                    to_tier,
+                    strategy,
+                    ..
                 } => {
-                    let action = if matches!(decision, PlacementDecision::Promote { .. }) {
-                        "Promoting"
-                    } else {
-                        "Demoting"
+                    if cancel.load(Ordering::SeqCst) {
+                        result.files_cancelled += 1;
+                        continue;
+                    }
+                    if budget_exhausted.is_none() {
+                        budget_exhausted = budget
+                            .exhausted_by(result.bytes_moved + result.bytes_copied, file.size);
+                    }
+                    if budget_exhausted.is_some() {
+                        result.files_deferred += 1;
+                        record(MoveStatus::Deferred);
+                        continue;
+                    }
+
+                    let copy = matches!(decision, PlacementDecision::Copy { .. });
+                    let action = match decision {
+                        PlacementDecision::Promote { .. } => "Promoting",
+                        PlacementDecision::Copy { .. } => "Copying",
+                        _ => "Demoting",
                     };
 
                     tracing::info!(
@@ -122,6 +560,7 @@ impl Executor {
                             provider: reason.provider,
                             reason: reason.reason,
                         });
+                        record(MoveStatus::Blocked);
                         continue;
                     }
 
@@ -133,12 +572,14 @@ impl Executor {
                                 file.path.display(),
                                 strategy
                             );
-                            result.errors.push(ExecutionError {
+                            result.files_skipped += 1;
+                            result.skipped.push(ExecutionSkipped {
                                 file: file.path.clone(),
                                 from_tier: from_tier.clone(),
                                 to_tier: to_tier.clone(),
-                                error: "File is currently in use".to_string(),
+                                reason: "File is open or still being written".to_string(),
                             });
+                            record(MoveStatus::Skipped);
                             continue;
                         }
                         Err(e) => {
@@ -153,46 +594,154 @@ impl Executor {
                         }
                     }
 
-                    match Self::move_file_between_tiers(
-                        &file.path, from_tier, to_tier, &tier_map, mover,
-                    ) {
-                        Ok(()) => {
+                    let mut attempts = 1;
+                    let outcome = loop {
+                        let outcome = Self::move_file_between_tiers(
+                            file, from_tier, to_tier, &tier_map, mover, copy,
+                        );
+                        match &outcome {
+                            Err(e)
+                                if attempts <= retry.retries
+                                    && ErrorClass::of(e) == ErrorClass::Transient =>
+                            {
+                                let delay = retry.backoff(attempts);
+                                tracing::warn!(
+                                    "Moving {} failed ({e}), retrying in {}s (attempt {} of {})",
+                                    file.path.display(),
+                                    delay.as_secs(),
+                                    attempts + 1,
+                                    retry.retries + 1
+                                );
+                                if !wait_unless_cancelled(delay, cancel) {
+                                    break outcome;
+                                }
+                                attempts += 1;
+                            }
+                            _ => break outcome,
+                        }
+                    };
+
+                    match outcome {
+                        Ok((verification, destination)) if copy => {
+                            result.files_copied += 1;
+                            result.bytes_copied += file.size;
+                            record(MoveStatus::Done);
+                            result.copied.push(ExecutionMove {
+                                file: file.path.clone(),
+                                destination,
+                                from_tier: from_tier.clone(),
+                                to_tier: to_tier.clone(),
+                                size: file.size,
+                                verification,
+                            });
+                        }
+                        Ok((verification, destination)) => {
                             result.files_moved += 1;
                             result.bytes_moved += file.size;
+                            let moved = ExecutionMove {
+                                file: file.path.clone(),
+                                destination,
+                                from_tier: from_tier.clone(),
+                                to_tier: to_tier.clone(),
+                                size: file.size,
+                                verification,
+                            };
+                            tracing::debug!(
+                                file = %moved.file.display(),
+                                destination = %moved.destination.display(),
+                                from_tier = %moved.from_tier,
+                                to_tier = %moved.to_tier,
+                                size = moved.size,
+                                verification = ?moved.verification,
+                                strategy = %strategy,
+                                "Moved file"
+                            );
+                            record(MoveStatus::Done);
+                            result.hooks.extend(hooks.post_move(&moved));
+                            result.moved.push(moved);
+                        }
+                        Err(e) if ConflictSkipped::is_skip(&e) => {
+                            result.files_skipped_conflict += 1;
+                            result.skipped.push(ExecutionSkipped {
+                                file: file.path.clone(),
+                                from_tier: from_tier.clone(),
+                                to_tier: to_tier.clone(),
+                                reason: e.to_string(),
+                            });
+                            record(MoveStatus::Skipped);
+                        }
+                        Err(e) if InsufficientSpace::is_insufficient_space(&e) => {
+                            tracing::warn!("Skipping {}: {e}", file.path.display());
+                            result.files_skipped_space += 1;
+                            result.skipped.push(ExecutionSkipped {
+                                file: file.path.clone(),
+                                from_tier: from_tier.clone(),
+                                to_tier: to_tier.clone(),
+                                reason: e.to_string(),
+                            });
+                            record(MoveStatus::Skipped);
                         }
                         Err(e) => {
                             tracing::error!("Failed to move {}: {}", file.path.display(), e);
+                            let kind = ExecutionErrorKind::of(&e);
+                            *result.errors_by_kind.entry(kind).or_default() += 1;
                             result.errors.push(ExecutionError {
                                 file: file.path.clone(),
                                 from_tier: from_tier.clone(),
                                 to_tier: to_tier.clone(),
                                 error: e.to_string(),
+                                kind,
+                                class: ErrorClass::of(&e),
+                                attempts,
                             });
+                            record(MoveStatus::Failed);
                         }
                     }
                 }
             }
         }
 
+        if result.files_cancelled > 0 {
+            tracing::warn!(
+                "Run cancelled: {} remaining moves not started",
+                result.files_cancelled
+            );
+        }
+        if let Some(reason) = budget_exhausted {
+            tracing::warn!(
+                "Execution budget exhausted ({reason}): {} moves deferred to the next run",
+                result.files_deferred
+            );
+        }
+
+        result.verify_throttle_time = mover.verify_throttle_time().saturating_sub(throttle_before);
+        let post_run = hooks.post_run(&result);
+        result.hooks.extend(post_run);
+
         tracing::info!(
-            "Execution complete: {} moved, {} stayed, {} blocked, {} errors",
+            "Execution complete: {} moved, {} copied, {} stayed, {} blocked, {} skipped, {} errors",
             result.files_moved,
+            result.files_copied,
             result.files_stayed,
             result.files_blocked,
+            result.files_skipped,
             result.errors.len()
         );
 
         result
     }
 
-    /// Перемещает файл между tier'ами
+    /// Перемещает файл между tier'ами, возвращая путь назначения
+    ///
+    /// With `copy` the source stays in place.
     fn move_file_between_tiers(
-        file_path: &Path,
+        file: &FileInfo,
         from_tier_name: &str,
         to_tier_name: &str,
         tier_map: &HashMap<String, &Tier>,
         mover: &dyn Mover,
-    ) -> std::io::Result<()> {
+        copy: bool,
+    ) -> std::io::Result<(Verification, PathBuf)> {
         let from_tier = tier_map.get(from_tier_name).ok_or_else(|| {
             std::io::Error::new(
                 std::io::ErrorKind::NotFound,
@@ -206,31 +755,94 @@ impl Executor {
                 format!("Destination tier not found: {to_tier_name}"),
             )
         })?;
+        if to_tier.read_only {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::ReadOnlyFilesystem,
+                format!("Tier '{to_tier_name}' is read-only, refusing to write to it"),
+            ));
+        }
 
+        let file_path = file.path.as_path();
         // Вычисляем относительный путь от tier root
-        let relative_path = file_path.strip_prefix(&from_tier.path).map_err(|e| {
+        let relative_path = from_tier.relative_path(file_path).ok_or_else(|| {
             std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
                 format!(
-                    "File {} is not under tier {}: {}",
+                    "File {} is not under tier {}",
                     file_path.display(),
-                    from_tier_name,
-                    e
+                    from_tier_name
                 ),
             )
         })?;
 
-        let destination_path = to_tier.path.join(relative_path);
+        // Remote files can't be stat'ed locally; the scanned size stands in
+        let size = fs::metadata(file_path).map_or(file.size, |m| m.len());
+        let root = to_tier.placement_root(size);
+
+        // The plan's simulation doesn't see other writers filling the disk meanwhile
+        if !mover.is_dry_run() {
+            let needed = size.saturating_add(to_tier.safety_margin);
+            let available = to_tier.free_space_at(root);
+            if available < needed {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::StorageFull,
+                    InsufficientSpace {
+                        tier: to_tier.name.clone(),
+                        root: root.to_path_buf(),
+                        needed,
+                        available,
+                    },
+                ));
+            }
+        }
+
+        let destination_path = root.join(relative_path);
 
         // Создаём директории если нужно
-        if let Some(parent) = destination_path.parent() {
+        if to_tier.remote.is_none()
+            && let Some(parent) = destination_path.parent()
+        {
             fs::create_dir_all(parent)?;
         }
 
+        // A stub left by an earlier move gives way to the real file coming back
+        if to_tier.remote.is_none()
+            && !mover.is_dry_run()
+            && let Ok(metadata) = fs::metadata(&destination_path)
+            && crate::stub::is_stub(&destination_path, metadata.len())
+        {
+            fs::remove_file(&destination_path)?;
+        }
+
         // Выполняем перемещение через Mover trait
-        mover.move_file(file_path, &destination_path)?;
+        let remote = from_tier.remote.is_some() || to_tier.remote.is_some();
+        let verification = if remote && !mover.is_dry_run() {
+            let transfer = crate::remote::RemoteTransfer {
+                from: from_tier.remote.as_deref(),
+                to: to_tier.remote.as_deref(),
+                source: file_path,
+                destination: &destination_path,
+                size,
+                copy,
+            };
+            mover.transfer_remote(&transfer, &mut |_| {})?
+        } else if copy {
+            mover.copy_file(file_path, &destination_path)?
+        } else {
+            mover.move_file(file_path, &destination_path)?
+        };
 
-        Ok(())
+        if to_tier.leave_stubs && !copy && !mover.is_dry_run() && from_tier.remote.is_none() {
+            let stub = crate::stub::Stub {
+                tier: to_tier.name.clone(),
+                location: to_tier.stub_location(&destination_path),
+                size,
+            };
+            if let Err(e) = stub.write(file_path) {
+                tracing::warn!("Failed to leave a stub at {}: {e}", file_path.display());
+            }
+        }
+        Ok((verification, destination_path))
     }
 }
 
@@ -239,7 +851,8 @@ mod tests {
     use super::*;
     use crate::move_blocker::{NoOpMoveBlocker, StaticMoveBlocker};
     use crate::{DryRunMover, FileInfo, PlacementDecision};
-    use std::collections::HashMap;
+    use std::collections::BTreeMap;
+    use std::path::Path;
     use std::time::SystemTime;
 
     fn create_test_tier(name: &str) -> Tier {
@@ -257,6 +870,7 @@ mod tests {
             size,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         }
     }
 
@@ -264,8 +878,9 @@ mod tests {
     fn test_execute_empty_plan() {
         let plan = BalancingPlan {
             decisions: vec![],
-            projected_tier_usage: HashMap::new(),
+            projected_tier_usage: BTreeMap::new(),
             warnings: vec![],
+            ..Default::default()
         };
         let mover = DryRunMover;
         let tiers = vec![];
@@ -302,8 +917,9 @@ mod tests {
                     priority: 1,
                 },
             ],
-            projected_tier_usage: HashMap::new(),
+            projected_tier_usage: BTreeMap::new(),
             warnings: vec![],
+            ..Default::default()
         };
         let mover = DryRunMover;
         let tiers = vec![cache];
@@ -333,8 +949,9 @@ mod tests {
                 strategy: "old_files".to_string(),
                 priority: 10,
             }],
-            projected_tier_usage: HashMap::new(),
+            projected_tier_usage: BTreeMap::new(),
             warnings: vec![],
+            ..Default::default()
         };
         let mover = DryRunMover;
         let tiers = vec![cache, storage];
@@ -364,8 +981,9 @@ mod tests {
                 strategy: "hot_files".to_string(),
                 priority: 20,
             }],
-            projected_tier_usage: HashMap::new(),
+            projected_tier_usage: BTreeMap::new(),
             warnings: vec![],
+            ..Default::default()
         };
         let mover = DryRunMover;
         let tiers = vec![cache, storage];
@@ -412,8 +1030,9 @@ mod tests {
                     priority: 20,
                 },
             ],
-            projected_tier_usage: HashMap::new(),
+            projected_tier_usage: BTreeMap::new(),
             warnings: vec![],
+            ..Default::default()
         };
         let mover = DryRunMover;
         let tiers = vec![cache, storage];
@@ -444,8 +1063,9 @@ mod tests {
                 strategy: "old".to_string(),
                 priority: 10,
             }],
-            projected_tier_usage: HashMap::new(),
+            projected_tier_usage: BTreeMap::new(),
             warnings: vec![],
+            ..Default::default()
         };
         let mover = DryRunMover;
         let tiers = vec![cache, storage];
@@ -465,6 +1085,585 @@ mod tests {
         assert!(result.errors.is_empty());
     }
 
+    #[test]
+    fn test_execute_skips_active_file() {
+        struct InUseChecker;
+        impl FileChecker for InUseChecker {
+            fn is_file_in_use(&self, _path: &Path) -> std::io::Result<bool> {
+                Ok(true)
+            }
+        }
+
+        let cache = create_test_tier("cache");
+        let storage = create_test_tier("storage");
+        let file = create_test_file_in_tier(&cache, "downloading.mkv", 2000);
+        let active_path = file.path.clone();
+
+        let plan = BalancingPlan {
+            decisions: vec![PlacementDecision::Demote {
+                file: std::sync::Arc::new(file),
+                from_tier: "cache".to_string(),
+                to_tier: "storage".to_string(),
+                strategy: "old".to_string(),
+                priority: 10,
+            }],
+            ..Default::default()
+        };
+        let tiers = vec![cache, storage];
+
+        let result =
+            Executor::execute_plan(&plan, &DryRunMover, &tiers, &InUseChecker, &NoOpMoveBlocker);
+
+        assert_eq!(result.files_moved, 0);
+        assert_eq!(result.files_skipped, 1);
+        assert_eq!(result.skipped[0].file, active_path);
+        assert!(result.errors.is_empty());
+    }
+
+    #[test]
+    fn test_execute_counts_conflict_skips() {
+        struct ConflictMover;
+        impl Mover for ConflictMover {
+            fn move_file(
+                &self,
+                _source: &Path,
+                destination: &Path,
+            ) -> std::io::Result<Verification> {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::AlreadyExists,
+                    ConflictSkipped {
+                        destination: destination.to_path_buf(),
+                    },
+                ))
+            }
+        }
+
+        let cache = create_test_tier("cache");
+        let storage = create_test_tier("storage");
+        let file = create_test_file_in_tier(&cache, "conflict.mkv", 1000);
+
+        let plan = BalancingPlan {
+            decisions: vec![PlacementDecision::Demote {
+                file: std::sync::Arc::new(file),
+                from_tier: "cache".to_string(),
+                to_tier: "storage".to_string(),
+                strategy: "old".to_string(),
+                priority: 10,
+            }],
+            ..Default::default()
+        };
+        let tiers = vec![cache, storage];
+
+        let result = Executor::execute_plan(
+            &plan,
+            &ConflictMover,
+            &tiers,
+            &NoOpFileChecker,
+            &NoOpMoveBlocker,
+        );
+
+        assert_eq!(result.files_moved, 0);
+        assert_eq!(result.files_skipped_conflict, 1);
+        assert_eq!(result.skipped.len(), 1);
+        assert!(result.errors.is_empty());
+    }
+
+    #[test]
+    fn test_execute_skips_move_without_free_space() {
+        struct CountingMover(std::cell::Cell<usize>);
+        impl Mover for CountingMover {
+            fn move_file(
+                &self,
+                _source: &Path,
+                _destination: &Path,
+            ) -> std::io::Result<Verification> {
+                self.0.set(self.0.get() + 1);
+                Ok(Verification::Size)
+            }
+        }
+
+        let cache = create_test_tier("cache");
+        // No real disk keeps this much free
+        let storage = create_test_tier("storage").with_safety_margin(u64::MAX / 2);
+        let file = create_test_file_in_tier(&cache, "no_space.mkv", 1000);
+        let plan = BalancingPlan {
+            decisions: vec![PlacementDecision::Demote {
+                file: std::sync::Arc::new(file),
+                from_tier: "cache".to_string(),
+                to_tier: "storage".to_string(),
+                strategy: "old".to_string(),
+                priority: 10,
+            }],
+            ..Default::default()
+        };
+        let tiers = vec![cache, storage];
+        let mover = CountingMover(std::cell::Cell::new(0));
+
+        let result =
+            Executor::execute_plan(&plan, &mover, &tiers, &NoOpFileChecker, &NoOpMoveBlocker);
+
+        assert_eq!(mover.0.get(), 0);
+        assert_eq!(result.files_skipped_space, 1);
+        assert!(result.skipped[0].reason.contains("Not enough free space"));
+        assert!(result.errors.is_empty());
+
+        // Dry runs don't change the disks, so the check is left to the plan
+        let result = Executor::execute_plan(
+            &plan,
+            &DryRunMover,
+            &tiers,
+            &NoOpFileChecker,
+            &NoOpMoveBlocker,
+        );
+        assert_eq!(result.files_moved, 1);
+    }
+
+    #[test]
+    fn test_execute_refuses_read_only_destination() {
+        let cache = create_test_tier("cache");
+        let storage = create_test_tier("storage").with_read_only(true);
+        let file = create_test_file_in_tier(&cache, "protected.mkv", 1000);
+        let plan = BalancingPlan {
+            decisions: vec![PlacementDecision::Demote {
+                file: std::sync::Arc::new(file),
+                from_tier: "cache".to_string(),
+                to_tier: "storage".to_string(),
+                strategy: "old".to_string(),
+                priority: 10,
+            }],
+            ..Default::default()
+        };
+        let tiers = vec![cache, storage];
+
+        let result = Executor::execute_plan(
+            &plan,
+            &DryRunMover,
+            &tiers,
+            &NoOpFileChecker,
+            &NoOpMoveBlocker,
+        );
+
+        assert_eq!(result.files_moved, 0);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].kind, ExecutionErrorKind::PermissionDenied);
+        assert!(result.errors[0].error.contains("read-only"));
+    }
+
+    #[test]
+    fn test_execute_leaves_stub_for_recall() {
+        struct RenameMover;
+        impl Mover for RenameMover {
+            fn move_file(
+                &self,
+                source: &Path,
+                destination: &Path,
+            ) -> std::io::Result<Verification> {
+                std::fs::rename(source, destination)?;
+                Ok(Verification::Size)
+            }
+        }
+
+        let cache = create_test_tier("stub_cache").with_skip_stubs(true);
+        let archive = create_test_tier("stub_archive").with_leave_stubs(true);
+        let file = create_test_file_in_tier(&cache, "archived.mkv", 5000);
+        let stub_path = file.path.clone();
+        let plan = BalancingPlan {
+            decisions: vec![PlacementDecision::Demote {
+                file: std::sync::Arc::new(file),
+                from_tier: "stub_cache".to_string(),
+                to_tier: "stub_archive".to_string(),
+                strategy: "old".to_string(),
+                priority: 10,
+            }],
+            ..Default::default()
+        };
+        let tiers = vec![cache, archive];
+
+        let result = Executor::execute_plan(
+            &plan,
+            &RenameMover,
+            &tiers,
+            &NoOpFileChecker,
+            &NoOpMoveBlocker,
+        );
+
+        assert_eq!(result.files_moved, 1);
+        let stub = crate::stub::Stub::read(&stub_path).unwrap().unwrap();
+        assert_eq!(stub.tier, "stub_archive");
+        assert_eq!(stub.size, 5000);
+        assert!(tiers[0].get_all_files().is_empty());
+        // Small files are only opened to look for stubs when asked to
+        assert_eq!(
+            tiers[0]
+                .clone()
+                .with_skip_stubs(false)
+                .get_all_files()
+                .len(),
+            1
+        );
+
+        crate::stub::recall(&stub_path).unwrap();
+        assert_eq!(std::fs::metadata(&stub_path).unwrap().len(), 5000);
+        assert!(!tiers[1].path.join("archived.mkv").exists());
+    }
+
+    #[test]
+    fn test_execute_stops_after_cancel() {
+        // Cancels while the first move is in progress
+        struct CancellingMover<'a>(&'a AtomicBool);
+        impl Mover for CancellingMover<'_> {
+            fn move_file(
+                &self,
+                _source: &Path,
+                _destination: &Path,
+            ) -> std::io::Result<Verification> {
+                self.0.store(true, Ordering::SeqCst);
+                Ok(Verification::Size)
+            }
+        }
+
+        let cache = create_test_tier("cache");
+        let storage = create_test_tier("storage");
+        let demote = |name: &str| PlacementDecision::Demote {
+            file: std::sync::Arc::new(create_test_file_in_tier(&cache, name, 100)),
+            from_tier: "cache".to_string(),
+            to_tier: "storage".to_string(),
+            strategy: "old".to_string(),
+            priority: 10,
+        };
+        let plan = BalancingPlan {
+            decisions: vec![
+                demote("cancel_a.mkv"),
+                demote("cancel_b.mkv"),
+                demote("cancel_c.mkv"),
+            ],
+            ..Default::default()
+        };
+        let tiers = vec![cache.clone(), storage];
+        let cancel = AtomicBool::new(false);
+
+        let result = Executor::execute_plan_with_hooks(
+            &plan,
+            &CancellingMover(&cancel),
+            &tiers,
+            &NoOpFileChecker,
+            &NoOpMoveBlocker,
+            None,
+            &Hooks::default(),
+            ExecutionBudget::default(),
+            RetryConfig::default(),
+            &cancel,
+        );
+
+        assert_eq!(result.files_moved, 1);
+        assert_eq!(result.moved[0].file, cache.path.join("cancel_a.mkv"));
+        assert_eq!(result.files_cancelled, 2);
+        assert!(result.errors.is_empty());
+    }
+
+    #[test]
+    fn test_execute_defers_moves_past_byte_budget() {
+        let cache = create_test_tier("cache");
+        let storage = create_test_tier("storage");
+        let demote = |name: &str| PlacementDecision::Demote {
+            file: std::sync::Arc::new(create_test_file_in_tier(&cache, name, 100)),
+            from_tier: "cache".to_string(),
+            to_tier: "storage".to_string(),
+            strategy: "old".to_string(),
+            priority: 10,
+        };
+        let plan = BalancingPlan {
+            decisions: vec![
+                demote("budget_a.mkv"),
+                demote("budget_b.mkv"),
+                demote("budget_c.mkv"),
+            ],
+            ..Default::default()
+        };
+        let tiers = vec![cache, storage];
+
+        let result = Executor::execute_plan_with_hooks(
+            &plan,
+            &DryRunMover,
+            &tiers,
+            &NoOpFileChecker,
+            &NoOpMoveBlocker,
+            None,
+            &Hooks::default(),
+            ExecutionBudget::new(Some(250), None),
+            RetryConfig::default(),
+            &AtomicBool::new(false),
+        );
+
+        assert_eq!(result.files_moved, 2);
+        assert_eq!(result.bytes_moved, 200);
+        assert_eq!(result.files_deferred, 1);
+
+        let expired = ExecutionBudget::new(None, Some(Duration::ZERO));
+        assert!(expired.exhausted_by(0, 1).is_some());
+        assert_eq!(
+            ExecutionBudget::new(Some(250), None).spend(200).max_bytes,
+            Some(50)
+        );
+    }
+
+    #[test]
+    fn test_execute_retries_transient_errors() {
+        // Fails with the given error until `failures` attempts have been made
+        struct FlakyMover {
+            failures: usize,
+            attempts: std::cell::Cell<usize>,
+            kind: std::io::ErrorKind,
+        }
+        impl Mover for FlakyMover {
+            fn move_file(
+                &self,
+                _source: &Path,
+                _destination: &Path,
+            ) -> std::io::Result<Verification> {
+                self.attempts.set(self.attempts.get() + 1);
+                if self.attempts.get() <= self.failures {
+                    return Err(std::io::Error::new(self.kind, "server not responding"));
+                }
+                Ok(Verification::Size)
+            }
+        }
+
+        let cache = create_test_tier("cache");
+        let storage = create_test_tier("storage");
+        let file = create_test_file_in_tier(&cache, "flaky.mkv", 100);
+        let plan = BalancingPlan {
+            decisions: vec![PlacementDecision::Demote {
+                file: std::sync::Arc::new(file),
+                from_tier: "cache".to_string(),
+                to_tier: "storage".to_string(),
+                strategy: "old".to_string(),
+                priority: 10,
+            }],
+            ..Default::default()
+        };
+        let tiers = vec![cache, storage];
+        let retry = RetryConfig {
+            retries: 2,
+            backoff_seconds: 0,
+        };
+        let execute = |mover: &FlakyMover| {
+            Executor::execute_plan_with_hooks(
+                &plan,
+                mover,
+                &tiers,
+                &NoOpFileChecker,
+                &NoOpMoveBlocker,
+                None,
+                &Hooks::default(),
+                ExecutionBudget::default(),
+                retry,
+                &AtomicBool::new(false),
+            )
+        };
+        let flaky = |failures, kind| FlakyMover {
+            failures,
+            attempts: std::cell::Cell::new(0),
+            kind,
+        };
+
+        let mover = flaky(2, std::io::ErrorKind::TimedOut);
+        let result = execute(&mover);
+        assert_eq!(result.files_moved, 1);
+        assert_eq!(mover.attempts.get(), 3);
+
+        let mover = flaky(5, std::io::ErrorKind::TimedOut);
+        let result = execute(&mover);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].attempts, 3);
+        assert_eq!(result.errors[0].class, ErrorClass::Transient);
+
+        let mover = flaky(5, std::io::ErrorKind::PermissionDenied);
+        let result = execute(&mover);
+        assert_eq!(mover.attempts.get(), 1);
+        assert_eq!(result.errors[0].class, ErrorClass::Permanent);
+        assert_eq!(result.errors[0].kind, ExecutionErrorKind::PermissionDenied);
+        assert_eq!(
+            result.errors_by_kind,
+            BTreeMap::from([(ExecutionErrorKind::PermissionDenied, 1)])
+        );
+    }
+
+    #[test]
+    fn test_execution_error_kind() {
+        let kind = |err: std::io::Error| ExecutionErrorKind::of(&err);
+        assert_eq!(
+            kind(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Source file does not exist"
+            )),
+            ExecutionErrorKind::SourceMissing
+        );
+        assert_eq!(
+            kind(std::io::Error::from(std::io::ErrorKind::StorageFull)),
+            ExecutionErrorKind::DestinationFull
+        );
+        assert_eq!(
+            kind(std::io::Error::other(VerificationFailed {
+                reason: "Checksum mismatch after copy".to_string()
+            })),
+            ExecutionErrorKind::VerificationFailed
+        );
+        assert_eq!(
+            kind(std::io::Error::other(RsyncFailed { code: Some(23) })),
+            ExecutionErrorKind::MoverFailed
+        );
+        assert_eq!(
+            kind(std::io::Error::other(RsyncFailed { code: Some(30) })),
+            ExecutionErrorKind::Network
+        );
+        assert_eq!(
+            kind(std::io::Error::from_raw_os_error(EIO)),
+            ExecutionErrorKind::Io
+        );
+        assert_eq!(
+            kind(std::io::Error::other("something else")),
+            ExecutionErrorKind::Other
+        );
+    }
+
+    #[test]
+    fn test_error_class() {
+        let rsync = |code| std::io::Error::other(RsyncFailed { code: Some(code) });
+        assert_eq!(ErrorClass::of(&rsync(30)), ErrorClass::Transient);
+        assert_eq!(ErrorClass::of(&rsync(23)), ErrorClass::Permanent);
+        assert_eq!(
+            ErrorClass::of(&std::io::Error::from_raw_os_error(EIO)),
+            ErrorClass::Transient
+        );
+        assert_eq!(
+            ErrorClass::of(&std::io::Error::other("File size mismatch after copy")),
+            ErrorClass::Permanent
+        );
+    }
+
+    #[test]
+    fn test_order_for_execution() {
+        let file = |name: &str, size: u64| {
+            std::sync::Arc::new(FileInfo {
+                path: PathBuf::from(format!("/cache/{name}")),
+                size,
+                modified: SystemTime::now(),
+                accessed: SystemTime::now(),
+                owner: None,
+            })
+        };
+        let demote = |name: &str, size: u64, priority: u32| PlacementDecision::Demote {
+            file: file(name, size),
+            from_tier: "cache".to_string(),
+            to_tier: "storage".to_string(),
+            strategy: "archive".to_string(),
+            priority,
+        };
+        let promote = |name: &str, size: u64, priority: u32| PlacementDecision::Promote {
+            file: file(name, size),
+            from_tier: "storage".to_string(),
+            to_tier: "cache".to_string(),
+            strategy: "active".to_string(),
+            priority,
+        };
+        let planned = BalancingPlan {
+            decisions: vec![
+                demote("big_old.mkv", 900, 10),
+                demote("small_old.mkv", 100, 10),
+                PlacementDecision::Stay {
+                    file: file("kept.mkv", 1),
+                    current_tier: "cache".to_string(),
+                    strategy: "default".to_string(),
+                    priority: 1,
+                },
+                promote("episode.mkv", 500, 50),
+            ],
+            ..Default::default()
+        };
+        let names = |order| {
+            let mut plan = planned.clone();
+            Executor::order_for_execution(&mut plan, order);
+            plan.decisions
+                .iter()
+                .map(|d| {
+                    d.file_path()
+                        .file_name()
+                        .unwrap()
+                        .to_str()
+                        .unwrap()
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            names(ExecutionOrder::Plan),
+            ["big_old.mkv", "small_old.mkv", "kept.mkv", "episode.mkv"]
+        );
+        assert_eq!(
+            names(ExecutionOrder::PromotesFirst),
+            ["episode.mkv", "big_old.mkv", "small_old.mkv", "kept.mkv"]
+        );
+        assert_eq!(
+            names(ExecutionOrder::PriorityDesc),
+            ["episode.mkv", "big_old.mkv", "small_old.mkv", "kept.mkv"]
+        );
+        assert_eq!(
+            names(ExecutionOrder::SmallestFirst),
+            ["small_old.mkv", "episode.mkv", "big_old.mkv", "kept.mkv"]
+        );
+    }
+
+    #[test]
+    fn test_order_for_execution_keeps_spindown_batches() {
+        let decision = |name: &str, size: u64, to_tier: &str| PlacementDecision::Demote {
+            file: std::sync::Arc::new(FileInfo {
+                path: PathBuf::from(format!("/cache/{name}")),
+                size,
+                modified: SystemTime::now(),
+                accessed: SystemTime::now(),
+                owner: None,
+            }),
+            from_tier: "cache".to_string(),
+            to_tier: to_tier.to_string(),
+            strategy: "archive".to_string(),
+            priority: 10,
+        };
+        let mut plan = BalancingPlan {
+            decisions: vec![
+                decision("archive_big.mkv", 900, "archive"),
+                decision("archive_small.mkv", 300, "archive"),
+                decision("storage_mid.mkv", 500, "storage"),
+                decision("storage_tiny.mkv", 100, "storage"),
+            ],
+            move_batches: vec![crate::MoveBatch {
+                tier: "archive".to_string(),
+                files: 2,
+                bytes: 1200,
+                deferred: false,
+            }],
+            ..Default::default()
+        };
+
+        Executor::order_for_execution(&mut plan, ExecutionOrder::SmallestFirst);
+
+        let names: Vec<_> = plan
+            .decisions
+            .iter()
+            .map(|d| d.file_path().file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "storage_tiny.mkv",
+                "archive_small.mkv",
+                "archive_big.mkv",
+                "storage_mid.mkv"
+            ]
+        );
+    }
+
     #[test]
     fn test_execute_with_nested_directories() {
         let cache = create_test_tier("cache");
@@ -481,6 +1680,7 @@ mod tests {
             size: 1000,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         };
 
         let plan = BalancingPlan {
@@ -491,8 +1691,9 @@ mod tests {
                 strategy: "old".to_string(),
                 priority: 10,
             }],
-            projected_tier_usage: HashMap::new(),
+            projected_tier_usage: BTreeMap::new(),
             warnings: vec![],
+            ..Default::default()
         };
         let mover = DryRunMover;
         let tiers = vec![cache, storage];
@@ -1,21 +1,47 @@
 use crate::conditions::{
-    ActiveWindowCondition, AgeCondition, AlwaysTrueCondition, FileExtensionCondition,
-    FileSizeCondition, FilenameContainsCondition, PathPrefixCondition,
+    ActiveWindowCondition, AgeCondition, AlwaysTrueCondition, DirectoryFileCountCondition,
+    DirectorySizeCondition, FileExtensionCondition, FileSizeCondition, FilenameContainsCondition,
+    NewestInDirectoryAgeCondition, OwnerCondition, OwnerKind, PathGlobCondition,
+    PathPrefixCondition, RecentlyWatchedMovieCondition, SeenAgeCondition,
+    SonarrAiringWithinCondition, SonarrMonitoredCondition, TierUsageCondition,
+    TorrentRatioCondition, TorrentSeedingCondition,
 };
 use crate::config::{
-    BlockersConfig, ConditionConfig, MoverConfig, MoverType, PlacementStrategyConfig,
+    AccountConfig, ActivityCheckConfig, BlockersConfig, ByteSize, ConditionConfig, ConfigError,
+    ExtensionGroups, HashAlgorithm, MovePolicyConfig, MoverConfig, MoverType, NotificationsConfig,
+    PlacementStrategyConfig, TorrentClientConfig, TorrentClientType, VerificationMode,
+    VerifyConfig, WatchHistoryConfig, WatchHistoryProviderType,
 };
 use crate::error::Result;
 use crate::move_blocker::{self, CompositeMoveBlocker, MoveBlocker, NoOpMoveBlocker};
+use crate::move_policy::{MovePolicies, MovePolicy};
+use crate::notify::Notifier;
+use crate::torrent::{QbittorrentClient, TorrentClient, TransmissionClient};
+use crate::watch_history::WatchHistoryProvider;
 use crate::{
-    Condition, DryRunMover, FileChecker, Mover, PlacementStrategy, RsyncMover, SmartFileChecker,
+    Condition, DryRunMover, FileActivityChecker, FileChecker, HashBudget, Hasher, JellyfinClient,
+    MoveQuota, Mover, NoOpFileChecker, PlacementStrategy, RsyncMover, TautulliClient,
+    TautulliFixture, Trash,
 };
+use std::sync::Arc;
 
-pub fn build_strategy(config: PlacementStrategyConfig) -> PlacementStrategy {
+/// Build a strategy, resolving `file_extension` groups against `extension_groups`
+pub fn build_strategy(
+    config: PlacementStrategyConfig,
+    extension_groups: &ExtensionGroups,
+) -> std::result::Result<PlacementStrategy, ConfigError> {
     let mut strategy = PlacementStrategy::new(config.name, config.priority);
 
     for condition_config in config.conditions {
-        strategy = strategy.add_condition(build_condition(condition_config));
+        // Identical configs give identical conditions, which then share results per file
+        let key = format!("{condition_config:?}");
+        let condition = build_condition(condition_config, extension_groups).map_err(|reason| {
+            ConfigError::InvalidCondition {
+                strategy: strategy.name.clone(),
+                reason,
+            }
+        })?;
+        strategy = strategy.add_shared_condition(key, condition);
     }
 
     for tier_name in config.preferred_tiers {
@@ -27,27 +53,92 @@ pub fn build_strategy(config: PlacementStrategyConfig) -> PlacementStrategy {
     }
 
     strategy.action = config.action;
+    strategy.quota = MoveQuota::new(config.max_moves_per_run, config.max_bytes_per_run);
+    if let Some(schedule) = config.schedule {
+        strategy = strategy.with_schedule(schedule);
+    }
 
-    strategy
+    Ok(strategy)
 }
 
-pub fn build_condition(config: ConditionConfig) -> Box<dyn Condition> {
-    match config {
+/// Build a condition; fails on an unknown extension group or an invalid glob
+pub fn build_condition(
+    config: ConditionConfig,
+    extension_groups: &ExtensionGroups,
+) -> std::result::Result<Box<dyn Condition>, String> {
+    Ok(match config {
         ConditionConfig::AlwaysTrue => Box::new(AlwaysTrueCondition),
         ConditionConfig::Age {
             min_hours,
             max_hours,
         } => Box::new(AgeCondition::new(min_hours, max_hours)),
+        ConditionConfig::SeenAge {
+            min_hours,
+            max_hours,
+        } => Box::new(SeenAgeCondition::new(min_hours, max_hours)),
+        ConditionConfig::DirectorySize { min_gb, max_gb } => {
+            Box::new(DirectorySizeCondition::new(min_gb, max_gb))
+        }
+        ConditionConfig::DirectoryFileCount { min, max } => {
+            Box::new(DirectoryFileCountCondition::new(min, max))
+        }
+        ConditionConfig::NewestInDirectoryAge {
+            min_hours,
+            max_hours,
+        } => Box::new(NewestInDirectoryAgeCondition::new(min_hours, max_hours)),
         ConditionConfig::FileSize {
+            min_size,
+            max_size,
             min_size_mb,
             max_size_mb,
-        } => Box::new(FileSizeCondition::new(min_size_mb, max_size_mb)),
-        ConditionConfig::FileExtension { extensions, mode } => Box::new(
-            FileExtensionCondition::new_with_mode(extensions, mode.into()),
-        ),
+        } => {
+            let bytes = |size: Option<ByteSize>, mb: Option<u64>| {
+                size.map(|size| size.0)
+                    .or(mb.map(FileSizeCondition::mb_to_bytes))
+            };
+            Box::new(FileSizeCondition::from_bytes(
+                bytes(min_size, min_size_mb),
+                bytes(max_size, max_size_mb),
+            ))
+        }
+        ConditionConfig::FileExtension {
+            mut extensions,
+            group,
+            mode,
+        } => {
+            if let Some(group) = group {
+                let Some(members) = extension_groups.get(&group) else {
+                    return Err(format!("unknown extension group: {group}"));
+                };
+                extensions.extend(members.iter().cloned());
+            }
+            Box::new(FileExtensionCondition::new_with_mode(
+                extensions,
+                mode.into(),
+            ))
+        }
         ConditionConfig::PathPrefix { prefix, mode } => {
             Box::new(PathPrefixCondition::new_with_mode(prefix, mode.into()))
         }
+        ConditionConfig::Owner { users, mode } => Box::new(OwnerCondition::new(
+            OwnerKind::User,
+            resolve_accounts(OwnerKind::User, users)?,
+            mode.into(),
+        )),
+        ConditionConfig::Group { groups, mode } => Box::new(OwnerCondition::new(
+            OwnerKind::Group,
+            resolve_accounts(OwnerKind::Group, groups)?,
+            mode.into(),
+        )),
+        ConditionConfig::TierUsage {
+            tier,
+            min_percent,
+            max_percent,
+        } => Box::new(TierUsageCondition::new(tier, min_percent, max_percent)),
+        ConditionConfig::PathGlob { patterns, mode } => Box::new(
+            PathGlobCondition::new_with_mode(&patterns, mode.into())
+                .map_err(|e| format!("invalid path_glob pattern: {e}"))?,
+        ),
         ConditionConfig::FilenameContains {
             patterns,
             mode,
@@ -66,37 +157,136 @@ pub fn build_condition(config: ConditionConfig) -> Box<dyn Condition> {
             }
         }
         ConditionConfig::ActiveWindow { name } => Box::new(ActiveWindowCondition::new(name)),
-    }
+        ConditionConfig::RecentlyWatchedMovie { days } => {
+            Box::new(RecentlyWatchedMovieCondition::new(days))
+        }
+        ConditionConfig::SonarrMonitored => Box::new(SonarrMonitoredCondition),
+        ConditionConfig::SonarrAiringWithin { days } => {
+            Box::new(SonarrAiringWithinCondition::new(days))
+        }
+        ConditionConfig::TorrentSeeding => Box::new(TorrentSeedingCondition),
+        ConditionConfig::TorrentRatio { min, max } => {
+            Box::new(TorrentRatioCondition::new(min, max))
+        }
+    })
+}
+
+/// Numeric ids of `owner`/`group` accounts, names looked up in the system databases
+fn resolve_accounts(
+    kind: OwnerKind,
+    accounts: Vec<AccountConfig>,
+) -> std::result::Result<Vec<u32>, String> {
+    accounts
+        .into_iter()
+        .map(|account| match account {
+            AccountConfig::Id(id) => Ok(id),
+            AccountConfig::Name(name) => {
+                OwnerCondition::resolve(kind, &name).ok_or_else(|| match kind {
+                    OwnerKind::User => format!("unknown user: {name}"),
+                    OwnerKind::Group => format!("unknown group: {name}"),
+                })
+            }
+        })
+        .collect()
 }
 
 /// Create a mover based on configuration
-/// Uses a consistent hasher implementation across all movers
-pub fn build_mover(config: Option<&MoverConfig>, dry_run: bool) -> Box<dyn Mover> {
+/// All verified moves share one hashing budget so limits apply globally
+/// With a `trash`, moved sources are kept there instead of deleted
+pub fn build_mover(
+    config: Option<&MoverConfig>,
+    verify: Option<&VerifyConfig>,
+    trash: Option<Trash>,
+    dry_run: bool,
+) -> Box<dyn Mover> {
     if dry_run {
         tracing::info!("Dry-run mode: using DryRunMover");
         return Box::new(DryRunMover);
     }
 
-    if let Some(config) = config {
+    let mover = if let Some(config) = config {
         match config.mover_type {
             MoverType::Rsync => {
                 tracing::info!("Using RsyncMover");
-                Box::new(RsyncMover::with_args(config.extra_args.clone()))
+                RsyncMover::with_args(config.extra_args.clone())
+                    .with_preserve(config.preserve)
+                    .with_conflict_policy(config.on_conflict)
+            }
+            MoverType::NativeFast => {
+                tracing::info!("Using RsyncMover with the native copier");
+                if !config.extra_args.is_empty() {
+                    tracing::warn!(
+                        "mover.extra_args are rsync arguments and are ignored by native_fast"
+                    );
+                }
+                RsyncMover::new()
+                    .with_native_copy(config.native)
+                    .with_preserve(config.preserve)
+                    .with_conflict_policy(config.on_conflict)
             }
             MoverType::DryRun => {
                 tracing::info!("Using DryRunMover from config");
-                Box::new(DryRunMover)
+                return Box::new(DryRunMover);
             }
         }
     } else {
         tracing::info!("Using RsyncMover (default)");
-        Box::new(RsyncMover::new())
+        RsyncMover::new()
+    };
+
+    let hash = verify.map_or(HashAlgorithm::Xxh3, |verify| verify.hash);
+    let mut verification =
+        config
+            .and_then(|config| config.verification)
+            .unwrap_or(if verify.is_some() {
+                VerificationMode::ChecksumOnce
+            } else {
+                VerificationMode::SizeOnly
+            });
+    if hash == HashAlgorithm::None && verification.hashes() {
+        if config.and_then(|config| config.verification).is_some() {
+            tracing::warn!(
+                "verify.hash is none, verifying copies by size instead of {verification:?}"
+            );
+        }
+        verification = VerificationMode::SizeOnly;
+    }
+    let mut mover = mover.with_verification(verification);
+    if let Some(trash) = trash {
+        tracing::info!(
+            "Moved sources are kept in the trash (run {})",
+            trash.run_id()
+        );
+        mover = mover.with_trash(trash);
+    }
+
+    match verify {
+        Some(verify) if verification.hashes() => {
+            tracing::info!(
+                "Hash verification enabled ({verification:?}, {hash}, max_mbps: {:?}, max_threads: {:?})",
+                verify.max_mbps,
+                verify.max_threads
+            );
+            let budget = HashBudget::new(verify.max_mbps, verify.max_threads);
+            let hasher = Hasher::new(hash).with_threads(verify.threads_per_file.unwrap_or(1));
+            Box::new(mover.with_hasher(hasher).with_hash_budget(Arc::new(budget)))
+        }
+        _ => {
+            tracing::info!("Copy verification: {verification:?}");
+            Box::new(mover)
+        }
     }
 }
 
 /// Create a file checker with default implementation
-pub fn build_file_checker() -> Box<dyn FileChecker> {
-    Box::new(SmartFileChecker::new())
+pub fn build_file_checker(config: Option<&ActivityCheckConfig>) -> Box<dyn FileChecker> {
+    let config = config.cloned().unwrap_or_default();
+    if !config.enabled {
+        tracing::info!("Active-transfer detection disabled");
+        return Box::new(NoOpFileChecker);
+    }
+
+    Box::new(FileActivityChecker::from_config(&config))
 }
 
 /// Create a move blocker from configuration.
@@ -123,3 +313,56 @@ pub fn build_move_blocker(config: Option<&BlockersConfig>) -> Result<Box<dyn Mov
 
     Ok(Box::new(CompositeMoveBlocker::new(providers, on_error)))
 }
+
+/// Create move policies from configuration, preserving rule order.
+pub fn build_move_policies(configs: &[MovePolicyConfig]) -> MovePolicies {
+    if !configs.is_empty() {
+        tracing::info!("Configured {} move policy rule(s)", configs.len());
+    }
+
+    MovePolicies::new(configs.iter().cloned().map(MovePolicy::from).collect())
+}
+
+/// Create the client for the configured watch history provider.
+pub fn build_watch_history_provider(
+    config: &WatchHistoryConfig,
+) -> Result<Box<dyn WatchHistoryProvider>> {
+    if let Some(fixture) = &config.fixture {
+        return Ok(Box::new(TautulliFixture::new(fixture.clone())));
+    }
+    let url = config.url.clone();
+    let api_key = config.api_key.clone();
+    Ok(match config.provider {
+        WatchHistoryProviderType::Tautulli => Box::new(TautulliClient::new(url, api_key)?),
+        WatchHistoryProviderType::Jellyfin => Box::new(JellyfinClient::new(url, api_key)?),
+    })
+}
+
+pub fn build_torrent_client(config: &TorrentClientConfig) -> Result<Box<dyn TorrentClient>> {
+    let url = config.url.clone();
+    let username = config.username.clone();
+    let password = config.password.clone();
+    Ok(match config.client {
+        TorrentClientType::Qbittorrent => {
+            Box::new(QbittorrentClient::new(url, username, password)?)
+        }
+        TorrentClientType::Transmission => {
+            Box::new(TransmissionClient::new(&url, username, password)?)
+        }
+    })
+}
+
+/// Create a notifier from configuration, if any webhooks are configured.
+pub fn build_notifier(config: Option<&NotificationsConfig>) -> Result<Option<Notifier>> {
+    let Some(config) = config.filter(|c| !c.webhooks.is_empty()) else {
+        return Ok(None);
+    };
+
+    tracing::info!(
+        "Configured {} notification webhook(s) (notify_on: {:?})",
+        config.webhooks.len(),
+        config.notify_on
+    );
+
+    Notifier::new(config).map(Some)
+}
@@ -1,3 +1,4 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 use std::{fs, io};
@@ -8,6 +9,15 @@ pub struct FileInfo {
     pub size: u64,
     pub modified: SystemTime,
     pub accessed: SystemTime,
+    /// Owning user and group; `None` where the platform has no Unix ownership
+    pub owner: Option<FileOwner>,
+}
+
+/// Numeric Unix owner of a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileOwner {
+    pub uid: u32,
+    pub gid: u32,
 }
 
 impl FileInfo {
@@ -19,6 +29,7 @@ impl FileInfo {
             size: metadata.len(),
             modified: metadata.modified()?,
             accessed: metadata.accessed()?,
+            owner: owner_of(&metadata),
         })
     }
 
@@ -48,6 +59,20 @@ impl FileInfo {
     }
 }
 
+#[cfg(unix)]
+fn owner_of(metadata: &fs::Metadata) -> Option<FileOwner> {
+    use std::os::unix::fs::MetadataExt;
+    Some(FileOwner {
+        uid: metadata.uid(),
+        gid: metadata.gid(),
+    })
+}
+
+#[cfg(not(unix))]
+const fn owner_of(_metadata: &fs::Metadata) -> Option<FileOwner> {
+    None
+}
+
 // Implement Eq/Hash/PartialEq for FileInfo based only on its path so it can be used in HashSet/HashMap
 impl PartialEq for FileInfo {
     fn eq(&self, other: &Self) -> bool {
@@ -64,6 +89,27 @@ impl std::hash::Hash for FileInfo {
     }
 }
 
+// Serialize timestamps as Unix seconds and paths lossily so plan output never fails on odd names
+impl Serialize for FileInfo {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("FileInfo", 4)?;
+        state.serialize_field("path", &self.path.to_string_lossy())?;
+        state.serialize_field("size", &self.size)?;
+        state.serialize_field("modified", &self.modified_timestamp())?;
+        state.serialize_field("accessed", &self.accessed_timestamp())?;
+        state.end()
+    }
+}
+
+/// Serializes a path as a (lossy) UTF-8 string
+pub fn serialize_path_lossy<P, S>(path: &P, serializer: S) -> Result<S::Ok, S::Error>
+where
+    P: AsRef<Path>,
+    S: Serializer,
+{
+    serializer.serialize_str(&path.as_ref().to_string_lossy())
+}
+
 pub fn is_internal_artifact_path(path: &Path) -> bool {
     path.file_name()
         .and_then(|name| name.to_str())
@@ -74,15 +120,16 @@ pub fn is_internal_artifact_name(name: &str) -> bool {
     name == ".tierflow.lock"
         || name.starts_with(".tierflow-remove-check-")
         || name.ends_with(".partial")
-        || has_backup_suffix(name)
+        || backup_timestamp(name).is_some()
 }
 
-fn has_backup_suffix(name: &str) -> bool {
-    let Some((_, suffix)) = name.rsplit_once(".backup-") else {
-        return false;
-    };
-
-    !suffix.is_empty() && suffix.bytes().all(|b| b.is_ascii_digit())
+/// Unix time in the `.backup-<timestamp>` suffix of a conflict backup
+pub fn backup_timestamp(name: &str) -> Option<u64> {
+    let (_, suffix) = name.rsplit_once(".backup-")?;
+    if suffix.is_empty() || !suffix.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    suffix.parse().ok()
 }
 
 #[cfg(test)]
@@ -114,4 +161,26 @@ mod tests {
             "/mnt/storage/backup-note.txt"
         )));
     }
+
+    #[test]
+    fn test_serialize_file_info() {
+        let file = FileInfo {
+            path: PathBuf::from("/mnt/cache/movie.mkv"),
+            size: 42,
+            modified: SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(100),
+            accessed: SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(200),
+            owner: None,
+        };
+
+        let value = serde_json::to_value(&file).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "path": "/mnt/cache/movie.mkv",
+                "size": 42,
+                "modified": 100,
+                "accessed": 200,
+            })
+        );
+    }
 }
@@ -1,9 +1,15 @@
 //! File usage checking implementations
 
+use crate::config::{ActivityCheckConfig, ActivityCheckMethod};
 use std::fs;
 use std::io;
 use std::path::Path;
 use std::process::Command;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// Files untouched for this long are not sampled for growth
+const SAMPLE_IF_MODIFIED_WITHIN: Duration = Duration::from_secs(3600);
 
 /// Trait for checking if a file is currently in use by any process
 pub trait FileChecker: Send + Sync {
@@ -61,6 +67,123 @@ impl FileChecker for LsofFileChecker {
     }
 }
 
+/// Implementation using fuser (psmisc), for systems without lsof
+pub struct FuserFileChecker;
+
+impl FileChecker for FuserFileChecker {
+    fn is_file_in_use(&self, path: &Path) -> io::Result<bool> {
+        // fuser -s: silent, exit code 0 = some process has the file open
+        let output = Command::new("fuser")
+            .arg("-s")
+            .arg(path.as_os_str())
+            .output()?;
+        if output.status.success() {
+            tracing::debug!("File {} is in use (fuser found processes)", path.display());
+        }
+        Ok(output.status.success())
+    }
+}
+
+/// Detects files that are still being written by sampling size and mtime twice
+///
+/// Catches writers that hold no local file handle, such as NFS clients.
+/// Files not modified within the last hour are assumed idle and not sampled.
+pub struct SizeStabilityChecker {
+    interval: Duration,
+}
+
+impl SizeStabilityChecker {
+    pub const fn new(interval: Duration) -> Self {
+        Self { interval }
+    }
+}
+
+impl FileChecker for SizeStabilityChecker {
+    fn is_file_in_use(&self, path: &Path) -> io::Result<bool> {
+        let before = fs::metadata(path)?;
+        let modified = before.modified()?;
+        let recently_modified = SystemTime::now()
+            .duration_since(modified)
+            .map_or(true, |age| age < SAMPLE_IF_MODIFIED_WITHIN);
+        if !recently_modified {
+            return Ok(false);
+        }
+
+        thread::sleep(self.interval);
+        let after = fs::metadata(path)?;
+        let growing = after.len() != before.len() || after.modified()? != modified;
+        if growing {
+            tracing::debug!(
+                "File {} is still being written ({} -> {} bytes)",
+                path.display(),
+                before.len(),
+                after.len()
+            );
+        }
+        Ok(growing)
+    }
+}
+
+/// Checks for open handles and growing files before a move
+///
+/// A file counts as active if any of the configured checks says so.
+pub struct FileActivityChecker {
+    checks: Vec<Box<dyn FileChecker>>,
+}
+
+impl FileActivityChecker {
+    pub fn new(checks: Vec<Box<dyn FileChecker>>) -> Self {
+        Self { checks }
+    }
+
+    /// Builds the checks for `config.method`, probing which tools are installed
+    pub fn from_config(config: &ActivityCheckConfig) -> Self {
+        let stability = || -> Box<dyn FileChecker> {
+            Box::new(SizeStabilityChecker::new(Duration::from_secs(
+                config.stability_secs,
+            )))
+        };
+
+        let checks: Vec<Box<dyn FileChecker>> = match config.method {
+            ActivityCheckMethod::Lsof => vec![Box::new(LsofFileChecker)],
+            ActivityCheckMethod::Fuser => vec![Box::new(FuserFileChecker)],
+            ActivityCheckMethod::SizeStability => vec![stability()],
+            ActivityCheckMethod::Auto => {
+                if command_available("lsof", "-v") {
+                    vec![Box::new(LsofFileChecker)]
+                } else if command_available("fuser", "-V") {
+                    vec![Box::new(FuserFileChecker)]
+                } else {
+                    tracing::info!(
+                        "Neither lsof nor fuser found, falling back to file locking and size-stability sampling"
+                    );
+                    vec![Box::new(FileLockChecker), stability()]
+                }
+            }
+        };
+
+        Self::new(checks)
+    }
+}
+
+impl FileChecker for FileActivityChecker {
+    fn is_file_in_use(&self, path: &Path) -> io::Result<bool> {
+        for check in &self.checks {
+            if check.is_file_in_use(path)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+fn command_available(program: &str, version_arg: &str) -> bool {
+    Command::new(program)
+        .arg(version_arg)
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
 /// Implementation using file locking (cross-platform but less reliable)
 pub struct FileLockChecker;
 
@@ -202,6 +325,60 @@ mod tests {
         std::fs::remove_file(&temp_file).unwrap();
     }
 
+    #[test]
+    fn test_size_stability_checker_detects_growth() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("growing.bin");
+        std::fs::write(&path, b"start").unwrap();
+
+        let writer = {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                let mut file = std::fs::OpenOptions::new().append(true).open(path).unwrap();
+                file.write_all(b" more data").unwrap();
+            })
+        };
+
+        let checker = SizeStabilityChecker::new(Duration::from_millis(200));
+        assert!(checker.is_file_in_use(&path).unwrap());
+        writer.join().unwrap();
+
+        assert!(!checker.is_file_in_use(&path).unwrap());
+    }
+
+    #[test]
+    fn test_size_stability_checker_skips_old_files() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("old.bin");
+        std::fs::write(&path, b"data").unwrap();
+        let file = File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() - 2 * SAMPLE_IF_MODIFIED_WITHIN)
+            .unwrap();
+
+        // Would block for an hour if the file were sampled
+        let checker = SizeStabilityChecker::new(Duration::from_secs(3600));
+        assert!(!checker.is_file_in_use(&path).unwrap());
+    }
+
+    #[test]
+    fn test_activity_checker_any_check_marks_active() {
+        struct Always(bool);
+        impl FileChecker for Always {
+            fn is_file_in_use(&self, _path: &Path) -> io::Result<bool> {
+                Ok(self.0)
+            }
+        }
+
+        let path = Path::new("/mock/file.mkv");
+        let idle = FileActivityChecker::new(vec![Box::new(Always(false))]);
+        assert!(!idle.is_file_in_use(path).unwrap());
+
+        let active =
+            FileActivityChecker::new(vec![Box::new(Always(false)), Box::new(Always(true))]);
+        assert!(active.is_file_in_use(path).unwrap());
+    }
+
     #[test]
     fn test_smart_checker() {
         let checker = SmartFileChecker::new();
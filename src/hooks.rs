@@ -0,0 +1,316 @@
+//! User commands run after moves and after each run
+
+use crate::config::HooksConfig;
+use crate::executor::{ExecutionMove, ExecutionResult};
+use std::io::{self, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Captured output kept per hook, from the end
+const MAX_OUTPUT_BYTES: usize = 4096;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// When a hook ran
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    PostMove,
+    PostRun,
+}
+
+impl std::fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PostMove => write!(f, "post_move"),
+            Self::PostRun => write!(f, "post_run"),
+        }
+    }
+}
+
+/// Outcome of one hook command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookRun {
+    pub event: HookEvent,
+    /// Command after placeholder substitution
+    pub command: String,
+    /// Exit code, `None` if the hook was killed or could not start
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    /// Combined stdout and stderr (last 4 KiB)
+    pub output: String,
+}
+
+impl HookRun {
+    pub const fn succeeded(&self) -> bool {
+        matches!(self.exit_code, Some(0))
+    }
+}
+
+/// Hook commands from the `hooks` config section
+#[derive(Debug, Clone, Default)]
+pub struct Hooks {
+    post_move: Vec<String>,
+    post_run: Vec<String>,
+    timeout: Duration,
+}
+
+impl Hooks {
+    pub const fn new(post_move: Vec<String>, post_run: Vec<String>, timeout: Duration) -> Self {
+        Self {
+            post_move,
+            post_run,
+            timeout,
+        }
+    }
+
+    pub fn from_config(config: &HooksConfig) -> Self {
+        Self::new(
+            config.post_move.clone(),
+            config.post_run.clone(),
+            Duration::from_secs(config.timeout_secs),
+        )
+    }
+
+    /// Runs the `post_move` hooks for a completed move
+    pub fn post_move(&self, moved: &ExecutionMove) -> Vec<HookRun> {
+        let dir = |path: &Path| {
+            path.parent()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default()
+        };
+        let vars = [
+            ("source", moved.file.display().to_string()),
+            ("destination", moved.destination.display().to_string()),
+            ("source_dir", dir(&moved.file)),
+            ("destination_dir", dir(&moved.destination)),
+            ("from_tier", moved.from_tier.clone()),
+            ("to_tier", moved.to_tier.clone()),
+            ("size", moved.size.to_string()),
+        ];
+        self.run_all(HookEvent::PostMove, &self.post_move, &vars)
+    }
+
+    /// Runs the `post_run` hooks, if the run moved anything
+    pub fn post_run(&self, result: &ExecutionResult) -> Vec<HookRun> {
+        if result.files_moved == 0 {
+            return Vec::new();
+        }
+        let vars = [
+            ("files_moved", result.files_moved.to_string()),
+            ("bytes_moved", result.bytes_moved.to_string()),
+            ("errors", result.errors.len().to_string()),
+        ];
+        self.run_all(HookEvent::PostRun, &self.post_run, &vars)
+    }
+
+    fn run_all(
+        &self,
+        event: HookEvent,
+        templates: &[String],
+        vars: &[(&str, String)],
+    ) -> Vec<HookRun> {
+        templates
+            .iter()
+            .map(|template| {
+                let run = run_hook(event, render(template, vars), vars, self.timeout);
+                if run.succeeded() {
+                    tracing::info!("{event} hook succeeded: {}", run.command);
+                    if !run.output.is_empty() {
+                        tracing::debug!("{event} hook output:\n{}", run.output);
+                    }
+                } else {
+                    tracing::warn!(
+                        "{event} hook failed ({}): {}\n{}",
+                        if run.timed_out {
+                            "timed out".to_string()
+                        } else {
+                            run.exit_code
+                                .map_or_else(|| "no exit code".to_string(), |c| format!("exit {c}"))
+                        },
+                        run.command,
+                        run.output
+                    );
+                }
+                run
+            })
+            .collect()
+    }
+}
+
+/// Replaces each `{name}` in `template` with the shell-quoted value
+fn render(template: &str, vars: &[(&str, String)]) -> String {
+    vars.iter()
+        .fold(template.to_string(), |command, (name, value)| {
+            command.replace(&format!("{{{name}}}"), &shell_quote(value))
+        })
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn run_hook(
+    event: HookEvent,
+    command: String,
+    vars: &[(&str, String)],
+    timeout: Duration,
+) -> HookRun {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c")
+        .arg(&command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    for (name, value) in vars {
+        cmd.env(format!("TIERFLOW_{}", name.to_uppercase()), value);
+    }
+    // Own process group, so a timeout also kills whatever the shell started
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            return HookRun {
+                event,
+                command,
+                exit_code: None,
+                timed_out: false,
+                output: format!("Failed to start hook: {e}"),
+            };
+        }
+    };
+
+    // Drain both pipes while waiting so a chatty hook can't block on a full pipe
+    let readers = [
+        child.stdout.take().map(spawn_reader),
+        child.stderr.take().map(spawn_reader),
+    ];
+
+    let started = Instant::now();
+    let (status, timed_out) = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break (Some(status), false),
+            Ok(None) if started.elapsed() >= timeout => {
+                kill_hook(&mut child);
+                break (child.wait().ok(), true);
+            }
+            Ok(None) => thread::sleep(POLL_INTERVAL),
+            Err(e) => {
+                tracing::warn!("Failed to wait for hook: {e}");
+                break (None, false);
+            }
+        }
+    };
+
+    let mut output = Vec::new();
+    for reader in readers.into_iter().flatten() {
+        output.extend(reader.join().unwrap_or_default());
+    }
+    let start = output.len().saturating_sub(MAX_OUTPUT_BYTES);
+
+    HookRun {
+        event,
+        command,
+        exit_code: if timed_out {
+            None
+        } else {
+            status.and_then(|s| s.code())
+        },
+        timed_out,
+        output: String::from_utf8_lossy(&output[start..])
+            .trim_end()
+            .to_string(),
+    }
+}
+
+fn kill_hook(child: &mut std::process::Child) {
+    #[cfg(unix)]
+    if let Ok(pid) = i32::try_from(child.id()) {
+        use nix::sys::signal::{Signal, killpg};
+        use nix::unistd::Pid;
+        let _ = killpg(Pid::from_raw(pid), Signal::SIGKILL);
+    }
+    let _ = child.kill();
+}
+
+fn spawn_reader(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Err(e) = pipe.read_to_end(&mut buf)
+            && e.kind() != io::ErrorKind::Interrupted
+        {
+            tracing::debug!("Failed to read hook output: {e}");
+        }
+        buf
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Verification;
+    use std::path::PathBuf;
+
+    fn moved(file: &str) -> ExecutionMove {
+        ExecutionMove {
+            file: PathBuf::from(file),
+            destination: PathBuf::from("/mnt/storage/Movies/it's here.mkv"),
+            from_tier: "cache".to_string(),
+            to_tier: "storage".to_string(),
+            size: 42,
+            verification: Verification::Size,
+        }
+    }
+
+    #[test]
+    fn test_render_quotes_placeholders() {
+        let vars = [("destination", "/tv/it's $(here).mkv".to_string())];
+        assert_eq!(
+            render("scan {destination} {unknown}", &vars),
+            r"scan '/tv/it'\''s $(here).mkv' {unknown}"
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_post_move_hook_captures_output() {
+        let hooks = Hooks::new(
+            vec!["echo {to_tier} {destination_dir}; echo \"$TIERFLOW_SIZE\" >&2".to_string()],
+            Vec::new(),
+            Duration::from_secs(10),
+        );
+
+        let runs = hooks.post_move(&moved("/mnt/cache/Movies/it's here.mkv"));
+
+        assert_eq!(runs.len(), 1);
+        assert!(runs[0].succeeded());
+        assert_eq!(runs[0].output, "storage /mnt/storage/Movies\n42");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_hook_timeout_and_failure() {
+        let hooks = Hooks::new(
+            Vec::new(),
+            vec!["sleep 5".to_string(), "exit 3".to_string()],
+            Duration::from_millis(200),
+        );
+        let result = ExecutionResult {
+            files_moved: 1,
+            ..ExecutionResult::default()
+        };
+
+        let runs = hooks.post_run(&result);
+
+        assert!(runs[0].timed_out);
+        assert_eq!(runs[0].exit_code, None);
+        assert_eq!(runs[1].exit_code, Some(3));
+        assert!(!runs[1].succeeded());
+
+        // Nothing moved, nothing to report
+        assert!(hooks.post_run(&ExecutionResult::default()).is_empty());
+    }
+}
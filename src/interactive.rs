@@ -0,0 +1,161 @@
+//! Approving a plan's moves group by group before execution, for `rebalance --interactive`
+
+use crate::{BalancingPlan, MoveGroup, Tier};
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// Answer for one group of moves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Answer {
+    Approve,
+    Deny,
+    ApproveAll,
+    DenyAll,
+    List,
+}
+
+impl Answer {
+    fn parse(line: &str) -> Option<Self> {
+        match line.trim().to_ascii_lowercase().as_str() {
+            "y" | "yes" => Some(Self::Approve),
+            "n" | "no" => Some(Self::Deny),
+            "a" | "all" => Some(Self::ApproveAll),
+            "q" | "quit" => Some(Self::DenyAll),
+            "l" | "list" => Some(Self::List),
+            _ => None,
+        }
+    }
+}
+
+/// Asks on the terminal which groups of moves to keep and drops the rest from `plan`
+///
+/// Returns the number of moves dropped.
+pub fn review_plan(plan: &mut BalancingPlan, tiers: &[Tier]) -> io::Result<usize> {
+    if !io::stdin().is_terminal() {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "--interactive needs a terminal on stdin",
+        ));
+    }
+    let groups = plan.move_groups(tiers);
+    let approved = review_groups(plan, &groups, &mut io::stdin().lock(), &mut io::stderr())?;
+    let denied = groups
+        .iter()
+        .zip(approved)
+        .filter(|(_, approved)| !approved)
+        .flat_map(|(group, _)| group.decisions.iter().copied());
+    Ok(plan.skip_decisions(denied))
+}
+
+/// Prompts for each group in turn; end of input denies the remaining groups
+pub fn review_groups(
+    plan: &BalancingPlan,
+    groups: &[MoveGroup],
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<Vec<bool>> {
+    let mut approved = Vec::with_capacity(groups.len());
+    let mut rest = None;
+    for (number, group) in groups.iter().enumerate() {
+        if let Some(all) = rest {
+            approved.push(all);
+            continue;
+        }
+        writeln!(
+            output,
+            "\n[{}/{}] {}: {} {} -> {} ({} file(s), {:.2} GB) in {}",
+            number + 1,
+            groups.len(),
+            group.strategy,
+            if group.copy { "copy" } else { "move" },
+            group.from_tier,
+            group.to_tier,
+            group.files,
+            group.bytes as f64 / 1_000_000_000.0,
+            group.directory
+        )?;
+        let answer = loop {
+            write!(
+                output,
+                "Apply? [y]es, [n]o, [a]ll remaining, [q]uit, [l]ist files: "
+            )?;
+            output.flush()?;
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                writeln!(output)?;
+                break Answer::DenyAll;
+            }
+            match Answer::parse(&line) {
+                Some(Answer::List) => {
+                    for &index in &group.decisions {
+                        writeln!(output, "  {}", plan.decisions[index].file_path().display())?;
+                    }
+                }
+                Some(answer) => break answer,
+                None => {}
+            }
+        };
+        let keep = matches!(answer, Answer::Approve | Answer::ApproveAll);
+        if matches!(answer, Answer::ApproveAll | Answer::DenyAll) {
+            rest = Some(keep);
+        }
+        approved.push(keep);
+    }
+    Ok(approved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileInfo, PlacementDecision};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::SystemTime;
+
+    fn demote(path: &str) -> PlacementDecision {
+        PlacementDecision::Demote {
+            file: Arc::new(FileInfo {
+                path: PathBuf::from(path),
+                size: 1,
+                modified: SystemTime::UNIX_EPOCH,
+                accessed: SystemTime::UNIX_EPOCH,
+                owner: None,
+            }),
+            from_tier: "cache".to_string(),
+            to_tier: "storage".to_string(),
+            strategy: "old".to_string(),
+            priority: 1,
+        }
+    }
+
+    #[test]
+    fn test_review_groups() {
+        let tiers =
+            vec![Tier::new_unchecked("cache".into(), "/mnt/cache".into(), 1, None, None).unwrap()];
+        let plan = BalancingPlan {
+            decisions: ["a", "b", "c", "d"]
+                .iter()
+                .map(|dir| demote(&format!("/mnt/cache/{dir}/file.mkv")))
+                .collect(),
+            ..Default::default()
+        };
+        let groups = plan.move_groups(&tiers);
+
+        let mut output = Vec::new();
+        let approved = review_groups(
+            &plan,
+            &groups,
+            &mut "l\nmaybe\ny\nn\na\n".as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(approved, vec![true, false, true, true]);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("[1/4] old: move cache -> storage (1 file(s), 0.00 GB) in a"));
+        assert!(output.contains("  /mnt/cache/a/file.mkv"));
+        assert!(!output.contains("[4/4]"));
+
+        // Running out of input denies what is left
+        let approved = review_groups(&plan, &groups, &mut "y\n".as_bytes(), &mut Vec::new());
+        assert_eq!(approved.unwrap(), vec![true, false, false, false]);
+    }
+}
@@ -0,0 +1,131 @@
+//! Flat per-file export of a plan for offline analysis
+
+use crate::file::serialize_path_lossy;
+use crate::{BalancingPlan, PlacementDecision};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// What the planner decided for a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InventoryAction {
+    Stay,
+    Promote,
+    Demote,
+    Copy,
+}
+
+/// One scanned file with its placement decision
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InventoryEntry {
+    #[serde(serialize_with = "serialize_path_lossy")]
+    pub path: PathBuf,
+    pub size: u64,
+    /// Unix time of the last modification
+    pub modified: u64,
+    /// Unix time of the last access
+    pub accessed: u64,
+    pub tier: String,
+    /// Matching strategy, `None` if no strategy matched
+    pub strategy: Option<String>,
+    pub decision: InventoryAction,
+    /// Destination of a promote, demote or copy
+    pub target_tier: Option<String>,
+}
+
+impl InventoryEntry {
+    pub fn from_decision(decision: &PlacementDecision) -> Self {
+        let (action, target_tier) = match decision {
+            PlacementDecision::Stay { .. } => (InventoryAction::Stay, None),
+            PlacementDecision::Promote { to_tier, .. } => {
+                (InventoryAction::Promote, Some(to_tier.clone()))
+            }
+            PlacementDecision::Demote { to_tier, .. } => {
+                (InventoryAction::Demote, Some(to_tier.clone()))
+            }
+            PlacementDecision::Copy { to_tier, .. } => {
+                (InventoryAction::Copy, Some(to_tier.clone()))
+            }
+        };
+        let file = decision.file();
+        let strategy = decision.strategy_name();
+
+        Self {
+            path: file.path.clone(),
+            size: file.size,
+            modified: file.modified_timestamp(),
+            accessed: file.accessed_timestamp(),
+            tier: decision.current_tier().to_string(),
+            strategy: (strategy != "no-match").then(|| strategy.to_string()),
+            decision: action,
+            target_tier,
+        }
+    }
+}
+
+/// Every file of the plan, sorted by path
+pub fn inventory(plan: &BalancingPlan) -> Vec<InventoryEntry> {
+    let mut entries: Vec<_> = plan
+        .decisions
+        .iter()
+        .map(InventoryEntry::from_decision)
+        .collect();
+    entries.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.tier.cmp(&b.tier)));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileInfo;
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    fn file(path: &str) -> Arc<FileInfo> {
+        Arc::new(FileInfo {
+            path: PathBuf::from(path),
+            size: 100,
+            modified: UNIX_EPOCH + Duration::from_secs(1000),
+            accessed: SystemTime::now(),
+            owner: None,
+        })
+    }
+
+    #[test]
+    fn test_inventory_from_plan() {
+        let plan = BalancingPlan {
+            decisions: vec![
+                PlacementDecision::Demote {
+                    file: file("/cache/b.mkv"),
+                    from_tier: "cache".to_string(),
+                    to_tier: "storage".to_string(),
+                    strategy: "old".to_string(),
+                    priority: 10,
+                },
+                PlacementDecision::Stay {
+                    file: file("/cache/a.mkv"),
+                    current_tier: "cache".to_string(),
+                    strategy: "no-match".to_string(),
+                    priority: 0,
+                },
+            ],
+            ..BalancingPlan::default()
+        };
+
+        let entries = inventory(&plan);
+
+        assert_eq!(entries[0].path, PathBuf::from("/cache/a.mkv"));
+        assert_eq!(entries[0].decision, InventoryAction::Stay);
+        assert_eq!(entries[0].strategy, None);
+        assert_eq!(entries[0].target_tier, None);
+
+        assert_eq!(entries[1].decision, InventoryAction::Demote);
+        assert_eq!(entries[1].strategy.as_deref(), Some("old"));
+        assert_eq!(entries[1].target_tier.as_deref(), Some("storage"));
+        assert_eq!(entries[1].modified, 1000);
+
+        let json = serde_json::to_value(&entries[1]).unwrap();
+        assert_eq!(json["decision"], "demote");
+        assert_eq!(json["tier"], "cache");
+    }
+}
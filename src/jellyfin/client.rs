@@ -0,0 +1,294 @@
+use crate::error::{AppError, Result};
+use crate::tautulli::HistoryItem;
+use crate::timestamp::{parse_utc, unix_now};
+use crate::watch_history::WatchHistoryProvider;
+use reqwest::blocking::Client;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+/// Jellyfin (and Emby) API client
+pub struct JellyfinClient {
+    base_url: String,
+    api_key: String,
+    client: Client,
+}
+
+impl JellyfinClient {
+    /// Create new Jellyfin client
+    pub fn new(base_url: String, api_key: String) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| AppError::Io(std::io::Error::other(e)))?;
+
+        // Normalize base_url: ensure it ends with /
+        let base_url = if base_url.ends_with('/') {
+            base_url
+        } else {
+            format!("{base_url}/")
+        };
+
+        Ok(Self {
+            base_url,
+            api_key,
+            client,
+        })
+    }
+
+    fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = format!("{}{path}", self.base_url);
+        let response = self
+            .client
+            .get(&url)
+            .header("X-Emby-Token", &self.api_key)
+            .send()
+            .map_err(|e| {
+                AppError::External(format!(
+                    "Failed to connect to Jellyfin at {}: {e}",
+                    self.base_url
+                ))
+            })?;
+
+        if !response.status().is_success() {
+            return Err(AppError::External(format!(
+                "Jellyfin API returned error status: {}",
+                response.status()
+            )));
+        }
+
+        response
+            .json()
+            .map_err(|e| AppError::External(format!("Failed to parse Jellyfin response: {e}")))
+    }
+
+    /// Health check - verify Jellyfin is reachable and API key is valid
+    pub fn health_check(&self) -> Result<()> {
+        tracing::info!("Performing Jellyfin health check: {}", self.base_url);
+
+        let info: SystemInfo = self.get("System/Info")?;
+        tracing::info!(
+            "Jellyfin health check passed: {} (version {})",
+            info.server_name,
+            info.version
+        );
+        Ok(())
+    }
+
+    /// Played episodes of every user plus episodes playing right now
+    ///
+    /// `length` limits the played episodes fetched per user.
+    pub fn get_history(&self, length: u32) -> Result<Vec<HistoryItem>> {
+        tracing::debug!("Fetching Jellyfin history (length: {length} per user)");
+
+        let users: Vec<User> = self.get("Users")?;
+        let mut history = Vec::new();
+        for user in &users {
+            let items: ItemsResponse = self.get(&format!(
+                "Users/{}/Items?Recursive=true&IncludeItemTypes=Episode&Filters=IsPlayed\
+                 &SortBy=DatePlayed&SortOrder=Descending&EnableUserData=true&Limit={length}",
+                user.id
+            ))?;
+            history.extend(history_from_items(&user.name, &items.items));
+        }
+
+        let sessions: Vec<Session> = self.get("Sessions")?;
+        history.extend(history_from_sessions(&sessions, unix_now()));
+
+        tracing::debug!(
+            "Fetched {} history items for {} users",
+            history.len(),
+            users.len()
+        );
+        Ok(history)
+    }
+}
+
+impl WatchHistoryProvider for JellyfinClient {
+    fn name(&self) -> &'static str {
+        "jellyfin"
+    }
+
+    fn health_check(&self) -> Result<()> {
+        Self::health_check(self)
+    }
+
+    fn get_history(&self, length: u32) -> Result<Vec<HistoryItem>> {
+        Self::get_history(self, length)
+    }
+}
+
+// API Response structures
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SystemInfo {
+    server_name: String,
+    version: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct User {
+    name: String,
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ItemsResponse {
+    #[serde(default)]
+    items: Vec<Item>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Item {
+    id: String,
+    #[serde(default)]
+    series_name: Option<String>,
+    /// Season number
+    #[serde(default)]
+    parent_index_number: Option<u32>,
+    /// Episode number
+    #[serde(default)]
+    index_number: Option<u32>,
+    #[serde(default)]
+    user_data: Option<UserData>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct UserData {
+    #[serde(default)]
+    played: bool,
+    #[serde(default)]
+    played_percentage: Option<f64>,
+    #[serde(default)]
+    last_played_date: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Session {
+    #[serde(default)]
+    user_name: Option<String>,
+    #[serde(default)]
+    now_playing_item: Option<Item>,
+}
+
+impl Item {
+    fn to_history_item(
+        &self,
+        user: &str,
+        percent_complete: u8,
+        stopped: u64,
+    ) -> Option<HistoryItem> {
+        Some(HistoryItem {
+            user: user.to_string(),
+            rating_key: self.id.clone(),
+            media_type: "episode".to_string(),
+            title: String::new(),
+            year: 0,
+            grandparent_title: self.series_name.clone()?,
+            parent_media_index: self.parent_index_number?,
+            media_index: self.index_number?,
+            percent_complete,
+            stopped,
+        })
+    }
+}
+
+/// History items for a user's played episodes
+fn history_from_items(user: &str, items: &[Item]) -> Vec<HistoryItem> {
+    items
+        .iter()
+        .filter_map(|item| {
+            let user_data = item.user_data.as_ref()?;
+            // Played items usually report no percentage, in-progress ones do
+            let percent = if user_data.played {
+                100
+            } else {
+                user_data.played_percentage?.clamp(0.0, 100.0) as u8
+            };
+            let stopped = user_data
+                .last_played_date
+                .as_deref()
+                .and_then(parse_utc)
+                .unwrap_or(0);
+            item.to_history_item(user, percent, stopped)
+        })
+        .collect()
+}
+
+/// History items for episodes being played right now
+///
+/// They count as fully watched so the viewing window follows live playback.
+fn history_from_sessions(sessions: &[Session], now: u64) -> Vec<HistoryItem> {
+    sessions
+        .iter()
+        .filter_map(|session| {
+            let user = session.user_name.as_deref()?;
+            session
+                .now_playing_item
+                .as_ref()?
+                .to_history_item(user, 100, now)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_from_items() {
+        let json = r#"{"Items": [
+            {"Id": "a1", "SeriesName": "Breaking Bad", "ParentIndexNumber": 1, "IndexNumber": 5,
+             "UserData": {"Played": true, "LastPlayedDate": "2024-01-15T20:31:12.0000000Z"}},
+            {"Id": "a2", "SeriesName": "The Wire", "ParentIndexNumber": 2, "IndexNumber": 3,
+             "UserData": {"Played": false, "PlayedPercentage": 42.5}},
+            {"Id": "m1", "UserData": {"Played": true}}
+        ]}"#;
+        let response: ItemsResponse = serde_json::from_str(json).unwrap();
+
+        let history = history_from_items("alice", &response.items);
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].user, "alice");
+        assert_eq!(history[0].rating_key, "a1");
+        assert_eq!(history[0].grandparent_title, "Breaking Bad");
+        assert_eq!(history[0].parent_media_index, 1);
+        assert_eq!(history[0].media_index, 5);
+        assert_eq!(history[0].percent_complete, 100);
+        assert_eq!(history[0].stopped, 1_705_350_672);
+        assert_eq!(history[1].percent_complete, 42);
+        assert_eq!(history[1].stopped, 0);
+    }
+
+    #[test]
+    fn test_history_from_sessions() {
+        let json = r#"[
+            {"UserName": "bob", "NowPlayingItem": {"Id": "e9", "SeriesName": "The Wire",
+             "ParentIndexNumber": 3, "IndexNumber": 4}},
+            {"UserName": "idle"}
+        ]"#;
+        let sessions: Vec<Session> = serde_json::from_str(json).unwrap();
+
+        let history = history_from_sessions(&sessions, 1000);
+
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].user, "bob");
+        assert_eq!(history[0].media_index, 4);
+        assert_eq!(history[0].percent_complete, 100);
+        assert_eq!(history[0].stopped, 1000);
+    }
+
+    #[test]
+    fn test_url_normalization_without_trailing_slash() {
+        let client =
+            JellyfinClient::new("http://localhost:8096".to_string(), "test-key".to_string())
+                .expect("Should create client");
+        assert_eq!(client.base_url, "http://localhost:8096/");
+    }
+}
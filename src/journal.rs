@@ -0,0 +1,404 @@
+use crate::drift::DriftReport;
+use crate::error::Result;
+use crate::{BalancingPlan, ExecutionResult, Tier, Verification};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const JOURNAL_FILE: &str = "journal.json";
+
+/// Minimum time move timestamps are kept in the journal
+pub const MOVE_RETENTION: Duration = Duration::from_secs(30 * 24 * 3600);
+
+/// Drift reports kept in the journal, most recent last
+const DRIFT_HISTORY: usize = 100;
+
+/// Default directory for persistent state (`$XDG_STATE_HOME/tierflow`)
+pub fn default_state_dir() -> PathBuf {
+    dirs::state_dir().or_else(dirs::data_local_dir).map_or_else(
+        || PathBuf::from("/var/lib/tierflow"),
+        |dir| dir.join("tierflow"),
+    )
+}
+
+/// What tierflow remembers about a single file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileRecord {
+    /// Unix time of the last successful move
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_moved: Option<u64>,
+    /// Unix time tierflow first found the file on a tier
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_seen: Option<u64>,
+    /// Checksum the last move was verified with, e.g. `blake3:<hex>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct JournalData {
+    #[serde(default)]
+    files: HashMap<PathBuf, FileRecord>,
+    /// Projection drift of recent runs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    drift: Vec<DriftReport>,
+}
+
+/// Persistent per-file state shared across runs
+///
+/// Files are keyed by their path relative to the tier root, so a record
+/// follows the file when it moves between tiers.
+#[derive(Debug, Default)]
+pub struct StateJournal {
+    path: Option<PathBuf>,
+    data: JournalData,
+}
+
+impl StateJournal {
+    /// Loads the journal from `state_dir`, starting empty if none exists yet
+    pub fn load(state_dir: &Path) -> Result<Self> {
+        let path = state_dir.join(JOURNAL_FILE);
+        let data = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => JournalData::default(),
+            Err(e) => return Err(e.into()),
+        };
+
+        tracing::debug!(
+            "Loaded state journal {} ({} files)",
+            path.display(),
+            data.files.len()
+        );
+        Ok(Self {
+            path: Some(path),
+            data,
+        })
+    }
+
+    /// Journal that is never written to disk
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, relative_path: &Path) -> Option<&FileRecord> {
+        self.data.files.get(relative_path)
+    }
+
+    /// Relative paths of all files with a recorded move
+    pub fn moved_files(&self) -> impl Iterator<Item = &Path> {
+        self.data
+            .files
+            .iter()
+            .filter(|(_, record)| record.last_moved.is_some())
+            .map(|(path, _)| path.as_path())
+    }
+
+    /// Relative paths of all files with a recorded checksum, with the checksum
+    pub fn checksums(&self) -> impl Iterator<Item = (&Path, &str)> {
+        self.data.files.iter().filter_map(|(path, record)| {
+            record
+                .checksum
+                .as_deref()
+                .map(|checksum| (path.as_path(), checksum))
+        })
+    }
+
+    /// Time since the file was last moved by tierflow
+    pub fn time_since_move(&self, relative_path: &Path, now: SystemTime) -> Option<Duration> {
+        let last_moved = self.get(relative_path)?.last_moved?;
+        let moved_at = UNIX_EPOCH + Duration::from_secs(last_moved);
+        Some(now.duration_since(moved_at).unwrap_or_default())
+    }
+
+    /// Relative paths of all files with a first-seen time
+    pub fn seen_files(&self) -> impl Iterator<Item = &Path> {
+        self.data
+            .files
+            .iter()
+            .filter(|(_, record)| record.first_seen.is_some())
+            .map(|(path, _)| path.as_path())
+    }
+
+    /// Time since tierflow first found the file
+    pub fn time_since_first_seen(&self, relative_path: &Path, now: SystemTime) -> Option<Duration> {
+        let first_seen = self.get(relative_path)?.first_seen?;
+        let seen_at = UNIX_EPOCH + Duration::from_secs(first_seen);
+        Some(now.duration_since(seen_at).unwrap_or_default())
+    }
+
+    /// Records every file of `plan` as seen, forgetting files no longer on any tier
+    ///
+    /// The plan has a decision for every scanned file, so it is the full file list.
+    pub fn record_seen(&mut self, plan: &BalancingPlan, tiers: &[Tier], at: SystemTime) {
+        let seen = Self::planned_paths(plan, tiers);
+
+        self.data.files.retain(|path, record| {
+            if !seen.contains(path.as_path()) {
+                record.first_seen = None;
+            }
+            *record != FileRecord::default()
+        });
+        for path in seen {
+            self.record_first_seen(path.to_path_buf(), at);
+        }
+    }
+
+    /// Records the files of a plan that covered only some directories; nothing is forgotten
+    pub fn record_seen_partial(&mut self, plan: &BalancingPlan, tiers: &[Tier], at: SystemTime) {
+        for path in Self::planned_paths(plan, tiers) {
+            self.record_first_seen(path.to_path_buf(), at);
+        }
+    }
+
+    fn planned_paths<'p>(plan: &'p BalancingPlan, tiers: &[Tier]) -> HashSet<&'p Path> {
+        plan.decisions
+            .iter()
+            .filter_map(|decision| {
+                tiers
+                    .iter()
+                    .find(|t| t.name == decision.current_tier())?
+                    .relative_path(decision.file_path())
+            })
+            .collect()
+    }
+
+    /// Records the file as seen at `at`, unless it was seen before
+    pub fn record_first_seen(&mut self, relative_path: PathBuf, at: SystemTime) {
+        let secs = at.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        self.data
+            .files
+            .entry(relative_path)
+            .or_default()
+            .first_seen
+            .get_or_insert(secs);
+    }
+
+    pub fn record_move(&mut self, relative_path: PathBuf, at: SystemTime) {
+        let secs = at.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        self.data.files.entry(relative_path).or_default().last_moved = Some(secs);
+    }
+
+    /// Records a move of `file` (source path) out of the tier named `from_tier`
+    pub fn record_tier_move(
+        &mut self,
+        file: &Path,
+        from_tier: &str,
+        tiers: &[Tier],
+        at: SystemTime,
+    ) {
+        let Some(tier) = tiers.iter().find(|t| t.name == from_tier) else {
+            return;
+        };
+        if let Some(relative) = tier.relative_path(file) {
+            self.record_move(relative.to_path_buf(), at);
+        }
+    }
+
+    /// Records every successful move of an execution, with its checksum if it was hashed
+    pub fn record_execution(&mut self, result: &ExecutionResult, tiers: &[Tier], at: SystemTime) {
+        for moved in &result.moved {
+            self.record_tier_move(&moved.file, &moved.from_tier, tiers, at);
+            let relative = tiers
+                .iter()
+                .find(|t| t.name == moved.from_tier)
+                .and_then(|tier| tier.relative_path(&moved.file));
+            if let Some(record) = relative.and_then(|path| self.data.files.get_mut(path)) {
+                record.checksum = match moved.verification {
+                    Verification::Checksum(checksum) => Some(checksum.to_string()),
+                    Verification::None | Verification::Size => None,
+                };
+            }
+        }
+    }
+
+    /// Keeps the drift of a run, dropping the oldest beyond the history size
+    pub fn record_drift(&mut self, report: DriftReport) {
+        self.data.drift.push(report);
+        let excess = self.data.drift.len().saturating_sub(DRIFT_HISTORY);
+        self.data.drift.drain(..excess);
+    }
+
+    /// Drift of recent runs, oldest first
+    pub fn drift_history(&self) -> &[DriftReport] {
+        &self.data.drift
+    }
+
+    /// Drops move timestamps older than `max_age`, and records left empty
+    pub fn prune_moves(&mut self, max_age: Duration, now: SystemTime) {
+        let cutoff = now
+            .checked_sub(max_age)
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs());
+
+        self.data.files.retain(|_, record| {
+            if record.last_moved.is_some_and(|moved| moved < cutoff) {
+                record.last_moved = None;
+                record.checksum = None;
+            }
+            *record != FileRecord::default()
+        });
+    }
+
+    /// Writes the journal atomically (temp file + rename)
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_vec(&self.data)?)?;
+        fs::rename(&tmp_path, path)?;
+
+        tracing::debug!(
+            "Saved state journal {} ({} files)",
+            path.display(),
+            self.data.files.len()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::ExecutionMove;
+    use tempfile::TempDir;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    #[test]
+    fn test_load_missing_journal_is_empty() {
+        let temp = TempDir::new().unwrap();
+        let journal = StateJournal::load(temp.path()).unwrap();
+        assert!(journal.get(Path::new("movie.mkv")).is_none());
+    }
+
+    #[test]
+    fn test_save_and_reload() {
+        let temp = TempDir::new().unwrap();
+        let now = SystemTime::now();
+
+        let mut journal = StateJournal::load(temp.path()).unwrap();
+        journal.record_move(PathBuf::from("Show/e1.mkv"), now - 2 * HOUR);
+        for at in 0..=DRIFT_HISTORY as u64 {
+            journal.record_drift(DriftReport { at, tiers: vec![] });
+        }
+        journal.save().unwrap();
+
+        let journal = StateJournal::load(temp.path()).unwrap();
+        let since = journal
+            .time_since_move(Path::new("Show/e1.mkv"), now)
+            .unwrap();
+        assert!(since >= 2 * HOUR && since < 3 * HOUR);
+        // The oldest drift report made room for the newest
+        assert_eq!(journal.drift_history().len(), DRIFT_HISTORY);
+        assert_eq!(journal.drift_history()[0].at, 1);
+    }
+
+    #[test]
+    fn test_record_execution_uses_relative_paths() {
+        let tier = Tier::new_mock_with_usage("cache", 1, None, 1000, 0);
+        let result = ExecutionResult {
+            moved: vec![ExecutionMove {
+                file: tier.path.join("Show/e1.mkv"),
+                destination: PathBuf::from("/storage/Show/e1.mkv"),
+                from_tier: "cache".to_string(),
+                to_tier: "storage".to_string(),
+                size: 100,
+                verification: crate::Verification::Size,
+            }],
+            ..ExecutionResult::default()
+        };
+
+        let mut journal = StateJournal::in_memory();
+        journal.record_execution(&result, &[tier], SystemTime::now());
+
+        let record = journal.get(Path::new("Show/e1.mkv")).unwrap();
+        assert!(record.last_moved.is_some());
+        assert_eq!(record.checksum, None);
+    }
+
+    #[test]
+    fn test_record_seen_keeps_first_sighting_and_forgets_missing_files() {
+        let tier = Tier::new_mock_with_usage("cache", 1, None, 1000, 0);
+        let stay = |name: &str| crate::PlacementDecision::Stay {
+            file: std::sync::Arc::new(crate::FileInfo {
+                path: tier.path.join(name),
+                size: 100,
+                modified: UNIX_EPOCH,
+                accessed: UNIX_EPOCH,
+                owner: None,
+            }),
+            current_tier: "cache".to_string(),
+            strategy: "keep".to_string(),
+            priority: 1,
+        };
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut journal = StateJournal::in_memory();
+        journal.record_first_seen(PathBuf::from("old.mkv"), now - 48 * HOUR);
+        journal.record_first_seen(PathBuf::from("gone.mkv"), now - 48 * HOUR);
+        journal.record_move(PathBuf::from("moved.mkv"), now - HOUR);
+        journal.record_first_seen(PathBuf::from("moved.mkv"), now - 48 * HOUR);
+
+        let plan = BalancingPlan {
+            decisions: vec![stay("old.mkv"), stay("new.mkv")],
+            ..BalancingPlan::default()
+        };
+        journal.record_seen(&plan, std::slice::from_ref(&tier), now);
+
+        let since = |path| journal.time_since_first_seen(Path::new(path), now);
+        assert!(since("old.mkv").unwrap() >= 48 * HOUR);
+        assert_eq!(since("new.mkv"), Some(Duration::ZERO));
+        assert!(journal.get(Path::new("gone.mkv")).is_none());
+        // Still cooling down, so the record stays without its first-seen time
+        assert_eq!(since("moved.mkv"), None);
+        assert!(journal.get(Path::new("moved.mkv")).is_some());
+    }
+
+    #[test]
+    fn test_record_seen_partial_forgets_nothing() {
+        let tier = Tier::new_mock_with_usage("cache", 1, None, 1000, 0);
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut journal = StateJournal::in_memory();
+        journal.record_first_seen(PathBuf::from("other/old.mkv"), now - 48 * HOUR);
+
+        let plan = BalancingPlan {
+            decisions: vec![crate::PlacementDecision::Stay {
+                file: std::sync::Arc::new(crate::FileInfo {
+                    path: tier.path.join("show/new.mkv"),
+                    size: 100,
+                    modified: UNIX_EPOCH,
+                    accessed: UNIX_EPOCH,
+                    owner: None,
+                }),
+                current_tier: "cache".to_string(),
+                strategy: "keep".to_string(),
+                priority: 1,
+            }],
+            ..BalancingPlan::default()
+        };
+        journal.record_seen_partial(&plan, std::slice::from_ref(&tier), now);
+
+        let since = |path| journal.time_since_first_seen(Path::new(path), now);
+        assert_eq!(since("show/new.mkv"), Some(Duration::ZERO));
+        assert!(since("other/old.mkv").unwrap() >= 48 * HOUR);
+    }
+
+    #[test]
+    fn test_prune_moves() {
+        let now = SystemTime::now();
+        let mut journal = StateJournal::in_memory();
+        journal.record_move(PathBuf::from("old.mkv"), now - 48 * HOUR);
+        journal.record_move(PathBuf::from("new.mkv"), now - HOUR);
+
+        journal.prune_moves(24 * HOUR, now);
+
+        assert!(journal.get(Path::new("old.mkv")).is_none());
+        assert!(journal.get(Path::new("new.mkv")).is_some());
+    }
+}
@@ -1,54 +1,129 @@
 #![cfg_attr(test, allow(clippy::unwrap_used, clippy::expect_used, clippy::panic))]
 
+pub mod api;
+pub mod arr;
 pub mod balancer;
+pub mod cleanup;
 pub mod cli;
+pub mod clock;
 pub mod conditions;
 pub mod config;
 pub mod disk;
+pub mod drift;
 pub mod error;
 pub mod executor;
 pub mod factory;
 pub mod file;
 pub mod file_checker;
+pub mod hooks;
+pub mod interactive;
+pub mod inventory;
+pub mod jellyfin;
+pub mod journal;
 pub mod lock;
+pub mod metrics;
 pub mod move_blocker;
+pub mod move_policy;
 pub mod mover;
+pub mod notify;
+pub mod path_rewrite;
+pub mod plex;
+pub mod progress;
+pub mod remote;
+pub mod report;
+pub mod run_log;
+pub mod scanner;
+pub mod scrub;
 pub mod stats;
 pub mod strategy;
+pub mod stub;
+pub mod systemd;
 pub mod tautulli;
 pub mod tdarr;
 pub mod tier;
+pub mod timestamp;
+pub mod torrent;
+pub mod trash;
+pub mod watch_history;
+pub mod watcher;
 
-pub use balancer::{Balancer, BalancingPlan, PlacementDecision, PlanWarning, TierUsageProjection};
-pub use cli::{Cli, Commands, OutputFormat, default_config_path};
+pub use api::{DaemonControl, serve_api};
+pub use arr::{SonarrClient, SonarrStats};
+pub use balancer::{
+    Balancer, BalancerBuilder, BalancingPlan, ConditionCheck, DirectoryComposition,
+    DirectoryRollup, DrainProgress, FileExplanation, MoveBatch, MoveCooldown, MoveGroup, MoveQuota,
+    PlacementDecision, PlanCaps, PlanWarning, PlanningPhase, PlanningProgress,
+    PlanningProgressCallback, RemainingDirectory, RemainingFile, SidecarAffinity, StrategyBytes,
+    StrategyEvaluation, StrategyHits, StrategyStats, TierCheck, TierOffenders, TierUsageProjection,
+    WarningSeverity,
+};
+pub use cli::{Cli, Commands, ConfigAction, LockAction, OutputFormat, default_config_path};
+pub use clock::{Clock, FixedClock, SystemClock};
 pub use conditions::{
-    ActiveWindowCondition, AgeCondition, AlwaysTrueCondition, Condition, ContainsMode, Context,
-    ExtensionMode, FileExtensionCondition, FileSizeCondition, FilenameContainsCondition,
-    PathPrefixCondition, PrefixMode,
+    ActiveWindowCondition, AgeCondition, AlwaysTrueCondition, Condition, ConditionCounter,
+    ConditionMemo, ContainsMode, Context, DirectoryFileCountCondition, DirectorySizeCondition,
+    ExtensionMode, FileExtensionCondition, FileSizeCondition, FilenameContainsCondition, GlobMode,
+    NewestInDirectoryAgeCondition, OwnerCondition, OwnerKind, OwnerMode, PathGlobCondition,
+    PathPrefixCondition, PlanningView, PrefixMode, SeenAgeCondition, SimulatedTier,
+    TierUsageCondition, TorrentRatioCondition, TorrentSeedingCondition,
 };
 pub use config::{
+    ActivityCheckConfig, ActivityCheckMethod, AffinityConfig, ApiConfig, ArrConfig,
     BalancingConfig, BlockerErrorPolicyConfig, BlockerProviderConfig, BlockersConfig,
-    ConditionConfig, ConfigError, MoverConfig, MoverType, PathMappingConfig,
-    PlacementStrategyConfig, StrategyAction, TautulliConfig, TdarrBlockerConfig, TierConfig,
+    CleanupConfig, ConditionConfig, ConfigError, ConfigFormat, ConfigReloader, ConflictPolicy,
+    EpisodeMatching, EvictionPolicy, ExecutionOrder, FailOn, FsBackend, HashAlgorithm, HooksConfig,
+    LintFinding, LintSeverity, LogLevel, LoggingConfig, MemberSelection, MetricsConfig,
+    MovePolicyAction, MovePolicyConfig, MoverConfig, MoverType, NativeCopyConfig,
+    NotificationsConfig, NotifyOn, PathMappingConfig, PathRewriteConfig, PlacementStrategyConfig,
+    PlexScanConfig, PreserveConfig, RetryConfig, ScanFilterConfig, ScheduleConfig, SonarrConfig,
+    StrategyAction, TdarrBlockerConfig, TierConfig, TorrentClientConfig, TorrentClientType,
+    VerificationMode, VerifyConfig, WatchConfig, WatchHistoryConfig, WatchHistoryProviderType,
+    WebhookConfig, WebhookFormat,
 };
-pub use disk::{DiskOperations, RealDisk};
+pub use disk::{DiskOperations, FsDisk, RealDisk};
+pub use drift::{DriftReport, TierDrift};
 pub use error::{AppError, Result};
-pub use executor::{ExecutionBlocked, ExecutionError, ExecutionResult, Executor};
-pub use file::FileInfo;
+pub use executor::{
+    ErrorClass, ExecutionBlocked, ExecutionBudget, ExecutionError, ExecutionErrorKind,
+    ExecutionMove, ExecutionResult, ExecutionSkipped, Executor, InsufficientSpace,
+};
+pub use file::{FileInfo, FileOwner};
 pub use file_checker::{
-    FileChecker, FileLockChecker, LsofFileChecker, NoOpFileChecker, SmartFileChecker,
+    FileActivityChecker, FileChecker, FileLockChecker, FuserFileChecker, LsofFileChecker,
+    NoOpFileChecker, SizeStabilityChecker, SmartFileChecker,
 };
-pub use lock::TierLockGuard;
+pub use hooks::{HookEvent, HookRun, Hooks};
+pub use jellyfin::JellyfinClient;
+pub use journal::{FileRecord, StateJournal, default_state_dir};
+pub use lock::{LockInfo, LockStatus, TierLockGuard};
+pub use metrics::{MetricsRegistry, RunMetrics, serve_metrics};
 pub use move_blocker::{
     BlockDecision, BlockReason, BlockerErrorPolicy, CompositeMoveBlocker, MoveBlocker,
     MoveBlockerSnapshot, NoOpMoveBlocker, StaticMoveBlocker,
 };
-pub use mover::{DryRunMover, Mover, RsyncMover};
-pub use stats::{FileStats, GlobalStats};
+pub use move_policy::{MovePolicies, MovePolicy};
+pub use mover::{
+    Checksum, ConflictSkipped, CopyProgress, DryRunMover, HashBudget, Hasher, Mover, ProgressMover,
+    RsyncFailed, RsyncMover, TransferProgress, Verification, VerificationFailed,
+};
+pub use notify::{Notifier, RunSummary};
+pub use path_rewrite::PathRewriter;
+pub use plex::PlexClient;
+pub use progress::{InterruptedRun, MoveStatus, PlannedMove, ProgressLog};
+pub use run_log::RunLog;
+pub use scanner::{
+    FileScanner, FilteredFiles, MemoryScanner, ScanFilter, ScopedScanner, TierScanner,
+};
+pub use stats::{DirectoryStats, FileStats, GlobalStats, SeenAges};
 pub use strategy::PlacementStrategy;
 pub use tautulli::{
-    EpisodeInfo, HistoryItem, ShowProgress, TautulliClient, TautulliStats, build_progress,
-    normalize_show_name, parse_episode,
+    EpisodeInfo, HistoryItem, ShowProgress, TautulliClient, TautulliFixture, TautulliStats,
+    build_in_progress, build_progress, normalize_show_name, parse_episode,
 };
 pub use tdarr::TdarrMoveBlocker;
 pub use tier::Tier;
+pub use torrent::{
+    QbittorrentClient, Torrent, TorrentClient, TorrentFile, TorrentStats, TransmissionClient,
+};
+pub use trash::{RunRecord, Trash, TrashedMove, UndoResult};
+pub use watch_history::WatchHistoryProvider;
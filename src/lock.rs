@@ -12,58 +12,115 @@ use crate::{Tier, error::AppError};
 
 const LOCK_DIR: &str = "/tmp/tierflow-locks";
 
-#[derive(Debug, Serialize, Deserialize)]
-struct LockInfo {
-    pid: u32,
-    hostname: String,
-    started_at: SystemTime,
-    command: String,
-    tier_paths: Vec<PathBuf>,
+/// Owner metadata written into every lock file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockInfo {
+    pub pid: u32,
+    pub hostname: String,
+    pub started_at: SystemTime,
+    pub command: String,
+    /// Every tier path the owner locked, not just the one in this file
+    pub tier_paths: Vec<PathBuf>,
 }
 
-pub struct TierLockGuard {
+/// Snapshot of the lock for one tier path, as shown by `tierflow lock status`
+#[derive(Debug, Clone)]
+pub struct LockStatus {
+    pub tier: String,
+    pub tier_path: PathBuf,
+    pub lock_path: PathBuf,
+    /// Owner metadata, if the lock file could be parsed
+    pub info: Option<LockInfo>,
+    /// Whether a process currently holds the flock on this host
+    pub held: bool,
+    /// Whether the owner runs on this host (liveness can only be checked locally)
+    pub local_owner: bool,
+    /// Owner liveness; `None` when the owner is on another host
+    pub owner_alive: Option<bool>,
+}
+
+impl LockStatus {
+    /// Time since the owner acquired the lock
+    pub fn age(&self) -> Option<Duration> {
+        self.info
+            .as_ref()
+            .and_then(|info| SystemTime::now().duration_since(info.started_at).ok())
+    }
+}
+
+struct HeldLock {
     lock_path: PathBuf,
     lock_file: File,
 }
 
-impl TierLockGuard {
-    /// Generate unique lock path based on tier paths
-    fn generate_lock_path(tiers: &[Tier]) -> PathBuf {
-        // Sort tier paths for consistent hashing
-        let mut paths: Vec<_> = tiers.iter().map(|t| &t.path).collect();
-        paths.sort();
+/// Exclusive locks on every path of a tier set, one lock file per path
+///
+/// Instances whose tier sets only partly overlap still exclude each other on the
+/// shared paths. Paths are locked in sorted order and released on drop.
+pub struct TierLockGuard {
+    locks: Vec<HeldLock>,
+}
 
-        // Hash the sorted paths
+impl TierLockGuard {
+    /// Generate unique lock path for one tier path
+    fn generate_lock_path(tier_path: &Path) -> PathBuf {
         let mut hasher = DefaultHasher::new();
-        for path in paths {
-            path.hash(&mut hasher);
-        }
+        tier_path.hash(&mut hasher);
         let hash = hasher.finish();
 
         PathBuf::from(LOCK_DIR).join(format!("lock-{:016x}.lock", hash))
     }
 
-    /// Try to acquire exclusive lock for this tier configuration
+    /// Tier paths with their tier names, sorted and deduplicated
+    fn sorted_paths(tiers: &[Tier]) -> Vec<(&Path, &str)> {
+        let mut paths: Vec<_> = tiers
+            .iter()
+            .flat_map(|t| t.paths.iter().map(|p| (p.as_path(), t.name.as_str())))
+            .collect();
+        paths.sort_unstable();
+        paths.dedup_by_key(|(path, _)| *path);
+        paths
+    }
+
+    /// Try to acquire exclusive locks on every path of this tier configuration
+    ///
+    /// Fails on the first contended path, releasing the locks taken so far.
     pub fn try_lock_tiers(tiers: &[Tier]) -> Result<Self, AppError> {
         // Ensure lock directory exists
         fs::create_dir_all(LOCK_DIR).map_err(|e| AppError::LockError {
             message: format!("Failed to create lock directory {}: {}", LOCK_DIR, e),
         })?;
 
-        let lock_path = Self::generate_lock_path(tiers);
+        let paths = Self::sorted_paths(tiers);
+        let info = LockInfo {
+            pid: process::id(),
+            hostname: local_hostname(),
+            started_at: SystemTime::now(),
+            command: std::env::args().collect::<Vec<_>>().join(" "),
+            tier_paths: paths.iter().map(|(path, _)| path.to_path_buf()).collect(),
+        };
+
+        let mut guard = Self { locks: Vec::new() };
+        for (tier_path, tier_name) in paths {
+            guard
+                .locks
+                .push(Self::lock_one(tier_path, tier_name, &info)?);
+        }
+        Ok(guard)
+    }
 
-        // Collect tier paths for lock info
-        let tier_paths: Vec<PathBuf> = tiers.iter().map(|t| t.path.clone()).collect();
+    fn lock_one(tier_path: &Path, tier_name: &str, info: &LockInfo) -> Result<HeldLock, AppError> {
+        let lock_path = Self::generate_lock_path(tier_path);
 
         // Clean up stale locks from dead processes
         if lock_path.exists() {
             Self::cleanup_stale_lock(&lock_path);
         }
 
-        // Create or open lock file
+        // Create or open lock file; truncating before the flock would erase the owner's info
         let mut lock_file = OpenOptions::new()
             .create(true)
-            .truncate(true)
+            .truncate(false)
             .write(true)
             .read(true)
             .open(&lock_path)
@@ -72,54 +129,35 @@ impl TierLockGuard {
             })?;
 
         // Try to acquire exclusive lock (non-blocking)
-        if matches!(lock_file.try_lock_exclusive(), Ok(())) {
-            // Write process info to lock file
-            let info = LockInfo {
-                pid: process::id(),
-                hostname: hostname::get().map_or_else(
-                    |_| "unknown".to_string(),
-                    |h| h.to_string_lossy().to_string(),
-                ),
-                started_at: SystemTime::now(),
-                command: std::env::args().collect::<Vec<_>>().join(" "),
-                tier_paths: tier_paths.clone(),
-            };
-
-            lock_file.set_len(0).ok(); // Truncate
-            lock_file
-                .write_all(serde_json::to_string(&info)?.as_bytes())
-                .map_err(|e| AppError::LockError {
-                    message: format!("Failed to write lock info: {e}"),
-                })?;
-            lock_file.sync_all().ok();
-
-            Ok(Self {
-                lock_path,
-                lock_file,
-            })
-        } else {
+        if lock_file.try_lock_exclusive().is_err() {
             // Lock is held by another process - get info about owner
             let owner_info = Self::read_lock_info(&lock_path);
-
-            if let Some(info) = owner_info {
-                let duration = SystemTime::now()
-                    .duration_since(info.started_at)
-                    .unwrap_or_default();
-
-                return Err(AppError::TierLocked {
-                    tier: format!("Tiers: {:?}", tier_paths),
-                    owner_pid: info.pid,
-                    owner_host: info.hostname,
-                    locked_for: duration,
-                });
-            }
-            Err(AppError::TierLocked {
-                tier: format!("Tiers: {:?}", tier_paths),
-                owner_pid: 0,
-                owner_host: "unknown".to_string(),
-                locked_for: Duration::from_secs(0),
-            })
+            return Err(AppError::TierLocked {
+                tier: tier_name.to_string(),
+                path: tier_path.to_path_buf(),
+                owner_pid: owner_info.as_ref().map_or(0, |info| info.pid),
+                owner_host: owner_info
+                    .as_ref()
+                    .map_or_else(|| "unknown".to_string(), |info| info.hostname.clone()),
+                locked_for: owner_info
+                    .and_then(|info| SystemTime::now().duration_since(info.started_at).ok())
+                    .unwrap_or_default(),
+            });
         }
+
+        // Write process info to lock file
+        lock_file.set_len(0).ok(); // Truncate
+        lock_file
+            .write_all(serde_json::to_string(info)?.as_bytes())
+            .map_err(|e| AppError::LockError {
+                message: format!("Failed to write lock info: {e}"),
+            })?;
+        lock_file.sync_all().ok();
+
+        Ok(HeldLock {
+            lock_path,
+            lock_file,
+        })
     }
 
     /// Clean up stale locks from dead processes
@@ -184,13 +222,156 @@ impl TierLockGuard {
         }
     }
 
-    /// Get lock file path for display
-    pub fn lock_path(&self) -> &Path {
-        &self.lock_path
+    /// Get lock file paths for display, in locking order
+    pub fn lock_paths(&self) -> impl Iterator<Item = &Path> {
+        self.locks.iter().map(|lock| lock.lock_path.as_path())
+    }
+
+    /// Inspect the locks for this tier set without acquiring them
+    ///
+    /// Returns one entry per tier path that has a lock file, empty when none do.
+    pub fn status(tiers: &[Tier]) -> Vec<LockStatus> {
+        Self::sorted_paths(tiers)
+            .into_iter()
+            .filter_map(|(tier_path, tier_name)| {
+                let lock_path = Self::generate_lock_path(tier_path);
+                if !lock_path.exists() {
+                    return None;
+                }
+
+                let held = match OpenOptions::new().read(true).write(true).open(&lock_path) {
+                    Ok(file) => {
+                        let free = file.try_lock_exclusive().is_ok();
+                        if free {
+                            let _ = file.unlock();
+                        }
+                        !free
+                    }
+                    Err(_) => false,
+                };
+
+                let info = Self::read_lock_info(&lock_path);
+                let local_owner = info
+                    .as_ref()
+                    .is_some_and(|info| info.hostname == local_hostname());
+                let owner_alive = info
+                    .as_ref()
+                    .filter(|_| local_owner)
+                    .map(|info| Self::is_process_alive(info.pid));
+
+                Some(LockStatus {
+                    tier: tier_name.to_string(),
+                    tier_path: tier_path.to_path_buf(),
+                    lock_path,
+                    info,
+                    held,
+                    local_owner,
+                    owner_alive,
+                })
+            })
+            .collect()
+    }
+
+    /// Remove the stuck locks owned by `pid`
+    ///
+    /// Refuses when a lock is held or its owner is alive on this host.
+    /// Locks owned by other hosts (e.g. NFS-shared lock dirs) cannot be checked
+    /// for liveness and require `force` plus a lock older than `min_age`.
+    /// Locks of other owners are left alone; unreadable ones are removed with `force`.
+    pub fn break_lock(
+        tiers: &[Tier],
+        pid: u32,
+        force: bool,
+        min_age: Duration,
+    ) -> Result<Vec<PathBuf>, AppError> {
+        let refuse = |message: String| Err(AppError::LockError { message });
+
+        let statuses = Self::status(tiers);
+        if statuses.is_empty() {
+            return refuse("No lock file exists for this tier set".to_string());
+        }
+
+        let mut owned = Vec::new();
+        for status in &statuses {
+            if status.info.as_ref().is_some_and(|info| info.pid != pid) {
+                continue;
+            }
+            if status.held {
+                return refuse(format!(
+                    "Lock {} on tier '{}' is actively held by a process on this host",
+                    status.lock_path.display(),
+                    status.tier
+                ));
+            }
+
+            match &status.info {
+                Some(info) if status.owner_alive == Some(true) => {
+                    return refuse(format!(
+                        "Owner process {} is still running on this host; stop it instead",
+                        info.pid
+                    ));
+                }
+                Some(info) if !status.local_owner => {
+                    let age = status.age().unwrap_or_default();
+                    if !force {
+                        return refuse(format!(
+                            "Lock is owned by host {}, whose processes cannot be checked; use --force",
+                            info.hostname
+                        ));
+                    }
+                    if age < min_age {
+                        return refuse(format!(
+                            "Lock is only {}s old (minimum {}s before a forced break)",
+                            age.as_secs(),
+                            min_age.as_secs()
+                        ));
+                    }
+                }
+                Some(_) => {}
+                None if !force => {
+                    return refuse(format!(
+                        "Lock file {} is unreadable; use --force to remove it",
+                        status.lock_path.display()
+                    ));
+                }
+                None => {}
+            }
+            owned.push(&status.lock_path);
+        }
+
+        if owned.is_empty() {
+            let owners: Vec<_> = statuses
+                .iter()
+                .filter_map(|s| s.info.as_ref())
+                .map(|info| format!("pid {} on {}", info.pid, info.hostname))
+                .collect();
+            return refuse(format!(
+                "No lock is owned by pid {pid} (owners: {})",
+                owners.join(", ")
+            ));
+        }
+
+        let mut removed = Vec::new();
+        for lock_path in owned {
+            fs::remove_file(lock_path).map_err(|e| AppError::LockError {
+                message: format!("Failed to remove lock file {}: {}", lock_path.display(), e),
+            })?;
+            tracing::warn!("Broke lock {}", lock_path.display());
+            removed.push(lock_path.clone());
+        }
+
+        Ok(removed)
     }
 }
 
-impl Drop for TierLockGuard {
+fn local_hostname() -> String {
+    hostname::get().map_or_else(
+        |_| "unknown".to_string(),
+        |h| h.to_string_lossy().to_string(),
+    )
+}
+
+impl Drop for HeldLock {
     fn drop(&mut self) {
         // Release lock and remove lock file
         let _ = self.lock_file.unlock();
@@ -222,7 +403,7 @@ mod tests {
         let guard = TierLockGuard::try_lock_tiers(&[tier.clone()]).unwrap();
 
         // Lock file should exist in /tmp
-        let lock_path = guard.lock_path().to_path_buf();
+        let lock_path = guard.lock_paths().next().unwrap().to_path_buf();
         assert!(lock_path.exists());
         assert!(lock_path.starts_with(LOCK_DIR));
 
@@ -242,15 +423,19 @@ mod tests {
 
         let guard = TierLockGuard::try_lock_tiers(&[tier1.clone(), tier2.clone()]).unwrap();
 
-        // Single lock file should exist in /tmp
-        let lock_path = guard.lock_path().to_path_buf();
-        assert!(lock_path.exists());
-        assert!(lock_path.starts_with(LOCK_DIR));
+        // One lock file per tier path should exist in /tmp
+        let lock_paths: Vec<_> = guard.lock_paths().map(Path::to_path_buf).collect();
+        assert_eq!(lock_paths.len(), 2);
+        assert!(
+            lock_paths
+                .iter()
+                .all(|p| p.exists() && p.starts_with(LOCK_DIR))
+        );
 
         drop(guard);
 
-        // Lock file should be removed after drop
-        assert!(!lock_path.exists());
+        // Lock files should be removed after drop
+        assert!(lock_paths.iter().all(|p| !p.exists()));
 
         // Cleanup
         fs::remove_dir_all(&tier1.path).ok();
@@ -285,11 +470,11 @@ mod tests {
 
         // Lock with tier1 only
         let guard1 = TierLockGuard::try_lock_tiers(&[tier1.clone()]).unwrap();
-        let lock_path1 = guard1.lock_path().to_path_buf();
+        let lock_path1 = guard1.lock_paths().next().unwrap().to_path_buf();
 
         // Lock with tier2 only - should succeed (different config)
         let guard2 = TierLockGuard::try_lock_tiers(&[tier2.clone()]).unwrap();
-        let lock_path2 = guard2.lock_path().to_path_buf();
+        let lock_path2 = guard2.lock_paths().next().unwrap().to_path_buf();
 
         // Different tier configurations should have different lock files
         assert_ne!(lock_path1, lock_path2);
@@ -302,6 +487,38 @@ mod tests {
         fs::remove_dir_all(&tier2.path).ok();
     }
 
+    #[test]
+    fn test_overlapping_tier_sets_conflict_on_shared_tier() {
+        let shared = create_test_tier("overlap_shared");
+        let own1 = create_test_tier("overlap_own1");
+        let own2 = create_test_tier("overlap_own2");
+
+        let _guard = TierLockGuard::try_lock_tiers(&[own1.clone(), shared.clone()]).unwrap();
+
+        match TierLockGuard::try_lock_tiers(&[shared.clone(), own2.clone()]) {
+            Err(AppError::TierLocked {
+                tier,
+                path,
+                owner_pid,
+                ..
+            }) => {
+                assert_eq!(tier, shared.name);
+                assert_eq!(path, shared.path);
+                assert_eq!(owner_pid, process::id());
+            }
+            _ => panic!("Expected TierLocked error"),
+        }
+
+        // own2 sorts before shared, so it was locked first and released again
+        assert!(!TierLockGuard::generate_lock_path(&own2.path).exists());
+        drop(TierLockGuard::try_lock_tiers(&[own2.clone()]).unwrap());
+
+        // Cleanup
+        for tier in [shared, own1, own2] {
+            fs::remove_dir_all(&tier.path).ok();
+        }
+    }
+
     #[test]
     fn test_stale_lock_cleanup() {
         let tier = create_test_tier("stale");
@@ -309,7 +526,7 @@ mod tests {
         // Ensure lock directory exists
         fs::create_dir_all(LOCK_DIR).unwrap();
 
-        let lock_path = TierLockGuard::generate_lock_path(&[tier.clone()]);
+        let lock_path = TierLockGuard::generate_lock_path(&tier.path);
 
         // Create a stale lock file with fake PID
         let stale_info = LockInfo {
@@ -336,4 +553,81 @@ mod tests {
         // Cleanup
         fs::remove_dir_all(&tier.path).ok();
     }
+
+    fn write_lock_file(tier: &Tier, pid: u32, hostname: &str, age: Duration) -> PathBuf {
+        fs::create_dir_all(LOCK_DIR).unwrap();
+        let lock_path = TierLockGuard::generate_lock_path(&tier.path);
+        let info = LockInfo {
+            pid,
+            hostname: hostname.to_string(),
+            started_at: SystemTime::now() - age,
+            command: "test".to_string(),
+            tier_paths: vec![tier.path.clone()],
+        };
+        fs::write(&lock_path, serde_json::to_string(&info).unwrap()).unwrap();
+        lock_path
+    }
+
+    #[test]
+    fn test_status_without_lock() {
+        let tier = create_test_tier("status_none");
+        assert!(TierLockGuard::status(&[tier.clone()]).is_empty());
+        fs::remove_dir_all(&tier.path).ok();
+    }
+
+    #[test]
+    fn test_status_of_held_lock() {
+        let tier = create_test_tier("status_held");
+        let _guard = TierLockGuard::try_lock_tiers(&[tier.clone()]).unwrap();
+
+        let status = TierLockGuard::status(&[tier.clone()]).remove(0);
+        assert!(status.held);
+        assert!(status.local_owner);
+        assert_eq!(status.owner_alive, Some(true));
+        assert_eq!(status.info.unwrap().pid, process::id());
+
+        let result =
+            TierLockGuard::break_lock(&[tier.clone()], process::id(), true, Duration::ZERO);
+        assert!(result.is_err());
+
+        fs::remove_dir_all(&tier.path).ok();
+    }
+
+    #[test]
+    fn test_break_stale_local_lock() {
+        let tier = create_test_tier("break_local");
+        let lock_path = write_lock_file(&tier, 999999999, &local_hostname(), Duration::ZERO);
+
+        // Wrong pid is refused
+        assert!(TierLockGuard::break_lock(&[tier.clone()], 123, false, Duration::ZERO).is_err());
+        assert!(lock_path.exists());
+
+        // Dead local owner can be broken without --force
+        let broken =
+            TierLockGuard::break_lock(&[tier.clone()], 999999999, false, Duration::ZERO).unwrap();
+        assert_eq!(broken, vec![lock_path.clone()]);
+        assert!(!lock_path.exists());
+
+        fs::remove_dir_all(&tier.path).ok();
+    }
+
+    #[test]
+    fn test_break_remote_lock_requires_force_and_age() {
+        let tier = create_test_tier("break_remote");
+        let hour = Duration::from_secs(3600);
+        let lock_path = write_lock_file(&tier, 4242, "other-node", hour);
+
+        let status = TierLockGuard::status(&[tier.clone()]).remove(0);
+        assert!(!status.local_owner);
+        assert_eq!(status.owner_alive, None);
+
+        assert!(TierLockGuard::break_lock(&[tier.clone()], 4242, false, Duration::ZERO).is_err());
+        assert!(TierLockGuard::break_lock(&[tier.clone()], 4242, true, 2 * hour).is_err());
+        assert!(lock_path.exists());
+
+        TierLockGuard::break_lock(&[tier.clone()], 4242, true, hour / 2).unwrap();
+        assert!(!lock_path.exists());
+
+        fs::remove_dir_all(&tier.path).ok();
+    }
 }
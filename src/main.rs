@@ -1,36 +1,131 @@
 use clap::Parser;
+use std::collections::HashSet;
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
 use std::process;
-use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::time::Duration;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant, SystemTime};
 use tierflow::{
-    Balancer, BalancingConfig, Cli, Commands, Executor, OutputFormat, PlacementDecision,
-    TierLockGuard, factory,
+    Balancer, BalancingConfig, Cli, Commands, ConfigAction, ConfigError, ConfigFormat,
+    ConfigReloader, DaemonControl, DriftReport, Executor, HashBudget, Hooks, InterruptedRun,
+    LintFinding, LintSeverity, LockAction, MetricsRegistry, MoveCooldown, OutputFormat,
+    PathRewriter, PlacementDecision, PlanningPhase, PlanningProgress, PlanningProgressCallback,
+    ProgressLog, ProgressMover, RunLog, RunMetrics, RunRecord, RunSummary, ScopedScanner, SeenAges,
+    StateJournal, Tier, TierLockGuard, TransferProgress, Trash, WatchConfig, cleanup, factory,
+    journal::MOVE_RETENTION,
+    report::{Cell, Color, MoveSort, Palette, PlanView, StrategyMoves, Table},
+    scrub::{self, ScrubOptions, ScrubReport},
+    stub, trash,
+    watcher::{DirtyDirs, TierWatcher},
 };
-use tracing_subscriber::EnvFilter;
 use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
 
 fn main() {
     // Parse CLI arguments first to get logging settings
     let cli = Cli::parse();
 
     // Setup tracing based on CLI flags
-    match &cli.command {
-        Commands::Rebalance { verbose, quiet, .. } | Commands::Daemon { verbose, quiet, .. } => {
-            setup_tracing(*verbose, *quiet);
+    let run_log = match &cli.command {
+        Commands::Rebalance { verbose, quiet, .. }
+        | Commands::Daemon { verbose, quiet, .. }
+        | Commands::ExportInventory { verbose, quiet, .. }
+        | Commands::Explain { verbose, quiet, .. }
+        | Commands::Resume { verbose, quiet, .. }
+        | Commands::Undo { verbose, quiet, .. }
+        | Commands::PurgeTrash { verbose, quiet, .. }
+        | Commands::Cleanup { verbose, quiet, .. }
+        | Commands::Recall { verbose, quiet, .. }
+        | Commands::Scrub { verbose, quiet, .. } => setup_tracing(*verbose, *quiet),
+        Commands::Lock { .. } | Commands::Validate { .. } | Commands::Config { .. } => {
+            setup_tracing(0, false)
         }
-    }
+    };
+
+    let format_flag = cli.config_format;
+    let config_format =
+        |path: &std::path::Path| format_flag.unwrap_or_else(|| ConfigFormat::from_path(path));
 
     match cli.command {
         Commands::Rebalance {
             config,
             dry_run,
+            iterations,
+            seed,
+            tautulli_fixture,
+            max_bytes,
+            max_duration,
+            force,
+            yes_i_mean_it,
+            interactive,
+            sort,
+            limit,
+            expand,
+            no_color,
             format,
             ..
         } => {
-            if let Err(e) = run_rebalance(&config, dry_run, format) {
-                tracing::error!("Error: {e}");
-                process::exit(1);
+            let view = PlanView {
+                sort,
+                limit,
+                expand,
+                palette: Palette::detect(no_color),
+            };
+            let budget = tierflow::ExecutionBudget::new(
+                max_bytes.map(|size| size.0),
+                max_duration.map(|minutes| Duration::from_secs(minutes * 60)),
+            );
+            tracing::info!("Loading configuration from: {}", config.display());
+            let loaded = match tautulli_fixture {
+                Some(fixture) => BalancingConfig::from_file_with_history_fixture(
+                    &config,
+                    config_format(&config),
+                    fixture,
+                ),
+                None => BalancingConfig::from_file_as(&config, config_format(&config)),
+            };
+            let result = loaded
+                .map_err(Into::<Box<dyn std::error::Error>>::into)
+                .and_then(|mut config| {
+                    config.seed = seed.or(config.seed);
+                    if force {
+                        for tier in &mut config.tiers {
+                            tier.max_depth = None;
+                            tier.max_files = None;
+                        }
+                    }
+                    if yes_i_mean_it && let Some(safety) = &mut config.safety {
+                        safety.max_plan_bytes = None;
+                        safety.max_plan_percent_of_tier = None;
+                    }
+                    start_run_log(&run_log, &config);
+                    let run = run_rebalance(
+                        &config,
+                        dry_run,
+                        iterations,
+                        budget,
+                        format,
+                        &view,
+                        interactive,
+                        None,
+                        None,
+                        &install_cancel_handler(),
+                    )?;
+                    Ok(config.fail_on.exit_code(run.critical_warnings, run.errors))
+                });
+            match result {
+                Ok(None) => {}
+                Ok(Some(code)) => {
+                    tracing::error!("Run completed with problems (fail_on), exiting with {code}");
+                    process::exit(code);
+                }
+                Err(e) => {
+                    tracing::error!("Error: {e}");
+                    process::exit(1);
+                }
             }
         }
         Commands::Daemon {
@@ -40,16 +135,132 @@ fn main() {
             format,
             ..
         } => {
-            if let Err(e) = run_daemon(&config, dry_run, interval, format) {
+            if let Err(e) = run_daemon(
+                &config,
+                config_format(&config),
+                &run_log,
+                dry_run,
+                interval,
+                format,
+            ) {
+                tracing::error!("Error: {e}");
+                process::exit(1);
+            }
+        }
+        Commands::Resume {
+            config,
+            dry_run,
+            discard,
+            format,
+            ..
+        } => {
+            if let Err(e) = run_resume(
+                &config,
+                config_format(&config),
+                &run_log,
+                dry_run,
+                discard,
+                format,
+                &install_cancel_handler(),
+            ) {
+                tracing::error!("Error: {e}");
+                process::exit(1);
+            }
+        }
+        Commands::Undo { config, run_id, .. } => {
+            if let Err(e) = run_undo(&config, config_format(&config), &run_id) {
+                tracing::error!("Error: {e}");
+                process::exit(1);
+            }
+        }
+        Commands::PurgeTrash { config, .. } => {
+            if let Err(e) = run_purge_trash(&config, config_format(&config)) {
+                tracing::error!("Error: {e}");
+                process::exit(1);
+            }
+        }
+        Commands::Cleanup {
+            config, dry_run, ..
+        } => {
+            if let Err(e) = run_cleanup(&config, config_format(&config), dry_run) {
+                tracing::error!("Error: {e}");
+                process::exit(1);
+            }
+        }
+        Commands::Recall { paths, config, .. } => {
+            if let Err(e) = run_recall(&config, config_format(&config), &paths) {
+                tracing::error!("Error: {e}");
+                process::exit(1);
+            }
+        }
+        Commands::Scrub {
+            config,
+            tier,
+            sample_percent,
+            format,
+            ..
+        } => {
+            let options = ScrubOptions {
+                tier,
+                sample_percent,
+                ..ScrubOptions::default()
+            };
+            match run_scrub(&config, config_format(&config), options, format) {
+                Ok(true) => {}
+                Ok(false) => process::exit(1),
+                Err(e) => {
+                    tracing::error!("Error: {e}");
+                    process::exit(1);
+                }
+            }
+        }
+        Commands::ExportInventory { config, output, .. } => {
+            if let Err(e) = run_export_inventory(&config, config_format(&config), output.as_deref())
+            {
                 tracing::error!("Error: {e}");
                 process::exit(1);
             }
         }
+        Commands::Explain {
+            path,
+            config,
+            format,
+            ..
+        } => match run_explain(&config, config_format(&config), &path, format) {
+            Ok(true) => {}
+            Ok(false) => process::exit(1),
+            Err(e) => {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        },
+        Commands::Validate { config, format } => {
+            match run_validate(&config, config_format(&config), format) {
+                Ok(true) => {}
+                Ok(false) => process::exit(1),
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            }
+        }
+        Commands::Lock { action } => {
+            if let Err(e) = run_lock(action, format_flag) {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        }
+        Commands::Config { action } => {
+            if let Err(e) = run_config(action) {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        }
     }
 }
 
 /// Setup tracing subscriber based on verbosity level
-fn setup_tracing(verbose: u8, quiet: bool) {
+fn setup_tracing(verbose: u8, quiet: bool) -> RunLog {
     let filter = if quiet {
         EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("error"))
     } else {
@@ -63,51 +274,79 @@ fn setup_tracing(verbose: u8, quiet: bool) {
             .unwrap_or_else(|_| EnvFilter::new(format!("tierflow={level}")))
     };
 
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .with_target(false)
-        .with_span_events(FmtSpan::NONE)
-        .with_writer(std::io::stderr) // All logs to stderr
+    let run_log = RunLog::new();
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_target(false)
+                .with_span_events(FmtSpan::NONE)
+                .with_writer(std::io::stderr) // All logs to stderr
+                .with_filter(filter),
+        )
+        .with(run_log.layer())
         .init();
+    run_log
+}
+
+/// Opens the run log file for a new run, if `logging` is configured
+/// Sets the returned flag on Ctrl+C so the executor stops after the file in progress
+fn install_cancel_handler() -> Arc<AtomicBool> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&cancel);
+    if let Err(e) = ctrlc::set_handler(move || {
+        tracing::info!("Received interrupt signal, finishing the current file and stopping...");
+        flag.store(true, Ordering::SeqCst);
+    }) {
+        tracing::warn!("Failed to set Ctrl-C handler: {}", e);
+    }
+    cancel
+}
+
+fn start_run_log(run_log: &RunLog, config: &BalancingConfig) {
+    if let Err(e) = run_log.start_run(config.logging.as_ref()) {
+        tracing::warn!("Failed to open the run log file: {e}");
+    }
 }
 
+/// Plans and executes, repeating up to `iterations` times (default from the config)
+/// until a plan has no moves
+#[allow(clippy::too_many_arguments)]
 fn run_rebalance(
-    config_path: &std::path::Path,
+    config: &BalancingConfig,
     dry_run: bool,
+    iterations: Option<u32>,
+    mut budget: tierflow::ExecutionBudget,
     format: OutputFormat,
-) -> Result<(), Box<dyn std::error::Error>> {
-    tracing::info!("Loading configuration from: {}", config_path.display());
-
-    // Load configuration
-    let config = BalancingConfig::from_file(config_path)?;
+    view: &PlanView,
+    interactive: bool,
+    scope: Option<&[PathBuf]>,
+    control: Option<&DaemonControl>,
+    cancel: &AtomicBool,
+) -> Result<RunMetrics, Box<dyn std::error::Error>> {
+    let started = Instant::now();
 
     // Extract config fields before consuming config
-    let tautulli_config = config.tautulli.clone();
     let blockers_config = config.blockers.clone();
     let mover_config = config.mover.clone();
+    let verify_config = config.verify.clone();
+    let activity_check_config = config.activity_check.clone();
+    let notifier = factory::build_notifier(config.notifications.as_ref())?;
+    let state_dir = config.state_dir();
+    let cooldown_window = cooldown_window(config);
+    let max_drift = max_drift_percent(config);
 
     // Convert configuration to runtime objects
-    let tiers: Vec<_> = config
-        .tiers
-        .into_iter()
-        .map(tierflow::TierConfig::into_tier)
-        .collect::<Result<_, _>>()?;
-
-    let strategies: Vec<_> = config
-        .strategies
-        .into_iter()
-        .map(tierflow::factory::build_strategy)
-        .collect();
+    let tiers = config.build_tiers()?;
 
     tracing::info!(
         "Configuration loaded: {} tiers, {} strategies{}",
         tiers.len(),
-        strategies.len(),
-        if tautulli_config.is_some() {
-            " (Tautulli enabled)"
-        } else {
-            ""
-        }
+        config.strategies.len(),
+        config
+            .watch_history
+            .as_ref()
+            .map(|w| format!(" ({} enabled)", w.provider))
+            .unwrap_or_default()
     );
     if let Some(blockers) = &blockers_config
         && !blockers.providers.is_empty()
@@ -126,12 +365,14 @@ fn run_rebalance(
         }
         Err(tierflow::AppError::TierLocked {
             tier,
+            path,
             owner_pid,
             owner_host,
             locked_for,
         }) => {
             eprintln!(
-                "Error: Tier '{tier}' is locked by process {owner_pid} on {owner_host} (running for {locked_for:?})"
+                "Error: Tier '{tier}' ({}) is locked by process {owner_pid} on {owner_host} (running for {locked_for:?})",
+                path.display()
             );
             eprintln!("Another instance is already working with this tier. Exiting.");
             process::exit(1);
@@ -142,81 +383,894 @@ fn run_rebalance(
         }
     };
 
-    // Create Balancer
-    let balancer = Balancer::new(tiers.clone(), strategies, tautulli_config);
-
-    // Plan rebalance
-    tracing::info!("Planning rebalance...");
-    let plan = balancer.plan_rebalance();
-
-    // Output plan to stderr (for human consumption)
-    if !matches!(format, OutputFormat::Json | OutputFormat::Yaml) {
-        print_plan(&plan);
+    for tier in &tiers {
+        tier.check_scan_limits()?;
     }
 
-    // Execute plan
-    tracing::info!("Executing plan...");
+    let mut journal = load_journal(&state_dir);
+    if let Some(interrupted) = recover_interrupted_run(&state_dir, &tiers, &mut journal, dry_run)
+        && !dry_run
+    {
+        tracing::warn!(
+            "Replanning instead of resuming ({} moves were pending)",
+            interrupted.pending().count()
+        );
+        InterruptedRun::discard(&state_dir)?;
+    }
+    if !dry_run {
+        purge_expired_trash(config, &tiers, &state_dir);
+        if let Some(cleanup) = &config.cleanup {
+            let report = clean_artifacts(&tiers, &state_dir, cleanup.retention(), false);
+            if !report.removed.is_empty() {
+                tracing::info!(
+                    "Removed {} leftover .partial/.backup file(s)",
+                    report.removed.len()
+                );
+            }
+        }
+    }
 
     // Use factory functions for consistent initialization
-    let mover = factory::build_mover(Some(&mover_config), dry_run);
-    let file_checker = factory::build_file_checker();
+    let trash = new_trash(config, &tiers);
+    let mover = factory::build_mover(
+        Some(&mover_config),
+        verify_config.as_ref(),
+        trash.clone(),
+        dry_run,
+    );
+    let file_checker = factory::build_file_checker(activity_check_config.as_ref());
     let move_blocker = factory::build_move_blocker(blockers_config.as_ref())?;
-    let result = Executor::execute_plan(
+    let hooks = build_hooks(config, dry_run);
+    let text_output = matches!(format, OutputFormat::Text);
+
+    let mut max_iterations = iterations.unwrap_or_else(|| config.iterations());
+    if dry_run && max_iterations > 1 {
+        tracing::info!("Dry run: nothing changes between iterations, planning once");
+        max_iterations = 1;
+    }
+    let mut totals: Option<(RunMetrics, RunSummary)> = None;
+
+    for iteration in 1..=max_iterations {
+        let iteration_started = Instant::now();
+        if max_iterations > 1 {
+            tracing::info!("Iteration {iteration} of up to {max_iterations}");
+        }
+
+        // Plan rebalance
+        tracing::info!("Planning rebalance...");
+        tierflow::systemd::status("Scanning tiers and planning");
+        let mut balancer = build_balancer(config, tiers.clone(), &journal)?
+            .with_progress(planning_progress(format));
+        if let Some(dirs) = scope {
+            balancer = balancer.with_scanner(Arc::new(ScopedScanner::new(dirs.to_vec())));
+        }
+        let mut plan = balancer.plan_rebalance();
+        Executor::order_for_execution(&mut plan, config.mover.execution_order);
+        if iteration > 1 && plan.move_count() == 0 {
+            tracing::info!("Converged after {} iteration(s)", iteration - 1);
+            break;
+        }
+        if let Some(control) = control {
+            control.set_plan(&plan);
+        }
+
+        // Output plan to stderr (for human consumption)
+        if text_output {
+            if max_iterations > 1 {
+                eprintln!("\n=== Iteration {iteration} ===");
+            }
+            print_plan(&plan, &tiers, view);
+        }
+        check_strategy_ties(config, &plan)?;
+        if interactive && plan.move_count() > 0 {
+            let skipped = tierflow::interactive::review_plan(&mut plan, &tiers)?;
+            tracing::info!("Skipping {skipped} move(s), {} approved", plan.move_count());
+        }
+        check_plan_caps(config, &plan, &tiers, dry_run)?;
+
+        // Execute plan
+        tracing::info!("Executing plan...");
+        tierflow::systemd::status(&format!("Executing {} move(s)", plan.move_count()));
+        let progress_mover = (!dry_run)
+            .then(|| copy_progress_mover(mover.as_ref(), &plan, format))
+            .flatten();
+        let result = execute_with_progress(
+            &plan,
+            progress_mover
+                .as_ref()
+                .map_or(mover.as_ref(), |m| m as &dyn tierflow::Mover),
+            &tiers,
+            file_checker.as_ref(),
+            move_blocker.as_ref(),
+            &hooks,
+            (!dry_run).then_some(state_dir.as_path()),
+            budget,
+            config.mover.retry,
+            cancel,
+        );
+        budget = budget.spend(result.bytes_moved);
+
+        let mut undo_run = None;
+        if !dry_run {
+            if scope.is_some() {
+                journal.record_seen_partial(&plan, &tiers, SystemTime::now());
+            } else {
+                journal.record_seen(&plan, &tiers, SystemTime::now());
+            }
+            update_journal(
+                &mut journal,
+                &plan,
+                &result,
+                &tiers,
+                cooldown_window,
+                max_drift,
+            );
+            undo_run = record_trashed_run(trash.as_ref(), &result, &state_dir);
+            publish_path_rewrites(config, &result);
+        }
+
+        print_result(&plan, &result, dry_run, undo_run.as_deref(), format)?;
+
+        let metrics = RunMetrics::from_run(&plan, &result, iteration_started.elapsed());
+        let summary = RunSummary::from_run(&plan, &result, dry_run);
+        match &mut totals {
+            Some((total_metrics, total_summary)) => {
+                total_metrics.merge(metrics);
+                total_summary.merge(summary);
+            }
+            None => totals = Some((metrics, summary)),
+        }
+
+        if cancel.load(Ordering::SeqCst) || result.files_deferred > 0 {
+            break;
+        }
+        // Replanning an unchanged disk gives the same plan
+        if result.files_moved == 0 {
+            if iteration < max_iterations {
+                tracing::info!("Nothing moved in iteration {iteration}, stopping");
+            }
+            break;
+        }
+        if iteration == max_iterations && max_iterations > 1 {
+            tracing::warn!(
+                "Stopped after {max_iterations} iterations; the next run may still find moves"
+            );
+        }
+    }
+
+    let (mut metrics, summary) = totals.unwrap_or_default();
+    if let Some(notifier) = &notifier {
+        notifier.notify(&summary);
+    }
+
+    metrics.duration = started.elapsed();
+    Ok(metrics)
+}
+
+/// Refuses plans over the `safety` caps; a dry run only warns
+fn check_plan_caps(
+    config: &BalancingConfig,
+    plan: &tierflow::BalancingPlan,
+    tiers: &[Tier],
+    dry_run: bool,
+) -> tierflow::Result<()> {
+    let Some(safety) = &config.safety else {
+        return Ok(());
+    };
+    match safety.plan_caps().check(plan, tiers) {
+        Err(e) if dry_run => {
+            tracing::warn!("{e}");
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+/// Fails the run on ambiguous strategy ties when `strict_strategy_ties` is set
+fn check_strategy_ties(
+    config: &BalancingConfig,
+    plan: &tierflow::BalancingPlan,
+) -> tierflow::Result<()> {
+    if !config.strict_strategy_ties {
+        return Ok(());
+    }
+    let ties: Vec<_> = plan
+        .warnings
+        .iter()
+        .filter(|w| matches!(w, tierflow::PlanWarning::AmbiguousStrategy { .. }))
+        .map(ToString::to_string)
+        .collect();
+    if ties.is_empty() {
+        Ok(())
+    } else {
+        Err(tierflow::AppError::Config(format!(
+            "ambiguous strategy ties with strict_strategy_ties enabled:\n  {}",
+            ties.join("\n  ")
+        )))
+    }
+}
+
+/// Cooldown window from `min_hours_between_moves`, if enabled
+fn cooldown_window(config: &BalancingConfig) -> Option<Duration> {
+    config
+        .min_hours_between_moves
+        .filter(|&hours| hours > 0)
+        .map(|hours| Duration::from_secs(hours * 3600))
+}
+
+fn build_balancer(
+    config: &BalancingConfig,
+    tiers: Vec<Tier>,
+    journal: &StateJournal,
+) -> Result<Balancer, ConfigError> {
+    let strategies = config
+        .strategies
+        .iter()
+        .cloned()
+        .map(|strategy| factory::build_strategy(strategy, &config.extension_groups))
+        .collect::<Result<_, _>>()?;
+
+    let now = SystemTime::now();
+    let mut balancer = Balancer::new(tiers, strategies, config.watch_history.clone())
+        .with_planned_at(now)
+        .with_seed(config.seed)
+        .with_move_policies(factory::build_move_policies(&config.move_policies))
+        .with_run_quota(tierflow::MoveQuota::new(
+            config.max_moves_per_run,
+            config.max_bytes_per_run,
+        ));
+    if let Some(sonarr) = config.sonarr() {
+        balancer = balancer.with_sonarr(sonarr.clone(), config.sonarr_lookahead_days());
+    }
+    if let Some(torrent_client) = &config.torrent_client {
+        balancer = balancer.with_torrent_client(torrent_client.clone());
+    }
+    balancer = balancer.with_seen_ages(SeenAges::from_journal(journal, now));
+    if let Some(window) = cooldown_window(config) {
+        balancer = balancer.with_move_cooldown(MoveCooldown::from_journal(journal, window, now));
+    }
+    if let Some(affinity) = &config.affinity {
+        balancer = balancer
+            .with_sidecar_affinity(tierflow::SidecarAffinity::new(&affinity.sidecar_extensions));
+    }
+    Ok(balancer)
+}
+
+fn run_resume(
+    config_path: &std::path::Path,
+    config_format: ConfigFormat,
+    run_log: &RunLog,
+    dry_run: bool,
+    discard: bool,
+    format: OutputFormat,
+    cancel: &AtomicBool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = BalancingConfig::from_file_as(config_path, config_format)?;
+    start_run_log(run_log, &config);
+    let state_dir = config.state_dir();
+    let cooldown_window = cooldown_window(&config);
+
+    let tiers = config.build_tiers()?;
+    let _lock_guard = TierLockGuard::try_lock_tiers(&tiers)?;
+
+    let mut journal = load_journal(&state_dir);
+    let Some(interrupted) = recover_interrupted_run(&state_dir, &tiers, &mut journal, dry_run)
+    else {
+        eprintln!("No interrupted run to resume");
+        return Ok(());
+    };
+
+    if discard {
+        if !dry_run {
+            InterruptedRun::discard(&state_dir)?;
+        }
+        eprintln!("Discarded interrupted plan");
+        return Ok(());
+    }
+
+    let plan = interrupted.remaining_plan();
+    eprintln!(
+        "Resuming interrupted run: {} of {} moves remaining",
+        plan.decisions.len(),
+        interrupted.moves.len()
+    );
+
+    let trash = new_trash(&config, &tiers);
+    let mover = factory::build_mover(
+        Some(&config.mover),
+        config.verify.as_ref(),
+        trash.clone(),
+        dry_run,
+    );
+    let file_checker = factory::build_file_checker(config.activity_check.as_ref());
+    let move_blocker = factory::build_move_blocker(config.blockers.as_ref())?;
+    let hooks = build_hooks(&config, dry_run);
+    let progress_mover = (!dry_run)
+        .then(|| copy_progress_mover(mover.as_ref(), &plan, format))
+        .flatten();
+    let result = execute_with_progress(
         &plan,
-        mover.as_ref(),
+        progress_mover
+            .as_ref()
+            .map_or(mover.as_ref(), |m| m as &dyn tierflow::Mover),
         &tiers,
         file_checker.as_ref(),
         move_blocker.as_ref(),
+        &hooks,
+        (!dry_run).then_some(state_dir.as_path()),
+        tierflow::ExecutionBudget::default(),
+        config.mover.retry,
+        cancel,
+    );
+
+    let mut undo_run = None;
+    if !dry_run {
+        let max_drift = max_drift_percent(&config);
+        update_journal(
+            &mut journal,
+            &plan,
+            &result,
+            &tiers,
+            cooldown_window,
+            max_drift,
+        );
+        undo_run = record_trashed_run(trash.as_ref(), &result, &state_dir);
+        publish_path_rewrites(&config, &result);
+    }
+
+    print_result(&plan, &result, dry_run, undo_run.as_deref(), format)
+}
+
+/// Trash for a new run, when `safety.trash_dir` is configured
+fn new_trash(config: &BalancingConfig, tiers: &[Tier]) -> Option<Trash> {
+    config
+        .trash_dir()
+        .map(|_| Trash::new(trash::new_run_id(SystemTime::now()), tiers))
+}
+
+/// Saves what the run trashed so it can be undone; returns the run id if anything was
+fn record_trashed_run(
+    trash: Option<&Trash>,
+    result: &tierflow::ExecutionResult,
+    state_dir: &std::path::Path,
+) -> Option<String> {
+    let record = RunRecord::from_execution(trash?, result);
+    if record.moves.is_empty() {
+        return None;
+    }
+    match record.save(state_dir) {
+        Ok(()) => Some(record.run_id),
+        Err(e) => {
+            tracing::warn!("Failed to save run record, this run cannot be undone: {e}");
+            None
+        }
+    }
+}
+
+/// Tells `path_mapping` targets where the executed moves went
+fn publish_path_rewrites(config: &BalancingConfig, result: &tierflow::ExecutionResult) {
+    let Some(path_mapping) = &config.path_mapping else {
+        return;
+    };
+    match PathRewriter::from_config(path_mapping) {
+        Ok(rewriter) => rewriter.publish(&result.moved),
+        Err(e) => tracing::warn!("Failed to set up path mapping: {e}"),
+    }
+}
+
+/// Maintenance step: drops trashed files past `safety.retention_hours`
+fn purge_expired_trash(config: &BalancingConfig, tiers: &[Tier], state_dir: &std::path::Path) {
+    if let Some(safety) = &config.safety
+        && safety.trash_dir.is_some()
+    {
+        let purged = trash::purge_expired(state_dir, tiers, safety.retention(), SystemTime::now());
+        if purged > 0 {
+            tracing::info!("Purged {purged} expired trash run(s)");
+        }
+    }
+}
+
+/// Restores the sources a run moved into the trash
+fn run_undo(
+    config_path: &std::path::Path,
+    config_format: ConfigFormat,
+    run_id: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = BalancingConfig::parse_file_as(config_path, config_format)?;
+    let tiers = config.build_tiers()?;
+    let _lock_guard = TierLockGuard::try_lock_tiers(&tiers)?;
+    let state_dir = config.state_dir();
+
+    let Some(record) = RunRecord::load(&state_dir, run_id)? else {
+        return Err(format!("No trashed files recorded for run {run_id}").into());
+    };
+
+    let undone = record.undo();
+    eprintln!(
+        "Restored {} of {} files from run {run_id}",
+        undone.restored.len(),
+        record.moves.len()
+    );
+    for (file, reason) in &undone.failed {
+        eprintln!("  Not restored: {} ({reason})", file.display());
+    }
+
+    if undone.failed.is_empty() {
+        RunRecord::remove(&state_dir, run_id)?;
+        Ok(())
+    } else {
+        Err(format!("{} files could not be restored", undone.failed.len()).into())
+    }
+}
+
+fn run_purge_trash(
+    config_path: &std::path::Path,
+    config_format: ConfigFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = BalancingConfig::parse_file_as(config_path, config_format)?;
+    let Some(safety) = config.safety.as_ref().filter(|s| s.trash_dir.is_some()) else {
+        eprintln!("No trash configured (safety.trash_dir)");
+        return Ok(());
+    };
+    let tiers = config.build_tiers()?;
+    let _lock_guard = TierLockGuard::try_lock_tiers(&tiers)?;
+
+    let purged = trash::purge_expired(
+        &config.state_dir(),
+        &tiers,
+        safety.retention(),
+        SystemTime::now(),
+    );
+    eprintln!("Purged {purged} expired trash run(s)");
+    Ok(())
+}
+
+/// Removes `.partial` and `.backup-*` files older than the cleanup retention
+fn run_cleanup(
+    config_path: &std::path::Path,
+    config_format: ConfigFormat,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = BalancingConfig::parse_file_as(config_path, config_format)?;
+    let tiers = config.build_tiers()?;
+    let _lock_guard = TierLockGuard::try_lock_tiers(&tiers)?;
+
+    let retention = config.cleanup.clone().unwrap_or_default().retention();
+    let report = clean_artifacts(&tiers, &config.state_dir(), retention, dry_run);
+    let verb = if dry_run { "Would remove" } else { "Removed" };
+    eprintln!(
+        "{verb} {} leftover file(s), {:.2} GB",
+        report.removed.len(),
+        report.bytes_removed() as f64 / 1_000_000_000.0
     );
+    if report.kept_recent > 0 {
+        eprintln!("  Kept (newer than retention): {}", report.kept_recent);
+    }
+    if report.kept_in_use > 0 {
+        eprintln!(
+            "  Kept (interrupted run can resume them): {}",
+            report.kept_in_use
+        );
+    }
+    for (path, reason) in &report.failed {
+        eprintln!("  Not removed: {} ({reason})", path.display());
+    }
+    if report.failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{} files could not be removed", report.failed.len()).into())
+    }
+}
+
+/// Swaps stubs for their real files while holding the tier locks, so a run
+/// cannot move or remove a file halfway through its recall
+fn run_recall(
+    config_path: &std::path::Path,
+    config_format: ConfigFormat,
+    paths: &[PathBuf],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = BalancingConfig::parse_file_as(config_path, config_format)?;
+    let tiers = config.build_tiers()?;
+    let _lock_guard = TierLockGuard::try_lock_tiers(&tiers)?;
+
+    let mut failed = 0;
+    for path in paths {
+        match stub::recall(path) {
+            Ok(recalled) => eprintln!(
+                "Recalled {} ({:.2} GB) from tier '{}'",
+                path.display(),
+                recalled.size as f64 / 1_000_000_000.0,
+                recalled.tier
+            ),
+            Err(e) => {
+                eprintln!("  Not recalled: {} ({e})", path.display());
+                failed += 1;
+            }
+        }
+    }
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(format!("{failed} files could not be recalled").into())
+    }
+}
+
+/// Cleanup pass that keeps the partial copies of an interrupted run's pending moves
+fn clean_artifacts(
+    tiers: &[Tier],
+    state_dir: &std::path::Path,
+    retention: Duration,
+    dry_run: bool,
+) -> cleanup::CleanupReport {
+    let in_use: HashSet<PathBuf> = match InterruptedRun::load(state_dir) {
+        Ok(interrupted) => interrupted
+            .map(|run| run.pending_partials(tiers).into_iter().collect())
+            .unwrap_or_default(),
+        Err(e) => {
+            tracing::warn!("Failed to read execution progress: {e}");
+            HashSet::new()
+        }
+    };
+    cleanup::clean_artifacts(tiers, &in_use, retention, SystemTime::now(), dry_run)
+}
+
+/// Scrubs the files of `config`'s tiers; returns false if any file failed to verify
+fn run_scrub(
+    config_path: &std::path::Path,
+    config_format: ConfigFormat,
+    options: ScrubOptions,
+    format: OutputFormat,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let config = BalancingConfig::parse_file_as(config_path, config_format)?;
+    let report = scrub_tiers(&config, options, &install_cancel_handler())?;
 
-    // Output result to stdout based on format
     match format {
-        OutputFormat::Json => {
-            let output = serde_json::json!({
-                "files_moved": result.files_moved,
-                "files_stayed": result.files_stayed,
-                "files_blocked": result.files_blocked,
-                "bytes_moved": result.bytes_moved,
-                "dry_run": dry_run,
-                "blocked": result.blocked.iter().map(|e| serde_json::json!({
-                    "file": e.file.display().to_string(),
-                    "from_tier": &e.from_tier,
-                    "to_tier": &e.to_tier,
-                    "provider": &e.provider,
-                    "reason": &e.reason,
-                })).collect::<Vec<_>>(),
-                "errors": result.errors.iter().map(|e| serde_json::json!({
-                    "file": e.file.display().to_string(),
-                    "from_tier": &e.from_tier,
-                    "to_tier": &e.to_tier,
-                    "error": &e.error,
-                })).collect::<Vec<_>>(),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&report)?),
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::Tsv => {
+            println!(
+                "Verified {} file(s), {:.2} GB ({} with a recorded checksum)",
+                report.verified,
+                report.bytes_verified as f64 / 1_000_000_000.0,
+                report.candidates
+            );
+            for mismatch in &report.mismatches {
+                println!(
+                    "  MISMATCH {} [{}]: expected {}, got {}",
+                    mismatch.path.display(),
+                    mismatch.tier,
+                    mismatch.expected,
+                    mismatch.actual
+                );
+            }
+            for (path, reason) in &report.errors {
+                println!("  Unreadable {}: {reason}", path.display());
+            }
+            for path in &report.missing {
+                println!("  Missing {}", path.display());
+            }
+            if report.cancelled {
+                println!("Cancelled before every sampled file was verified");
+            }
+        }
+    }
+    Ok(report.is_clean())
+}
+
+/// Rereads files with a journal checksum, holding the tier locks
+///
+/// Hashing uses the `verify` throughput and thread limits.
+fn scrub_tiers(
+    config: &BalancingConfig,
+    mut options: ScrubOptions,
+    cancel: &AtomicBool,
+) -> Result<ScrubReport, Box<dyn std::error::Error>> {
+    let tiers = config.build_tiers()?;
+    if let Some(name) = &options.tier
+        && !tiers.iter().any(|tier| tier.name == *name)
+    {
+        return Err(format!("Unknown tier: {name}").into());
+    }
+    let _lock_guard = TierLockGuard::try_lock_tiers(&tiers)?;
+    let journal = load_journal(&config.state_dir());
+
+    let verify = config.verify.clone().unwrap_or_default();
+    let budget = HashBudget::new(verify.max_mbps, verify.max_threads);
+    options.threads_per_file = verify.threads_per_file.unwrap_or(1);
+    options.salt = tierflow::timestamp::unix_now();
+    Ok(scrub::scrub(&journal, &tiers, &options, &budget, cancel))
+}
+
+fn load_journal(state_dir: &std::path::Path) -> StateJournal {
+    StateJournal::load(state_dir).unwrap_or_else(|e| {
+        tracing::warn!(
+            "Failed to load state journal from {}: {e}. Continuing without move history.",
+            state_dir.display()
+        );
+        StateJournal::in_memory()
+    })
+}
+
+/// Cleans up after an interrupted run and records the moves it completed
+fn recover_interrupted_run(
+    state_dir: &std::path::Path,
+    tiers: &[tierflow::Tier],
+    journal: &mut StateJournal,
+    dry_run: bool,
+) -> Option<InterruptedRun> {
+    let interrupted = match InterruptedRun::load(state_dir) {
+        Ok(interrupted) => interrupted?,
+        Err(e) => {
+            tracing::warn!("Failed to read execution progress: {e}");
+            return None;
+        }
+    };
+
+    tracing::warn!(
+        "Previous run was interrupted: {} of {} moves completed",
+        interrupted.completed().count(),
+        interrupted.moves.len()
+    );
+    if dry_run {
+        return Some(interrupted);
+    }
+
+    let removed = interrupted.clean_partials(tiers);
+    if removed > 0 {
+        tracing::info!("Removed {removed} orphaned partial copies");
+    }
+
+    let now = SystemTime::now();
+    for planned in interrupted.completed() {
+        journal.record_tier_move(&planned.file, &planned.from_tier, tiers, now);
+    }
+    if let Err(e) = journal.save() {
+        tracing::warn!("Failed to save state journal: {e}");
+    }
+
+    Some(interrupted)
+}
+
+/// Wraps `mover` to show copy progress: a live line when stderr is a terminal,
+/// or one JSON event per line on stderr with `--format json`
+fn copy_progress_mover<'a>(
+    mover: &'a dyn tierflow::Mover,
+    plan: &tierflow::BalancingPlan,
+    format: OutputFormat,
+) -> Option<ProgressMover<'a>> {
+    let on_progress: Box<dyn FnMut(&TransferProgress)> = match format {
+        OutputFormat::Json => Box::new(|progress: &TransferProgress| {
+            let event = serde_json::json!({
+                "event": "copy_progress",
+                "file": progress.file.display().to_string(),
+                "bytes_copied": progress.file_progress.bytes_copied,
+                "total_bytes": progress.file_progress.total_bytes,
+                "bytes_per_sec": progress.file_progress.bytes_per_sec,
+                "eta_secs": progress.file_progress.eta().map(|eta| eta.as_secs()),
+                "run_bytes_copied": progress.run_progress.bytes_copied,
+                "run_total_bytes": progress.run_progress.total_bytes,
+                "run_eta_secs": progress.run_progress.eta().map(|eta| eta.as_secs()),
             });
+            eprintln!("{event}");
+        }),
+        OutputFormat::Text if std::io::stderr().is_terminal() => {
+            Box::new(|progress: &TransferProgress| {
+                let file = progress.file_progress;
+                let run = progress.run_progress;
+                eprint!(
+                    "\r\x1b[K  {} {:>3}% {:.1} MB/s ETA {} | run {:>3}% ETA {}",
+                    progress
+                        .file
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy(),
+                    file.percent(),
+                    file.bytes_per_sec as f64 / 1_000_000.0,
+                    format_eta(file.eta()),
+                    run.percent(),
+                    format_eta(run.eta()),
+                );
+                if file.percent() == 100 {
+                    eprintln!();
+                }
+            })
+        }
+        OutputFormat::Text | OutputFormat::Yaml | OutputFormat::Csv | OutputFormat::Tsv => {
+            return None;
+        }
+    };
+
+    Some(ProgressMover::new(mover, plan.move_bytes(), on_progress))
+}
+
+/// Time between structured planning progress log events
+const PLANNING_LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Live planning progress on a terminal, plus periodic `Planning progress` log events
+fn planning_progress(format: OutputFormat) -> PlanningProgressCallback {
+    let live = matches!(format, OutputFormat::Text) && std::io::stderr().is_terminal();
+    let last_log = Mutex::new(Instant::now());
+    Arc::new(move |progress: &PlanningProgress| {
+        if live {
+            eprint!("\r\x1b[K");
+        }
+        let mut last_log = last_log.lock().unwrap_or_else(PoisonError::into_inner);
+        if progress.phase == PlanningPhase::Done || last_log.elapsed() >= PLANNING_LOG_INTERVAL {
+            *last_log = Instant::now();
+            tracing::info!(
+                phase = progress.phase.as_str(),
+                files_scanned = progress.files_scanned,
+                files_planned = progress.files_planned,
+                evictions = progress.evictions,
+                "Planning progress"
+            );
+        }
+        if live && progress.phase != PlanningPhase::Done {
+            let detail = match progress.phase {
+                PlanningPhase::Placement => format!(
+                    "{}/{} files ({}%)",
+                    progress.files_planned,
+                    progress.files_scanned,
+                    progress.percent()
+                ),
+                PlanningPhase::Promotion => format!("{} evictions", progress.evictions),
+                _ => format!("{} files", progress.files_scanned),
+            };
+            eprint!("  Planning [{}] {detail}", progress.phase.as_str());
+        }
+    })
+}
+
+fn format_eta(eta: Option<Duration>) -> String {
+    eta.map_or_else(
+        || "--:--".to_string(),
+        |eta| {
+            let secs = eta.as_secs();
+            format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+        },
+    )
+}
+
+/// Configured hooks; none run in dry-run mode
+fn build_hooks(config: &BalancingConfig, dry_run: bool) -> Hooks {
+    match &config.hooks {
+        Some(hooks) if !dry_run => Hooks::from_config(hooks),
+        _ => Hooks::default(),
+    }
+}
+
+/// Executes `plan`, persisting per-move progress to `state_dir` (none in dry-run mode)
+#[allow(clippy::too_many_arguments)]
+fn execute_with_progress(
+    plan: &tierflow::BalancingPlan,
+    mover: &dyn tierflow::Mover,
+    tiers: &[tierflow::Tier],
+    file_checker: &dyn tierflow::FileChecker,
+    move_blocker: &dyn tierflow::move_blocker::MoveBlocker,
+    hooks: &Hooks,
+    state_dir: Option<&std::path::Path>,
+    budget: tierflow::ExecutionBudget,
+    retry: tierflow::RetryConfig,
+    cancel: &AtomicBool,
+) -> tierflow::ExecutionResult {
+    let mut progress = state_dir.and_then(|state_dir| {
+        ProgressLog::start(state_dir, plan)
+            .inspect_err(|e| tracing::warn!("Failed to persist execution progress: {e}"))
+            .ok()
+    });
+
+    let result = Executor::execute_plan_with_hooks(
+        plan,
+        mover,
+        tiers,
+        file_checker,
+        move_blocker,
+        progress.as_mut(),
+        hooks,
+        budget,
+        retry,
+        cancel,
+    );
+    // Keep the progress of a cancelled or deferred run so `resume` can pick up the rest
+    if result.files_cancelled == 0
+        && result.files_deferred == 0
+        && let Some(progress) = progress
+        && let Err(e) = progress.finish()
+    {
+        tracing::warn!("Failed to remove execution progress: {e}");
+    }
+    result
+}
+
+fn update_journal(
+    journal: &mut StateJournal,
+    plan: &tierflow::BalancingPlan,
+    result: &tierflow::ExecutionResult,
+    tiers: &[tierflow::Tier],
+    cooldown_window: Option<Duration>,
+    max_drift_percent: u64,
+) {
+    let now = SystemTime::now();
+    journal.record_execution(result, tiers, now);
+    journal.prune_moves(
+        cooldown_window.map_or(MOVE_RETENTION, |w| w.max(MOVE_RETENTION)),
+        now,
+    );
+    if result.files_moved + result.files_copied > 0 {
+        journal.record_drift(report_drift(plan, result, tiers, max_drift_percent, now));
+    }
+    if let Err(e) = journal.save() {
+        tracing::warn!("Failed to save state journal: {e}");
+    }
+}
+
+/// Drift between projected and measured usage that makes a run warn
+fn max_drift_percent(config: &BalancingConfig) -> u64 {
+    config
+        .safety
+        .as_ref()
+        .map_or(tierflow::drift::DEFAULT_MAX_DRIFT_PERCENT, |safety| {
+            safety.max_drift_percent()
+        })
+}
+
+/// Compares the plan's projections with the tiers after execution, warning about large gaps
+fn report_drift(
+    plan: &tierflow::BalancingPlan,
+    result: &tierflow::ExecutionResult,
+    tiers: &[tierflow::Tier],
+    max_drift_percent: u64,
+    now: SystemTime,
+) -> DriftReport {
+    let report = DriftReport::reconcile(plan, result, tiers, now);
+    for drift in &report.tiers {
+        tracing::debug!(
+            "Projection drift on '{}': expected {:.2} GB used, measured {:.2} GB ({:.2}% of capacity)",
+            drift.tier,
+            drift.expected_used as f64 / 1_000_000_000.0,
+            drift.actual_used as f64 / 1_000_000_000.0,
+            drift.drift_percent()
+        );
+    }
+    for drift in report.exceeding(max_drift_percent) {
+        tracing::warn!(
+            "Tier '{}' is {:+.2} GB off the plan's projection after the run ({:.2}% of capacity, \
+             over {max_drift_percent}%); other writers or the filesystem make max_usage_percent less reliable",
+            drift.tier,
+            drift.drift_bytes() as f64 / 1_000_000_000.0,
+            drift.drift_percent()
+        );
+    }
+    report
+}
+
+/// Prints the execution result to stdout (JSON/YAML) or stderr (text)
+fn print_result(
+    plan: &tierflow::BalancingPlan,
+    result: &tierflow::ExecutionResult,
+    dry_run: bool,
+    undo_run: Option<&str>,
+    format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match format {
+        OutputFormat::Json => {
+            let output = structured_output(plan, result, dry_run, undo_run);
             println!("{}", serde_json::to_string_pretty(&output)?);
         }
         OutputFormat::Yaml => {
-            let output = serde_json::json!({
-                "files_moved": result.files_moved,
-                "files_stayed": result.files_stayed,
-                "files_blocked": result.files_blocked,
-                "bytes_moved": result.bytes_moved,
-                "dry_run": dry_run,
-                "blocked": result.blocked.iter().map(|e| serde_json::json!({
-                    "file": e.file.display().to_string(),
-                    "from_tier": &e.from_tier,
-                    "to_tier": &e.to_tier,
-                    "provider": &e.provider,
-                    "reason": &e.reason,
-                })).collect::<Vec<_>>(),
-                "errors": result.errors.iter().map(|e| serde_json::json!({
-                    "file": e.file.display().to_string(),
-                    "from_tier": &e.from_tier,
-                    "to_tier": &e.to_tier,
-                    "error": &e.error,
-                })).collect::<Vec<_>>(),
-            });
+            let output = structured_output(plan, result, dry_run, undo_run);
             println!("{}", serde_yaml::to_string(&output)?);
         }
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            let separator = if matches!(format, OutputFormat::Csv) {
+                ','
+            } else {
+                '\t'
+            };
+            plan.write_delimited(&mut std::io::stdout().lock(), separator)?;
+            // Strategy hits go to stderr, keeping stdout one table
+            print_strategy_stats(plan, Palette::new(false));
+        }
         OutputFormat::Text => {
             if dry_run {
                 eprintln!("\n[DRY-RUN MODE] No files were actually moved");
@@ -224,13 +1278,50 @@ fn run_rebalance(
 
             eprintln!("\nExecution complete:");
             eprintln!("  Files moved: {}", result.files_moved);
+            if result.files_copied > 0 {
+                eprintln!("  Files copied: {}", result.files_copied);
+            }
             eprintln!("  Files stayed: {}", result.files_stayed);
             eprintln!("  Files blocked: {}", result.files_blocked);
+            eprintln!("  Files skipped: {}", result.files_skipped);
+            if result.files_cancelled > 0 {
+                eprintln!(
+                    "  Not started (cancelled): {} - run `tierflow resume` to finish",
+                    result.files_cancelled
+                );
+            }
+            if result.files_deferred > 0 {
+                eprintln!(
+                    "  Deferred (budget exhausted): {} - the next run picks them up",
+                    result.files_deferred
+                );
+            }
+            if result.files_skipped_conflict > 0 {
+                eprintln!(
+                    "  Skipped (destination exists): {}",
+                    result.files_skipped_conflict
+                );
+            }
+            if result.files_skipped_space > 0 {
+                eprintln!(
+                    "  Skipped (not enough free space): {}",
+                    result.files_skipped_space
+                );
+            }
             eprintln!(
                 "  Bytes moved: {} ({:.2} GB)",
                 result.bytes_moved,
                 result.bytes_moved as f64 / 1_000_000_000.0
             );
+            if let Some(run_id) = undo_run {
+                eprintln!("  Undo with: tierflow undo {run_id}");
+            }
+            if !result.verify_throttle_time.is_zero() {
+                eprintln!(
+                    "  Verification throttled: {:.1}s",
+                    result.verify_throttle_time.as_secs_f64()
+                );
+            }
 
             if !result.blocked.is_empty() {
                 eprintln!("\nBlocked ({}):", result.blocked.len());
@@ -243,13 +1334,44 @@ fn run_rebalance(
                 }
             }
 
+            if !result.skipped.is_empty() {
+                eprintln!("\nSkipped ({}):", result.skipped.len());
+                for skipped in &result.skipped {
+                    eprintln!(
+                        "  {} -> {}: {}",
+                        skipped.from_tier, skipped.to_tier, skipped.reason
+                    );
+                    eprintln!("    File: {}", skipped.file.display());
+                }
+            }
+
+            let failed_hooks: Vec<_> = result.hooks.iter().filter(|h| !h.succeeded()).collect();
+            if !failed_hooks.is_empty() {
+                eprintln!("\nFailed hooks ({}):", failed_hooks.len());
+                for hook in failed_hooks {
+                    eprintln!("  {}: {}", hook.event, hook.command);
+                    if hook.timed_out {
+                        eprintln!("    Timed out");
+                    }
+                    for line in hook.output.lines() {
+                        eprintln!("    {line}");
+                    }
+                }
+            }
+
             if !result.errors.is_empty() {
                 eprintln!("\nErrors ({}):", result.errors.len());
                 for error in &result.errors {
                     eprintln!(
-                        "  {} -> {}: {}",
-                        error.from_tier, error.to_tier, error.error
+                        "  {} -> {} [{}]: {}",
+                        error.from_tier,
+                        error.to_tier,
+                        error.kind.as_str(),
+                        error.error
                     );
+                    if error.attempts > 1 {
+                        eprintln!("    (gave up after {} attempts)", error.attempts);
+                    }
                 }
             }
         }
@@ -260,6 +1382,8 @@ fn run_rebalance(
 
 fn run_daemon(
     config_path: &std::path::Path,
+    config_format: ConfigFormat,
+    run_log: &RunLog,
     dry_run: bool,
     interval: u64,
     format: OutputFormat,
@@ -270,62 +1394,579 @@ fn run_daemon(
         config_path.display()
     );
 
-    // Set up Ctrl+C handler
-    let running = Arc::new(AtomicBool::new(true));
-    let r = running.clone();
+    let cancel = install_cancel_handler();
 
-    if let Err(e) = ctrlc::set_handler(move || {
-        tracing::info!("Received interrupt signal, shutting down gracefully...");
-        r.store(false, Ordering::SeqCst);
-    }) {
-        tracing::warn!("Failed to set Ctrl-C handler: {}", e);
+    let mut reloader = ConfigReloader::load(config_path, config_format)?;
+    #[cfg(unix)]
+    if let Err(e) = signal_hook::flag::register(signal_hook::consts::SIGHUP, reloader.reload_flag())
+    {
+        tracing::warn!("Failed to set SIGHUP handler: {}", e);
+    }
+
+    // Failed runs never reach run_rebalance's own notification, so report them here
+    let mut failure_notifier = factory::build_notifier(reloader.current().notifications.as_ref())?;
+    let metrics_addr = reloader.current().metrics.as_ref().map(|m| m.listen_addr);
+    let metrics = match metrics_addr {
+        Some(listen_addr) => {
+            let registry = Arc::new(MetricsRegistry::new());
+            tierflow::serve_metrics(listen_addr, Arc::clone(&registry))?;
+            Some(registry)
+        }
+        None => None,
+    };
+
+    let control = Arc::new(DaemonControl::new());
+    let api_addr = reloader.current().api.as_ref().map(|a| a.listen_addr);
+    if let Some(listen_addr) = api_addr {
+        tierflow::serve_api(listen_addr, Arc::clone(&control))?;
     }
 
+    let mut watcher = reloader.current().daemon.watch.as_ref().and_then(|_| {
+        match reloader.current().build_tiers() {
+            Ok(tiers) => TierWatcher::new(&tiers)
+                .inspect_err(|e| tracing::warn!("Watch mode disabled: {e}"))
+                .ok(),
+            Err(e) => {
+                tracing::warn!("Watch mode disabled: {e}");
+                None
+            }
+        }
+    });
+    let mut dirty = DirtyDirs::default();
+
+    tierflow::systemd::ready();
+    let _watchdog = tierflow::systemd::Watchdog::start();
+
     let mut run_number = 1;
+    let mut last_scrub: Option<Instant> = None;
+    let interval = Duration::from_secs(interval);
+    let mut next_run = reloader
+        .current()
+        .daemon
+        .next_run(interval, None, SystemTime::now());
+
+    while !cancel.load(Ordering::SeqCst) {
+        if next_run.skipped > 0 {
+            tracing::warn!(
+                "Previous run was still executing, skipped {} scheduled run(s)",
+                next_run.skipped
+            );
+        }
+        let current = reloader.current();
+        let start_at = next_run.at + current.daemon.jitter(current.seed, next_run.at);
+        control.set_next_run(start_at);
+        if let Ok(wait) = start_at.duration_since(SystemTime::now())
+            && wait >= Duration::from_secs(1)
+        {
+            tracing::info!("Next run in {} seconds", wait.as_secs());
+            tierflow::systemd::status(&format!("Sleeping, next run in {} seconds", wait.as_secs()));
+        }
+        // Sleep in smaller chunks to allow quick shutdown, API-requested runs and
+        // incremental runs for changed directories
+        let debounce = current.daemon.watch.as_ref().map(WatchConfig::debounce);
+        let mut requested = false;
+        let mut scope = None;
+        while let Ok(wait) = start_at.duration_since(SystemTime::now()) {
+            if cancel.load(Ordering::SeqCst) {
+                break;
+            }
+            if control.take_run_request() {
+                requested = true;
+                break;
+            }
+            if let (Some(watcher), Some(debounce)) = (&mut watcher, debounce) {
+                watcher.poll(&mut dirty);
+                if dirty.is_settled(debounce, Instant::now()) && !control.is_paused() {
+                    scope = Some(dirty.take());
+                    break;
+                }
+            }
+            std::thread::sleep(wait.min(Duration::from_secs(1)));
+        }
+        if cancel.load(Ordering::SeqCst) {
+            break;
+        }
+        requested |= control.take_run_request();
+        if scope.is_none() && control.is_paused() && !requested {
+            tracing::info!("Daemon is paused, skipping scheduled run");
+            next_run =
+                reloader
+                    .current()
+                    .daemon
+                    .next_run(interval, Some(next_run.at), SystemTime::now());
+            continue;
+        }
+
+        if let Some(dirs) = &scope {
+            tracing::info!(
+                "===== Daemon run #{run_number} (incremental, {} changed directories) =====",
+                dirs.len()
+            );
+        } else {
+            tracing::info!("===== Daemon run #{run_number} =====");
+            // A full run covers every pending change
+            dirty.take();
+        }
 
-    while running.load(Ordering::SeqCst) {
-        tracing::info!("===== Daemon run #{run_number} =====");
+        if reloader.reload_if_changed() {
+            let config = reloader.current();
+            match factory::build_notifier(config.notifications.as_ref()) {
+                Ok(notifier) => failure_notifier = notifier,
+                Err(e) => tracing::warn!("Keeping previous failure notifier: {e}"),
+            }
+            if config.metrics.as_ref().map(|m| m.listen_addr) != metrics_addr {
+                tracing::warn!("Metrics listen address changes take effect after a restart");
+            }
+            if config.api.as_ref().map(|a| a.listen_addr) != api_addr {
+                tracing::warn!("API listen address changes take effect after a restart");
+            }
+        }
 
-        match run_rebalance(config_path, dry_run, format) {
-            Ok(()) => {
+        start_run_log(run_log, reloader.current());
+        let budget = tierflow::ExecutionBudget::default();
+        control.run_started(run_number);
+        let outcome = run_rebalance(
+            reloader.current(),
+            dry_run,
+            None,
+            budget,
+            format,
+            &PlanView::default(),
+            false,
+            scope.as_deref(),
+            Some(&control),
+            &cancel,
+        );
+        // Events of the run's own moves; the next full run catches anything else
+        if let Some(watcher) = &mut watcher {
+            watcher.poll(&mut DirtyDirs::default());
+        }
+        control.run_finished(outcome.as_ref().map_err(ToString::to_string));
+        match outcome {
+            Ok(run) => {
                 tracing::info!("Rebalance completed successfully");
+                if let Some(registry) = &metrics {
+                    registry.record_run(run);
+                }
             }
             Err(e) => {
                 tracing::error!("Rebalance failed: {e}");
+                if let Some(registry) = &metrics {
+                    registry.record_failure();
+                }
+                if let Some(notifier) = &failure_notifier {
+                    notifier.notify(&RunSummary::failed(dry_run, e.to_string()));
+                }
                 // Continue running even after errors
             }
         }
 
-        if !running.load(Ordering::SeqCst) {
-            break;
+        if let Some(scrub) = reloader.current().daemon.scrub.clone()
+            && !cancel.load(Ordering::SeqCst)
+            && last_scrub.is_none_or(|at| at.elapsed() >= scrub.interval())
+        {
+            let options = ScrubOptions {
+                sample_percent: scrub.sample_percent,
+                ..ScrubOptions::default()
+            };
+            tierflow::systemd::status("Scrubbing moved files");
+            match scrub_tiers(reloader.current(), options, &cancel) {
+                Ok(report) if report.is_clean() => {
+                    tracing::info!("Scrub verified {} file(s)", report.verified);
+                }
+                Ok(report) => {
+                    let reason = format!(
+                        "scrub found {} checksum mismatch(es) and {} unreadable file(s)",
+                        report.mismatches.len(),
+                        report.errors.len()
+                    );
+                    tracing::error!("{reason}");
+                    if let Some(notifier) = &failure_notifier {
+                        notifier.notify(&RunSummary::failed(dry_run, reason));
+                    }
+                }
+                Err(e) => tracing::error!("Scrub failed: {e}"),
+            }
+            last_scrub = Some(Instant::now());
+        }
+
+        if scope.is_some() {
+            // Incremental runs leave the schedule of full runs alone
+            next_run.skipped = 0;
+        } else {
+            next_run =
+                reloader
+                    .current()
+                    .daemon
+                    .next_run(interval, Some(next_run.at), SystemTime::now());
         }
+        run_number += 1;
+    }
 
-        tracing::info!("Sleeping for {interval} seconds until next run...");
+    tierflow::systemd::stopping();
+    tracing::info!("Daemon stopped gracefully");
+    Ok(())
+}
 
-        // Sleep in smaller chunks to allow quick shutdown
-        let sleep_chunk = Duration::from_secs(1);
-        let chunks = interval;
+/// Writes every scanned file with its planned placement as JSON
+fn run_export_inventory(
+    config_path: &std::path::Path,
+    config_format: ConfigFormat,
+    output: Option<&std::path::Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = BalancingConfig::from_file_as(config_path, config_format)?;
+    let tiers = config.build_tiers()?;
+    let journal = load_journal(&config.state_dir());
 
-        for _ in 0..chunks {
-            if !running.load(Ordering::SeqCst) {
-                break;
+    let plan = build_balancer(&config, tiers, &journal)?.plan_rebalance();
+    let entries = tierflow::inventory::inventory(&plan);
+
+    match output {
+        Some(path) => {
+            let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+            serde_json::to_writer_pretty(&mut writer, &entries)?;
+            writer.flush()?;
+            tracing::info!("Exported {} files to {}", entries.len(), path.display());
+        }
+        None => println!("{}", serde_json::to_string_pretty(&entries)?),
+    }
+    Ok(())
+}
+
+/// Explains the planner's decision for `path`; returns false if no tier has the file
+fn run_explain(
+    config_path: &std::path::Path,
+    config_format: ConfigFormat,
+    path: &std::path::Path,
+    format: OutputFormat,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let config = BalancingConfig::from_file_as(config_path, config_format)?;
+    let tiers = config.build_tiers()?;
+    let journal = load_journal(&config.state_dir());
+
+    let path = std::path::absolute(path)?;
+    let Some(explanation) = build_balancer(&config, tiers, &journal)?.explain_file(&path) else {
+        eprintln!("{} is not on any configured tier", path.display());
+        return Ok(false);
+    };
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&explanation)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&explanation)?),
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::Tsv => print!("{explanation}"),
+    }
+    Ok(true)
+}
+
+/// Lints the config; returns false if it has errors
+fn run_validate(
+    config_path: &std::path::Path,
+    config_format: ConfigFormat,
+    format: OutputFormat,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let findings = match BalancingConfig::parse_file_as(config_path, config_format) {
+        Ok(config) => config.lint(),
+        Err(e) => vec![LintFinding {
+            severity: LintSeverity::Error,
+            code: "invalid_config",
+            strategy: None,
+            message: e.to_string(),
+        }],
+    };
+    let errors = findings
+        .iter()
+        .filter(|f| f.severity == LintSeverity::Error)
+        .count();
+    let warnings = findings
+        .iter()
+        .filter(|f| f.severity == LintSeverity::Warning)
+        .count();
+
+    match format {
+        OutputFormat::Json | OutputFormat::Yaml => {
+            let output = serde_json::json!({
+                "config": config_path.display().to_string(),
+                "valid": errors == 0,
+                "errors": errors,
+                "warnings": warnings,
+                "findings": &findings,
+            });
+            if matches!(format, OutputFormat::Json) {
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else {
+                println!("{}", serde_yaml::to_string(&output)?);
+            }
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::Tsv => {
+            for finding in &findings {
+                println!("{finding}");
             }
-            std::thread::sleep(sleep_chunk);
+            println!(
+                "{}: {errors} error(s), {warnings} warning(s)",
+                config_path.display()
+            );
         }
+    }
 
-        run_number += 1;
+    Ok(errors == 0)
+}
+
+fn run_lock(
+    action: LockAction,
+    config_format: Option<ConfigFormat>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let load_tiers = |config_path: &std::path::Path| -> Result<Vec<_>, Box<dyn std::error::Error>> {
+        let format = config_format.unwrap_or_else(|| ConfigFormat::from_path(config_path));
+        let config = BalancingConfig::parse_file_as(config_path, format)?;
+        Ok(config
+            .tiers
+            .into_iter()
+            .map(tierflow::TierConfig::into_tier)
+            .collect::<Result<_, _>>()?)
+    };
+
+    match action {
+        LockAction::Status { config, format } => {
+            let tiers = load_tiers(&config)?;
+            let statuses = TierLockGuard::status(&tiers);
+
+            match format {
+                OutputFormat::Json | OutputFormat::Yaml => {
+                    let locks: Vec<_> = statuses
+                        .iter()
+                        .map(|status| {
+                            serde_json::json!({
+                                "tier": &status.tier,
+                                "tier_path": status.tier_path.display().to_string(),
+                                "lock_path": status.lock_path.display().to_string(),
+                                "held": status.held,
+                                "local_owner": status.local_owner,
+                                "owner_alive": status.owner_alive,
+                                "age_secs": status.age().map(|age| age.as_secs()),
+                                "owner": &status.info,
+                            })
+                        })
+                        .collect();
+                    let output = serde_json::json!({
+                        "locked": !locks.is_empty(),
+                        "locks": locks,
+                    });
+                    if matches!(format, OutputFormat::Json) {
+                        println!("{}", serde_json::to_string_pretty(&output)?);
+                    } else {
+                        println!("{}", serde_yaml::to_string(&output)?);
+                    }
+                }
+                OutputFormat::Text | OutputFormat::Csv | OutputFormat::Tsv
+                    if statuses.is_empty() =>
+                {
+                    println!("Not locked");
+                }
+                OutputFormat::Text | OutputFormat::Csv | OutputFormat::Tsv => {
+                    for status in statuses {
+                        println!(
+                            "Tier '{}' ({}): {}",
+                            status.tier,
+                            status.tier_path.display(),
+                            status.lock_path.display()
+                        );
+                        println!("  Held on this host: {}", status.held);
+                        match &status.info {
+                            Some(info) => {
+                                println!("  Owner: pid {} on {}", info.pid, info.hostname);
+                                println!("  Command: {}", info.command);
+                                if let Some(age) = status.age() {
+                                    println!("  Age: {}s", age.as_secs());
+                                }
+                                match status.owner_alive {
+                                    Some(true) => println!("  Owner process: running"),
+                                    Some(false) => println!("  Owner process: dead (stale lock)"),
+                                    None => println!("  Owner process: unknown (remote host)"),
+                                }
+                            }
+                            None => println!("  Owner: unreadable lock file"),
+                        }
+                    }
+                }
+            }
+        }
+        LockAction::Break {
+            config,
+            pid,
+            force,
+            min_age_hours,
+        } => {
+            let tiers = load_tiers(&config)?;
+            let min_age = Duration::from_secs(min_age_hours.saturating_mul(3600));
+            for lock_path in TierLockGuard::break_lock(&tiers, pid, force, min_age)? {
+                println!("Removed lock {}", lock_path.display());
+            }
+        }
     }
 
-    tracing::info!("Daemon stopped gracefully");
     Ok(())
 }
 
-fn print_plan(plan: &tierflow::BalancingPlan) {
+fn run_config(action: ConfigAction) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        ConfigAction::Init { config, force } => {
+            if config.exists() && !force {
+                return Err(format!(
+                    "{} already exists, use --force to overwrite it",
+                    config.display()
+                )
+                .into());
+            }
+            if let Some(parent) = config.parent()
+                && !parent.as_os_str().is_empty()
+            {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&config, tierflow::config::STARTER_CONFIG)?;
+            println!("Wrote starter config to {}", config.display());
+            println!(
+                "Edit the tiers and strategies, then check it with: tierflow validate --config {}",
+                config.display()
+            );
+        }
+        ConfigAction::Schema { output } => {
+            let schema = serde_json::to_string_pretty(&BalancingConfig::json_schema())?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, schema + "\n")?;
+                    tracing::info!("Wrote config schema to {}", path.display());
+                }
+                None => println!("{schema}"),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Machine-readable run summary shared by JSON and YAML output
+fn structured_output(
+    plan: &tierflow::BalancingPlan,
+    result: &tierflow::ExecutionResult,
+    dry_run: bool,
+    undo_run: Option<&str>,
+) -> serde_json::Value {
+    serde_json::json!({
+        "fingerprint": plan.fingerprint(),
+        "seed": plan.seed,
+        "files_filtered": plan.filtered_files,
+        "draining": plan.draining,
+        "strategy_stats": plan.strategy_stats,
+        "files_moved": result.files_moved,
+        "files_copied": result.files_copied,
+        "files_stayed": result.files_stayed,
+        "files_blocked": result.files_blocked,
+        "files_skipped": result.files_skipped,
+        "files_skipped_conflict": result.files_skipped_conflict,
+        "files_skipped_space": result.files_skipped_space,
+        "files_cancelled": result.files_cancelled,
+        "files_deferred": result.files_deferred,
+        "errors_by_kind": result.errors_by_kind.iter()
+            .map(|(kind, count)| (kind.as_str(), *count))
+            .collect::<std::collections::BTreeMap<_, _>>(),
+        "bytes_moved": result.bytes_moved,
+        "bytes_copied": result.bytes_copied,
+        "verify_throttle_secs": result.verify_throttle_time.as_secs_f64(),
+        "dry_run": dry_run,
+        "undo_run_id": undo_run,
+        "hooks": result.hooks.iter().map(|h| serde_json::json!({
+            "event": h.event.to_string(),
+            "command": &h.command,
+            "exit_code": h.exit_code,
+            "timed_out": h.timed_out,
+            "output": &h.output,
+        })).collect::<Vec<_>>(),
+        "moved": result.moved.iter().map(|m| serde_json::json!({
+            "file": m.file.display().to_string(),
+            "from_tier": &m.from_tier,
+            "to_tier": &m.to_tier,
+            "size": m.size,
+            "verification": m.verification.to_string(),
+        })).collect::<Vec<_>>(),
+        "blocked": result.blocked.iter().map(|e| serde_json::json!({
+            "file": e.file.display().to_string(),
+            "from_tier": &e.from_tier,
+            "to_tier": &e.to_tier,
+            "provider": &e.provider,
+            "reason": &e.reason,
+        })).collect::<Vec<_>>(),
+        "skipped": result.skipped.iter().map(|e| serde_json::json!({
+            "file": e.file.display().to_string(),
+            "from_tier": &e.from_tier,
+            "to_tier": &e.to_tier,
+            "reason": &e.reason,
+        })).collect::<Vec<_>>(),
+        "errors": result.errors.iter().map(|e| serde_json::json!({
+            "file": e.file.display().to_string(),
+            "from_tier": &e.from_tier,
+            "to_tier": &e.to_tier,
+            "error": &e.error,
+            "kind": e.kind.as_str(),
+            "class": e.class.as_str(),
+            "attempts": e.attempts,
+        })).collect::<Vec<_>>(),
+        "directories": plan.projected_directory_usage.values().map(|d| serde_json::json!({
+            "directory": &d.directory,
+            "total_bytes": d.total_bytes(),
+            "current_bytes": &d.current_bytes,
+            "projected_bytes": &d.projected_bytes,
+        })).collect::<Vec<_>>(),
+        "plan": plan,
+    })
+}
+
+/// Gigabytes with two decimals, as used throughout the text output
+fn gb(bytes: u64) -> String {
+    format!("{:.2} GB", bytes as f64 / 1_000_000_000.0)
+}
+
+/// Per-strategy hits of Pass 2, strategies that won nothing dimmed
+fn print_strategy_stats(plan: &tierflow::BalancingPlan, palette: Palette) {
+    if plan.strategy_stats.is_empty() {
+        return;
+    }
+    eprintln!("\nStrategy Hits:");
+    let mut table = Table::new(["Strategy", "Files", "Size", "Moves", "Stays", "Blocked"])
+        .align_right([1, 2, 3, 4, 5]);
+    for (strategy, hits) in plan.strategy_stats.iter() {
+        let name = if hits.files == 0 {
+            Cell::colored(format!("{strategy} (unused)"), Color::Dim)
+        } else {
+            strategy.into()
+        };
+        let blocked = if hits.blocked > 0 {
+            Cell::colored(hits.blocked.to_string(), Color::Red)
+        } else {
+            hits.blocked.to_string().into()
+        };
+        table.row([
+            name,
+            hits.files.to_string().into(),
+            gb(hits.bytes).into(),
+            hits.moves.to_string().into(),
+            hits.stays.to_string().into(),
+            blocked,
+        ]);
+    }
+    eprint!("{}", table.render(palette));
+}
+
+fn print_plan(plan: &tierflow::BalancingPlan, tiers: &[Tier], view: &PlanView) {
     eprintln!("\n=== Balancing Plan ===");
 
     // Warnings
     if !plan.warnings.is_empty() {
-        eprintln!("\nWarnings ({}):", plan.warnings.len());
+        eprintln!(
+            "\nWarnings ({}: {} critical, {} warning, {} info):",
+            plan.warnings.len(),
+            plan.warning_count(tierflow::WarningSeverity::Critical),
+            plan.warning_count(tierflow::WarningSeverity::Warning),
+            plan.warning_count(tierflow::WarningSeverity::Info)
+        );
         for warning in &plan.warnings {
             match warning {
                 tierflow::PlanWarning::InsufficientSpace {
@@ -347,100 +1988,334 @@ fn print_plan(plan: &tierflow::BalancingPlan) {
                     eprintln!("    Strategy: {strategy}");
                     eprintln!("    Reason: {reason}");
                 }
+                tierflow::PlanWarning::MoveDenied {
+                    file,
+                    strategy,
+                    from_tier,
+                    to_tier,
+                } => {
+                    eprintln!("  [MOVE DENIED] {}", file.display());
+                    eprintln!("    Strategy: {strategy}");
+                    eprintln!("    Move policy forbids {from_tier} -> {to_tier}");
+                }
+                tierflow::PlanWarning::BatchDeferred {
+                    tier,
+                    files,
+                    bytes,
+                    min_batch_bytes,
+                } => {
+                    eprintln!("  [BATCH DEFERRED] {tier}");
+                    eprintln!(
+                        "    {files} move(s), {:.2} GB < {:.2} GB batch minimum",
+                        *bytes as f64 / 1_000_000_000.0,
+                        *min_batch_bytes as f64 / 1_000_000_000.0
+                    );
+                }
+                tierflow::PlanWarning::CooldownActive {
+                    file,
+                    strategy,
+                    moved_hours_ago,
+                    min_hours_between_moves,
+                } => {
+                    eprintln!("  [COOLDOWN] {}", file.display());
+                    eprintln!("    Strategy: {strategy}");
+                    eprintln!(
+                        "    Moved {moved_hours_ago}h ago, cooldown is {min_hours_between_moves}h"
+                    );
+                }
+                tierflow::PlanWarning::QuotaReached {
+                    strategy,
+                    deferred_files,
+                    deferred_bytes,
+                } => {
+                    eprintln!(
+                        "  [QUOTA REACHED] {}",
+                        strategy.as_deref().unwrap_or("global")
+                    );
+                    eprintln!(
+                        "    {deferred_files} move(s), {:.2} GB deferred to later runs",
+                        *deferred_bytes as f64 / 1_000_000_000.0
+                    );
+                }
+                tierflow::PlanWarning::AmbiguousStrategy {
+                    strategies,
+                    priority,
+                    winner,
+                    files,
+                    example,
+                } => {
+                    eprintln!("  [AMBIGUOUS STRATEGY] {}", strategies.join(", "));
+                    eprintln!("    Priority {priority}, {files} file(s), '{winner}' wins by name");
+                    eprintln!("    Example: {}", example.display());
+                }
+                tierflow::PlanWarning::PinnedOverCapacity {
+                    tier,
+                    pinned_bytes,
+                    allowed_bytes,
+                    strategies,
+                } => {
+                    eprintln!("  [PINNED OVER CAPACITY] {tier}");
+                    eprintln!(
+                        "    {:.2} GB pinned by `action: stay`, max usage allows {:.2} GB",
+                        *pinned_bytes as f64 / 1_000_000_000.0,
+                        *allowed_bytes as f64 / 1_000_000_000.0
+                    );
+                    for s in strategies {
+                        eprintln!(
+                            "    {}: {:.2} GB",
+                            s.strategy,
+                            s.bytes as f64 / 1_000_000_000.0
+                        );
+                    }
+                }
+                tierflow::PlanWarning::BelowMinUsage {
+                    tier,
+                    usage_percent,
+                    min_usage_percent,
+                    files,
+                    bytes,
+                } => {
+                    eprintln!("  [BELOW MIN USAGE] {tier}");
+                    eprintln!(
+                        "    {usage_percent}% used < min_usage_percent {min_usage_percent}%, demotions held"
+                    );
+                    eprintln!(
+                        "    {files} file(s), {:.2} GB stay until the tier fills up",
+                        *bytes as f64 / 1_000_000_000.0
+                    );
+                }
             }
         }
     }
 
     // Tier projections
     eprintln!("\nTier Usage Projections:");
+    let mut table = Table::new(["Tier", "Current", "Projected", "Change", "Used", "Free"])
+        .align_right([1, 2, 3, 4, 5]);
     for (tier_name, projection) in &plan.projected_tier_usage {
-        eprintln!("  {tier_name}:");
-        eprintln!(
-            "    Current:   {}% ({:.2} GB used, {:.2} GB free)",
-            projection.current_percent,
-            projection.current_used as f64 / 1_000_000_000.0,
-            projection.current_free as f64 / 1_000_000_000.0
-        );
-        eprintln!(
-            "    Projected: {}% ({:.2} GB used, {:.2} GB free)",
-            projection.projected_percent,
-            projection.projected_used as f64 / 1_000_000_000.0,
-            projection.projected_free as f64 / 1_000_000_000.0
-        );
-
         let change = projection.projected_percent as i64 - projection.current_percent as i64;
-        if change != 0 {
-            let arrow = if change > 0 { "↑" } else { "↓" };
-            eprintln!("    Change: {} {}%", arrow, change.abs());
+        let change = match change.signum() {
+            1 => Cell::colored(format!("↑ {change}%"), Color::Yellow),
+            -1 => Cell::colored(format!("↓ {}%", change.abs()), Color::Green),
+            _ => Cell::colored("-", Color::Dim),
+        };
+        table.row([
+            tier_name.as_str().into(),
+            format!("{}%", projection.current_percent).into(),
+            format!("{}%", projection.projected_percent).into(),
+            change,
+            gb(projection.projected_used).into(),
+            gb(projection.projected_free).into(),
+        ]);
+    }
+    eprint!("{}", table.render(view.palette));
+
+    // What keeps each tier full
+    if !plan.largest_remaining.is_empty() {
+        eprintln!("\nLargest Remaining:");
+        for offenders in &plan.largest_remaining {
+            eprintln!("  {}:", offenders.tier);
+            for dir in &offenders.directories {
+                let strategies: Vec<String> = dir
+                    .strategies
+                    .iter()
+                    .map(|s| format!("{} {:.2} GB", s.strategy, s.bytes as f64 / 1_000_000_000.0))
+                    .collect();
+                eprintln!(
+                    "    {} {:.2} GB in {} file(s) ({})",
+                    dir.directory,
+                    dir.bytes as f64 / 1_000_000_000.0,
+                    dir.files,
+                    strategies.join(", ")
+                );
+            }
+            for file in &offenders.files {
+                eprintln!(
+                    "    {} {:.2} GB ({})",
+                    file.path.display(),
+                    file.size as f64 / 1_000_000_000.0,
+                    file.strategy
+                );
+            }
         }
     }
 
-    // Decisions summary
-    let promote_count = plan
-        .decisions
-        .iter()
-        .filter(|d| matches!(d, PlacementDecision::Promote { .. }))
-        .count();
-    let demote_count = plan
-        .decisions
-        .iter()
-        .filter(|d| matches!(d, PlacementDecision::Demote { .. }))
-        .count();
-    let stay_count = plan.stay_count();
+    // Scan filters
+    if !plan.filtered_files.is_empty() {
+        eprintln!("\nFiltered Files:");
+        for filtered in &plan.filtered_files {
+            eprintln!(
+                "  {}: {} hidden, {} below min_file_size ({:.2} GB)",
+                filtered.tier,
+                filtered.hidden,
+                filtered.too_small,
+                filtered.bytes as f64 / 1_000_000_000.0
+            );
+        }
+    }
 
-    eprintln!("\nDecisions Summary:");
-    eprintln!("  Total files: {}", plan.total_files());
-    eprintln!("  Promote: {promote_count}");
-    eprintln!("  Demote: {demote_count}");
-    eprintln!("  Stay: {stay_count}");
+    // Tiers being emptied
+    if !plan.draining.is_empty() {
+        eprintln!("\nDraining Tiers:");
+        for drain in &plan.draining {
+            if drain.is_complete() {
+                eprintln!(
+                    "  {}: empty after this run ({} file(s), {:.2} GB moving off)",
+                    drain.tier,
+                    drain.files_leaving,
+                    drain.bytes_leaving as f64 / 1_000_000_000.0
+                );
+            } else {
+                eprintln!(
+                    "  {}: {} of {} file(s) moving off ({:.2} of {:.2} GB), {} left for later runs",
+                    drain.tier,
+                    drain.files_leaving,
+                    drain.files,
+                    drain.bytes_leaving as f64 / 1_000_000_000.0,
+                    drain.bytes as f64 / 1_000_000_000.0,
+                    drain.files - drain.files_leaving
+                );
+            }
+        }
+    }
 
-    // Show first 10 moves
-    let moves: Vec<_> = plan
-        .decisions
-        .iter()
-        .filter(|d| !matches!(d, PlacementDecision::Stay { .. }))
-        .take(10)
-        .collect();
+    // Spin-down batches
+    if !plan.move_batches.is_empty() {
+        eprintln!("\nSpin-down Batches:");
+        for batch in &plan.move_batches {
+            let status = if batch.deferred {
+                "deferred"
+            } else {
+                "contiguous"
+            };
+            eprintln!(
+                "  {}: {} file(s), {:.2} GB ({status})",
+                batch.tier,
+                batch.files,
+                batch.bytes as f64 / 1_000_000_000.0
+            );
+        }
+    }
+
+    // Totals by direction
+    let mut totals = [(0, 0); 4];
+    for decision in &plan.decisions {
+        let slot = match decision {
+            PlacementDecision::Promote { .. } => 0,
+            PlacementDecision::Demote { .. } => 1,
+            PlacementDecision::Copy { .. } => 2,
+            PlacementDecision::Stay { .. } => 3,
+        };
+        totals[slot].0 += 1;
+        totals[slot].1 += decision.file_size();
+    }
+    eprintln!("\nDecisions Summary ({} files):", plan.total_files());
+    let mut table = Table::new(["Direction", "Files", "Size"]).align_right([1, 2]);
+    // Copies only show up when some strategy copies
+    let directions = [
+        ("↑ Promote", Color::Green, true),
+        ("↓ Demote", Color::Yellow, true),
+        ("⇉ Copy", Color::Cyan, false),
+        ("= Stay", Color::Dim, true),
+    ];
+    for ((label, color, always), (files, bytes)) in directions.into_iter().zip(totals) {
+        if files == 0 && !always {
+            continue;
+        }
+        table.row([
+            Cell::colored(label, color),
+            files.to_string().into(),
+            gb(bytes).into(),
+        ]);
+    }
+    eprint!("{}", table.render(view.palette));
+
+    let strategies = StrategyMoves::collect(plan);
+    if !strategies.is_empty() {
+        eprintln!("\nMoves by Strategy:");
+        let mut table =
+            Table::new(["Strategy", "Promote", "Demote", "Copy", "Size"]).align_right([1, 2, 3, 4]);
+        for s in &strategies {
+            table.row([
+                s.strategy.as_str().into(),
+                s.promoted.to_string().into(),
+                s.demoted.to_string().into(),
+                s.copied.to_string().into(),
+                gb(s.bytes).into(),
+            ]);
+        }
+        eprint!("{}", table.render(view.palette));
+    }
+
+    print_strategy_stats(plan, view.palette);
+
+    let rollups = plan.rollup_by_directory(tiers);
+    if !view.expand && !rollups.is_empty() {
+        let shown = if view.limit > 0 {
+            view.limit.min(rollups.len())
+        } else {
+            rollups.len()
+        };
+        eprintln!(
+            "\nPlanned Moves by Directory ({shown} of {}, largest first):",
+            rollups.len()
+        );
+        for rollup in &rollups[..shown] {
+            let color = match rollup.action {
+                "promote" => Color::Green,
+                "copy" => Color::Cyan,
+                _ => Color::Yellow,
+            };
+            eprintln!("  {}", view.palette.paint(&rollup.to_string(), color));
+        }
+        eprintln!("  (--expand lists the individual moves)");
+    }
 
+    let moves = if view.expand {
+        view.moves(plan)
+    } else {
+        Vec::new()
+    };
     if !moves.is_empty() {
+        let order = match view.sort {
+            Some(MoveSort::Size) => "largest first",
+            Some(MoveSort::Priority) => "highest priority first",
+            Some(MoveSort::Path) => "by path",
+            None => "in execution order",
+        };
         eprintln!(
-            "\nPlanned Moves (showing first 10 of {}):",
+            "\nPlanned Moves ({} of {}, {order}):",
+            moves.len(),
             plan.move_count()
         );
+        let mut table = Table::new(["", "Priority", "Size", "From", "To", "Strategy", "File"])
+            .align_right([1, 2]);
         for decision in moves {
-            match decision {
-                PlacementDecision::Promote {
-                    file,
-                    from_tier,
-                    to_tier,
-                    strategy,
-                    priority,
-                } => {
-                    eprintln!("  ↑ PROMOTE [priority={priority}]");
-                    eprintln!("    File: {}", file.path.display());
-                    eprintln!("    {from_tier} -> {to_tier} (strategy: {strategy})");
-                }
-                PlacementDecision::Demote {
-                    file,
-                    from_tier,
-                    to_tier,
-                    strategy,
-                    priority,
-                } => {
-                    eprintln!("  ↓ DEMOTE [priority={priority}]");
-                    eprintln!("    File: {}", file.path.display());
-                    eprintln!("    {from_tier} -> {to_tier} (strategy: {strategy})");
-                }
-                PlacementDecision::Stay { .. } => {}
-            }
-        }
-
-        if plan.move_count() > 10 {
-            eprintln!("  ... and {} more", plan.move_count() - 10);
+            let action = match decision {
+                PlacementDecision::Promote { .. } => Cell::colored("↑", Color::Green),
+                PlacementDecision::Demote { .. } => Cell::colored("↓", Color::Yellow),
+                PlacementDecision::Copy { .. } => Cell::colored("⇉", Color::Cyan),
+                PlacementDecision::Stay { .. } => continue,
+            };
+            table.row([
+                action,
+                decision.strategy_priority().to_string().into(),
+                gb(decision.file_size()).into(),
+                decision.current_tier().into(),
+                decision.target_tier().into(),
+                decision.strategy_name().into(),
+                decision.file_path().display().to_string().into(),
+            ]);
         }
+        eprint!("{}", table.render(view.palette));
     }
 
     if plan.is_empty() {
         eprintln!("\n✓ System is balanced, no moves needed");
     }
+
+    match plan.seed {
+        Some(seed) => eprintln!("\nPlan fingerprint: {} (seed {seed})", plan.fingerprint()),
+        None => eprintln!("\nPlan fingerprint: {}", plan.fingerprint()),
+    }
 }
@@ -0,0 +1,446 @@
+use crate::{BalancingPlan, ExecutionResult, WarningSeverity};
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Outcome of a single rebalance run, as exported to Prometheus
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunMetrics {
+    pub duration: Duration,
+    pub files_moved: usize,
+    pub bytes_moved: u64,
+    pub files_blocked: usize,
+    pub errors: usize,
+    /// Plan warnings of `critical` severity
+    pub critical_warnings: usize,
+    pub blocked_placements: usize,
+    pub evictions: usize,
+    pub verify_throttle_time: Duration,
+    pub tiers: Vec<TierMetrics>,
+}
+
+/// Current and projected usage of a tier after planning
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TierMetrics {
+    pub name: String,
+    pub current_used: u64,
+    pub projected_used: u64,
+    pub total: u64,
+}
+
+impl RunMetrics {
+    pub fn from_run(plan: &BalancingPlan, result: &ExecutionResult, duration: Duration) -> Self {
+        let mut tiers: Vec<_> = plan
+            .projected_tier_usage
+            .values()
+            .map(|p| TierMetrics {
+                name: p.tier_name.clone(),
+                current_used: p.current_used,
+                projected_used: p.projected_used,
+                total: p.current_used + p.current_free,
+            })
+            .collect();
+        tiers.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Self {
+            duration,
+            files_moved: result.files_moved,
+            bytes_moved: result.bytes_moved,
+            files_blocked: result.files_blocked,
+            errors: result.errors.len(),
+            critical_warnings: plan.warning_count(WarningSeverity::Critical),
+            blocked_placements: plan.blocked_placements,
+            evictions: plan.evictions,
+            verify_throttle_time: result.verify_throttle_time,
+            tiers,
+        }
+    }
+
+    /// Adds the next iteration of the same run; tier usage is taken from the later one
+    pub fn merge(&mut self, next: Self) {
+        self.duration += next.duration;
+        self.files_moved += next.files_moved;
+        self.bytes_moved += next.bytes_moved;
+        self.files_blocked += next.files_blocked;
+        self.errors += next.errors;
+        self.critical_warnings += next.critical_warnings;
+        self.blocked_placements = next.blocked_placements;
+        self.evictions += next.evictions;
+        self.verify_throttle_time += next.verify_throttle_time;
+        self.tiers = next.tiers;
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    runs_total: u64,
+    failed_runs_total: u64,
+    files_moved_total: u64,
+    bytes_moved_total: u64,
+    errors_total: u64,
+    evictions_total: u64,
+    last_run_timestamp: u64,
+    last_run: Option<RunMetrics>,
+}
+
+/// Metrics shared between the daemon loop and the HTTP endpoint
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    counters: Mutex<Counters>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a completed run
+    pub fn record_run(&self, run: RunMetrics) {
+        let mut counters = self.lock();
+        counters.runs_total += 1;
+        counters.files_moved_total += run.files_moved as u64;
+        counters.bytes_moved_total += run.bytes_moved;
+        counters.errors_total += run.errors as u64;
+        counters.evictions_total += run.evictions as u64;
+        counters.last_run_timestamp = unix_now();
+        counters.last_run = Some(run);
+    }
+
+    /// Records a run that failed before producing a result
+    pub fn record_failure(&self) {
+        let mut counters = self.lock();
+        counters.runs_total += 1;
+        counters.failed_runs_total += 1;
+        counters.last_run_timestamp = unix_now();
+    }
+
+    /// Renders all metrics in Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let counters = self.lock();
+        let mut out = String::new();
+
+        write_metric(
+            &mut out,
+            "tierflow_runs_total",
+            "counter",
+            "Total rebalance runs",
+            counters.runs_total,
+        );
+        write_metric(
+            &mut out,
+            "tierflow_failed_runs_total",
+            "counter",
+            "Rebalance runs that failed before execution",
+            counters.failed_runs_total,
+        );
+        write_metric(
+            &mut out,
+            "tierflow_files_moved_total",
+            "counter",
+            "Files moved across all runs",
+            counters.files_moved_total,
+        );
+        write_metric(
+            &mut out,
+            "tierflow_bytes_moved_total",
+            "counter",
+            "Bytes moved across all runs",
+            counters.bytes_moved_total,
+        );
+        write_metric(
+            &mut out,
+            "tierflow_errors_total",
+            "counter",
+            "Failed moves across all runs",
+            counters.errors_total,
+        );
+        write_metric(
+            &mut out,
+            "tierflow_evictions_total",
+            "counter",
+            "Files evicted across all runs",
+            counters.evictions_total,
+        );
+        write_metric(
+            &mut out,
+            "tierflow_last_run_timestamp_seconds",
+            "gauge",
+            "Unix time of the last run",
+            counters.last_run_timestamp,
+        );
+
+        if let Some(run) = &counters.last_run {
+            let _ = writeln!(
+                out,
+                "# HELP tierflow_last_run_duration_seconds Duration of the last run"
+            );
+            let _ = writeln!(out, "# TYPE tierflow_last_run_duration_seconds gauge");
+            let _ = writeln!(
+                out,
+                "tierflow_last_run_duration_seconds {}",
+                run.duration.as_secs_f64()
+            );
+            let _ = writeln!(
+                out,
+                "# HELP tierflow_last_run_verify_throttle_seconds Time hash verification was throttled in the last run"
+            );
+            let _ = writeln!(
+                out,
+                "# TYPE tierflow_last_run_verify_throttle_seconds gauge"
+            );
+            let _ = writeln!(
+                out,
+                "tierflow_last_run_verify_throttle_seconds {}",
+                run.verify_throttle_time.as_secs_f64()
+            );
+            write_metric(
+                &mut out,
+                "tierflow_last_run_files_moved",
+                "gauge",
+                "Files moved in the last run",
+                run.files_moved as u64,
+            );
+            write_metric(
+                &mut out,
+                "tierflow_last_run_bytes_moved",
+                "gauge",
+                "Bytes moved in the last run",
+                run.bytes_moved,
+            );
+            write_metric(
+                &mut out,
+                "tierflow_last_run_files_blocked",
+                "gauge",
+                "Moves skipped by move blockers in the last run",
+                run.files_blocked as u64,
+            );
+            write_metric(
+                &mut out,
+                "tierflow_last_run_errors",
+                "gauge",
+                "Failed moves in the last run",
+                run.errors as u64,
+            );
+            write_metric(
+                &mut out,
+                "tierflow_last_run_blocked_placements",
+                "gauge",
+                "Placements without space on the preferred tier in the last run",
+                run.blocked_placements as u64,
+            );
+            write_metric(
+                &mut out,
+                "tierflow_last_run_evictions",
+                "gauge",
+                "Files evicted in the last run",
+                run.evictions as u64,
+            );
+
+            write_tier_metric(
+                &mut out,
+                "tierflow_tier_used_bytes",
+                "Current tier usage",
+                &run.tiers,
+                |t| t.current_used,
+            );
+            write_tier_metric(
+                &mut out,
+                "tierflow_tier_projected_used_bytes",
+                "Tier usage projected by the last plan",
+                &run.tiers,
+                |t| t.projected_used,
+            );
+            write_tier_metric(
+                &mut out,
+                "tierflow_tier_size_bytes",
+                "Tier capacity",
+                &run.tiers,
+                |t| t.total,
+            );
+        }
+
+        out
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Counters> {
+        self.counters.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn write_metric(out: &mut String, name: &str, kind: &str, help: &str, value: u64) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+    let _ = writeln!(out, "{name} {value}");
+}
+
+fn write_tier_metric(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    tiers: &[TierMetrics],
+    value: impl Fn(&TierMetrics) -> u64,
+) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} gauge");
+    for tier in tiers {
+        let _ = writeln!(
+            out,
+            "{name}{{tier=\"{}\"}} {}",
+            escape_label(&tier.name),
+            value(tier)
+        );
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Serves `/metrics` on `addr` from a background thread
+pub fn serve_metrics(addr: SocketAddr, registry: Arc<MetricsRegistry>) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    tracing::info!("Serving Prometheus metrics on http://{addr}/metrics");
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if let Err(e) = handle_connection(stream, &registry) {
+                        tracing::debug!("Metrics request failed: {e}");
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to accept metrics connection: {e}"),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+fn handle_connection(mut stream: TcpStream, registry: &MetricsRegistry) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+
+    let (status, body) = if path == "/metrics" {
+        ("200 OK", registry.render())
+    } else {
+        ("404 Not Found", "Not Found\n".to_string())
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn sample_run() -> RunMetrics {
+        RunMetrics {
+            duration: Duration::from_millis(1500),
+            files_moved: 3,
+            bytes_moved: 3000,
+            files_blocked: 1,
+            errors: 1,
+            critical_warnings: 0,
+            blocked_placements: 2,
+            evictions: 4,
+            verify_throttle_time: Duration::from_secs(2),
+            tiers: vec![TierMetrics {
+                name: "cache".to_string(),
+                current_used: 500,
+                projected_used: 400,
+                total: 1000,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_render_before_first_run() {
+        let registry = MetricsRegistry::new();
+        let output = registry.render();
+
+        assert!(output.contains("tierflow_runs_total 0"));
+        assert!(!output.contains("tierflow_last_run_duration_seconds"));
+    }
+
+    #[test]
+    fn test_render_accumulates_runs() {
+        let registry = MetricsRegistry::new();
+        registry.record_run(sample_run());
+        registry.record_run(sample_run());
+        registry.record_failure();
+
+        let output = registry.render();
+        assert!(output.contains("tierflow_runs_total 3"));
+        assert!(output.contains("tierflow_failed_runs_total 1"));
+        assert!(output.contains("tierflow_files_moved_total 6"));
+        assert!(output.contains("tierflow_bytes_moved_total 6000"));
+        assert!(output.contains("tierflow_evictions_total 8"));
+        assert!(output.contains("tierflow_last_run_duration_seconds 1.5"));
+        assert!(output.contains("tierflow_last_run_blocked_placements 2"));
+        assert!(output.contains("tierflow_last_run_verify_throttle_seconds 2"));
+        assert!(output.contains("tierflow_tier_used_bytes{tier=\"cache\"} 500"));
+        assert!(output.contains("tierflow_tier_projected_used_bytes{tier=\"cache\"} 400"));
+    }
+
+    #[test]
+    fn test_merge_iterations() {
+        let mut run = sample_run();
+        let mut next = sample_run();
+        next.tiers[0].current_used = 400;
+        run.merge(next);
+
+        assert_eq!(run.files_moved, 6);
+        assert_eq!(run.evictions, 8);
+        assert_eq!(run.duration, Duration::from_secs(3));
+        assert_eq!(run.blocked_placements, 2);
+        assert_eq!(run.tiers[0].current_used, 400);
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label(r#"a"b\c"#), r#"a\"b\\c"#);
+    }
+
+    #[test]
+    fn test_serve_metrics() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let registry = Arc::new(MetricsRegistry::new());
+        registry.record_run(sample_run());
+        serve_metrics(addr, Arc::clone(&registry)).unwrap();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream
+            .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("tierflow_files_moved_total 3"));
+    }
+}
@@ -0,0 +1,106 @@
+use crate::config::{MovePolicyAction, MovePolicyConfig};
+
+const ANY_TIER: &str = "*";
+
+/// Single allow/deny rule for a (from, to) tier pair
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MovePolicy {
+    pub from: String,
+    pub to: String,
+    pub action: MovePolicyAction,
+}
+
+impl MovePolicy {
+    fn matches(&self, from_tier: &str, to_tier: &str) -> bool {
+        (self.from == ANY_TIER || self.from == from_tier)
+            && (self.to == ANY_TIER || self.to == to_tier)
+    }
+}
+
+impl From<MovePolicyConfig> for MovePolicy {
+    fn from(config: MovePolicyConfig) -> Self {
+        Self {
+            from: config.from,
+            to: config.to,
+            action: config.action,
+        }
+    }
+}
+
+/// Ordered set of move rules consulted during planning and eviction
+///
+/// The first rule matching a (from, to) pair decides; pairs without a
+/// matching rule are allowed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MovePolicies {
+    rules: Vec<MovePolicy>,
+}
+
+impl MovePolicies {
+    pub const fn new(rules: Vec<MovePolicy>) -> Self {
+        Self { rules }
+    }
+
+    pub fn is_allowed(&self, from_tier: &str, to_tier: &str) -> bool {
+        if from_tier == to_tier {
+            return true;
+        }
+
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(from_tier, to_tier))
+            .is_none_or(|rule| rule.action == MovePolicyAction::Allow)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(from: &str, to: &str, action: MovePolicyAction) -> MovePolicy {
+        MovePolicy {
+            from: from.to_string(),
+            to: to.to_string(),
+            action,
+        }
+    }
+
+    #[test]
+    fn test_empty_policies_allow_everything() {
+        let policies = MovePolicies::default();
+        assert!(policies.is_empty());
+        assert!(policies.is_allowed("landing", "archive"));
+    }
+
+    #[test]
+    fn test_deny_specific_pair() {
+        let policies = MovePolicies::new(vec![rule("landing", "archive", MovePolicyAction::Deny)]);
+
+        assert!(!policies.is_allowed("landing", "archive"));
+        assert!(policies.is_allowed("landing", "storage"));
+        assert!(policies.is_allowed("storage", "archive"));
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let policies = MovePolicies::new(vec![
+            rule("storage", "archive", MovePolicyAction::Allow),
+            rule("*", "archive", MovePolicyAction::Deny),
+        ]);
+
+        assert!(policies.is_allowed("storage", "archive"));
+        assert!(!policies.is_allowed("landing", "archive"));
+        assert!(!policies.is_allowed("cache", "archive"));
+    }
+
+    #[test]
+    fn test_same_tier_is_always_allowed() {
+        let policies = MovePolicies::new(vec![rule("*", "*", MovePolicyAction::Deny)]);
+        assert!(policies.is_allowed("cache", "cache"));
+        assert!(!policies.is_allowed("cache", "storage"));
+    }
+}
@@ -0,0 +1,421 @@
+use crate::config::HashAlgorithm;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+use xxhash_rust::xxh3::Xxh3;
+
+const CHUNK_SIZE: usize = 1024 * 1024;
+const BYTES_PER_MB: u64 = 1_000_000;
+
+/// Files larger than this are hashed as a hash of per-part hashes, so the parts
+/// can be hashed in parallel; fixed so the result doesn't depend on the thread count
+const PART_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Global hashing budget shared by every verification in a run
+///
+/// Caps total hashing throughput (`max_mbps`) and the number of files hashed
+/// at once (`max_threads`). Time spent waiting on either limit is accumulated
+/// and reported as throttle time.
+#[derive(Debug)]
+pub struct HashBudget {
+    bytes_per_sec: Option<u64>,
+    max_threads: Option<usize>,
+    active: Mutex<usize>,
+    slot_freed: Condvar,
+    next_slot: Mutex<Option<Instant>>,
+    throttled_nanos: AtomicU64,
+}
+
+impl HashBudget {
+    pub fn new(max_mbps: Option<u64>, max_threads: Option<usize>) -> Self {
+        Self {
+            bytes_per_sec: max_mbps.map(|mbps| mbps.saturating_mul(BYTES_PER_MB)),
+            max_threads,
+            active: Mutex::new(0),
+            slot_freed: Condvar::new(),
+            next_slot: Mutex::new(None),
+            throttled_nanos: AtomicU64::new(0),
+        }
+    }
+
+    pub fn unlimited() -> Self {
+        Self::new(None, None)
+    }
+
+    /// Total time hashing has been held back by this budget
+    pub fn throttled_time(&self) -> Duration {
+        Duration::from_nanos(self.throttled_nanos.load(Ordering::Relaxed))
+    }
+
+    fn acquire_thread(&self) -> ThreadPermit<'_> {
+        let mut active = lock(&self.active);
+        if let Some(max_threads) = self.max_threads {
+            let started = Instant::now();
+            while *active >= max_threads.max(1) {
+                active = self
+                    .slot_freed
+                    .wait(active)
+                    .unwrap_or_else(PoisonError::into_inner);
+            }
+            self.record_throttle(started.elapsed());
+        }
+        *active += 1;
+        ThreadPermit { budget: self }
+    }
+
+    /// Blocks until `bytes` fit into the throughput budget
+    fn consume(&self, bytes: usize) {
+        let Some(bytes_per_sec) = self.bytes_per_sec.filter(|&rate| rate > 0) else {
+            return;
+        };
+
+        let cost = Duration::from_secs_f64(bytes as f64 / bytes_per_sec as f64);
+        let now = Instant::now();
+        let start = {
+            let mut next_slot = lock(&self.next_slot);
+            let start = next_slot.map_or(now, |next| next.max(now));
+            *next_slot = Some(start + cost);
+            start
+        };
+
+        let wait = start.saturating_duration_since(now);
+        if !wait.is_zero() {
+            thread::sleep(wait);
+            self.record_throttle(wait);
+        }
+    }
+
+    fn record_throttle(&self, waited: Duration) {
+        let nanos = u64::try_from(waited.as_nanos()).unwrap_or(u64::MAX);
+        self.throttled_nanos.fetch_add(nanos, Ordering::Relaxed);
+    }
+}
+
+impl Default for HashBudget {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+struct ThreadPermit<'a> {
+    budget: &'a HashBudget,
+}
+
+impl Drop for ThreadPermit<'_> {
+    fn drop(&mut self) {
+        let mut active = lock(&self.budget.active);
+        *active = active.saturating_sub(1);
+        self.budget.slot_freed.notify_one();
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Digest of a file, tagged with the algorithm that produced it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checksum {
+    pub algorithm: HashAlgorithm,
+    digest: [u8; 32],
+}
+
+impl Checksum {
+    /// Digest bytes (8 for xxh3, 32 otherwise)
+    pub fn digest(&self) -> &[u8] {
+        let len = match self.algorithm {
+            HashAlgorithm::Xxh3 => 8,
+            HashAlgorithm::None => 0,
+            HashAlgorithm::Blake3 | HashAlgorithm::Sha256 => 32,
+        };
+        &self.digest[..len]
+    }
+}
+
+impl fmt::Display for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.algorithm)?;
+        self.digest().iter().try_for_each(|b| write!(f, "{b:02x}"))
+    }
+}
+
+/// Hashes files with one algorithm, splitting large files across threads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hasher {
+    algorithm: HashAlgorithm,
+    threads: usize,
+    part_size: u64,
+}
+
+impl Default for Hasher {
+    fn default() -> Self {
+        Self::new(HashAlgorithm::Xxh3)
+    }
+}
+
+impl Hasher {
+    pub const fn new(algorithm: HashAlgorithm) -> Self {
+        Self {
+            algorithm,
+            threads: 1,
+            part_size: PART_SIZE,
+        }
+    }
+
+    /// Hash parts of one large file on up to `threads` threads
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    pub const fn algorithm(&self) -> HashAlgorithm {
+        self.algorithm
+    }
+
+    /// Hashes `path` within the given budget
+    ///
+    /// Files up to 64 MiB get the plain digest of their content; larger ones the digest
+    /// of their 64 MiB parts' digests.
+    pub fn hash_file(&self, path: &Path, budget: &HashBudget) -> io::Result<Checksum> {
+        let _permit = budget.acquire_thread();
+        let len = File::open(path)?.metadata()?.len();
+        if len <= self.part_size {
+            return self.hash_part(path, 0, len, budget);
+        }
+
+        let parts = len.div_ceil(self.part_size) as usize;
+        let next = AtomicUsize::new(0);
+        let digests = Mutex::new(vec![None; parts]);
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.threads.min(parts))
+                .map(|_| {
+                    scope.spawn(|| -> io::Result<()> {
+                        loop {
+                            let part = next.fetch_add(1, Ordering::Relaxed);
+                            if part >= parts {
+                                return Ok(());
+                            }
+                            let offset = part as u64 * self.part_size;
+                            let size = self.part_size.min(len - offset);
+                            let digest = self.hash_part(path, offset, size, budget)?;
+                            lock(&digests)[part] = Some(digest);
+                        }
+                    })
+                })
+                .collect();
+            workers.into_iter().try_for_each(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|_| Err(io::Error::other("hashing thread panicked")))
+            })
+        })?;
+
+        let mut state = State::new(self.algorithm);
+        for digest in digests.into_inner().unwrap_or_else(PoisonError::into_inner) {
+            let digest = digest.ok_or_else(|| io::Error::other("file part was not hashed"))?;
+            state.update(digest.digest());
+        }
+        Ok(state.finish())
+    }
+
+    /// Hashes `len` bytes of `path` starting at `offset`
+    fn hash_part(
+        &self,
+        path: &Path,
+        offset: u64,
+        len: u64,
+        budget: &HashBudget,
+    ) -> io::Result<Checksum> {
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut file = file.take(len);
+        let mut state = State::new(self.algorithm);
+        let mut buffer = vec![0u8; CHUNK_SIZE];
+
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            budget.consume(read);
+            state.update(&buffer[..read]);
+        }
+
+        Ok(state.finish())
+    }
+}
+
+enum State {
+    Xxh3(Box<Xxh3>),
+    Blake3(Box<blake3::Hasher>),
+    Sha256(Sha256),
+    None,
+}
+
+impl State {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Xxh3 => Self::Xxh3(Box::new(Xxh3::new())),
+            HashAlgorithm::Blake3 => Self::Blake3(Box::new(blake3::Hasher::new())),
+            HashAlgorithm::Sha256 => Self::Sha256(Sha256::new()),
+            HashAlgorithm::None => Self::None,
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Xxh3(hasher) => hasher.update(data),
+            Self::Blake3(hasher) => {
+                hasher.update(data);
+            }
+            Self::Sha256(hasher) => hasher.update(data),
+            Self::None => {}
+        }
+    }
+
+    fn finish(self) -> Checksum {
+        let mut digest = [0u8; 32];
+        let algorithm = match self {
+            Self::Xxh3(hasher) => {
+                digest[..8].copy_from_slice(&hasher.digest().to_be_bytes());
+                HashAlgorithm::Xxh3
+            }
+            Self::Blake3(hasher) => {
+                digest.copy_from_slice(hasher.finalize().as_bytes());
+                HashAlgorithm::Blake3
+            }
+            Self::Sha256(hasher) => {
+                digest.copy_from_slice(&hasher.finalize());
+                HashAlgorithm::Sha256
+            }
+            Self::None => HashAlgorithm::None,
+        };
+        Checksum { algorithm, digest }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_hash_file_matches_for_identical_content() {
+        let temp = TempDir::new().unwrap();
+        let a = temp.path().join("a.bin");
+        let b = temp.path().join("b.bin");
+        let c = temp.path().join("c.bin");
+        std::fs::write(&a, b"same content").unwrap();
+        std::fs::write(&b, b"same content").unwrap();
+        std::fs::write(&c, b"other content").unwrap();
+
+        let budget = HashBudget::unlimited();
+        assert_eq!(
+            Hasher::default().hash_file(&a, &budget).unwrap(),
+            Hasher::default().hash_file(&b, &budget).unwrap()
+        );
+        assert_ne!(
+            Hasher::default().hash_file(&a, &budget).unwrap(),
+            Hasher::default().hash_file(&c, &budget).unwrap()
+        );
+        assert_eq!(budget.throttled_time(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_algorithms_produce_standard_digests() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("abc.txt");
+        std::fs::write(&path, b"abc").unwrap();
+        let budget = HashBudget::unlimited();
+        let hash = |algorithm| {
+            Hasher::new(algorithm)
+                .hash_file(&path, &budget)
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(
+            hash(HashAlgorithm::Sha256),
+            "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hash(HashAlgorithm::Blake3),
+            "blake3:6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+        );
+        assert_eq!(hash(HashAlgorithm::Xxh3).len(), "xxh3:".len() + 16);
+    }
+
+    #[test]
+    fn test_parallel_parts_match_sequential() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("large.bin");
+        let content: Vec<u8> = (0..10_000u32).map(|i| (i % 253) as u8).collect();
+        std::fs::write(&path, &content).unwrap();
+        let budget = HashBudget::unlimited();
+        let hasher = |threads| Hasher {
+            part_size: 1024,
+            ..Hasher::new(HashAlgorithm::Blake3).with_threads(threads)
+        };
+
+        let sequential = hasher(1).hash_file(&path, &budget).unwrap();
+        let parallel = hasher(4).hash_file(&path, &budget).unwrap();
+
+        assert_eq!(sequential, parallel);
+        // Hash of part hashes, not of the content
+        assert_ne!(
+            sequential,
+            Hasher::new(HashAlgorithm::Blake3)
+                .hash_file(&path, &budget)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_hash_file_missing() {
+        let budget = HashBudget::unlimited();
+        assert!(
+            Hasher::default()
+                .hash_file(Path::new("/nonexistent/file.bin"), &budget)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_throughput_limit_records_throttle_time() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("data.bin");
+        // 3 chunks at 10 MB/s: the second and third chunk have to wait
+        std::fs::write(&path, vec![0u8; 3 * CHUNK_SIZE]).unwrap();
+
+        let budget = HashBudget::new(Some(10), None);
+        Hasher::default().hash_file(&path, &budget).unwrap();
+
+        assert!(budget.throttled_time() >= Duration::from_millis(150));
+    }
+
+    #[test]
+    fn test_thread_limit_serializes_hashing() {
+        let budget = Arc::new(HashBudget::new(None, Some(1)));
+        let first = budget.acquire_thread();
+
+        let waiter = {
+            let budget = Arc::clone(&budget);
+            thread::spawn(move || {
+                let _permit = budget.acquire_thread();
+            })
+        };
+
+        thread::sleep(Duration::from_millis(50));
+        drop(first);
+        waiter.join().unwrap();
+
+        assert!(budget.throttled_time() >= Duration::from_millis(40));
+    }
+}
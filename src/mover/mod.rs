@@ -1,7 +1,20 @@
+mod hasher;
+pub mod native;
+mod progress;
+
+pub use hasher::{Checksum, HashBudget, Hasher};
+pub use progress::{CopyProgress, ProgressMover, TransferProgress, parse_rsync_progress};
+
+use crate::config::{ConflictPolicy, NativeCopyConfig, PreserveConfig, VerificationMode};
+use crate::remote::{self, RemoteTransfer, SshRemote};
+use crate::trash::Trash;
 use std::fs::{self, OpenOptions};
-use std::io;
-use std::path::Path;
-use std::process::{self, Command};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
 /// Trait for moving files between tiers
 /// Different implementations can use rsync, cp, mv, etc.
@@ -14,30 +27,319 @@ pub trait Mover {
     ///
     /// # Errors
     /// Returns `io::Error` if operation fails
-    fn move_file(&self, source: &Path, destination: &Path) -> io::Result<()>;
+    fn move_file(&self, source: &Path, destination: &Path) -> io::Result<Verification>;
+
+    /// Move file, reporting copy progress to `on_progress` while it runs
+    ///
+    /// Movers that cannot report progress just call `move_file`.
+    fn move_file_with_progress(
+        &self,
+        source: &Path,
+        destination: &Path,
+        on_progress: &mut dyn FnMut(CopyProgress),
+    ) -> io::Result<Verification> {
+        let _ = on_progress;
+        self.move_file(source, destination)
+    }
+
+    /// Copy file, leaving the source in place
+    fn copy_file(&self, source: &Path, destination: &Path) -> io::Result<Verification> {
+        self.copy_file_with_progress(source, destination, &mut |_| {})
+    }
+
+    /// Copy file, reporting progress; movers that cannot copy return `Unsupported`
+    fn copy_file_with_progress(
+        &self,
+        source: &Path,
+        destination: &Path,
+        on_progress: &mut dyn FnMut(CopyProgress),
+    ) -> io::Result<Verification> {
+        let _ = (destination, on_progress);
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("This mover cannot copy {}", source.display()),
+        ))
+    }
+
+    /// Move or copy a file to or from a tier on an SSH host, reporting progress
+    ///
+    /// Movers that cannot reach other hosts return `Unsupported`.
+    fn transfer_remote(
+        &self,
+        transfer: &RemoteTransfer<'_>,
+        on_progress: &mut dyn FnMut(CopyProgress),
+    ) -> io::Result<Verification> {
+        let _ = on_progress;
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "This mover cannot move {} to another host",
+                transfer.source.display()
+            ),
+        ))
+    }
+
+    /// Total time post-copy verification has been throttled by the hashing budget
+    fn verify_throttle_time(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    /// Whether moves only get logged, so the disks never change
+    fn is_dry_run(&self) -> bool {
+        false
+    }
+}
+
+/// How a completed move was verified
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verification {
+    /// Nothing beyond the copy tool's exit status
+    None,
+    /// Copy size matched the source
+    Size,
+    /// Copy content matched the source
+    Checksum(Checksum),
+}
+
+impl std::fmt::Display for Verification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Size => write!(f, "size"),
+            Self::Checksum(checksum) => write!(f, "{checksum}"),
+        }
+    }
+}
+
+/// A move left undone because its destination already holds a different file
+/// (`on_conflict: skip`); carried inside an `io::ErrorKind::AlreadyExists` error
+#[derive(Debug)]
+pub struct ConflictSkipped {
+    pub destination: PathBuf,
+}
+
+impl std::fmt::Display for ConflictSkipped {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Destination already exists and differs: {}",
+            self.destination.display()
+        )
+    }
+}
+
+impl std::error::Error for ConflictSkipped {}
+
+impl ConflictSkipped {
+    /// Whether `err` is a move skipped by the conflict policy
+    pub fn is_skip(err: &io::Error) -> bool {
+        matches!(err.get_ref(), Some(inner) if inner.is::<Self>())
+    }
+}
+
+/// rsync exited with a non-zero status; carried inside an `io::ErrorKind::Other` error
+#[derive(Debug)]
+pub struct RsyncFailed {
+    pub code: Option<i32>,
+}
+
+impl std::fmt::Display for RsyncFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rsync failed with exit code {:?}", self.code)
+    }
+}
+
+impl std::error::Error for RsyncFailed {}
+
+impl RsyncFailed {
+    /// Socket and protocol I/O errors and timeouts, which often pass on their own
+    pub const fn is_transient(&self) -> bool {
+        matches!(self.code, Some(10 | 12 | 30 | 35))
+    }
+}
+
+/// A copy that didn't match its source; carried inside an `io::ErrorKind::Other` error
+#[derive(Debug)]
+pub struct VerificationFailed {
+    pub reason: String,
+}
+
+impl std::fmt::Display for VerificationFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.reason)
+    }
+}
+
+impl std::error::Error for VerificationFailed {}
+
+impl VerificationFailed {
+    fn error(reason: String) -> io::Error {
+        io::Error::other(Self { reason })
+    }
 }
 
 /// `DryRun` implementation - only logs operations without actual movement
 pub struct DryRunMover;
 
+/// Tool that copies a file to its temporary name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Copier {
+    Rsync,
+    /// [`native::copy_file`], without spawning a process per file
+    Native(NativeCopyConfig),
+}
+
 /// Rsync-based mover for actual file movement
 /// Trusts rsync's built-in integrity verification (rolling checksums)
 pub struct RsyncMover {
+    copier: Copier,
     /// Additional rsync arguments (e.g., bandwidth limiting)
     extra_args: Vec<String>,
+    /// Checks done before the source is removed
+    verification: VerificationMode,
+    /// Algorithm and threads used by hash verification
+    hasher: Hasher,
+    /// Budget shared by every hash verification
+    hash_budget: Arc<HashBudget>,
+    /// Metadata carried over to the destination
+    preserve: PreserveConfig,
+    /// Where sources go instead of being deleted
+    trash: Option<Trash>,
+    /// Handling of a different file already at the destination
+    on_conflict: ConflictPolicy,
 }
 
 impl RsyncMover {
     /// Create a new `RsyncMover`
     pub fn new() -> Self {
-        Self {
-            extra_args: Vec::new(),
-        }
+        Self::with_args(Vec::new())
     }
 
     /// Create a new `RsyncMover` with custom rsync arguments
     pub fn with_args(args: Vec<String>) -> Self {
-        Self { extra_args: args }
+        Self {
+            copier: Copier::Rsync,
+            extra_args: args,
+            verification: VerificationMode::SizeOnly,
+            hasher: Hasher::default(),
+            hash_budget: Arc::new(HashBudget::unlimited()),
+            preserve: PreserveConfig::default(),
+            trash: None,
+            on_conflict: ConflictPolicy::Backup,
+        }
+    }
+
+    /// Copy in-process with [`native::copy_file`] instead of rsync; the rest of the
+    /// move (conflicts, verification, trash) is unchanged and rsync arguments are ignored
+    pub const fn with_native_copy(mut self, options: NativeCopyConfig) -> Self {
+        self.copier = Copier::Native(options);
+        self
+    }
+
+    /// Set what happens when a different file already exists at the destination
+    pub const fn with_conflict_policy(mut self, on_conflict: ConflictPolicy) -> Self {
+        self.on_conflict = on_conflict;
+        self
+    }
+
+    /// Set which file metadata is kept on the destination
+    pub const fn with_preserve(mut self, preserve: PreserveConfig) -> Self {
+        self.preserve = preserve;
+        self
+    }
+
+    /// Move sources into `trash` instead of deleting them
+    pub fn with_trash(mut self, trash: Trash) -> Self {
+        self.trash = Some(trash);
+        self
+    }
+
+    /// Deletes the moved source, or trashes it when a trash is set
+    fn remove_source(&self, source: &Path) -> io::Result<()> {
+        match &self.trash {
+            Some(trash) => trash.discard(source),
+            None => fs::remove_file(source),
+        }
+    }
+
+    /// rsync flags for the metadata kept by this mover
+    fn preserve_args(&self) -> Vec<&'static str> {
+        let preserve = &self.preserve;
+        [
+            (preserve.mode, &["--perms"][..]),
+            (preserve.owner, &["--owner", "--group"][..]),
+            (preserve.xattrs, &["--xattrs"][..]),
+            (preserve.acls, &["--acls"][..]),
+            (preserve.times, &["--times"][..]),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .flat_map(|(_, flags)| flags.iter().copied())
+        .collect()
+    }
+
+    /// Set how copies are checked before the source is removed
+    pub const fn with_verification(mut self, verification: VerificationMode) -> Self {
+        self.verification = verification;
+        self
+    }
+
+    /// Set the hash algorithm and per-file threads used by verification
+    pub const fn with_hasher(mut self, hasher: Hasher) -> Self {
+        self.hasher = hasher;
+        self
+    }
+
+    /// Share `budget` across all hash verifications
+    pub fn with_hash_budget(mut self, budget: Arc<HashBudget>) -> Self {
+        self.hash_budget = budget;
+        self
+    }
+}
+
+impl RsyncMover {
+    /// Copies `source` to `target` with the configured copier
+    fn copy(
+        &self,
+        source: &Path,
+        target: &Path,
+        on_progress: &mut dyn FnMut(CopyProgress),
+    ) -> io::Result<()> {
+        if let Copier::Native(options) = self.copier {
+            return native::copy_file(source, target, &self.preserve, options, on_progress)
+                .map(|_| ());
+        }
+
+        let mut cmd = Command::new("rsync");
+
+        cmd.args(self.preserve_args()).arg("--info=progress2");
+
+        for arg in &self.extra_args {
+            cmd.arg(arg);
+        }
+
+        cmd.arg(source.as_os_str()).arg(target.as_os_str());
+        // Keep Ctrl+C in the terminal from killing the copy; the executor stops after it
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+
+        // Execute rsync - stream stdout for progress instead of buffering it,
+        // stderr goes straight through
+        let total_bytes = fs::metadata(source)?.len();
+        let status = cmd.stdout(Stdio::piped()).spawn().and_then(|mut child| {
+            if let Some(stdout) = child.stdout.take() {
+                report_rsync_progress(stdout, total_bytes, on_progress);
+            }
+            child.wait()
+        })?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(io::Error::other(RsyncFailed {
+                code: status.code(),
+            }))
+        }
     }
 }
 
@@ -48,7 +350,226 @@ impl Default for RsyncMover {
 }
 
 impl Mover for RsyncMover {
-    fn move_file(&self, source: &Path, destination: &Path) -> io::Result<()> {
+    fn move_file(&self, source: &Path, destination: &Path) -> io::Result<Verification> {
+        self.move_file_with_progress(source, destination, &mut |_| {})
+    }
+
+    fn move_file_with_progress(
+        &self,
+        source: &Path,
+        destination: &Path,
+        on_progress: &mut dyn FnMut(CopyProgress),
+    ) -> io::Result<Verification> {
+        self.transfer(source, destination, on_progress, false)
+    }
+
+    fn copy_file_with_progress(
+        &self,
+        source: &Path,
+        destination: &Path,
+        on_progress: &mut dyn FnMut(CopyProgress),
+    ) -> io::Result<Verification> {
+        self.transfer(source, destination, on_progress, true)
+    }
+
+    fn transfer_remote(
+        &self,
+        transfer: &RemoteTransfer<'_>,
+        on_progress: &mut dyn FnMut(CopyProgress),
+    ) -> io::Result<Verification> {
+        self.transfer_with_host(transfer, on_progress)
+    }
+
+    fn verify_throttle_time(&self) -> Duration {
+        self.hash_budget.throttled_time()
+    }
+}
+
+impl RsyncMover {
+    /// [`Self::transfer`] with one side on an SSH host: the same conflict
+    /// policy, size verification, atomic rename and source removal, run
+    /// through `ssh` and rsync's remote syntax
+    ///
+    /// Content hashing needs both files locally; config validation rejects
+    /// checksum verification together with remote tiers.
+    fn transfer_with_host(
+        &self,
+        transfer: &RemoteTransfer<'_>,
+        on_progress: &mut dyn FnMut(CopyProgress),
+    ) -> io::Result<Verification> {
+        let RemoteTransfer {
+            from,
+            to,
+            source,
+            destination,
+            copy,
+            ..
+        } = *transfer;
+        transfer.remote()?;
+
+        let Some(source_stamp) = remote::stat_on(from, source)? else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Source file does not exist: {}", source.display()),
+            ));
+        };
+
+        let mut backup_path = None;
+        if let Some(destination_stamp) = remote::stat_on(to, destination)? {
+            if destination_stamp == source_stamp {
+                tracing::info!(
+                    "Destination already exists with same size/mtime: {} - skipping copy",
+                    destination.display()
+                );
+                if !copy {
+                    self.remove_source_on(from, source)?;
+                }
+                return Ok(Verification::Size);
+            }
+            match self.on_conflict {
+                ConflictPolicy::Backup => {
+                    let candidate_backup_path = backup_path_for(destination);
+                    tracing::warn!(
+                        "Destination already exists but differs: {} -> Backing up to: {}",
+                        destination.display(),
+                        candidate_backup_path.display()
+                    );
+                    remote::rename_on(to, destination, &candidate_backup_path)?;
+                    backup_path = Some(candidate_backup_path);
+                }
+                ConflictPolicy::Skip => {
+                    tracing::warn!(
+                        "Destination already exists but differs: {} - skipping (on_conflict: skip)",
+                        destination.display()
+                    );
+                    return Err(io::Error::new(
+                        io::ErrorKind::AlreadyExists,
+                        ConflictSkipped {
+                            destination: destination.to_path_buf(),
+                        },
+                    ));
+                }
+                ConflictPolicy::Fail => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::AlreadyExists,
+                        format!(
+                            "Destination already exists and differs: {} (on_conflict: fail)",
+                            destination.display()
+                        ),
+                    ));
+                }
+                ConflictPolicy::Overwrite => {
+                    tracing::warn!(
+                        "Destination already exists but differs: {} - overwriting",
+                        destination.display()
+                    );
+                }
+            }
+        }
+
+        let temp_destination = partial_path(destination);
+        // Drops the partial copy and puts a backed up destination back
+        let abort = |err: io::Error| {
+            if let Err(e) = remote::remove_on(to, &temp_destination)
+                && e.kind() != io::ErrorKind::NotFound
+            {
+                tracing::warn!("Failed to remove {}: {e}", temp_destination.display());
+            }
+            if let Some(backup_path) = &backup_path
+                && let Err(e) = remote::rename_on(to, backup_path, destination)
+            {
+                tracing::error!(
+                    "Failed to restore destination backup {} -> {}: {e}",
+                    backup_path.display(),
+                    destination.display()
+                );
+            }
+            err
+        };
+
+        tracing::info!(
+            "Copying file: {} -> {}",
+            source.display(),
+            destination.display()
+        );
+        transfer
+            .copy_to(
+                &temp_destination,
+                &self.preserve_args(),
+                &self.extra_args,
+                on_progress,
+            )
+            .map_err(abort)?;
+
+        let verification = if self.verification == VerificationMode::None {
+            Verification::None
+        } else {
+            match remote::stat_on(to, &temp_destination) {
+                Ok(Some((size, _))) if size == source_stamp.0 => Verification::Size,
+                Ok(copied) => {
+                    return Err(abort(VerificationFailed::error(format!(
+                        "File size mismatch after copy: source={} bytes, dest={} bytes",
+                        source_stamp.0,
+                        copied.map_or(0, |(size, _)| size)
+                    ))));
+                }
+                Err(e) => return Err(abort(e)),
+            }
+        };
+
+        match remote::stat_on(from, source) {
+            Ok(Some(stamp)) if stamp == source_stamp => {}
+            Ok(_) => {
+                return Err(abort(io::Error::new(
+                    io::ErrorKind::ResourceBusy,
+                    format!(
+                        "Source file was modified during copy. Stale copy removed: {}",
+                        temp_destination.display()
+                    ),
+                )));
+            }
+            Err(e) => return Err(abort(e)),
+        }
+
+        remote::rename_on(to, &temp_destination, destination).map_err(abort)?;
+
+        if copy {
+            tracing::info!(
+                "Successfully copied: {} -> {}",
+                source.display(),
+                destination.display()
+            );
+            return Ok(verification);
+        }
+        self.remove_source_on(from, source)?;
+        tracing::info!(
+            "Successfully moved: {} -> {}",
+            source.display(),
+            destination.display()
+        );
+        Ok(verification)
+    }
+
+    /// [`Self::remove_source`] for a source that may be on `host`
+    ///
+    /// Config validation rejects a trash together with remote tiers.
+    fn remove_source_on(&self, host: Option<&SshRemote>, source: &Path) -> io::Result<()> {
+        match host {
+            Some(host) => host.remove_file(source),
+            None => self.remove_source(source),
+        }
+    }
+}
+
+impl RsyncMover {
+    /// Copies `source` to `destination`, then removes the source unless `keep_source`
+    fn transfer(
+        &self,
+        source: &Path,
+        destination: &Path,
+        on_progress: &mut dyn FnMut(CopyProgress),
+        keep_source: bool,
+    ) -> io::Result<Verification> {
         // Check if source exists
         if !source.exists() {
             return Err(io::Error::new(
@@ -57,7 +578,9 @@ impl Mover for RsyncMover {
             ));
         }
 
-        ensure_source_parent_writable(source)?;
+        if !keep_source {
+            ensure_source_parent_writable(source)?;
+        }
 
         let mut backup_path = None;
 
@@ -75,32 +598,55 @@ impl Mover for RsyncMover {
                     "Destination already exists with same size/mtime: {} - skipping copy",
                     destination.display()
                 );
-                fs::remove_file(source)?;
-                return Ok(());
+                if !keep_source {
+                    self.remove_source(source)?;
+                }
+                return Ok(Verification::Size);
             }
 
-            // Files are different - backup destination with timestamp
-            let timestamp = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .map_or(0, |d| d.as_secs());
-
-            let candidate_backup_path = destination.with_extension(format!(
-                "{}.backup-{}",
-                destination
-                    .extension()
-                    .unwrap_or_default()
-                    .to_string_lossy(),
-                timestamp
-            ));
+            match self.on_conflict {
+                ConflictPolicy::Backup => {
+                    // Files are different - backup destination with timestamp
+                    let candidate_backup_path = backup_path_for(destination);
 
-            tracing::warn!(
-                "Destination already exists but differs: {} -> Backing up to: {}",
-                destination.display(),
-                candidate_backup_path.display()
-            );
+                    tracing::warn!(
+                        "Destination already exists but differs: {} -> Backing up to: {}",
+                        destination.display(),
+                        candidate_backup_path.display()
+                    );
 
-            fs::rename(destination, &candidate_backup_path)?;
-            backup_path = Some(candidate_backup_path);
+                    fs::rename(destination, &candidate_backup_path)?;
+                    backup_path = Some(candidate_backup_path);
+                }
+                ConflictPolicy::Skip => {
+                    tracing::warn!(
+                        "Destination already exists but differs: {} - skipping (on_conflict: skip)",
+                        destination.display()
+                    );
+                    return Err(io::Error::new(
+                        io::ErrorKind::AlreadyExists,
+                        ConflictSkipped {
+                            destination: destination.to_path_buf(),
+                        },
+                    ));
+                }
+                ConflictPolicy::Fail => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::AlreadyExists,
+                        format!(
+                            "Destination already exists and differs: {} (on_conflict: fail)",
+                            destination.display()
+                        ),
+                    ));
+                }
+                ConflictPolicy::Overwrite => {
+                    // The atomic rename below replaces the existing file
+                    tracing::warn!(
+                        "Destination already exists but differs: {} - overwriting",
+                        destination.display()
+                    );
+                }
+            }
         }
 
         // Ensure destination directory exists
@@ -110,24 +656,7 @@ impl Mover for RsyncMover {
 
         // Step 1: Copy file to temporary name (atomic rename pattern)
         // This prevents other processes (Tdarr, Plex, etc.) from accessing incomplete files
-        let temp_destination = destination.with_extension(format!(
-            "{}.partial",
-            destination
-                .extension()
-                .unwrap_or_default()
-                .to_string_lossy()
-        ));
-
-        let mut cmd = Command::new("rsync");
-
-        cmd.arg("--times");
-
-        for arg in &self.extra_args {
-            cmd.arg(arg);
-        }
-
-        cmd.arg(source.as_os_str())
-            .arg(temp_destination.as_os_str());
+        let temp_destination = partial_path(destination);
 
         tracing::info!(
             "Copying file: {} -> {}",
@@ -135,20 +664,9 @@ impl Mover for RsyncMover {
             destination.display()
         );
 
-        // Execute rsync - use status() instead of output() to avoid buffering
-        // large amounts of stdout/stderr in memory for big files
-        let status = match cmd.status() {
-            Ok(status) => status,
-            Err(err) => {
-                remove_file_if_exists(&temp_destination);
-                restore_destination_backup(destination, backup_path.as_deref());
-                return Err(err);
-            }
-        };
-
-        if !status.success() {
+        if let Err(err) = self.copy(source, &temp_destination, on_progress) {
             tracing::error!(
-                "Rsync failed for {} -> {}",
+                "Copy failed for {} -> {}: {err}",
                 source.display(),
                 destination.display()
             );
@@ -156,10 +674,7 @@ impl Mover for RsyncMover {
             remove_file_if_exists(&temp_destination);
             restore_destination_backup(destination, backup_path.as_deref());
 
-            return Err(io::Error::other(format!(
-                "rsync failed with exit code {:?}",
-                status.code()
-            )));
+            return Err(err);
         }
 
         // Step 2: Verify the temporary file was copied correctly
@@ -175,17 +690,35 @@ impl Mover for RsyncMover {
         let source_metadata = fs::metadata(source)?;
         let dest_metadata = fs::metadata(&temp_destination)?;
 
-        if source_metadata.len() != dest_metadata.len() {
+        if self.verification != VerificationMode::None
+            && source_metadata.len() != dest_metadata.len()
+        {
             // Try to clean up the incomplete copy
             remove_file_if_exists(&temp_destination);
             restore_destination_backup(destination, backup_path.as_deref());
-            return Err(io::Error::other(format!(
+            return Err(VerificationFailed::error(format!(
                 "File size mismatch after copy: source={} bytes, dest={} bytes",
                 source_metadata.len(),
                 dest_metadata.len()
             )));
         }
 
+        // Step 3b: Optional content verification within the hashing budget
+        let verification = match self.verification {
+            VerificationMode::None => Verification::None,
+            VerificationMode::SizeOnly => Verification::Size,
+            VerificationMode::ChecksumOnce | VerificationMode::Paranoid => {
+                match verify_copy(source, &temp_destination, &self.hasher, &self.hash_budget) {
+                    Ok(hash) => Verification::Checksum(hash),
+                    Err(err) => {
+                        remove_file_if_exists(&temp_destination);
+                        restore_destination_backup(destination, backup_path.as_deref());
+                        return Err(err);
+                    }
+                }
+            }
+        };
+
         // Step 4: Verify source file hasn't been modified during copy
         // (Protection against concurrent modifications - check both size and mtime)
         let source_metadata_after = fs::metadata(source)?;
@@ -203,10 +736,13 @@ impl Mover for RsyncMover {
             // Clean up the stale temporary copy since source was modified
             remove_file_if_exists(&temp_destination);
             restore_destination_backup(destination, backup_path.as_deref());
-            return Err(io::Error::other(format!(
-                "Source file was modified during copy. Stale copy removed: {}",
-                temp_destination.display()
-            )));
+            return Err(io::Error::new(
+                io::ErrorKind::ResourceBusy,
+                format!(
+                    "Source file was modified during copy. Stale copy removed: {}",
+                    temp_destination.display()
+                ),
+            ));
         }
 
         // Step 5: Atomic rename from .partial to final name
@@ -222,8 +758,38 @@ impl Mover for RsyncMover {
             return Err(err);
         }
 
-        // Step 6: Only now, after atomic rename, remove the source
-        fs::remove_file(source)?;
+        // Step 5b: Paranoid mode re-reads the final destination before the source goes away
+        if self.verification == VerificationMode::Paranoid
+            && let Verification::Checksum(source_hash) = verification
+        {
+            let final_hash = self.hasher.hash_file(destination, &self.hash_budget).and_then(|final_hash| {
+                if final_hash == source_hash {
+                    Ok(final_hash)
+                } else {
+                    Err(VerificationFailed::error(format!(
+                        "Checksum mismatch after rename: source={source_hash}, destination={final_hash} ({})",
+                        destination.display()
+                    )))
+                }
+            });
+            if let Err(err) = final_hash {
+                remove_file_if_exists(destination);
+                restore_destination_backup(destination, backup_path.as_deref());
+                return Err(err);
+            }
+        }
+
+        if keep_source {
+            tracing::info!(
+                "Successfully copied: {} -> {}",
+                source.display(),
+                destination.display()
+            );
+            return Ok(verification);
+        }
+
+        // Step 6: Only now, after atomic rename, remove (or trash) the source
+        self.remove_source(source)?;
 
         // Step 7: Clean up empty parent directories
         // Walk up the directory tree and remove empty directories
@@ -267,10 +833,72 @@ impl Mover for RsyncMover {
             destination.display()
         );
 
-        Ok(())
+        Ok(verification)
     }
 }
 
+/// Feeds rsync `--info=progress2` output to `on_progress` until stdout closes
+///
+/// rsync rewrites its progress line with `\r`, so both `\r` and `\n` end a line.
+pub(crate) fn report_rsync_progress(
+    mut stdout: impl Read,
+    total_bytes: u64,
+    on_progress: &mut dyn FnMut(CopyProgress),
+) {
+    let mut buffer = [0u8; 4096];
+    let mut line = Vec::new();
+
+    while let Ok(read) = stdout.read(&mut buffer) {
+        if read == 0 {
+            break;
+        }
+        for &byte in &buffer[..read] {
+            if byte != b'\r' && byte != b'\n' {
+                line.push(byte);
+                continue;
+            }
+            if let Some((bytes_copied, bytes_per_sec)) =
+                parse_rsync_progress(&String::from_utf8_lossy(&line))
+            {
+                on_progress(CopyProgress {
+                    bytes_copied,
+                    total_bytes,
+                    bytes_per_sec,
+                });
+            }
+            line.clear();
+        }
+    }
+}
+
+/// Hashes source and copy in parallel and fails on mismatch
+fn verify_copy(
+    source: &Path,
+    copy: &Path,
+    hasher: &Hasher,
+    budget: &HashBudget,
+) -> io::Result<Checksum> {
+    let (source_hash, copy_hash) = thread::scope(|scope| {
+        let source_hash = scope.spawn(|| hasher.hash_file(source, budget));
+        let copy_hash = hasher.hash_file(copy, budget);
+        let source_hash = source_hash
+            .join()
+            .unwrap_or_else(|_| Err(io::Error::other("source hashing thread panicked")));
+        (source_hash, copy_hash)
+    });
+    let (source_hash, copy_hash) = (source_hash?, copy_hash?);
+
+    if source_hash != copy_hash {
+        return Err(VerificationFailed::error(format!(
+            "Checksum mismatch after copy: source={source_hash}, copy={copy_hash} ({})",
+            copy.display()
+        )));
+    }
+
+    tracing::debug!("Verified copy of {} ({source_hash})", source.display());
+    Ok(source_hash)
+}
+
 fn ensure_source_parent_writable(source: &Path) -> io::Result<()> {
     let parent = source.parent().ok_or_else(|| {
         io::Error::new(
@@ -324,6 +952,31 @@ fn ensure_source_parent_writable(source: &Path) -> io::Result<()> {
     })
 }
 
+/// Where `on_conflict: backup` sets aside a different file found at `destination`
+fn backup_path_for(destination: &Path) -> PathBuf {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    destination.with_extension(format!(
+        "{}.backup-{timestamp}",
+        destination
+            .extension()
+            .unwrap_or_default()
+            .to_string_lossy()
+    ))
+}
+
+/// Temporary name a copy is written to before the atomic rename
+pub fn partial_path(destination: &Path) -> PathBuf {
+    destination.with_extension(format!(
+        "{}.partial",
+        destination
+            .extension()
+            .unwrap_or_default()
+            .to_string_lossy()
+    ))
+}
+
 fn remove_file_if_exists(path: &Path) {
     match fs::remove_file(path) {
         Ok(()) => {}
@@ -354,19 +1007,38 @@ fn restore_destination_backup(destination: &Path, backup_path: Option<&Path>) {
 }
 
 impl Mover for DryRunMover {
-    fn move_file(&self, source: &Path, destination: &Path) -> io::Result<()> {
+    fn move_file(&self, source: &Path, destination: &Path) -> io::Result<Verification> {
         tracing::info!(
             "[DRY-RUN] Would move: {} -> {}",
             source.display(),
             destination.display()
         );
-        Ok(())
+        Ok(Verification::None)
+    }
+
+    fn copy_file_with_progress(
+        &self,
+        source: &Path,
+        destination: &Path,
+        _on_progress: &mut dyn FnMut(CopyProgress),
+    ) -> io::Result<Verification> {
+        tracing::info!(
+            "[DRY-RUN] Would copy: {} -> {}",
+            source.display(),
+            destination.display()
+        );
+        Ok(Verification::None)
+    }
+
+    fn is_dry_run(&self) -> bool {
+        true
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::HashAlgorithm;
     use std::fs;
     use std::path::PathBuf;
     use tempfile::TempDir;
@@ -378,7 +1050,7 @@ mod tests {
         let dest = PathBuf::from("/dest/file.txt");
 
         let result = mover.move_file(&source, &dest);
-        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Verification::None);
     }
 
     #[test]
@@ -401,6 +1073,20 @@ mod tests {
         assert!(mover.move_file(&source, &dest).is_ok());
     }
 
+    #[test]
+    fn test_report_rsync_progress_splits_carriage_returns() {
+        let output: &[u8] =
+            b"      1,024  10%    1.00kB/s    0:00:09\r     10,240 100%    2.00kB/s    0:00:00 (xfr#1, to-chk=0/1)\n";
+        let mut reports = Vec::new();
+
+        report_rsync_progress(output, 10_240, &mut |progress| reports.push(progress));
+
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].bytes_copied, 1024);
+        assert_eq!(reports[0].bytes_per_sec, 1024);
+        assert_eq!(reports[1].percent(), 100);
+    }
+
     #[test]
     fn test_rsync_mover_new() {
         let mover = RsyncMover::new();
@@ -420,6 +1106,27 @@ mod tests {
         assert!(mover.extra_args.is_empty());
     }
 
+    #[test]
+    fn test_rsync_mover_preserve_args() {
+        let mover = RsyncMover::new();
+        assert_eq!(
+            mover.preserve_args(),
+            vec![
+                "--perms", "--owner", "--group", "--xattrs", "--acls", "--times"
+            ]
+        );
+
+        let mover = RsyncMover::new().with_preserve(PreserveConfig {
+            owner: false,
+            acls: false,
+            ..PreserveConfig::default()
+        });
+        assert_eq!(
+            mover.preserve_args(),
+            vec!["--perms", "--xattrs", "--times"]
+        );
+    }
+
     #[test]
     #[cfg(unix)]
     fn test_rsync_mover_rejects_unwritable_source_parent_before_destination_changes() {
@@ -489,6 +1196,95 @@ mod tests {
         assert_eq!(content, "test content");
     }
 
+    #[test]
+    fn test_native_copy_mover_actual_move() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_path = temp_dir.path().join("source.txt");
+        let dest_path = temp_dir.path().join("dest/source.txt");
+        fs::write(&source_path, "test content").unwrap();
+
+        let verification = RsyncMover::new()
+            .with_native_copy(NativeCopyConfig::default())
+            .with_verification(VerificationMode::Paranoid)
+            .move_file(&source_path, &dest_path)
+            .unwrap();
+
+        assert!(matches!(verification, Verification::Checksum(_)));
+        assert!(!source_path.exists(), "Source file should be removed");
+        assert!(!partial_path(&dest_path).exists());
+        assert_eq!(fs::read_to_string(&dest_path).unwrap(), "test content");
+    }
+
+    #[test]
+    fn test_native_copy_mover_copy_keeps_source() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_path = temp_dir.path().join("source.txt");
+        let dest_path = temp_dir.path().join("dest/source.txt");
+        fs::write(&source_path, "test content").unwrap();
+
+        RsyncMover::new()
+            .with_native_copy(NativeCopyConfig::default())
+            .copy_file(&source_path, &dest_path)
+            .unwrap();
+
+        assert_eq!(fs::read_to_string(&source_path).unwrap(), "test content");
+        assert!(!partial_path(&dest_path).exists());
+        assert_eq!(fs::read_to_string(&dest_path).unwrap(), "test content");
+    }
+
+    #[test]
+    #[ignore = "requires rsync, run with --ignored"]
+    fn test_rsync_mover_reports_verification() {
+        let temp_dir = TempDir::new().unwrap();
+        let dest_dir = temp_dir.path().join("dest");
+
+        for (mode, name) in [
+            (VerificationMode::None, "none.txt"),
+            (VerificationMode::SizeOnly, "size.txt"),
+            (VerificationMode::Paranoid, "paranoid.txt"),
+        ] {
+            let source_path = temp_dir.path().join(name);
+            fs::write(&source_path, "test content").unwrap();
+
+            let verification = RsyncMover::new()
+                .with_verification(mode)
+                .move_file(&source_path, &dest_dir.join(name))
+                .unwrap();
+
+            match mode {
+                VerificationMode::None => assert_eq!(verification, Verification::None),
+                VerificationMode::SizeOnly => assert_eq!(verification, Verification::Size),
+                _ => assert!(matches!(verification, Verification::Checksum(_))),
+            }
+        }
+    }
+
+    #[test]
+    fn test_rsync_mover_conflict_skip_and_fail() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_path = temp_dir.path().join("source.txt");
+        let dest_path = temp_dir.path().join("dest.txt");
+        fs::write(&source_path, "new content").unwrap();
+        fs::write(&dest_path, "old").unwrap();
+
+        let err = RsyncMover::new()
+            .with_conflict_policy(ConflictPolicy::Skip)
+            .move_file(&source_path, &dest_path)
+            .unwrap_err();
+        assert!(ConflictSkipped::is_skip(&err));
+
+        let err = RsyncMover::new()
+            .with_conflict_policy(ConflictPolicy::Fail)
+            .move_file(&source_path, &dest_path)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert!(!ConflictSkipped::is_skip(&err));
+
+        // Both files are left untouched
+        assert_eq!(fs::read_to_string(&source_path).unwrap(), "new content");
+        assert_eq!(fs::read_to_string(&dest_path).unwrap(), "old");
+    }
+
     #[test]
     #[ignore = "requires rsync, run with --ignored"]
     fn test_rsync_mover_source_not_found() {
@@ -611,6 +1407,38 @@ mod tests {
         assert_eq!(dest_perms.mode() & 0o777, 0o644);
     }
 
+    #[test]
+    #[cfg(unix)]
+    #[ignore = "requires rsync, run with --ignored"]
+    fn test_rsync_mover_preserves_mode_and_times() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let source_path = temp_dir.path().join("source.txt");
+        let dest_path = temp_dir.path().join("dest.txt");
+
+        fs::write(&source_path, "test").unwrap();
+        fs::set_permissions(&source_path, fs::Permissions::from_mode(0o640)).unwrap();
+        let mtime = std::time::SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+        fs::File::options()
+            .write(true)
+            .open(&source_path)
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+
+        let mover = RsyncMover::new().with_preserve(PreserveConfig {
+            xattrs: false,
+            acls: false,
+            ..PreserveConfig::default()
+        });
+        mover.move_file(&source_path, &dest_path).unwrap();
+
+        let metadata = fs::metadata(&dest_path).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o640);
+        assert_eq!(metadata.modified().unwrap(), mtime);
+    }
+
     #[test]
     #[ignore = "requires rsync, run with --ignored"]
     fn test_rsync_mover_large_file() {
@@ -706,4 +1534,41 @@ mod tests {
         // Destination should exist
         assert!(dest_path.exists(), "Destination file should exist");
     }
+
+    #[test]
+    fn test_verify_copy_matching_content() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("source.mkv");
+        let copy = temp.path().join("copy.mkv.partial");
+        fs::write(&source, b"video data").unwrap();
+        fs::write(&copy, b"video data").unwrap();
+
+        let checksum =
+            verify_copy(&source, &copy, &Hasher::default(), &HashBudget::unlimited()).unwrap();
+        assert_eq!(checksum.algorithm, HashAlgorithm::Xxh3);
+    }
+
+    #[test]
+    fn test_verify_copy_detects_mismatch() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("source.mkv");
+        let copy = temp.path().join("copy.mkv.partial");
+        fs::write(&source, b"video data").unwrap();
+        fs::write(&copy, b"video dat4").unwrap();
+
+        let hasher = Hasher::new(HashAlgorithm::Sha256);
+        let err = verify_copy(&source, &copy, &hasher, &HashBudget::unlimited()).unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"));
+        assert!(err.get_ref().unwrap().is::<VerificationFailed>());
+    }
+
+    #[test]
+    fn test_rsync_mover_reports_verify_throttle_time() {
+        assert_eq!(RsyncMover::new().verify_throttle_time(), Duration::ZERO);
+        let mover = RsyncMover::new()
+            .with_verification(VerificationMode::ChecksumOnce)
+            .with_hash_budget(Arc::new(HashBudget::new(Some(100), Some(1))));
+        assert!(mover.verification.hashes());
+        assert_eq!(mover.verify_throttle_time(), Duration::ZERO);
+    }
 }
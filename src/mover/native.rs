@@ -0,0 +1,535 @@
+//! In-process copier used by `mover: native_fast`
+//!
+//! Tries a reflink (`FICLONE`) first, which shares extents on copy-on-write
+//! filesystems such as btrfs and XFS, then `copy_file_range`, which lets the kernel
+//! copy without a round trip through user space, and finally a buffered copy.
+//! Only the data regions of sparse files are copied (`SEEK_DATA`/`SEEK_HOLE`), and
+//! their space is reserved with `fallocate` up front so the copy is not fragmented.
+
+use super::CopyProgress;
+use crate::config::{NativeCopyConfig, PreserveConfig};
+use std::fs::{self, File, FileTimes, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::Instant;
+
+/// Bytes per `copy_file_range` call or buffered chunk, between progress reports
+const CHUNK_SIZE: usize = 64 * 1024 * 1024;
+
+/// Buffer used by the buffered fallback
+const BUFFER_SIZE: usize = 1024 * 1024;
+
+/// How a file was copied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyMethod {
+    Reflink,
+    CopyFileRange,
+    Buffered,
+}
+
+impl std::fmt::Display for CopyMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Reflink => write!(f, "reflink"),
+            Self::CopyFileRange => write!(f, "copy_file_range"),
+            Self::Buffered => write!(f, "buffered"),
+        }
+    }
+}
+
+/// Copies `source` to `destination`, replacing it, and carries over the metadata in `preserve`
+pub fn copy_file(
+    source: &Path,
+    destination: &Path,
+    preserve: &PreserveConfig,
+    options: NativeCopyConfig,
+    on_progress: &mut dyn FnMut(CopyProgress),
+) -> io::Result<CopyMethod> {
+    let mut reader = File::open(source)?;
+    let metadata = reader.metadata()?;
+    let total_bytes = metadata.len();
+    let mut writer = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(destination)?;
+
+    let started = Instant::now();
+    let mut report = |bytes_copied: u64| {
+        let elapsed = started.elapsed().as_secs_f64();
+        on_progress(CopyProgress {
+            bytes_copied,
+            total_bytes,
+            bytes_per_sec: if elapsed > 0.0 {
+                (bytes_copied as f64 / elapsed) as u64
+            } else {
+                0
+            },
+        });
+    };
+
+    let method = if sys::reflink(&reader, &writer).is_ok() {
+        report(total_bytes);
+        CopyMethod::Reflink
+    } else {
+        copy_regions(&mut reader, &mut writer, total_bytes, options, &mut report)?
+    };
+    writer.sync_all()?;
+
+    preserve_metadata(&reader, &writer, &metadata, *preserve)?;
+    tracing::debug!(
+        "Copied {} -> {} ({method})",
+        source.display(),
+        destination.display()
+    );
+    Ok(method)
+}
+
+/// Copies each data region of `reader`, leaving holes in between, and sizes `writer` to match
+fn copy_regions(
+    reader: &mut File,
+    writer: &mut File,
+    total_bytes: u64,
+    options: NativeCopyConfig,
+    report: &mut dyn FnMut(u64),
+) -> io::Result<CopyMethod> {
+    let regions = if options.sparse {
+        data_regions(reader, total_bytes)?
+    } else {
+        vec![(0, total_bytes)]
+    };
+    let mut preallocate = options.preallocate;
+    let mut method = CopyMethod::CopyFileRange;
+    for (offset, len) in regions {
+        if preallocate && let Err(e) = sys::fallocate(writer, offset, len) {
+            if !sys::is_unsupported(&e) {
+                return Err(e);
+            }
+            tracing::debug!("Not preallocating: {e}");
+            preallocate = false;
+        }
+        method = copy_range(reader, writer, offset, len, method, report)?;
+    }
+    // Trailing hole; a source that shrank meanwhile fails the mover's size check
+    writer.set_len(total_bytes)?;
+    report(total_bytes);
+    Ok(method)
+}
+
+/// `(offset, len)` of every data region; the whole file where holes can't be found
+fn data_regions(file: &File, total_bytes: u64) -> io::Result<Vec<(u64, u64)>> {
+    let mut regions = Vec::new();
+    let mut offset = 0;
+    while offset < total_bytes {
+        let start = match sys::next_data(file, offset) {
+            Ok(Some(start)) => start,
+            Ok(None) => break,
+            Err(e) if sys::is_unsupported(&e) => return Ok(vec![(0, total_bytes)]),
+            Err(e) => return Err(e),
+        };
+        let end = sys::next_hole(file, start)?.min(total_bytes);
+        if end > start {
+            regions.push((start, end - start));
+        }
+        offset = end.max(start + 1);
+    }
+    Ok(regions)
+}
+
+/// `copy_file_range` of `len` bytes at `offset` in chunks, switching to a buffered copy
+/// where the kernel can't; `report` gets the offset reached
+fn copy_range(
+    reader: &mut File,
+    writer: &mut File,
+    offset: u64,
+    len: u64,
+    method: CopyMethod,
+    report: &mut dyn FnMut(u64),
+) -> io::Result<CopyMethod> {
+    reader.seek(SeekFrom::Start(offset))?;
+    writer.seek(SeekFrom::Start(offset))?;
+    if method == CopyMethod::Buffered {
+        return copy_buffered(&mut Read::by_ref(reader).take(len), writer, offset, report);
+    }
+    let mut copied = 0;
+    while copied < len {
+        let chunk = (len - copied).min(CHUNK_SIZE as u64) as usize;
+        match sys::copy_file_range(reader, writer, chunk) {
+            // Source shrank while copying; the mover's size check catches it
+            Ok(0) => break,
+            Ok(written) => {
+                copied += written as u64;
+                report(offset + copied);
+            }
+            // Cross-device copies on old kernels, unsupported filesystems
+            Err(e) if copied == 0 && sys::is_unsupported(&e) => {
+                return copy_buffered(&mut Read::by_ref(reader).take(len), writer, offset, report);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(CopyMethod::CopyFileRange)
+}
+
+fn copy_buffered(
+    reader: &mut impl Read,
+    writer: &mut File,
+    offset: u64,
+    report: &mut dyn FnMut(u64),
+) -> io::Result<CopyMethod> {
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    let mut copied = offset;
+    let mut since_report = 0;
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        writer.write_all(&buffer[..read])?;
+        copied += read as u64;
+        since_report += read;
+        if since_report >= CHUNK_SIZE {
+            report(copied);
+            since_report = 0;
+        }
+    }
+    Ok(CopyMethod::Buffered)
+}
+
+fn preserve_metadata(
+    reader: &File,
+    writer: &File,
+    metadata: &fs::Metadata,
+    preserve: PreserveConfig,
+) -> io::Result<()> {
+    if preserve.xattrs || preserve.acls {
+        sys::copy_xattrs(reader, writer, preserve)?;
+    }
+    #[cfg(unix)]
+    if preserve.owner {
+        use std::os::unix::fs::MetadataExt;
+        // Like rsync, only root can give files away; skip silently otherwise
+        match std::os::unix::fs::fchown(writer, Some(metadata.uid()), Some(metadata.gid())) {
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {}
+            result => result?,
+        }
+    }
+    // After chown, which clears setuid/setgid bits
+    if preserve.mode {
+        writer.set_permissions(metadata.permissions())?;
+    }
+    if preserve.times {
+        writer.set_times(
+            FileTimes::new()
+                .set_accessed(metadata.accessed()?)
+                .set_modified(metadata.modified()?),
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use crate::config::PreserveConfig;
+    use std::ffi::CStr;
+    use std::fs::File;
+    use std::io;
+    use std::os::fd::AsRawFd;
+
+    /// `_IOW(0x94, 9, int)` from `linux/fs.h`
+    const FICLONE: libc::c_ulong = 0x4004_9409;
+
+    pub fn reflink(reader: &File, writer: &File) -> io::Result<()> {
+        // SAFETY: both descriptors are open for the duration of the call
+        let result = unsafe { libc::ioctl(writer.as_raw_fd(), FICLONE, reader.as_raw_fd()) };
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
+    /// Copies up to `len` bytes from the current offset of `reader` to that of `writer`
+    pub fn copy_file_range(reader: &File, writer: &File, len: usize) -> io::Result<usize> {
+        // SAFETY: null offsets make the kernel use and advance the file offsets
+        let written = unsafe {
+            libc::copy_file_range(
+                reader.as_raw_fd(),
+                std::ptr::null_mut(),
+                writer.as_raw_fd(),
+                std::ptr::null_mut(),
+                len,
+                0,
+            )
+        };
+        usize::try_from(written).map_err(|_| io::Error::last_os_error())
+    }
+
+    pub fn is_unsupported(e: &io::Error) -> bool {
+        matches!(
+            e.raw_os_error(),
+            Some(libc::EXDEV | libc::ENOSYS | libc::EOPNOTSUPP | libc::EINVAL | libc::EPERM)
+        )
+    }
+
+    /// Allocates `len` bytes at `offset`, extending the file if needed
+    pub fn fallocate(file: &File, offset: u64, len: u64) -> io::Result<()> {
+        let (Ok(offset), Ok(len)) = (libc::off_t::try_from(offset), libc::off_t::try_from(len))
+        else {
+            return Err(io::Error::from_raw_os_error(libc::EINVAL));
+        };
+        // SAFETY: plain syscall on an open descriptor
+        if unsafe { libc::fallocate(file.as_raw_fd(), 0, offset, len) } == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
+    /// Start of the first data region at or after `offset`; `None` past the last one
+    pub fn next_data(file: &File, offset: u64) -> io::Result<Option<u64>> {
+        match seek(file, offset, libc::SEEK_DATA) {
+            Err(e) if e.raw_os_error() == Some(libc::ENXIO) => Ok(None),
+            result => result.map(Some),
+        }
+    }
+
+    /// Start of the first hole at or after `offset`; the end of the file counts as one
+    pub fn next_hole(file: &File, offset: u64) -> io::Result<u64> {
+        seek(file, offset, libc::SEEK_HOLE)
+    }
+
+    fn seek(file: &File, offset: u64, whence: libc::c_int) -> io::Result<u64> {
+        let offset = libc::off_t::try_from(offset)
+            .map_err(|_| io::Error::from_raw_os_error(libc::EINVAL))?;
+        // SAFETY: plain syscall on an open descriptor
+        let result = unsafe { libc::lseek(file.as_raw_fd(), offset, whence) };
+        u64::try_from(result).map_err(|_| io::Error::last_os_error())
+    }
+
+    /// Copies extended attributes; ACLs are the `system.posix_acl_*` attributes
+    pub fn copy_xattrs(reader: &File, writer: &File, preserve: PreserveConfig) -> io::Result<()> {
+        let names = list_xattrs(reader)?;
+        for name in names.split(|&b| b == 0).filter(|name| !name.is_empty()) {
+            let is_acl = name.starts_with(b"system.posix_acl_");
+            if (is_acl && !preserve.acls) || (!is_acl && !preserve.xattrs) {
+                continue;
+            }
+            let mut name = name.to_vec();
+            name.push(0);
+            let name = CStr::from_bytes_with_nul(&name).map_err(io::Error::other)?;
+            let value = get_xattr(reader, name)?;
+            // SAFETY: `name` is NUL-terminated and `value` outlives the call
+            let result = unsafe {
+                libc::fsetxattr(
+                    writer.as_raw_fd(),
+                    name.as_ptr(),
+                    value.as_ptr().cast(),
+                    value.len(),
+                    0,
+                )
+            };
+            if result != 0 {
+                let e = io::Error::last_os_error();
+                // Destination filesystem without xattr support, or a namespace we may not write
+                if matches!(e.raw_os_error(), Some(libc::EOPNOTSUPP | libc::EPERM)) {
+                    tracing::debug!("Skipping xattr {}: {e}", name.to_string_lossy());
+                    continue;
+                }
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    fn list_xattrs(file: &File) -> io::Result<Vec<u8>> {
+        read_sized(|buf, len| {
+            // SAFETY: `buf` is null (size query) or points to `len` writable bytes
+            unsafe { libc::flistxattr(file.as_raw_fd(), buf.cast(), len) }
+        })
+        .or_else(|e| {
+            if e.raw_os_error() == Some(libc::EOPNOTSUPP) {
+                Ok(Vec::new())
+            } else {
+                Err(e)
+            }
+        })
+    }
+
+    fn get_xattr(file: &File, name: &CStr) -> io::Result<Vec<u8>> {
+        read_sized(|buf, len| {
+            // SAFETY: as in `list_xattrs`, and `name` is NUL-terminated
+            unsafe { libc::fgetxattr(file.as_raw_fd(), name.as_ptr(), buf.cast(), len) }
+        })
+    }
+
+    /// Size query followed by the read, retried if the value grew in between
+    fn read_sized(call: impl Fn(*mut u8, usize) -> libc::ssize_t) -> io::Result<Vec<u8>> {
+        loop {
+            let size = usize::try_from(call(std::ptr::null_mut(), 0))
+                .map_err(|_| io::Error::last_os_error())?;
+            let mut buf = vec![0u8; size];
+            if let Ok(read) = usize::try_from(call(buf.as_mut_ptr(), size)) {
+                buf.truncate(read);
+                return Ok(buf);
+            }
+            let e = io::Error::last_os_error();
+            if e.raw_os_error() != Some(libc::ERANGE) {
+                return Err(e);
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use crate::config::PreserveConfig;
+    use std::fs::File;
+    use std::io;
+
+    pub fn reflink(_reader: &File, _writer: &File) -> io::Result<()> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+
+    pub fn copy_file_range(_reader: &File, _writer: &File, _len: usize) -> io::Result<usize> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+
+    pub fn is_unsupported(e: &io::Error) -> bool {
+        e.kind() == io::ErrorKind::Unsupported
+    }
+
+    pub fn fallocate(_file: &File, _offset: u64, _len: u64) -> io::Result<()> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+
+    pub fn next_data(_file: &File, _offset: u64) -> io::Result<Option<u64>> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+
+    pub fn next_hole(_file: &File, _offset: u64) -> io::Result<u64> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+
+    pub fn copy_xattrs(
+        _reader: &File,
+        _writer: &File,
+        _preserve: PreserveConfig,
+    ) -> io::Result<()> {
+        tracing::debug!("Extended attributes are not copied on this platform");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::{FileExt, MetadataExt};
+    use std::time::{Duration, SystemTime};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_copy_file_preserves_content_and_times() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("source.mkv");
+        let destination = temp.path().join("destination.mkv");
+        let content: Vec<u8> = (0..3_000_000u32).map(|i| (i % 251) as u8).collect();
+        fs::write(&source, &content).unwrap();
+        let modified = SystemTime::now() - Duration::from_secs(86_400);
+        File::options()
+            .write(true)
+            .open(&source)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        // Existing content is replaced, not appended to
+        fs::write(&destination, b"stale").unwrap();
+
+        let mut reports = Vec::new();
+        copy_file(
+            &source,
+            &destination,
+            &PreserveConfig::default(),
+            NativeCopyConfig::default(),
+            &mut |p| reports.push(p),
+        )
+        .unwrap();
+
+        assert_eq!(fs::read(&destination).unwrap(), content);
+        assert_eq!(
+            fs::metadata(&destination).unwrap().modified().unwrap(),
+            modified
+        );
+        let last = reports.last().unwrap();
+        assert_eq!(last.bytes_copied, last.total_bytes);
+    }
+
+    #[test]
+    fn test_buffered_fallback_copies_everything() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("source.bin");
+        let destination = temp.path().join("destination.bin");
+        fs::write(&source, vec![7u8; BUFFER_SIZE * 2 + 17]).unwrap();
+
+        let mut reader = File::open(&source).unwrap();
+        let mut writer = File::create(&destination).unwrap();
+        let len = (BUFFER_SIZE * 2 + 17) as u64;
+        let method = copy_range(
+            &mut reader,
+            &mut writer,
+            0,
+            len,
+            CopyMethod::Buffered,
+            &mut |_| {},
+        )
+        .unwrap();
+
+        assert_eq!(method, CopyMethod::Buffered);
+        assert_eq!(fs::read(&destination).unwrap().len(), BUFFER_SIZE * 2 + 17);
+    }
+
+    #[test]
+    fn test_sparse_source_copied_with_holes() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("preallocated.bin");
+        let destination = temp.path().join("destination.bin");
+        let file = File::create(&source).unwrap();
+        file.set_len(16 * 1024 * 1024).unwrap();
+        file.write_all_at(&[42u8; 8192], 4 * 1024 * 1024).unwrap();
+        drop(file);
+
+        for options in [
+            NativeCopyConfig::default(),
+            NativeCopyConfig {
+                sparse: false,
+                preallocate: false,
+            },
+        ] {
+            copy_file(
+                &source,
+                &destination,
+                &PreserveConfig::default(),
+                options,
+                &mut |_| {},
+            )
+            .unwrap();
+            assert_eq!(fs::read(&destination).unwrap(), fs::read(&source).unwrap());
+        }
+
+        copy_file(
+            &source,
+            &destination,
+            &PreserveConfig::default(),
+            NativeCopyConfig::default(),
+            &mut |_| {},
+        )
+        .unwrap();
+        let allocated = |path| fs::metadata(path).unwrap().blocks() * 512;
+        // Only where the temp filesystem keeps the source sparse itself
+        if allocated(&source) < 1024 * 1024 {
+            assert!(allocated(&destination) < 1024 * 1024);
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use super::{Mover, Verification};
+use crate::remote::RemoteTransfer;
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Minimum time between two reports for the same move
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+type ProgressCallback<'a> = Box<dyn FnMut(&TransferProgress) + 'a>;
+
+/// Bytes copied so far out of a total, with the current rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CopyProgress {
+    pub bytes_copied: u64,
+    pub total_bytes: u64,
+    pub bytes_per_sec: u64,
+}
+
+impl CopyProgress {
+    pub fn percent(&self) -> u64 {
+        if self.total_bytes == 0 {
+            return 100;
+        }
+        (self.bytes_copied.min(self.total_bytes) as f64 / self.total_bytes as f64 * 100.0) as u64
+    }
+
+    /// Estimated time left at the current rate
+    pub fn eta(&self) -> Option<Duration> {
+        let remaining = self.total_bytes.saturating_sub(self.bytes_copied);
+        (self.bytes_per_sec > 0).then(|| Duration::from_secs(remaining / self.bytes_per_sec))
+    }
+
+    const fn is_complete(&self) -> bool {
+        self.bytes_copied >= self.total_bytes
+    }
+}
+
+/// Progress of the file being moved and of the whole run
+#[derive(Debug, Clone, Copy)]
+pub struct TransferProgress<'a> {
+    pub file: &'a Path,
+    pub file_progress: CopyProgress,
+    pub run_progress: CopyProgress,
+}
+
+/// Parses an rsync `--info=progress2` line such as
+/// `  1,234,567  45%   12.34MB/s    0:00:12 (xfr#1, to-chk=0/1)`
+///
+/// Returns bytes copied and the rate in bytes per second.
+pub fn parse_rsync_progress(line: &str) -> Option<(u64, u64)> {
+    let mut fields = line.split_whitespace();
+    let bytes = fields.next()?.replace(',', "").parse().ok()?;
+    fields.next().filter(|percent| percent.ends_with('%'))?;
+    let rate = fields.next()?.strip_suffix("/s")?;
+
+    let split = rate.find(|c: char| c.is_ascii_alphabetic())?;
+    let (value, unit) = rate.split_at(split);
+    let multiplier: u64 = match unit {
+        "B" => 1,
+        "kB" | "KB" => 1024,
+        "MB" => 1024 * 1024,
+        "GB" => 1024 * 1024 * 1024,
+        "TB" => 1024 * 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    let rate = (value.parse::<f64>().ok()? * multiplier as f64) as u64;
+
+    Some((bytes, rate))
+}
+
+/// Mover wrapper that reports per-file and run-wide copy progress
+///
+/// Reports are throttled to one per second per file, plus one when a copy completes.
+pub struct ProgressMover<'a> {
+    inner: &'a dyn Mover,
+    total_bytes: u64,
+    finished_bytes: Cell<u64>,
+    started: Instant,
+    on_progress: RefCell<ProgressCallback<'a>>,
+}
+
+impl<'a> ProgressMover<'a> {
+    /// Wrap `inner` for a run moving `total_bytes` in all
+    pub fn new(
+        inner: &'a dyn Mover,
+        total_bytes: u64,
+        on_progress: impl FnMut(&TransferProgress) + 'a,
+    ) -> Self {
+        Self {
+            inner,
+            total_bytes,
+            finished_bytes: Cell::new(0),
+            started: Instant::now(),
+            on_progress: RefCell::new(Box::new(on_progress)),
+        }
+    }
+}
+
+impl ProgressMover<'_> {
+    /// Runs `transfer` on the inner mover, turning its file progress into run progress
+    fn track(
+        &self,
+        source: &Path,
+        file_size: u64,
+        transfer: impl FnOnce(&mut dyn FnMut(CopyProgress)) -> io::Result<Verification>,
+    ) -> io::Result<Verification> {
+        let finished = self.finished_bytes.get();
+        let mut last_report: Option<Instant> = None;
+
+        let result = transfer(&mut |file_progress| {
+            let now = Instant::now();
+            if !file_progress.is_complete()
+                && last_report.is_some_and(|last| now - last < REPORT_INTERVAL)
+            {
+                return;
+            }
+            last_report = Some(now);
+
+            let run_copied = finished + file_progress.bytes_copied;
+            let elapsed = self.started.elapsed().as_secs_f64();
+            let run_progress = CopyProgress {
+                bytes_copied: run_copied,
+                total_bytes: self.total_bytes,
+                bytes_per_sec: if elapsed > 0.0 {
+                    (run_copied as f64 / elapsed) as u64
+                } else {
+                    0
+                },
+            };
+
+            (self.on_progress.borrow_mut())(&TransferProgress {
+                file: source,
+                file_progress,
+                run_progress,
+            });
+        });
+
+        if result.is_ok() {
+            self.finished_bytes.set(finished + file_size);
+        }
+        result
+    }
+}
+
+/// Size of a local file, 0 if it can't be read
+fn local_size(path: &Path) -> u64 {
+    fs::metadata(path).map_or(0, |m| m.len())
+}
+
+impl Mover for ProgressMover<'_> {
+    fn move_file(&self, source: &Path, destination: &Path) -> io::Result<Verification> {
+        self.track(source, local_size(source), |on_progress| {
+            self.inner
+                .move_file_with_progress(source, destination, on_progress)
+        })
+    }
+
+    fn copy_file(&self, source: &Path, destination: &Path) -> io::Result<Verification> {
+        self.track(source, local_size(source), |on_progress| {
+            self.inner
+                .copy_file_with_progress(source, destination, on_progress)
+        })
+    }
+
+    fn transfer_remote(
+        &self,
+        transfer: &RemoteTransfer<'_>,
+        _on_progress: &mut dyn FnMut(CopyProgress),
+    ) -> io::Result<Verification> {
+        self.track(transfer.source, transfer.size, |on_progress| {
+            self.inner.transfer_remote(transfer, on_progress)
+        })
+    }
+
+    fn verify_throttle_time(&self) -> Duration {
+        self.inner.verify_throttle_time()
+    }
+
+    fn is_dry_run(&self) -> bool {
+        self.inner.is_dry_run()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StepMover;
+
+    impl Mover for StepMover {
+        fn move_file(&self, _source: &Path, _destination: &Path) -> io::Result<Verification> {
+            Ok(Verification::None)
+        }
+
+        fn move_file_with_progress(
+            &self,
+            _source: &Path,
+            _destination: &Path,
+            on_progress: &mut dyn FnMut(CopyProgress),
+        ) -> io::Result<Verification> {
+            for bytes_copied in [10, 50, 100] {
+                on_progress(CopyProgress {
+                    bytes_copied,
+                    total_bytes: 100,
+                    bytes_per_sec: 10,
+                });
+            }
+            Ok(Verification::Size)
+        }
+    }
+
+    #[test]
+    fn test_parse_rsync_progress() {
+        assert_eq!(
+            parse_rsync_progress("      1,234,567  45%   12.00MB/s    0:00:12 (xfr#1, to-chk=0/1)"),
+            Some((1_234_567, 12 * 1024 * 1024))
+        );
+        assert_eq!(
+            parse_rsync_progress("32,768   0%    0.00kB/s    0:00:00"),
+            Some((32_768, 0))
+        );
+        assert_eq!(parse_rsync_progress("sending incremental file list"), None);
+        assert_eq!(parse_rsync_progress(""), None);
+    }
+
+    #[test]
+    fn test_copy_progress_eta() {
+        let progress = CopyProgress {
+            bytes_copied: 40,
+            total_bytes: 100,
+            bytes_per_sec: 20,
+        };
+        assert_eq!(progress.percent(), 40);
+        assert_eq!(progress.eta(), Some(Duration::from_secs(3)));
+
+        let stalled = CopyProgress {
+            bytes_per_sec: 0,
+            ..progress
+        };
+        assert_eq!(stalled.eta(), None);
+    }
+
+    #[test]
+    fn test_progress_mover_reports_file_and_run_progress() {
+        let temp = tempfile::tempdir().unwrap();
+        let source = temp.path().join("movie.mkv");
+        fs::write(&source, vec![0u8; 100]).unwrap();
+
+        let mut reports = Vec::new();
+        {
+            let mover = ProgressMover::new(&StepMover, 200, |progress| {
+                reports.push((
+                    progress.file_progress.bytes_copied,
+                    progress.run_progress.bytes_copied,
+                ));
+            });
+            assert_eq!(
+                mover.move_file(&source, &temp.path().join("a")).unwrap(),
+                Verification::Size
+            );
+            mover.move_file(&source, &temp.path().join("b")).unwrap();
+        }
+
+        // First report and completion get through, the 50% one is throttled
+        assert_eq!(reports, vec![(10, 10), (100, 100), (10, 110), (100, 200)]);
+    }
+}
@@ -0,0 +1,252 @@
+use crate::config::{NotificationsConfig, NotifyOn, WebhookConfig, WebhookFormat};
+use crate::error::{AppError, Result};
+use crate::{BalancingPlan, ExecutionResult};
+use reqwest::blocking::Client;
+use std::fmt::Write as _;
+use std::time::Duration;
+
+/// Max warnings/errors listed in chat-style messages
+const MAX_LISTED_ITEMS: usize = 10;
+
+/// Outcome of a run, as reported to notification hooks
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunSummary {
+    pub dry_run: bool,
+    pub files_moved: usize,
+    pub bytes_moved: u64,
+    pub files_blocked: usize,
+    pub warnings: Vec<String>,
+    pub errors: Vec<String>,
+    /// Set when the run aborted before executing a plan
+    pub failure: Option<String>,
+}
+
+impl RunSummary {
+    pub fn from_run(plan: &BalancingPlan, result: &ExecutionResult, dry_run: bool) -> Self {
+        Self {
+            dry_run,
+            files_moved: result.files_moved,
+            bytes_moved: result.bytes_moved,
+            files_blocked: result.files_blocked,
+            warnings: plan.warnings.iter().map(ToString::to_string).collect(),
+            errors: result
+                .errors
+                .iter()
+                .map(|e| format!("{}: {}", e.file.display(), e.error))
+                .collect(),
+            failure: None,
+        }
+    }
+
+    /// Adds the next iteration of the same run
+    pub fn merge(&mut self, next: Self) {
+        self.files_moved += next.files_moved;
+        self.bytes_moved += next.bytes_moved;
+        self.files_blocked += next.files_blocked;
+        // A file that can't be placed is reported again by every iteration
+        for warning in next.warnings {
+            if !self.warnings.contains(&warning) {
+                self.warnings.push(warning);
+            }
+        }
+        self.errors.extend(next.errors);
+        self.failure = self.failure.take().or(next.failure);
+    }
+
+    pub fn failed(dry_run: bool, reason: impl Into<String>) -> Self {
+        Self {
+            dry_run,
+            failure: Some(reason.into()),
+            ..Self::default()
+        }
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.failure.is_some() || !self.errors.is_empty() || !self.warnings.is_empty()
+    }
+
+    pub fn should_notify(&self, notify_on: NotifyOn) -> bool {
+        match notify_on {
+            NotifyOn::Always => true,
+            NotifyOn::Errors => self.has_errors(),
+            NotifyOn::Moves => self.files_moved > 0 || self.failure.is_some(),
+        }
+    }
+
+    /// Human-readable message used by chat webhooks
+    pub fn message(&self) -> String {
+        let mut message = String::new();
+        let prefix = if self.dry_run { "[DRY-RUN] " } else { "" };
+
+        if let Some(reason) = &self.failure {
+            let _ = writeln!(message, "{prefix}tierflow run failed: {reason}");
+            return message;
+        }
+
+        let _ = writeln!(
+            message,
+            "{prefix}tierflow run complete: {} moved ({:.2} GB), {} blocked, {} errors, {} warnings",
+            self.files_moved,
+            self.bytes_moved as f64 / 1_000_000_000.0,
+            self.files_blocked,
+            self.errors.len(),
+            self.warnings.len()
+        );
+        write_list(&mut message, "Errors", &self.errors);
+        write_list(&mut message, "Warnings", &self.warnings);
+
+        message
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "status": if self.failure.is_some() { "failed" } else { "completed" },
+            "dry_run": self.dry_run,
+            "files_moved": self.files_moved,
+            "bytes_moved": self.bytes_moved,
+            "files_blocked": self.files_blocked,
+            "failure": &self.failure,
+            "warnings": &self.warnings,
+            "errors": &self.errors,
+        })
+    }
+}
+
+fn write_list(message: &mut String, title: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+
+    let _ = writeln!(message, "{title}:");
+    for item in items.iter().take(MAX_LISTED_ITEMS) {
+        let _ = writeln!(message, "- {item}");
+    }
+    if items.len() > MAX_LISTED_ITEMS {
+        let _ = writeln!(message, "... and {} more", items.len() - MAX_LISTED_ITEMS);
+    }
+}
+
+/// Posts run summaries to the configured webhooks
+pub struct Notifier {
+    notify_on: NotifyOn,
+    webhooks: Vec<WebhookConfig>,
+    client: Client,
+}
+
+impl Notifier {
+    pub fn new(config: &NotificationsConfig) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(15))
+            .build()
+            .map_err(|e| AppError::Io(std::io::Error::other(e)))?;
+
+        Ok(Self {
+            notify_on: config.notify_on,
+            webhooks: config.webhooks.clone(),
+            client,
+        })
+    }
+
+    /// Sends `summary` to every webhook; failures are logged, never returned
+    pub fn notify(&self, summary: &RunSummary) {
+        if !summary.should_notify(self.notify_on) {
+            tracing::debug!("Skipping notifications (notify_on: {:?})", self.notify_on);
+            return;
+        }
+
+        for webhook in &self.webhooks {
+            if let Err(e) = self.send(webhook, summary) {
+                tracing::warn!("Failed to send notification to {}: {e}", webhook.url);
+            }
+        }
+    }
+
+    fn send(&self, webhook: &WebhookConfig, summary: &RunSummary) -> Result<()> {
+        let response = self
+            .client
+            .post(&webhook.url)
+            .json(&payload(webhook.format, summary))
+            .send()
+            .map_err(|e| AppError::External(format!("Webhook request failed: {e}")))?;
+
+        if !response.status().is_success() {
+            return Err(AppError::External(format!(
+                "Webhook returned error status: {}",
+                response.status()
+            )));
+        }
+
+        tracing::debug!("Notification sent to {}", webhook.url);
+        Ok(())
+    }
+}
+
+fn payload(format: WebhookFormat, summary: &RunSummary) -> serde_json::Value {
+    match format {
+        WebhookFormat::Generic => summary.to_json(),
+        WebhookFormat::Discord => serde_json::json!({ "content": summary.message() }),
+        WebhookFormat::Slack => serde_json::json!({ "text": summary.message() }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(files_moved: usize, errors: usize) -> RunSummary {
+        RunSummary {
+            files_moved,
+            bytes_moved: files_moved as u64 * 1_000_000_000,
+            errors: (0..errors)
+                .map(|i| format!("/media/file{i}.mkv: boom"))
+                .collect(),
+            ..RunSummary::default()
+        }
+    }
+
+    #[test]
+    fn test_should_notify_filters() {
+        let quiet = summary(0, 0);
+        assert!(quiet.should_notify(NotifyOn::Always));
+        assert!(!quiet.should_notify(NotifyOn::Errors));
+        assert!(!quiet.should_notify(NotifyOn::Moves));
+
+        let moved = summary(2, 0);
+        assert!(moved.should_notify(NotifyOn::Moves));
+        assert!(!moved.should_notify(NotifyOn::Errors));
+
+        let failed = RunSummary::failed(false, "config error");
+        assert!(failed.should_notify(NotifyOn::Errors));
+        assert!(failed.should_notify(NotifyOn::Moves));
+    }
+
+    #[test]
+    fn test_message_truncates_long_lists() {
+        let message = summary(1, 15).message();
+        assert!(message.starts_with("tierflow run complete: 1 moved (1.00 GB)"));
+        assert!(message.contains("- /media/file9.mkv: boom"));
+        assert!(!message.contains("file10.mkv"));
+        assert!(message.contains("... and 5 more"));
+    }
+
+    #[test]
+    fn test_payload_formats() {
+        let summary = summary(1, 0);
+
+        let generic = payload(WebhookFormat::Generic, &summary);
+        assert_eq!(generic["status"], "completed");
+        assert_eq!(generic["files_moved"], 1);
+
+        let discord = payload(WebhookFormat::Discord, &summary);
+        assert!(discord["content"].as_str().unwrap().contains("1 moved"));
+
+        let slack = payload(WebhookFormat::Slack, &summary);
+        assert!(slack["text"].as_str().unwrap().contains("1 moved"));
+    }
+
+    #[test]
+    fn test_failed_message() {
+        let message = RunSummary::failed(true, "tier locked").message();
+        assert_eq!(message, "[DRY-RUN] tierflow run failed: tier locked\n");
+    }
+}
@@ -0,0 +1,232 @@
+//! Old-to-new path map of executed moves for media servers
+//!
+//! A media server that tracks files by path sees a tier move as a delete plus
+//! an add. Publishing the mapping, or scanning both directories right away,
+//! lets it match the file up again before it drops the metadata.
+
+use crate::config::{PathMappingConfig, PathRewriteConfig, PlexScanConfig};
+use crate::error::Result;
+use crate::executor::ExecutionMove;
+use crate::plex::{self, PlexClient};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One line of the mapping file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PathRewrite<'a> {
+    pub from: &'a Path,
+    pub to: &'a Path,
+    pub from_tier: &'a str,
+    pub to_tier: &'a str,
+    pub size: u64,
+    /// Unix seconds
+    pub moved_at: u64,
+}
+
+impl<'a> PathRewrite<'a> {
+    pub fn new(moved: &'a ExecutionMove, now: SystemTime) -> Self {
+        Self {
+            from: &moved.file,
+            to: &moved.destination,
+            from_tier: &moved.from_tier,
+            to_tier: &moved.to_tier,
+            size: moved.size,
+            moved_at: now.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+        }
+    }
+}
+
+struct PlexScanner {
+    client: PlexClient,
+    path_mappings: Vec<PathMappingConfig>,
+}
+
+/// Publishes executed moves as configured in `path_mapping`
+pub struct PathRewriter {
+    file: Option<PathBuf>,
+    plex: Option<PlexScanner>,
+}
+
+impl PathRewriter {
+    pub fn from_config(config: &PathRewriteConfig) -> Result<Self> {
+        Ok(Self {
+            file: config.file.clone(),
+            plex: config.plex.as_ref().map(PlexScanner::new).transpose()?,
+        })
+    }
+
+    /// Publishes `moved`; failures are logged and never fail the run
+    pub fn publish(&self, moved: &[ExecutionMove]) {
+        if moved.is_empty() {
+            return;
+        }
+        if let Some(file) = &self.file
+            && let Err(e) = append_rewrites(file, moved, SystemTime::now())
+        {
+            tracing::warn!("Failed to write path mapping to {}: {e}", file.display());
+        }
+        if let Some(plex) = &self.plex {
+            plex.scan(moved);
+        }
+    }
+}
+
+impl PlexScanner {
+    fn new(config: &PlexScanConfig) -> Result<Self> {
+        Ok(Self {
+            client: PlexClient::new(config.url.clone(), config.token.clone())?,
+            path_mappings: config.path_mappings.clone(),
+        })
+    }
+
+    fn scan(&self, moved: &[ExecutionMove]) {
+        let sections = match self.client.library_sections() {
+            Ok(sections) => sections,
+            Err(e) => {
+                tracing::warn!("Skipping Plex scans: {e}");
+                return;
+            }
+        };
+        for dir in scan_dirs(moved, &self.path_mappings) {
+            let Some(section) = plex::section_for(&sections, &dir) else {
+                tracing::debug!("No Plex library contains {dir}");
+                continue;
+            };
+            match self.client.scan_path(section, &dir) {
+                Ok(()) => tracing::info!("Plex scan of {dir} ({}) requested", section.title),
+                Err(e) => tracing::warn!("Failed to request Plex scan of {dir}: {e}"),
+            }
+        }
+    }
+}
+
+/// Appends one JSON line per move to `path`
+pub fn append_rewrites(path: &Path, moved: &[ExecutionMove], now: SystemTime) -> io::Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+    let mut lines = String::new();
+    for rewrite in moved.iter().map(|m| PathRewrite::new(m, now)) {
+        lines.push_str(&serde_json::to_string(&rewrite).map_err(io::Error::other)?);
+        lines.push('\n');
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(lines.as_bytes())
+}
+
+/// Source and destination directories of every move as the app sees them, deduplicated
+pub fn scan_dirs(moved: &[ExecutionMove], mappings: &[PathMappingConfig]) -> BTreeSet<String> {
+    moved
+        .iter()
+        .flat_map(|m| [&m.file, &m.destination])
+        .filter_map(|path| path.parent())
+        .map(|dir| host_to_app_path(dir, mappings))
+        .collect()
+}
+
+/// Translates a host path with the first matching mapping, the reverse of the Tdarr lookup
+fn host_to_app_path(path: &Path, mappings: &[PathMappingConfig]) -> String {
+    mappings
+        .iter()
+        .find_map(|mapping| {
+            let relative = path.strip_prefix(&mapping.host_prefix).ok()?;
+            let prefix = mapping.app_prefix.trim().trim_end_matches('/');
+            Some(if relative.as_os_str().is_empty() {
+                prefix.to_string()
+            } else {
+                format!("{prefix}/{}", relative.display())
+            })
+        })
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Verification;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn moved(file: &str, destination: &str) -> ExecutionMove {
+        ExecutionMove {
+            file: PathBuf::from(file),
+            destination: PathBuf::from(destination),
+            from_tier: "cache".to_string(),
+            to_tier: "storage".to_string(),
+            size: 42,
+            verification: Verification::Size,
+        }
+    }
+
+    #[test]
+    fn test_append_rewrites_writes_json_lines() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("state/moves.jsonl");
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        append_rewrites(
+            &file,
+            &[moved("/mnt/cache/a.mkv", "/mnt/storage/a.mkv")],
+            now,
+        )
+        .unwrap();
+        append_rewrites(
+            &file,
+            &[moved("/mnt/cache/b.mkv", "/mnt/storage/b.mkv")],
+            now,
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&file).unwrap();
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["from"], "/mnt/cache/a.mkv");
+        assert_eq!(lines[0]["to"], "/mnt/storage/a.mkv");
+        assert_eq!(lines[1]["to_tier"], "storage");
+        assert_eq!(lines[1]["moved_at"], 1_700_000_000);
+    }
+
+    #[test]
+    fn test_scan_dirs_maps_both_sides_once() {
+        let mappings = [
+            PathMappingConfig {
+                host_prefix: PathBuf::from("/mnt/cache/tv"),
+                app_prefix: "/tv/".to_string(),
+            },
+            PathMappingConfig {
+                host_prefix: PathBuf::from("/mnt/storage/tv"),
+                app_prefix: "/tv".to_string(),
+            },
+        ];
+        let moves = [
+            moved(
+                "/mnt/cache/tv/Show/S01E01.mkv",
+                "/mnt/storage/tv/Show/S01E01.mkv",
+            ),
+            moved(
+                "/mnt/cache/tv/Show/S01E02.mkv",
+                "/mnt/storage/tv/Show/S01E02.mkv",
+            ),
+            moved("/mnt/cache/movies/A.mkv", "/mnt/storage/movies/A.mkv"),
+        ];
+
+        let dirs: Vec<_> = scan_dirs(&moves, &mappings).into_iter().collect();
+
+        assert_eq!(
+            dirs,
+            vec!["/mnt/cache/movies", "/mnt/storage/movies", "/tv/Show"]
+        );
+    }
+}
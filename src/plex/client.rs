@@ -0,0 +1,153 @@
+use crate::error::{AppError, Result};
+use reqwest::blocking::{Client, Response};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Plex Media Server API client
+pub struct PlexClient {
+    base_url: String,
+    token: String,
+    client: Client,
+}
+
+/// A Plex library and the folders it scans
+#[derive(Debug, Clone, Deserialize)]
+pub struct LibrarySection {
+    pub key: String,
+    pub title: String,
+    #[serde(rename = "Location", default)]
+    pub locations: Vec<SectionLocation>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SectionLocation {
+    pub path: String,
+}
+
+impl LibrarySection {
+    /// Length of the longest library folder containing `path`, if any
+    fn matched_prefix_len(&self, path: &str) -> Option<usize> {
+        self.locations
+            .iter()
+            .map(|location| location.path.trim_end_matches('/'))
+            .filter(|prefix| {
+                path == *prefix
+                    || path
+                        .strip_prefix(prefix)
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+            .map(str::len)
+            .max()
+    }
+}
+
+/// Library whose folders most specifically contain `path`
+pub fn section_for<'a>(sections: &'a [LibrarySection], path: &str) -> Option<&'a LibrarySection> {
+    sections
+        .iter()
+        .filter_map(|section| section.matched_prefix_len(path).map(|len| (len, section)))
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, section)| section)
+}
+
+#[derive(Debug, Deserialize)]
+struct SectionsResponse {
+    #[serde(rename = "MediaContainer")]
+    media_container: SectionsContainer,
+}
+
+#[derive(Debug, Deserialize)]
+struct SectionsContainer {
+    #[serde(rename = "Directory", default)]
+    directory: Vec<LibrarySection>,
+}
+
+impl PlexClient {
+    /// Create new Plex client
+    pub fn new(base_url: String, token: String) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| AppError::Io(std::io::Error::other(e)))?;
+
+        // Normalize base_url: ensure it ends with /
+        let base_url = if base_url.ends_with('/') {
+            base_url
+        } else {
+            format!("{base_url}/")
+        };
+
+        Ok(Self {
+            base_url,
+            token,
+            client,
+        })
+    }
+
+    fn get(&self, path: &str, query: &[(&str, &str)]) -> Result<Response> {
+        let url = format!("{}{path}", self.base_url);
+        let response = self
+            .client
+            .get(&url)
+            .query(query)
+            .header("X-Plex-Token", &self.token)
+            .header("Accept", "application/json")
+            .send()
+            .map_err(|e| {
+                AppError::External(format!(
+                    "Failed to connect to Plex at {}: {e}",
+                    self.base_url
+                ))
+            })?;
+
+        if !response.status().is_success() {
+            return Err(AppError::External(format!(
+                "Plex API returned error status: {}",
+                response.status()
+            )));
+        }
+        Ok(response)
+    }
+
+    /// All libraries with their folders
+    pub fn library_sections(&self) -> Result<Vec<LibrarySection>> {
+        let response: SectionsResponse = self
+            .get("library/sections", &[])?
+            .json()
+            .map_err(|e| AppError::External(format!("Failed to parse Plex response: {e}")))?;
+        Ok(response.media_container.directory)
+    }
+
+    /// Partial scan of a single folder of a library
+    pub fn scan_path(&self, section: &LibrarySection, path: &str) -> Result<()> {
+        self.get(
+            &format!("library/sections/{}/refresh", section.key),
+            &[("path", path)],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_section_for_picks_most_specific_location() {
+        let json = r#"{"MediaContainer": {"Directory": [
+            {"key": "1", "title": "Media", "Location": [{"id": 1, "path": "/media"}]},
+            {"key": "2", "title": "TV Shows", "Location": [{"id": 2, "path": "/media/tv/"}]},
+            {"key": "3", "title": "Empty"}
+        ]}}"#;
+        let sections = serde_json::from_str::<SectionsResponse>(json)
+            .unwrap()
+            .media_container
+            .directory;
+
+        let key = |path| section_for(&sections, path).map(|s| s.key.as_str());
+        assert_eq!(key("/media/tv/Show/Season 01"), Some("2"));
+        assert_eq!(key("/media/tv"), Some("2"));
+        assert_eq!(key("/media/tvshows"), Some("1"));
+        assert_eq!(key("/movies"), None);
+    }
+}
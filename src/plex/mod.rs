@@ -0,0 +1,3 @@
+mod client;
+
+pub use client::{LibrarySection, PlexClient, SectionLocation, section_for};
@@ -0,0 +1,422 @@
+//! Execution progress log, used to recover and resume interrupted runs
+
+use crate::error::Result;
+use crate::mover::partial_path;
+use crate::{BalancingPlan, FileInfo, PlacementDecision, Tier};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const PROGRESS_FILE: &str = "progress.jsonl";
+
+/// A move as recorded in the progress log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedMove {
+    pub file: PathBuf,
+    pub from_tier: String,
+    pub to_tier: String,
+    pub size: u64,
+    pub strategy: String,
+    pub priority: u32,
+    pub promote: bool,
+    /// The original stays in place
+    #[serde(default)]
+    pub copy: bool,
+}
+
+impl PlannedMove {
+    pub fn from_decision(decision: &PlacementDecision) -> Option<Self> {
+        let (promote, copy) = match decision {
+            PlacementDecision::Stay { .. } => return None,
+            PlacementDecision::Promote { .. } => (true, false),
+            PlacementDecision::Demote { .. } => (false, false),
+            PlacementDecision::Copy { .. } => (false, true),
+        };
+
+        Some(Self {
+            file: decision.file_path().clone(),
+            from_tier: decision.current_tier().to_string(),
+            to_tier: decision.target_tier().to_string(),
+            size: decision.file_size(),
+            strategy: decision.strategy_name().to_string(),
+            priority: decision.strategy_priority(),
+            promote,
+            copy,
+        })
+    }
+
+    /// Possible destination paths of the move, one per member of the target tier
+    fn destinations(&self, tiers: &[Tier]) -> Vec<PathBuf> {
+        let from = tiers.iter().find(|t| t.name == self.from_tier);
+        let to = tiers.iter().find(|t| t.name == self.to_tier);
+        let (Some(relative), Some(to)) = (from.and_then(|f| f.relative_path(&self.file)), to)
+        else {
+            return Vec::new();
+        };
+        to.paths.iter().map(|root| root.join(relative)).collect()
+    }
+
+    fn into_decision(self, file: FileInfo) -> PlacementDecision {
+        let file = Arc::new(file);
+        if self.copy {
+            PlacementDecision::Copy {
+                file,
+                from_tier: self.from_tier,
+                to_tier: self.to_tier,
+                strategy: self.strategy,
+                priority: self.priority,
+            }
+        } else if self.promote {
+            PlacementDecision::Promote {
+                file,
+                from_tier: self.from_tier,
+                to_tier: self.to_tier,
+                strategy: self.strategy,
+                priority: self.priority,
+            }
+        } else {
+            PlacementDecision::Demote {
+                file,
+                from_tier: self.from_tier,
+                to_tier: self.to_tier,
+                strategy: self.strategy,
+                priority: self.priority,
+            }
+        }
+    }
+}
+
+/// Outcome of a single move
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MoveStatus {
+    Done,
+    Failed,
+    Skipped,
+    Blocked,
+    /// Not started because the execution budget ran out
+    Deferred,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    started_at: u64,
+    moves: Vec<PlannedMove>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StatusLine {
+    index: usize,
+    status: MoveStatus,
+}
+
+/// Writer for the progress of the running execution
+///
+/// Append-only JSON lines: a header with every planned move, then one line per
+/// finished move. The log is removed when execution completes, so a log found
+/// on startup means the previous run was interrupted.
+pub struct ProgressLog {
+    path: PathBuf,
+    file: File,
+}
+
+impl ProgressLog {
+    /// Starts a new log for the moves of `plan`, replacing any previous one
+    pub fn start(state_dir: &Path, plan: &BalancingPlan) -> Result<Self> {
+        fs::create_dir_all(state_dir)?;
+        let path = state_dir.join(PROGRESS_FILE);
+        let header = Header {
+            started_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            moves: plan
+                .decisions
+                .iter()
+                .filter_map(PlannedMove::from_decision)
+                .collect(),
+        };
+
+        let mut file = File::create(&path)?;
+        serde_json::to_writer(&mut file, &header)?;
+        file.write_all(b"\n")?;
+        file.sync_data()?;
+
+        Ok(Self { path, file })
+    }
+
+    /// Records the outcome of the `index`-th move of the plan
+    pub fn record(&mut self, index: usize, status: MoveStatus) {
+        let write = serde_json::to_vec(&StatusLine { index, status })
+            .map_err(std::io::Error::from)
+            .and_then(|mut line| {
+                line.push(b'\n');
+                self.file.write_all(&line)
+            });
+        if let Err(e) = write {
+            tracing::warn!("Failed to record execution progress: {e}");
+        }
+    }
+
+    /// Marks the execution as complete by removing the log
+    pub fn finish(self) -> Result<()> {
+        fs::remove_file(&self.path)?;
+        Ok(())
+    }
+}
+
+/// Progress of a run that did not finish
+#[derive(Debug)]
+pub struct InterruptedRun {
+    pub started_at: u64,
+    pub moves: Vec<PlannedMove>,
+    statuses: HashMap<usize, MoveStatus>,
+}
+
+impl InterruptedRun {
+    /// Loads the progress log left behind by an interrupted run, if any
+    pub fn load(state_dir: &Path) -> Result<Option<Self>> {
+        let file = match File::open(state_dir.join(PROGRESS_FILE)) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut lines = BufReader::new(file).lines();
+        let Some(header) = lines.next().transpose()? else {
+            return Ok(None);
+        };
+        let header: Header = serde_json::from_str(&header)?;
+
+        let mut statuses = HashMap::new();
+        for line in lines {
+            // The last line may be cut short by the interruption
+            match serde_json::from_str::<StatusLine>(&line?) {
+                Ok(status) => {
+                    statuses.insert(status.index, status.status);
+                }
+                Err(_) => break,
+            }
+        }
+
+        Ok(Some(Self {
+            started_at: header.started_at,
+            moves: header.moves,
+            statuses,
+        }))
+    }
+
+    pub fn status(&self, index: usize) -> Option<MoveStatus> {
+        self.statuses.get(&index).copied()
+    }
+
+    pub fn completed(&self) -> impl Iterator<Item = &PlannedMove> {
+        self.moves
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.status(*i) == Some(MoveStatus::Done))
+            .map(|(_, m)| m)
+    }
+
+    /// Moves that had not finished when the run was interrupted, or were deferred
+    pub fn pending(&self) -> impl Iterator<Item = &PlannedMove> {
+        self.moves
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| matches!(self.status(*i), None | Some(MoveStatus::Deferred)))
+            .map(|(_, m)| m)
+    }
+
+    /// Where the unfinished moves write their `.partial` copies
+    pub fn pending_partials(&self, tiers: &[Tier]) -> Vec<PathBuf> {
+        self.pending()
+            .flat_map(|planned| planned.destinations(tiers))
+            .map(|destination| partial_path(&destination))
+            .collect()
+    }
+
+    /// Removes `.partial` copies left behind by unfinished moves
+    pub fn clean_partials(&self, tiers: &[Tier]) -> usize {
+        let mut removed = 0;
+        for partial in self.pending_partials(tiers) {
+            match fs::remove_file(&partial) {
+                Ok(()) => {
+                    tracing::info!("Removed orphaned partial copy {}", partial.display());
+                    removed += 1;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    tracing::warn!("Failed to remove {}: {e}", partial.display());
+                }
+            }
+        }
+        removed
+    }
+
+    /// Plan with the pending moves whose source file still exists
+    pub fn remaining_plan(&self) -> BalancingPlan {
+        let decisions = self
+            .pending()
+            .filter_map(|planned| {
+                let Ok(file) = FileInfo::from_path(planned.file.clone()) else {
+                    tracing::info!(
+                        "Skipping {}: source no longer exists",
+                        planned.file.display()
+                    );
+                    return None;
+                };
+                Some(planned.clone().into_decision(file))
+            })
+            .collect();
+
+        BalancingPlan {
+            decisions,
+            ..BalancingPlan::default()
+        }
+    }
+
+    /// Deletes the progress log without resuming
+    pub fn discard(state_dir: &Path) -> Result<()> {
+        match fs::remove_file(state_dir.join(PROGRESS_FILE)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::OpenOptions;
+    use tempfile::TempDir;
+
+    fn demote(path: &Path, size: u64) -> PlacementDecision {
+        PlacementDecision::Demote {
+            file: Arc::new(FileInfo {
+                path: path.to_path_buf(),
+                size,
+                modified: SystemTime::now(),
+                accessed: SystemTime::now(),
+                owner: None,
+            }),
+            from_tier: "cache".to_string(),
+            to_tier: "storage".to_string(),
+            strategy: "old".to_string(),
+            priority: 10,
+        }
+    }
+
+    fn tier(name: &str, path: &Path) -> Tier {
+        fs::create_dir_all(path).unwrap();
+        Tier::new(name.to_string(), path.to_path_buf(), 1, None, None).unwrap()
+    }
+
+    #[test]
+    fn test_no_progress_log() {
+        let temp = TempDir::new().unwrap();
+        assert!(InterruptedRun::load(temp.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_finished_run_leaves_no_log() {
+        let temp = TempDir::new().unwrap();
+        let plan = BalancingPlan {
+            decisions: vec![demote(Path::new("/cache/a.mkv"), 100)],
+            ..BalancingPlan::default()
+        };
+
+        let mut log = ProgressLog::start(temp.path(), &plan).unwrap();
+        log.record(0, MoveStatus::Done);
+        log.finish().unwrap();
+
+        assert!(InterruptedRun::load(temp.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_interrupted_run_tracks_statuses() {
+        let temp = TempDir::new().unwrap();
+        let plan = BalancingPlan {
+            decisions: vec![
+                demote(Path::new("/cache/a.mkv"), 100),
+                demote(Path::new("/cache/b.mkv"), 200),
+                demote(Path::new("/cache/c.mkv"), 300),
+            ],
+            ..BalancingPlan::default()
+        };
+
+        let mut log = ProgressLog::start(temp.path(), &plan).unwrap();
+        log.record(0, MoveStatus::Done);
+        log.record(1, MoveStatus::Failed);
+        drop(log);
+        // Simulate a status line cut short by the crash
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(temp.path().join(PROGRESS_FILE))
+            .unwrap();
+        file.write_all(b"{\"index\":2,\"sta").unwrap();
+
+        let run = InterruptedRun::load(temp.path()).unwrap().unwrap();
+        assert_eq!(run.moves.len(), 3);
+        assert_eq!(
+            run.completed().map(|m| m.size).collect::<Vec<_>>(),
+            vec![100]
+        );
+        assert_eq!(run.pending().map(|m| m.size).collect::<Vec<_>>(), vec![300]);
+    }
+
+    #[test]
+    fn test_deferred_moves_are_pending() {
+        let temp = TempDir::new().unwrap();
+        let plan = BalancingPlan {
+            decisions: vec![
+                demote(Path::new("/cache/a.mkv"), 100),
+                demote(Path::new("/cache/b.mkv"), 200),
+            ],
+            ..BalancingPlan::default()
+        };
+
+        let mut log = ProgressLog::start(temp.path(), &plan).unwrap();
+        log.record(0, MoveStatus::Done);
+        log.record(1, MoveStatus::Deferred);
+        drop(log);
+
+        let run = InterruptedRun::load(temp.path()).unwrap().unwrap();
+        assert_eq!(run.pending().map(|m| m.size).collect::<Vec<_>>(), vec![200]);
+    }
+
+    #[test]
+    fn test_clean_partials_and_remaining_plan() {
+        let temp = TempDir::new().unwrap();
+        let cache = tier("cache", &temp.path().join("cache"));
+        let storage = tier("storage", &temp.path().join("storage"));
+        let state_dir = temp.path().join("state");
+
+        let moved = cache.path.join("moved.mkv");
+        let pending = cache.path.join("pending.mkv");
+        fs::write(&pending, b"data").unwrap();
+        let partial = partial_path(&storage.path.join("pending.mkv"));
+        fs::write(&partial, b"da").unwrap();
+
+        let plan = BalancingPlan {
+            decisions: vec![demote(&moved, 100), demote(&pending, 4)],
+            ..BalancingPlan::default()
+        };
+        let mut log = ProgressLog::start(&state_dir, &plan).unwrap();
+        log.record(0, MoveStatus::Done);
+        drop(log);
+
+        let run = InterruptedRun::load(&state_dir).unwrap().unwrap();
+        assert_eq!(run.clean_partials(&[cache, storage]), 1);
+        assert!(!partial.exists());
+
+        let remaining = run.remaining_plan();
+        assert_eq!(remaining.decisions.len(), 1);
+        assert_eq!(remaining.decisions[0].file_path(), &pending);
+
+        InterruptedRun::discard(&state_dir).unwrap();
+        assert!(InterruptedRun::load(&state_dir).unwrap().is_none());
+    }
+}
@@ -0,0 +1,505 @@
+//! Tiers on other machines, reached over SSH
+//!
+//! A tier with `path: ssh://user@host:port/path` is measured with `df`, scanned
+//! with GNU `find` and written with rsync's remote syntax, all through `ssh` in
+//! batch mode, so key-based login must already work.
+
+use crate::disk::{DiskOperations, Space, SpaceCache};
+use crate::file::{FileInfo, FileOwner};
+use crate::mover::{CopyProgress, RsyncFailed, report_rsync_progress};
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
+
+/// Prefix of a remote tier path
+pub const SCHEME: &str = "ssh://";
+
+/// How long a `df` answer is reused, since planning asks for space many times
+const SPACE_TTL: Duration = Duration::from_secs(10);
+
+/// Host and directory of a remote tier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshRemote {
+    pub user: Option<String>,
+    pub host: String,
+    pub port: Option<u16>,
+    /// Tier root on the remote host
+    pub path: PathBuf,
+}
+
+impl SshRemote {
+    /// Parses `ssh://[user@]host[:port]/absolute/path`, with IPv6 hosts as
+    /// `[addr]`; `None` for local paths
+    pub fn parse(path: &Path) -> Option<Result<Self, String>> {
+        let rest = path.to_str()?.strip_prefix(SCHEME)?;
+        Some(Self::parse_authority(rest))
+    }
+
+    fn parse_authority(rest: &str) -> Result<Self, String> {
+        let (authority, root) = rest
+            .find('/')
+            .map_or((rest, ""), |slash| rest.split_at(slash));
+        if root.len() < 2 {
+            return Err("remote tier path needs a directory: ssh://host/path".to_string());
+        }
+        let (user, host) = match authority.split_once('@') {
+            Some((user, host)) => (Some(user.to_string()), host),
+            None => (None, authority),
+        };
+        let (host, port) = match host.strip_prefix('[') {
+            Some(bracketed) => {
+                let (host, rest) = bracketed
+                    .split_once(']')
+                    .ok_or_else(|| format!("unclosed '[' in SSH host '{host}'"))?;
+                match rest.strip_prefix(':') {
+                    Some(port) => (host, Some(port)),
+                    None if rest.is_empty() => (host, None),
+                    None => return Err(format!("unexpected '{rest}' after SSH host '[{host}]'")),
+                }
+            }
+            None => match host.split_once(':') {
+                Some((_, port)) if port.contains(':') => {
+                    return Err(format!(
+                        "IPv6 SSH host '{host}' must be written in brackets: [{host}]"
+                    ));
+                }
+                Some((host, port)) => (host, Some(port)),
+                None => (host, None),
+            },
+        };
+        let port = port
+            .map(|port| {
+                port.parse()
+                    .map_err(|_| format!("invalid SSH port '{port}'"))
+            })
+            .transpose()?;
+        if host.is_empty() || user.as_deref() == Some("") {
+            return Err("remote tier path needs a host: ssh://user@host/path".to_string());
+        }
+        Ok(Self {
+            user,
+            host: host.to_string(),
+            port,
+            path: PathBuf::from(root.trim_end_matches('/')),
+        })
+    }
+
+    /// `user@host`, or just the host
+    pub fn destination(&self) -> String {
+        match &self.user {
+            Some(user) => format!("{user}@{}", self.host),
+            None => self.host.clone(),
+        }
+    }
+
+    /// `ssh` with the options for this host, before the destination
+    fn ssh_args(&self) -> Vec<String> {
+        let mut args = vec!["-o".to_string(), "BatchMode=yes".to_string()];
+        if let Some(port) = self.port {
+            args.extend(["-p".to_string(), port.to_string()]);
+        }
+        args
+    }
+
+    /// Runs `script` through the remote shell and returns its stdout
+    fn run(&self, script: &str) -> io::Result<Vec<u8>> {
+        let output = Command::new("ssh")
+            .args(self.ssh_args())
+            .arg(self.destination())
+            .arg("--")
+            .arg(script)
+            .stdin(Stdio::null())
+            .output()?;
+        if output.status.success() {
+            Ok(output.stdout)
+        } else {
+            Err(io::Error::other(format!(
+                "ssh {} '{script}' failed ({}): {}",
+                self.destination(),
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )))
+        }
+    }
+
+    /// Total and available bytes of the filesystem holding `path`
+    pub fn space(&self, path: &Path) -> io::Result<Space> {
+        let output = self.run(&format!("df -Pk {}", quote(path)))?;
+        parse_df(&String::from_utf8_lossy(&output)).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unexpected df output for {}", path.display()),
+            )
+        })
+    }
+
+    /// Regular files under `roots`; roots missing on the host are skipped
+    pub fn list_files(&self, roots: &[PathBuf]) -> io::Result<Vec<FileInfo>> {
+        let mut files = Vec::new();
+        for root in roots {
+            let script = format!(
+                "[ ! -d {root} ] || find {root} -type f -printf '%s %T@ %A@ %U %G %p\\0'",
+                root = quote(root)
+            );
+            files.extend(parse_find(&self.run(&script)?));
+        }
+        Ok(files)
+    }
+
+    /// `user@host` with an IPv6 host in brackets, as rsync and URLs expect
+    fn bracketed_destination(&self) -> String {
+        if self.host.contains(':') {
+            let user = self
+                .user
+                .as_ref()
+                .map(|u| format!("{u}@"))
+                .unwrap_or_default();
+            format!("{user}[{}]", self.host)
+        } else {
+            self.destination()
+        }
+    }
+
+    /// `ssh://` URL of `path` on this host, the form tier paths are configured in
+    pub fn url(&self, path: &Path) -> String {
+        let port = self.port.map(|port| format!(":{port}")).unwrap_or_default();
+        format!(
+            "{SCHEME}{}{port}{}",
+            self.bracketed_destination(),
+            path.display()
+        )
+    }
+
+    /// Deletes one file on the host
+    pub fn remove_file(&self, path: &Path) -> io::Result<()> {
+        self.run(&format!("rm -f -- {}", quote(path))).map(drop)
+    }
+
+    /// Size and whole-second mtime of `path`, `None` if it does not exist
+    pub fn stat(&self, path: &Path) -> io::Result<Option<FileStamp>> {
+        let path = quote(path);
+        let output = self.run(&format!("[ ! -e {path} ] || stat -c '%s %Y' -- {path}"))?;
+        let output = String::from_utf8_lossy(&output);
+        if output.trim().is_empty() {
+            return Ok(None);
+        }
+        output
+            .split_once(' ')
+            .and_then(|(size, mtime)| Some((size.parse().ok()?, mtime.trim().parse().ok()?)))
+            .map(Some)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unexpected stat output '{}'", output.trim()),
+                )
+            })
+    }
+
+    /// Renames a file on the host, replacing `to`
+    pub fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.run(&format!("mv -f -- {} {}", quote(from), quote(to)))
+            .map(drop)
+    }
+
+    /// `user@host:path` for rsync
+    fn rsync_spec(&self, path: &Path) -> OsString {
+        let mut spec = OsString::from(format!("{}:", self.bracketed_destination()));
+        spec.push(path.as_os_str());
+        spec
+    }
+}
+
+/// Size and modification time in whole seconds, enough to tell two files apart
+/// the way rsync's quick check does
+pub type FileStamp = (u64, u64);
+
+/// `stat` of `path` on `host`, or locally without one
+pub fn stat_on(host: Option<&SshRemote>, path: &Path) -> io::Result<Option<FileStamp>> {
+    let Some(host) = host else {
+        return match std::fs::metadata(path) {
+            Ok(metadata) => {
+                let mtime = metadata
+                    .modified()?
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs());
+                Ok(Some((metadata.len(), mtime)))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        };
+    };
+    host.stat(path)
+}
+
+/// Renames `from` to `to` on `host`, or locally without one
+pub fn rename_on(host: Option<&SshRemote>, from: &Path, to: &Path) -> io::Result<()> {
+    host.map_or_else(|| std::fs::rename(from, to), |host| host.rename(from, to))
+}
+
+/// Deletes `path` on `host`, or locally without one
+pub fn remove_on(host: Option<&SshRemote>, path: &Path) -> io::Result<()> {
+    host.map_or_else(|| std::fs::remove_file(path), |host| host.remove_file(path))
+}
+
+/// One file moved or copied with rsync while either side is on an SSH host
+///
+/// The mover decides conflicts, verification and source removal; this only
+/// runs the copy. Moves between two remote tiers are not supported.
+#[derive(Debug, Clone, Copy)]
+pub struct RemoteTransfer<'a> {
+    /// Host of the source tier, `None` when it is local
+    pub from: Option<&'a SshRemote>,
+    /// Host of the destination tier, `None` when it is local
+    pub to: Option<&'a SshRemote>,
+    pub source: &'a Path,
+    pub destination: &'a Path,
+    /// Size of the source as scanned, for progress
+    pub size: u64,
+    /// Keep the source in place
+    pub copy: bool,
+}
+
+impl RemoteTransfer<'_> {
+    /// The one remote side rsync connects to
+    pub fn remote(&self) -> io::Result<&SshRemote> {
+        match (self.from, self.to) {
+            (Some(_), Some(_)) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!(
+                    "Moving {} between two remote tiers is not supported",
+                    self.source.display()
+                ),
+            )),
+            (Some(remote), None) | (None, Some(remote)) => Ok(remote),
+            (None, None) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "remote transfer between two local paths",
+            )),
+        }
+    }
+
+    /// Copies the source to `target` on the destination side, creating its
+    /// directory; `args` go to rsync before the paths
+    pub fn copy_to(
+        &self,
+        target: &Path,
+        args: &[&str],
+        extra_args: &[String],
+        on_progress: &mut dyn FnMut(CopyProgress),
+    ) -> io::Result<()> {
+        let remote = self.remote()?;
+        if let Some(parent) = target.parent() {
+            match self.to {
+                Some(to) => to.run(&format!("mkdir -p {}", quote(parent))).map(drop)?,
+                None => std::fs::create_dir_all(parent)?,
+            }
+        }
+
+        let spec = |side: Option<&SshRemote>, path: &Path| {
+            side.map_or_else(|| path.as_os_str().to_owned(), |r| r.rsync_spec(path))
+        };
+        let mut cmd = Command::new("rsync");
+        cmd.arg("--protect-args")
+            .args(args)
+            .arg("--info=progress2")
+            .args(extra_args)
+            .arg("-e")
+            .arg(format!("ssh {}", remote.ssh_args().join(" ")))
+            .arg(spec(self.from, self.source))
+            .arg(spec(self.to, target))
+            .stdin(Stdio::null());
+        // Keep Ctrl+C in the terminal from killing the copy; the executor stops after it
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+
+        let status = cmd.stdout(Stdio::piped()).spawn().and_then(|mut child| {
+            if let Some(stdout) = child.stdout.take() {
+                report_rsync_progress(stdout, self.size, on_progress);
+            }
+            child.wait()
+        })?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(io::Error::other(RsyncFailed {
+                code: status.code(),
+            }))
+        }
+    }
+}
+
+/// Disk space of a remote tier, asked with `df` over SSH
+#[derive(Debug)]
+pub struct SshDisk {
+    remote: SshRemote,
+    cache: SpaceCache,
+}
+
+impl SshDisk {
+    pub fn new(remote: SshRemote) -> Self {
+        Self {
+            remote,
+            cache: SpaceCache::new(SPACE_TTL),
+        }
+    }
+
+    fn space(&self, path: &Path) -> Option<Space> {
+        self.cache.get(path, || {
+            self.remote
+                .space(path)
+                .inspect_err(|e| {
+                    tracing::warn!(
+                        "Failed to get space for {}:{}: {e}",
+                        self.remote.destination(),
+                        path.display()
+                    );
+                })
+                .ok()
+        })
+    }
+}
+
+impl DiskOperations for SshDisk {
+    fn get_total_space(&self, path: &Path) -> u64 {
+        // 1 avoids division by zero, like the local disk
+        self.space(path).map_or(1, |(total, _)| total)
+    }
+
+    fn get_free_space(&self, path: &Path) -> u64 {
+        self.space(path).map_or(0, |(_, free)| free)
+    }
+}
+
+/// Quotes `path` for a POSIX shell
+fn quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', r"'\''"))
+}
+
+/// Total and available bytes from `df -Pk` output
+fn parse_df(output: &str) -> Option<Space> {
+    let fields: Vec<&str> = output.lines().nth(1)?.split_whitespace().collect();
+    let total: u64 = fields.get(1)?.parse().ok()?;
+    let available: u64 = fields.get(3)?.parse().ok()?;
+    Some((total * 1024, available * 1024))
+}
+
+/// Files from NUL-separated `find -printf '%s %T@ %A@ %U %G %p\0'` records
+fn parse_find(output: &[u8]) -> Vec<FileInfo> {
+    output
+        .split(|&b| b == 0)
+        .filter(|record| !record.is_empty())
+        .filter_map(|record| {
+            let record = String::from_utf8_lossy(record);
+            let mut fields = record.splitn(6, ' ');
+            let size = fields.next()?.parse().ok()?;
+            let modified = timestamp(fields.next()?)?;
+            let accessed = timestamp(fields.next()?)?;
+            let uid = fields.next()?.parse().ok()?;
+            let gid = fields.next()?.parse().ok()?;
+            Some(FileInfo {
+                path: PathBuf::from(fields.next()?),
+                size,
+                modified,
+                accessed,
+                owner: Some(FileOwner { uid, gid }),
+            })
+        })
+        .collect()
+}
+
+/// `1700000000.123` seconds since the epoch
+fn timestamp(field: &str) -> Option<SystemTime> {
+    let secs: f64 = field.parse().ok()?;
+    (secs.is_finite() && secs >= 0.0)
+        .then(|| SystemTime::UNIX_EPOCH + Duration::from_secs_f64(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_remote_path() {
+        let remote = SshRemote::parse(Path::new("ssh://media@nas:2222/volume1/media/"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(remote.destination(), "media@nas");
+        assert_eq!(remote.port, Some(2222));
+        assert_eq!(remote.path, PathBuf::from("/volume1/media"));
+        assert_eq!(
+            remote.rsync_spec(Path::new("/volume1/media/a b.mkv")),
+            OsString::from("media@nas:/volume1/media/a b.mkv")
+        );
+
+        assert_eq!(
+            remote.url(Path::new("/volume1/media/a.mkv")),
+            "ssh://media@nas:2222/volume1/media/a.mkv"
+        );
+
+        assert!(SshRemote::parse(Path::new("/mnt/cache")).is_none());
+        assert!(SshRemote::parse(Path::new("ssh://nas")).unwrap().is_err());
+        assert!(
+            SshRemote::parse(Path::new("ssh://nas:x/data"))
+                .unwrap()
+                .is_err()
+        );
+
+        let ipv6 = SshRemote::parse(Path::new("ssh://media@[fd00::2]:2222/data"))
+            .unwrap()
+            .unwrap();
+        assert_eq!((ipv6.host.as_str(), ipv6.port), ("fd00::2", Some(2222)));
+        assert_eq!(ipv6.destination(), "media@fd00::2");
+        assert_eq!(
+            ipv6.rsync_spec(Path::new("/data/a.mkv")),
+            OsString::from("media@[fd00::2]:/data/a.mkv")
+        );
+        assert_eq!(
+            ipv6.url(Path::new("/data/a.mkv")),
+            "ssh://media@[fd00::2]:2222/data/a.mkv"
+        );
+        let ipv6 = SshRemote::parse(Path::new("ssh://[::1]/data"))
+            .unwrap()
+            .unwrap();
+        assert_eq!((ipv6.host.as_str(), ipv6.port), ("::1", None));
+        assert!(
+            SshRemote::parse(Path::new("ssh://fd00::2/data"))
+                .unwrap()
+                .is_err()
+        );
+        assert!(
+            SshRemote::parse(Path::new("ssh://[fd00::2/data"))
+                .unwrap()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_df_and_find() {
+        let df = "Filesystem     1024-blocks      Used Available Capacity Mounted on\n\
+                  /dev/md2        1000000    400000    600000      40% /volume1\n";
+        assert_eq!(parse_df(df), Some((1_024_000_000, 614_400_000)));
+        assert_eq!(parse_df("garbage"), None);
+
+        let find =
+            b"1000 1700000000.5 1700000100.0000000000 1026 100 /volume1/media/Show/e 1.mkv\0\
+                     bad record\0";
+        let files = parse_find(find);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, PathBuf::from("/volume1/media/Show/e 1.mkv"));
+        assert_eq!(files[0].size, 1000);
+        assert_eq!(
+            files[0].modified,
+            SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_500)
+        );
+        assert_eq!(
+            files[0].owner,
+            Some(FileOwner {
+                uid: 1026,
+                gid: 100
+            })
+        );
+        assert_eq!(quote(Path::new("/a/it's")), r"'/a/it'\''s'");
+    }
+}
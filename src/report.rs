@@ -0,0 +1,362 @@
+//! Text tables and colors for the human-readable plan output
+//!
+//! Colors are ANSI escapes, off when `NO_COLOR` is set, with `--no-color`, or
+//! when stderr is not a terminal.
+
+use crate::{BalancingPlan, PlacementDecision};
+use clap::ValueEnum;
+use std::collections::HashMap;
+use std::io::IsTerminal;
+
+/// Order of the moves listed in the text plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MoveSort {
+    /// Largest files first
+    Size,
+    /// Highest strategy priority first
+    Priority,
+    /// Alphabetical by file path
+    Path,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Cyan,
+    Dim,
+}
+
+impl Color {
+    const fn code(self) -> &'static str {
+        match self {
+            Self::Red => "31",
+            Self::Green => "32",
+            Self::Yellow => "33",
+            Self::Cyan => "36",
+            Self::Dim => "2",
+        }
+    }
+}
+
+/// Applies colors, or leaves text plain when colors are off
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Palette {
+    enabled: bool,
+}
+
+impl Palette {
+    pub const fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    /// Colors for a terminal on stderr, unless `no_color` or `NO_COLOR` turn them off
+    pub fn detect(no_color: bool) -> Self {
+        let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        Self::new(!no_color && !no_color_env && std::io::stderr().is_terminal())
+    }
+
+    pub fn paint(self, text: &str, color: Color) -> String {
+        if self.enabled {
+            format!("\x1b[{}m{text}\x1b[0m", color.code())
+        } else {
+            text.to_string()
+        }
+    }
+
+    pub fn bold(self, text: &str) -> String {
+        if self.enabled {
+            format!("\x1b[1m{text}\x1b[0m")
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// How `rebalance` prints the plan as text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlanView {
+    /// Order of the listed moves; `None` keeps the execution order
+    pub sort: Option<MoveSort>,
+    /// Moves or directories listed, 0 for all of them
+    pub limit: usize,
+    /// List individual moves instead of one line per directory
+    pub expand: bool,
+    pub palette: Palette,
+}
+
+impl Default for PlanView {
+    fn default() -> Self {
+        Self {
+            sort: None,
+            limit: 10,
+            expand: false,
+            palette: Palette::default(),
+        }
+    }
+}
+
+impl PlanView {
+    /// The plan's moves and copies in the chosen order, at most `limit` of them
+    pub fn moves<'a>(&self, plan: &'a BalancingPlan) -> Vec<&'a PlacementDecision> {
+        let mut moves: Vec<_> = plan
+            .decisions
+            .iter()
+            .filter(|d| !matches!(d, PlacementDecision::Stay { .. }))
+            .collect();
+        match self.sort {
+            Some(MoveSort::Size) => moves.sort_by(|a, b| {
+                b.file_size()
+                    .cmp(&a.file_size())
+                    .then_with(|| a.file_path().cmp(b.file_path()))
+            }),
+            Some(MoveSort::Priority) => moves.sort_by(|a, b| {
+                b.strategy_priority()
+                    .cmp(&a.strategy_priority())
+                    .then_with(|| a.file_path().cmp(b.file_path()))
+            }),
+            Some(MoveSort::Path) => moves.sort_by(|a, b| a.file_path().cmp(b.file_path())),
+            None => {}
+        }
+        if self.limit > 0 {
+            moves.truncate(self.limit);
+        }
+        moves
+    }
+}
+
+/// Files and bytes moved by one strategy, per direction
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StrategyMoves {
+    pub strategy: String,
+    pub promoted: usize,
+    pub demoted: usize,
+    pub copied: usize,
+    pub bytes: u64,
+}
+
+impl StrategyMoves {
+    /// One entry per strategy with moves, most bytes first
+    pub fn collect(plan: &BalancingPlan) -> Vec<Self> {
+        let mut by_strategy: HashMap<&str, Self> = HashMap::new();
+        for decision in &plan.decisions {
+            let entry = by_strategy
+                .entry(decision.strategy_name())
+                .or_insert_with(|| Self {
+                    strategy: decision.strategy_name().to_string(),
+                    ..Self::default()
+                });
+            match decision {
+                PlacementDecision::Stay { .. } => continue,
+                PlacementDecision::Promote { .. } => entry.promoted += 1,
+                PlacementDecision::Demote { .. } => entry.demoted += 1,
+                PlacementDecision::Copy { .. } => entry.copied += 1,
+            }
+            entry.bytes += decision.file_size();
+        }
+        let mut strategies: Vec<Self> = by_strategy
+            .into_values()
+            .filter(|s| s.promoted + s.demoted + s.copied > 0)
+            .collect();
+        strategies.sort_by(|a, b| {
+            b.bytes
+                .cmp(&a.bytes)
+                .then_with(|| a.strategy.cmp(&b.strategy))
+        });
+        strategies
+    }
+}
+
+/// One table cell, optionally colored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cell {
+    text: String,
+    color: Option<Color>,
+}
+
+impl Cell {
+    pub fn colored(text: impl Into<String>, color: Color) -> Self {
+        Self {
+            text: text.into(),
+            color: Some(color),
+        }
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Self { text, color: None }
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Self::from(text.to_string())
+    }
+}
+
+/// Columns padded to their widest cell, with a bold header
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    headers: Vec<String>,
+    right_aligned: Vec<bool>,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    pub fn new<'a>(headers: impl IntoIterator<Item = &'a str>) -> Self {
+        let headers: Vec<String> = headers.into_iter().map(str::to_string).collect();
+        Self {
+            right_aligned: vec![false; headers.len()],
+            headers,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Right-aligns the given columns, for numbers
+    pub fn align_right(mut self, columns: impl IntoIterator<Item = usize>) -> Self {
+        for column in columns {
+            if let Some(right) = self.right_aligned.get_mut(column) {
+                *right = true;
+            }
+        }
+        self
+    }
+
+    pub fn row(&mut self, cells: impl IntoIterator<Item = Cell>) {
+        self.rows.push(cells.into_iter().collect());
+    }
+
+    /// Lines indented by two spaces, each ending in a newline
+    pub fn render(&self, palette: Palette) -> String {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+        for row in &self.rows {
+            for (column, cell) in row.iter().enumerate() {
+                let width = cell.text.chars().count();
+                match widths.get_mut(column) {
+                    Some(max) => *max = (*max).max(width),
+                    None => widths.push(width),
+                }
+            }
+        }
+
+        let line = |cells: Vec<(String, Option<Color>)>, bold: bool| {
+            let mut out = String::from(" ");
+            for (column, (text, color)) in cells.into_iter().enumerate() {
+                let pad = widths[column].saturating_sub(text.chars().count());
+                let right = self.right_aligned.get(column).copied().unwrap_or(false);
+                let styled = match color {
+                    Some(color) => palette.paint(&text, color),
+                    None if bold => palette.bold(&text),
+                    None => text,
+                };
+                out.push(' ');
+                if right {
+                    out.push_str(&" ".repeat(pad));
+                    out.push_str(&styled);
+                } else {
+                    out.push_str(&styled);
+                    out.push_str(&" ".repeat(pad));
+                }
+                out.push(' ');
+            }
+            out.truncate(out.trim_end().len());
+            out.push('\n');
+            out
+        };
+
+        let mut out = line(
+            self.headers.iter().map(|h| (h.clone(), None)).collect(),
+            true,
+        );
+        let rule: Vec<String> = widths.iter().map(|&w| "─".repeat(w)).collect();
+        out.push_str(&line(
+            rule.into_iter().map(|r| (r, Some(Color::Dim))).collect(),
+            false,
+        ));
+        for row in &self.rows {
+            out.push_str(&line(
+                row.iter().map(|c| (c.text.clone(), c.color)).collect(),
+                false,
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileInfo;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::SystemTime;
+
+    fn decision(path: &str, size: u64, strategy: &str, priority: u32) -> PlacementDecision {
+        PlacementDecision::Demote {
+            file: Arc::new(FileInfo {
+                path: PathBuf::from(path),
+                size,
+                modified: SystemTime::UNIX_EPOCH,
+                accessed: SystemTime::UNIX_EPOCH,
+                owner: None,
+            }),
+            from_tier: "cache".to_string(),
+            to_tier: "storage".to_string(),
+            strategy: strategy.to_string(),
+            priority,
+        }
+    }
+
+    #[test]
+    fn test_table_render() {
+        let mut table = Table::new(["Tier", "Files"]).align_right([1]);
+        table.row(["cache".into(), "7".into()]);
+        table.row([Cell::colored("storage", Color::Green), "1234".into()]);
+
+        assert_eq!(
+            table.render(Palette::new(false)),
+            "  Tier     Files\n  ───────  ─────\n  cache        7\n  storage   1234\n"
+        );
+        let colored = table.render(Palette::new(true));
+        assert!(colored.contains("\x1b[1mTier\x1b[0m"));
+        assert!(colored.contains("\x1b[32mstorage\x1b[0m   1234"));
+    }
+
+    #[test]
+    fn test_plan_view_sorts_and_limits_moves() {
+        let plan = BalancingPlan {
+            decisions: vec![
+                decision("/mnt/cache/b.mkv", 5, "old", 10),
+                decision("/mnt/cache/a.mkv", 1, "big", 50),
+                decision("/mnt/cache/c.mkv", 9, "old", 10),
+            ],
+            ..Default::default()
+        };
+        let paths = |sort, limit| {
+            let view = PlanView {
+                sort,
+                limit,
+                ..PlanView::default()
+            };
+            view.moves(&plan)
+                .iter()
+                .map(|d| d.file_path().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(paths(None, 2), ["/mnt/cache/b.mkv", "/mnt/cache/a.mkv"]);
+        assert_eq!(paths(Some(MoveSort::Size), 1), ["/mnt/cache/c.mkv"]);
+        assert_eq!(
+            paths(Some(MoveSort::Priority), 0),
+            ["/mnt/cache/a.mkv", "/mnt/cache/b.mkv", "/mnt/cache/c.mkv"]
+        );
+        assert_eq!(paths(Some(MoveSort::Path), 1), ["/mnt/cache/a.mkv"]);
+
+        let strategies = StrategyMoves::collect(&plan);
+        assert_eq!(strategies.len(), 2);
+        assert_eq!(strategies[0].strategy, "old");
+        assert_eq!((strategies[0].demoted, strategies[0].bytes), (2, 14));
+    }
+}
@@ -0,0 +1,222 @@
+//! JSON log file per run, written alongside the stderr output
+//!
+//! Each run starts a fresh file so an audit can be read run by run; older files
+//! are kept as `<file>.1`, `<file>.2`, ... up to `max_files`.
+
+use crate::config::LoggingConfig;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use tracing::{Level, Subscriber};
+use tracing_subscriber::Layer;
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Handle to the run log; clones share the same file
+#[derive(Clone, Default)]
+pub struct RunLog {
+    file: Arc<Mutex<Option<RotatingFile>>>,
+    /// Most detailed level written, 0 while no file is open
+    level: Arc<AtomicU8>,
+}
+
+impl RunLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts the log of a new run; `None` stops writing to a file
+    pub fn start_run(&self, config: Option<&LoggingConfig>) -> io::Result<()> {
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        self.level.store(0, Ordering::SeqCst);
+        *file = None;
+
+        if let Some(config) = config {
+            *file = Some(RotatingFile::open(config)?);
+            self.level
+                .store(level_code(config.level.into()), Ordering::SeqCst);
+        }
+        Ok(())
+    }
+
+    /// JSON formatting layer for tierflow's own events
+    pub fn layer<S>(&self) -> impl Layer<S> + Send + Sync + 'static
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let level = Arc::clone(&self.level);
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_writer(self.clone())
+            .with_filter(filter_fn(move |meta| {
+                meta.target().starts_with("tierflow")
+                    && level_code(*meta.level()) <= level.load(Ordering::Relaxed)
+            }))
+    }
+}
+
+impl<'a> MakeWriter<'a> for RunLog {
+    type Writer = RunLogWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RunLogWriter {
+            file: Arc::clone(&self.file),
+        }
+    }
+}
+
+/// Writes one formatted event; discarded while no run log is open
+pub struct RunLogWriter {
+    file: Arc<Mutex<Option<RotatingFile>>>,
+}
+
+impl Write for RunLogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        match file.as_mut() {
+            Some(file) => file.write(buf),
+            None => Ok(buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_files: usize,
+    max_bytes: Option<u64>,
+}
+
+impl RotatingFile {
+    fn open(config: &LoggingConfig) -> io::Result<Self> {
+        if let Some(parent) = config.file.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        rotate(&config.file, config.max_files)?;
+        Ok(Self {
+            path: config.file.clone(),
+            file: create(&config.file)?,
+            written: 0,
+            max_files: config.max_files,
+            max_bytes: config.max_size_mb.map(|mb| mb * 1024 * 1024),
+        })
+    }
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write_all(buf)?;
+        self.written += buf.len() as u64;
+        if self.max_bytes.is_some_and(|max| self.written >= max) {
+            rotate(&self.path, self.max_files)?;
+            self.file = create(&self.path)?;
+            self.written = 0;
+        }
+        Ok(buf.len())
+    }
+}
+
+fn create(path: &Path) -> io::Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(path)
+}
+
+/// Shifts `path` to `path.1`, `path.1` to `path.2`, ..., dropping the oldest
+fn rotate(path: &Path, max_files: usize) -> io::Result<()> {
+    let numbered = |n: usize| {
+        let mut name = path.as_os_str().to_os_string();
+        name.push(format!(".{n}"));
+        PathBuf::from(name)
+    };
+    let ignore_missing = |result: io::Result<()>| match result {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        other => other,
+    };
+
+    if max_files == 0 {
+        return ignore_missing(fs::remove_file(path));
+    }
+    for n in (1..max_files).rev() {
+        ignore_missing(fs::rename(numbered(n), numbered(n + 1)))?;
+    }
+    ignore_missing(fs::rename(path, numbered(1)))
+}
+
+const fn level_code(level: Level) -> u8 {
+    match level {
+        Level::ERROR => 1,
+        Level::WARN => 2,
+        Level::INFO => 3,
+        Level::DEBUG => 4,
+        Level::TRACE => 5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LogLevel;
+    use tempfile::TempDir;
+
+    fn config(file: PathBuf, max_files: usize, max_size_mb: Option<u64>) -> LoggingConfig {
+        LoggingConfig {
+            file,
+            level: LogLevel::Debug,
+            max_files,
+            max_size_mb,
+        }
+    }
+
+    #[test]
+    fn test_each_run_starts_a_new_file() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("logs/run.jsonl");
+        let run_log = RunLog::new();
+        let config = config(path.clone(), 2, None);
+
+        for run in 1..=4 {
+            run_log.start_run(Some(&config)).unwrap();
+            writeln!(run_log.make_writer(), "run {run}").unwrap();
+        }
+        run_log.start_run(None).unwrap();
+        writeln!(run_log.make_writer(), "discarded").unwrap();
+
+        let read = |name: &str| fs::read_to_string(temp.path().join("logs").join(name)).unwrap();
+        assert_eq!(read("run.jsonl"), "run 4\n");
+        assert_eq!(read("run.jsonl.1"), "run 3\n");
+        assert_eq!(read("run.jsonl.2"), "run 2\n");
+        assert!(!temp.path().join("logs/run.jsonl.3").exists());
+    }
+
+    #[test]
+    fn test_rotates_within_a_run_by_size() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("run.jsonl");
+        let run_log = RunLog::new();
+        run_log
+            .start_run(Some(&config(path.clone(), 3, Some(1))))
+            .unwrap();
+
+        let line = vec![b'x'; 700 * 1024];
+        run_log.make_writer().write_all(&line).unwrap();
+        run_log.make_writer().write_all(&line).unwrap();
+        run_log.make_writer().write_all(b"tail").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "tail");
+        assert_eq!(
+            fs::metadata(temp.path().join("run.jsonl.1")).unwrap().len(),
+            1400 * 1024
+        );
+    }
+}
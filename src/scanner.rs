@@ -0,0 +1,189 @@
+//! Listing the files of a tier
+
+use crate::{FileInfo, Tier};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Operating system and NAS metadata, skipped with `ignore_hidden` like dotfiles
+const SYSTEM_NAMES: &[&str] = &[
+    "Thumbs.db",
+    "desktop.ini",
+    "@eaDir",
+    "$RECYCLE.BIN",
+    "System Volume Information",
+    "lost+found",
+];
+
+/// Lists the files the planner considers on a tier
+pub trait FileScanner: Send + Sync {
+    fn scan(&self, tier: &Tier) -> Vec<FileInfo>;
+}
+
+/// Walks the tier's scan roots on disk
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TierScanner;
+
+impl FileScanner for TierScanner {
+    fn scan(&self, tier: &Tier) -> Vec<FileInfo> {
+        tier.get_all_files()
+    }
+}
+
+/// Walks only some directories of every tier, for incremental planning
+///
+/// Directories are relative to the member roots, so a directory spread over
+/// several tiers is scanned on all of them.
+#[derive(Debug, Clone, Default)]
+pub struct ScopedScanner {
+    dirs: Vec<PathBuf>,
+}
+
+impl ScopedScanner {
+    pub const fn new(dirs: Vec<PathBuf>) -> Self {
+        Self { dirs }
+    }
+}
+
+impl FileScanner for ScopedScanner {
+    fn scan(&self, tier: &Tier) -> Vec<FileInfo> {
+        tier.get_files_under(&self.dirs)
+    }
+}
+
+/// Files the planner leaves out on a tier
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScanFilter {
+    /// Skip dotfiles, files in dot directories and system metadata
+    pub ignore_hidden: bool,
+    /// Skip files smaller than this many bytes
+    pub min_file_size: u64,
+}
+
+impl ScanFilter {
+    /// Removes the files the filter rejects from `files`, counting them
+    pub fn apply(&self, tier: &Tier, files: &mut Vec<FileInfo>) -> FilteredFiles {
+        self.apply_keeping_small(tier, files, |_| false)
+    }
+
+    /// [`Self::apply`], keeping files below `min_file_size` that `keep_small`
+    /// accepts, such as sidecars that must follow their media file
+    pub fn apply_keeping_small(
+        &self,
+        tier: &Tier,
+        files: &mut Vec<FileInfo>,
+        keep_small: impl Fn(&Path) -> bool,
+    ) -> FilteredFiles {
+        let mut filtered = FilteredFiles {
+            tier: tier.name.clone(),
+            ..FilteredFiles::default()
+        };
+        if *self == Self::default() {
+            return filtered;
+        }
+        files.retain(|file| {
+            let relative = tier.relative_path(&file.path).unwrap_or(&file.path);
+            if self.ignore_hidden && is_hidden(relative) {
+                filtered.hidden += 1;
+            } else if file.size < self.min_file_size && !keep_small(&file.path) {
+                filtered.too_small += 1;
+            } else {
+                return true;
+            }
+            filtered.bytes += file.size;
+            false
+        });
+        filtered
+    }
+}
+
+fn is_hidden(relative: &Path) -> bool {
+    relative.components().any(|component| match component {
+        Component::Normal(name) => {
+            let name = name.to_string_lossy();
+            name.starts_with('.') || SYSTEM_NAMES.contains(&name.as_ref())
+        }
+        _ => false,
+    })
+}
+
+/// Files a tier's scan filter left out of the plan
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FilteredFiles {
+    pub tier: String,
+    /// Hidden or system files, with `ignore_hidden`
+    pub hidden: usize,
+    /// Files below `min_file_size`
+    pub too_small: usize,
+    pub bytes: u64,
+}
+
+impl FilteredFiles {
+    pub const fn total(&self) -> usize {
+        self.hidden + self.too_small
+    }
+}
+
+/// Fixed file lists per tier name, for planning without a filesystem
+#[derive(Debug, Clone, Default)]
+pub struct MemoryScanner {
+    files: HashMap<String, Vec<FileInfo>>,
+}
+
+impl MemoryScanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_file(mut self, tier: impl Into<String>, file: FileInfo) -> Self {
+        self.files.entry(tier.into()).or_default().push(file);
+        self
+    }
+}
+
+impl FileScanner for MemoryScanner {
+    fn scan(&self, tier: &Tier) -> Vec<FileInfo> {
+        self.files.get(&tier.name).cloned().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    fn file(path: &str, size: u64) -> FileInfo {
+        FileInfo {
+            path: PathBuf::from(path),
+            size,
+            modified: SystemTime::UNIX_EPOCH,
+            accessed: SystemTime::UNIX_EPOCH,
+            owner: None,
+        }
+    }
+
+    #[test]
+    fn test_scan_filter_counts_skipped_files() {
+        let tier = Tier::new_mock("cache", 1, None, 1000, 500);
+        let mut files = vec![
+            file("/mock/Movie/movie.mkv", 5000),
+            file("/mock/Movie/movie.nfo", 20),
+            file("/mock/Movie/.movie.mkv.swp", 4000),
+            file("/mock/.stfolder/index", 4000),
+            file("/mock/Movie/@eaDir/thumb.jpg", 4000),
+        ];
+        let filter = ScanFilter {
+            ignore_hidden: true,
+            min_file_size: 1000,
+        };
+
+        let filtered = filter.apply(&tier, &mut files);
+
+        let kept: Vec<_> = files.iter().map(|f| f.path.as_path()).collect();
+        assert_eq!(kept, vec![Path::new("/mock/Movie/movie.mkv")]);
+        assert_eq!(filtered.hidden, 3);
+        assert_eq!(filtered.too_small, 1);
+        assert_eq!(filtered.bytes, 12_020);
+        assert_eq!(filtered.total(), 4);
+    }
+}
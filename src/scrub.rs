@@ -0,0 +1,245 @@
+//! At-rest verification of moved files against the checksums in the state journal
+//!
+//! A checksum is recorded when a move is verified by hash (`checksum_once` or
+//! `paranoid`), so only those files can be scrubbed, for as long as the journal
+//! keeps their move.
+
+use crate::config::HashAlgorithm;
+use crate::{HashBudget, Hasher, StateJournal, Tier};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use xxhash_rust::xxh3::xxh3_64_with_seed;
+
+/// Which files a scrub rereads
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScrubOptions {
+    /// Only files currently on this tier
+    pub tier: Option<String>,
+    /// Share of the candidate files to reread, 1-100
+    pub sample_percent: u8,
+    /// Picks the sample; a different salt samples different files
+    pub salt: u64,
+    /// Threads hashing parts of one large file in parallel
+    pub threads_per_file: usize,
+}
+
+impl Default for ScrubOptions {
+    fn default() -> Self {
+        Self {
+            tier: None,
+            sample_percent: 100,
+            salt: 0,
+            threads_per_file: 1,
+        }
+    }
+}
+
+/// A file whose content no longer matches the checksum of its last move
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScrubMismatch {
+    pub path: PathBuf,
+    pub tier: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+/// Outcome of a scrub
+#[derive(Debug, Default, Serialize)]
+pub struct ScrubReport {
+    /// Files with a recorded checksum on the scrubbed tiers
+    pub candidates: usize,
+    /// Files reread with a matching checksum
+    pub verified: usize,
+    pub bytes_verified: u64,
+    pub mismatches: Vec<ScrubMismatch>,
+    /// Journal paths not found on any tier (only reported without a tier filter)
+    pub missing: Vec<PathBuf>,
+    pub errors: Vec<(PathBuf, String)>,
+    /// Stopped before every sampled file was reread
+    pub cancelled: bool,
+}
+
+impl ScrubReport {
+    pub const fn is_clean(&self) -> bool {
+        self.mismatches.is_empty() && self.errors.is_empty()
+    }
+}
+
+struct Candidate<'a> {
+    path: PathBuf,
+    tier: &'a Tier,
+    expected: &'a str,
+}
+
+/// Rereads a sample of the files with a recorded checksum and compares the hashes
+pub fn scrub(
+    journal: &StateJournal,
+    tiers: &[Tier],
+    options: &ScrubOptions,
+    budget: &HashBudget,
+    cancel: &AtomicBool,
+) -> ScrubReport {
+    let mut report = ScrubReport::default();
+    let mut candidates = Vec::new();
+    for (relative, expected) in journal.checksums() {
+        match locate(relative, tiers) {
+            Some((path, tier)) if options.tier.as_ref().is_none_or(|name| *name == tier.name) => {
+                candidates.push(Candidate {
+                    path,
+                    tier,
+                    expected,
+                });
+            }
+            None if options.tier.is_none() => report.missing.push(relative.to_path_buf()),
+            Some(_) | None => {}
+        }
+    }
+    report.candidates = candidates.len();
+
+    let sample = sample_size(candidates.len(), options.sample_percent);
+    candidates.sort_by_cached_key(|candidate| {
+        xxh3_64_with_seed(candidate.path.as_os_str().as_encoded_bytes(), options.salt)
+    });
+    candidates.truncate(sample);
+    tracing::info!(
+        "Scrubbing {sample} of {} files with a recorded checksum",
+        report.candidates
+    );
+
+    for candidate in candidates {
+        if cancel.load(Ordering::SeqCst) {
+            report.cancelled = true;
+            break;
+        }
+        let Some(algorithm) = checksum_algorithm(candidate.expected) else {
+            report.errors.push((
+                candidate.path,
+                format!("unknown checksum '{}'", candidate.expected),
+            ));
+            continue;
+        };
+
+        let hasher = Hasher::new(algorithm).with_threads(options.threads_per_file);
+        let actual = match hasher.hash_file(&candidate.path, budget) {
+            Ok(checksum) => checksum.to_string(),
+            Err(e) => {
+                tracing::warn!("Failed to read {}: {e}", candidate.path.display());
+                report.errors.push((candidate.path, e.to_string()));
+                continue;
+            }
+        };
+        if actual == candidate.expected {
+            tracing::debug!("Verified {}", candidate.path.display());
+            report.verified += 1;
+            report.bytes_verified += candidate.path.metadata().map_or(0, |m| m.len());
+        } else {
+            tracing::error!(
+                "Checksum mismatch for {}: expected {}, got {actual}",
+                candidate.path.display(),
+                candidate.expected
+            );
+            report.mismatches.push(ScrubMismatch {
+                path: candidate.path,
+                tier: candidate.tier.name.clone(),
+                expected: candidate.expected.to_string(),
+                actual,
+            });
+        }
+    }
+    report
+}
+
+/// The file at `relative` under a tier member root, checking tiers in order
+fn locate<'a>(relative: &Path, tiers: &'a [Tier]) -> Option<(PathBuf, &'a Tier)> {
+    tiers.iter().find_map(|tier| {
+        tier.paths
+            .iter()
+            .map(|root| root.join(relative))
+            .find(|path| path.is_file())
+            .map(|path| (path, tier))
+    })
+}
+
+/// Files to reread out of `candidates`, rounded up so a non-empty set is never skipped
+fn sample_size(candidates: usize, percent: u8) -> usize {
+    (candidates * usize::from(percent.min(100))).div_ceil(100)
+}
+
+/// Algorithm of a checksum recorded as `<algorithm>:<hex>`
+fn checksum_algorithm(checksum: &str) -> Option<HashAlgorithm> {
+    let (name, _) = checksum.split_once(':')?;
+    [
+        HashAlgorithm::Xxh3,
+        HashAlgorithm::Blake3,
+        HashAlgorithm::Sha256,
+    ]
+    .into_iter()
+    .find(|algorithm| algorithm.to_string() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ExecutionMove, ExecutionResult, Verification};
+    use std::fs;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_sample_size() {
+        assert_eq!(sample_size(0, 10), 0);
+        assert_eq!(sample_size(3, 10), 1);
+        assert_eq!(sample_size(20, 10), 2);
+        assert_eq!(sample_size(7, 100), 7);
+    }
+
+    #[test]
+    fn test_scrub_detects_mismatch() {
+        let temp = TempDir::new().unwrap();
+        let cache = temp.path().join("cache");
+        let storage = temp.path().join("storage");
+        fs::create_dir_all(cache.join("Show")).unwrap();
+        fs::create_dir_all(storage.join("Show")).unwrap();
+        let tiers = vec![
+            Tier::new("cache".into(), cache.clone(), 1, None, None).unwrap(),
+            Tier::new("storage".into(), storage.clone(), 2, None, None).unwrap(),
+        ];
+
+        let hasher = Hasher::new(HashAlgorithm::Xxh3);
+        let mut result = ExecutionResult::default();
+        for name in ["intact.mkv", "rotten.mkv"] {
+            let path = storage.join("Show").join(name);
+            fs::write(&path, b"original").unwrap();
+            let checksum = hasher.hash_file(&path, &HashBudget::unlimited()).unwrap();
+            result.moved.push(ExecutionMove {
+                file: cache.join("Show").join(name),
+                destination: path,
+                from_tier: "cache".to_string(),
+                to_tier: "storage".to_string(),
+                size: 8,
+                verification: Verification::Checksum(checksum),
+            });
+        }
+        let mut journal = StateJournal::in_memory();
+        journal.record_execution(&result, &tiers, SystemTime::now());
+        fs::write(storage.join("Show/rotten.mkv"), b"bitrot!!").unwrap();
+
+        let cancel = AtomicBool::new(false);
+        let budget = HashBudget::unlimited();
+        let report = scrub(&journal, &tiers, &ScrubOptions::default(), &budget, &cancel);
+        assert_eq!((report.candidates, report.verified), (2, 1));
+        assert_eq!(report.mismatches.len(), 1);
+        assert_eq!(report.mismatches[0].path, storage.join("Show/rotten.mkv"));
+        assert_eq!(report.mismatches[0].tier, "storage");
+        assert!(!report.is_clean());
+
+        let options = ScrubOptions {
+            tier: Some("cache".into()),
+            ..ScrubOptions::default()
+        };
+        let report = scrub(&journal, &tiers, &options, &budget, &cancel);
+        assert_eq!(report.candidates, 0);
+        assert!(report.missing.is_empty());
+    }
+}
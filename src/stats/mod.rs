@@ -1,8 +1,11 @@
-use crate::FileInfo;
+use crate::arr::SonarrStats;
 use crate::tautulli::TautulliStats;
+use crate::torrent::TorrentStats;
+use crate::{FileInfo, StateJournal};
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::time::SystemTime;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 /// Global statistics collected during the first pass of file processing
 ///
@@ -15,6 +18,15 @@ pub struct GlobalStats {
 
     /// Tautulli statistics (optional, only if Tautulli is configured)
     pub tautulli_stats: Option<TautulliStats>,
+
+    /// Sonarr statistics (optional, only if Sonarr is configured)
+    pub sonarr_stats: Option<SonarrStats>,
+
+    /// Torrent client statistics (optional, only if a torrent client is configured)
+    pub torrent_stats: Option<TorrentStats>,
+
+    /// First-seen ages from the state journal (optional, only if the journal is loaded)
+    pub seen_ages: Option<Arc<SeenAges>>,
 }
 
 impl GlobalStats {
@@ -22,6 +34,9 @@ impl GlobalStats {
         Self {
             file_stats,
             tautulli_stats: None,
+            sonarr_stats: None,
+            torrent_stats: None,
+            seen_ages: None,
         }
     }
 
@@ -29,25 +44,85 @@ impl GlobalStats {
         self.tautulli_stats = Some(tautulli_stats);
         self
     }
+
+    pub fn with_sonarr(mut self, sonarr_stats: SonarrStats) -> Self {
+        self.sonarr_stats = Some(sonarr_stats);
+        self
+    }
+
+    pub fn with_torrents(mut self, torrent_stats: TorrentStats) -> Self {
+        self.torrent_stats = Some(torrent_stats);
+        self
+    }
+
+    pub fn with_seen_ages(mut self, seen_ages: &Arc<SeenAges>) -> Self {
+        self.seen_ages = Some(Arc::clone(seen_ages));
+        self
+    }
 }
 
-/// Basic file statistics collected from scanning all tiers
+/// How long tierflow has known each file, independent of its mtime
 #[derive(Debug, Clone, Default)]
-pub struct FileStats {
-    /// All files grouped by their parent directory
-    pub directory_files: HashMap<PathBuf, Vec<FileInfo>>,
+pub struct SeenAges {
+    /// Keyed by path relative to the tier root
+    ages: HashMap<PathBuf, Duration>,
+}
+
+impl SeenAges {
+    pub fn from_journal(journal: &StateJournal, now: SystemTime) -> Self {
+        let ages = journal
+            .seen_files()
+            .filter_map(|path| {
+                journal
+                    .time_since_first_seen(path, now)
+                    .map(|age| (path.to_path_buf(), age))
+            })
+            .collect();
+        Self { ages }
+    }
 
-    /// Newest (most recently modified) file per directory
-    pub newest_file_per_dir: HashMap<PathBuf, SystemTime>,
+    /// Time since the file was first seen; files missing from the journal are new
+    pub fn age(&self, relative_path: &Path) -> Duration {
+        self.ages.get(relative_path).copied().unwrap_or_default()
+    }
+}
 
-    /// Oldest (least recently modified) file per directory
-    pub oldest_file_per_dir: HashMap<PathBuf, SystemTime>,
+/// Aggregates of the files directly inside one directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirectoryStats {
+    /// Most recent modification time
+    pub newest: SystemTime,
+    /// Least recent modification time
+    pub oldest: SystemTime,
+    pub total_size: u64,
+    pub file_count: usize,
+}
 
-    /// Total size of all files in each directory
-    pub directory_total_size: HashMap<PathBuf, u64>,
+impl DirectoryStats {
+    const fn new(file: &FileInfo) -> Self {
+        Self {
+            newest: file.modified,
+            oldest: file.modified,
+            total_size: file.size,
+            file_count: 1,
+        }
+    }
 
-    /// Number of files in each directory
-    pub directory_file_count: HashMap<PathBuf, usize>,
+    fn add(&mut self, file: &FileInfo) {
+        self.newest = self.newest.max(file.modified);
+        self.oldest = self.oldest.min(file.modified);
+        self.total_size += file.size;
+        self.file_count += 1;
+    }
+}
+
+/// Basic file statistics collected from scanning all tiers
+///
+/// Only per-directory aggregates are kept, so memory grows with the number of
+/// directories rather than files.
+#[derive(Debug, Clone, Default)]
+pub struct FileStats {
+    directories: HashMap<PathBuf, DirectoryStats>,
 }
 
 impl FileStats {
@@ -72,67 +147,45 @@ impl FileStats {
     /// Add a single file to the statistics
     fn add_file(&mut self, file: &FileInfo) {
         // Get parent directory (use root if no parent)
-        let parent = file
-            .path
-            .parent()
-            .map_or_else(|| PathBuf::from("/"), std::path::Path::to_path_buf);
-
-        // Add to directory files list
-        self.directory_files
-            .entry(parent.clone())
-            .or_default()
-            .push(file.clone());
-
-        // Update newest file
-        let should_update_newest = self
-            .newest_file_per_dir
-            .get(&parent)
-            .is_none_or(|&newest| file.modified > newest);
-        if should_update_newest {
-            self.newest_file_per_dir
-                .insert(parent.clone(), file.modified);
+        let parent = file.path.parent().unwrap_or_else(|| Path::new("/"));
+
+        // The key is only allocated for the first file of a directory
+        if let Some(dir) = self.directories.get_mut(parent) {
+            dir.add(file);
+        } else {
+            self.directories
+                .insert(parent.to_path_buf(), DirectoryStats::new(file));
         }
+    }
 
-        // Update oldest file
-        let should_update_oldest = self
-            .oldest_file_per_dir
-            .get(&parent)
-            .is_none_or(|&oldest| file.modified < oldest);
-        if should_update_oldest {
-            self.oldest_file_per_dir
-                .insert(parent.clone(), file.modified);
-        }
-
-        // Update total size
-        *self.directory_total_size.entry(parent.clone()).or_insert(0) += file.size;
-
-        // Update file count
-        *self.directory_file_count.entry(parent).or_insert(0) += 1;
+    /// Number of directories holding at least one file
+    pub fn directory_count(&self) -> usize {
+        self.directories.len()
     }
 
-    /// Get all files in a specific directory
-    pub fn get_directory_files(&self, dir: &PathBuf) -> Option<&Vec<FileInfo>> {
-        self.directory_files.get(dir)
+    /// Aggregates of a directory, if it holds any file
+    pub fn get_directory(&self, dir: &Path) -> Option<&DirectoryStats> {
+        self.directories.get(dir)
     }
 
     /// Get the newest file timestamp in a directory
-    pub fn get_newest_in_directory(&self, dir: &PathBuf) -> Option<SystemTime> {
-        self.newest_file_per_dir.get(dir).copied()
+    pub fn get_newest_in_directory(&self, dir: &Path) -> Option<SystemTime> {
+        self.get_directory(dir).map(|d| d.newest)
     }
 
     /// Get the oldest file timestamp in a directory
-    pub fn get_oldest_in_directory(&self, dir: &PathBuf) -> Option<SystemTime> {
-        self.oldest_file_per_dir.get(dir).copied()
+    pub fn get_oldest_in_directory(&self, dir: &Path) -> Option<SystemTime> {
+        self.get_directory(dir).map(|d| d.oldest)
     }
 
     /// Get total size of all files in a directory
-    pub fn get_directory_size(&self, dir: &PathBuf) -> u64 {
-        self.directory_total_size.get(dir).copied().unwrap_or(0)
+    pub fn get_directory_size(&self, dir: &Path) -> u64 {
+        self.get_directory(dir).map_or(0, |d| d.total_size)
     }
 
     /// Get number of files in a directory
-    pub fn get_directory_file_count(&self, dir: &PathBuf) -> usize {
-        self.directory_file_count.get(dir).copied().unwrap_or(0)
+    pub fn get_directory_file_count(&self, dir: &Path) -> usize {
+        self.get_directory(dir).map_or(0, |d| d.file_count)
     }
 }
 
@@ -148,15 +201,16 @@ mod tests {
             size,
             modified,
             accessed: SystemTime::now(),
+            owner: None,
         }
     }
 
     #[test]
     fn test_file_stats_collect_empty() {
         let stats = FileStats::collect(&[]);
-        assert_eq!(stats.directory_files.len(), 0);
-        assert_eq!(stats.newest_file_per_dir.len(), 0);
-        assert_eq!(stats.oldest_file_per_dir.len(), 0);
+        assert_eq!(stats.directory_count(), 0);
+        assert!(stats.get_newest_in_directory(Path::new("/")).is_none());
+        assert!(stats.get_oldest_in_directory(Path::new("/")).is_none());
     }
 
     #[test]
@@ -165,8 +219,8 @@ mod tests {
         let stats = FileStats::collect(&[file]);
 
         let dir = PathBuf::from("/test/dir");
-        assert_eq!(stats.directory_files.len(), 1);
-        assert_eq!(stats.get_directory_files(&dir).unwrap().len(), 1);
+        assert_eq!(stats.directory_count(), 1);
+        assert_eq!(stats.get_directory(&dir).unwrap().file_count, 1);
         assert_eq!(stats.get_directory_size(&dir), 1000);
         assert_eq!(stats.get_directory_file_count(&dir), 1);
     }
@@ -182,7 +236,7 @@ mod tests {
         let stats = FileStats::collect(&files);
         let dir = PathBuf::from("/test/dir");
 
-        assert_eq!(stats.get_directory_files(&dir).unwrap().len(), 3);
+        assert_eq!(stats.get_directory(&dir).unwrap().file_count, 3);
         assert_eq!(stats.get_directory_size(&dir), 6000);
         assert_eq!(stats.get_directory_file_count(&dir), 3);
 
@@ -211,7 +265,7 @@ mod tests {
         let dir1 = PathBuf::from("/test/dir1");
         let dir2 = PathBuf::from("/test/dir2");
 
-        assert_eq!(stats.directory_files.len(), 2);
+        assert_eq!(stats.directory_count(), 2);
         assert_eq!(stats.get_directory_file_count(&dir1), 2);
         assert_eq!(stats.get_directory_file_count(&dir2), 2);
         assert_eq!(stats.get_directory_size(&dir1), 3000);
@@ -224,7 +278,7 @@ mod tests {
         let stats = FileStats::collect(&files);
 
         let nonexistent = PathBuf::from("/nonexistent");
-        assert!(stats.get_directory_files(&nonexistent).is_none());
+        assert!(stats.get_directory(&nonexistent).is_none());
         assert_eq!(stats.get_directory_size(&nonexistent), 0);
         assert_eq!(stats.get_directory_file_count(&nonexistent), 0);
     }
@@ -235,8 +289,8 @@ mod tests {
         let global_stats = GlobalStats::new(file_stats.clone());
 
         assert_eq!(
-            global_stats.file_stats.directory_files.len(),
-            file_stats.directory_files.len()
+            global_stats.file_stats.directory_count(),
+            file_stats.directory_count()
         );
     }
 
@@ -251,7 +305,7 @@ mod tests {
         let stats = FileStats::collect(&files);
 
         // Each file is in its own directory
-        assert_eq!(stats.directory_files.len(), 3);
+        assert_eq!(stats.directory_count(), 3);
 
         let dir_a = PathBuf::from("/test/a");
         let dir_b = PathBuf::from("/test/a/b");
@@ -282,7 +336,8 @@ mod tests {
         let stats = FileStats::collect(&files);
         let cloned = stats.clone();
 
-        assert_eq!(stats.directory_files.len(), cloned.directory_files.len());
-        assert_eq!(stats.newest_file_per_dir, cloned.newest_file_per_dir);
+        let dir = Path::new("/test");
+        assert_eq!(stats.directory_count(), cloned.directory_count());
+        assert_eq!(stats.get_directory(dir), cloned.get_directory(dir));
     }
 }
@@ -1,14 +1,23 @@
-use crate::{Condition, Context, FileInfo, Tier};
+use crate::conditions::ConditionMemo;
+use crate::config::ScheduleConfig;
+use crate::{Condition, Context, FileInfo, MoveQuota, Tier};
+use std::sync::Arc;
+use std::time::SystemTime;
 
 pub use crate::config::StrategyAction;
 
+/// A condition with the key it shares results under, if any
+type KeyedCondition = (Option<Arc<str>>, Box<dyn Condition>);
+
 pub struct PlacementStrategy {
     pub name: String,
     pub priority: u32,
-    conditions: Vec<Box<dyn Condition>>,
+    conditions: Vec<KeyedCondition>,
     preferred_tiers: Vec<String>,
     pub is_required: bool,
     pub action: StrategyAction,
+    pub quota: MoveQuota,
+    schedule: Option<ScheduleConfig>,
 }
 
 impl PlacementStrategy {
@@ -20,10 +29,23 @@ impl PlacementStrategy {
             conditions: Vec::new(),
             preferred_tiers: Vec::new(),
             action: StrategyAction::Evaluate,
+            quota: MoveQuota::default(),
+            schedule: None,
         }
     }
     pub fn add_condition(mut self, condition: Box<dyn Condition>) -> Self {
-        self.conditions.push(condition);
+        self.conditions.push((None, condition));
+        self
+    }
+
+    /// Adds a condition whose result for a file is reused by every condition
+    /// with the same `key`, e.g. one built from the same config
+    pub fn add_shared_condition(
+        mut self,
+        key: impl Into<Arc<str>>,
+        condition: Box<dyn Condition>,
+    ) -> Self {
+        self.conditions.push((Some(key.into()), condition));
         self
     }
     pub fn add_preferred_tier(mut self, tier_name: String) -> Self {
@@ -36,8 +58,66 @@ impl PlacementStrategy {
         self
     }
 
+    /// Limit the moves this strategy may schedule per run
+    pub const fn with_quota(mut self, quota: MoveQuota) -> Self {
+        self.quota = quota;
+        self
+    }
+
+    /// Whether both strategies would leave a file in the same place
+    pub fn same_destination(&self, other: &Self) -> bool {
+        self.action == other.action
+            && (self.action == StrategyAction::Stay
+                || self.preferred_tiers == other.preferred_tiers)
+    }
+
+    /// Only match files while `schedule` is active
+    pub fn with_schedule(mut self, schedule: ScheduleConfig) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
+
+    pub const fn schedule(&self) -> Option<&ScheduleConfig> {
+        self.schedule.as_ref()
+    }
+
+    /// Whether the strategy is inside its schedule at `now`; always true without one
+    pub fn is_active_at(&self, now: SystemTime) -> bool {
+        self.schedule.as_ref().is_none_or(|s| s.is_active_at(now))
+    }
+
     pub fn matches(&self, file: &FileInfo, context: &Context) -> bool {
-        self.conditions.iter().all(|c| c.matches(file, context))
+        self.conditions
+            .iter()
+            .all(|(_, c)| c.matches(file, context))
+    }
+
+    /// Whether any condition depends on the simulated tier state
+    pub fn reads_planning(&self) -> bool {
+        self.conditions.iter().any(|(_, c)| c.reads_planning())
+    }
+
+    /// Like `matches`, reusing results `memo` holds for the same file
+    pub fn matches_with(
+        &self,
+        file: &FileInfo,
+        context: &Context,
+        memo: &mut ConditionMemo,
+    ) -> bool {
+        self.conditions
+            .iter()
+            .all(|(key, c)| memo.evaluate(key.as_ref(), c.as_ref(), file, context))
+    }
+
+    /// Every condition with its result, without stopping at the first failure
+    pub fn evaluate_conditions<'a>(
+        &'a self,
+        file: &'a FileInfo,
+        context: &'a Context,
+    ) -> impl Iterator<Item = (&'a str, bool)> + 'a {
+        self.conditions
+            .iter()
+            .map(move |(_, c)| (c.name(), c.matches(file, context)))
     }
 
     pub fn get_ideal_tier<'a>(
@@ -71,6 +151,7 @@ mod tests {
             size,
             modified,
             accessed: SystemTime::now(),
+            owner: None,
         }
     }
 
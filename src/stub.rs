@@ -0,0 +1,206 @@
+//! Placeholder files left behind when files move to a tier with `leave_stubs`
+//!
+//! A stub is a small text file at the file's old path naming the tier and
+//! location the real file went to. The scanner skips stubs, and `recall`
+//! fetches the real file back over the stub.
+
+use crate::remote::{self, SshRemote};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// First line of every stub
+pub const MARKER: &str = "#tierflow-stub v1";
+
+/// Larger files are never read as stubs
+pub const MAX_LEN: u64 = 4096;
+
+/// Where the real file of a stub lives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stub {
+    /// Tier holding the real file
+    pub tier: String,
+    /// Path of the real file, or its `ssh://` URL on a remote tier
+    pub location: String,
+    /// Size of the real file in bytes
+    pub size: u64,
+}
+
+impl Stub {
+    /// The stub's text
+    pub fn render(&self) -> String {
+        format!(
+            "{MARKER}\ntier: {}\nlocation: {}\nsize: {}\n",
+            self.tier, self.location, self.size
+        )
+    }
+
+    /// Reads a stub back from its text; `None` if it is not one
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut lines = text.lines();
+        if lines.next()? != MARKER {
+            return None;
+        }
+        let (mut tier, mut location, mut size) = (None, None, None);
+        for line in lines {
+            match line.split_once(": ") {
+                Some(("tier", value)) => tier = Some(value.to_string()),
+                Some(("location", value)) => location = Some(value.to_string()),
+                Some(("size", value)) => size = value.parse().ok(),
+                _ => {}
+            }
+        }
+        Some(Self {
+            tier: tier?,
+            location: location?,
+            size: size?,
+        })
+    }
+
+    /// Writes the stub at `at`, replacing whatever is there in one rename
+    ///
+    /// The parent directory is recreated, since moves remove emptied ones.
+    pub fn write(&self, at: &Path) -> io::Result<()> {
+        if let Some(parent) = at.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp = partial_path(at);
+        fs::write(&temp, self.render())?;
+        fs::rename(&temp, at).inspect_err(|_| {
+            let _ = fs::remove_file(&temp);
+        })
+    }
+
+    /// The stub at `at`, or `None` if the file there is not a stub
+    pub fn read(at: &Path) -> io::Result<Option<Self>> {
+        let file = fs::File::open(at)?;
+        if file.metadata()?.len() > MAX_LEN {
+            return Ok(None);
+        }
+        let mut bytes = Vec::new();
+        file.take(MAX_LEN).read_to_end(&mut bytes)?;
+        Ok(std::str::from_utf8(&bytes).ok().and_then(Self::parse))
+    }
+}
+
+/// Whether the file at `path`, `len` bytes long, is a stub
+///
+/// Only files no larger than `MAX_LEN` are opened.
+pub fn is_stub(path: &Path, len: u64) -> bool {
+    len <= MAX_LEN && matches!(Stub::read(path), Ok(Some(_)))
+}
+
+/// Fetches the real file of the stub at `path` and swaps it in for the stub
+///
+/// The file is copied next to the stub as `.partial`, checked against the
+/// recorded size, renamed over the stub and only then deleted from the tier
+/// holding it. Returns the stub that was replaced.
+pub fn recall(path: &Path) -> io::Result<Stub> {
+    let stub = Stub::read(path)?.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a tierflow stub", path.display()),
+        )
+    })?;
+    let temp = partial_path(path);
+    let remote = SshRemote::parse(Path::new(&stub.location))
+        .transpose()
+        .map_err(|reason| io::Error::new(io::ErrorKind::InvalidData, reason))?;
+    let result = match &remote {
+        Some(remote) => fetch_remote(remote, &temp),
+        None => fetch_local(Path::new(&stub.location), &temp),
+    }
+    .and_then(|()| {
+        let size = fs::metadata(&temp)?.len();
+        if size == stub.size {
+            fs::rename(&temp, path)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} is {size} bytes, the stub recorded {}",
+                    stub.location, stub.size
+                ),
+            ))
+        }
+    });
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+
+    let removed = match &remote {
+        Some(remote) => remote.remove_file(&remote.path),
+        None => fs::remove_file(&stub.location),
+    };
+    if let Err(e) = removed {
+        tracing::warn!(
+            "Recalled {} but could not remove {}: {e}",
+            path.display(),
+            stub.location
+        );
+    }
+    Ok(stub)
+}
+
+fn fetch_remote(remote: &SshRemote, temp: &Path) -> io::Result<()> {
+    let transfer = remote::RemoteTransfer {
+        from: Some(remote),
+        to: None,
+        source: &remote.path,
+        destination: temp,
+        size: 0,
+        copy: true,
+    };
+    transfer.copy_to(temp, &["--times"], &[], &mut |_| {})
+}
+
+/// Copies `source` to `temp`, keeping its modification time for age-based strategies
+fn fetch_local(source: &Path, temp: &Path) -> io::Result<()> {
+    fs::copy(source, temp)?;
+    let modified = fs::metadata(source)?.modified()?;
+    fs::File::options()
+        .write(true)
+        .open(temp)?
+        .set_times(fs::FileTimes::new().set_modified(modified))
+}
+
+/// `path` with `.partial` appended, which the scanner and cleanup know about
+fn partial_path(path: &Path) -> PathBuf {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".partial");
+    PathBuf::from(temp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stub_round_trip_and_recall() {
+        let dir = tempfile::tempdir().unwrap();
+        let cold = dir.path().join("cold.mkv");
+        let hot = dir.path().join("hot.mkv");
+        fs::write(&cold, b"movie").unwrap();
+        let stub = Stub {
+            tier: "archive".to_string(),
+            location: cold.to_string_lossy().into_owned(),
+            size: 5,
+        };
+        assert_eq!(Stub::parse(&stub.render()), Some(stub.clone()));
+        assert_eq!(Stub::parse("#tierflow-stub v1\ntier: archive\n"), None);
+
+        stub.write(&hot).unwrap();
+        assert!(is_stub(&hot, fs::metadata(&hot).unwrap().len()));
+        assert!(!is_stub(&cold, 5));
+
+        assert_eq!(recall(&hot).unwrap(), stub);
+        assert_eq!(fs::read(&hot).unwrap(), b"movie");
+        assert!(!cold.exists());
+        assert!(!partial_path(&hot).exists());
+        assert_eq!(
+            recall(&hot).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+}
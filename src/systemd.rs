@@ -0,0 +1,114 @@
+//! Service manager notifications for `Type=notify` units (`systemd` feature)
+//!
+//! Without the feature, or outside systemd (`NOTIFY_SOCKET` unset), every call is a no-op.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Startup finished: systemd marks the unit active
+pub fn ready() {
+    #[cfg(feature = "systemd")]
+    send(&[sd_notify::NotifyState::Ready]);
+}
+
+/// Shutdown started
+pub fn stopping() {
+    #[cfg(feature = "systemd")]
+    send(&[sd_notify::NotifyState::Stopping]);
+}
+
+/// Free-form status shown by `systemctl status`
+pub fn status(message: &str) {
+    #[cfg(feature = "systemd")]
+    send(&[sd_notify::NotifyState::Status(message)]);
+    #[cfg(not(feature = "systemd"))]
+    let _ = message;
+}
+
+#[cfg(feature = "systemd")]
+fn send(states: &[sd_notify::NotifyState]) {
+    if let Err(e) = sd_notify::notify(false, states) {
+        tracing::debug!("sd_notify failed: {e}");
+    }
+}
+
+/// Watchdog timeout requested by the unit (`WatchdogSec=`), if any
+fn watchdog_timeout() -> Option<Duration> {
+    #[cfg(feature = "systemd")]
+    {
+        let mut usec = 0;
+        if sd_notify::watchdog_enabled(false, &mut usec) {
+            return Some(Duration::from_micros(usec));
+        }
+    }
+    None
+}
+
+/// Pings are sent at half the timeout, as `sd_watchdog_enabled(3)` recommends
+fn ping_interval(timeout: Duration) -> Duration {
+    (timeout / 2).max(Duration::from_millis(100))
+}
+
+/// Background thread sending `WATCHDOG=1` pings, so long copies don't trip the
+/// watchdog; stops when dropped
+pub struct Watchdog {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    /// Starts pinging if the unit has a watchdog configured
+    pub fn start() -> Option<Self> {
+        let interval = ping_interval(watchdog_timeout()?);
+        tracing::info!("Sending watchdog pings every {interval:?}");
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let handle = std::thread::spawn(move || {
+            let tick = interval.min(Duration::from_secs(1));
+            let mut since_ping = interval;
+            while !thread_stop.load(Ordering::SeqCst) {
+                if since_ping >= interval {
+                    #[cfg(feature = "systemd")]
+                    send(&[sd_notify::NotifyState::Watchdog]);
+                    since_ping = Duration::ZERO;
+                }
+                std::thread::sleep(tick);
+                since_ping += tick;
+            }
+        });
+
+        Some(Self {
+            stop,
+            handle: Some(handle),
+        })
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ping_interval() {
+        assert_eq!(
+            ping_interval(Duration::from_secs(60)),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            ping_interval(Duration::from_millis(50)),
+            Duration::from_millis(100)
+        );
+    }
+}
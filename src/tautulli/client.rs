@@ -1,4 +1,5 @@
 use crate::error::{AppError, Result};
+use crate::watch_history::WatchHistoryProvider;
 use reqwest::blocking::Client;
 use serde::Deserialize;
 use std::time::Duration;
@@ -106,23 +107,74 @@ impl TautulliClient {
 
         tracing::trace!("Tautulli history response: {response_text}");
 
-        let api_response: TautulliResponse<HistoryResponse> = serde_json::from_str(&response_text)
-            .map_err(|e| {
-                tracing::error!("Failed to parse Tautulli history response. Error: {e}");
-                tracing::debug!(
-                    "Response body (first 1000 chars): {}",
-                    &response_text[..response_text.len().min(1000)]
-                );
-                AppError::External(format!("Failed to parse Tautulli history response: {e}"))
+        let history = parse_history(&response_text)?;
+        tracing::debug!("Fetched {} history items", history.len());
+        Ok(history)
+    }
+}
+
+/// History items of a `get_history` response body
+///
+/// Shared by the API client and `TautulliFixture`, so a saved response is
+/// read exactly like a live one.
+pub(super) fn parse_history(body: &str) -> Result<Vec<HistoryItem>> {
+    let api_response: TautulliResponse<HistoryResponse> =
+        serde_json::from_str(body).map_err(|e| {
+            tracing::error!("Failed to parse Tautulli history response. Error: {e}");
+            tracing::debug!(
+                "Response body (first 1000 chars): {}",
+                body.chars().take(1000).collect::<String>()
+            );
+            AppError::External(format!("Failed to parse Tautulli history response: {e}"))
+        })?;
+
+    match api_response.response.result {
+        ResponseResult::Success => Ok(api_response.response.data.data),
+        ResponseResult::Error => Err(AppError::External(format!(
+            "Tautulli API returned error: {}",
+            api_response
+                .response
+                .message
+                .unwrap_or_else(|| "Unknown error".to_string())
+        ))),
+    }
+}
+
+impl TautulliClient {
+    /// Get file paths of an item from Plex metadata (`get_metadata`)
+    pub fn get_file_paths(&self, rating_key: &str) -> Result<Vec<String>> {
+        tracing::debug!("Fetching Tautulli metadata for rating key {rating_key}");
+
+        let url = format!(
+            "{}api/v2?apikey={}&cmd=get_metadata&rating_key={}",
+            self.base_url, self.api_key, rating_key
+        );
+
+        let response =
+            self.client.get(&url).send().map_err(|e| {
+                AppError::External(format!("Failed to fetch Tautulli metadata: {e}"))
             })?;
 
+        if !response.status().is_success() {
+            return Err(AppError::External(format!(
+                "Tautulli API returned error status: {}",
+                response.status()
+            )));
+        }
+
+        let api_response: TautulliResponse<Metadata> = response.json().map_err(|e| {
+            AppError::External(format!("Failed to parse Tautulli metadata response: {e}"))
+        })?;
+
         match api_response.response.result {
             ResponseResult::Success => {
-                tracing::debug!(
-                    "Fetched {} history items",
-                    api_response.response.data.data.len()
-                );
-                Ok(api_response.response.data.data)
+                let paths = api_response.response.data.file_paths();
+                if paths.is_empty() {
+                    return Err(AppError::External(format!(
+                        "No files found in Plex metadata for rating key {rating_key}"
+                    )));
+                }
+                Ok(paths)
             }
             ResponseResult::Error => Err(AppError::External(format!(
                 "Tautulli API returned error: {}",
@@ -135,6 +187,24 @@ impl TautulliClient {
     }
 }
 
+impl WatchHistoryProvider for TautulliClient {
+    fn name(&self) -> &'static str {
+        "tautulli"
+    }
+
+    fn health_check(&self) -> Result<()> {
+        Self::health_check(self)
+    }
+
+    fn get_history(&self, length: u32) -> Result<Vec<HistoryItem>> {
+        Self::get_history(self, length)
+    }
+
+    fn get_file_paths(&self, rating_key: &str) -> Result<Vec<String>> {
+        Self::get_file_paths(self, rating_key)
+    }
+}
+
 // API Response structures
 
 #[derive(Debug, Deserialize)]
@@ -164,6 +234,37 @@ struct ServerInfo {
     pms_version: String,
 }
 
+// Metadata structures
+
+#[derive(Debug, Default, Deserialize)]
+struct Metadata {
+    #[serde(default)]
+    media_info: Vec<MediaInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MediaInfo {
+    #[serde(default)]
+    parts: Vec<MediaPart>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MediaPart {
+    #[serde(default)]
+    file: String,
+}
+
+impl Metadata {
+    fn file_paths(self) -> Vec<String> {
+        self.media_info
+            .into_iter()
+            .flat_map(|info| info.parts)
+            .map(|part| part.file)
+            .filter(|file| !file.is_empty())
+            .collect()
+    }
+}
+
 // History structures
 
 #[derive(Debug, Deserialize)]
@@ -181,6 +282,18 @@ pub struct HistoryItem {
     #[serde(deserialize_with = "deserialize_flexible_string")]
     pub rating_key: String,
 
+    /// Media type (`episode`, `movie`, ...)
+    #[serde(default)]
+    pub media_type: String,
+
+    /// Episode or movie title
+    #[serde(default)]
+    pub title: String,
+
+    /// Release year (0 if unknown)
+    #[serde(default, deserialize_with = "deserialize_string_to_u32")]
+    pub year: u32,
+
     /// Show name (`grandparent_title` in Plex)
     #[serde(default)]
     pub grandparent_title: String,
@@ -273,6 +386,13 @@ where
             u32::try_from(value).map_err(de::Error::custom)
         }
 
+        fn visit_unit<E>(self) -> std::result::Result<u32, E>
+        where
+            E: de::Error,
+        {
+            Ok(0)
+        }
+
         fn visit_str<E>(self, value: &str) -> std::result::Result<u32, E>
         where
             E: de::Error,
@@ -402,6 +522,49 @@ mod tests {
         assert_eq!(item.stopped, 1234567890);
     }
 
+    #[test]
+    fn test_metadata_file_paths() {
+        let json = r#"{
+            "media_type": "episode",
+            "grandparent_title": "Breaking Bad",
+            "media_info": [
+                {"parts": [{"file": "/data/tv/Breaking Bad/Season 01/01x05.mkv"}]},
+                {"parts": [{"file": ""}]}
+            ]
+        }"#;
+
+        let metadata: Metadata = serde_json::from_str(json).expect("Should deserialize");
+        assert_eq!(
+            metadata.file_paths(),
+            vec!["/data/tv/Breaking Bad/Season 01/01x05.mkv"]
+        );
+
+        let empty: Metadata = serde_json::from_str("{}").expect("Should deserialize");
+        assert!(empty.file_paths().is_empty());
+    }
+
+    #[test]
+    fn test_deserialize_movie_history_item() {
+        let json = r#"{
+            "user": "alice",
+            "rating_key": 500,
+            "media_type": "movie",
+            "title": "Dune",
+            "year": 2021,
+            "grandparent_title": "",
+            "parent_media_index": "",
+            "media_index": "",
+            "percent_complete": 42,
+            "stopped": 1234567890
+        }"#;
+
+        let item: HistoryItem = serde_json::from_str(json).expect("Should deserialize");
+        assert_eq!(item.media_type, "movie");
+        assert_eq!(item.title, "Dune");
+        assert_eq!(item.year, 2021);
+        assert_eq!(item.parent_media_index, 0);
+    }
+
     #[test]
     fn test_deserialize_history_item_missing_grandparent() {
         let json = r#"{
@@ -1,4 +1,5 @@
 use lazy_regex::{Regex, regex};
+use std::ops::RangeInclusive;
 use std::path::Path;
 
 // Regex patterns for episode parsing
@@ -10,47 +11,61 @@ use std::path::Path;
 pub struct EpisodeInfo {
     pub show_name: String,
     pub season: u32,
+    /// First episode in the file
     pub episode: u32,
+    /// Last episode in the file (equals `episode` unless it is a multi-episode file)
+    pub last_episode: u32,
 }
 
 impl EpisodeInfo {
-    /// Calculate global episode index (for cross-season windows)
-    /// Assumes 100 episodes per season for simplicity
-    pub const fn global_index(&self) -> u32 {
-        (self.season - 1) * 100 + self.episode
-    }
-
-    /// Create from global index (inverse of `global_index`)
-    pub const fn from_global_index(show_name: String, global_idx: u32) -> Self {
-        let season = (global_idx / 100) + 1;
-        let episode = global_idx % 100;
+    /// Single-episode file
+    pub const fn new(show_name: String, season: u32, episode: u32) -> Self {
         Self {
             show_name,
             season,
             episode,
+            last_episode: episode,
         }
     }
+
+    /// All episodes contained in the file
+    pub const fn episodes(&self) -> RangeInclusive<u32> {
+        self.episode..=self.last_episode
+    }
 }
 
+/// Longest episode range accepted from a multi-episode filename
+const MAX_EPISODES_PER_FILE: u32 = 10;
+
 /// Parse episode information from file path
 ///
 /// Supports multiple formats:
 /// - Plex: "Show Name - s01e05 - Title.mkv"
 /// - Scene: "Show.Name.S01E05.1080p.mkv"
 /// - Simple: "Show Name S01E05.mkv"
+/// - Multi-episode: "Show.S01E05-E06.mkv", "Show.S01E05E06.mkv", "Show S01E05-06.mkv"
 ///
 /// Returns None if no episode pattern found
 pub fn parse_episode(path: &Path) -> Option<EpisodeInfo> {
     let filename = path.file_name()?.to_str()?;
 
-    // Case-insensitive regex: S01E05 or s01e05 (compiled at compile-time)
-    let re: &Regex = regex!(r"(?i)[sS](\d{1,2})[eE](\d{1,2})");
+    // Case-insensitive regex: S01E05 or s01e05, optionally followed by
+    // more episodes: -E06, E06 or -06 (compiled at compile-time)
+    let re: &Regex = regex!(r"(?i)[sS](\d{1,2})[eE](\d{1,2})((?:-?[eE]\d{1,2}|-\d{1,2}\b)*)");
 
     let captures = re.captures(filename)?;
 
     let season: u32 = captures.get(1)?.as_str().parse().ok()?;
     let episode: u32 = captures.get(2)?.as_str().parse().ok()?;
 
+    // Last episode of a multi-episode file; implausible ranges fall back to a single episode
+    let last_episode = captures
+        .get(3)
+        .and_then(|extra| regex!(r"\d{1,2}").find_iter(extra.as_str()).last())
+        .and_then(|last| last.as_str().parse::<u32>().ok())
+        .filter(|&last| last > episode && last - episode <= MAX_EPISODES_PER_FILE)
+        .unwrap_or(episode);
+
     // Extract show name from filename (everything before season/episode)
     let show_start = captures.get(0)?.start();
     let show_name_raw = &filename[..show_start];
@@ -71,6 +86,7 @@ pub fn parse_episode(path: &Path) -> Option<EpisodeInfo> {
         show_name,
         season,
         episode,
+        last_episode,
     })
 }
 
@@ -172,38 +188,34 @@ mod tests {
     }
 
     #[test]
-    fn test_global_index_calculation() {
-        let ep1 = EpisodeInfo {
-            show_name: "Test".to_string(),
-            season: 1,
-            episode: 5,
-        };
-        assert_eq!(ep1.global_index(), 5);
-
-        let ep2 = EpisodeInfo {
-            show_name: "Test".to_string(),
-            season: 2,
-            episode: 10,
-        };
-        assert_eq!(ep2.global_index(), 110); // (2-1)*100 + 10
-
-        let ep3 = EpisodeInfo {
-            show_name: "Test".to_string(),
-            season: 3,
-            episode: 1,
-        };
-        assert_eq!(ep3.global_index(), 201); // (3-1)*100 + 1
+    fn test_parse_multi_episode() {
+        for name in [
+            "Show.S01E05-E06.mkv",
+            "Show.S01E05E06.mkv",
+            "Show - s01e05-e06 - Title.mkv",
+            "Show S01E05-06.mkv",
+        ] {
+            let episode = parse_episode(&PathBuf::from(format!("/mnt/cache/{name}")))
+                .unwrap_or_else(|| panic!("Should parse {name}"));
+
+            assert_eq!(episode.show_name, "Show", "{name}");
+            assert_eq!(episode.season, 1, "{name}");
+            assert_eq!(episode.episodes(), 5..=6, "{name}");
+        }
+
+        let episode = parse_episode(&PathBuf::from("/mnt/cache/Show.S02E01E02E03.mkv")).unwrap();
+        assert_eq!(episode.episodes(), 1..=3);
     }
 
     #[test]
-    fn test_from_global_index() {
-        let ep = EpisodeInfo::from_global_index("Test".to_string(), 110);
-        assert_eq!(ep.season, 2);
-        assert_eq!(ep.episode, 10);
-
-        let ep2 = EpisodeInfo::from_global_index("Test".to_string(), 5);
-        assert_eq!(ep2.season, 1);
-        assert_eq!(ep2.episode, 5);
+    fn test_parse_single_episode_range() {
+        // Resolution and release tags are not episode numbers
+        let episode = parse_episode(&PathBuf::from("/mnt/cache/Show.S01E05-1080p.mkv")).unwrap();
+        assert_eq!(episode.episodes(), 5..=5);
+
+        // Descending or implausible ranges fall back to a single episode
+        let episode = parse_episode(&PathBuf::from("/mnt/cache/Show.S01E05-E02.mkv")).unwrap();
+        assert_eq!(episode.episodes(), 5..=5);
     }
 
     #[test]
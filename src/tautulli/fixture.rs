@@ -0,0 +1,188 @@
+use super::HistoryItem;
+use super::client::parse_history;
+use crate::error::{AppError, Result};
+use crate::watch_history::WatchHistoryProvider;
+use std::fs;
+use std::path::PathBuf;
+
+/// Viewing history read from a saved Tautulli `get_history` response
+///
+/// Stands in for the API, e.g. to test `active_window` strategies offline. The
+/// file holds the response body as returned by
+/// `api/v2?apikey=...&cmd=get_history&length=N` and is parsed like a live one.
+/// Metadata lookups are not available.
+#[derive(Debug, Clone)]
+pub struct TautulliFixture {
+    path: PathBuf,
+}
+
+impl TautulliFixture {
+    pub const fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn load(&self) -> Result<Vec<HistoryItem>> {
+        let body = fs::read_to_string(&self.path).map_err(|e| {
+            AppError::External(format!(
+                "Failed to read Tautulli fixture {}: {e}",
+                self.path.display()
+            ))
+        })?;
+        parse_history(&body)
+    }
+}
+
+impl WatchHistoryProvider for TautulliFixture {
+    fn name(&self) -> &'static str {
+        "tautulli fixture"
+    }
+
+    fn health_check(&self) -> Result<()> {
+        let history = self.load()?;
+        tracing::info!(
+            "Tautulli fixture {} holds {} history items",
+            self.path.display(),
+            history.len()
+        );
+        Ok(())
+    }
+
+    fn get_history(&self, length: u32) -> Result<Vec<HistoryItem>> {
+        let mut history = self.load()?;
+        history.truncate(length as usize);
+        Ok(history)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conditions::ActiveWindowCondition;
+    use crate::disk::MockDisk;
+    use crate::tautulli::TautulliClient;
+    use crate::tautulli::mock::{MockTautulli, envelope};
+    use crate::{
+        Balancer, FileInfo, MemoryScanner, PlacementDecision, PlacementStrategy, Tier,
+        WatchHistoryConfig,
+    };
+    use std::collections::HashMap;
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    const GB: u64 = 1_000_000_000;
+
+    /// `alice` finished S01E02 of Show an hour ago, after half of S01E01
+    fn history_body() -> String {
+        let stopped = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            - 3600;
+        let item = |episode: u32, percent: u8| {
+            serde_json::json!({
+                "user": "alice",
+                "rating_key": format!("{}", 100 + episode),
+                "media_type": "episode",
+                "title": format!("Episode {episode}"),
+                "grandparent_title": "Show",
+                "parent_media_index": "1",
+                "media_index": episode.to_string(),
+                "percent_complete": percent,
+                "stopped": stopped,
+            })
+        };
+        let data = serde_json::json!({ "data": [item(2, 100), item(1, 50)] });
+        envelope("success", &data.to_string(), None)
+    }
+
+    fn write_fixture(dir: &Path) -> PathBuf {
+        let path = dir.join("history.json");
+        fs::write(&path, history_body()).unwrap();
+        path
+    }
+
+    fn promoted(config: WatchHistoryConfig) -> Vec<String> {
+        let tiers = vec![
+            Tier::new_unchecked("cache".into(), "/mnt/cache".into(), 1, None, None)
+                .unwrap()
+                .with_disk_ops(Arc::new(MockDisk::new(1000 * GB, 1000 * GB))),
+            Tier::new_unchecked("storage".into(), "/mnt/storage".into(), 2, None, None)
+                .unwrap()
+                .with_disk_ops(Arc::new(MockDisk::new(1000 * GB, 500 * GB))),
+        ];
+        let strategies = vec![
+            PlacementStrategy::new("watching".into(), 100)
+                .add_condition(Box::new(ActiveWindowCondition::new("window".into())))
+                .add_preferred_tier("cache".into()),
+            PlacementStrategy::new("default".into(), 1).add_preferred_tier("storage".into()),
+        ];
+        let scanner = (1..=10).fold(MemoryScanner::new(), |scanner, episode| {
+            let modified = SystemTime::now();
+            scanner.with_file(
+                "storage",
+                FileInfo {
+                    path: format!("/mnt/storage/Show/Season 01/Show - S01E{episode:02}.mkv").into(),
+                    size: GB,
+                    modified,
+                    accessed: modified,
+                    owner: None,
+                },
+            )
+        });
+        let plan = Balancer::builder(tiers, strategies)
+            .scanner(Arc::new(scanner))
+            .watch_history(config)
+            .build()
+            .plan_rebalance();
+        let mut promoted: Vec<String> = plan
+            .decisions
+            .iter()
+            .filter_map(|decision| match decision {
+                PlacementDecision::Promote { file, .. } => file
+                    .path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect();
+        promoted.sort();
+        promoted
+    }
+
+    #[test]
+    fn test_fixture_matches_api_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = TautulliFixture::new(write_fixture(dir.path()));
+        let server = MockTautulli::start(history_body(), HashMap::new());
+        let client = TautulliClient::new(server.url, server.api_key).unwrap();
+
+        client.health_check().unwrap();
+        fixture.health_check().unwrap();
+        assert_eq!(
+            format!("{:?}", client.get_history(100).unwrap()),
+            format!("{:?}", fixture.get_history(100).unwrap())
+        );
+        assert_eq!(fixture.get_history(1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_active_window_from_fixture() {
+        let dir = tempfile::tempdir().unwrap();
+        let from_fixture = promoted(WatchHistoryConfig::from_fixture(write_fixture(dir.path())));
+
+        // Backward 2 and forward 5 episodes around S01E02
+        let expected: Vec<String> = (1..=7)
+            .map(|episode| format!("Show - S01E{episode:02}.mkv"))
+            .collect();
+        assert_eq!(from_fixture, expected);
+
+        // The same history served over the API gives the same plan
+        let server = MockTautulli::start(history_body(), HashMap::new());
+        let mut config = WatchHistoryConfig::from_fixture(PathBuf::new());
+        config.fixture = None;
+        config.url = server.url;
+        config.api_key = server.api_key;
+        assert_eq!(promoted(config), expected);
+    }
+}
@@ -0,0 +1,94 @@
+//! Tautulli API stand-in on a local port, for tests that go through `TautulliClient`
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+
+/// Serves `get_server_info`, `get_history` and `get_metadata` until the test exits
+pub struct MockTautulli {
+    pub url: String,
+    pub api_key: String,
+}
+
+impl MockTautulli {
+    /// Answers `get_history` with `history`, a full response body, and
+    /// `get_metadata` with the files listed per rating key
+    pub fn start(history: String, files: HashMap<String, Vec<String>>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let api_key = "mock-key".to_string();
+        let routes = Arc::new((history, files, api_key.clone()));
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let routes = Arc::clone(&routes);
+                thread::spawn(move || {
+                    let (history, files, api_key) = &*routes;
+                    respond(stream, history, files, api_key);
+                });
+            }
+        });
+        Self { url, api_key }
+    }
+}
+
+fn respond(
+    mut stream: TcpStream,
+    history: &str,
+    files: &HashMap<String, Vec<String>>,
+    api_key: &str,
+) {
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    // Headers are not needed, but must be read before the connection closes
+    let mut header = String::new();
+    while reader.read_line(&mut header).is_ok_and(|n| n > 2) {
+        header.clear();
+    }
+
+    let target = request_line.split_whitespace().nth(1).unwrap_or_default();
+    let query: HashMap<&str, &str> = target
+        .split_once('?')
+        .map(|(_, query)| query.split('&').filter_map(|p| p.split_once('=')).collect())
+        .unwrap_or_default();
+
+    let body = if query.get("apikey") == Some(&api_key) {
+        match query.get("cmd").copied() {
+            Some("get_server_info") => envelope(
+                "success",
+                r#"{"pms_name":"Mock Plex","pms_version":"1.0"}"#,
+                None,
+            ),
+            Some("get_history") => history.to_string(),
+            Some("get_metadata") => {
+                let parts: Vec<_> = query
+                    .get("rating_key")
+                    .and_then(|key| files.get(*key))
+                    .into_iter()
+                    .flatten()
+                    .map(|file| serde_json::json!({ "file": file }))
+                    .collect();
+                let data = serde_json::json!({ "media_info": [{ "parts": parts }] });
+                envelope("success", &data.to_string(), None)
+            }
+            _ => envelope("error", "{}", Some("Unknown command")),
+        }
+    } else {
+        envelope("error", "{}", Some("Invalid apikey"))
+    };
+    let _ = write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+}
+
+/// A Tautulli `{"response": {...}}` body
+pub fn envelope(result: &str, data: &str, message: Option<&str>) -> String {
+    let message = message.map_or_else(|| "null".to_string(), |m| format!("\"{m}\""));
+    format!(r#"{{"response":{{"result":"{result}","message":{message},"data":{data}}}}}"#)
+}
@@ -1,9 +1,15 @@
 mod client;
 mod episode;
+mod fixture;
+#[cfg(test)]
+pub(crate) mod mock;
+mod movie;
 mod progress;
 mod stats;
 
 pub use client::{HistoryItem, TautulliClient};
 pub use episode::{EpisodeInfo, normalize_show_name, parse_episode};
-pub use progress::{ShowProgress, build_progress};
+pub use fixture::TautulliFixture;
+pub use movie::{MovieInfo, MovieWatch, build_movie_watches, parse_movie};
+pub use progress::{ShowProgress, build_in_progress, build_progress};
 pub use stats::TautulliStats;
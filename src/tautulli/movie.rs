@@ -0,0 +1,180 @@
+use super::{HistoryItem, normalize_show_name, parse_episode};
+use lazy_regex::{Regex, regex};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Movie information parsed from filename
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MovieInfo {
+    pub title: String,
+    pub year: Option<u32>,
+}
+
+/// Parse movie information from file path
+///
+/// Supports multiple formats:
+/// - Plex: "Movie Title (2021).mkv"
+/// - Scene: "Movie.Title.2021.1080p.mkv"
+///
+/// The last year-like token is used, so "Blade Runner 2049 (2017)" keeps 2049 in the title.
+/// Returns None for episodes.
+pub fn parse_movie(path: &Path) -> Option<MovieInfo> {
+    if parse_episode(path).is_some() {
+        return None;
+    }
+
+    let stem = path.file_stem()?.to_str()?;
+
+    // Year surrounded by separators or brackets (compiled at compile-time)
+    let re: &Regex = regex!(r"^(.+)[\s._(\[]((?:19|20)\d{2})(?:[\s._)\]]|$)");
+
+    let (title_raw, year) = re.captures(stem).map_or((stem, None), |captures| {
+        let title = captures.get(1).map_or(stem, |m| m.as_str());
+        let year = captures.get(2).and_then(|m| m.as_str().parse().ok());
+        (title, year)
+    });
+
+    let title = title_raw
+        .replace(['.', '_'], " ")
+        .trim()
+        .trim_end_matches(['-', '(', '[', ' '])
+        .trim()
+        .to_string();
+
+    if title.is_empty() {
+        return None;
+    }
+
+    Some(MovieInfo { title, year })
+}
+
+/// Latest watch state of a movie, merged across users
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MovieWatch {
+    pub title: String,
+    pub year: Option<u32>,
+    pub rating_key: String,
+    /// Timestamp of the latest play
+    pub last_watched: u64,
+    /// Whether anyone finished the movie (`watched_threshold` reached)
+    pub completed: bool,
+}
+
+impl MovieWatch {
+    /// Started but not finished by anyone
+    pub const fn is_in_progress(&self) -> bool {
+        !self.completed
+    }
+
+    /// Whether this entry describes the given movie (years must agree when both are known)
+    pub fn matches(&self, movie: &MovieInfo) -> bool {
+        match (self.year, movie.year) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        }
+    }
+}
+
+/// Build movie watch state from history items
+///
+/// # Returns
+/// Watches keyed by normalized title; remakes with different years get separate entries
+pub fn build_movie_watches(
+    history: &[HistoryItem],
+    watched_threshold: u8,
+) -> HashMap<String, Vec<MovieWatch>> {
+    let mut watches: HashMap<String, Vec<MovieWatch>> = HashMap::new();
+
+    for item in history {
+        if item.media_type != "movie" || item.title.is_empty() || item.percent_complete == 0 {
+            continue;
+        }
+
+        let year = (item.year > 0).then_some(item.year);
+        let completed = item.percent_complete >= watched_threshold;
+        let entries = watches.entry(normalize_show_name(&item.title)).or_default();
+
+        if let Some(existing) = entries.iter_mut().find(|w| w.year == year) {
+            existing.last_watched = existing.last_watched.max(item.stopped);
+            existing.completed |= completed;
+        } else {
+            entries.push(MovieWatch {
+                title: item.title.clone(),
+                year,
+                rating_key: item.rating_key.clone(),
+                last_watched: item.stopped,
+                completed,
+            });
+        }
+    }
+
+    watches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn create_movie_item(title: &str, year: u32, percent: u8, stopped: u64) -> HistoryItem {
+        HistoryItem {
+            user: "alice".to_string(),
+            rating_key: "500".to_string(),
+            media_type: "movie".to_string(),
+            title: title.to_string(),
+            year,
+            grandparent_title: String::new(),
+            parent_media_index: 0,
+            media_index: 0,
+            percent_complete: percent,
+            stopped,
+        }
+    }
+
+    #[test]
+    fn test_parse_movie_formats() {
+        let movie = parse_movie(&PathBuf::from("/movies/Heat (1995)/Heat (1995).mkv")).unwrap();
+        assert_eq!(movie.title, "Heat");
+        assert_eq!(movie.year, Some(1995));
+
+        let movie = parse_movie(&PathBuf::from(
+            "/mnt/cache/The.Matrix.1999.1080p.BluRay.mkv",
+        ))
+        .expect("Should parse scene format");
+        assert_eq!(movie.title, "The Matrix");
+        assert_eq!(movie.year, Some(1999));
+
+        let movie = parse_movie(&PathBuf::from("/mnt/cache/Blade Runner 2049 (2017).mkv")).unwrap();
+        assert_eq!(movie.title, "Blade Runner 2049");
+        assert_eq!(movie.year, Some(2017));
+
+        let movie = parse_movie(&PathBuf::from("/mnt/cache/Amelie.mkv")).unwrap();
+        assert_eq!(movie.title, "Amelie");
+        assert_eq!(movie.year, None);
+    }
+
+    #[test]
+    fn test_parse_movie_skips_episodes() {
+        assert!(parse_movie(&PathBuf::from("/mnt/cache/Breaking.Bad.S01E05.mkv")).is_none());
+    }
+
+    #[test]
+    fn test_build_movie_watches() {
+        let history = vec![
+            create_movie_item("Dune", 2021, 40, 100),
+            create_movie_item("Dune", 2021, 95, 50),
+            create_movie_item("Dune", 1984, 100, 10),
+            create_movie_item("Heat", 1995, 0, 10),
+        ];
+
+        let watches = build_movie_watches(&history, 90);
+
+        assert!(!watches.contains_key("heat"));
+        let dune = &watches["dune"];
+        assert_eq!(dune.len(), 2);
+        assert_eq!(dune[0].year, Some(2021));
+        assert_eq!(dune[0].last_watched, 100);
+        assert!(dune[0].completed);
+        assert_eq!(dune[1].year, Some(1984));
+    }
+}
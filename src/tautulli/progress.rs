@@ -9,7 +9,6 @@ pub struct ShowProgress {
     pub show_name: String,
     pub last_watched_season: u32,
     pub last_watched_episode: u32,
-    pub last_watched_global: u32,
     pub last_watched_time: u64,
 }
 
@@ -21,16 +20,19 @@ impl ShowProgress {
         episode: u32,
         timestamp: u64,
     ) -> Self {
-        let global_index = (season - 1) * 100 + episode;
         Self {
             user,
             show_name,
             last_watched_season: season,
             last_watched_episode: episode,
-            last_watched_global: global_index,
             last_watched_time: timestamp,
         }
     }
+
+    /// Last watched (season, episode), ordered by season first
+    pub const fn last_watched(&self) -> (u32, u32) {
+        (self.last_watched_season, self.last_watched_episode)
+    }
 }
 
 /// Build user watch progress from history items
@@ -74,13 +76,13 @@ pub fn build_progress(
         // Key: (user, normalized_show_name)
         let key = (item.user.clone(), normalized_show);
 
-        let global_index = (item.parent_media_index - 1) * 100 + item.media_index;
+        let watched = (item.parent_media_index, item.media_index);
 
         // Update if this is the latest episode for this user+show
         progress_map
             .entry(key)
             .and_modify(|existing| {
-                if global_index > existing.last_watched_global {
+                if watched > existing.last_watched() {
                     *existing = ShowProgress::new(
                         item.user.clone(),
                         item.grandparent_title.clone(),
@@ -104,6 +106,65 @@ pub fn build_progress(
     progress_map.into_values().collect()
 }
 
+/// Build the episodes users stopped partway through, from history items
+///
+/// Counterpart of [`build_progress`] for plays below `watched_threshold`: per
+/// (user, show), the most recently stopped partial play within `days_back`.
+/// It is dropped once the same episode was watched to the threshold afterwards.
+pub fn build_in_progress(
+    history: &[HistoryItem],
+    days_back: u32,
+    watched_threshold: u8,
+) -> Vec<ShowProgress> {
+    let cutoff_time = calculate_cutoff_time(days_back);
+    let recent = history
+        .iter()
+        .filter(|item| !item.grandparent_title.is_empty() && item.stopped >= cutoff_time);
+
+    let mut in_progress: HashMap<(String, String), &HistoryItem> = HashMap::new();
+    for item in recent.clone() {
+        if item.percent_complete == 0 || item.percent_complete >= watched_threshold {
+            continue;
+        }
+        let key = (
+            item.user.clone(),
+            normalize_show_name(&item.grandparent_title),
+        );
+        in_progress
+            .entry(key)
+            .and_modify(|existing| {
+                if item.stopped > existing.stopped {
+                    *existing = item;
+                }
+            })
+            .or_insert(item);
+    }
+
+    in_progress
+        .into_values()
+        .filter(|partial| {
+            !recent.clone().any(|item| {
+                item.user == partial.user
+                    && normalize_show_name(&item.grandparent_title)
+                        == normalize_show_name(&partial.grandparent_title)
+                    && (item.parent_media_index, item.media_index)
+                        == (partial.parent_media_index, partial.media_index)
+                    && item.percent_complete >= watched_threshold
+                    && item.stopped >= partial.stopped
+            })
+        })
+        .map(|item| {
+            ShowProgress::new(
+                item.user.clone(),
+                item.grandparent_title.clone(),
+                item.parent_media_index,
+                item.media_index,
+                item.stopped,
+            )
+        })
+        .collect()
+}
+
 /// Calculate Unix timestamp for N days ago
 fn calculate_cutoff_time(days_back: u32) -> u64 {
     let now = SystemTime::now()
@@ -133,6 +194,9 @@ mod tests {
         HistoryItem {
             user: user.to_string(),
             rating_key: "12345".to_string(),
+            media_type: "episode".to_string(),
+            title: String::new(),
+            year: 0,
             grandparent_title: show.to_string(),
             parent_media_index: season,
             media_index: episode,
@@ -162,13 +226,11 @@ mod tests {
         let alice_progress = progress.iter().find(|p| p.user == "alice").unwrap();
         assert_eq!(alice_progress.last_watched_season, 1);
         assert_eq!(alice_progress.last_watched_episode, 6); // Latest episode
-        assert_eq!(alice_progress.last_watched_global, 6);
 
         // Find bob's progress
         let bob_progress = progress.iter().find(|p| p.user == "bob").unwrap();
         assert_eq!(bob_progress.last_watched_season, 2);
         assert_eq!(bob_progress.last_watched_episode, 1);
-        assert_eq!(bob_progress.last_watched_global, 101); // (2-1)*100 + 1
     }
 
     #[test]
@@ -301,6 +363,51 @@ mod tests {
         let alice = &progress[0];
         assert_eq!(alice.last_watched_season, 2);
         assert_eq!(alice.last_watched_episode, 3);
-        assert_eq!(alice.last_watched_global, 103); // (2-1)*100 + 3
+    }
+
+    #[test]
+    fn test_build_in_progress() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let history = vec![
+            create_history_item("alice", "Breaking Bad", 1, 5, 95, now - 300),
+            create_history_item("alice", "Breaking Bad", 1, 6, 40, now - 200),
+            create_history_item("alice", "Breaking Bad", 1, 2, 30, now - 400),
+            // Finished later, no longer in progress
+            create_history_item("bob", "Lost", 1, 1, 20, now - 200),
+            create_history_item("bob", "Lost", 1, 1, 100, now - 100),
+            // Barely started and then abandoned
+            create_history_item("bob", "Dark", 1, 1, 0, now - 100),
+        ];
+
+        let in_progress = build_in_progress(&history, 1, 90);
+
+        assert_eq!(in_progress.len(), 1);
+        assert_eq!(in_progress[0].user, "alice");
+        assert_eq!(in_progress[0].last_watched(), (1, 6));
+        // Partial plays don't count as watched
+        assert_eq!(build_progress(&history, 1, 90).len(), 2);
+    }
+
+    #[test]
+    fn test_build_progress_long_seasons_and_specials() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let history = vec![
+            create_history_item("alice", "One Piece", 0, 3, 95, now - 300),
+            create_history_item("alice", "One Piece", 1, 150, 95, now - 200),
+            create_history_item("alice", "One Piece", 2, 1, 95, now - 100),
+        ];
+
+        let progress = build_progress(&history, 1, 90);
+
+        assert_eq!(progress.len(), 1);
+        assert_eq!(progress[0].last_watched(), (2, 1));
     }
 }
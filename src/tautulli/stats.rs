@@ -1,7 +1,10 @@
-use super::{EpisodeInfo, ShowProgress, normalize_show_name, parse_episode};
+use super::{
+    EpisodeInfo, HistoryItem, MovieInfo, MovieWatch, ShowProgress, build_movie_watches,
+    normalize_show_name, parse_episode, parse_movie,
+};
 use crate::FileInfo;
-use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 /// Tautulli statistics for file placement decisions
 #[derive(Debug, Clone)]
@@ -15,6 +18,23 @@ pub struct TautulliStats {
 
     /// User watch progress (for debugging/logging)
     pub user_progress: Vec<ShowProgress>,
+
+    /// Mapping from file path to parsed movie info
+    pub movie_map: HashMap<PathBuf, MovieInfo>,
+
+    /// Watched and in-progress movies
+    /// Key: `normalized_title`
+    pub movie_watches: HashMap<String, Vec<MovieWatch>>,
+
+    /// Episodes users stopped partway through
+    pub in_progress: Vec<ShowProgress>,
+
+    /// Progress over all fetched history, for `keep_next_unwatched`
+    next_unwatched_progress: Vec<ShowProgress>,
+
+    backward_episodes: u32,
+    forward_episodes: u32,
+    next_unwatched_episodes: u32,
 }
 
 impl TautulliStats {
@@ -34,46 +54,177 @@ impl TautulliStats {
     where
         I: IntoIterator<Item = &'a FileInfo>,
     {
-        // Parse episodes from file paths
-        let episode_map: HashMap<PathBuf, EpisodeInfo> = files
-            .into_iter()
-            .filter_map(|file| {
-                parse_episode(&file.path).map(|episode| (file.path.clone(), episode))
-            })
-            .collect();
-
-        // Calculate active viewing windows
-        let active_window_episodes =
-            calculate_viewing_windows(&user_progress, backward_episodes, forward_episodes);
+        // Parse episodes and movies from file paths
+        let mut episode_map = HashMap::new();
+        let mut movie_map = HashMap::new();
+        for file in files {
+            if let Some(episode) = parse_episode(&file.path) {
+                episode_map.insert(file.path.clone(), episode);
+            } else if let Some(movie) = parse_movie(&file.path) {
+                movie_map.insert(file.path.clone(), movie);
+            }
+        }
 
-        Self {
-            active_window_episodes,
+        let mut stats = Self {
+            active_window_episodes: HashSet::new(),
             episode_map,
             user_progress,
+            movie_map,
+            movie_watches: HashMap::new(),
+            in_progress: Vec::new(),
+            next_unwatched_progress: Vec::new(),
+            backward_episodes,
+            forward_episodes,
+            next_unwatched_episodes: 0,
+        };
+        stats.update_viewing_windows();
+        stats
+    }
+
+    /// Recalculate active viewing windows from the episodes on disk
+    fn update_viewing_windows(&mut self) {
+        let episode_order = build_episode_order(self.episode_map.values());
+        self.active_window_episodes = calculate_viewing_windows(
+            &self.user_progress,
+            &episode_order,
+            self.backward_episodes,
+            self.forward_episodes,
+        );
+        // In-progress episodes are where users resume, so only look forward
+        self.active_window_episodes
+            .extend(calculate_viewing_windows(
+                &self.in_progress,
+                &episode_order,
+                0,
+                self.forward_episodes,
+            ));
+        self.active_window_episodes.extend(calculate_next_unwatched(
+            &self.next_unwatched_progress,
+            &episode_order,
+            self.next_unwatched_episodes,
+        ));
+    }
+
+    /// Also keep each in-progress episode and the forward window after it
+    pub fn with_in_progress(mut self, in_progress: Vec<ShowProgress>) -> Self {
+        self.in_progress = in_progress;
+        self.update_viewing_windows();
+        self
+    }
+
+    /// Also keep the next `episodes` unwatched episodes of every show in `progress`
+    ///
+    /// `progress` should span all history, so shows not watched within
+    /// `days_back` keep their next episodes too.
+    pub fn with_next_unwatched(mut self, progress: Vec<ShowProgress>, episodes: u32) -> Self {
+        self.next_unwatched_progress = progress;
+        self.next_unwatched_episodes = episodes;
+        self.update_viewing_windows();
+        self
+    }
+
+    /// Apply episode info resolved from media server metadata
+    ///
+    /// Resolved paths override filename parsing. Other episodes in the same show
+    /// directory (`Show/Season 01/...` layout) take the show name from metadata.
+    pub fn with_resolved_paths(mut self, resolved: HashMap<PathBuf, EpisodeInfo>) -> Self {
+        for (path, episode) in &resolved {
+            let Some(show_dir) = show_directory(path) else {
+                continue;
+            };
+            for (file, info) in &mut self.episode_map {
+                if file.starts_with(show_dir) {
+                    info.show_name.clone_from(&episode.show_name);
+                }
+            }
         }
+
+        self.episode_map.extend(resolved);
+        self.update_viewing_windows();
+        self
+    }
+
+    /// Add movie watch state from history items
+    pub fn with_movie_history(mut self, history: &[HistoryItem], watched_threshold: u8) -> Self {
+        self.movie_watches = build_movie_watches(history, watched_threshold);
+        self
+    }
+
+    /// Watch state of the movie stored at `file_path`, if anyone played it
+    pub fn movie_watch(&self, file_path: &Path) -> Option<&MovieWatch> {
+        let movie = self.movie_map.get(file_path)?;
+        self.movie_watches
+            .get(&normalize_show_name(&movie.title))?
+            .iter()
+            .find(|watch| watch.matches(movie))
+    }
+
+    /// Check if the movie at `file_path` was watched or in progress at or after `cutoff`
+    pub fn is_movie_watched_since(&self, file_path: &Path, cutoff: u64) -> bool {
+        self.movie_watch(file_path)
+            .is_some_and(|watch| watch.last_watched >= cutoff)
     }
 
     /// Check if file is in any active viewing window
+    ///
+    /// Multi-episode files match if any of their episodes is in a window.
     pub fn is_in_active_window(&self, file_path: &PathBuf) -> bool {
         if let Some(episode) = self.episode_map.get(file_path) {
-            let key = (
-                normalize_show_name(&episode.show_name),
-                episode.season,
-                episode.episode,
-            );
-            self.active_window_episodes.contains(&key)
+            let show = normalize_show_name(&episode.show_name);
+            episode.episodes().any(|number| {
+                self.active_window_episodes
+                    .contains(&(show.clone(), episode.season, number))
+            })
         } else {
             false
         }
     }
 }
 
+/// Show directory of an episode stored under a season folder
+fn show_directory(path: &Path) -> Option<&Path> {
+    let season_dir = path.parent()?;
+    let name = season_dir.file_name()?.to_str()?.to_lowercase();
+    if name.starts_with("season") || name == "specials" {
+        season_dir.parent()
+    } else {
+        None
+    }
+}
+
+/// Episodes present on disk per show, sorted by season and episode
+///
+/// Key: `normalized_show_name`. Specials (season 0) are left out so windows
+/// only advance through regular episodes.
+fn build_episode_order<'a, I>(episodes: I) -> HashMap<String, Vec<(u32, u32)>>
+where
+    I: IntoIterator<Item = &'a EpisodeInfo>,
+{
+    let mut order: HashMap<String, BTreeSet<(u32, u32)>> = HashMap::new();
+    for info in episodes {
+        if info.season == 0 {
+            continue;
+        }
+        order
+            .entry(normalize_show_name(&info.show_name))
+            .or_default()
+            .extend(info.episodes().map(|episode| (info.season, episode)));
+    }
+
+    order
+        .into_iter()
+        .map(|(show, episodes)| (show, episodes.into_iter().collect()))
+        .collect()
+}
+
 /// Calculate viewing windows for all users
 ///
-/// For each user's last watched episode, creates a window of episodes to keep.
+/// For each user's last watched episode, keeps `backward_episodes` before it and
+/// `forward_episodes` after it, counted over the show's episodes present on disk.
 /// Windows are merged across all users.
 fn calculate_viewing_windows(
     user_progress: &[ShowProgress],
+    episode_order: &HashMap<String, Vec<(u32, u32)>>,
     backward_episodes: u32,
     forward_episodes: u32,
 ) -> HashSet<(String, u32, u32)> {
@@ -81,23 +232,18 @@ fn calculate_viewing_windows(
 
     for progress in user_progress {
         let normalized_show = normalize_show_name(&progress.show_name);
-
-        // Calculate window range using global indices
-        let start_global = progress
-            .last_watched_global
-            .saturating_sub(backward_episodes);
-        let end_global = progress.last_watched_global + forward_episodes;
-
-        // Convert back to season/episode pairs
-        for global_idx in start_global..=end_global {
-            let season = (global_idx / 100) + 1;
-            let episode = global_idx % 100;
-
-            // Skip episode 0 (would happen if global_idx % 100 == 0)
-            if episode == 0 {
-                continue;
-            }
-
+        let watched = progress.last_watched();
+        windows.insert((
+            normalized_show.clone(),
+            progress.last_watched_season,
+            progress.last_watched_episode,
+        ));
+
+        let Some(order) = episode_order.get(&normalized_show) else {
+            continue;
+        };
+
+        for &(season, episode) in window(order, watched, backward_episodes, forward_episodes) {
             windows.insert((normalized_show.clone(), season, episode));
         }
     }
@@ -105,6 +251,44 @@ fn calculate_viewing_windows(
     windows
 }
 
+/// Next `count` episodes on disk after each user's last watched one
+fn calculate_next_unwatched(
+    user_progress: &[ShowProgress],
+    episode_order: &HashMap<String, Vec<(u32, u32)>>,
+    count: u32,
+) -> HashSet<(String, u32, u32)> {
+    let mut episodes = HashSet::new();
+    for progress in user_progress {
+        let normalized_show = normalize_show_name(&progress.show_name);
+        let Some(order) = episode_order.get(&normalized_show) else {
+            continue;
+        };
+        let watched = progress.last_watched();
+        for &(season, episode) in window(order, watched, 0, count)
+            .iter()
+            .filter(|&&episode| episode != watched)
+        {
+            episodes.insert((normalized_show.clone(), season, episode));
+        }
+    }
+    episodes
+}
+
+/// Episodes of `order` from `backward` before `watched` to `forward` after it
+fn window(order: &[(u32, u32)], watched: (u32, u32), backward: u32, forward: u32) -> &[(u32, u32)] {
+    // Position of the watched episode, or of the next one if it is not on disk
+    let position = order.partition_point(|&episode| episode < watched);
+    let after = if order.get(position) == Some(&watched) {
+        position + 1
+    } else {
+        position
+    };
+
+    let start = position.saturating_sub(backward as usize);
+    let end = (after + forward as usize).min(order.len());
+    &order[start..end]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,26 +301,41 @@ mod tests {
             size: 1024,
             modified: SystemTime::now(),
             accessed: SystemTime::now(),
+            owner: None,
         }
     }
 
     fn create_progress(show: &str, season: u32, episode: u32) -> ShowProgress {
-        let global = (season - 1) * 100 + episode;
         ShowProgress {
             user: "alice".to_string(),
             show_name: show.to_string(),
             last_watched_season: season,
             last_watched_episode: episode,
-            last_watched_global: global,
             last_watched_time: 1234567890,
         }
     }
 
+    /// Episode order for shows with `count` episodes in each listed season
+    fn create_order(shows: &[(&str, &[(u32, u32)])]) -> HashMap<String, Vec<(u32, u32)>> {
+        shows
+            .iter()
+            .map(|(show, seasons)| {
+                let episodes = seasons
+                    .iter()
+                    .flat_map(|&(season, count)| (1..=count).map(move |episode| (season, episode)))
+                    .collect();
+                (normalize_show_name(show), episodes)
+            })
+            .collect()
+    }
+
     #[test]
     fn test_calculate_viewing_windows_basic() {
         let progress = vec![create_progress("Breaking Bad", 1, 5)];
 
-        let windows = calculate_viewing_windows(&progress, 2, 3);
+        let order = create_order(&[("Breaking Bad", &[(1, 13)])]);
+
+        let windows = calculate_viewing_windows(&progress, &order, 2, 3);
 
         // Should have episodes 3, 4, 5, 6, 7, 8 (5 - 2 to 5 + 3)
         assert_eq!(windows.len(), 6);
@@ -154,15 +353,18 @@ mod tests {
     fn test_calculate_viewing_windows_cross_season() {
         let progress = vec![create_progress("Breaking Bad", 2, 2)];
 
-        let windows = calculate_viewing_windows(&progress, 5, 3);
+        let order = create_order(&[("Breaking Bad", &[(1, 7), (2, 13)])]);
+
+        let windows = calculate_viewing_windows(&progress, &order, 5, 3);
 
-        // Should span from S01E97 to S02E05
+        // Should span from S01E04 to S02E05 (season 1 has 7 episodes)
+        assert_eq!(windows.len(), 9);
         let normalized_show = normalize_show_name("Breaking Bad");
 
-        // Check S01E97, S01E98, S01E99
-        assert!(windows.contains(&(normalized_show.clone(), 1, 97)));
-        assert!(windows.contains(&(normalized_show.clone(), 1, 98)));
-        assert!(windows.contains(&(normalized_show.clone(), 1, 99)));
+        // Check S01E04 to S01E07, no phantom episodes past the season end
+        assert!(windows.contains(&(normalized_show.clone(), 1, 4)));
+        assert!(windows.contains(&(normalized_show.clone(), 1, 7)));
+        assert!(!windows.contains(&(normalized_show.clone(), 1, 8)));
 
         // Check S02E01, S02E02, S02E03, S02E04, S02E05
         assert!(windows.contains(&(normalized_show.clone(), 2, 1)));
@@ -179,7 +381,9 @@ mod tests {
             create_progress("Breaking Bad", 1, 10),
         ];
 
-        let windows = calculate_viewing_windows(&progress, 2, 2);
+        let order = create_order(&[("Breaking Bad", &[(1, 13)])]);
+
+        let windows = calculate_viewing_windows(&progress, &order, 2, 2);
 
         let normalized_show = normalize_show_name("Breaking Bad");
 
@@ -200,7 +404,12 @@ mod tests {
             create_progress("The Office", 2, 3),
         ];
 
-        let windows = calculate_viewing_windows(&progress, 1, 1);
+        let order = create_order(&[
+            ("Breaking Bad", &[(1, 13)]),
+            ("The Office", &[(1, 6), (2, 22)]),
+        ]);
+
+        let windows = calculate_viewing_windows(&progress, &order, 1, 1);
 
         assert_eq!(windows.len(), 6); // 3 episodes per show
 
@@ -231,7 +440,7 @@ mod tests {
         // Should have 3 files in episode_map
         assert_eq!(stats.episode_map.len(), 3);
 
-        // Episode 5,6,7 should be in active window
+        // Episode 5,6 and the next episode on disk (10) should be in active window
         assert_eq!(stats.active_window_episodes.len(), 3);
 
         let normalized_show = normalize_show_name("Breaking Bad");
@@ -248,14 +457,53 @@ mod tests {
         assert!(
             stats
                 .active_window_episodes
-                .contains(&(normalized_show, 1, 7))
+                .contains(&(normalized_show, 1, 10))
         );
     }
 
+    #[test]
+    fn test_with_in_progress() {
+        let files = [
+            create_file_info("/mnt/cache/Lost.S01E01.mkv"),
+            create_file_info("/mnt/cache/Lost.S01E02.mkv"),
+            create_file_info("/mnt/cache/Lost.S01E03.mkv"),
+            create_file_info("/mnt/cache/Lost.S01E04.mkv"),
+        ];
+
+        // Started E02 without finishing anything yet
+        let stats = TautulliStats::build(files.iter(), Vec::new(), 2, 1)
+            .with_in_progress(vec![create_progress("Lost", 1, 2)]);
+
+        assert!(!stats.is_in_active_window(&files[0].path));
+        assert!(stats.is_in_active_window(&files[1].path));
+        assert!(stats.is_in_active_window(&files[2].path));
+        assert!(!stats.is_in_active_window(&files[3].path));
+    }
+
+    #[test]
+    fn test_with_next_unwatched() {
+        let files = [
+            create_file_info("/mnt/cache/Lost.S01E01.mkv"),
+            create_file_info("/mnt/cache/Lost.S01E02.mkv"),
+            create_file_info("/mnt/cache/Lost.S01E03.mkv"),
+            create_file_info("/mnt/cache/Lost.S01E04.mkv"),
+        ];
+
+        // Not watched within days_back, so no regular window
+        let stats = TautulliStats::build(files.iter(), Vec::new(), 2, 5)
+            .with_next_unwatched(vec![create_progress("Lost", 1, 1)], 2);
+
+        assert!(!stats.is_in_active_window(&files[0].path));
+        assert!(stats.is_in_active_window(&files[1].path));
+        assert!(stats.is_in_active_window(&files[2].path));
+        assert!(!stats.is_in_active_window(&files[3].path));
+    }
+
     #[test]
     fn test_is_in_active_window() {
         let files = [
             create_file_info("/mnt/cache/Breaking.Bad.S01E05.mkv"),
+            create_file_info("/mnt/cache/Breaking.Bad.S01E07.mkv"),
             create_file_info("/mnt/cache/Breaking.Bad.S01E10.mkv"),
         ];
 
@@ -266,10 +514,28 @@ mod tests {
         // Episode 5 should be in window (6-1)
         assert!(stats.is_in_active_window(&PathBuf::from("/mnt/cache/Breaking.Bad.S01E05.mkv")));
 
-        // Episode 10 should NOT be in window (6+1 = 7, but 10 > 7)
+        // Episode 10 should NOT be in window (7 is the next episode on disk)
         assert!(!stats.is_in_active_window(&PathBuf::from("/mnt/cache/Breaking.Bad.S01E10.mkv")));
     }
 
+    #[test]
+    fn test_is_in_active_window_multi_episode() {
+        let files = [
+            create_file_info("/mnt/cache/Breaking.Bad.S01E03-E04.mkv"),
+            create_file_info("/mnt/cache/Breaking.Bad.S01E07E08.mkv"),
+            create_file_info("/mnt/cache/Breaking.Bad.S01E09E10.mkv"),
+        ];
+
+        let progress = vec![create_progress("Breaking Bad", 1, 6)];
+
+        let stats = TautulliStats::build(files.iter(), progress, 2, 2);
+
+        // Two episodes on disk on each side of E06: 3-4 and 7-8
+        assert!(stats.is_in_active_window(&files[0].path));
+        assert!(stats.is_in_active_window(&files[1].path));
+        assert!(!stats.is_in_active_window(&files[2].path));
+    }
+
     #[test]
     fn test_is_in_active_window_not_episode() {
         let files = [create_file_info("/mnt/cache/Movie.2021.1080p.mkv")];
@@ -281,4 +547,62 @@ mod tests {
         // Movie files should not be in active window
         assert!(!stats.is_in_active_window(&PathBuf::from("/mnt/cache/Movie.2021.1080p.mkv")));
     }
+
+    #[test]
+    fn test_with_resolved_paths() {
+        let files = [
+            create_file_info("/mnt/cache/tv/The Office (US)/Season 02/02x03.mkv"),
+            create_file_info("/mnt/cache/tv/The Office (US)/Season 02/Office.US.S02E04.mkv"),
+            create_file_info("/mnt/cache/tv/Office.US.S02E04.mkv"),
+        ];
+        let resolved = HashMap::from([(
+            PathBuf::from("/mnt/cache/tv/The Office (US)/Season 02/02x03.mkv"),
+            EpisodeInfo::new("The Office (US)".to_string(), 2, 3),
+        )]);
+
+        let progress = vec![create_progress("The Office (US)", 2, 3)];
+        let stats =
+            TautulliStats::build(files.iter(), progress, 0, 1).with_resolved_paths(resolved);
+
+        // Renamed file is matched through metadata
+        assert!(stats.is_in_active_window(&files[0].path));
+        // Next episode in the show directory takes the show name from metadata
+        assert!(stats.is_in_active_window(&files[1].path));
+        // Files outside the show directory keep their parsed name
+        assert!(!stats.is_in_active_window(&files[2].path));
+    }
+
+    #[test]
+    fn test_movie_watch_matching() {
+        let files = [
+            create_file_info("/mnt/cache/Dune (2021).mkv"),
+            create_file_info("/mnt/cache/Dune.1984.720p.mkv"),
+            create_file_info("/mnt/cache/Heat (1995).mkv"),
+        ];
+        let history = [HistoryItem {
+            user: "alice".to_string(),
+            rating_key: "500".to_string(),
+            media_type: "movie".to_string(),
+            title: "Dune".to_string(),
+            year: 2021,
+            grandparent_title: String::new(),
+            parent_media_index: 0,
+            media_index: 0,
+            percent_complete: 40,
+            stopped: 1000,
+        }];
+
+        let stats =
+            TautulliStats::build(files.iter(), vec![], 1, 1).with_movie_history(&history, 90);
+
+        assert_eq!(stats.movie_map.len(), 3);
+        let watch = stats
+            .movie_watch(Path::new("/mnt/cache/Dune (2021).mkv"))
+            .expect("Should match by title and year");
+        assert!(watch.is_in_progress());
+        assert!(stats.is_movie_watched_since(Path::new("/mnt/cache/Dune (2021).mkv"), 1000));
+        assert!(!stats.is_movie_watched_since(Path::new("/mnt/cache/Dune (2021).mkv"), 1001));
+        assert!(!stats.is_movie_watched_since(Path::new("/mnt/cache/Dune.1984.720p.mkv"), 0));
+        assert!(!stats.is_movie_watched_since(Path::new("/mnt/cache/Heat (1995).mkv"), 0));
+    }
 }
@@ -271,7 +271,7 @@ struct ClientResponse {
     total_count: usize,
 }
 
-fn map_app_path(app_path: &str, mappings: &[PathMappingConfig]) -> Vec<PathBuf> {
+pub(crate) fn map_app_path(app_path: &str, mappings: &[PathMappingConfig]) -> Vec<PathBuf> {
     let app_path = app_path.trim();
     let mut mapped_paths = Vec::new();
 
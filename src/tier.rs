@@ -1,18 +1,65 @@
+use crate::config::{EvictionPolicy, MemberSelection};
 use crate::disk::{DiskOperations, RealDisk};
+use crate::error::AppError;
 use crate::file::{FileInfo, is_internal_artifact_path};
+use crate::remote::{SshDisk, SshRemote};
+use crate::scanner::ScanFilter;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use walkdir::WalkDir;
 
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Clone)]
 pub struct Tier {
     pub name: String,
     pub path: PathBuf,
+    /// Member disks of a tier group, starting with `path`
+    pub paths: Vec<PathBuf>,
+    /// How the member disk receiving a moved file is chosen
+    pub member_selection: MemberSelection,
+    /// Subdirectories of each member that hold tier files; empty scans the whole member
+    pub scan_paths: Vec<PathBuf>,
+    /// Capacity allotted to the tier; usage then counts only files under `scan_paths`
+    pub capacity_override: Option<u64>,
+    /// Trash directory inside each member root, never scanned for tier files
+    pub trash_dir: Option<PathBuf>,
     pub priority: u32,
     pub max_usage_percent: Option<u64>,
     pub min_usage_percent: Option<u64>,
+    /// Moves into this tier are batched to avoid waking sleeping disks
+    pub spindown_aware: bool,
+    /// Defer batches into a spin-down aware tier until they reach this size
+    pub spindown_min_batch_bytes: Option<u64>,
+    /// Which files are evicted first when this tier needs space
+    pub eviction_policy: EvictionPolicy,
+    /// Free space kept on the receiving disk, re-checked before every move into the tier
+    pub safety_margin: u64,
+    /// Bytes left to writers outside tierflow, taken off capacity and free space when planning
+    pub reserve_bytes: u64,
+    /// Like `reserve_bytes`, as a percentage of capacity; the larger reserve wins
+    pub reserve_percent: u64,
+    /// Deepest level below a scan root an entry may sit at (a file directly in it is 1)
+    pub max_depth: Option<usize>,
+    /// Most files the scan roots may hold
+    pub max_files: Option<usize>,
+    /// Hidden and small files left out of planning
+    pub scan_filter: ScanFilter,
+    /// Files are read from but never written to this tier
+    pub read_only: bool,
+    /// Every file on this tier is moved off and nothing is moved onto it
+    pub drain: bool,
+    /// Larger files are never placed on this tier
+    pub max_file_size: Option<u64>,
+    /// Host holding the tier, which is then measured, scanned and written over SSH
+    pub remote: Option<Arc<SshRemote>>,
+    /// Files moved onto this tier leave a stub at their old path
+    pub leave_stubs: bool,
+    /// Scans open small files to skip stubs; set while any tier leaves stubs
+    pub skip_stubs: bool,
     disk_ops: Arc<dyn DiskOperations>,
+    next_member: Arc<AtomicUsize>,
 }
 
 // Manual Debug implementation to avoid requiring DiskOperations: Debug
@@ -21,9 +68,29 @@ impl std::fmt::Debug for Tier {
         f.debug_struct("Tier")
             .field("name", &self.name)
             .field("path", &self.path)
+            .field("paths", &self.paths)
+            .field("member_selection", &self.member_selection)
+            .field("scan_paths", &self.scan_paths)
+            .field("capacity_override", &self.capacity_override)
+            .field("trash_dir", &self.trash_dir)
             .field("priority", &self.priority)
             .field("max_usage_percent", &self.max_usage_percent)
             .field("min_usage_percent", &self.min_usage_percent)
+            .field("spindown_aware", &self.spindown_aware)
+            .field("spindown_min_batch_bytes", &self.spindown_min_batch_bytes)
+            .field("eviction_policy", &self.eviction_policy)
+            .field("safety_margin", &self.safety_margin)
+            .field("reserve_bytes", &self.reserve_bytes)
+            .field("reserve_percent", &self.reserve_percent)
+            .field("max_depth", &self.max_depth)
+            .field("max_files", &self.max_files)
+            .field("scan_filter", &self.scan_filter)
+            .field("read_only", &self.read_only)
+            .field("drain", &self.drain)
+            .field("max_file_size", &self.max_file_size)
+            .field("remote", &self.remote)
+            .field("leave_stubs", &self.leave_stubs)
+            .field("skip_stubs", &self.skip_stubs)
             .finish_non_exhaustive()
     }
 }
@@ -37,24 +104,27 @@ impl Tier {
         max_usage_percent: Option<u64>,
         min_usage_percent: Option<u64>,
     ) -> io::Result<Self> {
-        Self::with_disk_ops(
+        Self::with_disk_ops_internal(
             name,
             path,
             priority,
             max_usage_percent,
             min_usage_percent,
             Arc::new(RealDisk::new()),
+            false, // validate path
         )
     }
 
-    /// Internal constructor that accepts custom `DiskOperations`
-    fn with_disk_ops(
+    /// Create a tier without checking that `path` is a directory
+    ///
+    /// For planning against injected disk operations and scanners, where the
+    /// path only names the tier root.
+    pub fn new_unchecked(
         name: String,
         path: PathBuf,
         priority: u32,
         max_usage_percent: Option<u64>,
         min_usage_percent: Option<u64>,
-        disk_ops: Arc<dyn DiskOperations>,
     ) -> io::Result<Self> {
         Self::with_disk_ops_internal(
             name,
@@ -62,11 +132,17 @@ impl Tier {
             priority,
             max_usage_percent,
             min_usage_percent,
-            disk_ops,
-            false, // validate path
+            Arc::new(RealDisk::new()),
+            true, // skip path validation
         )
     }
 
+    /// Report space through `disk_ops` instead of the real filesystem
+    pub fn with_disk_ops(mut self, disk_ops: Arc<dyn DiskOperations>) -> Self {
+        self.disk_ops = disk_ops;
+        self
+    }
+
     /// Internal constructor with path validation control
     fn with_disk_ops_internal(
         name: String,
@@ -78,18 +154,7 @@ impl Tier {
         skip_path_validation: bool,
     ) -> io::Result<Self> {
         if !skip_path_validation {
-            if !path.exists() {
-                return Err(io::Error::new(
-                    io::ErrorKind::NotFound,
-                    format!("Path does not exist: {}", path.display()),
-                ));
-            }
-            if !path.is_dir() {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    format!("Path is not a directory: {}", path.display()),
-                ));
-            }
+            validate_directory(&path)?;
         }
 
         if let Some(max) = max_usage_percent {
@@ -128,20 +193,328 @@ impl Tier {
 
         Ok(Self {
             name,
+            paths: vec![path.clone()],
+            member_selection: MemberSelection::default(),
+            scan_paths: Vec::new(),
+            capacity_override: None,
+            trash_dir: None,
             path,
             priority,
             max_usage_percent,
             min_usage_percent,
+            spindown_aware: false,
+            spindown_min_batch_bytes: None,
+            eviction_policy: EvictionPolicy::default(),
+            safety_margin: 0,
+            reserve_bytes: 0,
+            reserve_percent: 0,
+            max_depth: None,
+            max_files: None,
+            scan_filter: ScanFilter::default(),
+            read_only: false,
+            drain: false,
+            max_file_size: None,
+            remote: None,
+            leave_stubs: false,
+            skip_stubs: false,
             disk_ops,
+            next_member: Arc::new(AtomicUsize::new(0)),
         })
     }
 
+    /// Mark the tier as spin-down aware, optionally deferring small batches
+    pub fn with_spindown(mut self, min_batch_bytes: Option<u64>) -> Self {
+        self.spindown_aware = true;
+        self.spindown_min_batch_bytes = min_batch_bytes;
+        self
+    }
+
+    /// Set the order in which files are evicted from this tier
+    pub const fn with_eviction_policy(mut self, eviction_policy: EvictionPolicy) -> Self {
+        self.eviction_policy = eviction_policy;
+        self
+    }
+
+    /// Add more member disks, turning the tier into a group
+    ///
+    /// Space is summed across members, so each must be a separate filesystem.
+    pub fn with_members(
+        mut self,
+        paths: impl IntoIterator<Item = PathBuf>,
+        member_selection: MemberSelection,
+    ) -> io::Result<Self> {
+        for path in paths {
+            validate_directory(&path)?;
+            self.paths.push(path);
+        }
+        self.member_selection = member_selection;
+        Ok(self)
+    }
+
+    /// Keep `bytes` free on the receiving disk when moving files into this tier
+    pub const fn with_safety_margin(mut self, bytes: u64) -> Self {
+        self.safety_margin = bytes;
+        self
+    }
+
+    /// Keep space for other writers, e.g. a download client, out of every plan
+    pub const fn with_reserve(mut self, bytes: u64, percent: u64) -> Self {
+        self.reserve_bytes = bytes;
+        self.reserve_percent = percent;
+        self
+    }
+
+    /// Guardrails checked by `check_scan_limits` before planning
+    pub const fn with_scan_limits(
+        mut self,
+        max_depth: Option<usize>,
+        max_files: Option<usize>,
+    ) -> Self {
+        self.max_depth = max_depth;
+        self.max_files = max_files;
+        self
+    }
+
+    /// Leaves hidden or small files on this tier out of planning
+    pub const fn with_scan_filter(mut self, scan_filter: ScanFilter) -> Self {
+        self.scan_filter = scan_filter;
+        self
+    }
+
+    /// Keeps files in place but never moves or copies anything onto this tier
+    pub const fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Moves every file off this tier, for emptying a disk before removing it
+    pub const fn with_drain(mut self, drain: bool) -> Self {
+        self.drain = drain;
+        self
+    }
+
+    /// Places the tier on a remote host; its paths are then paths on that host
+    pub fn with_remote(mut self, remote: SshRemote) -> Self {
+        self.disk_ops = Arc::new(SshDisk::new(remote.clone()));
+        self.remote = Some(Arc::new(remote));
+        self
+    }
+
+    /// Keeps files larger than `max_file_size` bytes off this tier
+    pub const fn with_max_file_size(mut self, max_file_size: Option<u64>) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Leaves a stub in place of every file moved onto this tier, for `tierflow recall`
+    ///
+    /// Stubs land on the source tiers, which need [`Self::with_skip_stubs`].
+    pub const fn with_leave_stubs(mut self, leave_stubs: bool) -> Self {
+        self.leave_stubs = leave_stubs;
+        self
+    }
+
+    /// Checks small files for the stub marker while scanning and leaves stubs out
+    ///
+    /// Off by default, since it opens every file up to `stub::MAX_LEN` bytes.
+    pub const fn with_skip_stubs(mut self, skip_stubs: bool) -> Self {
+        self.skip_stubs = skip_stubs;
+        self
+    }
+
+    /// Location of `path` on this tier as recorded in a stub
+    pub fn stub_location(&self, path: &Path) -> String {
+        match &self.remote {
+            Some(remote) => remote.url(path),
+            None => path.to_string_lossy().into_owned(),
+        }
+    }
+
+    /// Whether the balancer may move or copy files onto this tier
+    pub const fn accepts_files(&self) -> bool {
+        !self.read_only && !self.drain
+    }
+
+    /// Whether a file of `size` bytes is within the tier's `max_file_size`
+    pub fn fits_file_size(&self, size: u64) -> bool {
+        self.max_file_size.is_none_or(|max| size <= max)
+    }
+
+    /// Fails if the scan roots go deeper than `max_depth` or hold more than `max_files` files
+    ///
+    /// Stops at the first violation, so a tier pointing at `/` is caught quickly.
+    pub fn check_scan_limits(&self) -> Result<(), AppError> {
+        if self.max_depth.is_none() && self.max_files.is_none() {
+            return Ok(());
+        }
+        let exceeded = |reason: String| AppError::ScanLimitExceeded {
+            tier: self.name.clone(),
+            reason,
+        };
+
+        let mut files = 0;
+        for root in self.scan_roots().iter().filter(|root| root.is_dir()) {
+            let mut walk = WalkDir::new(root).follow_links(false);
+            if let Some(max_depth) = self.max_depth {
+                walk = walk.max_depth(max_depth + 1);
+            }
+            for entry in walk.into_iter().filter_map(Result::ok) {
+                if let Some(max_depth) = self.max_depth
+                    && entry.depth() > max_depth
+                {
+                    return Err(exceeded(format!(
+                        "{} is deeper than max_depth {max_depth}",
+                        entry.path().display()
+                    )));
+                }
+                if entry.file_type().is_file() {
+                    files += 1;
+                    if let Some(max_files) = self.max_files
+                        && files > max_files
+                    {
+                        return Err(exceeded(format!("more than max_files {max_files} files")));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Only scan these subdirectories (relative to each member root)
+    pub fn with_scan_paths(mut self, scan_paths: Vec<PathBuf>) -> Self {
+        self.scan_paths = scan_paths;
+        self
+    }
+
+    /// Compute usage against `bytes` instead of the whole device
+    pub const fn with_capacity_override(mut self, bytes: u64) -> Self {
+        self.capacity_override = Some(bytes);
+        self
+    }
+
+    /// Skip `dir` (relative to each member root) when scanning, it holds trashed files
+    pub fn with_trash_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.trash_dir = Some(dir.into());
+        self
+    }
+
+    /// Trash directory of each member, if a trash is configured
+    pub fn trash_roots(&self) -> Vec<PathBuf> {
+        self.trash_dir.as_ref().map_or_else(Vec::new, |dir| {
+            self.paths.iter().map(|root| root.join(dir)).collect()
+        })
+    }
+
+    /// Free space; with a capacity override, what is left of the allotment
+    /// (bounded by the space actually free on the device). The reserve is not free.
     pub fn get_free_space(&self) -> u64 {
-        self.disk_ops.get_free_space(&self.path)
+        let device_free = self
+            .paths
+            .iter()
+            .map(|path| self.disk_ops.get_free_space(path))
+            .sum();
+
+        let free = match self.capacity_override {
+            Some(capacity) => capacity
+                .saturating_sub(self.scanned_usage())
+                .min(device_free),
+            None => device_free,
+        };
+        free.saturating_sub(self.reserved_space())
     }
 
+    /// Capacity without the reserve
     pub fn get_total_space(&self) -> u64 {
-        self.disk_ops.get_total_space(&self.path)
+        // 1 avoids division by zero when the reserve takes everything
+        self.raw_total_space()
+            .saturating_sub(self.reserved_space())
+            .max(1)
+    }
+
+    fn raw_total_space(&self) -> u64 {
+        self.capacity_override.unwrap_or_else(|| {
+            self.paths
+                .iter()
+                .map(|path| self.disk_ops.get_total_space(path))
+                .sum()
+        })
+    }
+
+    /// Bytes kept for writers outside tierflow: the larger of `reserve_bytes`
+    /// and `reserve_percent` of the capacity
+    pub fn reserved_space(&self) -> u64 {
+        if self.reserve_percent == 0 {
+            return self.reserve_bytes;
+        }
+        let percent =
+            (u128::from(self.raw_total_space()) * u128::from(self.reserve_percent) / 100) as u64;
+        self.reserve_bytes.max(percent)
+    }
+
+    /// Directories walked for tier files: each member root joined with each scan path
+    pub fn scan_roots(&self) -> Vec<PathBuf> {
+        if self.scan_paths.is_empty() {
+            return self.paths.clone();
+        }
+
+        self.paths
+            .iter()
+            .flat_map(|root| self.scan_paths.iter().map(move |scan| root.join(scan)))
+            .collect()
+    }
+
+    /// Bytes taken by files under the scan roots
+    fn scanned_usage(&self) -> u64 {
+        self.walk_scan_roots()
+            .filter_map(|e| e.metadata().ok())
+            .map(|metadata| metadata.len())
+            .sum()
+    }
+
+    /// Member root the file lives under
+    pub fn root_of(&self, file: &Path) -> Option<&Path> {
+        self.paths
+            .iter()
+            .find(|root| file.starts_with(root))
+            .map(PathBuf::as_path)
+    }
+
+    /// File path relative to its member root
+    pub fn relative_path<'p>(&self, file: &'p Path) -> Option<&'p Path> {
+        self.root_of(file)
+            .and_then(|root| file.strip_prefix(root).ok())
+    }
+
+    /// Member root that should receive a file of `size` bytes
+    ///
+    /// Only members with room for the file are considered; falls back to `path`.
+    pub fn placement_root(&self, size: u64) -> &Path {
+        if self.paths.len() == 1 {
+            return &self.path;
+        }
+
+        let fits: Vec<&PathBuf> = self
+            .paths
+            .iter()
+            .filter(|path| self.disk_ops.get_free_space(path) >= size)
+            .collect();
+
+        let chosen = match self.member_selection {
+            MemberSelection::MostFreeSpace => fits
+                .into_iter()
+                .max_by_key(|path| self.disk_ops.get_free_space(path)),
+            MemberSelection::RoundRobin if !fits.is_empty() => {
+                let turn = self.next_member.fetch_add(1, Ordering::Relaxed);
+                Some(fits[turn % fits.len()])
+            }
+            MemberSelection::RoundRobin => None,
+        };
+        chosen.unwrap_or(&self.path)
+    }
+
+    /// Space currently free on the device holding member `root`
+    pub fn free_space_at(&self, root: &Path) -> u64 {
+        self.disk_ops.get_free_space(root)
     }
 
     pub fn usage_percent(&self) -> u64 {
@@ -183,22 +556,60 @@ impl Tier {
     }
 
     pub fn get_all_files(&self) -> Vec<FileInfo> {
-        WalkDir::new(&self.path)
-            .follow_links(false)
-            .into_iter()
-            .filter_map(|e| match e {
-                Ok(entry) => Some(entry),
-                Err(err) => {
-                    // Log the error but continue processing other files
-                    tracing::warn!(
-                        "Failed to read directory entry in tier '{}': {}",
-                        self.name,
-                        err
-                    );
-                    None
-                }
-            })
-            .filter(|e| e.file_type().is_file())
+        if self.remote.is_some() {
+            return self.remote_files(&self.scan_roots());
+        }
+        self.collect_files(self.walk_scan_roots())
+    }
+
+    /// Files listed over SSH under `roots`, none if the host can't be reached
+    fn remote_files(&self, roots: &[PathBuf]) -> Vec<FileInfo> {
+        let Some(remote) = &self.remote else {
+            return Vec::new();
+        };
+        let trash_roots = self.trash_roots();
+        match remote.list_files(roots) {
+            Ok(files) => files
+                .into_iter()
+                .filter(|f| !trash_roots.iter().any(|trash| f.path.starts_with(trash)))
+                .filter(|f| !is_internal_artifact_path(&f.path))
+                .collect(),
+            Err(e) => {
+                tracing::warn!("Failed to scan remote tier '{}': {e}", self.name);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Files under the scan roots that are also under one of `dirs`, given
+    /// relative to the member roots
+    pub fn get_files_under(&self, dirs: &[PathBuf]) -> Vec<FileInfo> {
+        let mut dirs = dirs.to_vec();
+        dirs.sort();
+        // Nested directories would be walked twice
+        dirs.dedup_by(|dir, parent| dir.starts_with(parent));
+
+        let scan_roots = self.scan_roots();
+        let walk_roots: Vec<_> = self
+            .paths
+            .iter()
+            .flat_map(|root| dirs.iter().map(move |dir| root.join(dir)))
+            .collect();
+        if self.remote.is_some() {
+            let mut files = self.remote_files(&walk_roots);
+            files.retain(|f| scan_roots.iter().any(|root| f.path.starts_with(root)));
+            return files;
+        }
+        let entries = self
+            .walk(walk_roots)
+            .filter(|e| scan_roots.iter().any(|root| e.path().starts_with(root)));
+        self.collect_files(entries)
+    }
+
+    fn collect_files(&self, entries: impl Iterator<Item = walkdir::DirEntry>) -> Vec<FileInfo> {
+        let trash_roots = self.trash_roots();
+        entries
+            .filter(|e| !trash_roots.iter().any(|trash| e.path().starts_with(trash)))
             .filter(|e| {
                 if is_internal_artifact_path(e.path()) {
                     tracing::debug!(
@@ -214,6 +625,14 @@ impl Tier {
             .filter_map(|e| {
                 let path = e.path().to_path_buf();
                 match FileInfo::from_path(path.clone()) {
+                    Ok(info) if self.skip_stubs && crate::stub::is_stub(&path, info.size) => {
+                        tracing::debug!(
+                            "Skipping stub in tier '{}': {}",
+                            self.name,
+                            path.display()
+                        );
+                        None
+                    }
                     Ok(info) => Some(info),
                     Err(err) => {
                         tracing::warn!(
@@ -228,6 +647,47 @@ impl Tier {
             })
             .collect()
     }
+
+    /// Regular files under the scan roots; scan roots missing on a member are skipped
+    pub(crate) fn walk_scan_roots(&self) -> impl Iterator<Item = walkdir::DirEntry> + '_ {
+        self.walk(self.scan_roots())
+    }
+
+    fn walk(&self, roots: Vec<PathBuf>) -> impl Iterator<Item = walkdir::DirEntry> + '_ {
+        roots
+            .into_iter()
+            .filter(|root| root.is_dir())
+            .flat_map(|root| WalkDir::new(root).follow_links(false))
+            .filter_map(|e| match e {
+                Ok(entry) => Some(entry),
+                Err(err) => {
+                    // Log the error but continue processing other files
+                    tracing::warn!(
+                        "Failed to read directory entry in tier '{}': {}",
+                        self.name,
+                        err
+                    );
+                    None
+                }
+            })
+            .filter(|e| e.file_type().is_file())
+    }
+}
+
+fn validate_directory(path: &Path) -> io::Result<()> {
+    if !path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Path does not exist: {}", path.display()),
+        ));
+    }
+    if !path.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Path is not a directory: {}", path.display()),
+        ));
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -433,6 +893,183 @@ mod tests {
         fs::remove_dir_all(temp_dir).ok();
     }
 
+    #[test]
+    fn test_tier_group_spans_members() {
+        let temp = tempfile::tempdir().unwrap();
+        let (disk1, disk2) = (temp.path().join("disk1"), temp.path().join("disk2"));
+        fs::create_dir_all(disk1.join("Show")).unwrap();
+        fs::create_dir_all(&disk2).unwrap();
+        fs::write(disk1.join("Show/e1.mkv"), b"content").unwrap();
+        fs::write(disk2.join("movie.mkv"), b"content").unwrap();
+
+        let tier = Tier::new("storage".to_string(), disk1.clone(), 10, None, None)
+            .unwrap()
+            .with_members([disk2.clone()], MemberSelection::RoundRobin)
+            .unwrap();
+
+        assert_eq!(tier.get_all_files().len(), 2);
+        assert_eq!(
+            tier.relative_path(&disk2.join("movie.mkv")),
+            Some(Path::new("movie.mkv"))
+        );
+        assert_eq!(
+            tier.root_of(&disk1.join("Show/e1.mkv")),
+            Some(disk1.as_path())
+        );
+        assert_eq!(tier.root_of(Path::new("/elsewhere/file.mkv")), None);
+
+        // Round-robin alternates between members
+        assert_eq!(tier.placement_root(1), disk1);
+        assert_eq!(tier.placement_root(1), disk2);
+        assert_eq!(tier.placement_root(1), disk1);
+    }
+
+    #[test]
+    fn test_tier_scan_paths_and_capacity_override() {
+        let temp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(temp.path().join("media")).unwrap();
+        fs::create_dir_all(temp.path().join("appdata")).unwrap();
+        fs::write(temp.path().join("media/movie.mkv"), vec![0u8; 600]).unwrap();
+        fs::write(temp.path().join("appdata/db.sqlite"), vec![0u8; 4000]).unwrap();
+
+        let tier = Tier::new(
+            "cache".to_string(),
+            temp.path().to_path_buf(),
+            1,
+            None,
+            None,
+        )
+        .unwrap()
+        .with_scan_paths(vec![PathBuf::from("media"), PathBuf::from("missing")])
+        .with_capacity_override(1000);
+
+        let files = tier.get_all_files();
+        assert_eq!(files.len(), 1);
+        assert_eq!(
+            tier.relative_path(&files[0].path),
+            Some(Path::new("media/movie.mkv"))
+        );
+
+        // Appdata doesn't count toward the allotted capacity
+        assert_eq!(tier.get_total_space(), 1000);
+        assert_eq!(tier.get_free_space(), 400);
+        assert_eq!(tier.usage_percent(), 60);
+    }
+
+    #[test]
+    fn test_tier_get_files_under() {
+        let temp = tempfile::tempdir().unwrap();
+        for file in [
+            "media/Show/s01/e1.mkv",
+            "media/Show/e2.mkv",
+            "media/Other/e3.mkv",
+        ] {
+            let path = temp.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, b"data").unwrap();
+        }
+        fs::create_dir_all(temp.path().join("appdata")).unwrap();
+        fs::write(temp.path().join("appdata/db.sqlite"), b"data").unwrap();
+
+        let tier = Tier::new(
+            "cache".to_string(),
+            temp.path().to_path_buf(),
+            1,
+            None,
+            None,
+        )
+        .unwrap()
+        .with_scan_paths(vec![PathBuf::from("media")]);
+
+        let mut files: Vec<_> = tier
+            .get_files_under(&[
+                PathBuf::from("media/Show"),
+                PathBuf::from("media/Show/s01"),
+                PathBuf::from("appdata"),
+                PathBuf::from("missing"),
+            ])
+            .into_iter()
+            .map(|f| f.path)
+            .collect();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                temp.path().join("media/Show/e2.mkv"),
+                temp.path().join("media/Show/s01/e1.mkv"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tier_check_scan_limits() {
+        let temp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(temp.path().join("Show/Season 1")).unwrap();
+        fs::write(temp.path().join("Show/Season 1/e1.mkv"), b"data").unwrap();
+        fs::write(temp.path().join("Show/Season 1/e2.mkv"), b"data").unwrap();
+        let tier = Tier::new(
+            "cache".to_string(),
+            temp.path().to_path_buf(),
+            1,
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert!(tier.check_scan_limits().is_ok());
+        assert!(
+            tier.clone()
+                .with_scan_limits(Some(3), Some(2))
+                .check_scan_limits()
+                .is_ok()
+        );
+
+        let err = tier
+            .clone()
+            .with_scan_limits(Some(2), None)
+            .check_scan_limits()
+            .unwrap_err();
+        assert!(err.to_string().contains("deeper than max_depth 2"));
+        let err = tier
+            .with_scan_limits(None, Some(1))
+            .check_scan_limits()
+            .unwrap_err();
+        assert!(matches!(err, AppError::ScanLimitExceeded { tier, .. } if tier == "cache"));
+    }
+
+    #[test]
+    fn test_tier_skips_trash_dir() {
+        let temp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(temp.path().join(".trash/1700000000")).unwrap();
+        fs::write(temp.path().join(".trash/1700000000/old.mkv"), b"old").unwrap();
+        fs::write(temp.path().join("movie.mkv"), b"movie").unwrap();
+
+        let tier = Tier::new(
+            "cache".to_string(),
+            temp.path().to_path_buf(),
+            1,
+            None,
+            None,
+        )
+        .unwrap()
+        .with_trash_dir(".trash");
+
+        let files = tier.get_all_files();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, temp.path().join("movie.mkv"));
+        assert_eq!(tier.trash_roots(), vec![temp.path().join(".trash")]);
+    }
+
+    #[test]
+    fn test_tier_group_rejects_missing_member() {
+        let tier = Tier::new("storage".to_string(), env::temp_dir(), 10, None, None).unwrap();
+        let result = tier.with_members(
+            [PathBuf::from("/nonexistent/disk2")],
+            MemberSelection::MostFreeSpace,
+        );
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_tier_clone() {
         let temp_dir = env::temp_dir();
@@ -471,6 +1108,22 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_tier_reserve_is_taken_off_capacity() {
+        let tier = Tier::new_mock("cache", 1, Some(80), 1000 * GB, 300 * GB);
+        let reserved = tier.clone().with_reserve(100 * GB, 0);
+        assert_eq!(reserved.get_total_space(), 900 * GB);
+        assert_eq!(reserved.get_free_space(), 200 * GB);
+        // 700 GB used of 900 GB is over 77%, so 30 GB more would pass 80%
+        assert!(tier.has_space_for(30 * GB));
+        assert!(!reserved.has_space_for(30 * GB));
+
+        // The larger reserve wins
+        assert_eq!(tier.clone().with_reserve(GB, 20).reserved_space(), 200 * GB);
+        let full = tier.with_reserve(0, 100);
+        assert_eq!((full.get_total_space(), full.get_free_space()), (1, 0));
+    }
+
     #[test]
     fn test_tier_has_space_for_with_max_usage() {
         let temp_dir = env::temp_dir();
@@ -0,0 +1,89 @@
+//! UTC timestamp helpers for external APIs (ISO 8601 ⇄ Unix seconds)
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECS_PER_DAY: i64 = 86_400;
+
+/// Current Unix time in seconds
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Parses a UTC timestamp like `2024-01-15T20:31:12.0000000Z` into Unix seconds
+pub fn parse_utc(value: &str) -> Option<u64> {
+    let value = value.get(..19)?;
+    let field = |range: std::ops::Range<usize>| value.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (field(0..4)?, field(5..7)?, field(8..10)?);
+    let (hour, minute, second) = (field(11..13)?, field(14..16)?, field(17..19)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day);
+    u64::try_from(days * SECS_PER_DAY + hour * 3600 + minute * 60 + second).ok()
+}
+
+/// Formats Unix seconds as `YYYY-MM-DDTHH:MM:SSZ`
+pub fn format_utc(secs: u64) -> String {
+    let secs = i64::try_from(secs).unwrap_or(i64::MAX);
+    let (year, month, day) = civil_from_days(secs.div_euclid(SECS_PER_DAY));
+    let time = secs.rem_euclid(SECS_PER_DAY);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+// Proleptic Gregorian calendar conversions (Howard Hinnant's algorithms)
+
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_utc() {
+        assert_eq!(parse_utc("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(
+            parse_utc("2024-01-15T20:31:12.0000000Z"),
+            Some(1_705_350_672)
+        );
+        assert_eq!(parse_utc("2024-02-29T00:00:00Z"), Some(1_709_164_800));
+        assert_eq!(parse_utc("not a date"), None);
+    }
+
+    #[test]
+    fn test_format_utc_round_trip() {
+        assert_eq!(format_utc(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_utc(1_705_350_672), "2024-01-15T20:31:12Z");
+        for secs in [1_709_164_800, 951_782_400, 4_102_444_799] {
+            assert_eq!(parse_utc(&format_utc(secs)), Some(secs));
+        }
+    }
+}
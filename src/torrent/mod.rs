@@ -0,0 +1,42 @@
+//! Torrent clients, so files still seeding can be kept on fast storage
+
+mod qbittorrent;
+mod stats;
+mod transmission;
+
+pub use qbittorrent::QbittorrentClient;
+pub use stats::{TorrentFile, TorrentStats};
+pub use transmission::TransmissionClient;
+
+use crate::error::Result;
+
+/// A torrent and the absolute paths of its files, as the client sees them
+#[derive(Debug, Clone, PartialEq)]
+pub struct Torrent {
+    pub name: String,
+    /// Completed and uploading or queued to upload
+    pub seeding: bool,
+    /// Uploaded divided by downloaded
+    pub ratio: f64,
+    pub files: Vec<String>,
+}
+
+/// Source of the torrent list for `torrent_*` conditions
+pub trait TorrentClient: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Verify the client is reachable and the credentials are valid
+    fn health_check(&self) -> Result<()>;
+
+    /// Every torrent with its files
+    fn torrents(&self) -> Result<Vec<Torrent>>;
+}
+
+/// `save_path` joined with a file name relative to it
+fn join_client_path(dir: &str, name: &str) -> String {
+    format!(
+        "{}/{}",
+        dir.trim_end_matches('/'),
+        name.trim_start_matches('/')
+    )
+}
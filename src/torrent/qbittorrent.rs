@@ -0,0 +1,199 @@
+use super::{Torrent, TorrentClient, join_client_path};
+use crate::error::{AppError, Result};
+use reqwest::blocking::{Client, RequestBuilder};
+use reqwest::header::{COOKIE, REFERER, SET_COOKIE};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+/// States of a complete torrent that is uploading or waiting to
+const SEEDING_STATES: &[&str] = &[
+    "uploading",
+    "stalledUP",
+    "forcedUP",
+    "queuedUP",
+    "checkingUP",
+];
+
+/// qBittorrent Web API v2 client
+pub struct QbittorrentClient {
+    base_url: String,
+    credentials: Option<(String, String)>,
+    client: Client,
+}
+
+impl QbittorrentClient {
+    pub fn new(
+        base_url: String,
+        username: Option<String>,
+        password: Option<String>,
+    ) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| AppError::Io(std::io::Error::other(e)))?;
+
+        // Normalize base_url: ensure it ends with /
+        let base_url = if base_url.ends_with('/') {
+            base_url
+        } else {
+            format!("{base_url}/")
+        };
+
+        Ok(Self {
+            base_url,
+            credentials: username.map(|user| (user, password.unwrap_or_default())),
+            client,
+        })
+    }
+
+    /// Session cookie, or `None` when no credentials are configured
+    fn login(&self) -> Result<Option<String>> {
+        let Some((username, password)) = &self.credentials else {
+            return Ok(None);
+        };
+        let response = self
+            .client
+            .post(format!("{}api/v2/auth/login", self.base_url))
+            // Requests without a matching Referer are rejected as cross-site
+            .header(REFERER, &self.base_url)
+            .form(&[("username", username), ("password", password)])
+            .send()
+            .map_err(|e| self.connect_error(&e))?;
+
+        let cookie = response
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .find_map(|value| value.split(';').next().filter(|c| c.starts_with("SID=")))
+            .map(str::to_string);
+        let body = response.text().unwrap_or_default();
+        match cookie {
+            Some(cookie) if body.trim() != "Fails." => Ok(Some(cookie)),
+            _ => Err(AppError::External(
+                "qBittorrent login failed: check username and password".to_string(),
+            )),
+        }
+    }
+
+    fn get<T: DeserializeOwned>(&self, path: &str, session: Option<&str>) -> Result<T> {
+        let mut request: RequestBuilder = self
+            .client
+            .get(format!("{}api/v2/{path}", self.base_url))
+            .header(REFERER, &self.base_url);
+        if let Some(session) = session {
+            request = request.header(COOKIE, session);
+        }
+        let response = request.send().map_err(|e| self.connect_error(&e))?;
+
+        if !response.status().is_success() {
+            return Err(AppError::External(format!(
+                "qBittorrent API returned error status: {}",
+                response.status()
+            )));
+        }
+
+        response
+            .json()
+            .map_err(|e| AppError::External(format!("Failed to parse qBittorrent response: {e}")))
+    }
+
+    fn connect_error(&self, e: &reqwest::Error) -> AppError {
+        AppError::External(format!(
+            "Failed to connect to qBittorrent at {}: {e}",
+            self.base_url
+        ))
+    }
+}
+
+impl TorrentClient for QbittorrentClient {
+    fn name(&self) -> &'static str {
+        "qBittorrent"
+    }
+
+    fn health_check(&self) -> Result<()> {
+        tracing::info!("Performing qBittorrent health check: {}", self.base_url);
+
+        let session = self.login()?;
+        let version: String = self
+            .client
+            .get(format!("{}api/v2/app/version", self.base_url))
+            .header(REFERER, &self.base_url)
+            .header(COOKIE, session.unwrap_or_default())
+            .send()
+            .and_then(reqwest::blocking::Response::error_for_status)
+            .and_then(reqwest::blocking::Response::text)
+            .map_err(|e| self.connect_error(&e))?;
+        tracing::info!("qBittorrent health check passed (version {version})");
+        Ok(())
+    }
+
+    fn torrents(&self) -> Result<Vec<Torrent>> {
+        let session = self.login()?;
+        let infos: Vec<TorrentInfo> = self.get("torrents/info", session.as_deref())?;
+
+        let mut torrents = Vec::with_capacity(infos.len());
+        for info in infos {
+            let files: Vec<TorrentContent> = self.get(
+                &format!("torrents/files?hash={}", info.hash),
+                session.as_deref(),
+            )?;
+            torrents.push(Torrent {
+                seeding: SEEDING_STATES.contains(&info.state.as_str()),
+                ratio: info.ratio,
+                files: files
+                    .iter()
+                    .map(|file| join_client_path(&info.save_path, &file.name))
+                    .collect(),
+                name: info.name,
+            });
+        }
+        tracing::debug!("Fetched {} torrents from qBittorrent", torrents.len());
+        Ok(torrents)
+    }
+}
+
+// API Response structures
+
+#[derive(Debug, Deserialize)]
+struct TorrentInfo {
+    hash: String,
+    name: String,
+    state: String,
+    #[serde(default)]
+    ratio: f64,
+    save_path: String,
+}
+
+/// File of a torrent, `name` relative to the torrent's `save_path`
+#[derive(Debug, Deserialize)]
+struct TorrentContent {
+    name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_torrent_info() {
+        let json = r#"[{
+            "hash": "8c212779b4abde7c6bc608063a0d008b7e40ce32",
+            "name": "Show.S01",
+            "state": "stalledUP",
+            "ratio": 1.25,
+            "save_path": "/downloads/tv/",
+            "content_path": "/downloads/tv/Show.S01"
+        }]"#;
+
+        let infos: Vec<TorrentInfo> = serde_json::from_str(json).expect("Should deserialize");
+        assert_eq!(infos[0].name, "Show.S01");
+        assert!(SEEDING_STATES.contains(&infos[0].state.as_str()));
+        assert!((infos[0].ratio - 1.25).abs() < f64::EPSILON);
+        assert_eq!(
+            join_client_path(&infos[0].save_path, "Show.S01/e01.mkv"),
+            "/downloads/tv/Show.S01/e01.mkv"
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use super::Torrent;
+use crate::config::PathMappingConfig;
+use crate::tdarr::map_app_path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Torrent state of one file; a file shared by several torrents is merged
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TorrentFile {
+    /// Some torrent with the file is seeding
+    pub seeding: bool,
+    /// Lowest ratio of the torrents with the file
+    pub ratio: f64,
+}
+
+/// Torrent client state for file placement decisions, keyed by host path
+#[derive(Debug, Clone, Default)]
+pub struct TorrentStats {
+    files: HashMap<PathBuf, TorrentFile>,
+    torrents: usize,
+}
+
+impl TorrentStats {
+    /// Build `TorrentStats`, mapping client paths to host paths with `path_mappings`
+    pub fn build(torrents: &[Torrent], path_mappings: &[PathMappingConfig]) -> Self {
+        let mut files: HashMap<PathBuf, TorrentFile> = HashMap::new();
+        for torrent in torrents {
+            for path in torrent
+                .files
+                .iter()
+                .flat_map(|file| map_app_path(file, path_mappings))
+            {
+                files
+                    .entry(path)
+                    .and_modify(|file| {
+                        file.seeding |= torrent.seeding;
+                        file.ratio = file.ratio.min(torrent.ratio);
+                    })
+                    .or_insert(TorrentFile {
+                        seeding: torrent.seeding,
+                        ratio: torrent.ratio,
+                    });
+            }
+        }
+        Self {
+            files,
+            torrents: torrents.len(),
+        }
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&TorrentFile> {
+        self.files.get(path)
+    }
+
+    /// Number of files in a seeding torrent
+    pub fn seeding_files(&self) -> usize {
+        self.files.values().filter(|file| file.seeding).count()
+    }
+
+    pub const fn torrent_count(&self) -> usize {
+        self.torrents
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn torrent(seeding: bool, ratio: f64, files: &[&str]) -> Torrent {
+        Torrent {
+            name: "t".to_string(),
+            seeding,
+            ratio,
+            files: files.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn test_build_maps_paths_and_merges_cross_seeds() {
+        let mappings = vec![PathMappingConfig {
+            host_prefix: PathBuf::from("/mnt/cache/torrents"),
+            app_prefix: "/downloads".to_string(),
+        }];
+        let stats = TorrentStats::build(
+            &[
+                torrent(false, 3.0, &["/downloads/Movie/Movie.mkv"]),
+                torrent(
+                    true,
+                    0.5,
+                    &["/downloads/Movie/Movie.mkv", "/downloads/Movie/Movie.nfo"],
+                ),
+                torrent(false, 1.0, &["/elsewhere/Show.mkv"]),
+            ],
+            &mappings,
+        );
+
+        let movie = stats
+            .get(Path::new("/mnt/cache/torrents/Movie/Movie.mkv"))
+            .unwrap();
+        assert!(movie.seeding);
+        assert!((movie.ratio - 0.5).abs() < f64::EPSILON);
+        // Unmapped paths are taken as host paths
+        assert!(!stats.get(Path::new("/elsewhere/Show.mkv")).unwrap().seeding);
+        assert!(stats.get(Path::new("/downloads/Movie/Movie.mkv")).is_none());
+        assert_eq!(stats.seeding_files(), 2);
+        assert_eq!(stats.torrent_count(), 3);
+    }
+}
@@ -0,0 +1,222 @@
+use super::{Torrent, TorrentClient, join_client_path};
+use crate::error::{AppError, Result};
+use reqwest::StatusCode;
+use reqwest::blocking::Client;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::sync::Mutex;
+use std::time::Duration;
+
+const SESSION_HEADER: &str = "X-Transmission-Session-Id";
+
+/// `status` values of a torrent waiting to seed or seeding
+const SEEDING_STATUSES: &[u8] = &[5, 6];
+
+/// Transmission RPC client
+pub struct TransmissionClient {
+    rpc_url: String,
+    credentials: Option<(String, String)>,
+    /// CSRF token handed out with the first 409 response
+    session_id: Mutex<Option<String>>,
+    client: Client,
+}
+
+impl TransmissionClient {
+    pub fn new(base_url: &str, username: Option<String>, password: Option<String>) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| AppError::Io(std::io::Error::other(e)))?;
+
+        let base_url = base_url.trim_end_matches('/');
+        let rpc_url = if base_url.ends_with("/rpc") {
+            base_url.to_string()
+        } else {
+            format!("{base_url}/transmission/rpc")
+        };
+
+        Ok(Self {
+            rpc_url,
+            credentials: username.map(|user| (user, password.unwrap_or_default())),
+            session_id: Mutex::new(None),
+            client,
+        })
+    }
+
+    fn rpc<T: DeserializeOwned>(&self, method: &str, arguments: &Value) -> Result<T> {
+        let body = json!({ "method": method, "arguments": arguments });
+        // The first request only fetches the session id
+        for _ in 0..2 {
+            let mut request = self.client.post(&self.rpc_url).json(&body);
+            if let Some((username, password)) = &self.credentials {
+                request = request.basic_auth(username, Some(password));
+            }
+            let session_id = self.session_id.lock().ok().and_then(|id| id.clone());
+            if let Some(session_id) = session_id {
+                request = request.header(SESSION_HEADER, session_id);
+            }
+            let response = request.send().map_err(|e| {
+                AppError::External(format!(
+                    "Failed to connect to Transmission at {}: {e}",
+                    self.rpc_url
+                ))
+            })?;
+
+            if response.status() == StatusCode::CONFLICT {
+                let session_id = response
+                    .headers()
+                    .get(SESSION_HEADER)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string);
+                if let Ok(mut current) = self.session_id.lock() {
+                    *current = session_id;
+                }
+                continue;
+            }
+            if !response.status().is_success() {
+                return Err(AppError::External(format!(
+                    "Transmission RPC returned error status: {}",
+                    response.status()
+                )));
+            }
+
+            let reply: RpcResponse<T> = response.json().map_err(|e| {
+                AppError::External(format!("Failed to parse Transmission response: {e}"))
+            })?;
+            return match reply.arguments {
+                Some(arguments) if reply.result == "success" => Ok(arguments),
+                _ => Err(AppError::External(format!(
+                    "Transmission RPC {method} failed: {}",
+                    reply.result
+                ))),
+            };
+        }
+        Err(AppError::External(
+            "Transmission kept rejecting the session id".to_string(),
+        ))
+    }
+}
+
+impl TorrentClient for TransmissionClient {
+    fn name(&self) -> &'static str {
+        "Transmission"
+    }
+
+    fn health_check(&self) -> Result<()> {
+        tracing::info!("Performing Transmission health check: {}", self.rpc_url);
+
+        let session: Session = self.rpc("session-get", &json!({ "fields": ["version"] }))?;
+        tracing::info!(
+            "Transmission health check passed (version {})",
+            session.version
+        );
+        Ok(())
+    }
+
+    fn torrents(&self) -> Result<Vec<Torrent>> {
+        let reply: TorrentList = self.rpc(
+            "torrent-get",
+            &json!({ "fields": ["name", "status", "uploadRatio", "downloadDir", "files"] }),
+        )?;
+        let torrents: Vec<Torrent> = reply
+            .torrents
+            .into_iter()
+            .map(TransmissionTorrent::into_torrent)
+            .collect();
+        tracing::debug!("Fetched {} torrents from Transmission", torrents.len());
+        Ok(torrents)
+    }
+}
+
+// API Response structures
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: String,
+    arguments: Option<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Session {
+    version: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TorrentList {
+    torrents: Vec<TransmissionTorrent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TransmissionTorrent {
+    name: String,
+    status: u8,
+    upload_ratio: f64,
+    download_dir: String,
+    #[serde(default)]
+    files: Vec<TransmissionFile>,
+}
+
+impl TransmissionTorrent {
+    fn into_torrent(self) -> Torrent {
+        // -1 means nothing downloaded yet, -2 an infinite ratio
+        let ratio = match self.upload_ratio {
+            r if r <= -2.0 => f64::INFINITY,
+            r if r < 0.0 => 0.0,
+            r => r,
+        };
+        Torrent {
+            seeding: SEEDING_STATUSES.contains(&self.status),
+            ratio,
+            files: self
+                .files
+                .iter()
+                .map(|file| join_client_path(&self.download_dir, &file.name))
+                .collect(),
+            name: self.name,
+        }
+    }
+}
+
+/// File of a torrent, `name` relative to the torrent's `downloadDir`
+#[derive(Debug, Deserialize)]
+struct TransmissionFile {
+    name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_torrent_get() {
+        let json = r#"{
+            "result": "success",
+            "arguments": {"torrents": [{
+                "name": "Movie (2020)",
+                "status": 6,
+                "uploadRatio": -2,
+                "downloadDir": "/downloads/movies",
+                "files": [{"name": "Movie (2020)/Movie.mkv", "length": 100, "bytesCompleted": 100}]
+            }]}
+        }"#;
+
+        let reply: RpcResponse<TorrentList> =
+            serde_json::from_str(json).expect("Should deserialize");
+        let torrent = reply.arguments.unwrap().torrents.remove(0).into_torrent();
+        assert!(torrent.seeding);
+        assert!(torrent.ratio.is_infinite());
+        assert_eq!(
+            torrent.files,
+            vec!["/downloads/movies/Movie (2020)/Movie.mkv".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_rpc_url() {
+        let client = TransmissionClient::new("http://localhost:9091/", None, None)
+            .expect("Should create client");
+        assert_eq!(client.rpc_url, "http://localhost:9091/transmission/rpc");
+    }
+}
@@ -0,0 +1,499 @@
+//! Per-tier trash for moved sources, and undo of past runs
+//!
+//! With `safety.trash_dir` set, the mover renames each source into
+//! `<tier root>/<trash_dir>/<run id>/<relative path>` instead of deleting it.
+//! Every run that trashed files leaves a record in `<state_dir>/runs/<run id>.json`
+//! mapping trashed sources to their new location, which `tierflow undo` replays backwards.
+
+use crate::error::Result;
+use crate::{ExecutionResult, Tier};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const RUNS_DIR: &str = "runs";
+
+/// Runs started by this process, so two in the same second get distinct ids
+static RUN_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Identifier for a new run: its start time in Unix seconds, then the process
+/// id and a sequence number, so runs starting in the same second never share
+/// a trash directory or record
+pub fn new_run_id(now: SystemTime) -> String {
+    format!(
+        "{}-{}-{}",
+        now.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+        std::process::id(),
+        RUN_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Start time of a run, parsed back from its identifier
+fn run_started_at(run_id: &str) -> Option<SystemTime> {
+    if !run_id.bytes().all(|b| b.is_ascii_digit() || b == b'-') {
+        return None;
+    }
+    let secs = run_id.split('-').next()?.parse().ok()?;
+    Some(UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+/// Trash area of one run across all tier members
+#[derive(Debug, Clone)]
+pub struct Trash {
+    run_id: String,
+    /// (member root, trash root of that member)
+    roots: Vec<(PathBuf, PathBuf)>,
+}
+
+impl Trash {
+    /// Trash for `run_id` in every tier with a trash directory
+    pub fn new(run_id: impl Into<String>, tiers: &[Tier]) -> Self {
+        let roots = tiers
+            .iter()
+            .flat_map(|tier| tier.paths.iter().cloned().zip(tier.trash_roots()))
+            .collect();
+        Self {
+            run_id: run_id.into(),
+            roots,
+        }
+    }
+
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    /// Trash location for `source`, on the same member so trashing is a rename
+    pub fn path_for(&self, source: &Path) -> Option<PathBuf> {
+        let (root, trash_root) = self
+            .roots
+            .iter()
+            .filter(|(root, _)| source.starts_with(root))
+            .max_by_key(|(root, _)| root.components().count())?;
+        let relative = source.strip_prefix(root).ok()?;
+        Some(trash_root.join(&self.run_id).join(relative))
+    }
+
+    /// Moves `source` into the trash; sources outside every tier are deleted
+    pub fn discard(&self, source: &Path) -> io::Result<()> {
+        let Some(trash_path) = self.path_for(source) else {
+            tracing::warn!(
+                "{} is not under a tier with a trash, deleting it",
+                source.display()
+            );
+            return fs::remove_file(source);
+        };
+
+        if let Some(parent) = trash_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(source, &trash_path)?;
+        tracing::debug!(
+            "Moved source to trash: {} -> {}",
+            source.display(),
+            trash_path.display()
+        );
+        Ok(())
+    }
+}
+
+/// A moved file whose source was kept in the trash
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrashedMove {
+    /// Original location of the file
+    pub source: PathBuf,
+    /// Where the file was moved to
+    pub destination: PathBuf,
+    /// Trashed copy of the source
+    pub trash: PathBuf,
+    /// Size of `destination` when the run finished
+    #[serde(default)]
+    pub destination_size: Option<u64>,
+    /// Modification time of `destination` when the run finished
+    #[serde(default)]
+    pub destination_modified: Option<SystemTime>,
+}
+
+/// Moves of one run that can be undone
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub run_id: String,
+    pub moves: Vec<TrashedMove>,
+}
+
+/// Outcome of `tierflow undo`
+#[derive(Debug, Default)]
+pub struct UndoResult {
+    /// Original paths restored from the trash
+    pub restored: Vec<PathBuf>,
+    /// Original paths that could not be restored, with the reason
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+impl RunRecord {
+    /// Moves of `result` whose source landed in `trash`
+    pub fn from_execution(trash: &Trash, result: &ExecutionResult) -> Self {
+        let moves = result
+            .moved
+            .iter()
+            .filter_map(|moved| {
+                let trash_path = trash.path_for(&moved.file)?;
+                let destination = fs::metadata(&moved.destination).ok();
+                trash_path.exists().then(|| TrashedMove {
+                    source: moved.file.clone(),
+                    destination: moved.destination.clone(),
+                    trash: trash_path,
+                    destination_size: destination.as_ref().map(fs::Metadata::len),
+                    destination_modified: destination.and_then(|meta| meta.modified().ok()),
+                })
+            })
+            .collect();
+
+        Self {
+            run_id: trash.run_id().to_string(),
+            moves,
+        }
+    }
+
+    fn path(state_dir: &Path, run_id: &str) -> PathBuf {
+        state_dir.join(RUNS_DIR).join(format!("{run_id}.json"))
+    }
+
+    /// Writes the record unless the run trashed nothing
+    pub fn save(&self, state_dir: &Path) -> Result<()> {
+        if self.moves.is_empty() {
+            return Ok(());
+        }
+
+        let path = Self::path(state_dir, &self.run_id);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Loads the record of `run_id`, if the run trashed anything
+    pub fn load(state_dir: &Path, run_id: &str) -> Result<Option<Self>> {
+        if run_started_at(run_id).is_none() {
+            return Ok(None);
+        }
+
+        match fs::read_to_string(Self::path(state_dir, run_id)) {
+            Ok(contents) => Ok(Some(serde_json::from_str(&contents)?)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Puts every trashed source back and removes the copy the run made
+    ///
+    /// A copy changed since the run is kept, and its source left in the trash.
+    pub fn undo(&self) -> UndoResult {
+        let mut result = UndoResult::default();
+
+        for moved in &self.moves {
+            match restore(moved) {
+                Ok(()) => {
+                    tracing::info!("Restored {}", moved.source.display());
+                    result.restored.push(moved.source.clone());
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to restore {}: {e}", moved.source.display());
+                    result.failed.push((moved.source.clone(), e.to_string()));
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Deletes the record, once nothing is left to undo
+    pub fn remove(state_dir: &Path, run_id: &str) -> Result<()> {
+        match fs::remove_file(Self::path(state_dir, run_id)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn restore(moved: &TrashedMove) -> io::Result<()> {
+    if !moved.trash.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} is no longer in the trash", moved.trash.display()),
+        ));
+    }
+    if moved.source.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", moved.source.display()),
+        ));
+    }
+    check_destination_unchanged(moved)?;
+
+    if let Some(parent) = moved.source.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(&moved.trash, &moved.source)?;
+
+    // The source is back, so a leftover copy only wastes space
+    match fs::remove_file(&moved.destination) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => {
+            tracing::warn!(
+                "Restored {} but could not remove the moved copy {}: {e}",
+                moved.source.display(),
+                moved.destination.display()
+            );
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Fails unless the moved copy is gone or still as the run left it
+fn check_destination_unchanged(moved: &TrashedMove) -> io::Result<()> {
+    let meta = match fs::metadata(&moved.destination) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        result => result?,
+    };
+    if moved.destination_size == Some(meta.len())
+        && moved.destination_modified.is_some()
+        && moved.destination_modified == meta.modified().ok()
+    {
+        return Ok(());
+    }
+    Err(io::Error::other(format!(
+        "{} changed since the run, keeping it",
+        moved.destination.display()
+    )))
+}
+
+/// Deletes trashed sources and run records older than `retention`
+///
+/// Returns the number of trashed run directories removed.
+pub fn purge_expired(
+    state_dir: &Path,
+    tiers: &[Tier],
+    retention: Duration,
+    now: SystemTime,
+) -> usize {
+    let is_expired = |run_id: &str| {
+        run_started_at(run_id).is_some_and(|started| {
+            now.duration_since(started)
+                .is_ok_and(|age| age >= retention)
+        })
+    };
+
+    let mut removed = 0;
+    for trash_root in tiers.iter().flat_map(Tier::trash_roots) {
+        for entry in read_dir_or_empty(&trash_root) {
+            let path = entry.path();
+            if !path.is_dir() || !entry.file_name().to_str().is_some_and(is_expired) {
+                continue;
+            }
+            match fs::remove_dir_all(&path) {
+                Ok(()) => {
+                    tracing::info!("Purged trash {}", path.display());
+                    removed += 1;
+                }
+                Err(e) => tracing::warn!("Failed to purge trash {}: {e}", path.display()),
+            }
+        }
+    }
+
+    for entry in read_dir_or_empty(&state_dir.join(RUNS_DIR)) {
+        let path = entry.path();
+        if path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(is_expired)
+            && let Err(e) = fs::remove_file(&path)
+        {
+            tracing::warn!("Failed to remove run record {}: {e}", path.display());
+        }
+    }
+
+    removed
+}
+
+fn read_dir_or_empty(dir: &Path) -> Vec<fs::DirEntry> {
+    match fs::read_dir(dir) {
+        Ok(entries) => entries.filter_map(std::result::Result::ok).collect(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => {
+            tracing::warn!("Failed to read {}: {e}", dir.display());
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Verification;
+    use crate::executor::ExecutionMove;
+    use tempfile::TempDir;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    fn tier(name: &str, path: &Path) -> Tier {
+        fs::create_dir_all(path).unwrap();
+        Tier::new(name.to_string(), path.to_path_buf(), 1, None, None)
+            .unwrap()
+            .with_trash_dir(".trash")
+    }
+
+    #[test]
+    fn test_trash_path_is_on_source_tier() {
+        let temp = TempDir::new().unwrap();
+        let cache = tier("cache", &temp.path().join("cache"));
+        let trash = Trash::new("1700000000", &[cache]);
+
+        assert_eq!(
+            trash.path_for(&temp.path().join("cache/Show/e1.mkv")),
+            Some(temp.path().join("cache/.trash/1700000000/Show/e1.mkv"))
+        );
+        assert_eq!(trash.path_for(&temp.path().join("other/e1.mkv")), None);
+    }
+
+    #[test]
+    fn test_discard_and_undo() {
+        let temp = TempDir::new().unwrap();
+        let cache = tier("cache", &temp.path().join("cache"));
+        let storage = tier("storage", &temp.path().join("storage"));
+        let state_dir = temp.path().join("state");
+
+        // Simulate a finished move: copy at the destination, source trashed
+        let source = cache.path.join("Show/e1.mkv");
+        let destination = storage.path.join("Show/e1.mkv");
+        fs::create_dir_all(source.parent().unwrap()).unwrap();
+        fs::create_dir_all(destination.parent().unwrap()).unwrap();
+        fs::write(&source, b"episode").unwrap();
+        fs::write(&destination, b"episode").unwrap();
+
+        let trash = Trash::new("1700000000", &[cache, storage]);
+        trash.discard(&source).unwrap();
+        assert!(!source.exists());
+
+        let result = ExecutionResult {
+            moved: vec![ExecutionMove {
+                file: source.clone(),
+                destination: destination.clone(),
+                from_tier: "cache".to_string(),
+                to_tier: "storage".to_string(),
+                size: 7,
+                verification: Verification::Size,
+            }],
+            ..ExecutionResult::default()
+        };
+        RunRecord::from_execution(&trash, &result)
+            .save(&state_dir)
+            .unwrap();
+
+        let record = RunRecord::load(&state_dir, "1700000000").unwrap().unwrap();
+        let undone = record.undo();
+
+        assert_eq!(undone.restored, vec![source.clone()]);
+        assert!(undone.failed.is_empty());
+        assert_eq!(fs::read(&source).unwrap(), b"episode");
+        assert!(!destination.exists());
+
+        // Nothing left in the trash the second time
+        assert_eq!(record.undo().failed.len(), 1);
+    }
+
+    #[test]
+    fn test_undo_keeps_changed_destination() {
+        let temp = TempDir::new().unwrap();
+        let cache = tier("cache", &temp.path().join("cache"));
+        let storage = tier("storage", &temp.path().join("storage"));
+
+        let source = cache.path.join("e1.mkv");
+        let destination = storage.path.join("e1.mkv");
+        fs::write(&source, b"episode").unwrap();
+        fs::write(&destination, b"episode").unwrap();
+        let trash = Trash::new(new_run_id(SystemTime::now()), &[cache, storage]);
+        trash.discard(&source).unwrap();
+        let result = ExecutionResult {
+            moved: vec![ExecutionMove {
+                file: source.clone(),
+                destination: destination.clone(),
+                from_tier: "cache".to_string(),
+                to_tier: "storage".to_string(),
+                size: 7,
+                verification: Verification::Size,
+            }],
+            ..ExecutionResult::default()
+        };
+        let record = RunRecord::from_execution(&trash, &result);
+
+        // Edited after the run
+        fs::write(&destination, b"episode, re-encoded").unwrap();
+        let undone = record.undo();
+
+        assert!(undone.restored.is_empty());
+        assert_eq!(undone.failed.len(), 1);
+        assert_eq!(fs::read(&destination).unwrap(), b"episode, re-encoded");
+        assert!(!source.exists());
+        assert!(record.moves[0].trash.exists());
+    }
+
+    #[test]
+    fn test_run_ids_are_unique_within_a_second() {
+        let now = SystemTime::now();
+        let (first, second) = (new_run_id(now), new_run_id(now));
+
+        assert_ne!(first, second);
+        assert_eq!(run_started_at(&first), run_started_at(&second));
+        assert!(run_started_at("1700000000").is_some());
+    }
+
+    #[test]
+    fn test_load_rejects_non_run_ids() {
+        let temp = TempDir::new().unwrap();
+        assert!(
+            RunRecord::load(temp.path(), "../journal")
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_purge_expired() {
+        let temp = TempDir::new().unwrap();
+        let cache = tier("cache", &temp.path().join("cache"));
+        let state_dir = temp.path().join("state");
+        let now = SystemTime::now();
+
+        let old_id = new_run_id(now - 48 * HOUR);
+        let new_id = new_run_id(now - HOUR);
+        for run_id in [&old_id, &new_id] {
+            let dir = cache.path.join(".trash").join(run_id);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("e1.mkv"), b"episode").unwrap();
+            RunRecord {
+                run_id: run_id.clone(),
+                moves: vec![TrashedMove {
+                    source: cache.path.join("e1.mkv"),
+                    destination: PathBuf::from("/storage/e1.mkv"),
+                    trash: dir.join("e1.mkv"),
+                    destination_size: None,
+                    destination_modified: None,
+                }],
+            }
+            .save(&state_dir)
+            .unwrap();
+        }
+
+        let removed = purge_expired(&state_dir, &[cache.clone()], 24 * HOUR, now);
+
+        assert_eq!(removed, 1);
+        assert!(!cache.path.join(".trash").join(&old_id).exists());
+        assert!(cache.path.join(".trash").join(&new_id).exists());
+        assert!(RunRecord::load(&state_dir, &old_id).unwrap().is_none());
+        assert!(RunRecord::load(&state_dir, &new_id).unwrap().is_some());
+    }
+}
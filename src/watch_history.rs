@@ -0,0 +1,160 @@
+use crate::config::PathMappingConfig;
+use crate::error::{AppError, Result};
+use crate::tautulli::{EpisodeInfo, HistoryItem, ShowProgress};
+use crate::tdarr::map_app_path;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+/// Source of viewing history for the `active_window` pipeline
+///
+/// Providers report history as Tautulli-style [`HistoryItem`]s so the
+/// progress and viewing-window logic is shared by all of them.
+pub trait WatchHistoryProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Verify the provider is reachable and the API key is valid
+    fn health_check(&self) -> Result<()>;
+
+    /// Up to `length` most recent episode views
+    fn get_history(&self, length: u32) -> Result<Vec<HistoryItem>>;
+
+    /// File paths of a history item as seen by the media server
+    fn get_file_paths(&self, rating_key: &str) -> Result<Vec<String>> {
+        Err(AppError::External(format!(
+            "{} does not support metadata lookups (rating key {rating_key})",
+            self.name()
+        )))
+    }
+}
+
+/// Resolve the last watched episode of each show to host file paths
+///
+/// Looks up every history item that `user_progress` was built from, so
+/// renamed files still anchor the viewing window. Failed lookups are logged and skipped.
+pub fn resolve_episode_paths(
+    provider: &dyn WatchHistoryProvider,
+    history: &[HistoryItem],
+    user_progress: &[ShowProgress],
+    path_mappings: &[PathMappingConfig],
+) -> HashMap<PathBuf, EpisodeInfo> {
+    let last_watched: HashSet<(&str, &str, u32, u32)> = user_progress
+        .iter()
+        .map(|p| {
+            (
+                p.user.as_str(),
+                p.show_name.as_str(),
+                p.last_watched_season,
+                p.last_watched_episode,
+            )
+        })
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut resolved = HashMap::new();
+    for item in history {
+        let key = (
+            item.user.as_str(),
+            item.grandparent_title.as_str(),
+            item.parent_media_index,
+            item.media_index,
+        );
+        if !last_watched.contains(&key) || !seen.insert(item.rating_key.as_str()) {
+            continue;
+        }
+
+        let paths = match provider.get_file_paths(&item.rating_key) {
+            Ok(paths) => paths,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to resolve {} S{:02}E{:02}: {e}",
+                    item.grandparent_title,
+                    item.parent_media_index,
+                    item.media_index
+                );
+                continue;
+            }
+        };
+
+        let episode = EpisodeInfo::new(
+            item.grandparent_title.clone(),
+            item.parent_media_index,
+            item.media_index,
+        );
+        for path in paths {
+            for host_path in map_app_path(&path, path_mappings) {
+                resolved.insert(host_path, episode.clone());
+            }
+        }
+    }
+
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tautulli::build_progress;
+    use crate::timestamp::unix_now;
+
+    struct FakeProvider;
+
+    impl WatchHistoryProvider for FakeProvider {
+        fn name(&self) -> &'static str {
+            "fake"
+        }
+
+        fn health_check(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn get_history(&self, _length: u32) -> Result<Vec<HistoryItem>> {
+            Ok(Vec::new())
+        }
+
+        fn get_file_paths(&self, rating_key: &str) -> Result<Vec<String>> {
+            match rating_key {
+                "6" => Ok(vec!["/data/tv/Show/Season 01/renamed.mkv".to_string()]),
+                _ => Err(AppError::External("not found".to_string())),
+            }
+        }
+    }
+
+    fn create_history_item(rating_key: &str, episode: u32) -> HistoryItem {
+        HistoryItem {
+            user: "alice".to_string(),
+            rating_key: rating_key.to_string(),
+            media_type: "episode".to_string(),
+            title: String::new(),
+            year: 0,
+            grandparent_title: "Show".to_string(),
+            parent_media_index: 1,
+            media_index: episode,
+            percent_complete: 100,
+            stopped: unix_now() - 60 * u64::from(10 - episode),
+        }
+    }
+
+    #[test]
+    fn test_resolve_episode_paths() {
+        let history = vec![create_history_item("5", 5), create_history_item("6", 6)];
+        let progress = build_progress(&history, 30, 90);
+        let mappings = vec![
+            PathMappingConfig {
+                host_prefix: "/mnt/cache/tv".into(),
+                app_prefix: "/data/tv".to_string(),
+            },
+            PathMappingConfig {
+                host_prefix: "/mnt/storage/tv".into(),
+                app_prefix: "/data/tv".to_string(),
+            },
+        ];
+
+        let resolved = resolve_episode_paths(&FakeProvider, &history, &progress, &mappings);
+
+        // Only the last watched episode is looked up
+        assert_eq!(resolved.len(), 2);
+        let episode = &resolved[&PathBuf::from("/mnt/storage/tv/Show/Season 01/renamed.mkv")];
+        assert_eq!(episode.episode, 6);
+        assert!(resolved.contains_key(&PathBuf::from("/mnt/cache/tv/Show/Season 01/renamed.mkv")));
+    }
+}
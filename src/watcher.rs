@@ -0,0 +1,337 @@
+//! Filesystem events on the tiers, for the daemon's reactive mode (Linux inotify)
+//!
+//! Every directory under the scan roots gets a watch. Finished writes and files
+//! moved in mark their directory as changed; the daemon plans the changed
+//! directories once no event arrived for the debounce time.
+
+use crate::Tier;
+use crate::file::is_internal_artifact_path;
+use std::collections::{BTreeSet, HashMap};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+/// Changed directories collected between runs, relative to the member roots
+#[derive(Debug, Default)]
+pub struct DirtyDirs {
+    dirs: BTreeSet<PathBuf>,
+    last_event: Option<Instant>,
+}
+
+impl DirtyDirs {
+    pub fn add(&mut self, dir: PathBuf, at: Instant) {
+        self.dirs.insert(dir);
+        self.last_event = Some(at);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dirs.is_empty()
+    }
+
+    /// Some directory changed and nothing happened for `debounce` since
+    pub fn is_settled(&self, debounce: Duration, now: Instant) -> bool {
+        !self.dirs.is_empty()
+            && self
+                .last_event
+                .is_some_and(|at| now.saturating_duration_since(at) >= debounce)
+    }
+
+    /// Changed directories without those nested in another one; an empty path is the whole tier
+    pub fn take(&mut self) -> Vec<PathBuf> {
+        self.last_event = None;
+        let mut dirs: Vec<PathBuf> = std::mem::take(&mut self.dirs).into_iter().collect();
+        dirs.dedup_by(|dir, parent| dir.starts_with(parent));
+        dirs
+    }
+}
+
+/// What happened in a watched directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EventKind {
+    /// A file was closed after writing, or moved in
+    FileChanged,
+    /// A directory was created or moved in
+    DirAdded,
+    /// The watch is gone (directory removed or unmounted)
+    WatchRemoved,
+    /// The kernel dropped events
+    Overflow,
+}
+
+/// inotify watches on the scan roots of every tier
+pub struct TierWatcher {
+    inotify: sys::Inotify,
+    watches: HashMap<i32, PathBuf>,
+    tiers: Vec<Tier>,
+}
+
+impl TierWatcher {
+    pub fn new(tiers: &[Tier]) -> io::Result<Self> {
+        let mut watcher = Self {
+            inotify: sys::Inotify::new()?,
+            watches: HashMap::new(),
+            tiers: tiers.to_vec(),
+        };
+        let roots: Vec<_> = tiers.iter().flat_map(Tier::scan_roots).collect();
+        for root in roots.iter().filter(|root| root.is_dir()) {
+            watcher.watch_tree(root);
+        }
+        tracing::info!("Watching {} directories for changes", watcher.watches.len());
+        Ok(watcher)
+    }
+
+    /// Adds the pending events to `dirty`; new directories get watches too
+    pub fn poll(&mut self, dirty: &mut DirtyDirs) {
+        let events = match self.inotify.read_events() {
+            Ok(events) => events,
+            Err(e) => {
+                tracing::warn!("Failed to read filesystem events: {e}");
+                return;
+            }
+        };
+        let now = Instant::now();
+        for (wd, kind, name) in events {
+            if kind == EventKind::Overflow {
+                tracing::warn!("Filesystem event queue overflowed, planning all directories");
+                dirty.add(PathBuf::new(), now);
+                continue;
+            }
+            let Some(dir) = self.watches.get(&wd).cloned() else {
+                continue;
+            };
+            match kind {
+                EventKind::FileChanged => {
+                    let path = name.map_or_else(|| dir.clone(), |name| dir.join(name));
+                    if !is_internal_artifact_path(&path)
+                        && let Some(relative) = self.relative_dir(&dir)
+                    {
+                        dirty.add(relative, now);
+                    }
+                }
+                EventKind::DirAdded => {
+                    let Some(name) = name else { continue };
+                    let path = dir.join(name);
+                    self.watch_tree(&path);
+                    if let Some(relative) = self.relative_dir(&path) {
+                        dirty.add(relative, now);
+                    }
+                }
+                EventKind::WatchRemoved => {
+                    self.watches.remove(&wd);
+                }
+                EventKind::Overflow => {}
+            }
+        }
+    }
+
+    /// Watches `dir` and the directories below it, except trash
+    fn watch_tree(&mut self, dir: &Path) {
+        let trash_roots: Vec<_> = self.tiers.iter().flat_map(Tier::trash_roots).collect();
+        let dirs = WalkDir::new(dir)
+            .follow_links(false)
+            .into_iter()
+            .filter_entry(|e| !trash_roots.iter().any(|trash| e.path().starts_with(trash)))
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_dir());
+        for entry in dirs {
+            match self.inotify.add_watch(entry.path()) {
+                Ok(wd) => {
+                    self.watches.insert(wd, entry.path().to_path_buf());
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to watch {}: {e}", entry.path().display());
+                    if e.raw_os_error() == Some(sys::ENOSPC) {
+                        tracing::warn!(
+                            "Raise fs.inotify.max_user_watches to watch every directory"
+                        );
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    fn relative_dir(&self, dir: &Path) -> Option<PathBuf> {
+        self.tiers
+            .iter()
+            .find_map(|tier| tier.relative_path(dir))
+            .map(Path::to_path_buf)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use super::EventKind;
+    use std::ffi::{CString, OsStr, OsString};
+    use std::fs::File;
+    use std::io::{self, Read};
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    pub const ENOSPC: i32 = libc::ENOSPC;
+
+    const MASK: u32 = libc::IN_CLOSE_WRITE
+        | libc::IN_MOVED_TO
+        | libc::IN_CREATE
+        | libc::IN_ONLYDIR
+        | libc::IN_DONT_FOLLOW;
+    const HEADER: usize = std::mem::size_of::<libc::inotify_event>();
+
+    pub struct Inotify {
+        file: File,
+    }
+
+    impl Inotify {
+        pub fn new() -> io::Result<Self> {
+            // SAFETY: plain syscall without pointers
+            let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            // SAFETY: `fd` was just opened and nothing else owns it
+            let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+            Ok(Self {
+                file: File::from(fd),
+            })
+        }
+
+        pub fn add_watch(&self, dir: &Path) -> io::Result<i32> {
+            let path = CString::new(dir.as_os_str().as_bytes())?;
+            // SAFETY: `path` is NUL-terminated and outlives the call
+            let wd = unsafe { libc::inotify_add_watch(self.file.as_raw_fd(), path.as_ptr(), MASK) };
+            if wd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(wd)
+        }
+
+        /// Events queued so far, without blocking
+        pub fn read_events(&self) -> io::Result<Vec<(i32, EventKind, Option<OsString>)>> {
+            let mut events = Vec::new();
+            let mut buf = vec![0u8; 64 * 1024];
+            loop {
+                let len = match (&self.file).read(&mut buf) {
+                    Ok(len) => len,
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(events),
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                };
+                let mut offset = 0;
+                while offset + HEADER <= len {
+                    // SAFETY: the kernel writes whole events; the buffer is not aligned for them
+                    let event: libc::inotify_event =
+                        unsafe { std::ptr::read_unaligned(buf[offset..].as_ptr().cast()) };
+                    let name_end = (offset + HEADER + event.len as usize).min(len);
+                    let name = &buf[offset + HEADER..name_end];
+                    let name = name.split(|&b| b == 0).next().filter(|n| !n.is_empty());
+                    offset = name_end;
+
+                    let kind = if event.mask & libc::IN_Q_OVERFLOW != 0 {
+                        EventKind::Overflow
+                    } else if event.mask & libc::IN_IGNORED != 0 {
+                        EventKind::WatchRemoved
+                    } else if event.mask & libc::IN_ISDIR != 0 {
+                        EventKind::DirAdded
+                    } else if event.mask & (libc::IN_CLOSE_WRITE | libc::IN_MOVED_TO) != 0 {
+                        EventKind::FileChanged
+                    } else {
+                        continue;
+                    };
+                    events.push((
+                        event.wd,
+                        kind,
+                        name.map(|n| OsStr::from_bytes(n).to_os_string()),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use super::EventKind;
+    use std::ffi::OsString;
+    use std::io;
+    use std::path::Path;
+
+    pub const ENOSPC: i32 = 28;
+
+    pub struct Inotify;
+
+    impl Inotify {
+        pub fn new() -> io::Result<Self> {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "watch mode needs inotify (Linux)",
+            ))
+        }
+
+        pub fn add_watch(&self, _dir: &Path) -> io::Result<i32> {
+            Err(io::Error::from(io::ErrorKind::Unsupported))
+        }
+
+        pub fn read_events(&self) -> io::Result<Vec<(i32, EventKind, Option<OsString>)>> {
+            Ok(Vec::new())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_dirty_dirs_debounce_and_nesting() {
+        let start = Instant::now();
+        let debounce = Duration::from_secs(60);
+        let mut dirty = DirtyDirs::default();
+        assert!(!dirty.is_settled(debounce, start + debounce));
+
+        dirty.add(PathBuf::from("Show/Season 1"), start);
+        dirty.add(PathBuf::from("Show"), start + Duration::from_secs(30));
+        dirty.add(PathBuf::from("Movies"), start + Duration::from_secs(30));
+        assert!(!dirty.is_settled(debounce, start + debounce));
+        assert!(dirty.is_settled(debounce, start + Duration::from_secs(90)));
+
+        assert_eq!(
+            dirty.take(),
+            vec![PathBuf::from("Movies"), PathBuf::from("Show")]
+        );
+        assert!(dirty.is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_watcher_reports_changed_dirs() {
+        let temp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(temp.path().join("Show")).unwrap();
+        let tier = Tier::new(
+            "cache".to_string(),
+            temp.path().to_path_buf(),
+            1,
+            None,
+            None,
+        )
+        .unwrap();
+        let mut watcher = TierWatcher::new(&[tier]).unwrap();
+
+        fs::write(temp.path().join("Show/e1.mkv"), b"data").unwrap();
+        fs::write(temp.path().join("Show/e2.mkv.partial"), b"data").unwrap();
+        fs::create_dir(temp.path().join("Movie")).unwrap();
+        let mut dirty = DirtyDirs::default();
+        watcher.poll(&mut dirty);
+
+        // Files in the new directory are seen through its own watch
+        fs::write(temp.path().join("Movie/m.mkv"), b"data").unwrap();
+        watcher.poll(&mut dirty);
+
+        assert_eq!(
+            dirty.take(),
+            vec![PathBuf::from("Movie"), PathBuf::from("Show")]
+        );
+    }
+}